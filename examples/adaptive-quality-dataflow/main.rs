@@ -0,0 +1,153 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, node_env};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Seconds that the encoder may take to converge, counted from the first received frame.
+const SETTLE_SECS: u64 = 5;
+/// How far the bitrate of a whole run after settling, and of a single second, may be off the
+/// target.
+const MEAN_TOLERANCE: f64 = 0.10;
+const SECOND_TOLERANCE: f64 = 0.25;
+/// Once the encoder has converged, frames shouldn't wait in the link for long.
+const MAX_SETTLED_DELAY_MS: i64 = 250;
+
+/// Subset of `ReceiverReport` in `nodes/src/receiver.rs`.
+#[derive(Debug, Deserialize)]
+struct ReceiverReport {
+    invalid: u64,
+    last_frame: Option<i64>,
+    seconds: Vec<Second>,
+}
+
+/// Subset of `Second` in `nodes/src/receiver.rs`.
+#[derive(Debug, Deserialize)]
+struct Second {
+    second: u64,
+    frames: u64,
+    kbps: f64,
+    mean_quality: f64,
+    max_link_delay_ms: i64,
+}
+
+/// Subset of `Adjustment` in `nodes/src/encoder.rs`.
+#[derive(Debug, Deserialize)]
+struct Adjustment {
+    feedback: LinkFeedback,
+}
+
+/// Subset of `LinkFeedback` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct LinkFeedback {
+    queue_frames: usize,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("adaptive-quality-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dataflow = Path::new("dataflow.yml");
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let target_kbps: f64 = node_env(&descriptor, "encoder", "TARGET_KBPS")?.parse()?;
+    let frames: i64 = node_env(&descriptor, "camera", "FRAMES")?.parse()?;
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: ReceiverReport = serde_json::from_str(
+        &std::fs::read_to_string("out/receiver.json")
+            .context("the receiver did not write a report")?,
+    )?;
+    let adjustments: Vec<Adjustment> = serde_json::from_str(
+        &std::fs::read_to_string("out/encoder.json")
+            .context("the encoder did not write a report")?,
+    )?;
+
+    println!(
+        "{:>6}  {:>6}  {:>10}  {:>7}  {:>10}",
+        "second", "frames", "kbit/s", "quality", "link delay"
+    );
+    for second in &report.seconds {
+        println!(
+            "{:>6}  {:>6}  {:>10.0}  {:>7.1}  {:>7} ms",
+            second.second,
+            second.frames,
+            second.kbps,
+            second.mean_quality,
+            second.max_link_delay_ms
+        );
+    }
+
+    if report.invalid > 0 {
+        bail!("the receiver got {} invalid JPEG frames", report.invalid);
+    }
+    if report.last_frame != Some(frames - 1) {
+        bail!(
+            "expected the last frame {} to arrive, the last one was {:?}",
+            frames - 1,
+            report.last_frame
+        );
+    }
+
+    // the starting quality is far too high for the link, so frames pile up at first
+    if !adjustments
+        .iter()
+        .any(|adjustment| adjustment.feedback.queue_frames > 1)
+    {
+        bail!("expected frames to queue up in the link before the encoder backed off");
+    }
+
+    // the last second is usually incomplete
+    let complete = &report.seconds[..report.seconds.len().saturating_sub(1)];
+    let settled: Vec<&Second> = complete
+        .iter()
+        .filter(|second| second.second >= SETTLE_SECS)
+        .collect();
+    if settled.len() < 3 {
+        bail!(
+            "expected at least 3 complete seconds after the first {SETTLE_SECS}, got {}",
+            settled.len()
+        );
+    }
+    let mean_kbps = settled.iter().map(|second| second.kbps).sum::<f64>() / settled.len() as f64;
+    if (mean_kbps - target_kbps).abs() > MEAN_TOLERANCE * target_kbps {
+        bail!(
+            "expected the bitrate to converge to {target_kbps} kbit/s, it was {mean_kbps:.0} \
+             kbit/s after {SETTLE_SECS} s"
+        );
+    }
+    for second in &settled {
+        if (second.kbps - target_kbps).abs() > SECOND_TOLERANCE * target_kbps {
+            bail!(
+                "the bitrate of second {} was {:.0} kbit/s, too far off {target_kbps} kbit/s",
+                second.second,
+                second.kbps
+            );
+        }
+        if second.max_link_delay_ms > MAX_SETTLED_DELAY_MS {
+            bail!(
+                "frames waited up to {} ms in the link in second {}, expected a short queue \
+                 once the encoder converged",
+                second.max_link_delay_ms,
+                second.second
+            );
+        }
+    }
+    println!(
+        "the bitrate converged to {mean_kbps:.0} kbit/s, for a target of {target_kbps} kbit/s"
+    );
+
+    println!("Everything Done");
+    Ok(())
+}
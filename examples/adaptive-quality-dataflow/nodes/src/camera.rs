@@ -0,0 +1,72 @@
+use adaptive_quality_dataflow_nodes::{ENCODING_KEY, FRAME_KEY, HEIGHT_KEY, WIDTH_KEY, env_or};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::UInt8Array,
+    dora_core::config::DataId,
+};
+
+/// Renders a `WIDTH` x `HEIGHT` `rgb8` image on every `tick`, and stops after `FRAMES`
+/// frames.
+///
+/// The images are moving color waves with some sensor noise, so that their JPEG size depends
+/// on the quality about as much as for a real camera: noise is what makes high qualities
+/// expensive.
+fn main() -> eyre::Result<()> {
+    let width: u32 = env_or("WIDTH", 320)?;
+    let height: u32 = env_or("HEIGHT", 240)?;
+    let frames: u64 = env_or("FRAMES", 300)?;
+    let output = DataId::from("image".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut frame = 0u64;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    if frame == frames {
+                        break;
+                    }
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(FRAME_KEY.into(), Parameter::Integer(frame as i64));
+                    parameters.insert(WIDTH_KEY.into(), Parameter::Integer(width.into()));
+                    parameters.insert(HEIGHT_KEY.into(), Parameter::Integer(height.into()));
+                    parameters.insert(ENCODING_KEY.into(), Parameter::String("rgb8".into()));
+                    node.send_output(
+                        output.clone(),
+                        parameters,
+                        UInt8Array::from(render(frame, width, height)),
+                    )?;
+                    frame += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("rendered {frame} frames");
+    Ok(())
+}
+
+fn render(frame: u64, width: u32, height: u32) -> Vec<u8> {
+    let t = frame as f64 * 0.1;
+    // xorshift, seeded per frame, so that the noise changes like on a real sensor
+    let mut state = 0x9e37_79b9_7f4a_7c15 ^ frame;
+    let mut image = Vec::with_capacity(width as usize * height as usize * 3);
+    for y in 0..height {
+        for x in 0..width {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let noise = (state % 24) as f64 - 12.0;
+            let (x, y) = (x as f64, y as f64);
+            let r = 128.0 + 60.0 * (x * 0.05 + t).sin() + noise;
+            let g = 128.0 + 60.0 * (y * 0.07 - t).cos() + noise;
+            let b = 128.0 + 50.0 * ((x + y) * 0.03 + 2.0 * t).sin() + noise;
+            image.extend([r, g, b].map(|value| value.clamp(0.0, 255.0) as u8));
+        }
+    }
+    image
+}
@@ -0,0 +1,177 @@
+use adaptive_quality_dataflow_nodes::{
+    ENCODING_KEY, HEIGHT_KEY, LinkFeedback, QUALITY_KEY, WIDTH_KEY, env_or, integer_parameter,
+    write_json,
+};
+use dora_node_api::{
+    self, DoraNode, Event, Parameter,
+    arrow::{
+        array::{AsArray, UInt8Array},
+        datatypes::UInt8Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::{OptionExt, bail};
+use jpeg_encoder::{ColorType, Encoder};
+use serde::Serialize;
+use std::{path::PathBuf, time::Instant};
+
+/// One decision of the controller, at the end of a window of the link.
+#[derive(Debug, Serialize)]
+struct Adjustment {
+    elapsed_ms: u64,
+    /// What the encoder sent during the window.
+    sent_kbps: f64,
+    feedback: LinkFeedback,
+    budget_kbps: f64,
+    /// The quality of the next frames.
+    quality: u8,
+}
+
+/// Keeps the bitrate of the encoded frames at `TARGET_KBPS`, by adjusting the JPEG quality.
+///
+/// The size of a JPEG grows roughly exponentially with its quality, so the quality follows the
+/// logarithm of the ratio between the budget and the bitrate that was actually sent.
+struct Controller {
+    target_kbps: f64,
+    /// Seconds in which a backlog in the link should be sent.
+    drain_secs: f64,
+    /// Quality steps per unit of the log ratio.
+    gain: f64,
+    quality: u8,
+    min_quality: u8,
+    max_quality: u8,
+}
+
+impl Controller {
+    /// The bitrate that the next window may use.
+    ///
+    /// As long as frames don't pile up in the link, that's the target. Once they do, the link
+    /// is slower than the encoder, so the budget is what the link delivered, less the rate
+    /// needed to send the backlog within `drain_secs`.
+    fn budget_kbps(&self, feedback: &LinkFeedback) -> f64 {
+        // one frame in flight is normal
+        if feedback.queue_frames <= 1 {
+            return self.target_kbps;
+        }
+        let drain_kbps = feedback.queue_bytes as f64 * 8.0 / 1000.0 / self.drain_secs;
+        let budget = self.target_kbps.min(feedback.throughput_kbps) - drain_kbps;
+        // never starve the link completely, or it can't measure anything
+        budget.max(0.1 * self.target_kbps)
+    }
+
+    fn update(&mut self, sent_kbps: f64, budget_kbps: f64) {
+        if sent_kbps <= 0.0 {
+            return;
+        }
+        let error = (budget_kbps / sent_kbps).ln();
+        // within 5% is close enough, the size of the frames varies more than that
+        if error.abs() < 0.05 {
+            return;
+        }
+        let step = (self.gain * error).round().clamp(-15.0, 15.0) as i32;
+        self.quality = (self.quality as i32 + step)
+            .clamp(self.min_quality.into(), self.max_quality.into()) as u8;
+    }
+}
+
+/// Encodes the `rgb8` frames of `image` as JPEG and sends them on `jpeg`, with the quality in
+/// their metadata.
+///
+/// Adjusts the quality on every `feedback` of the link, and writes its decisions to
+/// `REPORT_FILE` when `image` is closed.
+fn main() -> eyre::Result<()> {
+    let mut controller = Controller {
+        target_kbps: env_or("TARGET_KBPS", 1200.0)?,
+        drain_secs: env_or("DRAIN_SECS", 2.0)?,
+        gain: env_or("GAIN", 20.0)?,
+        quality: env_or("INITIAL_QUALITY", 90)?,
+        min_quality: env_or("MIN_QUALITY", 5)?,
+        max_quality: env_or("MAX_QUALITY", 95)?,
+    };
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/encoder.json".into())?;
+    let output = DataId::from("jpeg".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    let mut window_start = Instant::now();
+    let mut window_bytes = 0usize;
+    let mut adjustments = Vec::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let width: u16 =
+                        integer_parameter(&metadata.parameters, WIDTH_KEY)?.try_into()?;
+                    let height: u16 =
+                        integer_parameter(&metadata.parameters, HEIGHT_KEY)?.try_into()?;
+                    match metadata.parameters.get(ENCODING_KEY) {
+                        Some(Parameter::String(encoding)) if encoding == "rgb8" => {}
+                        other => bail!("expected rgb8 image, got encoding {other:?}"),
+                    }
+                    let pixels = data
+                        .as_primitive_opt::<UInt8Type>()
+                        .ok_or_eyre("expected UInt8 image")?
+                        .values();
+
+                    let mut jpeg = Vec::new();
+                    Encoder::new(&mut jpeg, controller.quality).encode(
+                        pixels,
+                        width,
+                        height,
+                        ColorType::Rgb,
+                    )?;
+                    window_bytes += jpeg.len();
+
+                    // keeps the frame number of the camera
+                    let mut parameters = metadata.parameters;
+                    parameters.insert(
+                        QUALITY_KEY.into(),
+                        Parameter::Integer(controller.quality.into()),
+                    );
+                    node.send_output(output.clone(), parameters, UInt8Array::from(jpeg))?;
+                }
+                "feedback" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    for row in rows.iter().flatten() {
+                        let feedback: LinkFeedback = serde_json::from_str(row)?;
+                        let sent_kbps = window_bytes as f64 * 8.0
+                            / 1000.0
+                            / window_start.elapsed().as_secs_f64();
+                        (window_start, window_bytes) = (Instant::now(), 0);
+
+                        let budget_kbps = controller.budget_kbps(&feedback);
+                        controller.update(sent_kbps, budget_kbps);
+                        println!(
+                            "sent {sent_kbps:>6.0} kbit/s, link delivered {:>6.0} kbit/s with \
+                             {} frames queued, budget {budget_kbps:>6.0} kbit/s -> quality {}",
+                            feedback.throughput_kbps, feedback.queue_frames, controller.quality
+                        );
+                        adjustments.push(Adjustment {
+                            elapsed_ms: start.elapsed().as_millis() as u64,
+                            sent_kbps,
+                            feedback,
+                            budget_kbps,
+                            quality: controller.quality,
+                        });
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                // the feedback of the link only closes once the encoder has stopped
+                if id.as_str() == "image" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    write_json(&report_file, &adjustments)?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::bail;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Metadata keys of the `image` output of the camera.
+pub const WIDTH_KEY: &str = "width";
+pub const HEIGHT_KEY: &str = "height";
+pub const ENCODING_KEY: &str = "encoding";
+/// Metadata key of the frame number, kept from the camera to the receiver.
+pub const FRAME_KEY: &str = "frame";
+/// Metadata key of the JPEG quality that the encoder used for a frame.
+pub const QUALITY_KEY: &str = "quality";
+/// Metadata key of the milliseconds that a frame waited in the queue of the link.
+pub const LINK_DELAY_KEY: &str = "link_delay_ms";
+
+/// Sent by the link on `feedback` at the end of every window, as a JSON row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkFeedback {
+    pub window_ms: u64,
+    /// The bitrate that the link delivered during the window.
+    pub throughput_kbps: f64,
+    /// Frames and bytes waiting to be sent, including the frame being sent.
+    pub queue_frames: usize,
+    pub queue_bytes: usize,
+    /// How long the oldest waiting frame has waited.
+    pub queue_delay_ms: u64,
+    /// Frames dropped because the queue was full, since the link started.
+    pub dropped: u64,
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
@@ -0,0 +1,125 @@
+use adaptive_quality_dataflow_nodes::{LINK_DELAY_KEY, LinkFeedback, env_or};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter,
+    arrow::array::{ArrayRef, StringArray, make_array},
+    dora_core::config::DataId,
+};
+use std::{collections::VecDeque, time::Instant};
+
+/// A frame waiting to be sent.
+struct Queued {
+    data: ArrayRef,
+    parameters: MetadataParameters,
+    bytes: usize,
+    since: Instant,
+}
+
+/// Simulates a link of `LINK_KBPS` between the encoder and the receiver, like a radio link to
+/// a remote operator.
+///
+/// Queues the frames of `jpeg` and forwards them on `frames` no faster than the link could
+/// send them, with the time they waited in the queue in their metadata. When `QUEUE_LIMIT`
+/// frames are waiting, it drops the oldest one, like the send buffer of a real link. Sends
+/// a [`LinkFeedback`] on `feedback` on every `report`.
+///
+/// Stops once `jpeg` is closed and the queue is empty.
+fn main() -> eyre::Result<()> {
+    let link_kbps: f64 = env_or("LINK_KBPS", 2000.0)?;
+    let queue_limit: usize = env_or("QUEUE_LIMIT", 40)?;
+    let frames_output = DataId::from("frames".to_owned());
+    let feedback_output = DataId::from("feedback".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut queue: VecDeque<Queued> = VecDeque::new();
+    // bits that the link could have sent since it started sending the first queued frame
+    let mut credit_bits = 0.0;
+    let mut last_tick = Instant::now();
+    let mut window_start = Instant::now();
+    let mut window_bytes = 0usize;
+    let mut dropped = 0u64;
+    let mut jpeg_closed = false;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "jpeg" => {
+                    let data = make_array(data.to_data());
+                    if queue.len() == queue_limit {
+                        queue.pop_front();
+                        dropped += 1;
+                    }
+                    queue.push_back(Queued {
+                        bytes: data.len(),
+                        data,
+                        parameters: metadata.parameters,
+                        since: Instant::now(),
+                    });
+                }
+                "tick" => {
+                    let seconds = last_tick.elapsed().as_secs_f64();
+                    last_tick = Instant::now();
+                    if queue.is_empty() {
+                        // an idle link can't save up bandwidth for later
+                        credit_bits = 0.0;
+                    } else {
+                        credit_bits += link_kbps * 1000.0 * seconds;
+                    }
+                    while let Some(front) = queue.front() {
+                        let bits = front.bytes as f64 * 8.0;
+                        if bits > credit_bits {
+                            break;
+                        }
+                        credit_bits -= bits;
+                        let Queued {
+                            data,
+                            mut parameters,
+                            bytes,
+                            since,
+                        } = queue.pop_front().unwrap();
+                        window_bytes += bytes;
+                        parameters.insert(
+                            LINK_DELAY_KEY.into(),
+                            Parameter::Integer(since.elapsed().as_millis() as i64),
+                        );
+                        node.send_output(frames_output.clone(), parameters, data)?;
+                    }
+                    if jpeg_closed && queue.is_empty() {
+                        break;
+                    }
+                }
+                "report" => {
+                    let feedback = LinkFeedback {
+                        window_ms: window_start.elapsed().as_millis() as u64,
+                        throughput_kbps: window_bytes as f64 * 8.0
+                            / 1000.0
+                            / window_start.elapsed().as_secs_f64(),
+                        queue_frames: queue.len(),
+                        queue_bytes: queue.iter().map(|queued| queued.bytes).sum(),
+                        queue_delay_ms: queue
+                            .front()
+                            .map_or(0, |queued| queued.since.elapsed().as_millis() as u64),
+                        dropped,
+                    };
+                    (window_start, window_bytes) = (Instant::now(), 0);
+                    node.send_output(
+                        feedback_output.clone(),
+                        Default::default(),
+                        StringArray::from(vec![serde_json::to_string(&feedback)?]),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "jpeg" {
+                    jpeg_closed = true;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("dropped {dropped} frames");
+    Ok(())
+}
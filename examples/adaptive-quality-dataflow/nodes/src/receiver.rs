@@ -0,0 +1,107 @@
+use adaptive_quality_dataflow_nodes::{
+    FRAME_KEY, LINK_DELAY_KEY, QUALITY_KEY, env_or, integer_parameter, write_json,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{array::AsArray, datatypes::UInt8Type},
+};
+use eyre::OptionExt;
+use serde::Serialize;
+use std::{path::PathBuf, time::Instant};
+
+/// What the receiver got during one second.
+#[derive(Debug, Default, Serialize)]
+struct Second {
+    second: u64,
+    frames: u64,
+    kbps: f64,
+    mean_quality: f64,
+    max_link_delay_ms: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ReceiverReport {
+    frames: u64,
+    /// Frames that don't start and end like a JPEG.
+    invalid: u64,
+    last_frame: Option<i64>,
+    /// Counted from the first frame. The last second is usually incomplete.
+    seconds: Vec<Second>,
+}
+
+/// The remote end of the link: measures the bitrate, the quality and the delay of the
+/// `frames` that arrive, per second, and writes them to `REPORT_FILE` once `frames` is closed.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/receiver.json".into())?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = ReceiverReport::default();
+    let mut start = None;
+    let mut quality_sum = 0.0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "frames" => {
+                    let elapsed = start.get_or_insert_with(Instant::now).elapsed();
+                    let jpeg = data
+                        .as_primitive_opt::<UInt8Type>()
+                        .ok_or_eyre("expected a UInt8 array")?
+                        .values();
+                    if !(jpeg.starts_with(&[0xff, 0xd8]) && jpeg.ends_with(&[0xff, 0xd9])) {
+                        report.invalid += 1;
+                    }
+                    let quality = integer_parameter(&metadata.parameters, QUALITY_KEY)?;
+                    let link_delay = integer_parameter(&metadata.parameters, LINK_DELAY_KEY)?;
+                    report.last_frame = Some(integer_parameter(&metadata.parameters, FRAME_KEY)?);
+                    report.frames += 1;
+
+                    let second = elapsed.as_secs();
+                    if report
+                        .seconds
+                        .last()
+                        .is_none_or(|last| last.second != second)
+                    {
+                        print_second(report.seconds.last());
+                        report.seconds.push(Second {
+                            second,
+                            ..Default::default()
+                        });
+                        quality_sum = 0.0;
+                    }
+                    let current = report.seconds.last_mut().unwrap();
+                    current.frames += 1;
+                    current.kbps += jpeg.len() as f64 * 8.0 / 1000.0;
+                    quality_sum += quality as f64;
+                    current.mean_quality = quality_sum / current.frames as f64;
+                    current.max_link_delay_ms = current.max_link_delay_ms.max(link_delay);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    print_second(report.seconds.last());
+    println!(
+        "received {} frames, {} invalid",
+        report.frames, report.invalid
+    );
+    write_json(&report_file, &report)?;
+    Ok(())
+}
+
+fn print_second(second: Option<&Second>) {
+    if let Some(second) = second {
+        println!(
+            "second {:>3}: {:>6.0} kbit/s, {:>2} frames, quality {:>4.1}, link delay up to {} ms",
+            second.second,
+            second.kbps,
+            second.frames,
+            second.mean_quality,
+            second.max_link_delay_ms
+        );
+    }
+}
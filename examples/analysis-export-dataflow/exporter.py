@@ -0,0 +1,119 @@
+#!/usr/bin/env python3
+"""
+Analysis exporter.
+- Accumulates the messages of every input, Arrow struct arrays, into one Polars DataFrame per
+  input
+- When the dataflow ends, writes each frame to `<EXPORT_DIR>/<input>.feather`
+- Aggregates the numeric columns of frames that have a `GROUP_BY` column into a stats table per
+  group, written to `<EXPORT_DIR>/<input>_stats.feather`
+- Writes a Markdown summary with the schema and the stats tables of every frame to
+  `<EXPORT_DIR>/summary.md`
+
+Feather files are Arrow IPC files, which Polars, pandas, DuckDB and R read directly, so the
+recorded data can be analyzed in a notebook without any dora-specific code.
+"""
+
+import os
+
+import polars as pl
+import pyarrow as pa
+from dora import Node
+
+EXPORT_DIR = os.getenv("EXPORT_DIR", "out")
+GROUP_BY = os.getenv("GROUP_BY", "joint")
+# columns that identify a message instead of measuring something
+KEY_COLUMNS = {"seq", GROUP_BY}
+
+
+def write_feather(frame, path):
+    # the oldest compatibility level writes strings as `large_utf8` instead of the newer
+    # `utf8_view`, which not every Arrow reader supports yet
+    frame.write_ipc(path, compression="uncompressed", compat_level=pl.CompatLevel.oldest())
+    print(f"wrote {frame.height} rows to {path}", flush=True)
+
+
+def group_stats(frame):
+    """Count, mean, standard deviation, min and max of every numeric column per group."""
+    columns = [
+        name
+        for name, dtype in frame.schema.items()
+        if dtype.is_numeric() and name not in KEY_COLUMNS
+    ]
+    return (
+        frame.group_by(GROUP_BY)
+        .agg(
+            pl.len().alias("samples"),
+            *[
+                stat
+                for column in columns
+                for stat in (
+                    pl.col(column).mean().alias(f"{column}_mean"),
+                    pl.col(column).std().alias(f"{column}_std"),
+                    pl.col(column).min().alias(f"{column}_min"),
+                    pl.col(column).max().alias(f"{column}_max"),
+                )
+            ],
+        )
+        .sort(GROUP_BY)
+    )
+
+
+def markdown(frame):
+    with pl.Config(
+        tbl_formatting="MARKDOWN",
+        tbl_hide_column_data_types=True,
+        tbl_hide_dataframe_shape=True,
+        tbl_rows=-1,
+        tbl_cols=-1,
+        float_precision=3,
+    ):
+        return str(frame)
+
+
+def main():
+    node = Node()
+    batches = {}
+    for event in node:
+        if event["type"] == "INPUT":
+            # a struct array is a table, with one column per field
+            batch = pa.RecordBatch.from_struct_array(event["value"])
+            batches.setdefault(event["id"], []).append(batch)
+        elif event["type"] == "STOP":
+            break
+
+    os.makedirs(EXPORT_DIR, exist_ok=True)
+    summary = ["# Dataflow export", ""]
+    for input_id, input_batches in sorted(batches.items()):
+        frame = pl.from_arrow(pa.Table.from_batches(input_batches))
+        write_feather(frame, os.path.join(EXPORT_DIR, f"{input_id}.feather"))
+
+        summary += [
+            f"## `{input_id}`",
+            "",
+            f"{frame.height} rows from {len(input_batches)} messages, "
+            f"exported to `{input_id}.feather`.",
+            "",
+            "| column | type |",
+            "|---|---|",
+            *[f"| {name} | {dtype} |" for name, dtype in frame.schema.items()],
+            "",
+            markdown(frame.describe()),
+            "",
+        ]
+        if GROUP_BY in frame.columns:
+            stats = group_stats(frame)
+            write_feather(stats, os.path.join(EXPORT_DIR, f"{input_id}_stats.feather"))
+            summary += [
+                f"### Per `{GROUP_BY}`",
+                "",
+                markdown(stats),
+                "",
+            ]
+
+    with open(os.path.join(EXPORT_DIR, "summary.md"), "w") as file:
+        file.write("\n".join(summary))
+    print(f"exported {len(batches)} inputs to {EXPORT_DIR}", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,148 @@
+use arrow::{
+    array::{AsArray, RecordBatch},
+    datatypes::{DataType, SchemaRef, UInt32Type},
+    ipc::reader::FileReader,
+};
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use std::path::Path;
+
+/// Must match the `env` of the robot in `dataflow.yml`.
+const MESSAGES: usize = 100;
+const BATTERY_EVERY: usize = 10;
+/// Must match `JOINTS` in `robot.py`.
+const JOINTS: [&str; 6] = [
+    "joint_1", "joint_2", "joint_3", "joint_4", "joint_5", "joint_6",
+];
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("analysis-export-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let dora = Dora::from_env()?.uv();
+    dora.uv_venv("3.11").create().await?;
+
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    // read the exports with arrow-rs instead of Polars, like any other tool would
+    let joints = read_feather("out/joints.feather")?;
+    check_schema(
+        "joints",
+        &joints.0,
+        &[
+            ("seq", DataType::UInt64),
+            ("joint", DataType::LargeUtf8),
+            ("position", DataType::Float64),
+            ("velocity", DataType::Float64),
+            ("effort", DataType::Float64),
+        ],
+    )?;
+    check_rows("joints", &joints.1, MESSAGES * JOINTS.len())?;
+
+    let battery = read_feather("out/battery.feather")?;
+    check_schema(
+        "battery",
+        &battery.0,
+        &[
+            ("seq", DataType::UInt64),
+            ("voltage", DataType::Float64),
+            ("current", DataType::Float64),
+        ],
+    )?;
+    check_rows("battery", &battery.1, MESSAGES.div_ceil(BATTERY_EVERY))?;
+
+    let stats = read_feather("out/joints_stats.feather")?;
+    let stat_columns: Vec<String> = ["position", "velocity", "effort"]
+        .into_iter()
+        .flat_map(|column| ["mean", "std", "min", "max"].map(|stat| format!("{column}_{stat}")))
+        .collect();
+    let mut stats_fields = vec![
+        ("joint", DataType::LargeUtf8),
+        ("samples", DataType::UInt32),
+    ];
+    stats_fields.extend(
+        stat_columns
+            .iter()
+            .map(|name| (name.as_str(), DataType::Float64)),
+    );
+    check_schema("joints_stats", &stats.0, &stats_fields)?;
+    check_rows("joints_stats", &stats.1, JOINTS.len())?;
+    let mut joint_samples = Vec::new();
+    for batch in &stats.1 {
+        let joint = batch.column(0).as_string::<i64>();
+        let samples = batch.column(1).as_primitive::<UInt32Type>();
+        joint_samples.extend(
+            joint
+                .iter()
+                .zip(samples.iter())
+                .map(|(joint, samples)| (joint.unwrap_or_default(), samples.unwrap_or_default())),
+        );
+    }
+    let expected_samples: Vec<_> = JOINTS
+        .iter()
+        .map(|joint| (*joint, MESSAGES as u32))
+        .collect();
+    if joint_samples != expected_samples {
+        bail!(
+            "expected {expected_samples:?} samples per joint, the stats table has {joint_samples:?}"
+        );
+    }
+    println!("the exported frames have the expected schemas and row counts");
+
+    let summary =
+        std::fs::read_to_string("out/summary.md").context("out/summary.md was not written")?;
+    for section in ["## `battery`", "## `joints`", "### Per `joint`"] {
+        if !summary.contains(section) {
+            bail!("the summary has no section `{section}`");
+        }
+    }
+    println!("the summary has the stats tables of all inputs");
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Reads an Arrow IPC file, the format of Feather v2.
+fn read_feather(path: &str) -> eyre::Result<(SchemaRef, Vec<RecordBatch>)> {
+    let file = std::fs::File::open(path).with_context(|| format!("{path} was not written"))?;
+    let reader = FileReader::try_new(file, None)
+        .with_context(|| format!("{path} is not an Arrow IPC file"))?;
+    let schema = reader.schema();
+    let batches = reader
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("failed to read {path}"))?;
+    Ok((schema, batches))
+}
+
+fn check_schema(name: &str, schema: &SchemaRef, expected: &[(&str, DataType)]) -> eyre::Result<()> {
+    let actual: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| (field.name().as_str(), field.data_type().clone()))
+        .collect();
+    if actual != expected {
+        bail!("expected the columns {expected:?} in the `{name}` export, found {actual:?}");
+    }
+    Ok(())
+}
+
+fn check_rows(name: &str, batches: &[RecordBatch], expected: usize) -> eyre::Result<()> {
+    let rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+    if rows != expected {
+        bail!("expected {expected} rows in the `{name}` export, found {rows}");
+    }
+    Ok(())
+}
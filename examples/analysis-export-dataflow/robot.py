@@ -0,0 +1,77 @@
+#!/usr/bin/env python3
+"""
+Simulated robot arm.
+- Sends the state of its 6 joints on `joints` every tick, one row per joint
+- Sends its battery state on `battery` every `BATTERY_EVERY` ticks
+- Exits after `MESSAGES` ticks, which closes both outputs
+
+Both outputs are Arrow struct arrays, so every message is a small table with named, typed
+columns, like the messages of most robot drivers.
+"""
+
+import math
+import os
+
+import pyarrow as pa
+from dora import Node
+
+MESSAGES = int(os.getenv("MESSAGES", "100"))
+BATTERY_EVERY = int(os.getenv("BATTERY_EVERY", "10"))
+# Must match `JOINTS` in `main.rs`.
+JOINTS = [f"joint_{index}" for index in range(1, 7)]
+
+JOINT_STATE = pa.struct(
+    [
+        pa.field("seq", pa.uint64(), nullable=False),
+        pa.field("joint", pa.utf8(), nullable=False),
+        pa.field("position", pa.float64(), nullable=False),
+        pa.field("velocity", pa.float64(), nullable=False),
+        pa.field("effort", pa.float64(), nullable=False),
+    ]
+)
+BATTERY_STATE = pa.struct(
+    [
+        pa.field("seq", pa.uint64(), nullable=False),
+        pa.field("voltage", pa.float64(), nullable=False),
+        pa.field("current", pa.float64(), nullable=False),
+    ]
+)
+
+
+def joint_states(seq):
+    t = seq * 0.05
+    return [
+        {
+            "seq": seq,
+            "joint": joint,
+            "position": 0.5 * (index + 1) * math.sin(t + index),
+            "velocity": 0.5 * (index + 1) * math.cos(t + index),
+            "effort": 2.0 + math.sin(3.0 * t + index),
+        }
+        for index, joint in enumerate(JOINTS)
+    ]
+
+
+def main():
+    node = Node()
+    seq = 0
+    for event in node:
+        if event["type"] == "INPUT" and event["id"] == "tick":
+            if seq == MESSAGES:
+                break
+            node.send_output("joints", pa.array(joint_states(seq), type=JOINT_STATE))
+            if seq % BATTERY_EVERY == 0:
+                battery = {
+                    "seq": seq,
+                    "voltage": 24.0 - 0.01 * seq,
+                    "current": 3.0 + 0.5 * math.sin(seq * 0.05),
+                }
+                node.send_output("battery", pa.array([battery], type=BATTERY_STATE))
+            seq += 1
+        elif event["type"] == "STOP":
+            break
+    print(f"sent {seq} joint states", flush=True)
+
+
+if __name__ == "__main__":
+    main()
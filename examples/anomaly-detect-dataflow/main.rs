@@ -0,0 +1,28 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::Context;
+use std::path::Path;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("anomaly-detect-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    // pass `--isolation-forest` to also evaluate the optional isolation forest backend
+    if std::env::args().any(|arg| arg == "--isolation-forest") {
+        let dataflow = Path::new("dataflow_isolation_forest.yml");
+        dora.build_dataflow(dataflow).await?;
+        dora.run_dataflow(dataflow).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, MetadataParameters, Parameter,
+    arrow::{array::AsArray, datatypes::Float64Type},
+    dora_core::config::DataId,
+};
+use example_node_utils::env_or;
+use eyre::{bail, eyre};
+use std::collections::VecDeque;
+
+/// Mean and standard deviation over the last `capacity` normal values of one element.
+struct RollingStats {
+    values: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl RollingStats {
+    fn new(capacity: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    fn is_warm(&self) -> bool {
+        self.values.len() == self.capacity
+    }
+
+    fn mean(&self) -> f64 {
+        self.values.iter().sum::<f64>() / self.values.len() as f64
+    }
+
+    fn std_dev(&self) -> f64 {
+        let mean = self.mean();
+        let variance =
+            self.values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / self.values.len() as f64;
+        variance.sqrt().max(f64::EPSILON)
+    }
+
+    fn last(&self) -> Option<f64> {
+        self.values.back().copied()
+    }
+}
+
+/// Drift-invariant description of the most deviating element of a sample.
+struct Features {
+    index: usize,
+    /// Distance to the rolling mean, in rolling standard deviations.
+    z_score: f64,
+    /// Change compared to the previous normal value, in rolling standard deviations.
+    step: f64,
+}
+
+trait Backend {
+    /// Returns the anomaly score, or `None` while the backend is still warming up.
+    fn score(&mut self, features: &Features) -> eyre::Result<Option<f64>>;
+}
+
+/// Flags samples that are more than a fixed number of standard deviations away from the
+/// rolling mean.
+struct ZScore;
+
+impl Backend for ZScore {
+    fn score(&mut self, features: &Features) -> eyre::Result<Option<f64>> {
+        Ok(Some(features.z_score.abs()))
+    }
+}
+
+/// Trains an isolation forest on the first `train_samples` samples and scores all later
+/// samples with it. Scores are in `0..1`, values well above 0.5 indicate anomalies.
+#[cfg(feature = "isolation-forest")]
+struct IsolationForest {
+    train_samples: usize,
+    training: Vec<[f64; 2]>,
+    forest: Option<extended_isolation_forest::Forest<f64, 2>>,
+}
+
+#[cfg(feature = "isolation-forest")]
+impl Backend for IsolationForest {
+    fn score(&mut self, features: &Features) -> eyre::Result<Option<f64>> {
+        let point = [features.z_score, features.step];
+        if let Some(forest) = &self.forest {
+            return Ok(Some(forest.score(&point)));
+        }
+
+        self.training.push(point);
+        if self.training.len() == self.train_samples {
+            let options = extended_isolation_forest::ForestOptions {
+                n_trees: 100,
+                sample_size: self.train_samples.min(256),
+                max_tree_depth: None,
+                extension_level: 1,
+            };
+            let forest = extended_isolation_forest::Forest::from_slice(&self.training, &options)
+                .map_err(|err| eyre!("failed to train isolation forest: {err:?}"))?;
+            println!("trained isolation forest on {} samples", self.train_samples);
+            self.forest = Some(forest);
+        }
+        Ok(None)
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let window = env_or("WINDOW", 50.0)? as usize;
+    let backend_name = std::env::var("BACKEND").unwrap_or_else(|_| "zscore".to_owned());
+    let (mut backend, threshold): (Box<dyn Backend>, f64) = match backend_name.as_str() {
+        "zscore" => (Box::new(ZScore), env_or("THRESHOLD", 4.0)?),
+        #[cfg(feature = "isolation-forest")]
+        "isolation-forest" => (
+            Box::new(IsolationForest {
+                train_samples: env_or("TRAIN_SAMPLES", 300.0)? as usize,
+                training: Vec::new(),
+                forest: None,
+            }),
+            env_or("THRESHOLD", 0.7)?,
+        ),
+        #[cfg(not(feature = "isolation-forest"))]
+        "isolation-forest" => bail!("detector was built without the `isolation-forest` feature"),
+        other => bail!("unknown BACKEND `{other}`"),
+    };
+    let output = DataId::from("events".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut stats: Vec<RollingStats> = Vec::new();
+    let mut detected = 0;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "data" => {
+                    let values = data
+                        .as_primitive_opt::<Float64Type>()
+                        .ok_or_else(|| eyre!("expected float64 data"))?
+                        .values();
+                    if stats.is_empty() {
+                        stats = (0..values.len())
+                            .map(|_| RollingStats::new(window))
+                            .collect();
+                    } else if stats.len() != values.len() {
+                        bail!(
+                            "sample length changed from {} to {}",
+                            stats.len(),
+                            values.len()
+                        );
+                    }
+
+                    let features = if stats.iter().all(RollingStats::is_warm) {
+                        Some(features(values, &stats))
+                    } else {
+                        None
+                    };
+                    let score = match &features {
+                        Some(features) => backend.score(features)?,
+                        None => None,
+                    };
+
+                    match (score, features) {
+                        (Some(score), Some(features)) if score > threshold => {
+                            let seq = match metadata.parameters.get("seq") {
+                                Some(Parameter::Integer(seq)) => *seq,
+                                _ => bail!("sample without `seq` parameter"),
+                            };
+                            let label = if features.z_score > 0.0 {
+                                "spike"
+                            } else {
+                                "dip"
+                            };
+                            let mut parameters = MetadataParameters::default();
+                            parameters.insert("seq".into(), Parameter::Integer(seq));
+                            parameters.insert("score".into(), Parameter::Float(score));
+                            parameters.insert("label".into(), Parameter::String(label.into()));
+                            parameters
+                                .insert("detector".into(), Parameter::String(backend_name.clone()));
+                            parameters.insert(
+                                "element".into(),
+                                Parameter::Integer(features.index as i64),
+                            );
+                            println!("anomaly at seq {seq}: {label} (score {score:.3})");
+                            node.send_output(
+                                output.clone(),
+                                parameters,
+                                values[features.index].into_arrow(),
+                            )?;
+                            detected += 1;
+                        }
+                        // anomalies are kept out of the rolling statistics
+                        _ => {
+                            for (stats, value) in stats.iter_mut().zip(values.iter()) {
+                                stats.push(*value);
+                            }
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("detected {detected} anomalies");
+    Ok(())
+}
+
+fn features(values: &[f64], stats: &[RollingStats]) -> Features {
+    values
+        .iter()
+        .zip(stats)
+        .enumerate()
+        .map(|(index, (value, stats))| {
+            let std_dev = stats.std_dev();
+            Features {
+                index,
+                z_score: (value - stats.mean()) / std_dev,
+                step: (value - stats.last().unwrap_or(*value)) / std_dev,
+            }
+        })
+        .max_by(|a, b| a.z_score.abs().total_cmp(&b.z_score.abs()))
+        .expect("samples are never empty")
+}
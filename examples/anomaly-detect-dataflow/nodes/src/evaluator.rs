@@ -0,0 +1,72 @@
+use dora_node_api::{self, DoraNode, Event, Parameter};
+use example_node_utils::env_or;
+use eyre::{Context, bail};
+use std::collections::BTreeSet;
+
+/// Compares the detected anomalies with the ground truth of the generator.
+fn main() -> eyre::Result<()> {
+    // anomalies during the warm-up phase of the detector are not counted
+    let warmup: i64 = env_or("WARMUP_SAMPLES", 50.0)? as i64;
+    let min_recall = env_or("MIN_RECALL", 0.9)?;
+    let max_false_positive_rate = env_or("MAX_FALSE_POSITIVE_RATE", 0.005)?;
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut samples: i64 = 0;
+    let mut injected = BTreeSet::new();
+    let mut detected = BTreeSet::new();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "data" => samples += 1,
+                "ground_truth" => {
+                    let seq = u64::try_from(&data).context("expected u64 seq")? as i64;
+                    if seq >= warmup {
+                        injected.insert(seq);
+                    }
+                }
+                "events" => {
+                    let Some(Parameter::Integer(seq)) = metadata.parameters.get("seq") else {
+                        bail!("anomaly event without `seq` parameter");
+                    };
+                    if *seq >= warmup {
+                        detected.insert(*seq);
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let true_positives = detected.intersection(&injected).count();
+    let false_positives = detected.difference(&injected).count();
+    let missed: Vec<_> = injected.difference(&detected).collect();
+    let normal_samples = ((samples - warmup).max(0) as usize).saturating_sub(injected.len());
+    let recall = true_positives as f64 / injected.len().max(1) as f64;
+    let false_positive_rate = false_positives as f64 / normal_samples.max(1) as f64;
+
+    println!("samples:              {samples} ({warmup} warm-up)");
+    println!("injected anomalies:   {}", injected.len());
+    println!("detected correctly:   {true_positives} (recall {recall:.3})");
+    println!("false positives:      {false_positives} (rate {false_positive_rate:.4})");
+    if !missed.is_empty() {
+        println!("missed:               {missed:?}");
+    }
+
+    if injected.is_empty() {
+        bail!("no anomalies were injected after the warm-up phase");
+    }
+    if recall < min_recall {
+        bail!("recall {recall:.3} is below the required {min_recall}");
+    }
+    if false_positive_rate > max_false_positive_rate {
+        bail!(
+            "false positive rate {false_positive_rate:.4} exceeds the allowed \
+             {max_false_positive_rate}"
+        );
+    }
+    Ok(())
+}
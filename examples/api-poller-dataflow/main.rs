@@ -0,0 +1,97 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Must match `API_URL` in `dataflow.yml`.
+const API_PORT: &str = "18080";
+
+/// Subset of `ApiReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ApiReport {
+    requests: u64,
+    not_modified: u64,
+    rate_limited: u64,
+    service_unavailable: u64,
+    early_retries: u64,
+    final_version: u64,
+    vehicles: Vec<serde_json::Value>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("api-poller-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    // stands in for a weather or fleet backend; the poller runs for 15 s of the API's 20 s
+    let mut api = tokio::process::Command::new("nodes/target/release/mock-api")
+        .args(["--port", API_PORT])
+        .args(["--duration-secs", "20"])
+        .args(["--change-every-ms", "1000"])
+        .args(["--changes-until-secs", "10"])
+        .args(["--limit", "10"])
+        .args(["--window-secs", "5"])
+        .args(["--outage-at-secs", "6"])
+        .args(["--retry-after-secs", "2"])
+        .args(["--report", "out/api.json"])
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start mock API")?;
+
+    dora.run_dataflow(dataflow).await?;
+
+    if !api.wait().await?.success() {
+        bail!("mock API failed");
+    }
+    let report: ApiReport = serde_json::from_str(
+        &std::fs::read_to_string("out/api.json").context("mock API did not write a report")?,
+    )?;
+    let mut fleet: Vec<serde_json::Value> = serde_json::from_str(
+        &std::fs::read_to_string("out/fleet.json").context("fleet-log did not write the fleet")?,
+    )?;
+
+    if report.rate_limited > 0 {
+        bail!(
+            "the poller exceeded the rate limit {} times",
+            report.rate_limited
+        );
+    }
+    if report.early_retries > 0 {
+        bail!(
+            "the poller retried {} times before `Retry-After` had passed",
+            report.early_retries
+        );
+    }
+    if report.service_unavailable != 1 {
+        bail!("expected the poller to hit the outage of the API");
+    }
+    if report.not_modified == 0 {
+        bail!("the poller never sent the ETag of the last response");
+    }
+    let mut expected = report.vehicles;
+    let by_id = |v: &serde_json::Value| v["id"].as_str().unwrap_or_default().to_owned();
+    expected.sort_by_key(by_id);
+    fleet.sort_by_key(by_id);
+    if fleet != expected {
+        bail!(
+            "the fleet rebuilt from the deltas differs from version {} of the API:\n{}\nexpected:\n{}",
+            report.final_version,
+            serde_json::to_string_pretty(&fleet)?,
+            serde_json::to_string_pretty(&expected)?
+        );
+    }
+    println!(
+        "{} requests, {} answered with 304, no rate limit violations, fleet matches version {}",
+        report.requests, report.not_modified, report.final_version
+    );
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use api_poller_dataflow_nodes::{Change, ETAG_KEY, Fleet, Vehicle, VehicleDelta, env_or};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::StringArray,
+    dora_core::config::DataId,
+};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Polls the JSON API at `API_URL` every `POLL_INTERVAL_MS`, or less often when the API asks
+/// for it, and sends the vehicles that changed on `delta`, one JSON `VehicleDelta` per row.
+///
+/// - Sends the `ETag` of the last response in `If-None-Match`, so that an unchanged fleet
+///   costs a `304` without a body.
+/// - Pauses until the window resets when `X-RateLimit-Remaining` reaches 0.
+/// - Waits for `Retry-After` on `429` and `503`, and backs off exponentially on other errors.
+///
+/// Stops after `DURATION_MS`, if set.
+fn main() -> eyre::Result<()> {
+    let url: String = env_or(
+        "API_URL",
+        "http://127.0.0.1:18080/fleet/vehicles".to_owned(),
+    )?;
+    let interval = Duration::from_millis(env_or("POLL_INTERVAL_MS", 200)?);
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let output = DataId::from("delta".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut poller = Poller {
+        agent: ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(2))
+            .user_agent("dora-api-poller/0.1")
+            .build(),
+        url,
+        interval,
+        etag: None,
+        fleet: BTreeMap::new(),
+        next_poll: Instant::now(),
+        backoff: MIN_BACKOFF,
+        stats: Stats::default(),
+    };
+    let start = Instant::now();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                // the tick only checks whether the next poll is due
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    if Instant::now() < poller.next_poll {
+                        continue;
+                    }
+                    let deltas = poller.poll();
+                    if deltas.is_empty() {
+                        continue;
+                    }
+                    let rows = deltas
+                        .iter()
+                        .map(serde_json::to_string)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let mut parameters = MetadataParameters::default();
+                    if let Some(etag) = &poller.etag {
+                        parameters.insert(ETAG_KEY.into(), Parameter::String(etag.clone()));
+                    }
+                    node.send_output(output.clone(), parameters, StringArray::from(rows))?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let stats = &poller.stats;
+    println!(
+        "{} requests: {} changed, {} not modified, {} throttled, {} errors",
+        stats.requests, stats.changed, stats.not_modified, stats.throttled, stats.errors
+    );
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    requests: u64,
+    changed: u64,
+    not_modified: u64,
+    throttled: u64,
+    errors: u64,
+}
+
+struct Poller {
+    agent: ureq::Agent,
+    url: String,
+    interval: Duration,
+    /// `ETag` of the last `200` response, and the fleet that it contained.
+    etag: Option<String>,
+    fleet: BTreeMap<String, Vehicle>,
+    next_poll: Instant,
+    backoff: Duration,
+    stats: Stats,
+}
+
+impl Poller {
+    /// Sends one request and returns the changes since the last successful one.
+    fn poll(&mut self) -> Vec<VehicleDelta> {
+        let mut request = self.agent.get(&self.url);
+        if let Some(etag) = &self.etag {
+            request = request.set("If-None-Match", etag);
+        }
+        self.stats.requests += 1;
+        let result = request.call();
+        // waits are relative to the response, which the server sent after it counted the
+        // request
+        let now = Instant::now();
+        self.next_poll = now + self.interval;
+        match result {
+            Ok(response) => {
+                self.backoff = MIN_BACKOFF;
+                self.respect_rate_limit(&response, now);
+                if response.status() == 304 {
+                    self.stats.not_modified += 1;
+                    return Vec::new();
+                }
+                let etag = response.header("ETag").map(str::to_owned);
+                let body = response.into_string().map_err(|err| err.to_string());
+                let fleet = match body.and_then(|body| {
+                    serde_json::from_str::<Fleet>(&body).map_err(|err| err.to_string())
+                }) {
+                    Ok(fleet) => fleet,
+                    Err(err) => {
+                        eprintln!("invalid response body: {err}");
+                        self.stats.errors += 1;
+                        return Vec::new();
+                    }
+                };
+                // only remember the ETag together with the body that it belongs to
+                self.etag = etag;
+                self.stats.changed += 1;
+                self.update(fleet.vehicles)
+            }
+            Err(ureq::Error::Status(status @ (429 | 503), response)) => {
+                self.stats.throttled += 1;
+                self.respect_rate_limit(&response, now);
+                match retry_after(&response) {
+                    Some(wait) => {
+                        eprintln!("API answered {status}, retrying in {wait:?}");
+                        self.next_poll = self.next_poll.max(now + wait);
+                    }
+                    None => self.back_off(now),
+                }
+                Vec::new()
+            }
+            Err(err) => {
+                eprintln!("request failed: {err}");
+                self.stats.errors += 1;
+                self.back_off(now);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Waits for the next window once the current one is used up.
+    fn respect_rate_limit(&mut self, response: &ureq::Response, now: Instant) {
+        let header = |name| {
+            response
+                .header(name)
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        if let (Some(0), Some(reset)) =
+            (header("X-RateLimit-Remaining"), header("X-RateLimit-Reset"))
+        {
+            self.next_poll = self.next_poll.max(now + Duration::from_secs(reset));
+        }
+    }
+
+    fn back_off(&mut self, now: Instant) {
+        self.next_poll = self.next_poll.max(now + self.backoff);
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+    }
+
+    /// Replaces the fleet and returns the vehicles that were added, changed, or removed.
+    fn update(&mut self, vehicles: Vec<Vehicle>) -> Vec<VehicleDelta> {
+        let mut previous = std::mem::take(&mut self.fleet);
+        let mut deltas = Vec::new();
+        for vehicle in vehicles {
+            let change = match previous.remove(&vehicle.id) {
+                None => Some(Change::Added),
+                Some(old) if old != vehicle => Some(Change::Updated),
+                Some(_) => None,
+            };
+            if let Some(change) = change {
+                deltas.push(VehicleDelta {
+                    id: vehicle.id.clone(),
+                    change,
+                    vehicle: Some(vehicle.clone()),
+                });
+            }
+            self.fleet.insert(vehicle.id.clone(), vehicle);
+        }
+        deltas.extend(previous.into_keys().map(|id| VehicleDelta {
+            id,
+            change: Change::Removed,
+            vehicle: None,
+        }));
+        deltas
+    }
+}
+
+/// `Retry-After` in seconds. It may also be an HTTP date, which this example does not handle.
+fn retry_after(response: &ureq::Response) -> Option<Duration> {
+    let seconds = response.header("Retry-After")?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
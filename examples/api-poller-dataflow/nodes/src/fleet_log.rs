@@ -0,0 +1,65 @@
+use api_poller_dataflow_nodes::{
+    Change, ETAG_KEY, Vehicle, VehicleDelta, create_jsonl, env_or, write_jsonl,
+};
+use dora_node_api::{self, DoraNode, Event, Parameter, arrow::array::AsArray};
+use eyre::{OptionExt, bail};
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Applies the deltas of the poller to its own copy of the fleet, and logs every delta to
+/// `DELTA_FILE`.
+///
+/// Writes the resulting fleet to `FLEET_FILE` when the `delta` input closes.
+fn main() -> eyre::Result<()> {
+    let delta_file: PathBuf = env_or("DELTA_FILE", "out/deltas.jsonl".to_owned())?.into();
+    let fleet_file: PathBuf = env_or("FLEET_FILE", "out/fleet.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut log = create_jsonl(&delta_file)?;
+    let mut fleet: BTreeMap<String, Vehicle> = BTreeMap::new();
+    let mut deltas = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "delta" => {
+                    let etag = match metadata.parameters.get(ETAG_KEY) {
+                        Some(Parameter::String(etag)) => etag.as_str(),
+                        _ => "",
+                    };
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array of deltas")?;
+                    for row in rows.iter() {
+                        let delta: VehicleDelta =
+                            serde_json::from_str(row.ok_or_eyre("null delta")?)?;
+                        match (delta.change, &delta.vehicle) {
+                            (Change::Added | Change::Updated, Some(vehicle)) => {
+                                fleet.insert(delta.id.clone(), vehicle.clone());
+                            }
+                            (Change::Removed, None) => {
+                                fleet.remove(&delta.id);
+                            }
+                            (change, _) => bail!("invalid {change:?} delta for `{}`", delta.id),
+                        }
+                        println!("{etag} {:?} {}", delta.change, delta.id);
+                        write_jsonl(&mut log, &delta)?;
+                        deltas += 1;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "delta" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("applied {deltas} deltas, {} vehicles", fleet.len());
+    let vehicles: Vec<&Vehicle> = fleet.values().collect();
+    std::fs::write(&fleet_file, serde_json::to_string_pretty(&vehicles)?)?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use eyre::Context;
+use serde::{Deserialize, Serialize};
+use std::{io::Write, path::Path};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the `ETag` of the response that a delta message was computed from.
+pub const ETAG_KEY: &str = "etag";
+
+/// A vehicle as returned by the fleet API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vehicle {
+    pub id: String,
+    pub status: String,
+    pub battery_pct: u8,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Body of `GET /fleet/vehicles`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Fleet {
+    pub vehicles: Vec<Vehicle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Added,
+    Updated,
+    Removed,
+}
+
+/// One row of a `delta` message: a vehicle that changed since the previous response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VehicleDelta {
+    pub id: String,
+    pub change: Change,
+    /// The new state, `None` for removed vehicles.
+    pub vehicle: Option<Vehicle>,
+}
+
+/// Written by the mock API when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ApiReport {
+    pub requests: u64,
+    pub ok: u64,
+    pub not_modified: u64,
+    /// Requests over the rate limit, answered with `429 Too Many Requests`.
+    pub rate_limited: u64,
+    pub service_unavailable: u64,
+    /// Requests that arrived before the `Retry-After` of a previous response had passed.
+    pub early_retries: u64,
+    pub final_version: u64,
+    pub vehicles: Vec<Vehicle>,
+}
+
+pub fn create_jsonl(path: &Path) -> eyre::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))
+}
+
+pub fn write_jsonl<T: Serialize>(file: &mut std::fs::File, record: &T) -> eyre::Result<()> {
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
@@ -0,0 +1,287 @@
+//! A minimal fleet backend that serves `GET /fleet/vehicles` with ETags and a rate limit.
+//!
+//! Usage: `mock-api --port <port> [--duration-secs <n>] [--change-every-ms <n>]
+//! [--changes-until-secs <n>] [--limit <n>] [--window-secs <n>] [--outage-at-secs <n>]
+//! [--retry-after-secs <n>] [--report <path>]`
+//!
+//! The fleet changes every `--change-every-ms` until `--changes-until-secs`. Each client may
+//! send `--limit` requests per window of `--window-secs`, more are answered with `429`. Once,
+//! at `--outage-at-secs`, the API answers with `503` and a `Retry-After` header. When it
+//! stops, the API writes what it has seen to `--report`.
+
+use api_poller_dataflow_nodes::{ApiReport, Fleet, Vehicle};
+use eyre::{Context, bail};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+struct Args {
+    port: u16,
+    duration: Duration,
+    change_every: Duration,
+    changes_until: Duration,
+    limit: u64,
+    window_secs: u64,
+    outage_at: Duration,
+    retry_after_secs: u64,
+    report: PathBuf,
+}
+
+fn main() -> eyre::Result<()> {
+    let args = parse_args()?;
+    let listener = TcpListener::bind(("127.0.0.1", args.port))
+        .with_context(|| format!("failed to listen on port {}", args.port))?;
+    // poll for connections, so that the API stops after `--duration-secs` even when idle
+    listener.set_nonblocking(true)?;
+    println!("fleet API listening on port {}", args.port);
+
+    let mut report = ApiReport::default();
+    let mut window = 0;
+    let mut window_requests = 0;
+    let mut outage_done = false;
+    let mut retry_deadline: Option<Instant> = None;
+    let start = Instant::now();
+    while start.elapsed() < args.duration {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+        let request = match read_request(&mut BufReader::new(&stream)) {
+            Ok(request) => request,
+            Err(err) => {
+                eprintln!("failed to read request: {err}");
+                continue;
+            }
+        };
+        report.requests += 1;
+        let now = Instant::now();
+        let elapsed = now - start;
+
+        // the client has to wait for `Retry-After` before it tries again
+        if let Some(deadline) = retry_deadline {
+            if now < deadline {
+                report.early_retries += 1;
+                let wait = (deadline - now).as_secs() + 1;
+                respond(
+                    &stream,
+                    "503 Service Unavailable",
+                    &[("Retry-After", wait)],
+                    None,
+                )?;
+                continue;
+            }
+            retry_deadline = None;
+        }
+        if !outage_done && elapsed >= args.outage_at {
+            outage_done = true;
+            report.service_unavailable += 1;
+            retry_deadline = Some(now + Duration::from_secs(args.retry_after_secs));
+            let headers = [("Retry-After", args.retry_after_secs)];
+            respond(&stream, "503 Service Unavailable", &headers, None)?;
+            continue;
+        }
+
+        // fixed windows, like most APIs that send `X-RateLimit-*` headers
+        let elapsed_secs = elapsed.as_secs();
+        if elapsed_secs / args.window_secs != window {
+            window = elapsed_secs / args.window_secs;
+            window_requests = 0;
+        }
+        window_requests += 1;
+        let reset = args.window_secs - elapsed_secs % args.window_secs;
+        if window_requests > args.limit {
+            report.rate_limited += 1;
+            let headers = [
+                ("Retry-After", reset),
+                ("X-RateLimit-Limit", args.limit),
+                ("X-RateLimit-Remaining", 0),
+                ("X-RateLimit-Reset", reset),
+            ];
+            respond(&stream, "429 Too Many Requests", &headers, None)?;
+            continue;
+        }
+        let rate_headers = [
+            ("X-RateLimit-Limit", args.limit),
+            ("X-RateLimit-Remaining", args.limit - window_requests),
+            ("X-RateLimit-Reset", reset),
+        ];
+
+        if request.path != "/fleet/vehicles" {
+            respond(&stream, "404 Not Found", &rate_headers, None)?;
+            continue;
+        }
+        let version = version(&args, elapsed);
+        let etag = format!("\"fleet-{version}\"");
+        if request.if_none_match.as_deref() == Some(etag.as_str()) {
+            report.not_modified += 1;
+            respond(
+                &stream,
+                "304 Not Modified",
+                &rate_headers,
+                Some((&etag, None)),
+            )?;
+        } else {
+            report.ok += 1;
+            let body = serde_json::to_string(&Fleet {
+                vehicles: fleet(version),
+            })?;
+            respond(&stream, "200 OK", &rate_headers, Some((&etag, Some(&body))))?;
+        }
+    }
+
+    report.final_version = version(&args, start.elapsed());
+    report.vehicles = fleet(report.final_version);
+    println!(
+        "served {} requests: {} ok, {} not modified, {} rate limited, {} early retries",
+        report.requests, report.ok, report.not_modified, report.rate_limited, report.early_retries
+    );
+    if let Some(parent) = args.report.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&args.report, serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
+
+fn version(args: &Args, elapsed: Duration) -> u64 {
+    (elapsed.min(args.changes_until).as_millis() / args.change_every.as_millis()) as u64
+}
+
+/// The fleet after `version` changes: one vehicle joins at version 3, one leaves at version 6,
+/// and otherwise one vehicle per version drives or charges.
+fn fleet(version: u64) -> Vec<Vehicle> {
+    let mut vehicles: Vec<Vehicle> = (1..=5).map(|i| vehicle(&format!("v{i}"), i)).collect();
+    for change in 1..=version {
+        match change {
+            3 => vehicles.push(vehicle("v6", 6)),
+            6 => vehicles.retain(|v| v.id != "v2"),
+            _ => {
+                let index = change as usize % vehicles.len();
+                let v = &mut vehicles[index];
+                if v.status == "driving" {
+                    v.status = "charging".to_owned();
+                    v.battery_pct = (v.battery_pct + 10).min(100);
+                } else {
+                    v.status = "driving".to_owned();
+                    v.battery_pct = v.battery_pct.saturating_sub(4);
+                    v.lat += 0.001;
+                }
+            }
+        }
+    }
+    vehicles
+}
+
+fn vehicle(id: &str, i: u64) -> Vehicle {
+    Vehicle {
+        id: id.to_owned(),
+        status: "parked".to_owned(),
+        battery_pct: 60 + 5 * i as u8,
+        lat: 47.37 + 0.01 * i as f64,
+        lon: 8.54,
+    }
+}
+
+struct Request {
+    path: String,
+    if_none_match: Option<String>,
+}
+
+fn read_request(reader: &mut impl BufRead) -> eyre::Result<Request> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            bail!("connection closed during request");
+        }
+        let line = line.trim_end().to_owned();
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    let path = lines
+        .first()
+        .and_then(|line| line.strip_prefix("GET "))
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap_or_default()
+        .to_owned();
+    let if_none_match = lines.iter().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("If-None-Match")
+            .then(|| value.trim().to_owned())
+    });
+    Ok(Request {
+        path,
+        if_none_match,
+    })
+}
+
+/// Sends a response and closes the connection. `entity` is the `ETag` and the JSON body.
+fn respond(
+    mut stream: &TcpStream,
+    status: &str,
+    headers: &[(&str, u64)],
+    entity: Option<(&str, Option<&str>)>,
+) -> eyre::Result<()> {
+    let mut response = format!("HTTP/1.1 {status}\r\nConnection: close\r\n");
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    let body = match entity {
+        Some((etag, body)) => {
+            response.push_str(&format!("ETag: {etag}\r\n"));
+            body.unwrap_or_default()
+        }
+        None => "",
+    };
+    if !body.is_empty() {
+        response.push_str("Content-Type: application/json\r\n");
+    }
+    response.push_str(&format!("Content-Length: {}\r\n\r\n{body}", body.len()));
+    stream.write_all(response.as_bytes())?;
+    Ok(())
+}
+
+fn parse_args() -> eyre::Result<Args> {
+    let mut args = Args {
+        port: 18080,
+        duration: Duration::from_secs(20),
+        change_every: Duration::from_millis(1000),
+        changes_until: Duration::from_secs(10),
+        limit: 10,
+        window_secs: 5,
+        outage_at: Duration::from_secs(6),
+        retry_after_secs: 2,
+        report: PathBuf::from("out/api.json"),
+    };
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = || {
+            argv.next()
+                .ok_or_else(|| eyre::eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--port" => args.port = value()?.parse()?,
+            "--duration-secs" => args.duration = Duration::from_secs(value()?.parse()?),
+            "--change-every-ms" => args.change_every = Duration::from_millis(value()?.parse()?),
+            "--changes-until-secs" => args.changes_until = Duration::from_secs(value()?.parse()?),
+            "--limit" => args.limit = value()?.parse()?,
+            "--window-secs" => args.window_secs = value()?.parse()?,
+            "--outage-at-secs" => args.outage_at = Duration::from_secs(value()?.parse()?),
+            "--retry-after-secs" => args.retry_after_secs = value()?.parse()?,
+            "--report" => args.report = value()?.into(),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    Ok(args)
+}
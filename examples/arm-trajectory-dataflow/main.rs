@@ -0,0 +1,97 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Largest allowed joint error while moving, in radians.
+const MAX_TRACKING_ERROR: f64 = 0.05;
+/// Largest allowed joint error after holding the end of a trajectory, in radians.
+const MAX_SETTLED_ERROR: f64 = 0.005;
+/// The interpolator should stream at 250 Hz, allow for some timer jitter.
+const MIN_RATE_HZ: f64 = 200.0;
+/// Number of trajectories that the generator sends, 3 moves per cycle.
+const TRAJECTORIES: usize = 6;
+
+/// Subset of `TrackingReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct TrackingReport {
+    trajectory_id: u64,
+    rate_hz: f64,
+    max_error: f64,
+    settled_error: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("arm-trajectory-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    if std::env::args().any(|arg| arg == "--ros2") {
+        return run_with_ros2().await;
+    }
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let reports: Vec<TrackingReport> = serde_json::from_str(
+        &std::fs::read_to_string("out/tracking.json").context("arm did not write a report")?,
+    )?;
+    if reports.len() != TRAJECTORIES {
+        bail!(
+            "expected reports for {TRAJECTORIES} trajectories, got {}",
+            reports.len()
+        );
+    }
+    for report in &reports {
+        let id = report.trajectory_id;
+        if report.rate_hz < MIN_RATE_HZ {
+            bail!("trajectory {id} was streamed at {:.0} Hz", report.rate_hz);
+        }
+        if report.max_error > MAX_TRACKING_ERROR {
+            bail!(
+                "trajectory {id}: tracking error {:.4} rad exceeds {MAX_TRACKING_ERROR} rad",
+                report.max_error
+            );
+        }
+        if report.settled_error > MAX_SETTLED_ERROR {
+            bail!(
+                "trajectory {id}: settled error {:.5} rad exceeds {MAX_SETTLED_ERROR} rad",
+                report.settled_error
+            );
+        }
+    }
+    println!("all {TRAJECTORIES} trajectories were tracked within bounds");
+
+    Ok(())
+}
+
+/// Sends the trajectories to a mock `FollowJointTrajectory` controller instead of the
+/// simulated arm.
+async fn run_with_ros2() -> eyre::Result<()> {
+    let ros = std::env::var("ROS").unwrap_or_else(|_| "/opt/ros/jazzy/setup.bash".into());
+    let mut controller = tokio::process::Command::new("bash")
+        .arg("-c")
+        .arg(format!(
+            "source {ros}; exec python3 ros2/mock_arm_controller.py --goals {TRAJECTORIES}"
+        ))
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start mock controller")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow_ros2.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    if !controller.wait().await?.success() {
+        bail!("mock controller failed");
+    }
+    Ok(())
+}
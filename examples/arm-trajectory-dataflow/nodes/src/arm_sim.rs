@@ -0,0 +1,136 @@
+use arm_trajectory_dataflow_nodes::{
+    HOLDING_KEY, HOME, TrackingReport, env_or, setpoint_from_arrow, setpoint_to_arrow,
+    trajectory_id,
+};
+use dora_node_api::{self, DoraNode, Event, IntoArrow, Parameter, dora_core::config::DataId};
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Tracking statistics of a trajectory, while it's being collected.
+#[derive(Default)]
+struct Tracking {
+    report: TrackingReport,
+    squared_error_sum: f64,
+    moving_samples: u64,
+    first_setpoint: Option<Instant>,
+    last_moving_setpoint: Option<Instant>,
+}
+
+/// A simulated arm with velocity-controlled joints.
+///
+/// Every joint follows the velocity of the setpoint plus a correction proportional to its
+/// position error. The drive reaches a commanded velocity with the time constant
+/// `TIME_CONSTANT_MS` and at most `MAX_ACCELERATION`, so the arm lags behind the setpoints
+/// like a real one.
+fn main() -> eyre::Result<()> {
+    let position_gain: f64 = env_or("POSITION_GAIN", 20.0)?;
+    let time_constant = env_or::<f64>("TIME_CONSTANT_MS", 15.0)? / 1000.0;
+    let max_acceleration: f64 = env_or("MAX_ACCELERATION", 10.0)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/tracking.json".to_owned())?.into();
+    let state_output = DataId::from("joint_state".to_owned());
+    let error_output = DataId::from("tracking_error".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut positions = HOME.to_vec();
+    let mut velocities = vec![0.0; HOME.len()];
+    let mut last_update: Option<Instant> = None;
+    let mut tracking: BTreeMap<u64, Tracking> = BTreeMap::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "setpoint" => {
+                    let (target_positions, target_velocities) = setpoint_from_arrow(&data.0)?;
+                    let now = Instant::now();
+                    // a late setpoint must not make the simulation jump
+                    let dt = last_update
+                        .map_or(Duration::ZERO, |last| now - last)
+                        .min(Duration::from_millis(20))
+                        .as_secs_f64();
+                    last_update = Some(now);
+
+                    let mut error = 0.0f64;
+                    for j in 0..positions.len() {
+                        let command = target_velocities[j]
+                            + position_gain * (target_positions[j] - positions[j]);
+                        let acceleration = ((command - velocities[j]) / time_constant)
+                            .clamp(-max_acceleration, max_acceleration);
+                        velocities[j] += acceleration * dt;
+                        positions[j] += velocities[j] * dt;
+                        error = error.max((target_positions[j] - positions[j]).abs());
+                    }
+
+                    if let Ok(trajectory) = trajectory_id(&metadata.parameters) {
+                        let holding = matches!(
+                            metadata.parameters.get(HOLDING_KEY),
+                            Some(Parameter::Bool(true))
+                        );
+                        let entry = tracking.entry(trajectory).or_default();
+                        entry.report.trajectory_id = trajectory;
+                        entry.report.setpoints += 1;
+                        if holding {
+                            // overwritten until the next trajectory starts
+                            entry.report.settled_error = error;
+                        } else {
+                            entry.first_setpoint.get_or_insert(now);
+                            entry.last_moving_setpoint = Some(now);
+                            entry.moving_samples += 1;
+                            entry.squared_error_sum += error * error;
+                            entry.report.max_error = entry.report.max_error.max(error);
+                        }
+                    }
+
+                    node.send_output(
+                        state_output.clone(),
+                        Default::default(),
+                        setpoint_to_arrow(&positions, &velocities),
+                    )?;
+                    node.send_output(error_output.clone(), Default::default(), error.into_arrow())?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let reports: Vec<TrackingReport> = tracking
+        .into_values()
+        .map(|mut tracking| {
+            if let (Some(first), Some(last)) =
+                (tracking.first_setpoint, tracking.last_moving_setpoint)
+            {
+                let duration = (last - first).as_secs_f64();
+                tracking.report.duration_s = duration;
+                if duration > 0.0 {
+                    tracking.report.rate_hz = (tracking.moving_samples - 1) as f64 / duration;
+                }
+            }
+            if tracking.moving_samples > 0 {
+                tracking.report.rms_error =
+                    (tracking.squared_error_sum / tracking.moving_samples as f64).sqrt();
+            }
+            tracking.report
+        })
+        .collect();
+    for report in &reports {
+        println!(
+            "trajectory {}: {:.2} s at {:.0} Hz, max error {:.4} rad, rms {:.4} rad, \
+             settled {:.5} rad",
+            report.trajectory_id,
+            report.duration_s,
+            report.rate_hz,
+            report.max_error,
+            report.rms_error,
+            report.settled_error
+        );
+    }
+    if let Some(parent) = report_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&reports)?)?;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use arm_trajectory_dataflow_nodes::{
+    HOLDING_KEY, HOME, TIME_FROM_START_KEY, TRAJECTORY_ID_KEY, Trajectory, TrajectoryPoint, env_or,
+    setpoint_to_arrow,
+};
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, MetadataParameters, Parameter, dora_core::config::DataId,
+};
+use eyre::bail;
+use std::time::{Duration, Instant};
+
+struct Active {
+    trajectory: Trajectory,
+    started: Instant,
+}
+
+/// Streams setpoints of the current trajectory on every `tick`, e.g. at 250 Hz.
+///
+/// A new trajectory replaces the current one and starts from the last setpoint, so the arm
+/// never jumps. After the end of a trajectory, its last point is held.
+fn main() -> eyre::Result<()> {
+    let settle = Duration::from_millis(env_or("SETTLE_MS", 200)?);
+    let setpoint_output = DataId::from("setpoint".to_owned());
+    let done_output = DataId::from("done".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut positions = HOME.to_vec();
+    let mut velocities = vec![0.0; HOME.len()];
+    let mut active: Option<Active> = None;
+    let mut last_id = None;
+    let mut holding_since = Instant::now();
+    let mut trajectories_closed = false;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "trajectory" => {
+                    let mut trajectory = Trajectory::from_arrow(&metadata.parameters, &data.0)?;
+                    if trajectory.joint_names.len() != positions.len() {
+                        bail!(
+                            "trajectory {} has {} joints, the arm has {}",
+                            trajectory.id,
+                            trajectory.joint_names.len(),
+                            positions.len()
+                        );
+                    }
+                    // like ROS2 controllers, start from the current setpoint if the first
+                    // point is in the future
+                    if trajectory
+                        .points
+                        .first()
+                        .is_some_and(|point| point.time_from_start > 0.0)
+                    {
+                        trajectory.points.insert(
+                            0,
+                            TrajectoryPoint {
+                                time_from_start: 0.0,
+                                positions: positions.clone(),
+                                velocities: velocities.clone(),
+                            },
+                        );
+                    }
+                    if let Some(previous) = &active {
+                        println!(
+                            "trajectory {} preempts trajectory {}",
+                            trajectory.id, previous.trajectory.id
+                        );
+                    }
+                    last_id = Some(trajectory.id);
+                    active = Some(Active {
+                        trajectory,
+                        started: Instant::now(),
+                    });
+                }
+                "tick" => {
+                    let mut parameters = MetadataParameters::default();
+                    if let Some(current) = &active {
+                        let t = current.started.elapsed().as_secs_f64();
+                        (positions, velocities) = current.trajectory.sample(t);
+                        parameters.insert(TIME_FROM_START_KEY.into(), Parameter::Float(t));
+                        if t >= current.trajectory.duration() {
+                            let mut done = MetadataParameters::default();
+                            done.insert(
+                                TRAJECTORY_ID_KEY.into(),
+                                Parameter::Integer(current.trajectory.id as i64),
+                            );
+                            node.send_output(done_output.clone(), done, t.into_arrow())?;
+                            active = None;
+                            holding_since = Instant::now();
+                        }
+                    } else {
+                        velocities.fill(0.0);
+                    }
+                    if let Some(id) = last_id {
+                        parameters.insert(TRAJECTORY_ID_KEY.into(), Parameter::Integer(id as i64));
+                    }
+                    parameters.insert(HOLDING_KEY.into(), Parameter::Bool(active.is_none()));
+                    node.send_output(
+                        setpoint_output.clone(),
+                        parameters,
+                        setpoint_to_arrow(&positions, &velocities),
+                    )?;
+
+                    // give the arm some time to settle at the end of the last trajectory
+                    if trajectories_closed && active.is_none() && holding_since.elapsed() >= settle
+                    {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "trajectory" {
+                    trajectories_closed = true;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
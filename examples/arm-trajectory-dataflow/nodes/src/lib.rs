@@ -0,0 +1,187 @@
+use dora_node_api::{
+    MetadataParameters, Parameter,
+    arrow::{
+        array::{Array, AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+};
+use eyre::{OptionExt, bail, eyre};
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the id of a trajectory, also attached to its setpoints.
+pub const TRAJECTORY_ID_KEY: &str = "trajectory_id";
+/// Metadata key of the joint names of a trajectory.
+pub const JOINT_NAMES_KEY: &str = "joint_names";
+/// Metadata key of the time of a setpoint relative to the start of its trajectory.
+pub const TIME_FROM_START_KEY: &str = "time_from_start";
+/// Metadata key that is `true` for setpoints that hold the end of a finished trajectory.
+pub const HOLDING_KEY: &str = "holding";
+
+/// Joints of a 6-axis arm, in the order of all position and velocity vectors.
+pub const JOINT_NAMES: [&str; 6] = [
+    "shoulder_pan_joint",
+    "shoulder_lift_joint",
+    "elbow_joint",
+    "wrist_1_joint",
+    "wrist_2_joint",
+    "wrist_3_joint",
+];
+
+/// Joint positions that the arm starts at, in radians.
+pub const HOME: [f64; 6] = [0.0, -1.57, 1.57, -1.57, -1.57, 0.0];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryPoint {
+    /// Seconds since the start of the trajectory.
+    pub time_from_start: f64,
+    pub positions: Vec<f64>,
+    pub velocities: Vec<f64>,
+}
+
+/// A time-parameterized joint trajectory, like `trajectory_msgs/JointTrajectory`.
+#[derive(Debug, Clone)]
+pub struct Trajectory {
+    pub id: u64,
+    pub joint_names: Vec<String>,
+    pub points: Vec<TrajectoryPoint>,
+}
+
+impl Trajectory {
+    pub fn duration(&self) -> f64 {
+        self.points.last().map_or(0.0, |p| p.time_from_start)
+    }
+
+    /// Returns positions and velocities at `t`, interpolated with cubic Hermite splines
+    /// between the points. Before the first and after the last point, the trajectory holds.
+    pub fn sample(&self, t: f64) -> (Vec<f64>, Vec<f64>) {
+        let hold =
+            |point: &TrajectoryPoint| (point.positions.clone(), vec![0.0; point.positions.len()]);
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            return (Vec::new(), Vec::new());
+        };
+        if t <= first.time_from_start {
+            return (first.positions.clone(), first.velocities.clone());
+        }
+        if t >= last.time_from_start {
+            return hold(last);
+        }
+        let end = self.points.partition_point(|p| p.time_from_start <= t);
+        let (a, b) = (&self.points[end - 1], &self.points[end]);
+        let h = b.time_from_start - a.time_from_start;
+        let s = (t - a.time_from_start) / h;
+        let (s2, s3) = (s * s, s * s * s);
+        let positions = (0..a.positions.len())
+            .map(|j| {
+                (2.0 * s3 - 3.0 * s2 + 1.0) * a.positions[j]
+                    + (s3 - 2.0 * s2 + s) * h * a.velocities[j]
+                    + (-2.0 * s3 + 3.0 * s2) * b.positions[j]
+                    + (s3 - s2) * h * b.velocities[j]
+            })
+            .collect();
+        let velocities = (0..a.positions.len())
+            .map(|j| {
+                ((6.0 * s2 - 6.0 * s) * a.positions[j]
+                    + (3.0 * s2 - 4.0 * s + 1.0) * h * a.velocities[j]
+                    + (-6.0 * s2 + 6.0 * s) * b.positions[j]
+                    + (3.0 * s2 - 2.0 * s) * h * b.velocities[j])
+                    / h
+            })
+            .collect();
+        (positions, velocities)
+    }
+
+    /// Encodes the points as `[t, positions..., velocities...]` per point.
+    pub fn to_arrow(&self) -> (MetadataParameters, Float64Array) {
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(TRAJECTORY_ID_KEY.into(), Parameter::Integer(self.id as i64));
+        parameters.insert(
+            JOINT_NAMES_KEY.into(),
+            Parameter::ListString(self.joint_names.clone()),
+        );
+        let values = self.points.iter().flat_map(|point| {
+            std::iter::once(point.time_from_start)
+                .chain(point.positions.iter().copied())
+                .chain(point.velocities.iter().copied())
+        });
+        (parameters, Float64Array::from_iter_values(values))
+    }
+
+    pub fn from_arrow(parameters: &MetadataParameters, data: &dyn Array) -> eyre::Result<Self> {
+        let id = trajectory_id(parameters)?;
+        let Some(Parameter::ListString(joint_names)) = parameters.get(JOINT_NAMES_KEY) else {
+            bail!("trajectory without `{JOINT_NAMES_KEY}`");
+        };
+        let values = data
+            .as_primitive_opt::<Float64Type>()
+            .ok_or_eyre("expected a Float64 trajectory")?
+            .values();
+        let joints = joint_names.len();
+        let stride = 1 + 2 * joints;
+        if values.len() % stride != 0 {
+            bail!(
+                "trajectory length {} is not a multiple of {stride}",
+                values.len()
+            );
+        }
+        let points = values
+            .chunks(stride)
+            .map(|chunk| TrajectoryPoint {
+                time_from_start: chunk[0],
+                positions: chunk[1..1 + joints].to_vec(),
+                velocities: chunk[1 + joints..].to_vec(),
+            })
+            .collect::<Vec<_>>();
+        if points
+            .windows(2)
+            .any(|w| w[1].time_from_start <= w[0].time_from_start)
+        {
+            bail!("trajectory {id} has points that are not strictly increasing in time");
+        }
+        Ok(Self {
+            id,
+            joint_names: joint_names.clone(),
+            points,
+        })
+    }
+}
+
+/// Encodes a setpoint as `[positions..., velocities...]`.
+pub fn setpoint_to_arrow(positions: &[f64], velocities: &[f64]) -> Float64Array {
+    Float64Array::from_iter_values(positions.iter().chain(velocities).copied())
+}
+
+pub fn setpoint_from_arrow(data: &dyn Array) -> eyre::Result<(Vec<f64>, Vec<f64>)> {
+    let values = data
+        .as_primitive_opt::<Float64Type>()
+        .ok_or_eyre("expected a Float64 setpoint")?
+        .values();
+    if values.len() % 2 != 0 {
+        bail!("setpoint has an odd number of values");
+    }
+    let (positions, velocities) = values.split_at(values.len() / 2);
+    Ok((positions.to_vec(), velocities.to_vec()))
+}
+
+pub fn trajectory_id(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(TRAJECTORY_ID_KEY) {
+        Some(Parameter::Integer(id)) => Ok(*id as u64),
+        _ => Err(eyre!("missing `{TRAJECTORY_ID_KEY}` parameter")),
+    }
+}
+
+/// Tracking statistics of one trajectory, written to `REPORT_FILE` by the arm.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrackingReport {
+    pub trajectory_id: u64,
+    pub duration_s: f64,
+    pub setpoints: u64,
+    /// Setpoints per second while the trajectory was moving.
+    pub rate_hz: f64,
+    /// Largest absolute joint error while moving, in radians.
+    pub max_error: f64,
+    pub rms_error: f64,
+    /// Largest absolute joint error at the end of the holding phase, in radians.
+    pub settled_error: f64,
+}
@@ -0,0 +1,251 @@
+use arm_trajectory_dataflow_nodes::{TRAJECTORY_ID_KEY, Trajectory, env_or};
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, MetadataParameters, Parameter,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use dora_ros2_bridge::{
+    messages::{
+        builtin_interfaces::msg::Duration as RosDuration,
+        control_msgs::action::{
+            FollowJointTrajectory, FollowJointTrajectoryFeedback, FollowJointTrajectoryGoal,
+        },
+        trajectory_msgs::msg::{JointTrajectory, JointTrajectoryPoint},
+    },
+    ros2_client::{
+        self, NodeOptions,
+        action::{ActionClientQosPolicies, GoalId},
+        action_msgs::GoalStatusEnum,
+    },
+    rustdds::{self, policy},
+};
+use eyre::{Context, bail, eyre};
+use futures::{StreamExt, channel::mpsc, pin_mut, task::SpawnExt};
+use std::sync::Arc;
+
+enum GoalEvent {
+    Accepted {
+        trajectory_id: u64,
+        goal_id: GoalId,
+    },
+    Feedback(FollowJointTrajectoryFeedback),
+    Result {
+        trajectory_id: u64,
+        status: GoalStatusEnum,
+        error_code: i32,
+        error_string: String,
+    },
+    Error(String),
+}
+
+/// Forwards trajectories to a ROS2 controller through the `FollowJointTrajectory` action.
+///
+/// This replaces the interpolator and the simulated arm with a real controller, e.g. the
+/// `joint_trajectory_controller` of `ros2_control`. Like the interpolator, the bridge sends
+/// `done` when a trajectory is finished, and the tracking error from the action feedback.
+fn main() -> eyre::Result<()> {
+    let action_namespace: String = env_or("ACTION_NAMESPACE", "/arm_controller".to_owned())?;
+    let action_name: String = env_or("ACTION_NAME", "follow_joint_trajectory".to_owned())?;
+    let done_output = DataId::from("done".to_owned());
+    let error_output = DataId::from("tracking_error".to_owned());
+
+    let mut ros_node = init_ros_node()?;
+    let pool = futures::executor::ThreadPool::new()?;
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+
+    let qos = rustdds::QosPolicyBuilder::new()
+        .reliability(policy::Reliability::Reliable {
+            max_blocking_time: rustdds::Duration::from_millis(100),
+        })
+        .history(policy::History::KeepLast { depth: 1 })
+        .build();
+    let client = Arc::new(
+        ros_node
+            .create_action_client::<FollowJointTrajectory>(
+                ros2_client::ServiceMapping::Enhanced,
+                &ros2_client::Name::new(&action_namespace, &action_name)
+                    .map_err(|e| eyre!("invalid action name: {e}"))?,
+                &ros2_client::ActionTypeName::new("control_msgs", "FollowJointTrajectory"),
+                ActionClientQosPolicies {
+                    goal_service: qos.clone(),
+                    result_service: qos.clone(),
+                    cancel_service: qos.clone(),
+                    feedback_subscription: qos.clone(),
+                    status_subscription: qos,
+                },
+            )
+            .map_err(|e| eyre!("failed to create action client: {e:?}"))?,
+    );
+
+    let (goal_events_tx, goal_events) = mpsc::unbounded();
+    let (mut node, dora_events) = DoraNode::init_from_env()?;
+    let merged = dora_events.merge_external(Box::pin(goal_events));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut active: Option<u64> = None;
+    let mut trajectories_closed = false;
+    for event in events {
+        match event {
+            MergedEvent::Dora(Event::Input { id, metadata, data }) => match id.as_str() {
+                "trajectory" => {
+                    let trajectory = Trajectory::from_arrow(&metadata.parameters, &data.0)?;
+                    println!("sending trajectory {} as goal", trajectory.id);
+                    active = Some(trajectory.id);
+                    let client = client.clone();
+                    let events = goal_events_tx.clone();
+                    let pool_clone = pool.clone();
+                    pool.spawn(async move {
+                        let event = execute(client, trajectory, events.clone(), pool_clone).await;
+                        let _ = events.unbounded_send(event);
+                    })
+                    .context("failed to spawn goal task")?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            MergedEvent::Dora(Event::InputClosed { id }) => {
+                if id.as_str() == "trajectory" {
+                    trajectories_closed = true;
+                    if active.is_none() {
+                        break;
+                    }
+                }
+            }
+            MergedEvent::Dora(Event::Stop(_)) => break,
+            MergedEvent::Dora(other) => eprintln!("Received unexpected input: {other:?}"),
+            MergedEvent::External(GoalEvent::Accepted {
+                trajectory_id,
+                goal_id,
+            }) => {
+                println!("controller accepted trajectory {trajectory_id} as goal {goal_id:?}");
+            }
+            MergedEvent::External(GoalEvent::Feedback(feedback)) => {
+                let error = feedback
+                    .error
+                    .positions
+                    .iter()
+                    .fold(0.0f64, |max, e| max.max(e.abs()));
+                node.send_output(error_output.clone(), Default::default(), error.into_arrow())?;
+            }
+            MergedEvent::External(GoalEvent::Result {
+                trajectory_id,
+                status,
+                error_code,
+                error_string,
+            }) => {
+                // `error_code` 0 is `SUCCESSFUL`, negative codes are tolerance violations
+                if status != GoalStatusEnum::Succeeded || error_code != 0 {
+                    bail!(
+                        "trajectory {trajectory_id} ended as {status:?} with error code \
+                         {error_code}: {error_string}"
+                    );
+                }
+                let mut parameters = MetadataParameters::default();
+                parameters.insert(
+                    TRAJECTORY_ID_KEY.into(),
+                    Parameter::Integer(trajectory_id as i64),
+                );
+                node.send_output(done_output.clone(), parameters, 0.0f64.into_arrow())?;
+                if active == Some(trajectory_id) {
+                    active = None;
+                    if trajectories_closed {
+                        break;
+                    }
+                }
+            }
+            MergedEvent::External(GoalEvent::Error(message)) => bail!("{message}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends the goal, forwards its feedback, and returns its result.
+async fn execute(
+    client: Arc<ros2_client::action::ActionClient<FollowJointTrajectory>>,
+    trajectory: Trajectory,
+    events: mpsc::UnboundedSender<GoalEvent>,
+    pool: futures::executor::ThreadPool,
+) -> GoalEvent {
+    let trajectory_id = trajectory.id;
+    let goal = FollowJointTrajectoryGoal {
+        trajectory: to_ros(&trajectory),
+        ..Default::default()
+    };
+    let goal_id = match client.async_send_goal(goal).await {
+        Ok((goal_id, response)) if response.accepted => goal_id,
+        Ok(_) => {
+            return GoalEvent::Error(format!("controller rejected trajectory {trajectory_id}"));
+        }
+        Err(err) => return GoalEvent::Error(format!("failed to send goal: {err:?}")),
+    };
+    let _ = events.unbounded_send(GoalEvent::Accepted {
+        trajectory_id,
+        goal_id,
+    });
+
+    let feedback_client = client.clone();
+    let feedback_events = events.clone();
+    let _ = pool.spawn(async move {
+        let feedback = feedback_client.feedback_stream(goal_id);
+        pin_mut!(feedback);
+        while let Some(Ok(feedback)) = feedback.next().await {
+            if feedback_events
+                .unbounded_send(GoalEvent::Feedback(feedback))
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    match client.async_request_result(goal_id).await {
+        Ok((status, result)) => GoalEvent::Result {
+            trajectory_id,
+            status,
+            error_code: result.error_code,
+            error_string: result.error_string,
+        },
+        Err(err) => GoalEvent::Error(format!("failed to get result: {err:?}")),
+    }
+}
+
+fn to_ros(trajectory: &Trajectory) -> JointTrajectory {
+    JointTrajectory {
+        joint_names: trajectory.joint_names.clone(),
+        points: trajectory
+            .points
+            .iter()
+            .map(|point| JointTrajectoryPoint {
+                positions: point.positions.clone(),
+                velocities: point.velocities.clone(),
+                time_from_start: RosDuration {
+                    sec: point.time_from_start.trunc() as i32,
+                    nanosec: (point.time_from_start.fract() * 1e9) as u32,
+                },
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn init_ros_node() -> eyre::Result<ros2_client::Node> {
+    let ros_context =
+        ros2_client::Context::new().map_err(|e| eyre!("failed to create ROS2 context: {e:?}"))?;
+
+    ros_context
+        .new_node(
+            ros2_client::NodeName::new("/dora", "trajectory_bridge")
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre!("failed to create ros2 node: {e:?}"))
+}
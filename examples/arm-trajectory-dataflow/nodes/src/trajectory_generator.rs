@@ -0,0 +1,125 @@
+use arm_trajectory_dataflow_nodes::{
+    HOME, JOINT_NAMES, Trajectory, TrajectoryPoint, env_or, trajectory_id,
+};
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+
+/// Poses of a pick-and-place cycle, in radians. Starts and ends at `HOME`, where the arm
+/// starts.
+static POSES: [[f64; 6]; 4] = [
+    HOME,
+    [0.8, -1.2, 1.9, -2.2, -1.57, 0.8],
+    [-0.6, -1.0, 1.4, -1.9, -1.57, -0.6],
+    HOME,
+];
+
+/// Sends one trajectory per move between `POSES`, and waits until it is done before sending
+/// the next one.
+///
+/// The trajectories have a point every `POINT_INTERVAL_MS`, much coarser than the control
+/// rate of the arm. The interpolator fills the gaps.
+fn main() -> eyre::Result<()> {
+    let max_velocity: f64 = env_or("MAX_JOINT_VELOCITY", 1.0)?;
+    let point_interval = env_or::<f64>("POINT_INTERVAL_MS", 100.0)? / 1000.0;
+    let cycles: u64 = env_or("CYCLES", 2)?;
+    let output = DataId::from("trajectory".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let moves = (0..cycles)
+        .flat_map(|_| POSES.windows(2))
+        .enumerate()
+        .map(|(id, poses)| {
+            minimum_jerk(
+                id as u64,
+                &poses[0],
+                &poses[1],
+                max_velocity,
+                point_interval,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut moves = moves.into_iter();
+    let mut active: Option<u64> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if active.is_some() {
+                        continue;
+                    }
+                    let Some(trajectory) = moves.next() else {
+                        break;
+                    };
+                    println!(
+                        "sending trajectory {} ({} points, {:.2} s)",
+                        trajectory.id,
+                        trajectory.points.len(),
+                        trajectory.duration()
+                    );
+                    let (parameters, data) = trajectory.to_arrow();
+                    node.send_output(output.clone(), parameters, data)?;
+                    active = Some(trajectory.id);
+                }
+                "done" => {
+                    let done = trajectory_id(&metadata.parameters)?;
+                    if active == Some(done) {
+                        println!("trajectory {done} done");
+                        active = None;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// A minimum-jerk move from `start` to `end`, sampled every `interval` seconds.
+///
+/// The duration is chosen so that no joint exceeds `max_velocity`.
+fn minimum_jerk(
+    id: u64,
+    start: &[f64; 6],
+    end: &[f64; 6],
+    max_velocity: f64,
+    interval: f64,
+) -> Trajectory {
+    let distance = start
+        .iter()
+        .zip(end)
+        .map(|(a, b)| (b - a).abs())
+        .fold(0.0, f64::max);
+    // the peak velocity of a minimum-jerk profile is 1.875 times the mean velocity
+    let duration = (1.875 * distance / max_velocity).max(interval);
+    let steps = (duration / interval).ceil() as usize;
+
+    let points = (1..=steps)
+        .map(|step| {
+            let t = duration * step as f64 / steps as f64;
+            let tau = t / duration;
+            let s = 10.0 * tau.powi(3) - 15.0 * tau.powi(4) + 6.0 * tau.powi(5);
+            let ds = (30.0 * tau.powi(2) - 60.0 * tau.powi(3) + 30.0 * tau.powi(4)) / duration;
+            TrajectoryPoint {
+                time_from_start: t,
+                positions: start
+                    .iter()
+                    .zip(end)
+                    .map(|(a, b)| a + (b - a) * s)
+                    .collect(),
+                velocities: start.iter().zip(end).map(|(a, b)| (b - a) * ds).collect(),
+            }
+        })
+        .collect();
+    Trajectory {
+        id,
+        joint_names: JOINT_NAMES.iter().map(|name| name.to_string()).collect(),
+        points,
+    }
+}
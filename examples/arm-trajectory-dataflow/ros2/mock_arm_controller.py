@@ -0,0 +1,111 @@
+"""A stand-in for a `joint_trajectory_controller` that serves `FollowJointTrajectory`.
+
+It follows the goal trajectory with a simulated first-order lag, publishes feedback and
+`/joint_states`, and exits after `--goals` goals.
+"""
+
+import argparse
+import time
+
+import rclpy
+from control_msgs.action import FollowJointTrajectory
+from rclpy.action import ActionServer
+from rclpy.executors import MultiThreadedExecutor
+from rclpy.node import Node
+from sensor_msgs.msg import JointState
+from trajectory_msgs.msg import JointTrajectoryPoint
+
+RATE_HZ = 100.0
+TIME_CONSTANT = 0.02
+HOME = [0.0, -1.57, 1.57, -1.57, -1.57, 0.0]
+
+
+def seconds(duration):
+    return duration.sec + duration.nanosec * 1e-9
+
+
+def sample(points, t, start):
+    """Linear interpolation between the trajectory points, starting at `start`."""
+    previous_time, previous = 0.0, start
+    for point in points:
+        point_time = seconds(point.time_from_start)
+        if t <= point_time:
+            s = (t - previous_time) / max(point_time - previous_time, 1e-9)
+            return [a + (b - a) * s for a, b in zip(previous, point.positions)]
+        previous_time, previous = point_time, list(point.positions)
+    return previous
+
+
+class MockArmController(Node):
+    def __init__(self, goals):
+        super().__init__("arm_controller")
+        self.remaining_goals = goals
+        self.positions = list(HOME)
+        self.joint_states = self.create_publisher(JointState, "/joint_states", 10)
+        self.server = ActionServer(
+            self,
+            FollowJointTrajectory,
+            "/arm_controller/follow_joint_trajectory",
+            self.execute,
+        )
+
+    def execute(self, goal_handle):
+        trajectory = goal_handle.request.trajectory
+        start = list(self.positions)
+        duration = seconds(trajectory.points[-1].time_from_start)
+        self.get_logger().info(
+            f"executing {len(trajectory.points)} points over {duration:.2f} s"
+        )
+
+        started = time.monotonic()
+        while True:
+            t = time.monotonic() - started
+            desired = sample(trajectory.points, t, start)
+            alpha = min(1.0, 1.0 / (RATE_HZ * TIME_CONSTANT))
+            self.positions = [
+                p + (d - p) * alpha for p, d in zip(self.positions, desired)
+            ]
+
+            feedback = FollowJointTrajectory.Feedback()
+            feedback.joint_names = trajectory.joint_names
+            feedback.desired = JointTrajectoryPoint(positions=desired)
+            feedback.actual = JointTrajectoryPoint(positions=self.positions)
+            feedback.error = JointTrajectoryPoint(
+                positions=[d - p for d, p in zip(desired, self.positions)]
+            )
+            goal_handle.publish_feedback(feedback)
+            self.joint_states.publish(
+                JointState(name=trajectory.joint_names, position=self.positions)
+            )
+            if t >= duration + 0.1:
+                break
+            time.sleep(1.0 / RATE_HZ)
+
+        goal_handle.succeed()
+        result = FollowJointTrajectory.Result()
+        result.error_code = FollowJointTrajectory.Result.SUCCESSFUL
+        self.remaining_goals -= 1
+        return result
+
+
+def main():
+    parser = argparse.ArgumentParser()
+    parser.add_argument("--goals", type=int, default=6)
+    args = parser.parse_args()
+
+    rclpy.init()
+    controller = MockArmController(args.goals)
+    executor = MultiThreadedExecutor()
+    executor.add_node(controller)
+    while rclpy.ok() and controller.remaining_goals > 0:
+        executor.spin_once(timeout_sec=0.1)
+    # let the last result reach the client
+    deadline = time.monotonic() + 1.0
+    while time.monotonic() < deadline:
+        executor.spin_once(timeout_sec=0.1)
+    controller.destroy_node()
+    rclpy.shutdown()
+
+
+if __name__ == "__main__":
+    main()
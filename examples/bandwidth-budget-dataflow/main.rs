@@ -0,0 +1,114 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, node_env};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+/// Subset of `Alarm` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct Alarm {
+    window_end_ms: u64,
+    edge: String,
+    level: String,
+    bytes_per_sec: f64,
+    budget: f64,
+}
+
+/// Subset of `Budgets` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct Budgets {
+    edges: BTreeMap<String, f64>,
+}
+
+fn node<'a>(dataflow: &'a serde_yaml::Value, id: &str) -> eyre::Result<&'a serde_yaml::Value> {
+    dataflow["nodes"]
+        .as_sequence()
+        .and_then(|nodes| nodes.iter().find(|node| node["id"] == id))
+        .ok_or_else(|| eyre::eyre!("dataflow has no node `{id}`"))
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("bandwidth-budget-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dataflow = Path::new("dataflow.yml");
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let budgets: Budgets = serde_yaml::from_str(&std::fs::read_to_string("budgets.yml")?)?;
+    check_budgets(&descriptor, &budgets)?;
+    let burst_at: u64 = node_env(&descriptor, "camera", "BURST_AT_MS")?.parse()?;
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let alarms: Vec<Alarm> = std::fs::read_to_string("out/alarms.jsonl")
+        .context("`budget` did not write out/alarms.jsonl")?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    println!(
+        "{:>9}  {:<10} {:<8} {:>12} {:>12}",
+        "window", "edge", "level", "bytes/s", "budget"
+    );
+    for alarm in &alarms {
+        println!(
+            "{:>6} ms  {:<10} {:<8} {:>12.0} {:>12.0}",
+            alarm.window_end_ms, alarm.edge, alarm.level, alarm.bytes_per_sec, alarm.budget
+        );
+    }
+    let levels = |edge: &str| -> Vec<&Alarm> { alarms.iter().filter(|a| a.edge == edge).collect() };
+
+    // the burst of the camera overloads its edge and the link, until the camera throttles
+    for edge in ["camera", "link"] {
+        let changes = levels(edge);
+        let Some(raised) = changes.iter().position(|a| a.level == "alarm") else {
+            bail!("expected an alarm for `{edge}` after the burst of the camera");
+        };
+        if changes[raised].window_end_ms < burst_at {
+            bail!(
+                "`{edge}` raised an alarm at {} ms, before the burst at {burst_at} ms",
+                changes[raised].window_end_ms
+            );
+        }
+        if !changes[raised..].iter().any(|a| a.level == "ok") {
+            bail!("expected the alarm of `{edge}` to clear after the camera throttled");
+        }
+    }
+    // close to its budget, but within it
+    let lidar = levels("lidar");
+    if !lidar.iter().any(|a| a.level == "warning") || lidar.iter().any(|a| a.level == "alarm") {
+        bail!("expected warnings but no alarm for `lidar`, got {lidar:?}");
+    }
+    if !levels("telemetry").is_empty() {
+        bail!("`telemetry` is far below its budget, but its level changed");
+    }
+    println!("alarms were raised and cleared as expected");
+    Ok(())
+}
+
+/// Checks that every edge that the tap measures has a budget, and the other way round.
+fn check_budgets(dataflow: &serde_yaml::Value, budgets: &Budgets) -> eyre::Result<()> {
+    let inputs: BTreeSet<&str> = node(dataflow, "tap")?["inputs"]
+        .as_mapping()
+        .into_iter()
+        .flatten()
+        .filter_map(|(input, _)| input.as_str())
+        .filter(|input| *input != "tick")
+        .collect();
+    let budgeted: BTreeSet<&str> = budgets.edges.keys().map(String::as_str).collect();
+    if inputs != budgeted {
+        bail!(
+            "the inputs of `tap` ({inputs:?}) must match the edges of budgets.yml \
+             ({budgeted:?})"
+        );
+    }
+    Ok(())
+}
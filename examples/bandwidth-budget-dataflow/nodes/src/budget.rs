@@ -0,0 +1,137 @@
+use bandwidth_budget_dataflow_nodes::{
+    Alarm, Budgets, LINK, Level, WindowRates, create_jsonl, env_or, write_jsonl,
+};
+use dora_node_api::{
+    DoraNode, Event,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+/// The reported level of an edge, and a different level that was measured recently.
+#[derive(Debug)]
+struct EdgeState {
+    level: Level,
+    pending: Option<(Level, u32)>,
+}
+
+impl EdgeState {
+    /// Returns the new level once `measured` was seen in `sustain` windows in a row.
+    fn update(&mut self, measured: Level, sustain: u32) -> Option<Level> {
+        if measured == self.level {
+            self.pending = None;
+            return None;
+        }
+        let windows = match self.pending {
+            Some((level, windows)) if level == measured => windows + 1,
+            _ => 1,
+        };
+        if windows < sustain {
+            self.pending = Some((measured, windows));
+            return None;
+        }
+        self.level = measured;
+        self.pending = None;
+        Some(measured)
+    }
+}
+
+/// Compares the `rates` of the tap with the budgets of `BUDGETS_FILE`, per edge and for the
+/// link as a whole.
+///
+/// Sends an [`Alarm`] on `alarm` whenever the level of an edge changes between ok, warning,
+/// and alarm, and writes all of them to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let budgets = Budgets::read(&PathBuf::from(env_or(
+        "BUDGETS_FILE",
+        "budgets.yml".to_owned(),
+    )?))?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/alarms.jsonl".to_owned())?.into();
+    let output = DataId::from("alarm".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = create_jsonl(&report_file)?;
+    let mut states: BTreeMap<String, EdgeState> = BTreeMap::new();
+    let mut unbudgeted = BTreeSet::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "rates" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array of rates")?;
+                    for row in rows.iter().flatten() {
+                        let rates: WindowRates = serde_json::from_str(row)?;
+                        let mut measured = Vec::new();
+                        for (edge, rate) in &rates.edges {
+                            match budgets.edges.get(edge) {
+                                Some(&budget) => {
+                                    measured.push((edge.clone(), rate.bytes_per_sec, budget))
+                                }
+                                None => {
+                                    if unbudgeted.insert(edge.clone()) {
+                                        eprintln!("edge `{edge}` has no budget");
+                                    }
+                                }
+                            }
+                        }
+                        if let Some(budget) = budgets.link {
+                            let total = rates.edges.values().map(|r| r.bytes_per_sec).sum();
+                            measured.push((LINK.to_owned(), total, budget));
+                        }
+
+                        for (edge, bytes_per_sec, budget) in measured {
+                            let ratio = bytes_per_sec / budget;
+                            let level = if ratio > 1.0 {
+                                Level::Alarm
+                            } else if ratio >= budgets.warn_ratio {
+                                Level::Warning
+                            } else {
+                                Level::Ok
+                            };
+                            let state = states.entry(edge.clone()).or_insert(EdgeState {
+                                level: Level::Ok,
+                                pending: None,
+                            });
+                            let Some(level) = state.update(level, budgets.sustain_windows) else {
+                                continue;
+                            };
+                            let alarm = Alarm {
+                                window_end_ms: rates.window_end_ms,
+                                edge,
+                                level,
+                                bytes_per_sec,
+                                budget,
+                            };
+                            println!(
+                                "{:>6} ms: `{}` is {:?} at {:.0} of {:.0} bytes/s ({:.0} %)",
+                                alarm.window_end_ms,
+                                alarm.edge,
+                                alarm.level,
+                                alarm.bytes_per_sec,
+                                alarm.budget,
+                                ratio * 100.0
+                            );
+                            write_jsonl(&mut report, &alarm)?;
+                            node.send_output(
+                                output.clone(),
+                                Default::default(),
+                                StringArray::from(vec![serde_json::to_string(&alarm)?]),
+                            )?;
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use eyre::Context;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io::Write, path::Path};
+
+pub use example_node_utils::env_or;
+
+/// The key of the budget of all edges together in [`Alarm::edge`].
+pub const LINK: &str = "link";
+
+/// The traffic of one edge during a window of the tap.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeRate {
+    pub bytes_per_sec: f64,
+    pub messages_per_sec: f64,
+}
+
+/// Sent by the tap at the end of every window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowRates {
+    /// Milliseconds since the tap started.
+    pub window_end_ms: u64,
+    pub edges: BTreeMap<String, EdgeRate>,
+}
+
+/// The budgets of `budgets.yml`, in bytes per second.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Budgets {
+    pub edges: BTreeMap<String, f64>,
+    pub link: Option<f64>,
+    pub warn_ratio: f64,
+    pub sustain_windows: u32,
+}
+
+impl Budgets {
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let yaml = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_yaml::from_str(&yaml)
+            .with_context(|| format!("invalid budgets in {}", path.display()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Ok,
+    Warning,
+    Alarm,
+}
+
+/// Sent by the budget node when the level of an edge, or of the link, changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alarm {
+    pub window_end_ms: u64,
+    /// An input of the tap, or [`LINK`].
+    pub edge: String,
+    pub level: Level,
+    pub bytes_per_sec: f64,
+    pub budget: f64,
+}
+
+pub fn create_jsonl(path: &Path) -> eyre::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))
+}
+
+pub fn write_jsonl<T: Serialize>(file: &mut std::fs::File, record: &T) -> eyre::Result<()> {
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
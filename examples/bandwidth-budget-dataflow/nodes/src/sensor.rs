@@ -0,0 +1,70 @@
+use bandwidth_budget_dataflow_nodes::{Alarm, Level, env_or};
+use dora_node_api::{
+    DoraNode, Event,
+    arrow::array::{AsArray, UInt8Array},
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use std::time::{Duration, Instant};
+
+/// Sends `PAYLOAD_BYTES` on `data` on every `tick`, for `DURATION_MS`.
+///
+/// From `BURST_AT_MS` on, it sends `BURST_PAYLOAD_BYTES` instead, like a camera that is
+/// switched from compressed to raw images. An `alarm` input for its `EDGE` at the alarm level
+/// throttles it back to `PAYLOAD_BYTES`.
+fn main() -> eyre::Result<()> {
+    let edge: String = env_or("EDGE", String::new())?;
+    let payload_bytes: usize = env_or("PAYLOAD_BYTES", 1024)?;
+    // never bursts if unset
+    let burst_at = Duration::from_millis(env_or("BURST_AT_MS", u64::MAX)?);
+    let burst_payload_bytes: usize = env_or("BURST_PAYLOAD_BYTES", payload_bytes)?;
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let output = DataId::from("data".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    let mut throttled = false;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "tick" => {
+                    let elapsed = start.elapsed();
+                    if elapsed >= duration {
+                        break;
+                    }
+                    let bytes = if elapsed >= burst_at && !throttled {
+                        burst_payload_bytes
+                    } else {
+                        payload_bytes
+                    };
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        UInt8Array::from(vec![0; bytes]),
+                    )?;
+                }
+                "alarm" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array of alarms")?;
+                    for row in rows.iter().flatten() {
+                        let alarm: Alarm = serde_json::from_str(row)?;
+                        if alarm.edge == edge && alarm.level == Level::Alarm && !throttled {
+                            println!(
+                                "`{edge}` is over budget, throttling to {payload_bytes} bytes"
+                            );
+                            throttled = true;
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
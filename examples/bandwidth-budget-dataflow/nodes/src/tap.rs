@@ -0,0 +1,67 @@
+use bandwidth_budget_dataflow_nodes::{EdgeRate, WindowRates};
+use dora_node_api::{DoraNode, Event, arrow::array::StringArray, dora_core::config::DataId};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Instant,
+};
+
+/// Measures the traffic of the edges that it's subscribed to. Every input except `tick` is an
+/// edge, named after it, and counted with the size of its Arrow data.
+///
+/// Sends the [`WindowRates`] of the window since the previous `tick` on `rates`. Stops when the
+/// inputs of all edges are closed.
+fn main() -> eyre::Result<()> {
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let output = DataId::from("rates".to_owned());
+
+    let start = Instant::now();
+    let mut window_start = Instant::now();
+    // bytes and messages in the current window
+    let mut traffic: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    let mut closed = BTreeSet::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "tick" => {
+                    let seconds = window_start.elapsed().as_secs_f64();
+                    window_start = Instant::now();
+                    let edges = traffic
+                        .iter_mut()
+                        .map(|(edge, (bytes, messages))| {
+                            let rate = EdgeRate {
+                                bytes_per_sec: *bytes as f64 / seconds,
+                                messages_per_sec: *messages as f64 / seconds,
+                            };
+                            (*bytes, *messages) = (0, 0);
+                            (edge.clone(), rate)
+                        })
+                        .collect();
+                    let rates = WindowRates {
+                        window_end_ms: start.elapsed().as_millis() as u64,
+                        edges,
+                    };
+                    let row = serde_json::to_string(&rates)?;
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        StringArray::from(vec![row]),
+                    )?;
+                }
+                edge => {
+                    let (bytes, messages) = traffic.entry(edge.to_owned()).or_default();
+                    *bytes += data.to_data().get_slice_memory_size()?;
+                    *messages += 1;
+                }
+            },
+            Event::InputClosed { id } => {
+                closed.insert(id.to_string());
+                if traffic.keys().all(|edge| closed.contains(edge)) {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
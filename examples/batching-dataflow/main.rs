@@ -0,0 +1,32 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use std::path::Path;
+
+/// Must match the `batcher` configuration in `dataflow.yml`.
+const MAX_ROWS: &str = "100";
+const TIMEOUT_MS: &str = "200";
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("batching-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let mut cmd = tokio::process::Command::new("nodes/target/release/verify-batches");
+    cmd.args(["out/batches.jsonl", "out/phases.jsonl"])
+        .args(["--max-rows", MAX_ROWS])
+        .args(["--timeout-ms", TIMEOUT_MS]);
+    if !cmd.status().await?.success() {
+        bail!("batch verification failed");
+    }
+
+    Ok(())
+}
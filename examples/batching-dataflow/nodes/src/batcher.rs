@@ -0,0 +1,159 @@
+use batching_dataflow_nodes::{FlushReason, batch_parameters, env_or};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{Array, ArrayRef},
+        compute::concat,
+        datatypes::DataType,
+    },
+    dora_core::config::DataId,
+};
+use std::time::{Duration, Instant};
+
+/// Input id of the timer that triggers timeout flushes while no data arrives.
+const CHECK_INPUT: &str = "check";
+
+/// A flushed batch.
+struct Batch {
+    data: ArrayRef,
+    reason: FlushReason,
+    /// Time that the oldest row of the batch waited.
+    age_ms: u64,
+}
+
+/// Accumulates rows until a batch is full or its oldest row waited too long.
+struct Batcher {
+    max_rows: usize,
+    timeout: Duration,
+    pending: Vec<ArrayRef>,
+    pending_rows: usize,
+    /// Arrival time of the oldest pending row.
+    oldest: Option<Instant>,
+}
+
+impl Batcher {
+    /// Adds the rows of `data` and returns the batches that are complete.
+    ///
+    /// Batches that reach `max_rows` are flushed with exactly `max_rows` rows. The rows of
+    /// an input that don't fit are sliced off and start the next batch.
+    fn push(&mut self, data: ArrayRef) -> eyre::Result<Vec<Batch>> {
+        let mut flushed = Vec::new();
+        if self
+            .data_type()
+            .is_some_and(|data_type| data_type != data.data_type())
+        {
+            flushed.extend(self.flush(FlushReason::Schema)?);
+        }
+
+        let mut offset = 0;
+        while offset < data.len() {
+            let take = (self.max_rows - self.pending_rows).min(data.len() - offset);
+            // slicing is zero-copy, it only adjusts offset and length
+            self.pending.push(data.slice(offset, take));
+            self.pending_rows += take;
+            self.oldest.get_or_insert_with(Instant::now);
+            offset += take;
+            if self.pending_rows == self.max_rows {
+                flushed.extend(self.flush(FlushReason::Size)?);
+            }
+        }
+        Ok(flushed)
+    }
+
+    fn check_timeout(&mut self) -> eyre::Result<Option<Batch>> {
+        match self.oldest {
+            Some(oldest) if oldest.elapsed() >= self.timeout => self.flush(FlushReason::Timeout),
+            _ => Ok(None),
+        }
+    }
+
+    fn flush(&mut self, reason: FlushReason) -> eyre::Result<Option<Batch>> {
+        let Some(oldest) = self.oldest.take() else {
+            return Ok(None);
+        };
+        let arrays: Vec<&dyn Array> = self.pending.iter().map(|array| array.as_ref()).collect();
+        let data = concat(&arrays)?;
+        self.pending.clear();
+        self.pending_rows = 0;
+        Ok(Some(Batch {
+            data,
+            reason,
+            age_ms: oldest.elapsed().as_millis() as u64,
+        }))
+    }
+
+    fn data_type(&self) -> Option<&DataType> {
+        self.pending.first().map(|array| array.data_type())
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let mut batcher = Batcher {
+        max_rows: env_or("MAX_ROWS", 100)?,
+        timeout: Duration::from_millis(env_or("TIMEOUT_MS", 200)?),
+        pending: Vec::new(),
+        pending_rows: 0,
+        oldest: None,
+    };
+    eyre::ensure!(batcher.max_rows > 0, "MAX_ROWS must be positive");
+    let output = DataId::from("batch".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    // the check timer never closes, so stop once all data inputs are closed
+    let mut open_inputs = node
+        .node_config()
+        .inputs
+        .keys()
+        .filter(|id| id.as_str() != CHECK_INPUT)
+        .count();
+
+    let mut batches = 0;
+    while let Some(event) = events.recv() {
+        let (flushed, closed) = match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => {
+                if id.as_str() == CHECK_INPUT {
+                    (batcher.check_timeout()?.into_iter().collect(), false)
+                } else {
+                    (batcher.push(data.0)?, false)
+                }
+            }
+            Event::InputClosed { id } => {
+                if id.as_str() == CHECK_INPUT {
+                    continue;
+                }
+                open_inputs -= 1;
+                if open_inputs > 0 {
+                    continue;
+                }
+                // flush the remaining rows, no more rows will arrive to fill the batch
+                (
+                    batcher.flush(FlushReason::Close)?.into_iter().collect(),
+                    true,
+                )
+            }
+            Event::Stop(_) => (
+                batcher.flush(FlushReason::Close)?.into_iter().collect(),
+                true,
+            ),
+            other => {
+                eprintln!("Received unexpected input: {other:?}");
+                continue;
+            }
+        };
+        for batch in flushed {
+            let parameters = batch_parameters(batch.reason, batch.data.len(), batch.age_ms);
+            node.send_output(output.clone(), parameters, batch.data)?;
+            batches += 1;
+        }
+        if closed {
+            break;
+        }
+    }
+
+    println!("sent {batches} batches");
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{Context, bail, eyre};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{io::Write, path::Path, str::FromStr};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the reason why a batch was flushed.
+pub const FLUSH_REASON_KEY: &str = "flush_reason";
+/// Metadata key of the number of rows in a batch.
+pub const ROWS_KEY: &str = "rows";
+/// Metadata key of the time between the arrival of the first row of a batch and its flush.
+pub const AGE_MS_KEY: &str = "batch_age_ms";
+
+/// Why the batcher flushed a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlushReason {
+    /// The batch reached `MAX_ROWS`.
+    Size,
+    /// The first row of the batch waited for `TIMEOUT_MS`.
+    Timeout,
+    /// The next input had a different Arrow type, which can't be concatenated.
+    Schema,
+    /// The input closed, so no more rows will arrive.
+    Close,
+}
+
+impl FlushReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Size => "size",
+            Self::Timeout => "timeout",
+            Self::Schema => "schema",
+            Self::Close => "close",
+        }
+    }
+}
+
+impl FromStr for FlushReason {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "size" => Ok(Self::Size),
+            "timeout" => Ok(Self::Timeout),
+            "schema" => Ok(Self::Schema),
+            "close" => Ok(Self::Close),
+            other => Err(eyre!("unknown flush reason `{other}`")),
+        }
+    }
+}
+
+/// How the source sends rows during a phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadShape {
+    /// Many rows on every tick, batches fill up long before the timeout.
+    Burst,
+    /// A single row every few ticks, batches never fill up.
+    Trickle,
+    /// One message that is larger than a batch, then nothing.
+    Spike,
+}
+
+impl FromStr for LoadShape {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "burst" => Ok(Self::Burst),
+            "trickle" => Ok(Self::Trickle),
+            "spike" => Ok(Self::Spike),
+            other => Err(eyre!("unknown load shape `{other}`")),
+        }
+    }
+}
+
+/// A phase of the source, written to `PHASE_FILE` so that the verifier knows which load
+/// shape produced which rows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseRecord {
+    pub shape: LoadShape,
+    pub first_seq: u64,
+    pub last_seq: u64,
+}
+
+/// A batch as received by the sink, written to `BATCH_FILE`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRecord {
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub rows: u64,
+    pub reason: FlushReason,
+    pub age_ms: u64,
+}
+
+/// Parses `burst:100,trickle:200` into load shapes and their length in ticks.
+pub fn parse_phases(spec: &str) -> eyre::Result<Vec<(LoadShape, u64)>> {
+    spec.split(',')
+        .map(|phase| {
+            let (shape, ticks) = phase
+                .trim()
+                .split_once(':')
+                .ok_or_else(|| eyre!("expected `<shape>:<ticks>`, got `{phase}`"))?;
+            Ok((shape.parse()?, ticks.parse().context("invalid tick count")?))
+        })
+        .collect()
+}
+
+pub fn batch_parameters(reason: FlushReason, rows: usize, age_ms: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(
+        FLUSH_REASON_KEY.into(),
+        Parameter::String(reason.as_str().to_owned()),
+    );
+    parameters.insert(ROWS_KEY.into(), Parameter::Integer(rows as i64));
+    parameters.insert(AGE_MS_KEY.into(), Parameter::Integer(age_ms as i64));
+    parameters
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
+
+pub fn string_parameter<'a>(
+    parameters: &'a MetadataParameters,
+    key: &str,
+) -> eyre::Result<&'a str> {
+    match parameters.get(key) {
+        Some(Parameter::String(value)) => Ok(value),
+        Some(other) => bail!("expected string `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
+
+/// Creates an empty JSON lines file, replacing the file of a previous run.
+pub fn create_jsonl(path: &Path) -> eyre::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))
+}
+
+pub fn write_jsonl<T: Serialize>(file: &mut std::fs::File, record: &T) -> eyre::Result<()> {
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+pub fn read_jsonl<T: DeserializeOwned>(path: &Path) -> eyre::Result<Vec<T>> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).context("invalid JSON line"))
+        .collect()
+}
@@ -0,0 +1,50 @@
+use batching_dataflow_nodes::{
+    AGE_MS_KEY, BatchRecord, FLUSH_REASON_KEY, ROWS_KEY, create_jsonl, env_or, integer_parameter,
+    string_parameter, write_jsonl,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{array::AsArray, datatypes::UInt64Type},
+};
+use eyre::{OptionExt, bail};
+use std::path::PathBuf;
+
+/// Records the boundaries and flush reasons of all received batches.
+fn main() -> eyre::Result<()> {
+    let batch_file: PathBuf = env_or("BATCH_FILE", "out/batches.jsonl".to_owned())?.into();
+    let mut batch_log = create_jsonl(&batch_file)?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut batches = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let seqs = data
+                    .as_primitive_opt::<UInt64Type>()
+                    .ok_or_eyre("expected a UInt64 batch")?;
+                let rows = integer_parameter(&metadata.parameters, ROWS_KEY)? as u64;
+                if rows != seqs.len() as u64 {
+                    bail!(
+                        "`{id}` batch has {} rows, but its metadata says {rows}",
+                        seqs.len()
+                    );
+                }
+                let record = BatchRecord {
+                    first_seq: seqs.values().first().copied().ok_or_eyre("empty batch")?,
+                    last_seq: seqs.values().last().copied().ok_or_eyre("empty batch")?,
+                    rows,
+                    reason: string_parameter(&metadata.parameters, FLUSH_REASON_KEY)?.parse()?,
+                    age_ms: integer_parameter(&metadata.parameters, AGE_MS_KEY)? as u64,
+                };
+                write_jsonl(&mut batch_log, &record)?;
+                batches += 1;
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("received {batches} batches");
+    Ok(())
+}
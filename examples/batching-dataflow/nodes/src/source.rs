@@ -0,0 +1,74 @@
+use batching_dataflow_nodes::{
+    LoadShape, PhaseRecord, create_jsonl, env_or, parse_phases, write_jsonl,
+};
+use dora_node_api::{self, DoraNode, Event, arrow::array::UInt64Array, dora_core::config::DataId};
+use std::path::PathBuf;
+
+/// Sends rows of sequence numbers in phases of different load shapes.
+fn main() -> eyre::Result<()> {
+    let phases = parse_phases(&env_or(
+        "PHASES",
+        "burst:100,trickle:100,spike:50,burst:50".to_owned(),
+    )?)?;
+    let burst_rows: u64 = env_or("BURST_ROWS", 25)?;
+    let trickle_every: u64 = env_or("TRICKLE_EVERY", 10)?;
+    let spike_rows: u64 = env_or("SPIKE_ROWS", 250)?;
+    let phase_file: PathBuf = env_or("PHASE_FILE", "out/phases.jsonl".to_owned())?.into();
+    let output = DataId::from("rows".to_owned());
+
+    let mut phase_log = create_jsonl(&phase_file)?;
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut phases = phases.into_iter();
+    let mut current = phases.next();
+    let mut phase_tick = 0;
+    let mut phase_first_seq = 0;
+    let mut next_seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    let Some((shape, ticks)) = current else {
+                        break;
+                    };
+                    let rows = match shape {
+                        LoadShape::Burst => burst_rows,
+                        LoadShape::Trickle if phase_tick % trickle_every == 0 => 1,
+                        LoadShape::Spike if phase_tick == 0 => spike_rows,
+                        LoadShape::Trickle | LoadShape::Spike => 0,
+                    };
+                    if rows > 0 {
+                        let seqs = UInt64Array::from_iter_values(next_seq..next_seq + rows);
+                        node.send_output(output.clone(), Default::default(), seqs)?;
+                        next_seq += rows;
+                    }
+
+                    phase_tick += 1;
+                    if phase_tick == ticks {
+                        if next_seq > phase_first_seq {
+                            let record = PhaseRecord {
+                                shape,
+                                first_seq: phase_first_seq,
+                                last_seq: next_seq - 1,
+                            };
+                            write_jsonl(&mut phase_log, &record)?;
+                        }
+                        current = phases.next();
+                        phase_tick = 0;
+                        phase_first_seq = next_seq;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {next_seq} rows");
+    Ok(())
+}
@@ -0,0 +1,128 @@
+//! Checks the batches recorded by `sink` against the load phases recorded by `source`.
+//!
+//! Usage: `verify-batches <batches.jsonl> <phases.jsonl> --max-rows <n> --timeout-ms <n>`
+
+use batching_dataflow_nodes::{BatchRecord, FlushReason, LoadShape, PhaseRecord, read_jsonl};
+use eyre::{bail, eyre};
+use std::path::PathBuf;
+
+/// How much later than `--timeout-ms` a timeout flush may happen. Covers the interval of the
+/// `check` timer and scheduling delays.
+const TIMEOUT_SLACK_MS: u64 = 100;
+
+fn main() -> eyre::Result<()> {
+    let mut files = Vec::new();
+    let mut max_rows = None;
+    let mut timeout_ms = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-rows" => max_rows = args.next().map(|v| v.parse::<u64>()).transpose()?,
+            "--timeout-ms" => timeout_ms = args.next().map(|v| v.parse::<u64>()).transpose()?,
+            other if files.len() < 2 => files.push(PathBuf::from(other)),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    let usage =
+        "usage: verify-batches <batches.jsonl> <phases.jsonl> --max-rows <n> --timeout-ms <n>";
+    let [batch_file, phase_file] = <[PathBuf; 2]>::try_from(files).map_err(|_| eyre!(usage))?;
+    let max_rows = max_rows.ok_or_else(|| eyre!(usage))?;
+    let timeout_ms = timeout_ms.ok_or_else(|| eyre!(usage))?;
+
+    let batches: Vec<BatchRecord> = read_jsonl(&batch_file)?;
+    let phases: Vec<PhaseRecord> = read_jsonl(&phase_file)?;
+    let Some(last_phase) = phases.last() else {
+        bail!("no phases in {}", phase_file.display());
+    };
+
+    // every row arrives exactly once and in order
+    let mut next_seq = 0;
+    for (i, batch) in batches.iter().enumerate() {
+        if batch.first_seq != next_seq || batch.last_seq - batch.first_seq + 1 != batch.rows {
+            bail!(
+                "batch {i} contains rows {}..={}, expected them to start at {next_seq} without gaps",
+                batch.first_seq,
+                batch.last_seq
+            );
+        }
+        next_seq = batch.last_seq + 1;
+    }
+    if next_seq != last_phase.last_seq + 1 {
+        bail!(
+            "received {next_seq} rows, but the source sent {}",
+            last_phase.last_seq + 1
+        );
+    }
+
+    // each flush trigger leaves its own signature on the batch
+    for (i, batch) in batches.iter().enumerate() {
+        match batch.reason {
+            FlushReason::Size if batch.rows != max_rows => {
+                bail!(
+                    "size-flushed batch {i} has {} rows instead of {max_rows}",
+                    batch.rows
+                )
+            }
+            FlushReason::Timeout if batch.rows >= max_rows => {
+                bail!("timeout-flushed batch {i} is full ({} rows)", batch.rows)
+            }
+            FlushReason::Timeout
+                if !(timeout_ms..=timeout_ms + TIMEOUT_SLACK_MS).contains(&batch.age_ms) =>
+            {
+                bail!(
+                    "timeout-flushed batch {i} waited {} ms, expected {timeout_ms} ms",
+                    batch.age_ms
+                )
+            }
+            FlushReason::Close if i != batches.len() - 1 => {
+                bail!("batch {i} was flushed on close, but more batches followed")
+            }
+            FlushReason::Schema => bail!("batch {i} was flushed on a schema change"),
+            _ => {}
+        }
+    }
+
+    // the load shape decides which trigger fires first
+    for phase in &phases {
+        let inside: Vec<_> = batches
+            .iter()
+            .filter(|batch| batch.first_seq >= phase.first_seq && batch.last_seq <= phase.last_seq)
+            .collect();
+        let count = |reason| inside.iter().filter(|batch| batch.reason == reason).count();
+        println!(
+            "{:?} rows {}..={}: {} batches ({} size, {} timeout, {} close)",
+            phase.shape,
+            phase.first_seq,
+            phase.last_seq,
+            inside.len(),
+            count(FlushReason::Size),
+            count(FlushReason::Timeout),
+            count(FlushReason::Close),
+        );
+
+        // the last batch of a phase may be flushed by the next phase or on close
+        let settled = &inside[..inside.len().saturating_sub(1)];
+        let expected = match phase.shape {
+            LoadShape::Burst | LoadShape::Spike => FlushReason::Size,
+            LoadShape::Trickle => FlushReason::Timeout,
+        };
+        if let Some(batch) = settled.iter().find(|batch| batch.reason != expected) {
+            bail!(
+                "{:?} phase: batch {}..={} was flushed by {:?}, expected {expected:?}",
+                phase.shape,
+                batch.first_seq,
+                batch.last_seq,
+                batch.reason
+            );
+        }
+        if count(expected) == 0 {
+            bail!("{:?} phase produced no {expected:?} flush", phase.shape);
+        }
+    }
+
+    println!(
+        "{} batches of {next_seq} rows match the load phases",
+        batches.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use blue_green_dataflow_nodes::{seq, value};
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use std::collections::BTreeMap;
+
+fn main() -> eyre::Result<()> {
+    let required_matches: usize = std::env::var("REQUIRED_MATCHES")
+        .ok()
+        .and_then(|matches| matches.parse().ok())
+        .unwrap_or(50);
+    let tolerance: f64 = std::env::var("TOLERANCE")
+        .ok()
+        .and_then(|tolerance| tolerance.parse().ok())
+        .unwrap_or(1e-9);
+    let output = DataId::from("promote".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    // results that are still waiting for their counterpart of the other version
+    let mut blue = BTreeMap::new();
+    let mut green = BTreeMap::new();
+    let mut matches = 0;
+    let mut mismatches = 0;
+    let mut promoted = false;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let seq = seq(&metadata.parameters)?;
+                let value = value(&data)?;
+                let (own, other) = match id.as_str() {
+                    "blue" => (&mut blue, &mut green),
+                    "green" => (&mut green, &mut blue),
+                    other => {
+                        eprintln!("Ignoring unexpected input `{other}`");
+                        continue;
+                    }
+                };
+                let Some(counterpart) = other.remove(&seq) else {
+                    own.insert(seq, value);
+                    continue;
+                };
+
+                if (value - counterpart).abs() <= tolerance {
+                    matches += 1;
+                } else {
+                    println!("mismatch at seq {seq}: {value} != {counterpart}");
+                    mismatches += 1;
+                    matches = 0;
+                }
+
+                if !promoted && matches >= required_matches {
+                    println!("green matched blue for {matches} consecutive results, promoting");
+                    node.send_output(output.clone(), Default::default(), true.into_arrow())?;
+                    promoted = true;
+                }
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("compared results: {mismatches} mismatches, promoted: {promoted}");
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use blue_green_dataflow_nodes::{seq, version};
+use dora_node_api::{self, DoraNode, Event};
+use eyre::bail;
+
+fn main() -> eyre::Result<()> {
+    let expected: i64 = std::env::var("READINGS")
+        .ok()
+        .and_then(|readings| readings.parse().ok())
+        .unwrap_or(300);
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut next_seq = 0;
+    let mut current_version = String::new();
+    let mut switched_at = None;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata,
+                data: _,
+            } => match id.as_str() {
+                "result" => {
+                    let seq = seq(&metadata.parameters)?;
+                    if seq != next_seq {
+                        bail!("expected result {next_seq}, got {seq}");
+                    }
+                    next_seq += 1;
+
+                    let version = version(&metadata.parameters);
+                    if version != current_version {
+                        if !current_version.is_empty() {
+                            if switched_at.is_some() {
+                                bail!("switched versions more than once (to {version} at {seq})");
+                            }
+                            println!("switched from {current_version} to {version} at seq {seq}");
+                            switched_at = Some(seq);
+                        }
+                        current_version = version.to_owned();
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if next_seq != expected {
+        bail!("received {next_seq} of {expected} results");
+    }
+    if current_version != "green" {
+        bail!("consumer was never switched to the green pipeline");
+    }
+    println!("received all {next_seq} results without gaps, switched at {switched_at:?}");
+    Ok(())
+}
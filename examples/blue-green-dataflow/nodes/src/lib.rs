@@ -0,0 +1,28 @@
+use dora_node_api::{ArrowData, MetadataParameters, Parameter};
+use eyre::{Context, eyre};
+
+/// Every message carries the sequence number of the source reading it was derived from.
+pub fn seq(parameters: &MetadataParameters) -> eyre::Result<i64> {
+    match parameters.get("seq") {
+        Some(Parameter::Integer(seq)) => Ok(*seq),
+        other => Err(eyre!("expected integer `seq` parameter, got {other:?}")),
+    }
+}
+
+pub fn parameters(seq: i64, version: &str) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert("seq".into(), Parameter::Integer(seq));
+    parameters.insert("version".into(), Parameter::String(version.to_owned()));
+    parameters
+}
+
+pub fn version(parameters: &MetadataParameters) -> &str {
+    match parameters.get("version") {
+        Some(Parameter::String(version)) => version,
+        _ => "unknown",
+    }
+}
+
+pub fn value(data: &ArrowData) -> eyre::Result<f64> {
+    f64::try_from(data).context("expected a single float64 value")
+}
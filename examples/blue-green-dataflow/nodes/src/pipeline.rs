@@ -0,0 +1,78 @@
+use blue_green_dataflow_nodes::{parameters, seq, value};
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use std::collections::VecDeque;
+
+const WINDOW: usize = 8;
+
+/// Moving average over the last `WINDOW` readings.
+///
+/// Both pipeline versions compute the same result: `v1` sums up the whole window on every
+/// reading, `v2` keeps a running sum.
+trait MovingAverage {
+    fn push(&mut self, value: f64) -> f64;
+}
+
+#[derive(Default)]
+struct V1 {
+    window: VecDeque<f64>,
+}
+
+impl MovingAverage for V1 {
+    fn push(&mut self, value: f64) -> f64 {
+        if self.window.len() == WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back(value);
+        self.window.iter().sum::<f64>() / self.window.len() as f64
+    }
+}
+
+#[derive(Default)]
+struct V2 {
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl MovingAverage for V2 {
+    fn push(&mut self, value: f64) -> f64 {
+        if self.window.len() == WINDOW {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        self.window.push_back(value);
+        self.sum += value;
+        self.sum / self.window.len() as f64
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let version = std::env::var("PIPELINE_VERSION").unwrap_or_else(|_| "v1".to_owned());
+    let mut average: Box<dyn MovingAverage> = match version.as_str() {
+        "v1" => Box::new(V1::default()),
+        "v2" => Box::new(V2::default()),
+        other => eyre::bail!("unknown PIPELINE_VERSION `{other}`"),
+    };
+    let output = DataId::from("result".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    println!("running pipeline {version}");
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "reading" => {
+                    let result = average.push(value(&data)?);
+                    node.send_output(
+                        output.clone(),
+                        parameters(seq(&metadata.parameters)?, &version),
+                        result.into_arrow(),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use blue_green_dataflow_nodes::parameters;
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+
+fn main() -> eyre::Result<()> {
+    let readings: i64 = std::env::var("READINGS")
+        .ok()
+        .and_then(|readings| readings.parse().ok())
+        .unwrap_or(300);
+    let output = DataId::from("reading".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut seq = 0;
+    while seq < readings {
+        let event = match events.recv() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            Event::Input { id, .. } if id.as_str() == "tick" => {
+                let value = (seq as f64 * 0.1).sin() * 10.0 + (seq % 7) as f64;
+                node.send_output(
+                    output.clone(),
+                    parameters(seq, "source"),
+                    value.into_arrow(),
+                )?;
+                seq += 1;
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} readings");
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use blue_green_dataflow_nodes::{parameters, seq, value};
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use std::collections::BTreeMap;
+
+/// Forwards the results of exactly one pipeline version to the downstream consumer.
+///
+/// Results are released strictly in sequence order. When the comparator promotes the green
+/// version, every result from the next unreleased sequence number on is taken from green, so
+/// the consumer sees neither a gap nor a duplicate at the switchover.
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("output".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut blue = BTreeMap::new();
+    let mut green = BTreeMap::new();
+    let mut next_seq = 0;
+    let mut switch_at = None;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "blue" => {
+                    blue.insert(seq(&metadata.parameters)?, value(&data)?);
+                }
+                "green" => {
+                    green.insert(seq(&metadata.parameters)?, value(&data)?);
+                }
+                "promote" => {
+                    if switch_at.is_none() {
+                        println!("switching to green at seq {next_seq}");
+                        switch_at = Some(next_seq);
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+
+        // release all results that are available from the active version
+        loop {
+            let active_is_green = switch_at.is_some_and(|switch_at| next_seq >= switch_at);
+            let (active, version) = if active_is_green {
+                (&mut green, "green")
+            } else {
+                (&mut blue, "blue")
+            };
+            let Some(result) = active.remove(&next_seq) else {
+                break;
+            };
+            node.send_output(
+                output.clone(),
+                parameters(next_seq, version),
+                result.into_arrow(),
+            )?;
+            next_seq += 1;
+        }
+        // results of the inactive version that were already superseded are not needed anymore
+        blue.retain(|seq, _| *seq >= next_seq);
+        green.retain(|seq, _| *seq >= next_seq);
+    }
+
+    println!("forwarded {next_seq} results");
+    Ok(())
+}
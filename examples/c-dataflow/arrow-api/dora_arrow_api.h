@@ -0,0 +1,92 @@
+#ifndef DORA_ARROW_API_H
+#define DORA_ARROW_API_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C"
+{
+#endif
+
+// The structs of the Arrow C data interface, as defined by its specification:
+// https://arrow.apache.org/docs/format/CDataInterface.html
+#ifndef ARROW_C_DATA_INTERFACE
+#define ARROW_C_DATA_INTERFACE
+
+#define ARROW_FLAG_DICTIONARY_ORDERED 1
+#define ARROW_FLAG_NULLABLE 2
+#define ARROW_FLAG_MAP_KEYS_SORTED 4
+
+    struct ArrowSchema
+    {
+        // Array type description
+        const char *format;
+        const char *name;
+        const char *metadata;
+        int64_t flags;
+        int64_t n_children;
+        struct ArrowSchema **children;
+        struct ArrowSchema *dictionary;
+
+        // Release callback
+        void (*release)(struct ArrowSchema *);
+        // Opaque producer-specific data
+        void *private_data;
+    };
+
+    struct ArrowArray
+    {
+        // Array data description
+        int64_t length;
+        int64_t null_count;
+        int64_t offset;
+        int64_t n_buffers;
+        int64_t n_children;
+        const void **buffers;
+        struct ArrowArray **children;
+        struct ArrowArray *dictionary;
+
+        // Release callback
+        void (*release)(struct ArrowArray *);
+        // Opaque producer-specific data
+        void *private_data;
+    };
+
+#endif // ARROW_C_DATA_INTERFACE
+
+    // Must match `EventType` in `src/lib.rs`.
+    enum DoraArrowEventType
+    {
+        DoraArrowEventType_Input,
+        DoraArrowEventType_InputClosed,
+        DoraArrowEventType_Stop,
+        // the event stream ended, no more events follow
+        DoraArrowEventType_End,
+        DoraArrowEventType_Error,
+    };
+
+    // Initializes the node from the environment that dora sets, or returns NULL on errors.
+    void *dora_arrow_init_from_env();
+    void dora_arrow_free_context(void *dora_context);
+
+    // Waits for the next event.
+    //
+    // For inputs and closed inputs, points `id` to the input id, which is valid until the next
+    // call. For inputs, moves the data into `array` and `schema`, which the caller must
+    // release with their `release` callbacks.
+    enum DoraArrowEventType dora_arrow_next_event(void *dora_context, const char **id,
+                                                  struct ArrowArray *array,
+                                                  struct ArrowSchema *schema);
+
+    // Sends `array` on the output `id`, and returns 0, or -1 on errors.
+    //
+    // Moves `array` and `schema` like a consumer of the C data interface, and releases them
+    // once the data is sent, also on errors.
+    int dora_arrow_send_output(void *dora_context, const char *id, struct ArrowArray *array,
+                               struct ArrowSchema *schema);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // DORA_ARROW_API_H
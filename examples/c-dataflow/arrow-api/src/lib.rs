@@ -0,0 +1,175 @@
+//! A C node API that exchanges Arrow arrays instead of bytes, through the Arrow C data
+//! interface, see `dora_arrow_api.h`.
+//!
+//! The C API of dora only reads and sends `UInt8` arrays, so a C node can't receive the
+//! typed outputs of other nodes, or send typed data itself. This library wraps the Rust node
+//! API instead, and moves the arrays across the language boundary with `arrow::ffi`.
+
+use arrow::{
+    array::{Array, make_array},
+    ffi::{FFI_ArrowArray, FFI_ArrowSchema, from_ffi, to_ffi},
+};
+use dora_node_api::{DoraNode, Event, EventStream, dora_core::config::DataId};
+use eyre::{Context as _, bail};
+use std::{
+    ffi::{CStr, CString, c_char, c_int, c_void},
+    ptr,
+};
+
+struct Context {
+    node: DoraNode,
+    events: EventStream,
+    /// The id of the last input, which the pointer of `dora_arrow_next_event` points into.
+    input_id: CString,
+}
+
+/// Must match `enum DoraArrowEventType` in `dora_arrow_api.h`.
+#[repr(C)]
+pub enum EventType {
+    Input,
+    InputClosed,
+    Stop,
+    End,
+    Error,
+}
+
+/// Initializes the node from the environment that dora sets, or returns null on errors.
+#[unsafe(no_mangle)]
+pub extern "C" fn dora_arrow_init_from_env() -> *mut c_void {
+    match DoraNode::init_from_env() {
+        Ok((node, events)) => Box::into_raw(Box::new(Context {
+            node,
+            events,
+            input_id: CString::default(),
+        }))
+        .cast(),
+        Err(err) => {
+            eprintln!("failed to init dora node: {err:?}");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+///
+/// `context` must come from `dora_arrow_init_from_env` and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dora_arrow_free_context(context: *mut c_void) {
+    if !context.is_null() {
+        drop(unsafe { Box::from_raw(context.cast::<Context>()) });
+    }
+}
+
+/// Waits for the next event.
+///
+/// For inputs and closed inputs, points `id` to the nul-terminated input id, which is valid
+/// until the next call. For inputs, it moves the data into `array` and `schema`, which the
+/// caller must release.
+///
+/// # Safety
+///
+/// `context` must come from `dora_arrow_init_from_env`, and the other pointers must be
+/// valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dora_arrow_next_event(
+    context: *mut c_void,
+    id: *mut *const c_char,
+    array: *mut FFI_ArrowArray,
+    schema: *mut FFI_ArrowSchema,
+) -> EventType {
+    let context = unsafe { &mut *context.cast::<Context>() };
+    loop {
+        let Some(event) = context.events.recv() else {
+            return EventType::End;
+        };
+        let (input_id, data) = match event {
+            Event::Input { id, data, .. } => (id, Some(data)),
+            Event::InputClosed { id } => (id, None),
+            Event::Stop(_) => return EventType::Stop,
+            Event::Error(err) => {
+                eprintln!("dora error: {err}");
+                return EventType::Error;
+            }
+            _ => continue,
+        };
+
+        context.input_id = match CString::new(input_id.to_string()) {
+            Ok(input_id) => input_id,
+            Err(err) => {
+                eprintln!("invalid input id: {err}");
+                return EventType::Error;
+            }
+        };
+        unsafe { *id = context.input_id.as_ptr() };
+        let Some(data) = data else {
+            return EventType::InputClosed;
+        };
+        match to_ffi(&data.to_data()) {
+            Ok((ffi_array, ffi_schema)) => {
+                unsafe {
+                    array.write(ffi_array);
+                    schema.write(ffi_schema);
+                }
+                return EventType::Input;
+            }
+            Err(err) => {
+                eprintln!("failed to export input `{input_id}`: {err}");
+                return EventType::Error;
+            }
+        }
+    }
+}
+
+/// Sends the array on the output `id`, and returns 0, or -1 on errors.
+///
+/// Moves the array, and releases it and the schema, also on errors.
+///
+/// # Safety
+///
+/// `context` must come from `dora_arrow_init_from_env`, `id` must be nul-terminated, and
+/// `array` and `schema` must be valid Arrow C data interface structs.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dora_arrow_send_output(
+    context: *mut c_void,
+    id: *const c_char,
+    array: *mut FFI_ArrowArray,
+    schema: *mut FFI_ArrowSchema,
+) -> c_int {
+    let context = unsafe { &mut *context.cast::<Context>() };
+    // take ownership, so that the structs are released when these are dropped
+    let (array, schema) = unsafe {
+        (
+            FFI_ArrowArray::from_raw(array),
+            FFI_ArrowSchema::from_raw(schema),
+        )
+    };
+    let id = unsafe { CStr::from_ptr(id) };
+    match send_output(context, id, array, &schema) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("{err:?}");
+            -1
+        }
+    }
+}
+
+fn send_output(
+    context: &mut Context,
+    id: &CStr,
+    array: FFI_ArrowArray,
+    schema: &FFI_ArrowSchema,
+) -> eyre::Result<()> {
+    let Ok(id) = id.to_str() else {
+        bail!("output id is not UTF-8");
+    };
+    let data = unsafe { from_ffi(array, schema) }
+        .with_context(|| format!("invalid array for output `{id}`"))?;
+    context
+        .node
+        .send_output(
+            DataId::from(id.to_owned()),
+            Default::default(),
+            make_array(data),
+        )
+        .with_context(|| format!("failed to send output `{id}`"))
+}
@@ -0,0 +1,121 @@
+#include <stdio.h>
+#include <string.h>
+#include "arrow-api/dora_arrow_api.h"
+
+// Must match `arrow_source.c`.
+#define MESSAGES 10
+#define SENSORS 3
+
+static double reading(int message, int sensor, int k)
+{
+    return message * 10.0 + sensor + k * 0.5;
+}
+
+// Checks the schema and the values of message `message`, and prints them.
+static int check_readings(int message, const struct ArrowArray *array, const struct ArrowSchema *schema)
+{
+    if (strcmp(schema->format, "+l") != 0 || schema->n_children != 1 ||
+        strcmp(schema->children[0]->format, "g") != 0)
+    {
+        fprintf(stderr, "[c arrow sink] expected List<Float64>, got format `%s`\n", schema->format);
+        return -1;
+    }
+    if (array->length != SENSORS || array->null_count != 0)
+    {
+        fprintf(stderr, "[c arrow sink] expected %d lists without nulls\n", SENSORS);
+        return -1;
+    }
+
+    // the offsets select the values of each list from the child array, both can be offset
+    const int32_t *offsets = (const int32_t *)array->buffers[1] + array->offset;
+    const struct ArrowArray *child = array->children[0];
+    const double *values = (const double *)child->buffers[1] + child->offset;
+
+    printf("[c arrow sink] message %d:", message);
+    for (int sensor = 0; sensor < SENSORS; sensor++)
+    {
+        int32_t start = offsets[sensor];
+        int32_t len = offsets[sensor + 1] - start;
+        if (len != sensor + 1)
+        {
+            fprintf(stderr, "[c arrow sink] sensor %d has %d values\n", sensor, len);
+            return -1;
+        }
+        printf(" [");
+        for (int32_t k = 0; k < len; k++)
+        {
+            double value = values[start + k];
+            if (value != reading(message, sensor, k))
+            {
+                fprintf(stderr, "[c arrow sink] unexpected value %f\n", value);
+                return -1;
+            }
+            printf(k == 0 ? "%.1f" : ", %.1f", value);
+        }
+        printf("]");
+    }
+    printf("\n");
+    return 0;
+}
+
+int main()
+{
+    printf("[c arrow sink] Hello World\n");
+
+    void *dora_context = dora_arrow_init_from_env();
+    if (dora_context == NULL)
+    {
+        fprintf(stderr, "failed to init dora context\n");
+        return -1;
+    }
+
+    int received = 0;
+    int result = 0;
+    while (result == 0)
+    {
+        const char *id;
+        struct ArrowArray array;
+        struct ArrowSchema schema;
+        enum DoraArrowEventType ty = dora_arrow_next_event(dora_context, &id, &array, &schema);
+
+        if (ty == DoraArrowEventType_Input)
+        {
+            if (strcmp(id, "readings") == 0)
+            {
+                result = check_readings(received, &array, &schema);
+                received++;
+            }
+            else
+            {
+                printf("[c arrow sink] ignoring unexpected input `%s`\n", id);
+            }
+            array.release(&array);
+            schema.release(&schema);
+        }
+        else if (ty == DoraArrowEventType_InputClosed)
+        {
+            printf("[c arrow sink] input `%s` was closed\n", id);
+        }
+        else if (ty == DoraArrowEventType_Stop || ty == DoraArrowEventType_End)
+        {
+            break;
+        }
+        else
+        {
+            fprintf(stderr, "[c arrow sink] received an error event\n");
+            result = -1;
+        }
+    }
+
+    dora_arrow_free_context(dora_context);
+    if (result == 0 && received != MESSAGES)
+    {
+        fprintf(stderr, "[c arrow sink] received %d of %d messages\n", received, MESSAGES);
+        result = -1;
+    }
+    if (result == 0)
+    {
+        printf("[c arrow sink] all %d messages had the expected schema and values\n", MESSAGES);
+    }
+    return result;
+}
@@ -0,0 +1,184 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "arrow-api/dora_arrow_api.h"
+
+// Must match `arrow_sink.c`.
+#define MESSAGES 10
+#define SENSORS 3
+
+// The value `k` of sensor `sensor` in message `message`. Sensor `sensor` has `sensor + 1` values.
+static double reading(int message, int sensor, int k)
+{
+    return message * 10.0 + sensor + k * 0.5;
+}
+
+// Frees the buffers and children of an array that `build_readings` allocated.
+static void release_array(struct ArrowArray *array)
+{
+    for (int64_t i = 0; i < array->n_children; i++)
+    {
+        struct ArrowArray *child = array->children[i];
+        if (child->release != NULL)
+        {
+            child->release(child);
+        }
+        free(child);
+    }
+    free(array->children);
+    // the first buffer is the validity bitmap, which is NULL without nulls
+    for (int64_t i = 1; i < array->n_buffers; i++)
+    {
+        free((void *)array->buffers[i]);
+    }
+    free(array->buffers);
+    array->release = NULL;
+}
+
+static void release_schema(struct ArrowSchema *schema)
+{
+    for (int64_t i = 0; i < schema->n_children; i++)
+    {
+        struct ArrowSchema *child = schema->children[i];
+        if (child->release != NULL)
+        {
+            child->release(child);
+        }
+        free(child);
+    }
+    free(schema->children);
+    schema->release = NULL;
+}
+
+// Builds a `List<Float64>` array with one list of readings per sensor.
+static void build_readings(int message, struct ArrowArray *array, struct ArrowSchema *schema)
+{
+    int32_t *offsets = malloc((SENSORS + 1) * sizeof(int32_t));
+    offsets[0] = 0;
+    for (int sensor = 0; sensor < SENSORS; sensor++)
+    {
+        offsets[sensor + 1] = offsets[sensor] + sensor + 1;
+    }
+    double *values = malloc(offsets[SENSORS] * sizeof(double));
+    for (int sensor = 0; sensor < SENSORS; sensor++)
+    {
+        for (int k = 0; k < sensor + 1; k++)
+        {
+            values[offsets[sensor] + k] = reading(message, sensor, k);
+        }
+    }
+
+    struct ArrowArray *child = malloc(sizeof(struct ArrowArray));
+    const void **child_buffers = malloc(2 * sizeof(void *));
+    child_buffers[0] = NULL;
+    child_buffers[1] = values;
+    *child = (struct ArrowArray){
+        .length = offsets[SENSORS],
+        .null_count = 0,
+        .offset = 0,
+        .n_buffers = 2,
+        .n_children = 0,
+        .buffers = child_buffers,
+        .children = NULL,
+        .dictionary = NULL,
+        .release = release_array,
+        .private_data = NULL,
+    };
+
+    const void **buffers = malloc(2 * sizeof(void *));
+    buffers[0] = NULL;
+    buffers[1] = offsets;
+    struct ArrowArray **children = malloc(sizeof(struct ArrowArray *));
+    children[0] = child;
+    *array = (struct ArrowArray){
+        .length = SENSORS,
+        .null_count = 0,
+        .offset = 0,
+        .n_buffers = 2,
+        .n_children = 1,
+        .buffers = buffers,
+        .children = children,
+        .dictionary = NULL,
+        .release = release_array,
+        .private_data = NULL,
+    };
+
+    // `+l` is a list with 32-bit offsets, `g` a 64-bit float
+    struct ArrowSchema *item = malloc(sizeof(struct ArrowSchema));
+    *item = (struct ArrowSchema){
+        .format = "g",
+        .name = "item",
+        .metadata = NULL,
+        .flags = ARROW_FLAG_NULLABLE,
+        .n_children = 0,
+        .children = NULL,
+        .dictionary = NULL,
+        .release = release_schema,
+        .private_data = NULL,
+    };
+    struct ArrowSchema **schema_children = malloc(sizeof(struct ArrowSchema *));
+    schema_children[0] = item;
+    *schema = (struct ArrowSchema){
+        .format = "+l",
+        .name = "readings",
+        .metadata = NULL,
+        .flags = ARROW_FLAG_NULLABLE,
+        .n_children = 1,
+        .children = schema_children,
+        .dictionary = NULL,
+        .release = release_schema,
+        .private_data = NULL,
+    };
+}
+
+int main()
+{
+    printf("[c arrow source] Hello World\n");
+
+    void *dora_context = dora_arrow_init_from_env();
+    if (dora_context == NULL)
+    {
+        fprintf(stderr, "failed to init dora context\n");
+        return -1;
+    }
+
+    int sent = 0;
+    while (sent < MESSAGES)
+    {
+        const char *id;
+        struct ArrowArray input;
+        struct ArrowSchema input_schema;
+        enum DoraArrowEventType ty = dora_arrow_next_event(dora_context, &id, &input, &input_schema);
+
+        if (ty == DoraArrowEventType_Input)
+        {
+            // the timer ticks carry no data
+            input.release(&input);
+            input_schema.release(&input_schema);
+
+            struct ArrowArray array;
+            struct ArrowSchema schema;
+            build_readings(sent, &array, &schema);
+            if (dora_arrow_send_output(dora_context, "readings", &array, &schema) != 0)
+            {
+                fprintf(stderr, "[c arrow source] failed to send readings\n");
+                return -1;
+            }
+            sent++;
+        }
+        else if (ty == DoraArrowEventType_Stop || ty == DoraArrowEventType_End)
+        {
+            printf("[c arrow source] stopped\n");
+            break;
+        }
+        else if (ty == DoraArrowEventType_Error)
+        {
+            fprintf(stderr, "[c arrow source] received an error event\n");
+            return -1;
+        }
+    }
+
+    printf("[c arrow source] sent %d messages\n", sent);
+    dora_arrow_free_context(dora_context);
+    return 0;
+}
@@ -1,73 +1,115 @@
 #include "build/node_api.h"
-#include <assert.h>
-#include <string.h>
 #include <stdio.h>
-#include <stdlib.h>
+#include <string.h>
 
-int main() {
-  printf("[c node] Hello World\n");
-  size_t counter = 0;
+// Input ids are not NUL-terminated.
+static int is_input(const char *id, size_t id_len, const char *expected)
+{
+    return id_len == strlen(expected) && memcmp(id, expected, id_len) == 0;
+}
 
-  void *dora_context = init_dora_context_from_env();
-  if (dora_context == NULL) {
-    fprintf(stderr, "failed to init dora context\n");
-    return -1;
-  }
+// Formats `value` with `format` and sends it on `output`.
+static int send_count(void *dora_context, const char *output, const char *format, size_t value)
+{
+    char data[100];
+    int data_len = snprintf(data, sizeof(data), format, value);
+    if (data_len < 0 || data_len >= (int)sizeof(data))
+    {
+        fprintf(stderr, "[c counter] failed to format output `%s`\n", output);
+        return -1;
+    }
+    if (dora_send_output(dora_context, (char *)output, strlen(output), data, data_len) != 0)
+    {
+        fprintf(stderr, "[c counter] failed to send output `%s`\n", output);
+        return -1;
+    }
+    return 0;
+}
 
-  printf("[c node] dora context initialized\n");
+// Counts the messages of `c_node`, and sends the count after each one on `counter`.
+//
+// Once `message` is closed, or the dataflow is stopped, it sends the number of messages it
+// counted on `total`, so that its receivers know that nothing is missing, frees the dora
+// context, and exits with 0. Outputs can only be sent while the context exists, so the total
+// has to be sent before it's freed.
+int main()
+{
+    printf("[c counter] Hello World\n");
 
-  while (1) {
-    void *event = dora_next_event(dora_context);
-    if (event == NULL)
-      {
-        printf("[c node] ERROR: unexpected end of event\n");
+    void *dora_context = init_dora_context_from_env();
+    if (dora_context == NULL)
+    {
+        fprintf(stderr, "failed to init dora context\n");
         return -1;
-      }
-
-    enum DoraEventType ty = read_dora_event_type(event);
+    }
 
-    if (ty == DoraEventType_Input) {
-      char *id;
-      size_t id_len;
-      read_dora_input_id(event, &id, &id_len);
-          
-      if (strcmp(id, "message") == 0) {
-        printf("message event\n");
+    printf("[c counter] dora context initialized\n");
 
-        char *data;
-        size_t data_len;
-        read_dora_input_data(event, &data, &data_len);
+    size_t counter = 0;
+    int result = 0;
+    int done = 0;
+    while (!done && result == 0)
+    {
+        void *event = dora_next_event(dora_context);
+        if (event == NULL)
+        {
+            // all inputs are closed
+            break;
+        }
 
-        counter += 1;
-        printf("C counter received message `%.*s`, counter: %zu\n", (int)data_len, data, counter);
+        enum DoraEventType ty = read_dora_event_type(event);
 
-        char *out_id = "counter";
-        char *out_id_heap = strdup(out_id);
+        if (ty == DoraEventType_Input)
+        {
+            char *id;
+            size_t id_len;
+            read_dora_input_id(event, &id, &id_len);
 
-        int data_alloc_size = 100;
-        char *out_data = (char *)malloc(data_alloc_size);
-        int out_data_len = snprintf(out_data, data_alloc_size, "The current counter value is %zu", counter);
-        assert(out_data_len >= 0 && out_data_len < 100);
-        dora_send_output(dora_context, out_id, strlen(out_id), out_data, out_data_len);
-      } else {
-        printf("C counter received unexpected input %s, context: %zu\n", id, counter);
-      }
-    }
-    else if (ty == DoraEventType_Stop) {
-      printf("[c node] received stop event\n");
-      break;
-    } else {
-      printf("[c node] received unexpected event: %d\n", ty);
-    }
+            if (is_input(id, id_len, "message"))
+            {
+                char *data;
+                size_t data_len;
+                read_dora_input_data(event, &data, &data_len);
 
-    free_dora_event(event);
-  }
+                counter += 1;
+                printf("[c counter] received message `%.*s`, counter: %zu\n", (int)data_len, data, counter);
+                result = send_count(dora_context, "counter", "The current counter value is %zu", counter);
+            }
+            else
+            {
+                printf("[c counter] ignoring unexpected input `%.*s`\n", (int)id_len, id);
+            }
+        }
+        else if (ty == DoraEventType_InputClosed)
+        {
+            // `message` is the only input, so no more messages will arrive
+            printf("[c counter] input was closed\n");
+            done = 1;
+        }
+        else if (ty == DoraEventType_Stop)
+        {
+            printf("[c counter] received stop event\n");
+            done = 1;
+        }
+        else
+        {
+            fprintf(stderr, "[c counter] received an error event\n");
+            result = -1;
+        }
 
-  printf("[c node] received 10 events\n");
+        free_dora_event(event);
+    }
 
-  free_dora_context(dora_context);
+    if (result == 0)
+    {
+        result = send_count(dora_context, "total", "%zu", counter);
+    }
 
-  printf("[c node] finished successfully\n");
+    free_dora_context(dora_context);
 
-  return 0;
+    if (result == 0)
+    {
+        printf("[c counter] counted %zu messages, finished successfully\n", counter);
+    }
+    return result;
 }
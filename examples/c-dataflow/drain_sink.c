@@ -0,0 +1,203 @@
+#include "build/node_api.h"
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Lines that are buffered before they're written to the output file.
+#define BATCH_LINES 8
+
+// Lines that were received, but not written yet.
+struct batch
+{
+    char *data;
+    size_t len;
+    size_t capacity;
+    size_t lines;
+};
+
+// Input ids are not NUL-terminated.
+static int is_input(const char *id, size_t id_len, const char *expected)
+{
+    return id_len == strlen(expected) && memcmp(id, expected, id_len) == 0;
+}
+
+static int batch_push(struct batch *batch, const char *line, size_t line_len)
+{
+    size_t needed = batch->len + line_len + 1;
+    if (needed > batch->capacity)
+    {
+        size_t capacity = batch->capacity == 0 ? 256 : batch->capacity;
+        while (capacity < needed)
+        {
+            capacity *= 2;
+        }
+        char *data = realloc(batch->data, capacity);
+        if (data == NULL)
+        {
+            return -1;
+        }
+        batch->data = data;
+        batch->capacity = capacity;
+    }
+    memcpy(batch->data + batch->len, line, line_len);
+    batch->data[batch->len + line_len] = '\n';
+    batch->len = needed;
+    batch->lines += 1;
+    return 0;
+}
+
+static int batch_flush(struct batch *batch, FILE *file)
+{
+    if (batch->len > 0 && fwrite(batch->data, 1, batch->len, file) != batch->len)
+    {
+        return -1;
+    }
+    batch->len = 0;
+    batch->lines = 0;
+    return fflush(file) == 0 ? 0 : -1;
+}
+
+// Writes the `counter` messages of the counter to `OUTPUT_FILE`, in batches of `BATCH_LINES`.
+//
+// On a stop event, it doesn't exit right away, but drains the inputs: it keeps handling the
+// messages that were sent before the stop until dora closes the event stream. Then it writes
+// the last, partial batch, followed by the `total` that the counter sent when it exited, and
+// frees the batch and the dora context. It exits with an error if the total doesn't match the
+// messages it received, or the counter exited without sending it.
+int main()
+{
+    printf("[c drain sink] Hello World\n");
+
+    const char *path = getenv("OUTPUT_FILE");
+    if (path == NULL)
+    {
+        path = "build/drain_sink.txt";
+    }
+    FILE *file = fopen(path, "w");
+    if (file == NULL)
+    {
+        fprintf(stderr, "[c drain sink] failed to open %s\n", path);
+        return -1;
+    }
+
+    void *dora_context = init_dora_context_from_env();
+    if (dora_context == NULL)
+    {
+        fprintf(stderr, "failed to init dora context\n");
+        fclose(file);
+        return -1;
+    }
+
+    printf("[c drain sink] dora context initialized\n");
+
+    struct batch batch = {NULL, 0, 0, 0};
+    size_t received = 0;
+    long long total = -1;
+    int stopped = 0;
+    int result = 0;
+    while (result == 0)
+    {
+        void *event = dora_next_event(dora_context);
+        if (event == NULL)
+        {
+            // all inputs are closed, there's nothing left to drain
+            break;
+        }
+
+        enum DoraEventType ty = read_dora_event_type(event);
+
+        if (ty == DoraEventType_Input)
+        {
+            char *id;
+            size_t id_len;
+            read_dora_input_id(event, &id, &id_len);
+
+            char *data;
+            size_t data_len;
+            read_dora_input_data(event, &data, &data_len);
+
+            if (is_input(id, id_len, "counter"))
+            {
+                received += 1;
+                if (stopped)
+                {
+                    printf("[c drain sink] draining `%.*s`\n", (int)data_len, data);
+                }
+                if (batch_push(&batch, data, data_len) != 0)
+                {
+                    fprintf(stderr, "[c drain sink] out of memory\n");
+                    result = -1;
+                }
+                else if (batch.lines == BATCH_LINES && batch_flush(&batch, file) != 0)
+                {
+                    fprintf(stderr, "[c drain sink] failed to write %s\n", path);
+                    result = -1;
+                }
+            }
+            else if (is_input(id, id_len, "total"))
+            {
+                char text[32];
+                size_t len = data_len < sizeof(text) - 1 ? data_len : sizeof(text) - 1;
+                memcpy(text, data, len);
+                text[len] = '\0';
+                total = strtoll(text, NULL, 10);
+                printf("[c drain sink] the counter counted %lld messages\n", total);
+            }
+            else
+            {
+                printf("[c drain sink] ignoring unexpected input `%.*s`\n", (int)id_len, id);
+            }
+        }
+        else if (ty == DoraEventType_InputClosed)
+        {
+            printf("[c drain sink] received InputClosed event\n");
+        }
+        else if (ty == DoraEventType_Stop)
+        {
+            printf("[c drain sink] received stop event, draining the remaining inputs\n");
+            stopped = 1;
+        }
+        else
+        {
+            fprintf(stderr, "[c drain sink] received an error event\n");
+            result = -1;
+        }
+
+        free_dora_event(event);
+    }
+
+    // the last batch is usually incomplete
+    if (result == 0 && batch_flush(&batch, file) != 0)
+    {
+        fprintf(stderr, "[c drain sink] failed to write %s\n", path);
+        result = -1;
+    }
+    if (result == 0 && total < 0)
+    {
+        fprintf(stderr, "[c drain sink] the counter exited without sending its total\n");
+        result = -1;
+    }
+    if (result == 0 && (size_t)total != received)
+    {
+        fprintf(stderr, "[c drain sink] received %zu messages, but the counter sent %lld\n", received, total);
+        result = -1;
+    }
+    if (result == 0)
+    {
+        fprintf(file, "total %lld\n", total);
+    }
+
+    free(batch.data);
+    free_dora_context(dora_context);
+    if (fclose(file) != 0 && result == 0)
+    {
+        fprintf(stderr, "[c drain sink] failed to write %s\n", path);
+        result = -1;
+    }
+
+    if (result == 0)
+    {
+        printf("[c drain sink] wrote %zu messages to %s, finished successfully\n", received, path);
+    }
+    return result;
+}
@@ -1,141 +1,109 @@
 use dora_tracing::set_up_tracing;
+use example_runner_utils::{CargoBuild, Dora, NativeNode};
 use eyre::{Context, bail};
-use std::{
-    env::consts::{DLL_PREFIX, DLL_SUFFIX, EXE_SUFFIX},
-    path::Path,
-};
+use std::path::Path;
+
+/// The messages that `node.c` sends.
+const MESSAGES: usize = 100;
+/// `OUTPUT_FILE` of `c_drain_sink` in `dataflow.yml`.
+const DRAIN_SINK_OUTPUT: &str = "build/drain_sink.txt";
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     set_up_tracing("c-dataflow-runner").wrap_err("failed to set up tracing")?;
 
-    let dora = std::path::PathBuf::from(std::env::var("DORA").unwrap());
+    // pass `--cmake` to build the nodes with the `CMakeLists.txt` of the example instead
+    let cmake = std::env::args().any(|arg| arg == "--cmake");
+    let dora = Dora::from_env()?;
     let root = Path::new(env!("CARGO_MANIFEST_DIR"));
     std::env::set_current_dir(root.join(file!()).parent().unwrap())
         .wrap_err("failed to set working dir")?;
 
-    tokio::fs::create_dir_all("build").await?;
-
-    build_package("dora-node-api-c").await?;
+    dora.package("dora-node-api-c").build().await?;
+    // the Arrow nodes use the API in `arrow-api` instead, which exchanges typed Arrow arrays
+    CargoBuild::new("c-dataflow-arrow-api")
+        .manifest_path("arrow-api/Cargo.toml")
+        .build()
+        .await?;
 
     tokio::fs::create_dir_all("build").await?;
     let build_dir = Path::new("build");
     tokio::fs::copy(
-        dora.join("apis/c/node/node_api.h"),
+        dora.root().join("apis/c/node/node_api.h"),
         build_dir.join("node_api.h"),
     )
     .await?;
 
-    build_c_node(&dora, "node.c", "c_node").await?;
-    build_c_node(&dora, "sink.c", "c_sink").await?;
-    build_c_node(&dora, "counter.c", "c_counter").await?;
-
-    let dataflow = Path::new("dataflow.yml").to_owned();
-    run_dataflow(&dataflow).await?;
-
-    Ok(())
-}
+    if cmake {
+        dora.cmake(".").build().await?;
+    } else {
+        for (source, name) in [
+            ("node.c", "c_node"),
+            ("sink.c", "c_sink"),
+            ("counter.c", "c_counter"),
+            ("drain_sink.c", "c_drain_sink"),
+        ] {
+            NativeNode::c(name)
+                .source(source)
+                .link("dora_node_api_c")
+                .lib_dir(dora.release_dir())
+                .build()
+                .await?;
+        }
+        for (source, name) in [
+            ("arrow_source.c", "c_arrow_source"),
+            ("arrow_sink.c", "c_arrow_sink"),
+        ] {
+            NativeNode::c(name)
+                .source(source)
+                .link("dora_arrow_api")
+                .lib_dir("arrow-api/target/release")
+                .build()
+                .await?;
+        }
+    }
 
-async fn build_package(package: &str) -> eyre::Result<()> {
-    let dora = std::env::var("DORA").unwrap();
-    let cargo = std::env::var("CARGO").unwrap();
+    let dataflow = Path::new("dataflow.yml");
+    validate_dataflows::check(dataflow)?;
+    match tokio::fs::remove_file(DRAIN_SINK_OUTPUT).await {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    // fails unless every node exits with 0
+    dora.run_dataflow(dataflow).await?;
 
-    let mut cmd = tokio::process::Command::new("bash");
-    let manifest = std::path::PathBuf::from(dora).join("Cargo.toml");
-    let manifest = manifest.to_str().unwrap();
-    cmd.args([
-        "-c",
-        &format!("cargo build --release --manifest-path {manifest} --package {package}",),
-    ]);
-    if !cmd.status().await?.success() {
-        bail!("failed to compile {package}");
-    };
+    check_drain_sink_output()?;
+    println!("Everything Done");
     Ok(())
 }
 
-async fn run_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--")
-        .arg("daemon")
-        .arg("--run-dataflow")
-        .arg(dataflow);
-    if !cmd.status().await?.success() {
-        bail!("failed to run dataflow");
+/// Checks that the drain sink wrote every count of the counter, followed by the total that the
+/// counter sent on exit.
+fn check_drain_sink_output() -> eyre::Result<()> {
+    let output = std::fs::read_to_string(DRAIN_SINK_OUTPUT)
+        .with_context(|| format!("the drain sink did not write {DRAIN_SINK_OUTPUT}"))?;
+    let mut lines: Vec<&str> = output.lines().collect();
+    let Some(total) = lines.pop().and_then(|line| line.strip_prefix("total ")) else {
+        bail!("expected {DRAIN_SINK_OUTPUT} to end with the total of the counter");
     };
-    Ok(())
-}
-
-async fn build_c_node(dora: &Path, name: &str, out_name: &str) -> eyre::Result<()> {
-    let mut clang = tokio::process::Command::new("clang");
-    clang.arg(name);
-    clang.arg("-l").arg("dora_node_api_c");
-    #[cfg(target_os = "linux")]
-    {
-        clang.arg("-l").arg("m");
-        clang.arg("-l").arg("rt");
-        clang.arg("-l").arg("dl");
-        clang.arg("-l").arg("z");
-        clang.arg("-pthread");
+    if total != MESSAGES.to_string() {
+        bail!("expected the counter to count {MESSAGES} messages, it sent a total of {total}");
     }
-    #[cfg(target_os = "windows")]
-    {
-        clang.arg("-ladvapi32");
-        clang.arg("-luserenv");
-        clang.arg("-lkernel32");
-        clang.arg("-lws2_32");
-        clang.arg("-lbcrypt");
-        clang.arg("-lncrypt");
-        clang.arg("-lschannel");
-        clang.arg("-lntdll");
-        clang.arg("-liphlpapi");
-
-        clang.arg("-lcfgmgr32");
-        clang.arg("-lcredui");
-        clang.arg("-lcrypt32");
-        clang.arg("-lcryptnet");
-        clang.arg("-lfwpuclnt");
-        clang.arg("-lgdi32");
-        clang.arg("-lmsimg32");
-        clang.arg("-lmswsock");
-        clang.arg("-lole32");
-        clang.arg("-loleaut32");
-        clang.arg("-lopengl32");
-        clang.arg("-lsecur32");
-        clang.arg("-lshell32");
-        clang.arg("-lsynchronization");
-        clang.arg("-luser32");
-        clang.arg("-lwinspool");
-        clang.arg("-lwinhttp");
-        clang.arg("-lrpcrt4");
-
-        clang.arg("-Wl,-nodefaultlib:libcmt");
-        clang.arg("-D_DLL");
-        clang.arg("-lmsvcrt");
+    if lines.len() != MESSAGES {
+        bail!(
+            "expected the drain sink to write {MESSAGES} counts, it wrote {}",
+            lines.len()
+        );
     }
-    #[cfg(target_os = "macos")]
-    {
-        clang.arg("-framework").arg("CoreServices");
-        clang.arg("-framework").arg("Security");
-        clang.arg("-l").arg("System");
-        clang.arg("-l").arg("resolv");
-        clang.arg("-l").arg("pthread");
-        clang.arg("-l").arg("c");
-        clang.arg("-l").arg("m");
-        clang.arg("-l").arg("z");
+    for (i, line) in lines.iter().enumerate() {
+        let expected = format!("The current counter value is {}", i + 1);
+        if *line != expected {
+            bail!(
+                "expected `{expected}` in line {} of {DRAIN_SINK_OUTPUT}, got `{line}`",
+                i + 1
+            );
+        }
     }
-    clang.arg("-L").arg(dora.join("target").join("release"));
-    clang
-        .arg("--output")
-        .arg(Path::new("build").join(format!("{out_name}{EXE_SUFFIX}")));
-    if !clang.status().await?.success() {
-        bail!("failed to compile c node");
-    };
+    println!("the drain sink received all {MESSAGES} counts and the total");
     Ok(())
 }
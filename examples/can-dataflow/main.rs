@@ -0,0 +1,425 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Must match `CAN_INTERFACE` in `dataflow.yml`.
+const INTERFACE: &str = "vcan0";
+
+/// The ids of the messages in `vehicle.dbc`.
+const ENGINE_STATUS_ID: u32 = 0x100;
+const WHEEL_SPEEDS_ID: u32 = 0x200;
+const BATTERY_STATUS_ID: u32 = 0x18FF_50E5;
+const CRUISE_COMMAND_ID: u32 = 0x400;
+/// Not in `vehicle.dbc`.
+const UNKNOWN_ID: u32 = 0x7FF;
+
+/// The drive of the runner: it accelerates to 60 km/h and cruises, sending the engine and the
+/// wheel speeds every step and the battery every tenth step.
+const STEPS: u64 = 300;
+const STEP: Duration = Duration::from_millis(10);
+const ACCELERATION_PER_STEP: f64 = 0.4;
+const CRUISE_SPEED_KMH: f64 = 60.0;
+const BATTERY_VOLTAGE_V: f64 = 13.8;
+/// Negative while charging, which tests the decoding of signed signals.
+const BATTERY_CURRENT_A: f64 = -12.5;
+
+/// Must match `ENGAGE_ABOVE_KMH` of `vehicle-monitor` in `dataflow.yml`.
+const ENGAGE_ABOVE_KMH: f64 = 50.0;
+
+/// Subset of `ReaderReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ReaderReport {
+    messages: BTreeMap<String, u64>,
+    own_frames: u64,
+    unknown_frames: u64,
+    invalid_frames: u64,
+}
+
+/// Subset of `MonitorReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct MonitorReport {
+    engine_status: u64,
+    wheel_speeds: u64,
+    battery_status: u64,
+    max_speed_kmh: f64,
+    max_engine_speed_rpm: f64,
+    battery_voltage_v: Option<f64>,
+    battery_current_a: Option<f64>,
+    engaged_at_kmh: Option<f64>,
+    commands: u64,
+}
+
+/// Subset of `WriterReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct WriterReport {
+    messages: BTreeMap<String, u64>,
+}
+
+/// A `CruiseCommand` frame of `can-writer`.
+#[derive(Debug, Clone, Copy)]
+struct CruiseCommand {
+    target_speed_kmh: f64,
+    enable: bool,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("can-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let bus = Bus::open(INTERFACE).await?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let vehicle = tokio::task::spawn_blocking({
+        let stop = stop.clone();
+        move || drive(bus, &stop)
+    });
+    let result = dora.run_dataflow(dataflow).await;
+    stop.store(true, Ordering::Relaxed);
+    result?;
+    let commands = vehicle.await??;
+
+    let reader: ReaderReport = read_json("out/reader.json")?;
+    println!(
+        "reader: {:?}, {} own, {} unknown, {} invalid frames",
+        reader.messages, reader.own_frames, reader.unknown_frames, reader.invalid_frames
+    );
+    let expected = [
+        ("EngineStatus", STEPS),
+        ("WheelSpeeds", STEPS),
+        ("BatteryStatus", STEPS / 10),
+    ];
+    for (message, count) in expected {
+        if reader.messages.get(message) != Some(&count) {
+            bail!("expected the reader to decode {count} `{message}` frames");
+        }
+    }
+    if reader.unknown_frames != 1 || reader.invalid_frames != 1 {
+        bail!("expected the reader to skip the frame with the unknown id and the short frame");
+    }
+    if reader.own_frames == 0 {
+        bail!("expected the reader to skip the frames of the writer");
+    }
+
+    let monitor: MonitorReport = read_json("out/monitor.json")?;
+    println!(
+        "monitor: max {:.2} km/h, max {:.1} rpm, battery {:?} V {:?} A, engaged at {:?} km/h",
+        monitor.max_speed_kmh,
+        monitor.max_engine_speed_rpm,
+        monitor.battery_voltage_v,
+        monitor.battery_current_a,
+        monitor.engaged_at_kmh
+    );
+    let received = [
+        monitor.engine_status,
+        monitor.wheel_speeds,
+        monitor.battery_status,
+    ];
+    if received != [STEPS, STEPS, STEPS / 10] {
+        bail!("expected the monitor to receive every decoded message, got {received:?}");
+    }
+    let close = |value: Option<f64>, expected: f64, tolerance: f64| {
+        value.is_some_and(|value| (value - expected).abs() <= tolerance)
+    };
+    if !close(monitor.battery_voltage_v, BATTERY_VOLTAGE_V, 0.01)
+        || !close(monitor.battery_current_a, BATTERY_CURRENT_A, 0.1)
+    {
+        bail!("expected {BATTERY_VOLTAGE_V} V and {BATTERY_CURRENT_A} A from the battery");
+    }
+    // the mean of the wheels is a bit faster than the vehicle
+    if !close(Some(monitor.max_speed_kmh), CRUISE_SPEED_KMH, 0.1)
+        || !close(
+            Some(monitor.max_engine_speed_rpm),
+            800.0 + 35.0 * CRUISE_SPEED_KMH,
+            1.0,
+        )
+    {
+        bail!("expected the monitor to see the cruise speed of {CRUISE_SPEED_KMH} km/h");
+    }
+    let Some(engaged_at) = monitor.engaged_at_kmh else {
+        bail!("expected the cruise control to engage above {ENGAGE_ABOVE_KMH} km/h");
+    };
+    if !(ENGAGE_ABOVE_KMH..=ENGAGE_ABOVE_KMH + 2.0).contains(&engaged_at) {
+        bail!("expected the cruise control to engage at {ENGAGE_ABOVE_KMH} km/h");
+    }
+
+    let writer: WriterReport = read_json("out/writer.json")?;
+    let written = writer.messages.get("CruiseCommand").copied().unwrap_or(0);
+    println!(
+        "writer: {written} commands, the runner received {}",
+        commands.len()
+    );
+    if written != monitor.commands || commands.len() as u64 != written {
+        bail!(
+            "the monitor sent {} commands, the writer wrote {written}, and the runner received {}",
+            monitor.commands,
+            commands.len()
+        );
+    }
+    if commands[0].enable {
+        bail!("expected the cruise control to start disengaged");
+    }
+    let engaged: Vec<_> = commands.iter().filter(|command| command.enable).collect();
+    if engaged.is_empty() {
+        bail!("expected the writer to send the engaged cruise control");
+    }
+    if engaged
+        .iter()
+        .any(|command| (command.target_speed_kmh - engaged_at).abs() > 0.01)
+    {
+        bail!("expected the cruise commands to target {engaged_at} km/h, got {engaged:?}");
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Plays the other ECUs of the vehicle on `bus`, and records the cruise commands of the dataflow
+/// until `stop` is set.
+fn drive(bus: Bus, stop: &AtomicBool) -> eyre::Result<Vec<CruiseCommand>> {
+    let mut commands = Vec::new();
+    // the monitor sends its first command once all nodes run, so the reader listens by then
+    let start = Instant::now();
+    while commands.is_empty() {
+        if stop.load(Ordering::Relaxed) {
+            bail!("the dataflow stopped before it sent a cruise command");
+        }
+        if start.elapsed() > Duration::from_secs(60) {
+            bail!("the dataflow sent no cruise command within 60 s");
+        }
+        receive_commands(
+            &bus,
+            &mut commands,
+            Instant::now() + Duration::from_millis(100),
+        )?;
+    }
+
+    let mut next_step = Instant::now();
+    for step in 0..STEPS {
+        let speed = (step as f64 * ACCELERATION_PER_STEP).min(CRUISE_SPEED_KMH);
+        let throttle = if speed < CRUISE_SPEED_KMH { 40.0 } else { 15.0 };
+        let coolant = 80.0 + step as f64 / 30.0;
+        bus.send(
+            ENGINE_STATUS_ID,
+            false,
+            &engine_status(800.0 + 35.0 * speed, coolant, throttle),
+        )?;
+        let wheels = [speed, speed + 0.2, (speed - 0.1).max(0.0), speed + 0.1];
+        bus.send(WHEEL_SPEEDS_ID, false, &wheel_speeds(wheels))?;
+        if step % 10 == 0 {
+            bus.send(
+                BATTERY_STATUS_ID,
+                true,
+                &battery_status(BATTERY_VOLTAGE_V, BATTERY_CURRENT_A),
+            )?;
+        }
+        match step {
+            100 => bus.send(UNKNOWN_ID, false, &[0; 8])?,
+            // too short for `EngineStatus`
+            200 => bus.send(ENGINE_STATUS_ID, false, &[0; 2])?,
+            _ => {}
+        }
+        next_step += STEP;
+        receive_commands(&bus, &mut commands, next_step)?;
+    }
+
+    while !stop.load(Ordering::Relaxed) {
+        receive_commands(
+            &bus,
+            &mut commands,
+            Instant::now() + Duration::from_millis(100),
+        )?;
+    }
+    // the last commands may still be queued in the socket
+    receive_commands(
+        &bus,
+        &mut commands,
+        Instant::now() + Duration::from_millis(100),
+    )?;
+    Ok(commands)
+}
+
+/// Records the cruise commands on `bus` until `deadline`.
+fn receive_commands(
+    bus: &Bus,
+    commands: &mut Vec<CruiseCommand>,
+    deadline: Instant,
+) -> eyre::Result<()> {
+    while let Some(timeout) = deadline
+        .checked_duration_since(Instant::now())
+        .filter(|timeout| !timeout.is_zero())
+    {
+        let Some((id, data)) = bus.receive(timeout)? else {
+            continue;
+        };
+        if id == CRUISE_COMMAND_ID {
+            let [low, high, flags, ..] = data[..] else {
+                bail!("cruise command with {} bytes", data.len());
+            };
+            commands.push(CruiseCommand {
+                target_speed_kmh: f64::from(u16::from_le_bytes([low, high])) * 0.01,
+                enable: flags & 1 == 1,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The frame data of `EngineStatus` in `vehicle.dbc`.
+fn engine_status(engine_speed_rpm: f64, coolant_temp_c: f64, throttle_pct: f64) -> Vec<u8> {
+    let mut data = ((engine_speed_rpm / 0.25).round() as u16)
+        .to_le_bytes()
+        .to_vec();
+    data.push((coolant_temp_c + 40.0).round() as u8);
+    data.push((throttle_pct / 0.4).round() as u8);
+    data.resize(8, 0);
+    data
+}
+
+/// The frame data of `WheelSpeeds` in `vehicle.dbc`.
+fn wheel_speeds(speeds_kmh: [f64; 4]) -> Vec<u8> {
+    speeds_kmh
+        .iter()
+        .flat_map(|speed| ((speed / 0.01).round() as u16).to_le_bytes())
+        .collect()
+}
+
+/// The frame data of `BatteryStatus` in `vehicle.dbc`.
+fn battery_status(voltage_v: f64, current_a: f64) -> Vec<u8> {
+    let mut data = ((voltage_v / 0.01).round() as u16).to_le_bytes().to_vec();
+    data.extend(((current_a / 0.1).round() as i16).to_le_bytes());
+    data
+}
+
+/// A raw socket on the CAN interface, on which the runner plays the other ECUs of the vehicle.
+#[cfg(target_os = "linux")]
+struct Bus(socketcan::CanSocket);
+
+#[cfg(target_os = "linux")]
+impl Bus {
+    /// Opens the virtual CAN interface `interface`, and creates it first if needed, which
+    /// needs the `vcan` kernel module and root, through `sudo` unless running as root.
+    async fn open(interface: &str) -> eyre::Result<Self> {
+        use socketcan::Socket;
+
+        let flags = std::fs::read_to_string(format!("/sys/class/net/{interface}/flags"));
+        let is_up = flags
+            .ok()
+            .and_then(|flags| u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok())
+            .is_some_and(|flags| flags & libc::IFF_UP as u32 != 0);
+        if !is_up {
+            // SAFETY: `geteuid` has no preconditions
+            let sudo = if unsafe { libc::geteuid() } == 0 {
+                ""
+            } else {
+                "sudo "
+            };
+            let create = if Path::new("/sys/class/net").join(interface).exists() {
+                String::new()
+            } else {
+                // `vcan` may be built into the kernel, so ignore if `modprobe` fails
+                format!("{sudo}modprobe vcan; {sudo}ip link add dev {interface} type vcan && ")
+            };
+            let mut cmd = tokio::process::Command::new("bash");
+            cmd.arg("-c")
+                .arg(format!("{create}{sudo}ip link set up {interface}"));
+            example_runner_utils::run(
+                &mut cmd,
+                &format!(
+                    "failed to set up {interface}, which needs the `vcan` kernel module, e.g. \
+                     from `linux-modules-extra-$(uname -r)` on Ubuntu"
+                ),
+            )
+            .await?;
+        }
+
+        let socket = socketcan::CanSocket::open(interface)
+            .with_context(|| format!("failed to open CAN interface {interface}"))?;
+        Ok(Self(socket))
+    }
+
+    fn send(&self, id: u32, extended: bool, data: &[u8]) -> eyre::Result<()> {
+        use socketcan::{CanFrame, EmbeddedFrame, ExtendedId, Id, Socket, StandardId};
+
+        let id = if extended {
+            ExtendedId::new(id).map(Id::Extended)
+        } else {
+            u16::try_from(id)
+                .ok()
+                .and_then(StandardId::new)
+                .map(Id::Standard)
+        };
+        let frame = id
+            .and_then(|id| CanFrame::new(id, data))
+            .ok_or_else(|| eyre::eyre!("invalid CAN frame {id:?} {data:02X?}"))?;
+        self.0
+            .write_frame(&frame)
+            .wrap_err("failed to write CAN frame")
+    }
+
+    /// The id and data of the next data frame, or `None` after `timeout`.
+    fn receive(&self, timeout: Duration) -> eyre::Result<Option<(u32, Vec<u8>)>> {
+        use socketcan::{EmbeddedFrame, Id, Socket};
+
+        self.0.set_read_timeout(timeout)?;
+        match self.0.read_frame() {
+            Ok(frame) if frame.is_remote_frame() => Ok(None),
+            Ok(frame) => {
+                let id = match frame.id() {
+                    Id::Standard(id) => u32::from(id.as_raw()),
+                    Id::Extended(id) => id.as_raw(),
+                };
+                Ok(Some((id, frame.data().to_vec())))
+            }
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err).wrap_err("failed to read CAN frame"),
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct Bus(std::convert::Infallible);
+
+#[cfg(not(target_os = "linux"))]
+impl Bus {
+    async fn open(_interface: &str) -> eyre::Result<Self> {
+        bail!("SocketCAN, and so this example, is only available on Linux");
+    }
+
+    fn send(&self, _id: u32, _extended: bool, _data: &[u8]) -> eyre::Result<()> {
+        match self.0 {}
+    }
+
+    fn receive(&self, _timeout: Duration) -> eyre::Result<Option<(u32, Vec<u8>)>> {
+        match self.0 {}
+    }
+}
@@ -0,0 +1,134 @@
+use can_dataflow_nodes::{Dbc, ReaderReport, env_or, signals_to_arrow, write_json};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, dora_core::config::DataId,
+};
+use eyre::{Context, bail};
+use socketcan::{CanSocket, EmbeddedFrame, Id, Socket};
+use std::{
+    path::PathBuf,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+/// Reads the frames of the CAN interface `CAN_INTERFACE`, and decodes them with the messages of
+/// the DBC file `DBC_FILE`.
+///
+/// Sends every decoded message on the output named after it in snake case, e.g. `EngineStatus`
+/// on `engine_status`, as a struct array with a Float64 column per signal in physical units, and
+/// its CAN id as the `can_id` metadata parameter. Skips the messages that `DBC_NODE` transmits,
+/// i.e. the frames of `can-writer`, and counts frames with unknown ids or too little data.
+///
+/// Stops when no other frame arrived for `IDLE_TIMEOUT_MS` after the first, e.g. at the end of a
+/// replayed log, or never if it's 0. Writes a `ReaderReport` to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let interface: String = env_or("CAN_INTERFACE", "vcan0".to_owned())?;
+    let dbc_file: PathBuf = env_or("DBC_FILE", "vehicle.dbc".to_owned())?.into();
+    let own_node: String = env_or("DBC_NODE", "Dataflow".to_owned())?;
+    let idle_timeout = Duration::from_millis(env_or("IDLE_TIMEOUT_MS", 0)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/reader.json".to_owned())?.into();
+
+    let dbc = Dbc::read(&dbc_file)?;
+    // open the socket before the node, so that it receives every frame once the dataflow runs
+    let socket = CanSocket::open(&interface)
+        .with_context(|| format!("failed to open CAN interface {interface}"))?;
+    println!(
+        "reading {interface} with {} messages of {}",
+        dbc.messages.len(),
+        dbc_file.display()
+    );
+
+    let (frames_tx, frames) = mpsc::channel();
+    std::thread::spawn({
+        let interface = interface.clone();
+        move || {
+            loop {
+                match socket.read_frame() {
+                    Ok(frame) => {
+                        if frames_tx.send(frame).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("failed to read from {interface}: {err}");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = ReaderReport::default();
+    let mut last_frame = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    loop {
+                        let frame = match frames.try_recv() {
+                            Ok(frame) => frame,
+                            Err(mpsc::TryRecvError::Empty) => break,
+                            Err(mpsc::TryRecvError::Disconnected) => {
+                                bail!("stopped reading from {interface}")
+                            }
+                        };
+                        report.frames += 1;
+                        if frame.is_remote_frame() {
+                            continue;
+                        }
+                        let (can_id, extended) = match frame.id() {
+                            Id::Standard(id) => (u32::from(id.as_raw()), false),
+                            Id::Extended(id) => (id.as_raw(), true),
+                        };
+                        let Some(message) = dbc.message(can_id, extended) else {
+                            eprintln!("Ignoring frame with unknown id {can_id:#X}");
+                            report.unknown_frames += 1;
+                            last_frame = Some(Instant::now());
+                            continue;
+                        };
+                        if message.transmitter == own_node {
+                            report.own_frames += 1;
+                            continue;
+                        }
+                        last_frame = Some(Instant::now());
+
+                        match message.decode(frame.data()) {
+                            Ok(values) => {
+                                let mut parameters = MetadataParameters::default();
+                                parameters
+                                    .insert("can_id".into(), Parameter::Integer(can_id.into()));
+                                node.send_output(
+                                    DataId::from(message.data_id()),
+                                    parameters,
+                                    signals_to_arrow(&message.signal_names(), &[values]),
+                                )?;
+                                *report.messages.entry(message.name.clone()).or_default() += 1;
+                            }
+                            Err(err) => {
+                                eprintln!("Ignoring frame {can_id:#X}: {err}");
+                                report.invalid_frames += 1;
+                            }
+                        }
+                    }
+                    let idle =
+                        last_frame.is_some_and(|last: Instant| last.elapsed() >= idle_timeout);
+                    if !idle_timeout.is_zero() && idle {
+                        println!("{interface} was idle for {idle_timeout:?}");
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "read {} frames: {:?}, {} unknown, {} invalid",
+        report.frames, report.messages, report.unknown_frames, report.invalid_frames
+    );
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,68 @@
+use can_dataflow_nodes::{Dbc, WriterReport, env_or, signals_from_arrow, write_json};
+use dora_node_api::{self, DoraNode, Event};
+use eyre::{Context, OptionExt};
+use socketcan::{CanFrame, CanSocket, EmbeddedFrame, ExtendedId, Id, Socket, StandardId};
+use std::path::PathBuf;
+
+/// Writes the inputs as frames to the CAN interface `CAN_INTERFACE`, encoded with the messages
+/// of the DBC file `DBC_FILE` that `DBC_NODE` transmits.
+///
+/// An input is encoded with the message that it's named after in snake case, e.g.
+/// `cruise_command` with `CruiseCommand`. It has to be a struct array with a Float64 column per
+/// signal in physical units, and every row becomes a frame. Writes a `WriterReport` to
+/// `REPORT_FILE` when all inputs are closed.
+fn main() -> eyre::Result<()> {
+    let interface: String = env_or("CAN_INTERFACE", "vcan0".to_owned())?;
+    let dbc_file: PathBuf = env_or("DBC_FILE", "vehicle.dbc".to_owned())?.into();
+    let own_node: String = env_or("DBC_NODE", "Dataflow".to_owned())?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/writer.json".to_owned())?.into();
+
+    let dbc = Dbc::read(&dbc_file)?;
+    let socket = CanSocket::open(&interface)
+        .with_context(|| format!("failed to open CAN interface {interface}"))?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = WriterReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => {
+                let message = dbc
+                    .messages
+                    .iter()
+                    .find(|m| m.transmitter == own_node && m.data_id() == id.as_str());
+                let Some(message) = message else {
+                    eprintln!("Ignoring unexpected input `{id}`");
+                    continue;
+                };
+                let can_id = if message.extended {
+                    ExtendedId::new(message.id).map(Id::Extended)
+                } else {
+                    u16::try_from(message.id)
+                        .ok()
+                        .and_then(StandardId::new)
+                        .map(Id::Standard)
+                };
+                let can_id =
+                    can_id.ok_or_else(|| eyre::eyre!("invalid CAN id {:#X}", message.id))?;
+
+                for values in signals_from_arrow(&message.signal_names(), &data.0)
+                    .with_context(|| format!("invalid input `{id}`"))?
+                {
+                    let frame = CanFrame::new(can_id, &message.encode(&values)?)
+                        .ok_or_eyre("frame data is longer than 8 bytes")?;
+                    socket
+                        .write_frame(&frame)
+                        .with_context(|| format!("failed to write to {interface}"))?;
+                    *report.messages.entry(message.name.clone()).or_default() += 1;
+                }
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("wrote {:?}", report.messages);
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,319 @@
+//! A subset of the DBC format, which describes how the signals of a CAN bus are packed into the
+//! data of its frames, and the Arrow encoding of decoded messages.
+//!
+//! The parser reads the messages (`BO_`) and their signals (`SG_`), and skips everything else,
+//! e.g. comments and value tables. It supports signed and unsigned little-endian (Intel, `@1`)
+//! signals in classic CAN frames of up to 8 bytes, without multiplexing.
+
+use dora_node_api::arrow::{
+    array::{Array, ArrayRef, AsArray, Float64Array, StructArray},
+    datatypes::{DataType, Field, Float64Type},
+};
+use eyre::{Context, OptionExt, bail, eyre};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Set in the id of a `BO_` for messages with a 29-bit extended id.
+const EXTENDED_ID_FLAG: u32 = 0x8000_0000;
+
+#[derive(Debug, Clone)]
+pub struct Dbc {
+    pub messages: Vec<MessageDef>,
+}
+
+/// A `BO_` and its signals.
+#[derive(Debug, Clone)]
+pub struct MessageDef {
+    /// The CAN id, without the extended flag.
+    pub id: u32,
+    pub extended: bool,
+    pub name: String,
+    /// The length of the data of its frames in bytes.
+    pub size: usize,
+    /// The node of the bus that sends the message.
+    pub transmitter: String,
+    pub signals: Vec<Signal>,
+}
+
+/// A `SG_`, whose physical value is `raw * factor + offset`.
+#[derive(Debug, Clone)]
+pub struct Signal {
+    pub name: String,
+    pub start_bit: u32,
+    pub length: u32,
+    pub signed: bool,
+    pub factor: f64,
+    pub offset: f64,
+    pub unit: String,
+}
+
+impl Dbc {
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("invalid DBC file {}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> eyre::Result<Self> {
+        let mut messages: Vec<MessageDef> = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            let context = || format!("line {}: `{line}`", number + 1);
+            if let Some(definition) = line.strip_prefix("BO_ ") {
+                messages.push(parse_message(definition).with_context(context)?);
+            } else if let Some(definition) = line.strip_prefix("SG_ ") {
+                let message = messages
+                    .last_mut()
+                    .ok_or_else(|| eyre!("signal outside of a message"))
+                    .with_context(context)?;
+                let signal = parse_signal(definition, message.size).with_context(context)?;
+                message.signals.push(signal);
+            }
+        }
+        Ok(Self { messages })
+    }
+
+    /// The message with the CAN id `id`.
+    pub fn message(&self, id: u32, extended: bool) -> Option<&MessageDef> {
+        self.messages
+            .iter()
+            .find(|message| message.id == id && message.extended == extended)
+    }
+}
+
+impl MessageDef {
+    /// The id of the dora output or input of the message, its name in snake case, e.g.
+    /// `engine_status` for `EngineStatus`.
+    pub fn data_id(&self) -> String {
+        let mut id = String::new();
+        let mut previous_lowercase = false;
+        for c in self.name.chars() {
+            if c.is_uppercase() && previous_lowercase {
+                id.push('_');
+            }
+            previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+            id.extend(c.to_lowercase());
+        }
+        id
+    }
+
+    pub fn signal_names(&self) -> Vec<&str> {
+        self.signals.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// The physical values of the signals in the frame data `data`, in the order of `signals`.
+    pub fn decode(&self, data: &[u8]) -> eyre::Result<Vec<f64>> {
+        if data.len() < self.size {
+            bail!(
+                "`{}` has {} bytes, the frame has {}",
+                self.name,
+                self.size,
+                data.len()
+            );
+        }
+        let mut bytes = [0; 8];
+        bytes[..self.size].copy_from_slice(&data[..self.size]);
+        let data = u64::from_le_bytes(bytes);
+        Ok(self.signals.iter().map(|s| s.decode(data)).collect())
+    }
+
+    /// The frame data of the physical values `values`, in the order of `signals`.
+    pub fn encode(&self, values: &[f64]) -> eyre::Result<Vec<u8>> {
+        if values.len() != self.signals.len() {
+            bail!(
+                "`{}` has {} signals, got {} values",
+                self.name,
+                self.signals.len(),
+                values.len()
+            );
+        }
+        let mut data = 0;
+        for (signal, value) in self.signals.iter().zip(values) {
+            signal.encode(*value, &mut data)?;
+        }
+        Ok(data.to_le_bytes()[..self.size].to_vec())
+    }
+}
+
+impl Signal {
+    fn decode(&self, data: u64) -> f64 {
+        // shift the signal to the top bits, so that shifting it back sign-extends it
+        let top = data << (64 - self.start_bit - self.length);
+        let shift = 64 - self.length;
+        let raw = if self.signed {
+            ((top as i64) >> shift) as f64
+        } else {
+            (top >> shift) as f64
+        };
+        raw * self.factor + self.offset
+    }
+
+    fn encode(&self, value: f64, data: &mut u64) -> eyre::Result<()> {
+        let raw = ((value - self.offset) / self.factor).round();
+        let (min, max) = if self.signed {
+            let half = 2f64.powi(self.length as i32 - 1);
+            (-half, half - 1.0)
+        } else {
+            (0.0, 2f64.powi(self.length as i32) - 1.0)
+        };
+        if !(min..=max).contains(&raw) {
+            bail!(
+                "`{}` can't encode {value}, its raw values are {min}..={max}",
+                self.name
+            );
+        }
+        let mask = u64::MAX >> (64 - self.length);
+        *data |= (raw as i64 as u64 & mask) << self.start_bit;
+        Ok(())
+    }
+}
+
+/// Parses `<id> <name>: <size> <transmitter>`.
+fn parse_message(definition: &str) -> eyre::Result<MessageDef> {
+    let (id, rest) = definition
+        .split_once(' ')
+        .ok_or_eyre("expected `BO_ <id> <name>: <size> <transmitter>`")?;
+    let (name, rest) = rest.split_once(':').ok_or_eyre("expected `<name>:`")?;
+    let mut fields = rest.split_whitespace();
+    let (Some(size), Some(transmitter)) = (fields.next(), fields.next()) else {
+        bail!("expected `<size> <transmitter>` after the name");
+    };
+    let id: u32 = id.parse().context("invalid message id")?;
+    let size: usize = size.parse().context("invalid message size")?;
+    if size > 8 {
+        bail!("messages of more than 8 bytes need CAN FD, which is not supported");
+    }
+    Ok(MessageDef {
+        id: id & !EXTENDED_ID_FLAG,
+        extended: id & EXTENDED_ID_FLAG != 0,
+        name: name.trim().to_owned(),
+        size,
+        transmitter: transmitter.to_owned(),
+        signals: Vec::new(),
+    })
+}
+
+/// Parses `<name> : <start>|<length>@<order><sign> (<factor>,<offset>) [<min>|<max>] "<unit>"
+/// <receivers>`.
+fn parse_signal(definition: &str, message_size: usize) -> eyre::Result<Signal> {
+    let (name, rest) = definition
+        .split_once(':')
+        .ok_or_eyre("expected `SG_ <name> : <layout> (<factor>,<offset>) ...`")?;
+    let name = name.trim();
+    if name.contains(char::is_whitespace) {
+        bail!("multiplexed signal `{name}` is not supported");
+    }
+    let mut fields = rest.split_whitespace();
+    let (Some(layout), Some(scaling)) = (fields.next(), fields.next()) else {
+        bail!("expected a layout and a scaling after the name");
+    };
+
+    let (start_bit, rest) = layout.split_once('|').ok_or_eyre("invalid layout")?;
+    let (length, rest) = rest.split_once('@').ok_or_eyre("invalid layout")?;
+    let signed = match rest {
+        "1+" => false,
+        "1-" => true,
+        "0+" | "0-" => bail!("big-endian signal `{name}` is not supported"),
+        other => bail!("invalid byte order and sign `{other}`"),
+    };
+    let start_bit: u32 = start_bit.parse().context("invalid start bit")?;
+    let length: u32 = length.parse().context("invalid length")?;
+    if length == 0 || start_bit + length > message_size as u32 * 8 {
+        bail!("signal `{name}` doesn't fit into a message of {message_size} bytes");
+    }
+
+    let (factor, offset) = scaling
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split_once(',')
+        .ok_or_eyre("expected `(<factor>,<offset>)`")?;
+    Ok(Signal {
+        name: name.to_owned(),
+        start_bit,
+        length,
+        signed,
+        factor: factor.parse().context("invalid factor")?,
+        offset: offset.parse().context("invalid offset")?,
+        // in quotes, and may contain spaces
+        unit: definition.split('"').nth(1).unwrap_or_default().to_owned(),
+    })
+}
+
+/// Encodes decoded messages as a struct array with a Float64 column per signal, named after
+/// the signal, with a row per message.
+pub fn signals_to_arrow(names: &[&str], rows: &[Vec<f64>]) -> StructArray {
+    StructArray::from(
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let column: ArrayRef = Arc::new(Float64Array::from_iter_values(
+                    rows.iter().map(|row| row[i]),
+                ));
+                (
+                    Arc::new(Field::new(*name, DataType::Float64, false)),
+                    column,
+                )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// The rows of the struct array of `signals_to_arrow`, with the values of `names` in order.
+pub fn signals_from_arrow(names: &[&str], data: &dyn Array) -> eyre::Result<Vec<Vec<f64>>> {
+    let columns = names
+        .iter()
+        .map(|name| signal_column(data, name))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    Ok((0..data.len())
+        .map(|i| columns.iter().map(|column| column.value(i)).collect())
+        .collect())
+}
+
+/// The column of the signal `name` in the struct array of `signals_to_arrow`.
+pub fn signal_column<'a>(data: &'a dyn Array, name: &str) -> eyre::Result<&'a Float64Array> {
+    data.as_struct_opt()
+        .ok_or_eyre("expected a struct array")?
+        .column_by_name(name)
+        .ok_or_else(|| eyre!("no column for the signal `{name}`"))?
+        .as_primitive_opt::<Float64Type>()
+        .ok_or_else(|| eyre!("`{name}` is not a Float64 array"))
+}
+
+/// Written by `can-reader` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReaderReport {
+    pub frames: u64,
+    /// Decoded frames by message name.
+    pub messages: BTreeMap<String, u64>,
+    /// Frames of messages that `DBC_NODE` transmits, which the reader skips.
+    pub own_frames: u64,
+    pub unknown_frames: u64,
+    /// Frames of known messages that failed to decode, e.g. with too little data.
+    pub invalid_frames: u64,
+}
+
+/// Written by `vehicle-monitor` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MonitorReport {
+    pub engine_status: u64,
+    pub wheel_speeds: u64,
+    pub battery_status: u64,
+    pub max_speed_kmh: f64,
+    pub max_engine_speed_rpm: f64,
+    pub coolant_temp_c: Option<f64>,
+    pub battery_voltage_v: Option<f64>,
+    pub battery_current_a: Option<f64>,
+    /// The target speed when the cruise control engaged for the first time.
+    pub engaged_at_kmh: Option<f64>,
+    pub commands: u64,
+}
+
+/// Written by `can-writer` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WriterReport {
+    /// Written frames by message name.
+    pub messages: BTreeMap<String, u64>,
+}
@@ -0,0 +1,111 @@
+use can_dataflow_nodes::{
+    MonitorReport, env_or, signal_column, signals_from_arrow, signals_to_arrow, write_json,
+};
+use dora_node_api::{self, DoraNode, Event, MetadataParameters, dora_core::config::DataId};
+use std::path::PathBuf;
+
+/// The inputs with the decoded messages of `can-reader`.
+const MESSAGE_INPUTS: [&str; 3] = ["engine_status", "wheel_speeds", "battery_status"];
+
+/// Watches the decoded messages of the vehicle, and controls its cruise control.
+///
+/// Engages the cruise control at the current speed, the mean of the wheel speeds, once it's
+/// above `ENGAGE_ABOVE_KMH` while the coolant is below `MAX_COOLANT_TEMP_C` and the battery
+/// above `MIN_VOLTAGE_V`, and disengages it below `DISENGAGE_BELOW_KMH`. Sends its state on
+/// every `tick` on `cruise_command`, like a cyclic CAN message, with the signals `TargetSpeed`
+/// and `Enable`.
+///
+/// Stops when the inputs of the decoded messages are closed. Writes a `MonitorReport` to
+/// `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let engage_above: f64 = env_or("ENGAGE_ABOVE_KMH", 50.0)?;
+    let disengage_below: f64 = env_or("DISENGAGE_BELOW_KMH", 30.0)?;
+    let max_coolant_temp: f64 = env_or("MAX_COOLANT_TEMP_C", 110.0)?;
+    let min_voltage: f64 = env_or("MIN_VOLTAGE_V", 11.5)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/monitor.json".to_owned())?.into();
+    let output = DataId::from("cruise_command".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = MonitorReport::default();
+    let mut target_speed: Option<f64> = None;
+    let mut open_inputs = MESSAGE_INPUTS.len();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "engine_status" => {
+                    let engine_speed = signal_column(&data.0, "EngineSpeed")?;
+                    let coolant = signal_column(&data.0, "CoolantTemp")?;
+                    for i in 0..data.len() {
+                        report.max_engine_speed_rpm =
+                            report.max_engine_speed_rpm.max(engine_speed.value(i));
+                        report.coolant_temp_c = Some(coolant.value(i));
+                        report.engine_status += 1;
+                    }
+                }
+                "battery_status" => {
+                    let voltage = signal_column(&data.0, "Voltage")?;
+                    let current = signal_column(&data.0, "Current")?;
+                    for i in 0..data.len() {
+                        report.battery_voltage_v = Some(voltage.value(i));
+                        report.battery_current_a = Some(current.value(i));
+                        report.battery_status += 1;
+                    }
+                }
+                "wheel_speeds" => {
+                    let wheels = ["FrontLeft", "FrontRight", "RearLeft", "RearRight"];
+                    for row in signals_from_arrow(&wheels, &data.0)? {
+                        let speed = row.iter().sum::<f64>() / wheels.len() as f64;
+                        report.max_speed_kmh = report.max_speed_kmh.max(speed);
+                        report.wheel_speeds += 1;
+
+                        let healthy = report.coolant_temp_c.is_some_and(|t| t < max_coolant_temp)
+                            && report.battery_voltage_v.is_some_and(|v| v > min_voltage);
+                        match target_speed {
+                            None if speed > engage_above && healthy => {
+                                println!("engaging cruise control at {speed:.1} km/h");
+                                target_speed = Some(speed.round());
+                                report.engaged_at_kmh.get_or_insert(speed.round());
+                            }
+                            Some(_) if speed < disengage_below => {
+                                println!("disengaging cruise control at {speed:.1} km/h");
+                                target_speed = None;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                "tick" => {
+                    let command = vec![
+                        target_speed.unwrap_or(0.0),
+                        f64::from(u8::from(target_speed.is_some())),
+                    ];
+                    node.send_output(
+                        output.clone(),
+                        MetadataParameters::default(),
+                        signals_to_arrow(&["TargetSpeed", "Enable"], &[command]),
+                    )?;
+                    report.commands += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if MESSAGE_INPUTS.contains(&id.as_str()) {
+                    open_inputs -= 1;
+                    if open_inputs == 0 {
+                        break;
+                    }
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "max speed {:.1} km/h, engaged at {:?} km/h, sent {} commands",
+        report.max_speed_kmh, report.engaged_at_kmh, report.commands
+    );
+    write_json(&report_file, &report)
+}
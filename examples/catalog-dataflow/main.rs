@@ -0,0 +1,58 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, OptionExt, bail};
+use std::path::Path;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("catalog-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let catalog: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("out/catalog.json").context("catalog was not written")?,
+    )?;
+    let edges = catalog["edges"]
+        .as_array()
+        .ok_or_eyre("catalog has no edges")?;
+    println!(
+        "{:<22} {:<30} {:<20} {:>8} {:>8}",
+        "edge", "schema", "consumers", "msgs", "bytes/msg"
+    );
+    for edge in edges {
+        let schemas = edge["schemas"].as_array().ok_or_eyre("missing schemas")?;
+        let consumers: Vec<_> = edge["consumers"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|consumer| consumer.as_str())
+            .collect();
+        println!(
+            "{:<22} {:<30} {:<20} {:>8} {:>8.0}",
+            edge["id"].as_str().unwrap_or("?"),
+            schemas
+                .iter()
+                .filter_map(|schema| schema.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            consumers.join(", "),
+            edge["messages"],
+            edge["mean_bytes"].as_f64().unwrap_or_default()
+        );
+        if edge["messages"].as_u64().unwrap_or_default() == 0 || schemas.is_empty() {
+            bail!("edge {} was not observed", edge["id"]);
+        }
+    }
+    if edges.len() != 4 {
+        bail!("expected 4 edges in the catalog, got {}", edges.len());
+    }
+
+    Ok(())
+}
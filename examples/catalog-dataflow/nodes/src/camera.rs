@@ -0,0 +1,41 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter, arrow::array::UInt8Array, dora_core::config::DataId,
+};
+
+const WIDTH: usize = 64;
+const HEIGHT: usize = 48;
+
+fn main() -> eyre::Result<()> {
+    let messages: u64 = std::env::var("MESSAGES")
+        .ok()
+        .and_then(|messages| messages.parse().ok())
+        .unwrap_or(200);
+    let output = DataId::from("image".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } if id.as_str() == "tick" => {
+                let pixels: Vec<u8> = (0..WIDTH * HEIGHT * 3)
+                    .map(|i| ((i as u64 + sent) % 256) as u8)
+                    .collect();
+                let mut parameters = dora_node_api::MetadataParameters::default();
+                parameters.insert("width".into(), Parameter::Integer(WIDTH as i64));
+                parameters.insert("height".into(), Parameter::Integer(HEIGHT as i64));
+                parameters.insert("encoding".into(), Parameter::String("rgb8".into()));
+                node.send_output(output.clone(), parameters, UInt8Array::from(pixels))?;
+                sent += 1;
+                if sent >= messages {
+                    break;
+                }
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::Array};
+use example_node_utils::now_us;
+use eyre::{Context, OptionExt, eyre};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Input id of the timer that triggers writing the catalog.
+const FLUSH_INPUT: &str = "flush";
+/// Time window over which the message rate is computed.
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Everything known about one output of a node.
+#[derive(Debug, Default, Serialize)]
+struct Edge {
+    /// `<node>/<output>`
+    id: String,
+    producer: String,
+    output: String,
+    consumers: Vec<String>,
+    /// Every Arrow data type seen on this edge, in order of appearance. More than one entry
+    /// means that the producer changed its schema while running.
+    schemas: Vec<String>,
+    metadata_keys: BTreeSet<String>,
+    messages: u64,
+    bytes: u64,
+    rate_hz: f64,
+    mean_bytes: f64,
+    last_seen_us: Option<u64>,
+    #[serde(skip)]
+    recent: VecDeque<Instant>,
+}
+
+#[derive(Debug, Serialize)]
+struct Catalog<'a> {
+    dataflow: String,
+    generated_at_us: u64,
+    edges: Vec<&'a Edge>,
+}
+
+/// Observes all edges of the dataflow and keeps a JSON catalog of them up to date.
+///
+/// Producers and consumers are read from the dataflow YAML. Schemas, metadata keys, and rates
+/// are derived from the messages that the sidecar receives, so the catalog documents what is
+/// actually sent, not what was intended.
+fn main() -> eyre::Result<()> {
+    let dataflow_path = std::env::var("DATAFLOW").unwrap_or_else(|_| "dataflow.yml".to_owned());
+    let catalog_path =
+        PathBuf::from(std::env::var("CATALOG_FILE").unwrap_or_else(|_| "out/catalog.json".into()));
+
+    let (node, mut events) = DoraNode::init_from_env()?;
+    let own_id = node.id().to_string();
+
+    let dataflow: serde_yaml::Value = serde_yaml::from_str(
+        &std::fs::read_to_string(&dataflow_path)
+            .with_context(|| format!("failed to read {dataflow_path}"))?,
+    )
+    .context("failed to parse dataflow")?;
+    let (mut edges, observed) = edges_from_dataflow(&dataflow, &own_id)?;
+    // the flush timer never closes, so stop once all observed edges are closed
+    let mut open_inputs = observed.len();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                if id.as_str() == FLUSH_INPUT {
+                    write_catalog(&catalog_path, &dataflow_path, &mut edges)?;
+                    continue;
+                }
+                let edge_id = observed
+                    .get(id.as_str())
+                    .ok_or_else(|| eyre!("input `{id}` is not in the dataflow YAML"))?;
+                let edge = edges.get_mut(edge_id).ok_or_eyre("unknown edge")?;
+
+                let schema = data.data_type().to_string();
+                if !edge.schemas.contains(&schema) {
+                    if !edge.schemas.is_empty() {
+                        println!("schema of `{edge_id}` changed to {schema}");
+                    }
+                    edge.schemas.push(schema);
+                }
+                edge.metadata_keys
+                    .extend(metadata.parameters.keys().cloned());
+                edge.messages += 1;
+                edge.bytes += data.get_array_memory_size() as u64;
+                edge.last_seen_us = Some(now_us());
+                edge.recent.push_back(Instant::now());
+            }
+            Event::InputClosed { id } => {
+                if id.as_str() != FLUSH_INPUT {
+                    open_inputs -= 1;
+                    if open_inputs == 0 {
+                        break;
+                    }
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    write_catalog(&catalog_path, &dataflow_path, &mut edges)?;
+    println!(
+        "wrote catalog of {} edges to {}",
+        edges.len(),
+        catalog_path.display()
+    );
+    Ok(())
+}
+
+/// Returns all edges of the dataflow by id, and a map from the input ids of this node to the
+/// edges they observe.
+fn edges_from_dataflow(
+    dataflow: &serde_yaml::Value,
+    own_id: &str,
+) -> eyre::Result<(BTreeMap<String, Edge>, BTreeMap<String, String>)> {
+    let nodes = dataflow["nodes"]
+        .as_sequence()
+        .ok_or_eyre("dataflow has no `nodes` list")?;
+    let mut edges: BTreeMap<String, Edge> = BTreeMap::new();
+    let mut observed = BTreeMap::new();
+
+    for node in nodes {
+        let node_id = node["id"].as_str().ok_or_eyre("node without id")?;
+        if node_id == own_id {
+            continue;
+        }
+        for output in node["outputs"].as_sequence().into_iter().flatten() {
+            let output = output.as_str().ok_or_eyre("invalid output")?;
+            let id = format!("{node_id}/{output}");
+            edges.insert(
+                id.clone(),
+                Edge {
+                    id,
+                    producer: node_id.to_owned(),
+                    output: output.to_owned(),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    for node in nodes {
+        let node_id = node["id"].as_str().ok_or_eyre("node without id")?;
+        for (input_id, input) in node["inputs"].as_mapping().into_iter().flatten() {
+            // inputs are either `source` strings or maps with a `source` key
+            let source = input
+                .as_str()
+                .or_else(|| input["source"].as_str())
+                .ok_or_eyre("input without source")?;
+            if source.starts_with("dora/") {
+                continue;
+            }
+            if node_id == own_id {
+                let input_id = input_id.as_str().ok_or_eyre("invalid input id")?;
+                observed.insert(input_id.to_owned(), source.to_owned());
+            } else if let Some(edge) = edges.get_mut(source) {
+                edge.consumers.push(node_id.to_owned());
+            }
+        }
+    }
+
+    for edge in edges.keys() {
+        if !observed.values().any(|source| source == edge) {
+            eprintln!("edge `{edge}` is not observed, add it to the inputs of `{own_id}`");
+        }
+    }
+    Ok((edges, observed))
+}
+
+/// Updates the rates and replaces the catalog file.
+fn write_catalog(
+    path: &Path,
+    dataflow: &str,
+    edges: &mut BTreeMap<String, Edge>,
+) -> eyre::Result<()> {
+    let now = Instant::now();
+    for edge in edges.values_mut() {
+        while edge
+            .recent
+            .front()
+            .is_some_and(|time| now.duration_since(*time) > RATE_WINDOW)
+        {
+            edge.recent.pop_front();
+        }
+        edge.rate_hz = edge.recent.len() as f64 / RATE_WINDOW.as_secs_f64();
+        if edge.messages > 0 {
+            edge.mean_bytes = edge.bytes as f64 / edge.messages as f64;
+        }
+    }
+
+    let catalog = Catalog {
+        dataflow: dataflow.to_owned(),
+        generated_at_us: now_us(),
+        edges: edges.values().collect(),
+    };
+    // write to a temporary file first, so that readers never see a partial catalog
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(&catalog)?)?;
+    std::fs::rename(&tmp, path).context("failed to replace catalog file")
+}
@@ -0,0 +1,44 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{ArrayRef, Float32Array, StringArray, StructArray},
+        datatypes::{DataType, Field},
+    },
+    dora_core::config::DataId,
+};
+use std::sync::Arc;
+
+/// Pretends to detect objects and sends them as a struct array with one row per detection.
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("detections".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut frame = 0usize;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } if id.as_str() == "image" => {
+                let count = frame % 3;
+                let labels: Vec<&str> = ["person", "chair", "cup"][..count].to_vec();
+                let scores: Vec<f32> = (0..count).map(|i| 0.9 - i as f32 * 0.2).collect();
+                let detections = StructArray::from(vec![
+                    (
+                        Arc::new(Field::new("label", DataType::Utf8, false)),
+                        Arc::new(StringArray::from(labels)) as ArrayRef,
+                    ),
+                    (
+                        Arc::new(Field::new("score", DataType::Float32, false)),
+                        Arc::new(Float32Array::from(scores)) as ArrayRef,
+                    ),
+                ]);
+                node.send_output(output.clone(), Default::default(), detections)?;
+                frame += 1;
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter, arrow::array::Float64Array, dora_core::config::DataId,
+};
+
+fn main() -> eyre::Result<()> {
+    let messages: u64 = std::env::var("MESSAGES")
+        .ok()
+        .and_then(|messages| messages.parse().ok())
+        .unwrap_or(660);
+    let output = DataId::from("imu".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } if id.as_str() == "tick" => {
+                let t = sent as f64 * 0.01;
+                // [ax, ay, az, gx, gy, gz]
+                let values = vec![t.sin() * 0.1, t.cos() * 0.1, 9.81, 0.0, 0.0, t.sin() * 0.05];
+                let mut parameters = dora_node_api::MetadataParameters::default();
+                parameters.insert("frame_id".into(), Parameter::String("imu_link".into()));
+                node.send_output(output.clone(), parameters, Float64Array::from(values))?;
+                sent += 1;
+                if sent >= messages {
+                    break;
+                }
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, arrow::array::Array, dora_core::config::DataId,
+};
+
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("command".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "detections" => {
+                    let command = if !data.is_empty() {
+                        "slow_down"
+                    } else {
+                        "cruise"
+                    };
+                    node.send_output(output.clone(), Default::default(), command.into_arrow())?;
+                }
+                "imu" => {}
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// See `dataflow.yml`.
+const ITERATIONS: u64 = 50_000;
+const CHECKPOINT_DIR: &str = "checkpoints";
+const RESULT_FILE: &str = "out/result.json";
+const PID_FILE: &str = "out/solver.pid";
+/// The runner kills the solver once a checkpoint of this iteration exists.
+const KILL_AFTER: u64 = ITERATIONS / 2;
+
+/// Subset of `SolverResult` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SolverResult {
+    iterations: u64,
+    state_checksum: u64,
+    resumed_from: Option<u64>,
+    skipped_checkpoints: Vec<String>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("checkpointing-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // the runner needs to control the `dora` process, so run the binary directly
+    let checkout = Dora::from_env()?;
+    let dora = checkout.build_cli().await?;
+    let dataflow = Path::new("dataflow.yml");
+    checkout.build_dataflow(dataflow).await?;
+
+    tracing::info!("reference run without interruption");
+    clean()?;
+    run_to_completion(&dora, dataflow).await?;
+    let reference = read_result()?;
+    if reference.resumed_from.is_some() {
+        bail!("the reference run should start from scratch");
+    }
+    if Path::new(CHECKPOINT_DIR).exists() {
+        bail!("the solver did not remove its checkpoints after finishing");
+    }
+
+    tracing::info!("killing the solver after iteration {KILL_AFTER}");
+    clean()?;
+    let mut run = tokio::process::Command::new(&dora)
+        .arg("run")
+        .arg(dataflow)
+        .spawn()
+        .context("failed to spawn `dora run`")?;
+    wait_for_checkpoint(KILL_AFTER, Duration::from_secs(120)).await?;
+    kill_solver().await?;
+    // the dataflow fails because the solver was killed
+    tokio::time::timeout(Duration::from_secs(30), run.wait())
+        .await
+        .context("`dora run` did not exit after the solver was killed")??;
+    if Path::new(RESULT_FILE).exists() {
+        bail!("the solver finished before it was killed");
+    }
+
+    // the newest checkpoint has to be skipped, the one before it is used
+    let checkpoints = checkpoint_iterations()?;
+    let [(newest, newest_path), (expected_resume, _), ..] = checkpoints.as_slice() else {
+        bail!("expected at least two checkpoints, found {checkpoints:?}");
+    };
+    tracing::info!("corrupting the checkpoint of iteration {newest}");
+    let mut bytes = std::fs::read(newest_path)?;
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 0xff;
+    std::fs::write(newest_path, bytes)?;
+
+    tracing::info!("restarting the dataflow");
+    run_to_completion(&dora, dataflow).await?;
+    let resumed = read_result()?;
+
+    if resumed.resumed_from != Some(*expected_resume) {
+        bail!(
+            "expected the solver to resume from iteration {expected_resume}, it resumed from {:?}",
+            resumed.resumed_from
+        );
+    }
+    if resumed.skipped_checkpoints.len() != 1 {
+        bail!(
+            "expected the solver to skip the corrupted checkpoint, it skipped {:?}",
+            resumed.skipped_checkpoints
+        );
+    }
+    if resumed.iterations != reference.iterations
+        || resumed.state_checksum != reference.state_checksum
+    {
+        bail!(
+            "resumed result differs from the reference: {} iterations with checksum {:016x}, \
+             expected {} with {:016x}",
+            resumed.iterations,
+            resumed.state_checksum,
+            reference.iterations,
+            reference.state_checksum
+        );
+    }
+    if Path::new(CHECKPOINT_DIR).exists() {
+        bail!("the solver did not remove its checkpoints after finishing");
+    }
+    println!(
+        "resumed from iteration {expected_resume} after skipping the corrupted checkpoint of \
+         iteration {newest}, the result is identical to the uninterrupted run"
+    );
+
+    Ok(())
+}
+
+fn clean() -> eyre::Result<()> {
+    if Path::new(CHECKPOINT_DIR).exists() {
+        std::fs::remove_dir_all(CHECKPOINT_DIR)?;
+    }
+    for file in [RESULT_FILE, PID_FILE] {
+        if Path::new(file).exists() {
+            std::fs::remove_file(file)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_result() -> eyre::Result<SolverResult> {
+    let result = serde_json::from_str(
+        &std::fs::read_to_string(RESULT_FILE).context("solver did not write a result")?,
+    )?;
+    std::fs::remove_file(RESULT_FILE)?;
+    Ok(result)
+}
+
+/// Returns the iterations and paths of the checkpoints, newest first, like
+/// `list_checkpoints` in `nodes/src/lib.rs`.
+fn checkpoint_iterations() -> eyre::Result<Vec<(u64, PathBuf)>> {
+    if !Path::new(CHECKPOINT_DIR).exists() {
+        return Ok(Vec::new());
+    }
+    let mut checkpoints = Vec::new();
+    for entry in std::fs::read_dir(CHECKPOINT_DIR)? {
+        let path = entry?.path();
+        let iteration = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("ckpt-")?.strip_suffix(".bin"))
+            .and_then(|iteration| iteration.parse::<u64>().ok());
+        if let Some(iteration) = iteration {
+            checkpoints.push((iteration, path));
+        }
+    }
+    checkpoints.sort_by_key(|(iteration, _)| std::cmp::Reverse(*iteration));
+    Ok(checkpoints)
+}
+
+async fn wait_for_checkpoint(iteration: u64, timeout: Duration) -> eyre::Result<()> {
+    let start = std::time::Instant::now();
+    while start.elapsed() < timeout {
+        if checkpoint_iterations()?
+            .first()
+            .is_some_and(|(newest, _)| *newest >= iteration)
+        {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    bail!("no checkpoint of iteration {iteration} after {timeout:?}")
+}
+
+/// Kills the solver without giving it a chance to save its state, like a crash or a power
+/// loss would.
+async fn kill_solver() -> eyre::Result<()> {
+    let pid = std::fs::read_to_string(PID_FILE).context("solver did not write its pid")?;
+    let pid = pid.trim();
+    let mut kill = if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("taskkill");
+        cmd.args(["/F", "/PID", pid]);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("kill");
+        cmd.args(["-KILL", pid]);
+        cmd
+    };
+    if !kill.status().await?.success() {
+        bail!("failed to kill the solver (pid {pid})");
+    }
+    Ok(())
+}
+
+async fn run_to_completion(dora: &Path, dataflow: &Path) -> eyre::Result<()> {
+    let status = tokio::process::Command::new(dora)
+        .arg("run")
+        .arg(dataflow)
+        .status()
+        .await
+        .context("failed to spawn `dora run`")?;
+    if !status.success() {
+        bail!("`dora run` failed");
+    }
+    Ok(())
+}
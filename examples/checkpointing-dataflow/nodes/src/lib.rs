@@ -0,0 +1,176 @@
+use eyre::{Context, bail};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+pub use example_node_utils::env_or;
+
+const MAGIC: &[u8; 8] = b"DORACKPT";
+/// Increase when the layout changes, old checkpoints are then ignored instead of misread.
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 4 + 8 + 4;
+
+/// Temperatures on a square plate whose top edge is held at 100 degrees and the other edges
+/// at 0, relaxed towards the steady state with Jacobi iterations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    pub size: usize,
+    pub cells: Vec<f64>,
+}
+
+impl Grid {
+    pub fn new(size: usize) -> Self {
+        let mut cells = vec![0.0; size * size];
+        cells[..size].fill(100.0);
+        Self { size, cells }
+    }
+
+    /// Runs one iteration and returns the largest change of a cell.
+    ///
+    /// Only depends on the cells, so continuing from a checkpoint gives exactly the same
+    /// values as running without interruption.
+    pub fn step(&mut self, scratch: &mut Vec<f64>) -> f64 {
+        let n = self.size;
+        scratch.clone_from(&self.cells);
+        let mut residual: f64 = 0.0;
+        for row in 1..n - 1 {
+            for col in 1..n - 1 {
+                let i = row * n + col;
+                let value =
+                    0.25 * (scratch[i - n] + scratch[i + n] + scratch[i - 1] + scratch[i + 1]);
+                residual = residual.max((value - scratch[i]).abs());
+                self.cells[i] = value;
+            }
+        }
+        residual
+    }
+
+    /// Checksum of the exact bits of all cells.
+    pub fn checksum(&self) -> u64 {
+        fnv1a(self.cells.iter().flat_map(|cell| cell.to_le_bytes()))
+    }
+}
+
+/// The whole state of the solver after `iteration` iterations.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub iteration: u64,
+    pub grid: Grid,
+}
+
+impl Checkpoint {
+    /// Magic, version, iteration, grid size, all cells, and a checksum of everything before.
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.grid.cells.len() * 8 + 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.iteration.to_le_bytes());
+        bytes.extend_from_slice(&(self.grid.size as u32).to_le_bytes());
+        for cell in &self.grid.cells {
+            bytes.extend_from_slice(&cell.to_le_bytes());
+        }
+        let checksum = fnv1a(bytes.iter().copied());
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> eyre::Result<Self> {
+        let Some((content, checksum)) = bytes.split_last_chunk::<8>() else {
+            bail!("file too short");
+        };
+        if fnv1a(content.iter().copied()) != u64::from_le_bytes(*checksum) {
+            bail!("checksum mismatch");
+        }
+        if content.len() < HEADER_LEN || &content[..MAGIC.len()] != MAGIC {
+            bail!("not a checkpoint");
+        }
+        let (header, cells) = content.split_at(HEADER_LEN);
+        let version = u32::from_le_bytes(header[8..12].try_into()?);
+        if version != VERSION {
+            bail!("unsupported version {version}");
+        }
+        let iteration = u64::from_le_bytes(header[12..20].try_into()?);
+        let size = u32::from_le_bytes(header[20..24].try_into()?) as usize;
+        if cells.len() != size * size * 8 {
+            bail!("expected {size}x{size} cells");
+        }
+        let cells = cells
+            .chunks_exact(8)
+            .map(|cell| f64::from_le_bytes(cell.try_into().unwrap()))
+            .collect();
+        Ok(Self {
+            iteration,
+            grid: Grid { size, cells },
+        })
+    }
+}
+
+/// Checkpoints are named by their iteration, zero-padded so that they sort by name.
+pub fn checkpoint_path(dir: &Path, iteration: u64) -> PathBuf {
+    dir.join(format!("ckpt-{iteration:010}.bin"))
+}
+
+/// Returns the iterations and paths of all checkpoints in `dir`, newest first.
+pub fn list_checkpoints(dir: &Path) -> eyre::Result<Vec<(u64, PathBuf)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut checkpoints = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let iteration = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("ckpt-")?.strip_suffix(".bin"))
+            .and_then(|iteration| iteration.parse::<u64>().ok());
+        if let Some(iteration) = iteration {
+            checkpoints.push((iteration, path));
+        }
+    }
+    checkpoints.sort_by_key(|(iteration, _)| std::cmp::Reverse(*iteration));
+    Ok(checkpoints)
+}
+
+/// Writes the checkpoint so that a crash at any point leaves either the complete file or
+/// none at all: into a temporary file first, flushed to disk, then renamed.
+pub fn write_checkpoint(dir: &Path, checkpoint: &Checkpoint) -> eyre::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = checkpoint_path(dir, checkpoint.iteration);
+    let temp = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&temp)
+        .with_context(|| format!("failed to create {}", temp.display()))?;
+    file.write_all(&checkpoint.encode())?;
+    file.sync_all()?;
+    std::fs::rename(&temp, &path)?;
+    // the rename is only durable once the directory is flushed, too
+    #[cfg(unix)]
+    std::fs::File::open(dir)?.sync_all()?;
+    Ok(path)
+}
+
+pub fn read_checkpoint(path: &Path) -> eyre::Result<Checkpoint> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Checkpoint::decode(&bytes).with_context(|| format!("invalid checkpoint {}", path.display()))
+}
+
+/// Written by the solver when it has finished all iterations.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SolverResult {
+    pub iterations: u64,
+    pub residual: f64,
+    pub state_checksum: u64,
+    /// Iteration of the checkpoint that the solver continued from, if any.
+    pub resumed_from: Option<u64>,
+    /// Checkpoints that were found but could not be used.
+    pub skipped_checkpoints: Vec<String>,
+}
+
+/// 64-bit FNV-1a, enough to detect corruption. It does not protect against tampering.
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
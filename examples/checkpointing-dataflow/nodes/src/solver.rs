@@ -0,0 +1,146 @@
+use checkpointing_dataflow_nodes::{
+    Checkpoint, Grid, SolverResult, env_or, list_checkpoints, read_checkpoint, write_checkpoint,
+};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, arrow::array::UInt64Array, dora_core::config::DataId,
+};
+use std::path::{Path, PathBuf};
+
+/// Runs `ITERATIONS` Jacobi iterations on a `GRID_SIZE` square grid, `ITERATIONS_PER_TICK` on
+/// every tick, and sends the iteration count on `progress`.
+///
+/// Every `CHECKPOINT_EVERY` iterations, and when it is stopped, the solver writes its state to
+/// `CHECKPOINT_DIR` and keeps the newest `KEEP_CHECKPOINTS` of them. On start, it continues
+/// from the newest valid checkpoint. When all iterations are done, it writes the result to
+/// `RESULT_FILE` and removes the checkpoints.
+fn main() -> eyre::Result<()> {
+    let size: usize = env_or("GRID_SIZE", 128)?;
+    let iterations: u64 = env_or("ITERATIONS", 50_000)?;
+    let per_tick: u64 = env_or("ITERATIONS_PER_TICK", 100)?;
+    let checkpoint_every: u64 = env_or("CHECKPOINT_EVERY", 5000)?;
+    let keep: usize = env_or("KEEP_CHECKPOINTS", 2)?;
+    let checkpoint_dir: PathBuf = env_or("CHECKPOINT_DIR", "checkpoints".to_owned())?.into();
+    let result_file: PathBuf = env_or("RESULT_FILE", "out/result.json".to_owned())?.into();
+    let output = DataId::from("progress".to_owned());
+
+    // lets the runner kill this process in the middle of the computation
+    if let Ok(pid_file) = std::env::var("PID_FILE") {
+        if let Some(parent) = Path::new(&pid_file).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(pid_file, std::process::id().to_string())?;
+    }
+
+    let mut skipped_checkpoints = Vec::new();
+    let mut state = None;
+    for (_, path) in list_checkpoints(&checkpoint_dir)? {
+        match read_checkpoint(&path) {
+            Ok(checkpoint) if checkpoint.grid.size == size => {
+                state = Some(checkpoint);
+                break;
+            }
+            Ok(checkpoint) => skipped_checkpoints.push(format!(
+                "{}: grid size {} instead of {size}",
+                path.display(),
+                checkpoint.grid.size
+            )),
+            Err(err) => skipped_checkpoints.push(format!("{err:#}")),
+        }
+    }
+    for skipped in &skipped_checkpoints {
+        eprintln!("skipping checkpoint: {skipped}");
+    }
+    let resumed_from = state.as_ref().map(|checkpoint| checkpoint.iteration);
+    let Checkpoint {
+        mut iteration,
+        mut grid,
+    } = state.unwrap_or_else(|| Checkpoint {
+        iteration: 0,
+        grid: Grid::new(size),
+    });
+    match resumed_from {
+        Some(resumed_from) => println!("resuming from iteration {resumed_from}"),
+        None => println!("starting from scratch"),
+    }
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let save = |iteration: u64, grid: &Grid| -> eyre::Result<()> {
+        let checkpoint = Checkpoint {
+            iteration,
+            grid: grid.clone(),
+        };
+        write_checkpoint(&checkpoint_dir, &checkpoint)?;
+        // only remove old checkpoints once the new one is safely written
+        for (_, old) in list_checkpoints(&checkpoint_dir)?.iter().skip(keep) {
+            std::fs::remove_file(old)?;
+        }
+        Ok(())
+    };
+
+    let mut scratch = Vec::new();
+    let mut residual = f64::INFINITY;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    let until = (iteration + per_tick).min(iterations);
+                    while iteration < until {
+                        residual = grid.step(&mut scratch);
+                        iteration += 1;
+                        // between iterations, the grid is consistent
+                        if iteration % checkpoint_every == 0 && iteration < iterations {
+                            save(iteration, &grid)?;
+                        }
+                    }
+                    node.send_output(
+                        output.clone(),
+                        MetadataParameters::default(),
+                        UInt64Array::from(vec![iteration]),
+                    )?;
+                    if iteration == iterations {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => {
+                if iteration < iterations {
+                    save(iteration, &grid)?;
+                    println!("stopped at iteration {iteration}, saved a checkpoint");
+                }
+                return Ok(());
+            }
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    if iteration < iterations {
+        save(iteration, &grid)?;
+        return Ok(());
+    }
+
+    let result = SolverResult {
+        iterations: iteration,
+        residual,
+        state_checksum: grid.checksum(),
+        resumed_from,
+        skipped_checkpoints,
+    };
+    if let Some(parent) = result_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&result_file, serde_json::to_string_pretty(&result)?)?;
+    // the checkpoints are only needed until the result is safely written
+    if checkpoint_dir.exists() {
+        std::fs::remove_dir_all(&checkpoint_dir)?;
+    }
+    println!(
+        "finished {iteration} iterations, residual {residual:.3e}, checksum {:016x}",
+        result.state_checksum
+    );
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use chunking_dataflow_nodes::{chunk_parameters, chunk_ranges, env_or, row_sizes};
+use dora_node_api::{self, DoraNode, Event, arrow::array::Array, dora_core::config::DataId};
+
+/// Splits every input into chunks of at most `MAX_CHUNK_BYTES`.
+///
+/// Chunks are zero-copy slices of the input. Their metadata describes where they belong,
+/// so that consumers can process them in order or reassemble the original batch.
+fn main() -> eyre::Result<()> {
+    let max_chunk_bytes: usize = env_or("MAX_CHUNK_BYTES", 64 * 1024)?;
+    let output = DataId::from("chunks".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut batch_seq = 0;
+    let mut chunks = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id: _,
+                metadata,
+                data,
+            } => {
+                let sizes = row_sizes(&data.0)?;
+                let mut ranges = chunk_ranges(&sizes, max_chunk_bytes)?;
+                if ranges.is_empty() {
+                    // forward empty batches too, consumers may rely on the batch sequence
+                    ranges.push((0, 0));
+                }
+                if ranges.len() > 1 {
+                    println!(
+                        "splitting batch {batch_seq} ({} rows, {} bytes) into {} chunks",
+                        data.len(),
+                        sizes.iter().sum::<usize>(),
+                        ranges.len()
+                    );
+                }
+
+                for (index, &(offset, len)) in ranges.iter().enumerate() {
+                    let mut parameters =
+                        chunk_parameters(batch_seq, data.len(), index, ranges.len(), offset);
+                    // keep the parameters of the producer, e.g. its timestamps
+                    for (key, value) in &metadata.parameters {
+                        parameters
+                            .entry(key.clone())
+                            .or_insert_with(|| value.clone());
+                    }
+                    node.send_output(output.clone(), parameters, data.slice(offset, len))?;
+                    chunks += 1;
+                }
+                batch_seq += 1;
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("split {batch_seq} batches into {chunks} chunks");
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use chunking_dataflow_nodes::{
+    BATCH_ROWS_KEY, BATCH_SEQ_KEY, CHUNK_COUNT_KEY, CHUNK_INDEX_KEY, ROW_OFFSET_KEY, env_or,
+    integer_parameter, payload_bytes,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{Array, AsArray},
+        datatypes::{Float64Type, UInt64Type},
+    },
+};
+use eyre::{OptionExt, bail};
+
+/// Aggregates batches chunk by chunk, like a consumer on a device with little memory.
+///
+/// Fails if a chunk exceeds `MEMORY_LIMIT_BYTES` or arrives out of order.
+fn main() -> eyre::Result<()> {
+    let memory_limit: usize = env_or("MEMORY_LIMIT_BYTES", 64 * 1024)?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut expected_batch = 0;
+    let mut expected_chunk = 0;
+    let mut batch_rows_received = 0;
+    let mut batch_sum = 0.0;
+    let mut next_seq = 0;
+    let mut chunks = 0;
+    let mut largest_chunk = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id: _,
+                metadata,
+                data,
+            } => {
+                let bytes = payload_bytes(&data.0)?;
+                if bytes > memory_limit {
+                    bail!("received a chunk of {bytes} bytes, the limit is {memory_limit}");
+                }
+                largest_chunk = largest_chunk.max(bytes);
+                chunks += 1;
+
+                // chunks must arrive in order: batch by batch, chunk by chunk
+                let parameters = &metadata.parameters;
+                let batch_seq = integer_parameter(parameters, BATCH_SEQ_KEY)?;
+                let chunk_index = integer_parameter(parameters, CHUNK_INDEX_KEY)?;
+                let chunk_count = integer_parameter(parameters, CHUNK_COUNT_KEY)?;
+                let row_offset = integer_parameter(parameters, ROW_OFFSET_KEY)?;
+                if (batch_seq, chunk_index) != (expected_batch, expected_chunk) {
+                    bail!(
+                        "expected chunk {expected_chunk} of batch {expected_batch}, \
+                         got chunk {chunk_index} of batch {batch_seq}"
+                    );
+                }
+                if row_offset != batch_rows_received {
+                    bail!(
+                        "chunk {chunk_index} of batch {batch_seq} starts at row {row_offset}, \
+                         expected {batch_rows_received}"
+                    );
+                }
+
+                let records = data
+                    .as_struct_opt()
+                    .ok_or_eyre("expected a struct array of records")?;
+                let seqs = records
+                    .column_by_name("seq")
+                    .and_then(|column| column.as_primitive_opt::<UInt64Type>())
+                    .ok_or_eyre("missing `seq` column")?;
+                for seq in seqs.values() {
+                    if *seq != next_seq {
+                        bail!("expected row {next_seq}, got {seq}");
+                    }
+                    next_seq += 1;
+                }
+                let values = records
+                    .column_by_name("value")
+                    .and_then(|column| column.as_primitive_opt::<Float64Type>())
+                    .ok_or_eyre("missing `value` column")?;
+                batch_sum += values.values().iter().sum::<f64>();
+                batch_rows_received += records.len() as u64;
+
+                if chunk_index + 1 < chunk_count {
+                    expected_chunk += 1;
+                    continue;
+                }
+                let batch_rows = integer_parameter(parameters, BATCH_ROWS_KEY)?;
+                if batch_rows_received != batch_rows {
+                    bail!(
+                        "batch {batch_seq} has {batch_rows} rows, received {batch_rows_received}"
+                    );
+                }
+                if chunk_count > 1 {
+                    println!(
+                        "batch {batch_seq}: {batch_rows} rows in {chunk_count} chunks, mean {:.4}",
+                        batch_sum / batch_rows as f64
+                    );
+                }
+                expected_batch += 1;
+                expected_chunk = 0;
+                batch_rows_received = 0;
+                batch_sum = 0.0;
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if expected_chunk != 0 {
+        bail!("batch {expected_batch} is incomplete");
+    }
+    println!(
+        "received {expected_batch} batches in {chunks} chunks, largest chunk {largest_chunk} \
+         bytes (limit {memory_limit})"
+    );
+    Ok(())
+}
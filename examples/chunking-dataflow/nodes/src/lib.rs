@@ -0,0 +1,126 @@
+use dora_node_api::{
+    MetadataParameters, Parameter,
+    arrow::{
+        array::{Array, AsArray},
+        datatypes::DataType,
+    },
+};
+use eyre::{bail, eyre};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the position of the original batch in the stream.
+pub const BATCH_SEQ_KEY: &str = "batch_seq";
+/// Metadata key of the number of rows of the original batch.
+pub const BATCH_ROWS_KEY: &str = "batch_rows";
+/// Metadata key of the position of the chunk within its batch, starting at 0.
+pub const CHUNK_INDEX_KEY: &str = "chunk_index";
+/// Metadata key of the number of chunks that the batch was split into.
+pub const CHUNK_COUNT_KEY: &str = "chunk_count";
+/// Metadata key of the index of the first row of the chunk within its batch.
+pub const ROW_OFFSET_KEY: &str = "row_offset";
+
+/// Returns the payload size of every row of `array`.
+///
+/// The payload is the bytes of the values, plus the offsets of variable-length values.
+/// Validity bitmaps are ignored, they add at most one bit per value and column.
+pub fn row_sizes(array: &dyn Array) -> eyre::Result<Vec<usize>> {
+    let sizes = match array.data_type() {
+        DataType::Struct(_) => {
+            let mut sizes = vec![0; array.len()];
+            for column in array.as_struct().columns() {
+                for (size, column_size) in sizes.iter_mut().zip(row_sizes(column)?) {
+                    *size += column_size;
+                }
+            }
+            sizes
+        }
+        DataType::Utf8 => {
+            let offsets = array.as_string::<i32>().value_offsets();
+            offsets
+                .windows(2)
+                .map(|w| (w[1] - w[0]) as usize + 4)
+                .collect()
+        }
+        DataType::LargeUtf8 => {
+            let offsets = array.as_string::<i64>().value_offsets();
+            offsets
+                .windows(2)
+                .map(|w| (w[1] - w[0]) as usize + 8)
+                .collect()
+        }
+        DataType::Binary => {
+            let offsets = array.as_binary::<i32>().value_offsets();
+            offsets
+                .windows(2)
+                .map(|w| (w[1] - w[0]) as usize + 4)
+                .collect()
+        }
+        DataType::Boolean => vec![1; array.len()],
+        other => match other.primitive_width() {
+            Some(width) => vec![width; array.len()],
+            None => bail!("row sizes of `{other}` arrays are not supported"),
+        },
+    };
+    Ok(sizes)
+}
+
+/// Total payload size of `array`, see [`row_sizes`].
+pub fn payload_bytes(array: &dyn Array) -> eyre::Result<usize> {
+    Ok(row_sizes(array)?.iter().sum())
+}
+
+/// Splits rows with the given sizes into consecutive ranges of at most `max_bytes`.
+///
+/// Returns `(offset, len)` pairs. Fails if a single row is larger than `max_bytes`, since
+/// rows can't be split.
+pub fn chunk_ranges(row_sizes: &[usize], max_bytes: usize) -> eyre::Result<Vec<(usize, usize)>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut bytes = 0;
+    for (row, &size) in row_sizes.iter().enumerate() {
+        if size > max_bytes {
+            bail!("row {row} has {size} bytes, which exceeds the chunk budget of {max_bytes}");
+        }
+        if bytes + size > max_bytes {
+            ranges.push((start, row - start));
+            start = row;
+            bytes = 0;
+        }
+        bytes += size;
+    }
+    if start < row_sizes.len() {
+        ranges.push((start, row_sizes.len() - start));
+    }
+    Ok(ranges)
+}
+
+pub fn chunk_parameters(
+    batch_seq: u64,
+    batch_rows: usize,
+    chunk_index: usize,
+    chunk_count: usize,
+    row_offset: usize,
+) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    for (key, value) in [
+        (BATCH_SEQ_KEY, batch_seq as i64),
+        (BATCH_ROWS_KEY, batch_rows as i64),
+        (CHUNK_INDEX_KEY, chunk_index as i64),
+        (CHUNK_COUNT_KEY, chunk_count as i64),
+        (ROW_OFFSET_KEY, row_offset as i64),
+    ] {
+        parameters.insert(key.into(), Parameter::Integer(value));
+    }
+    parameters
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<u64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => {
+            u64::try_from(*value).map_err(|_| eyre!("negative `{key}` parameter"))
+        }
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
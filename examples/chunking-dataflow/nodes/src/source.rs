@@ -0,0 +1,79 @@
+use chunking_dataflow_nodes::env_or;
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{ArrayRef, Float64Array, StringArray, StructArray, UInt64Array},
+        datatypes::{DataType, Field},
+    },
+    dora_core::config::DataId,
+};
+use std::sync::Arc;
+
+/// Sends record batches of mostly small, but occasionally very large, size.
+fn main() -> eyre::Result<()> {
+    let batches: u64 = env_or("BATCHES", 200)?;
+    let small_rows: u64 = env_or("SMALL_ROWS", 200)?;
+    let large_rows: u64 = env_or("LARGE_ROWS", 20_000)?;
+    let large_every: u64 = env_or("LARGE_EVERY", 20)?;
+    let output = DataId::from("records".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut batch = 0;
+    let mut next_seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    // e.g. a point cloud or a log dump that arrives in one piece
+                    let rows = if batch % large_every == large_every - 1 {
+                        large_rows
+                    } else {
+                        small_rows / 2 + batch * 7 % small_rows
+                    };
+                    node.send_output(output.clone(), Default::default(), records(next_seq, rows))?;
+                    next_seq += rows;
+                    batch += 1;
+                    if batch >= batches {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {batch} batches with {next_seq} rows");
+    Ok(())
+}
+
+fn records(first_seq: u64, rows: u64) -> StructArray {
+    let seqs = first_seq..first_seq + rows;
+    let seq = UInt64Array::from_iter_values(seqs.clone());
+    // labels of different lengths, so that rows have different sizes
+    let label = StringArray::from_iter_values(
+        seqs.clone()
+            .map(|seq| format!("sensor-{}/{}", seq % 7, "x".repeat((seq % 40) as usize))),
+    );
+    let value = Float64Array::from_iter_values(seqs.map(|seq| (seq as f64 * 0.01).sin()));
+    StructArray::from(vec![
+        (
+            Arc::new(Field::new("seq", DataType::UInt64, false)),
+            Arc::new(seq) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("label", DataType::Utf8, false)),
+            Arc::new(label) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("value", DataType::Float64, false)),
+            Arc::new(value) as ArrayRef,
+        ),
+    ])
+}
@@ -0,0 +1,135 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path};
+
+/// Drift of the IMU clock, see `dataflow.yml`.
+const IMU_DRIFT_PPM: f64 = 120.0;
+const MAX_DRIFT_ERROR_PPM: f64 = 20.0;
+/// Largest acceptable 95th percentile of the conversion error per source.
+const MAX_P95_ERROR_US: [(&str, u64); 3] = [("camera", 0), ("gnss", 100), ("imu", 1000)];
+/// Share of messages whose error must be within their reported uncertainty.
+const MIN_COVERAGE: f64 = 0.9;
+
+/// Subset of `AlignedRecord` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct AlignedRecord {
+    source: String,
+    truth_mono_us: i64,
+    mapped_mono_us: i64,
+    uncertainty_us: i64,
+    received_mono_us: i64,
+    calibrated: bool,
+}
+
+/// Subset of `Summary` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct Summary {
+    utc_steps: u64,
+    imu_drift_ppm: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct SourceStats {
+    errors_us: Vec<u64>,
+    naive_errors_us: Vec<u64>,
+    covered: usize,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("clock-domains-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let summary: Summary = serde_json::from_str(
+        &std::fs::read_to_string("out/summary.json").context("timeline did not write a summary")?,
+    )?;
+    let mut stats: BTreeMap<String, SourceStats> = BTreeMap::new();
+    for line in std::fs::read_to_string("out/aligned.jsonl")
+        .context("timeline did not write the aligned messages")?
+        .lines()
+    {
+        let record: AlignedRecord = serde_json::from_str(line)?;
+        if !record.calibrated {
+            continue;
+        }
+        let stats = stats.entry(record.source).or_default();
+        let error = record.mapped_mono_us.abs_diff(record.truth_mono_us);
+        stats.errors_us.push(error);
+        stats
+            .naive_errors_us
+            .push(record.received_mono_us.abs_diff(record.truth_mono_us));
+        if error <= record.uncertainty_us as u64 {
+            stats.covered += 1;
+        }
+    }
+
+    println!(
+        "{:<8} {:>8} {:>12} {:>12} {:>12} {:>10}",
+        "source", "messages", "p50 err us", "p95 err us", "naive p50", "covered"
+    );
+    for (source, stats) in &mut stats {
+        stats.errors_us.sort();
+        stats.naive_errors_us.sort();
+        println!(
+            "{source:<8} {:>8} {:>12} {:>12} {:>12} {:>9.1}%",
+            stats.errors_us.len(),
+            percentile(&stats.errors_us, 0.5),
+            percentile(&stats.errors_us, 0.95),
+            percentile(&stats.naive_errors_us, 0.5),
+            coverage(stats) * 100.0
+        );
+    }
+
+    for (source, max_p95) in MAX_P95_ERROR_US {
+        let Some(stats) = stats.get(source) else {
+            bail!("no calibrated {source} messages");
+        };
+        let p95 = percentile(&stats.errors_us, 0.95);
+        if p95 > max_p95 {
+            bail!("{source}: 95% of the errors are within {p95} us, expected {max_p95} us");
+        }
+        if coverage(stats) < MIN_COVERAGE {
+            bail!(
+                "{source}: only {:.1}% of the errors are within the reported uncertainty",
+                coverage(stats) * 100.0
+            );
+        }
+    }
+    let imu = &stats["imu"];
+    if percentile(&imu.errors_us, 0.5) * 2 > percentile(&imu.naive_errors_us, 0.5) {
+        bail!("converting the IMU stamps is not better than using the arrival time");
+    }
+    if summary.utc_steps != 1 {
+        bail!("expected one UTC step, detected {}", summary.utc_steps);
+    }
+    match summary.imu_drift_ppm {
+        Some(drift) if (drift - IMU_DRIFT_PPM).abs() <= MAX_DRIFT_ERROR_PPM => {
+            println!("estimated IMU clock drift: {drift:.1} ppm (actual {IMU_DRIFT_PPM} ppm)");
+        }
+        other => bail!("expected an IMU clock drift of {IMU_DRIFT_PPM} ppm, estimated {other:?}"),
+    }
+
+    Ok(())
+}
+
+fn percentile(sorted: &[u64], quantile: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[((sorted.len() - 1) as f64 * quantile).round() as usize]
+}
+
+fn coverage(stats: &SourceStats) -> f64 {
+    stats.covered as f64 / stats.errors_us.len().max(1) as f64
+}
@@ -0,0 +1,49 @@
+use clock_domains_dataflow_nodes::{STAMP_KEY, TRUTH_KEY, env_or, monotonic_us};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::Float64Array,
+    dora_core::config::DataId,
+};
+use std::time::{Duration, Instant};
+
+/// Sends a frame on every tick, stamped with the host monotonic clock, like a V4L2 camera.
+///
+/// The stamp is already on the common timeline, so it serves as the reference for the other
+/// sources. Stops after `DURATION_MS`.
+fn main() -> eyre::Result<()> {
+    let duration = Duration::from_millis(env_or("DURATION_MS", 10_000)?);
+    let output = DataId::from("frame".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    let mut frames = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    let stamp = monotonic_us();
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(STAMP_KEY.into(), Parameter::Integer(stamp));
+                    parameters.insert(TRUTH_KEY.into(), Parameter::Integer(stamp));
+                    // mean brightness, stands in for the image
+                    let brightness = Float64Array::from(vec![0.5]);
+                    node.send_output(output.clone(), parameters, brightness)?;
+                    frames += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {frames} frames");
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use clock_domains_dataflow_nodes::{
+    HALF_WIDTH_KEY, KIND_FIX, KIND_KEY, KIND_SYNC, MONO_KEY, STAMP_KEY, TRUTH_KEY, env_or,
+    sample_clock, utc_us,
+};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::Float64Array,
+    dora_core::config::DataId,
+};
+use std::time::{Duration, Instant};
+
+/// Sends position fixes stamped with UTC on `tick`, like a GNSS receiver, and a sample of
+/// the UTC and monotonic clocks on `sync`.
+///
+/// Both go out on the same `utc` output, so that the timeline node sees every fix between
+/// the clock samples that were taken before and after it. After `UTC_STEP_AT_MS`, the UTC
+/// clock jumps by `UTC_STEP_MS`, like a system clock that NTP steps instead of slewing.
+/// Stops after `DURATION_MS`.
+fn main() -> eyre::Result<()> {
+    let duration = Duration::from_millis(env_or("DURATION_MS", 10_000)?);
+    let step_at = Duration::from_millis(env_or("UTC_STEP_AT_MS", 5000)?);
+    let step_us = env_or::<i64>("UTC_STEP_MS", -300)? * 1000;
+    let output = DataId::from("utc".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    let utc = || {
+        if start.elapsed() >= step_at {
+            utc_us() + step_us
+        } else {
+            utc_us()
+        }
+    };
+    let send_sync = |node: &mut DoraNode| {
+        let (stamp, mono, half_width) = sample_clock(utc);
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(KIND_KEY.into(), Parameter::String(KIND_SYNC.into()));
+        parameters.insert(STAMP_KEY.into(), Parameter::Integer(stamp));
+        parameters.insert(MONO_KEY.into(), Parameter::Integer(mono));
+        parameters.insert(HALF_WIDTH_KEY.into(), Parameter::Integer(half_width));
+        node.send_output(
+            output.clone(),
+            parameters,
+            Float64Array::from(Vec::<f64>::new()),
+        )
+    };
+
+    // a fix can only be converted once a clock sample before it exists
+    send_sync(&mut node)?;
+    let mut fixes = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    let (stamp, truth, _) = sample_clock(utc);
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(KIND_KEY.into(), Parameter::String(KIND_FIX.into()));
+                    parameters.insert(STAMP_KEY.into(), Parameter::Integer(stamp));
+                    parameters.insert(TRUTH_KEY.into(), Parameter::Integer(truth));
+                    // latitude and longitude
+                    let position = Float64Array::from(vec![48.137, 11.575]);
+                    node.send_output(output.clone(), parameters, position)?;
+                    fixes += 1;
+                }
+                "sync" => send_sync(&mut node)?,
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    // a last sample, so that the timeline node can convert the last fixes
+    send_sync(&mut node)?;
+
+    println!("sent {fixes} fixes");
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use clock_domains_dataflow_nodes::{STAMP_KEY, TRUTH_KEY, env_or, monotonic_us};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::Float64Array,
+    dora_core::config::DataId,
+};
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+/// Sends a sample on every tick, stamped with the free-running clock of the sensor, like an
+/// IMU on a serial or CAN bus.
+///
+/// The sensor clock counts at a nominal 1 MHz, from an arbitrary start value, and runs
+/// `DRIFT_PPM` fast. Each sample was taken `MIN_DELAY_US` plus a random, exponentially
+/// distributed `JITTER_US` before the node sends it, the transport delay of the bus and the
+/// driver. Stops after `DURATION_MS`.
+fn main() -> eyre::Result<()> {
+    let duration = Duration::from_millis(env_or("DURATION_MS", 10_000)?);
+    let drift_ppm: f64 = env_or("DRIFT_PPM", 120.0)?;
+    let min_delay_us: f64 = env_or("MIN_DELAY_US", 1000.0)?;
+    let jitter_us: f64 = env_or("JITTER_US", 500.0)?;
+    let output = DataId::from("sample".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut rng = rand::thread_rng();
+    let clock_start = monotonic_us();
+    let tick_offset: i64 = rng.gen_range(0..1 << 31);
+    let sensor_ticks = |mono_us: i64| {
+        tick_offset + ((mono_us - clock_start) as f64 * (1.0 + drift_ppm * 1e-6)) as i64
+    };
+
+    let start = Instant::now();
+    let mut samples = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    let delay = min_delay_us - jitter_us * rng.gen_range(f64::EPSILON..1.0).ln();
+                    let taken_at = monotonic_us() - delay as i64;
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(STAMP_KEY.into(), Parameter::Integer(sensor_ticks(taken_at)));
+                    parameters.insert(TRUTH_KEY.into(), Parameter::Integer(taken_at));
+                    // angular rate around x, y, z
+                    let rates = Float64Array::from(vec![0.01, -0.02, 0.3]);
+                    node.send_output(output.clone(), parameters, rates)?;
+                    samples += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {samples} samples");
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{Context, bail};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the timestamp of a message, in the clock domain of its source.
+pub const STAMP_KEY: &str = "stamp";
+/// Metadata key of the host monotonic time at which the message was actually taken.
+///
+/// This is ground truth that only a simulation has. The timeline node never uses it for the
+/// conversion, it only records it so that the runner can measure the error.
+pub const TRUTH_KEY: &str = "truth_mono_us";
+/// Metadata key that tells apart fixes and clock samples on the `utc` output of the GNSS node.
+pub const KIND_KEY: &str = "kind";
+pub const KIND_FIX: &str = "fix";
+pub const KIND_SYNC: &str = "sync";
+/// Metadata keys of a clock sample: the monotonic time that corresponds to `stamp`, and half
+/// of the time it took to read both clocks.
+pub const MONO_KEY: &str = "mono_us";
+pub const HALF_WIDTH_KEY: &str = "half_width_us";
+/// Metadata keys of the `aligned` output of the timeline node.
+pub const SOURCE_KEY: &str = "source";
+pub const UNCERTAINTY_KEY: &str = "uncertainty_us";
+
+/// Host monotonic clock in microseconds, the common timeline of this example.
+///
+/// Unlike `Instant`, `CLOCK_MONOTONIC` has the same epoch in every process of the host, and
+/// unlike UTC it never jumps.
+// `time_t` and `c_long` are only `i64` on 64-bit targets, the casts are needed on the others
+#[cfg_attr(target_pointer_width = "64", allow(clippy::unnecessary_cast))]
+pub fn monotonic_us() -> i64 {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid `timespec` and `CLOCK_MONOTONIC` is always available
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time) };
+    time.tv_sec as i64 * 1_000_000 + time.tv_nsec as i64 / 1000
+}
+
+pub fn utc_us() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as i64)
+        .unwrap_or_default()
+}
+
+/// Reads `clock` between two reads of the monotonic clock.
+///
+/// Returns the reading, the monotonic time in the middle, and half of the time between the
+/// monotonic reads, which bounds the error of the pair.
+pub fn sample_clock(clock: impl FnOnce() -> i64) -> (i64, i64, i64) {
+    let before = monotonic_us();
+    let reading = clock();
+    let after = monotonic_us();
+    (reading, (before + after) / 2, (after - before + 1) / 2)
+}
+
+/// One line of `out/aligned.jsonl`, written by the timeline node for every message.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlignedRecord {
+    pub source: String,
+    pub truth_mono_us: i64,
+    pub mapped_mono_us: i64,
+    pub uncertainty_us: i64,
+    /// When the message arrived at the timeline node, the naive timestamp.
+    pub received_mono_us: i64,
+    /// `false` while a clock model still has too few samples.
+    pub calibrated: bool,
+}
+
+/// Written by the timeline node when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Summary {
+    pub utc_steps: u64,
+    pub imu_drift_ppm: Option<f64>,
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
+
+pub fn string_parameter<'a>(
+    parameters: &'a MetadataParameters,
+    key: &str,
+) -> eyre::Result<&'a str> {
+    match parameters.get(key) {
+        Some(Parameter::String(value)) => Ok(value),
+        Some(other) => bail!("expected string `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
+
+pub fn create_jsonl(path: &Path) -> eyre::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))
+}
+
+pub fn write_jsonl<T: Serialize>(file: &mut std::fs::File, record: &T) -> eyre::Result<()> {
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
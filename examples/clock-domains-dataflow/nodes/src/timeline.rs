@@ -0,0 +1,311 @@
+use clock_domains_dataflow_nodes::{
+    AlignedRecord, HALF_WIDTH_KEY, KIND_FIX, KIND_KEY, KIND_SYNC, MONO_KEY, SOURCE_KEY, STAMP_KEY,
+    Summary, TRUTH_KEY, UNCERTAINTY_KEY, create_jsonl, env_or, integer_parameter, monotonic_us,
+    string_parameter, write_jsonl,
+};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::ArrayRef,
+    dora_core::config::DataId,
+};
+use std::{collections::VecDeque, path::PathBuf};
+
+/// Converts the stamps of all sources to the host monotonic clock and sends the messages on
+/// `aligned`, with `mono_us` and `uncertainty_us` in the metadata.
+///
+/// - `camera` stamps are monotonic already.
+/// - `utc` stamps are converted with the clock samples that the GNSS node sends around them.
+/// - `imu` stamps are converted with a linear model of the sensor clock, fitted to the times
+///   at which the samples arrive.
+///
+/// Writes every converted message to `ALIGNED_FILE` and a summary to `SUMMARY_FILE`.
+fn main() -> eyre::Result<()> {
+    let aligned_file: PathBuf = env_or("ALIGNED_FILE", "out/aligned.jsonl".to_owned())?.into();
+    let summary_file: PathBuf = env_or("SUMMARY_FILE", "out/summary.json".to_owned())?.into();
+    let resolution_us: i64 = env_or("RESOLUTION_US", 2)?;
+    let mut utc = UtcConversion {
+        previous: None,
+        pending: Vec::new(),
+        step_tolerance_us: env_or("STEP_TOLERANCE_US", 1000)?,
+        resolution_us,
+        steps: 0,
+    };
+    let mut imu = SensorClockFit {
+        samples: VecDeque::new(),
+        window: env_or("WINDOW", 1000)?,
+        min_samples: env_or("MIN_SAMPLES", 100)?,
+        min_delay_us: env_or("MIN_DELAY_US", 1000.0)?,
+        delay_uncertainty_us: env_or("DELAY_UNCERTAINTY_US", 300.0)?,
+        resolution_us,
+    };
+    let output = DataId::from("aligned".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut aligned = create_jsonl(&aligned_file)?;
+    let mut send = |node: &mut DoraNode, message: Message, converted: Converted| {
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(SOURCE_KEY.into(), Parameter::String(message.source.into()));
+        parameters.insert(MONO_KEY.into(), Parameter::Integer(converted.mono_us));
+        parameters.insert(
+            UNCERTAINTY_KEY.into(),
+            Parameter::Integer(converted.uncertainty_us),
+        );
+        node.send_output(output.clone(), parameters, message.data)?;
+        write_jsonl(
+            &mut aligned,
+            &AlignedRecord {
+                source: message.source.to_owned(),
+                truth_mono_us: message.truth_mono_us,
+                mapped_mono_us: converted.mono_us,
+                uncertainty_us: converted.uncertainty_us,
+                received_mono_us: message.received_mono_us,
+                calibrated: converted.calibrated,
+            },
+        )
+    };
+
+    let mut open_inputs = 3;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let received_mono_us = monotonic_us();
+                let parameters = &metadata.parameters;
+                let stamp = integer_parameter(parameters, STAMP_KEY)?;
+                let message = |source: &'static str| -> eyre::Result<Message> {
+                    Ok(Message {
+                        source,
+                        data: data.0.clone(),
+                        truth_mono_us: integer_parameter(parameters, TRUTH_KEY)?,
+                        received_mono_us,
+                    })
+                };
+                match id.as_str() {
+                    "camera" => {
+                        let converted = Converted {
+                            mono_us: stamp,
+                            uncertainty_us: resolution_us,
+                            calibrated: true,
+                        };
+                        send(&mut node, message("camera")?, converted)?;
+                    }
+                    "utc" => match string_parameter(parameters, KIND_KEY)? {
+                        KIND_FIX => utc.pending.push((stamp, message("gnss")?)),
+                        KIND_SYNC => {
+                            let sample = SyncSample {
+                                utc_us: stamp,
+                                mono_us: integer_parameter(parameters, MONO_KEY)?,
+                                half_width_us: integer_parameter(parameters, HALF_WIDTH_KEY)?,
+                            };
+                            for (message, converted) in utc.add_sample(sample) {
+                                send(&mut node, message, converted)?;
+                            }
+                        }
+                        other => eprintln!("Ignoring unexpected utc message `{other}`"),
+                    },
+                    "imu" => {
+                        imu.add(stamp, received_mono_us);
+                        let converted = imu.convert(stamp);
+                        send(&mut node, message("imu")?, converted)?;
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                }
+            }
+            Event::InputClosed { .. } => {
+                open_inputs -= 1;
+                if open_inputs == 0 {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    if !utc.pending.is_empty() {
+        eprintln!(
+            "dropping {} fixes without a later clock sample",
+            utc.pending.len()
+        );
+    }
+
+    let summary = Summary {
+        utc_steps: utc.steps,
+        imu_drift_ppm: imu.drift_ppm(),
+    };
+    println!(
+        "UTC steps: {}, IMU clock drift: {:.1} ppm",
+        summary.utc_steps,
+        summary.imu_drift_ppm.unwrap_or(f64::NAN)
+    );
+    std::fs::write(&summary_file, serde_json::to_string_pretty(&summary)?)?;
+    Ok(())
+}
+
+struct Message {
+    source: &'static str,
+    data: ArrayRef,
+    truth_mono_us: i64,
+    received_mono_us: i64,
+}
+
+struct Converted {
+    mono_us: i64,
+    uncertainty_us: i64,
+    calibrated: bool,
+}
+
+/// A reading of the UTC clock and the monotonic clock at the same time.
+struct SyncSample {
+    utc_us: i64,
+    mono_us: i64,
+    half_width_us: i64,
+}
+
+impl SyncSample {
+    fn offset(&self) -> i64 {
+        self.utc_us - self.mono_us
+    }
+}
+
+/// Converts UTC stamps by interpolating between the clock samples before and after them.
+///
+/// Interpolating delays every fix until the next clock sample, but unlike extrapolating from
+/// the last sample, it notices when the UTC clock steps in between.
+struct UtcConversion {
+    previous: Option<SyncSample>,
+    /// Fixes received since `previous`, with their UTC stamp.
+    pending: Vec<(i64, Message)>,
+    /// Larger changes of the offset between two samples are steps, smaller ones are slewing.
+    step_tolerance_us: i64,
+    resolution_us: i64,
+    steps: u64,
+}
+
+impl UtcConversion {
+    fn add_sample(&mut self, next: SyncSample) -> Vec<(Message, Converted)> {
+        let Some(previous) = self.previous.take() else {
+            self.previous = Some(next);
+            return Vec::new();
+        };
+        let change = next.offset() - previous.offset();
+        let stepped = change.abs() > self.step_tolerance_us;
+        if stepped {
+            self.steps += 1;
+            println!("UTC clock stepped by {} ms", change / 1000);
+        }
+        let width = previous.half_width_us.max(next.half_width_us) + self.resolution_us;
+
+        let converted = self
+            .pending
+            .drain(..)
+            .map(|(utc_us, message)| {
+                let (mono_us, uncertainty_us) = if !stepped {
+                    // the offset changes linearly while the clock is slewed
+                    let span = (next.utc_us - previous.utc_us).max(1) as f64;
+                    let fraction = ((utc_us - previous.utc_us) as f64 / span).clamp(0.0, 1.0);
+                    let offset = previous.offset() + (fraction * change as f64) as i64;
+                    (utc_us - offset, width)
+                } else {
+                    // the fix was taken between the two samples, which rules out the offset
+                    // that maps it outside of them
+                    let before = utc_us - previous.offset();
+                    let after = utc_us - next.offset();
+                    let plausible = |mono: i64| (previous.mono_us..=next.mono_us).contains(&mono);
+                    match (plausible(before), plausible(after)) {
+                        (true, false) => (before, width),
+                        (false, true) => (after, width),
+                        _ => ((before + after) / 2, (before - after).abs() / 2 + width),
+                    }
+                };
+                let converted = Converted {
+                    mono_us,
+                    uncertainty_us,
+                    calibrated: true,
+                };
+                (message, converted)
+            })
+            .collect();
+        self.previous = Some(next);
+        converted
+    }
+}
+
+/// Fits `mono = offset + rate * ticks` to the sensor stamps and arrival times of the last
+/// `window` samples.
+///
+/// The rate comes from a least-squares fit. Every sample arrives some transport delay after
+/// it was taken, so the fitted line is shifted down to the sample with the smallest delay,
+/// and then by the known minimum delay.
+struct SensorClockFit {
+    samples: VecDeque<(i64, i64)>,
+    window: usize,
+    min_samples: usize,
+    min_delay_us: f64,
+    /// How well the minimum delay is known, e.g. from a calibration.
+    delay_uncertainty_us: f64,
+    resolution_us: i64,
+}
+
+struct Fit {
+    reference_ticks: i64,
+    /// Monotonic microseconds per tick.
+    rate: f64,
+    offset: f64,
+    /// Spread of the lowest residuals, how well the smallest delay is pinned down.
+    spread: f64,
+}
+
+impl SensorClockFit {
+    fn add(&mut self, ticks: i64, received_mono_us: i64) {
+        self.samples.push_back((ticks, received_mono_us));
+        if self.samples.len() > self.window {
+            self.samples.pop_front();
+        }
+    }
+
+    fn fit(&self) -> Option<Fit> {
+        let &(reference_ticks, reference_mono) = self.samples.front()?;
+        let points: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .map(|&(ticks, mono)| {
+                (
+                    (ticks - reference_ticks) as f64,
+                    (mono - reference_mono) as f64,
+                )
+            })
+            .collect();
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+        let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+        // assume the nominal rate until the samples span some time
+        let rate = if sxx > 0.0 { sxy / sxx } else { 1.0 };
+
+        let mut residuals: Vec<f64> = points.iter().map(|(x, y)| y - rate * x).collect();
+        residuals.sort_by(f64::total_cmp);
+        let lowest = &residuals[..residuals.len().min(5)];
+        Some(Fit {
+            reference_ticks,
+            rate,
+            offset: reference_mono as f64 + lowest[0] - self.min_delay_us,
+            spread: lowest[lowest.len() - 1] - lowest[0],
+        })
+    }
+
+    fn convert(&self, ticks: i64) -> Converted {
+        let fit = self.fit().expect("sample added before converting");
+        let mono_us = fit.offset + fit.rate * (ticks - fit.reference_ticks) as f64;
+        Converted {
+            mono_us: mono_us as i64,
+            uncertainty_us: (self.delay_uncertainty_us + fit.spread) as i64 + self.resolution_us,
+            calibrated: self.samples.len() >= self.min_samples,
+        }
+    }
+
+    /// How much faster the sensor clock runs than its nominal 1 MHz.
+    fn drift_ppm(&self) -> Option<f64> {
+        let fit = self.fit()?;
+        (self.samples.len() >= self.min_samples).then(|| (1.0 / fit.rate - 1.0) * 1e6)
+    }
+}
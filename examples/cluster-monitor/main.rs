@@ -0,0 +1,247 @@
+use dora_tracing::TracingBuilder;
+use example_runner_utils::{Control, Dora};
+use eyre::{Context, OptionExt, bail};
+use monitor::ClusterStatus;
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::Path,
+    time::Duration,
+};
+use tokio::task::JoinSet;
+
+mod monitor;
+
+struct Args {
+    /// Control address of an already running coordinator, e.g. `127.0.0.1:6012`.
+    attach: Option<SocketAddr>,
+    json: bool,
+    interval: Duration,
+    polls: Option<usize>,
+}
+
+impl Args {
+    fn parse() -> eyre::Result<Self> {
+        let mut args = Self {
+            attach: None,
+            json: false,
+            interval: Duration::from_secs(1),
+            polls: None,
+        };
+        let mut raw = std::env::args().skip(1);
+        while let Some(arg) = raw.next() {
+            match arg.as_str() {
+                "--attach" => {
+                    let addr = raw.next().ok_or_eyre("--attach requires an address")?;
+                    args.attach = Some(addr.parse().wrap_err("invalid --attach address")?);
+                }
+                "--json" => args.json = true,
+                "--interval-ms" => {
+                    let ms = raw.next().ok_or_eyre("--interval-ms requires a value")?;
+                    args.interval = Duration::from_millis(ms.parse()?);
+                }
+                "--polls" => {
+                    let polls = raw.next().ok_or_eyre("--polls requires a value")?;
+                    args.polls = Some(polls.parse()?);
+                }
+                other => bail!("unknown argument `{other}`"),
+            }
+        }
+        Ok(args)
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    TracingBuilder::new("cluster-monitor-runner")
+        .with_stdout("info")
+        .build()?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let args = Args::parse()?;
+    if let Some(control_addr) = args.attach {
+        // monitor an existing cluster until interrupted
+        return tokio::task::spawn_blocking(move || {
+            // fails right away if no coordinator listens
+            let mut control = Control::connect(control_addr, Duration::ZERO)?;
+            poll(&mut control, &args, |_| true)
+        })
+        .await?;
+    }
+
+    // reuse the two-daemon setup of the `multiple-daemons` example
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("../multiple-daemons/dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let coordinator_addr = Ipv4Addr::LOCALHOST;
+    let interface_port =
+        port_check::free_local_ipv4_port_in_range(10000..=15000).ok_or_eyre("No available port")?;
+    let control_port = port_check::free_local_ipv4_port_in_range((interface_port + 1)..=15000)
+        .ok_or_eyre("No available port")?;
+    let coordinator = run_coordinator(
+        dora.clone(),
+        coordinator_addr.to_string(),
+        interface_port,
+        control_port,
+    );
+    let daemon_a = run_daemon(
+        dora.clone(),
+        coordinator_addr.to_string(),
+        "A",
+        interface_port,
+    );
+    let daemon_b = run_daemon(
+        dora.clone(),
+        coordinator_addr.to_string(),
+        "B",
+        interface_port,
+    );
+
+    tracing::info!("Spawning coordinator and daemons");
+    let mut tasks = JoinSet::new();
+    tasks.spawn(coordinator);
+    tasks.spawn(daemon_b);
+    tasks.spawn(daemon_a);
+
+    let control_addr = SocketAddr::from((coordinator_addr, control_port));
+    let mut control = tokio::task::spawn_blocking(move || {
+        let mut control = Control::connect(control_addr, Duration::from_secs(60))?;
+        tracing::info!("waiting until both daemons are connected to the coordinator");
+        control
+            .wait_until(Duration::from_secs(60), Duration::from_millis(200), |c| {
+                Ok(c.connected_daemons()?.len() >= 2)
+            })
+            .wrap_err("daemons did not connect to coordinator")?;
+        eyre::Ok(control)
+    })
+    .await??;
+
+    tracing::info!("starting dataflow");
+    start_dataflow(
+        &dora,
+        dataflow,
+        coordinator_addr.to_string(),
+        interface_port,
+    )
+    .await?;
+
+    let args = Args {
+        polls: args.polls.or(Some(5)),
+        ..args
+    };
+    tokio::task::spawn_blocking(move || {
+        let mut saw_running = false;
+        poll(&mut control, &args, |status| {
+            saw_running |= status.daemons.len() == 2 && status.running_dataflows() == 1;
+            true
+        })?;
+        if !saw_running {
+            bail!("monitor never observed the dataflow running on both daemons");
+        }
+
+        tracing::info!("shutting down coordinator and daemons");
+        control.destroy()
+    })
+    .await??;
+
+    tracing::info!("joining tasks");
+    while let Some(res) = tasks.join_next().await {
+        res.unwrap()?;
+    }
+
+    tracing::info!("done");
+    Ok(())
+}
+
+/// Queries the cluster status every `args.interval` and prints it, until `args.polls` is
+/// reached or `on_status` returns `false`.
+fn poll(
+    control: &mut Control,
+    args: &Args,
+    mut on_status: impl FnMut(&ClusterStatus) -> bool,
+) -> eyre::Result<()> {
+    let mut polls = 0;
+    while args.polls.is_none_or(|max| polls < max) {
+        let status = ClusterStatus::query(control)?;
+        if args.json {
+            println!("{}", serde_json::to_string(&status)?);
+        } else {
+            status.print_table();
+        }
+        if !on_status(&status) {
+            break;
+        }
+        polls += 1;
+        std::thread::sleep(args.interval);
+    }
+    Ok(())
+}
+
+async fn start_dataflow(
+    dora: &Dora,
+    dataflow: &Path,
+    coordinator_addr: String,
+    coordinator_port: u16,
+) -> eyre::Result<()> {
+    let mut cmd = dora.cli(["start"]);
+    cmd.arg(dataflow).args([
+        "--coordinator-addr",
+        &coordinator_addr,
+        "--coordinator-port",
+        &coordinator_port.to_string(),
+    ]);
+    if !cmd.status().await?.success() {
+        bail!("failed to start dataflow");
+    };
+    Ok(())
+}
+
+async fn run_coordinator(
+    dora: Dora,
+    interface: String,
+    interface_port: u16,
+    control_port: u16,
+) -> eyre::Result<()> {
+    let mut cmd = dora.cli(["coordinator"]);
+    cmd.args([
+        "--interface",
+        &interface,
+        "--control-interface",
+        &interface,
+        "--port",
+        &interface_port.to_string(),
+        "--control-port",
+        &control_port.to_string(),
+    ]);
+    if !cmd.status().await?.success() {
+        bail!("failed to run dataflow");
+    };
+    Ok(())
+}
+
+async fn run_daemon(
+    dora: Dora,
+    coordinator: String,
+    machine_id: &str,
+    interface_port: u16,
+) -> eyre::Result<()> {
+    let daemon_port =
+        port_check::free_local_ipv4_port_in_range(11000..=15000).ok_or_eyre("No available port")?;
+    let mut cmd = dora.cli(["daemon"]);
+    cmd.arg("--machine-id")
+        .arg(machine_id)
+        .arg("--coordinator-addr")
+        .arg(coordinator)
+        .arg("--coordinator-port")
+        .arg(interface_port.to_string())
+        .arg("--local-listen-port")
+        .arg(daemon_port.to_string()); // random port
+    if !cmd.status().await?.success() {
+        bail!("failed to run dataflow");
+    };
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use dora_message::coordinator_to_cli::DataflowStatus;
+use example_runner_utils::Control;
+use serde::Serialize;
+use std::time::SystemTime;
+
+/// Snapshot of the cluster state as seen by the coordinator.
+#[derive(Debug, Serialize)]
+pub struct ClusterStatus {
+    /// Seconds since the Unix epoch at which the status was queried.
+    pub timestamp: u64,
+    pub daemons: Vec<String>,
+    pub dataflows: Vec<DataflowSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataflowSummary {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub status: DataflowStatus,
+}
+
+impl ClusterStatus {
+    /// Queries the connected daemons and the known dataflows from the coordinator.
+    pub fn query(control: &mut Control) -> eyre::Result<Self> {
+        let daemons = control.connected_daemons()?;
+        let dataflows = control
+            .dataflows()?
+            .into_iter()
+            .map(|entry| DataflowSummary {
+                uuid: entry.id.uuid.to_string(),
+                name: entry.id.name,
+                status: entry.status,
+            })
+            .collect();
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+
+        Ok(Self {
+            timestamp,
+            daemons,
+            dataflows,
+        })
+    }
+
+    pub fn running_dataflows(&self) -> usize {
+        self.dataflows
+            .iter()
+            .filter(|dataflow| matches!(dataflow.status, DataflowStatus::Running))
+            .count()
+    }
+
+    pub fn print_table(&self) {
+        println!("daemons ({}):", self.daemons.len());
+        for daemon in &self.daemons {
+            println!("  {daemon}");
+        }
+        println!("dataflows ({}):", self.dataflows.len());
+        println!("  {:<38} {:<20} STATUS", "UUID", "NAME");
+        for dataflow in &self.dataflows {
+            println!(
+                "  {:<38} {:<20} {:?}",
+                dataflow.uuid,
+                dataflow.name.as_deref().unwrap_or("-"),
+                dataflow.status
+            );
+        }
+    }
+}
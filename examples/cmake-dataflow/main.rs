@@ -1,4 +1,5 @@
 use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
 use eyre::{Context, bail};
 use std::path::Path;
 
@@ -38,36 +39,11 @@ async fn main() -> eyre::Result<()> {
         bail!("failed to build a cmake-generated project binary tree");
     }
 
-    let dataflow = Path::new("dataflow.yml").to_owned();
-    build_package("dora-runtime").await?;
-    run_dataflow(&dataflow).await?;
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.package("dora-runtime").debug().build().await?;
+    validate_dataflows::check(dataflow)?;
+    dora.run_dataflow(dataflow).await?;
 
     Ok(())
 }
-
-async fn build_package(package: &str) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("build");
-    cmd.arg("--package").arg(package);
-    if !cmd.status().await?.success() {
-        bail!("failed to build {package}");
-    }
-    Ok(())
-}
-
-async fn run_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--")
-        .arg("daemon")
-        .arg("--run-dataflow")
-        .arg(dataflow);
-    if !cmd.status().await?.success() {
-        bail!("failed to run dataflow");
-    };
-    Ok(())
-}
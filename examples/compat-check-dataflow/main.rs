@@ -0,0 +1,49 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use std::path::Path;
+
+/// The problem that the checker reports for `dataflow_mismatch.yml`.
+const EXPECTED_PROBLEM: &str =
+    "`consumer` requires `sensor-readings` ^1.2, but `producer` provides 2.0.0";
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("compat-check-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    // the producer was upgraded to a new major version, which the consumer does not accept
+    let mismatch = Path::new("dataflow_mismatch.yml");
+    dora.build_dataflow(mismatch).await?;
+    let mut run = dora.cli(["daemon", "--run-dataflow"]);
+    run.arg(mismatch);
+    let output = run.output().await.context("failed to run dataflow")?;
+    let log = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    print!("{log}");
+    if output.status.success() {
+        bail!("checker accepted an incompatible interface version");
+    }
+    // a build error or a crashing node fails the dataflow as well, without the report
+    if !log.contains(EXPECTED_PROBLEM) {
+        bail!(
+            "incompatible dataflow failed ({}), but not with the report of the checker",
+            output.status
+        );
+    }
+    tracing::info!("incompatible dataflow was rejected as expected");
+
+    Ok(())
+}
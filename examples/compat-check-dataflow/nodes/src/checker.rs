@@ -0,0 +1,89 @@
+use compat_check_dataflow_nodes::Handshake;
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use eyre::{Context, bail};
+use std::collections::BTreeMap;
+
+fn main() -> eyre::Result<()> {
+    let verdict_output = DataId::from("verdict".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    // every input of the checker is the handshake output of one node
+    let expected = node.node_config().inputs.len();
+    let mut handshakes = BTreeMap::new();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => {
+                let raw: &str = TryFrom::try_from(&data).context("expected string handshake")?;
+                let handshake: Handshake = serde_json::from_str(raw)
+                    .with_context(|| format!("invalid handshake on input `{id}`"))?;
+                println!("received handshake from `{}`", handshake.node);
+                handshakes.insert(handshake.node.clone(), handshake);
+            }
+            Event::InputClosed { id } => {
+                if handshakes.len() < expected {
+                    bail!("input `{id}` closed before all handshakes were received");
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+
+        if handshakes.len() == expected {
+            break;
+        }
+    }
+
+    let problems = check(&handshakes);
+    if !problems.is_empty() {
+        eprintln!("interface compatibility check failed:");
+        for problem in &problems {
+            eprintln!("  - {problem}");
+        }
+        node.send_output(
+            verdict_output,
+            Default::default(),
+            "incompatible interfaces".into_arrow(),
+        )?;
+        bail!("{} incompatible interface(s) found", problems.len());
+    }
+
+    println!(
+        "all {} nodes agree on their interface versions",
+        handshakes.len()
+    );
+    node.send_output(verdict_output, Default::default(), "ok".into_arrow())?;
+
+    Ok(())
+}
+
+fn check(handshakes: &BTreeMap<String, Handshake>) -> Vec<String> {
+    let mut problems = Vec::new();
+    for consumer in handshakes.values() {
+        for (interface, req) in &consumer.requires {
+            let producers: Vec<_> = handshakes
+                .values()
+                .filter_map(|h| h.provides.get(interface).map(|v| (&h.node, v)))
+                .collect();
+            if producers.is_empty() {
+                problems.push(format!(
+                    "`{}` requires `{interface}` {req}, but no node provides it",
+                    consumer.node
+                ));
+            }
+            for (producer, version) in producers {
+                if !req.matches(version) {
+                    problems.push(format!(
+                        "`{}` requires `{interface}` {req}, but `{producer}` provides {version}",
+                        consumer.node
+                    ));
+                }
+            }
+        }
+    }
+    problems
+}
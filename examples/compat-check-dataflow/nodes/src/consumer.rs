@@ -0,0 +1,59 @@
+use compat_check_dataflow_nodes::Handshake;
+use dora_node_api::{self, DoraNode, Event};
+use eyre::{Context, bail};
+
+fn main() -> eyre::Result<()> {
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let handshake = Handshake::from_env(&node)?;
+    println!("announcing {handshake:?}");
+    handshake.send(&mut node)?;
+
+    // readings are held back until the checker confirmed that the versions match
+    let mut verified = false;
+    let mut pending = Vec::new();
+    let mut processed = 0;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "verdict" => {
+                    let verdict: &str =
+                        TryFrom::try_from(&data).context("expected string verdict")?;
+                    if verdict != "ok" {
+                        bail!("checker rejected the dataflow: {verdict}");
+                    }
+                    verified = true;
+                    for value in pending.drain(..) {
+                        println!("processing held back reading {value}");
+                        processed += 1;
+                    }
+                }
+                "readings" => {
+                    let value = u64::try_from(&data).context("unexpected data type")?;
+                    if verified {
+                        println!("processing reading {value}");
+                        processed += 1;
+                    } else {
+                        pending.push(value);
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "verdict" && !verified {
+                    bail!("checker exited without approving the interface versions");
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("processed {processed} readings");
+    Ok(())
+}
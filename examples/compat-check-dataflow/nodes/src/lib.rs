@@ -0,0 +1,67 @@
+use dora_node_api::{DoraNode, IntoArrow, dora_core::config::DataId};
+use eyre::{Context, eyre};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Startup message that every node sends on its `handshake` output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Handshake {
+    pub node: String,
+    /// Interfaces published by the node, with their semantic version.
+    pub provides: BTreeMap<String, Version>,
+    /// Interfaces consumed by the node, with the version range it accepts.
+    pub requires: BTreeMap<String, VersionReq>,
+}
+
+impl Handshake {
+    /// Reads the `PROVIDES` and `REQUIRES` env variables set in the dataflow YAML.
+    ///
+    /// Both are comma-separated lists of `<interface>@<version>` entries, e.g.
+    /// `PROVIDES: sensor-readings@1.3.0` or `REQUIRES: sensor-readings@^1.2`.
+    pub fn from_env(node: &DoraNode) -> eyre::Result<Self> {
+        let mut provides = BTreeMap::new();
+        for (interface, version) in parse_entries("PROVIDES")? {
+            let version = Version::parse(&version)
+                .with_context(|| format!("invalid version `{version}` for `{interface}`"))?;
+            provides.insert(interface, version);
+        }
+        let mut requires = BTreeMap::new();
+        for (interface, req) in parse_entries("REQUIRES")? {
+            let req = VersionReq::parse(&req)
+                .with_context(|| format!("invalid version range `{req}` for `{interface}`"))?;
+            requires.insert(interface, req);
+        }
+        Ok(Self {
+            node: node.id().to_string(),
+            provides,
+            requires,
+        })
+    }
+
+    pub fn send(&self, node: &mut DoraNode) -> eyre::Result<()> {
+        let serialized = serde_json::to_string(self)?;
+        node.send_output(
+            DataId::from("handshake".to_owned()),
+            Default::default(),
+            serialized.into_arrow(),
+        )
+    }
+}
+
+fn parse_entries(var: &str) -> eyre::Result<Vec<(String, String)>> {
+    let Ok(value) = std::env::var(var) else {
+        return Ok(Vec::new());
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (interface, version) = entry
+                .split_once('@')
+                .ok_or_else(|| eyre!("expected `<interface>@<version>` in {var}, got `{entry}`"))?;
+            Ok((interface.to_owned(), version.to_owned()))
+        })
+        .collect()
+}
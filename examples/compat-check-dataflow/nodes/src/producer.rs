@@ -0,0 +1,35 @@
+use compat_check_dataflow_nodes::Handshake;
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("readings".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let handshake = Handshake::from_env(&node)?;
+    println!("announcing {handshake:?}");
+    handshake.send(&mut node)?;
+
+    for i in 0..20u64 {
+        let event = match events.recv() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            Event::Input {
+                id,
+                metadata,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    node.send_output(output.clone(), metadata.parameters, (i * 10).into_arrow())?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
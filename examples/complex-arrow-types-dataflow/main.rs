@@ -0,0 +1,116 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, NativeNode, pkg_config};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Number of messages that the producer sends, see `dataflow.yml`.
+const MESSAGES: u64 = 50;
+
+/// Written by `sink.py`.
+#[derive(Debug, Deserialize)]
+struct Report {
+    sent: u64,
+    direct: InputReport,
+    transformed: InputReport,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputReport {
+    messages: u64,
+    mismatches: u64,
+    first_mismatch: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("complex-arrow-types-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    if cfg!(windows) {
+        tracing::error!(
+            "The c++ example does not work on Windows currently because of a linker error"
+        );
+        return Ok(());
+    }
+
+    // pass `--cmake` to build the nodes with the `CMakeLists.txt` of the example instead
+    let cmake = std::env::args().any(|arg| arg == "--cmake");
+
+    let dora = Dora::from_env()?;
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target = dora.target_dir();
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // C++ transformer
+    tokio::fs::create_dir_all("build").await?;
+    let build_dir = Path::new("build");
+    dora.package("dora-node-api-cxx").build().await?;
+    let node_cxxbridge = target
+        .join("cxxbridge")
+        .join("dora-node-api-cxx")
+        .join("src");
+    tokio::fs::copy(
+        node_cxxbridge.join("lib.rs.cc"),
+        build_dir.join("node-bridge.cc"),
+    )
+    .await?;
+    tokio::fs::copy(
+        node_cxxbridge.join("lib.rs.h"),
+        build_dir.join("dora-node-api.h"),
+    )
+    .await?;
+    if cmake {
+        // finds Arrow with its own CMake package instead of pkg-config
+        dora.cmake(".").build().await?;
+    } else {
+        let arrow_flags = pkg_config("arrow").wrap_err("Failed to find Arrow configuration")?;
+        NativeNode::cxx("transformer")
+            .source(Path::new("transformer").join("main.cc"))
+            .source(build_dir.join("node-bridge.cc"))
+            .link("dora_node_api_cxx")
+            .flags(&arrow_flags)
+            .lib_dir(dora.release_dir())
+            .build()
+            .await?;
+    }
+
+    // Python sink
+    dora.uv_venv("3.11").create().await?;
+
+    let dataflow = Path::new("dataflow.yml");
+    validate_dataflows::check(dataflow)?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: Report = serde_json::from_str(
+        &std::fs::read_to_string("out/report.json").context("sink did not write a report")?,
+    )?;
+    if report.sent != MESSAGES {
+        bail!(
+            "expected the producer to send {MESSAGES} messages, it sent {}",
+            report.sent
+        );
+    }
+    for (name, input) in [
+        ("direct", &report.direct),
+        ("transformed", &report.transformed),
+    ] {
+        if input.messages != MESSAGES {
+            bail!(
+                "sink received {} of {MESSAGES} {name} messages",
+                input.messages
+            );
+        }
+        if input.mismatches > 0 {
+            bail!(
+                "{} {name} messages differ from the sent values, first: {}",
+                input.mismatches,
+                input.first_mismatch.as_deref().unwrap_or_default()
+            );
+        }
+    }
+    println!("all {MESSAGES} messages arrived unchanged in Python, directly and through C++");
+
+    Ok(())
+}
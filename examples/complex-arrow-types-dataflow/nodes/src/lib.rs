@@ -0,0 +1,134 @@
+use dora_node_api::arrow::{
+    array::{ArrayRef, Float32Array, Int32Array, ListArray, StringArray, StructArray, UInt64Array},
+    buffer::{NullBuffer, OffsetBuffer},
+    datatypes::{DataType, Field, FieldRef, Fields},
+};
+use eyre::Context;
+use serde::Serialize;
+use std::{io::Write, path::Path, sync::Arc};
+
+pub use example_node_utils::env_or;
+
+/// A camera frame with the objects detected in it, one row of a `frames` message.
+///
+/// The JSON form of this struct is what the Python sink gets from `to_pylist()`, so the sink
+/// compares the received values to the expected ones without any conversion.
+#[derive(Debug, Clone, Serialize)]
+pub struct Frame {
+    pub frame_id: u64,
+    pub sensor: String,
+    /// `None` if the detector did not run on this frame, which is different from an empty list.
+    pub detections: Option<Vec<Detection>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Detection {
+    /// `None` if the object could not be classified.
+    pub label: Option<String>,
+    pub score: f32,
+    pub keypoints: Vec<i32>,
+}
+
+/// One line of the file that the producer writes next to every message it sends.
+#[derive(Debug, Serialize)]
+pub struct SentMessage {
+    pub seq: u64,
+    pub frames: Vec<Frame>,
+}
+
+fn keypoint_field() -> FieldRef {
+    Arc::new(Field::new("item", DataType::Int32, false))
+}
+
+fn detection_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("label", DataType::Utf8, true),
+        Field::new("score", DataType::Float32, false),
+        Field::new("keypoints", DataType::List(keypoint_field()), false),
+    ])
+}
+
+fn detection_field() -> FieldRef {
+    Arc::new(Field::new(
+        "item",
+        DataType::Struct(detection_fields()),
+        false,
+    ))
+}
+
+/// `Struct<frame_id, sensor, detections: List<Struct<label, score, keypoints: List<Int32>>>>`
+///
+/// The C++ transformer and the Python sink declare the same type and reject anything else.
+pub fn frame_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("frame_id", DataType::UInt64, false),
+        Field::new("sensor", DataType::Utf8, false),
+        Field::new("detections", DataType::List(detection_field()), true),
+    ])
+}
+
+/// Builds the nested array from the child arrays up, with one offset buffer per list level.
+pub fn frames_to_arrow(frames: &[Frame]) -> StructArray {
+    let detections: Vec<&Detection> = frames
+        .iter()
+        .flat_map(|frame| frame.detections.iter().flatten())
+        .collect();
+
+    let keypoints = ListArray::new(
+        keypoint_field(),
+        OffsetBuffer::from_lengths(detections.iter().map(|d| d.keypoints.len())),
+        Arc::new(Int32Array::from_iter_values(
+            detections.iter().flat_map(|d| d.keypoints.iter().copied()),
+        )),
+        None,
+    );
+    let detection_columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter(
+            detections.iter().map(|d| d.label.as_deref()),
+        )),
+        Arc::new(Float32Array::from_iter_values(
+            detections.iter().map(|d| d.score),
+        )),
+        Arc::new(keypoints),
+    ];
+    let detections = StructArray::new(detection_fields(), detection_columns, None);
+
+    // a missing list still takes a slot in the offsets, with a length of zero
+    let detection_lists = ListArray::new(
+        detection_field(),
+        OffsetBuffer::from_lengths(
+            frames
+                .iter()
+                .map(|frame| frame.detections.as_ref().map_or(0, Vec::len)),
+        ),
+        Arc::new(detections),
+        Some(NullBuffer::from(
+            frames
+                .iter()
+                .map(|frame| frame.detections.is_some())
+                .collect::<Vec<_>>(),
+        )),
+    );
+    let frame_columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(
+            frames.iter().map(|frame| frame.frame_id),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            frames.iter().map(|frame| frame.sensor.as_str()),
+        )),
+        Arc::new(detection_lists),
+    ];
+    StructArray::new(frame_fields(), frame_columns, None)
+}
+
+pub fn create_jsonl(path: &Path) -> eyre::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))
+}
+
+pub fn write_jsonl<T: Serialize>(file: &mut std::fs::File, record: &T) -> eyre::Result<()> {
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
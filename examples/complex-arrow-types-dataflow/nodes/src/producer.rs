@@ -0,0 +1,99 @@
+use complex_arrow_types_dataflow_nodes::{
+    Detection, Frame, SentMessage, create_jsonl, env_or, frames_to_arrow, write_jsonl,
+};
+use dora_node_api::{self, DoraNode, Event, MetadataParameters, dora_core::config::DataId};
+use rand::Rng;
+use std::path::PathBuf;
+
+const SENSORS: [&str; 3] = ["front", "left", "right"];
+const LABELS: [&str; 4] = ["car", "pedestrian", "cyclist", "traffic-sign"];
+
+/// Sends `COUNT` messages of nested frames on `frames`, and appends the values of every
+/// message to `SENT_FILE` before sending it.
+///
+/// The frames cover the cases that conversions tend to get wrong: null and empty lists, null
+/// strings inside a nested struct, lists of different lengths next to each other, and every
+/// tenth message without any rows at all.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 50)?;
+    let sent_file: PathBuf = env_or("SENT_FILE", "out/sent.jsonl".to_owned())?.into();
+    let output = DataId::from("frames".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent = create_jsonl(&sent_file)?;
+    let mut rng = rand::thread_rng();
+    let mut frame_id = 0;
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if seq == count {
+                        break;
+                    }
+                    let rows = if seq % 10 == 0 {
+                        0
+                    } else {
+                        rng.gen_range(1..8)
+                    };
+                    let frames: Vec<Frame> = (0..rows)
+                        .map(|_| {
+                            frame_id += 1;
+                            random_frame(&mut rng, frame_id)
+                        })
+                        .collect();
+
+                    // the sink reads this file once the dataflow is done, so writing it before
+                    // sending is enough
+                    write_jsonl(
+                        &mut sent,
+                        &SentMessage {
+                            seq,
+                            frames: frames.clone(),
+                        },
+                    )?;
+                    node.send_output(
+                        output.clone(),
+                        MetadataParameters::default(),
+                        frames_to_arrow(&frames),
+                    )?;
+                    seq += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} messages with {frame_id} frames");
+    Ok(())
+}
+
+fn random_frame(rng: &mut impl Rng, frame_id: u64) -> Frame {
+    // about one frame in ten was not run through the detector
+    let detections = (rng.gen_range(0..10) != 0).then(|| {
+        (0..rng.gen_range(0..5))
+            .map(|_| Detection {
+                label: (rng.gen_range(0..10) != 0)
+                    .then(|| LABELS[rng.gen_range(0..LABELS.len())].to_owned()),
+                // multiples of 1/256 are exact in `f32`, `f64`, and JSON, so the sink can
+                // compare scores with `==`
+                score: rng.gen_range(0..=256) as f32 / 256.0,
+                keypoints: (0..rng.gen_range(0..4))
+                    .map(|_| rng.gen_range(-1000..1000))
+                    .collect(),
+            })
+            .collect()
+    });
+    Frame {
+        frame_id,
+        sensor: SENSORS[rng.gen_range(0..SENSORS.len())].to_owned(),
+        detections,
+    }
+}
@@ -0,0 +1,105 @@
+#!/usr/bin/env python3
+"""
+Frame sink.
+- Receives the frames straight from the Rust producer on `direct`, and after the C++
+  transformer on `transformed`
+- Once both inputs are closed, compares every message to the values in `SENT_FILE`
+- Writes the result per input to `REPORT_FILE` (JSON)
+
+The C++ node API cannot forward metadata, so the n-th message of an input is compared to the
+n-th line of `SENT_FILE`. dora delivers the messages of an input in order.
+"""
+
+import json
+import os
+
+import pyarrow as pa
+from dora import Node
+
+SENT_FILE = os.getenv("SENT_FILE", "out/sent.jsonl")
+REPORT_FILE = os.getenv("REPORT_FILE", "out/report.json")
+
+# Must match `frame_fields` in `nodes/src/lib.rs`.
+KEYPOINTS = pa.list_(pa.field("item", pa.int32(), nullable=False))
+DETECTION = pa.struct(
+    [
+        pa.field("label", pa.utf8(), nullable=True),
+        pa.field("score", pa.float32(), nullable=False),
+        pa.field("keypoints", KEYPOINTS, nullable=False),
+    ]
+)
+FRAME = pa.struct(
+    [
+        pa.field("frame_id", pa.uint64(), nullable=False),
+        pa.field("sensor", pa.utf8(), nullable=False),
+        pa.field(
+            "detections",
+            pa.list_(pa.field("item", DETECTION, nullable=False)),
+            nullable=True,
+        ),
+    ]
+)
+
+
+def reverse_detections(frames):
+    """Applies the transformation of the C++ transformer to the expected values."""
+    return [
+        {
+            **frame,
+            "detections": (
+                None
+                if frame["detections"] is None
+                else list(reversed(frame["detections"]))
+            ),
+        }
+        for frame in frames
+    ]
+
+
+def compare(received, sent, transform):
+    result = {"messages": len(received), "mismatches": 0, "first_mismatch": None}
+    for index, (value, expected) in enumerate(zip(received, sent)):
+        if not value.type.equals(FRAME):
+            problem = f"type {value.type}"
+        elif value.to_pylist() != transform(expected["frames"]):
+            problem = f"values {value.to_pylist()}"
+        else:
+            continue
+        result["mismatches"] += 1
+        if result["first_mismatch"] is None:
+            result["first_mismatch"] = f"message {index}: {problem}"
+    return result
+
+
+def main():
+    node = Node()
+    received = {"direct": [], "transformed": []}
+    for event in node:
+        if event["type"] == "INPUT" and event["id"] in received:
+            received[event["id"]].append(event["value"])
+
+    with open(SENT_FILE) as file:
+        sent = [json.loads(line) for line in file if line.strip()]
+
+    report = {
+        "sent": len(sent),
+        "direct": compare(received["direct"], sent, lambda frames: frames),
+        "transformed": compare(received["transformed"], sent, reverse_detections),
+    }
+    for name in received:
+        result = report[name]
+        print(
+            f"{name}: {result['messages']} of {len(sent)} messages, "
+            f"{result['mismatches']} mismatches",
+            flush=True,
+        )
+        if result["first_mismatch"] is not None:
+            print(f"  first mismatch: {result['first_mismatch']}", flush=True)
+
+    os.makedirs(os.path.dirname(REPORT_FILE) or ".", exist_ok=True)
+    with open(REPORT_FILE, "w") as file:
+        json.dump(report, file, indent=2)
+
+
+if __name__ == "__main__":
+    main()
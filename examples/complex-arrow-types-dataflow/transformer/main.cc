@@ -0,0 +1,144 @@
+#include "../build/dora-node-api.h"
+#include <arrow/api.h>
+#include <arrow/c/bridge.h>
+#include <iostream>
+#include <memory>
+#include <string>
+
+// Must match `frame_fields` in `nodes/src/lib.rs`.
+std::shared_ptr<arrow::DataType> frame_type() {
+    auto keypoints = arrow::list(arrow::field("item", arrow::int32(), false));
+    auto detection = arrow::struct_({
+        arrow::field("label", arrow::utf8(), true),
+        arrow::field("score", arrow::float32(), false),
+        arrow::field("keypoints", keypoints, false),
+    });
+    return arrow::struct_({
+        arrow::field("frame_id", arrow::uint64(), false),
+        arrow::field("sensor", arrow::utf8(), false),
+        arrow::field("detections", arrow::list(arrow::field("item", detection, false)), true),
+    });
+}
+
+// Rebuilds the frames with the detections of every frame in reverse order.
+//
+// The array is walked value by value instead of forwarding its buffers, so the sink only sees
+// the right values if the offsets, validity bitmaps, and child arrays of every level were read
+// and written correctly.
+arrow::Result<std::shared_ptr<arrow::Array>> reverse_detections(const std::shared_ptr<arrow::Array>& input) {
+    if (!input->type()->Equals(frame_type())) {
+        return arrow::Status::TypeError("unexpected type ", input->type()->ToString());
+    }
+    auto frames = std::static_pointer_cast<arrow::StructArray>(input);
+    auto frame_ids = std::static_pointer_cast<arrow::UInt64Array>(frames->field(0));
+    auto sensors = std::static_pointer_cast<arrow::StringArray>(frames->field(1));
+    auto detection_lists = std::static_pointer_cast<arrow::ListArray>(frames->field(2));
+    // the offsets of a list index into the whole child array, not into a slice of it
+    auto detections = std::static_pointer_cast<arrow::StructArray>(detection_lists->values());
+    auto labels = std::static_pointer_cast<arrow::StringArray>(detections->field(0));
+    auto scores = std::static_pointer_cast<arrow::FloatArray>(detections->field(1));
+    auto keypoint_lists = std::static_pointer_cast<arrow::ListArray>(detections->field(2));
+    auto keypoints = std::static_pointer_cast<arrow::Int32Array>(keypoint_lists->values());
+
+    // `MakeBuilder` creates the nested builders in the order of the fields
+    ARROW_ASSIGN_OR_RAISE(auto builder, arrow::MakeBuilder(frame_type()));
+    auto& frame_builder = static_cast<arrow::StructBuilder&>(*builder);
+    auto& frame_id_builder = static_cast<arrow::UInt64Builder&>(*frame_builder.field_builder(0));
+    auto& sensor_builder = static_cast<arrow::StringBuilder&>(*frame_builder.field_builder(1));
+    auto& detection_list_builder = static_cast<arrow::ListBuilder&>(*frame_builder.field_builder(2));
+    auto& detection_builder = static_cast<arrow::StructBuilder&>(*detection_list_builder.value_builder());
+    auto& label_builder = static_cast<arrow::StringBuilder&>(*detection_builder.field_builder(0));
+    auto& score_builder = static_cast<arrow::FloatBuilder&>(*detection_builder.field_builder(1));
+    auto& keypoint_list_builder = static_cast<arrow::ListBuilder&>(*detection_builder.field_builder(2));
+    auto& keypoint_builder = static_cast<arrow::Int32Builder&>(*keypoint_list_builder.value_builder());
+
+    for (int64_t i = 0; i < frames->length(); i++) {
+        ARROW_RETURN_NOT_OK(frame_builder.Append());
+        ARROW_RETURN_NOT_OK(frame_id_builder.Append(frame_ids->Value(i)));
+        ARROW_RETURN_NOT_OK(sensor_builder.Append(sensors->GetView(i)));
+        // a null list and an empty list are different values
+        if (detection_lists->IsNull(i)) {
+            ARROW_RETURN_NOT_OK(detection_list_builder.AppendNull());
+            continue;
+        }
+        ARROW_RETURN_NOT_OK(detection_list_builder.Append());
+        for (int64_t j = detection_lists->value_offset(i + 1) - 1; j >= detection_lists->value_offset(i); j--) {
+            ARROW_RETURN_NOT_OK(detection_builder.Append());
+            if (labels->IsNull(j)) {
+                ARROW_RETURN_NOT_OK(label_builder.AppendNull());
+            } else {
+                ARROW_RETURN_NOT_OK(label_builder.Append(labels->GetView(j)));
+            }
+            ARROW_RETURN_NOT_OK(score_builder.Append(scores->Value(j)));
+            ARROW_RETURN_NOT_OK(keypoint_list_builder.Append());
+            for (int64_t k = keypoint_lists->value_offset(j); k < keypoint_lists->value_offset(j + 1); k++) {
+                ARROW_RETURN_NOT_OK(keypoint_builder.Append(keypoints->Value(k)));
+            }
+        }
+    }
+
+    std::shared_ptr<arrow::Array> output;
+    ARROW_RETURN_NOT_OK(builder->Finish(&output));
+    return output;
+}
+
+int main() {
+    try {
+        auto dora_node = init_dora_node();
+        int transformed = 0;
+        while (true) {
+            auto event = dora_node.events->next();
+            auto type = event_type(event);
+
+            if (type == DoraEventType::Stop || type == DoraEventType::AllInputsClosed) {
+                break;
+            }
+            else if (type == DoraEventType::Input) {
+                struct ArrowArray c_array;
+                struct ArrowSchema c_schema;
+                auto result = event_as_arrow_input(
+                    std::move(event),
+                    reinterpret_cast<uint8_t*>(&c_array),
+                    reinterpret_cast<uint8_t*>(&c_schema)
+                );
+                if (!result.error.empty()) {
+                    std::cerr << "Error getting Arrow array: " << std::string(result.error) << std::endl;
+                    return 1;
+                }
+                auto input = arrow::ImportArray(&c_array, &c_schema).ValueOrDie();
+
+                auto output = reverse_detections(input);
+                if (!output.ok()) {
+                    std::cerr << "Error transforming frames: " << output.status().ToString() << std::endl;
+                    return 1;
+                }
+
+                struct ArrowArray out_c_array;
+                struct ArrowSchema out_c_schema;
+                auto status = arrow::ExportArray(**output, &out_c_array, &out_c_schema);
+                if (!status.ok()) {
+                    std::cerr << "Error exporting frames: " << status.ToString() << std::endl;
+                    return 1;
+                }
+                auto send_result = send_arrow_output(
+                    dora_node.send_output,
+                    "frames",
+                    reinterpret_cast<uint8_t*>(&out_c_array),
+                    reinterpret_cast<uint8_t*>(&out_c_schema)
+                );
+                if (!send_result.error.empty()) {
+                    std::cerr << "Error sending frames: " << std::string(send_result.error) << std::endl;
+                    return 1;
+                }
+                transformed++;
+            }
+        }
+
+        std::cout << "transformed " << transformed << " messages" << std::endl;
+        return 0;
+    }
+    catch (const std::exception& e) {
+        std::cerr << "Error: " << e.what() << std::endl;
+        return 1;
+    }
+}
@@ -0,0 +1,20 @@
+use dora_tracing::set_up_tracing;
+use eyre::Context;
+use std::path::Path;
+
+/// Runs the contract tests in `nodes/tests/contracts.rs`, which build and run the dataflow
+/// themselves.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("contract-tests-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // the cargo that runs the example, `DORA` is passed on to the tests
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut test = tokio::process::Command::new(cargo);
+    test.args(["test", "--manifest-path", "nodes/Cargo.toml"]);
+    example_runner_utils::run(&mut test, "contract tests failed").await
+}
@@ -0,0 +1,49 @@
+use contract_tests_nodes::{Calibrated, RawReading, env_or, from_json_rows, to_json_rows};
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+
+/// Converts the `raw` readings to degrees Celsius with `GAIN` and `OFFSET`, and clamps them to
+/// `MIN..=MAX`. Sends the results on `celsius`.
+fn main() -> eyre::Result<()> {
+    let offset: f64 = env_or("OFFSET", 0.0)?;
+    let gain: f64 = env_or("GAIN", 1.0)?;
+    let min: f64 = env_or("MIN", -40.0)?;
+    let max: f64 = env_or("MAX", 85.0)?;
+    let output = DataId::from("celsius".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut clamped = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "raw" => {
+                    let readings: Vec<RawReading> = from_json_rows(&data)?;
+                    let calibrated: Vec<_> = readings
+                        .into_iter()
+                        .map(|reading| {
+                            let celsius = reading.value * gain + offset;
+                            Calibrated {
+                                seq: reading.seq,
+                                sensor: reading.sensor,
+                                celsius: celsius.clamp(min, max),
+                                clamped: !(min..=max).contains(&celsius),
+                            }
+                        })
+                        .collect();
+                    clamped += calibrated.iter().filter(|c| c.clamped).count();
+                    node.send_output(
+                        output.clone(),
+                        metadata.parameters,
+                        to_json_rows(&calibrated)?,
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("clamped {clamped} readings");
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use dora_node_api::{
+    ArrowData,
+    arrow::array::{AsArray, StringArray},
+};
+use eyre::{OptionExt, eyre};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+pub use example_node_utils::env_or;
+
+/// Sent by the sensor on `raw`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawReading {
+    pub seq: u64,
+    pub sensor: String,
+    pub value: f64,
+}
+
+/// Sent by the calibration node on `celsius`.
+///
+/// This is the schema that the contract tests check the sink's results against, so unknown
+/// fields are an error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Calibrated {
+    pub seq: u64,
+    pub sensor: String,
+    pub celsius: f64,
+    /// Whether the value was out of range and was clamped to `MIN` or `MAX`.
+    pub clamped: bool,
+}
+
+/// Streamed by the sink to `RESULTS_ADDR`, one JSON line per received reading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinkRecord {
+    /// When the sink received the reading, in milliseconds since the UNIX epoch.
+    pub received_ms: f64,
+    /// The reading as received, so that the tests can check it against [`Calibrated`].
+    pub reading: serde_json::Value,
+}
+
+/// Encodes `rows` as JSON strings, one per array element.
+pub fn to_json_rows<T: Serialize>(rows: &[T]) -> eyre::Result<StringArray> {
+    let rows = rows
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(StringArray::from(rows))
+}
+
+/// Decodes the JSON strings of an input, see [`to_json_rows`].
+pub fn from_json_rows<T: DeserializeOwned>(data: &ArrowData) -> eyre::Result<Vec<T>> {
+    let rows = data
+        .as_string_opt::<i32>()
+        .ok_or_eyre("expected a Utf8 array")?;
+    rows.iter()
+        .flatten()
+        .map(|row| serde_json::from_str(row).map_err(|err| eyre!("invalid row {row}: {err}")))
+        .collect()
+}
@@ -0,0 +1,48 @@
+use contract_tests_nodes::{RawReading, env_or, to_json_rows};
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+
+/// Sends a raw temperature reading on `raw` on every tick, `COUNT` times.
+///
+/// Every 50th reading is a spike far outside of the valid range, which the calibration node
+/// has to clamp.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 300)?;
+    let sensor: String = env_or("SENSOR_ID", "thermo-1".to_owned())?;
+    let output = DataId::from("raw".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let value = if seq % 50 == 49 {
+                        500.0
+                    } else {
+                        22.0 + 15.0 * (seq as f64 / 20.0).sin()
+                    };
+                    let reading = RawReading {
+                        seq,
+                        sensor: sensor.clone(),
+                        value,
+                    };
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        to_json_rows(&[reading])?,
+                    )?;
+                    seq += 1;
+                    if seq == count {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("sent {seq} readings");
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use contract_tests_nodes::{SinkRecord, from_json_rows};
+use dora_node_api::{self, DoraNode, Event};
+use eyre::Context;
+use std::{
+    io::{BufWriter, Write},
+    net::TcpStream,
+    time::SystemTime,
+};
+
+/// Streams the `celsius` readings that it receives to `RESULTS_ADDR`, as one JSON
+/// [`SinkRecord`] per line. The contract tests listen on that address.
+///
+/// Prints the readings instead if `RESULTS_ADDR` is not set, e.g. in a plain `dora run`.
+fn main() -> eyre::Result<()> {
+    let mut results: Box<dyn Write> = match std::env::var("RESULTS_ADDR") {
+        Ok(addr) => {
+            let stream = TcpStream::connect(&addr)
+                .with_context(|| format!("failed to connect to {addr}"))?;
+            Box::new(BufWriter::new(stream))
+        }
+        Err(_) => Box::new(std::io::stdout()),
+    };
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut received = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "celsius" => {
+                    let received_ms = SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)?
+                        .as_secs_f64()
+                        * 1000.0;
+                    for reading in from_json_rows::<serde_json::Value>(&data)? {
+                        let record = SinkRecord {
+                            received_ms,
+                            reading,
+                        };
+                        writeln!(results, "{}", serde_json::to_string(&record)?)?;
+                        received += 1;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    // closes the connection, which tells the tests that all results are in
+    results.flush()?;
+    println!("received {received} readings");
+    Ok(())
+}
@@ -0,0 +1,210 @@
+//! Contracts of `dataflow.yml`, checked on what arrives at its sink.
+//!
+//! The first test starts the dataflow through the dora checkout in `DORA`, like the example
+//! runners do, and the other tests share its results. The sink streams every reading that it
+//! receives to a listener of the tests, which collects them over a channel.
+
+use contract_tests_nodes::{Calibrated, SinkRecord};
+use example_runner_utils::{Dora, node_env};
+use eyre::{Context, bail};
+use std::{path::Path, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    net::TcpListener,
+    sync::{OnceCell, mpsc},
+};
+
+/// How long the dataflow may run, after it is built.
+const RUN_TIMEOUT: Duration = Duration::from_secs(120);
+/// How far the mean interval between readings may be off the tick of the sensor.
+const RATE_TOLERANCE: f64 = 0.25;
+/// The longest pause between two readings at the sink.
+const MAX_GAP_MS: f64 = 500.0;
+
+static RESULTS: OnceCell<Results> = OnceCell::const_new();
+
+struct Results {
+    descriptor: serde_yaml::Value,
+    records: Vec<SinkRecord>,
+}
+
+impl Results {
+    /// The readings, which must match the schema, see `readings_match_the_schema`.
+    fn readings(&self) -> Vec<Calibrated> {
+        self.records
+            .iter()
+            .map(|record| serde_json::from_value(record.reading.clone()))
+            .collect::<Result<_, _>>()
+            .expect("the sink received readings that don't match the schema")
+    }
+
+    fn node_env(&self, id: &str, key: &str) -> String {
+        node_env(&self.descriptor, id, key).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn node(&self, id: &str) -> &serde_yaml::Value {
+        self.descriptor["nodes"]
+            .as_sequence()
+            .and_then(|nodes| nodes.iter().find(|node| node["id"] == id))
+            .unwrap_or_else(|| panic!("dataflow has no node `{id}`"))
+    }
+}
+
+/// Runs the dataflow once, for all tests.
+async fn results() -> &'static Results {
+    RESULTS
+        .get_or_try_init(run_dataflow)
+        .await
+        .expect("failed to run the dataflow")
+}
+
+async fn run_dataflow() -> eyre::Result<Results> {
+    let dataflow = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../dataflow.yml")
+        .canonicalize()?;
+    let descriptor = serde_yaml::from_str(&std::fs::read_to_string(&dataflow)?)?;
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(&dataflow).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (records_tx, mut records_rx) = mpsc::unbounded_channel();
+    let collector = tokio::spawn(collect(listener, records_tx));
+
+    // the daemon passes `RESULTS_ADDR` on to the sink
+    let mut run = dora.cli(["daemon", "--run-dataflow"]);
+    run.arg(&dataflow)
+        .env("RESULTS_ADDR", addr.to_string())
+        .kill_on_drop(true);
+    let status = tokio::time::timeout(RUN_TIMEOUT, run.status())
+        .await
+        .context("the dataflow did not finish in time")??;
+    if !status.success() {
+        bail!("failed to run dataflow: {status}");
+    }
+    // the sink has closed its connection when it stopped
+    tokio::time::timeout(Duration::from_secs(10), collector)
+        .await
+        .context("the sink did not connect to the tests")???;
+
+    let mut records = Vec::new();
+    while let Some(record) = records_rx.recv().await {
+        records.push(record);
+    }
+    Ok(Results {
+        descriptor,
+        records,
+    })
+}
+
+/// Accepts the connection of the sink, and sends every record that it streams on `records`.
+async fn collect(
+    listener: TcpListener,
+    records: mpsc::UnboundedSender<SinkRecord>,
+) -> eyre::Result<()> {
+    let (stream, _) = listener.accept().await?;
+    let mut lines = BufReader::new(stream).lines();
+    while let Some(line) = lines.next_line().await? {
+        let record =
+            serde_json::from_str(&line).with_context(|| format!("invalid record `{line}`"))?;
+        if records.send(record).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn readings_match_the_schema() {
+    let results = results().await;
+    let sensor = results.node_env("sensor", "SENSOR_ID");
+
+    assert!(!results.records.is_empty(), "the sink received nothing");
+    for record in &results.records {
+        let reading: Calibrated = serde_json::from_value(record.reading.clone())
+            .unwrap_or_else(|err| panic!("`{}` doesn't match the schema: {err}", record.reading));
+        assert_eq!(
+            reading.sensor, sensor,
+            "reading {} is from the wrong sensor",
+            reading.seq
+        );
+        assert!(
+            reading.celsius.is_finite(),
+            "reading {} is not finite",
+            reading.seq
+        );
+    }
+}
+
+#[tokio::test]
+async fn every_reading_arrives_once_and_in_order() {
+    let results = results().await;
+    let count: u64 = results.node_env("sensor", "COUNT").parse().unwrap();
+
+    let seqs: Vec<u64> = results
+        .readings()
+        .iter()
+        .map(|reading| reading.seq)
+        .collect();
+    assert_eq!(seqs, (0..count).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn values_stay_in_range() {
+    let results = results().await;
+    let min: f64 = results.node_env("calibrate", "MIN").parse().unwrap();
+    let max: f64 = results.node_env("calibrate", "MAX").parse().unwrap();
+
+    let readings = results.readings();
+    for reading in &readings {
+        assert!(
+            (min..=max).contains(&reading.celsius),
+            "reading {} is out of range: {}",
+            reading.seq,
+            reading.celsius
+        );
+        if reading.clamped {
+            assert!(
+                reading.celsius == min || reading.celsius == max,
+                "clamped reading {} is not at a bound: {}",
+                reading.seq,
+                reading.celsius
+            );
+        }
+    }
+    // the sensor sends spikes, so the range must have been enforced at least once
+    assert!(
+        readings.iter().any(|reading| reading.clamped),
+        "no reading was clamped"
+    );
+}
+
+#[tokio::test]
+async fn rate_matches_the_tick() {
+    let results = results().await;
+    let tick = results.node("sensor")["inputs"]["tick"]
+        .as_str()
+        .and_then(|tick| tick.strip_prefix("dora/timer/millis/"))
+        .expect("the sensor should tick with a `dora/timer/millis` timer");
+    let period_ms: f64 = tick.parse().unwrap();
+
+    let intervals: Vec<f64> = results
+        .records
+        .windows(2)
+        .map(|pair| pair[1].received_ms - pair[0].received_ms)
+        .collect();
+    assert!(!intervals.is_empty(), "the sink received too few readings");
+    let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    let longest = intervals.iter().copied().fold(0.0, f64::max);
+    println!("mean interval {mean:.2} ms, longest {longest:.2} ms");
+
+    assert!(
+        (mean - period_ms).abs() <= period_ms * RATE_TOLERANCE,
+        "mean interval {mean:.2} ms, expected {period_ms} ms"
+    );
+    assert!(
+        longest <= MAX_GAP_MS,
+        "the sink received nothing for {longest:.2} ms"
+    );
+}
@@ -0,0 +1,139 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, node_env};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path};
+
+/// Must match `CRASH_DIR` in `dataflow.yml`.
+const CRASH_DIR: &str = "out/crashes";
+/// Must match `RECENT_MESSAGES` in `nodes/src/crash.rs`.
+const RECENT_MESSAGES: usize = 16;
+
+/// Subset of `CrashReport` in `nodes/src/crash.rs`.
+#[derive(Debug, Deserialize)]
+struct CrashReport {
+    node: String,
+    kind: String,
+    config: BTreeMap<String, String>,
+    message: Option<String>,
+    location: Option<String>,
+    backtrace: Option<String>,
+    signal_name: Option<String>,
+    recent_message_ids: Vec<u64>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("crash-reporting-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dataflow = Path::new("dataflow.yml");
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let panic_at: u64 = node_env(&descriptor, "processor", "PANIC_AT")?.parse()?;
+    let signal_at: u64 = node_env(&descriptor, "checker", "SIGNAL_AT")?.parse()?;
+
+    // reports of earlier runs have other pids, so they'd pile up
+    if Path::new(CRASH_DIR).exists() {
+        std::fs::remove_dir_all(CRASH_DIR).context("failed to clear crash dir")?;
+    }
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+    // the crashes are controlled, so a failed dataflow is expected
+    match dora.run_dataflow(dataflow).await {
+        Ok(()) => println!("dataflow finished"),
+        Err(err) => println!("dataflow failed, as expected: {err}"),
+    }
+
+    let mut reports = Vec::new();
+    for entry in std::fs::read_dir(CRASH_DIR).context("no crash reports were written")? {
+        let path = entry?.path();
+        let report: CrashReport = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .with_context(|| format!("invalid crash report {}", path.display()))?;
+        println!(
+            "{}: `{}` crashed with a {} after messages {:?}",
+            path.display(),
+            report.node,
+            report.kind,
+            report.recent_message_ids
+        );
+        reports.push(report);
+    }
+    let report = |node: &str, kind: &str| {
+        reports
+            .iter()
+            .find(|report| report.node == node && report.kind == kind)
+            .ok_or_else(|| eyre::eyre!("no {kind} report of `{node}`"))
+    };
+    if reports.iter().any(|report| report.node == "source") {
+        bail!("`source` didn't crash, but wrote a crash report");
+    }
+
+    // the processor panics on the first zero reading, i.e. multiple of 10, from `PANIC_AT` on
+    let panic = report("processor", "panic")?;
+    check_recent(panic, panic_at.div_ceil(10) * 10)?;
+    check_config(panic, "PANIC_AT", panic_at)?;
+    let message = panic.message.as_deref().unwrap_or_default();
+    if !message.contains("calibration failed") {
+        bail!("unexpected panic message `{message}`");
+    }
+    let location = panic.location.as_deref().unwrap_or_default();
+    if !location.contains("processor.rs") {
+        bail!("expected the panic to be located in `processor.rs`, got `{location}`");
+    }
+    let backtrace = panic.backtrace.as_deref().unwrap_or_default();
+    if backtrace.lines().count() < 2 {
+        bail!("panic report has no backtrace");
+    }
+    println!("panic report of `processor` is complete: {message} at {location}");
+
+    if cfg!(target_os = "linux") {
+        let signal = report("checker", "signal")?;
+        check_recent(signal, signal_at)?;
+        check_config(signal, "SIGNAL_AT", signal_at)?;
+        if signal.signal_name.as_deref() != Some("SIGSEGV") {
+            bail!("expected SIGSEGV, got {:?}", signal.signal_name);
+        }
+        println!("signal report of `checker` is complete");
+    }
+    Ok(())
+}
+
+/// Checks that the report ends with the message that caused the crash.
+fn check_recent(report: &CrashReport, crashed_at: u64) -> eyre::Result<()> {
+    let ids = &report.recent_message_ids;
+    if ids.last() != Some(&crashed_at) {
+        bail!(
+            "expected the last message of `{}` to be {crashed_at}, got {ids:?}",
+            report.node
+        );
+    }
+    if ids.len() != RECENT_MESSAGES.min(crashed_at as usize) {
+        bail!(
+            "expected {RECENT_MESSAGES} recent messages of `{}`",
+            report.node
+        );
+    }
+    if !ids.is_sorted() {
+        bail!(
+            "recent messages of `{}` are out of order: {ids:?}",
+            report.node
+        );
+    }
+    Ok(())
+}
+
+fn check_config(report: &CrashReport, key: &str, expected: u64) -> eyre::Result<()> {
+    let value = report.config.get(key);
+    if value != Some(&expected.to_string()) {
+        bail!(
+            "expected `{key}: {expected}` in the config of `{}`, got {value:?}",
+            report.node
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Checks readings, and on Linux raises `SIGSEGV` at message `SIGNAL_AT`, like a crash in
+//! native code that no panic hook would see.
+
+use crash_reporting_dataflow_nodes::{crash, env_or, seq};
+use dora_node_api::{DoraNode, Event};
+
+fn main() -> eyre::Result<()> {
+    // never crashes if unset
+    let signal_at = env_or("SIGNAL_AT", u64::MAX)?;
+    let max = env_or("MAX_READING", 9.0)?;
+
+    let (node, mut events) = DoraNode::init_from_env()?;
+    crash::Reporter::new(node.id().to_string())
+        .config("SIGNAL_AT", signal_at)
+        .config("MAX_READING", max)
+        .install()?;
+
+    let mut out_of_range = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "reading" => {
+                    let seq = seq(&metadata.parameters)?;
+                    crash::record(seq);
+                    if seq == signal_at {
+                        crash();
+                    }
+                    if f64::try_from(&data)? > max {
+                        out_of_range += 1;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("{out_of_range} readings out of range");
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn crash() {
+    // SAFETY: raising a signal is safe, the handler of the crash reporter takes it from here
+    unsafe { libc::raise(libc::SIGSEGV) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn crash() {
+    eprintln!("signal handlers are only installed on Linux, not crashing");
+}
@@ -0,0 +1,272 @@
+//! Crash reports for nodes: a panic hook and, on Linux, handlers for fatal signals that write a
+//! JSON report to a crash directory before the node dies.
+//!
+//! Reports contain the node id, the configuration that the node registered, and the ids of the
+//! last [`RECENT_MESSAGES`] messages that were passed to [`record`]. Panic reports also contain
+//! the panic message, its location, and a backtrace.
+//!
+//! Signal handlers may only call async-signal-safe functions, so they can't allocate, lock, or
+//! capture a backtrace. Everything except the signal and the message ids is serialized when the
+//! reporter is installed, and the handler writes the report with plain `open`/`write` calls.
+
+use eyre::Context;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+/// How many message ids a report contains.
+pub const RECENT_MESSAGES: usize = 16;
+
+static RECENT: [AtomicU64; RECENT_MESSAGES] = [const { AtomicU64::new(0) }; RECENT_MESSAGES];
+static RECORDED: AtomicUsize = AtomicUsize::new(0);
+
+/// Remembers the id of a message for crash reports.
+///
+/// Call it when a message arrives, before processing it, so that a report includes the message
+/// that caused the crash.
+pub fn record(message_id: u64) {
+    let index = RECORDED.fetch_add(1, Ordering::Relaxed);
+    RECENT[index % RECENT_MESSAGES].store(message_id, Ordering::Relaxed);
+}
+
+/// The last recorded message ids, oldest first. Doesn't allocate.
+fn recent(buffer: &mut [u64; RECENT_MESSAGES]) -> &[u64] {
+    let recorded = RECORDED.load(Ordering::Relaxed);
+    let count = recorded.min(RECENT_MESSAGES);
+    for (i, index) in (recorded - count..recorded).enumerate() {
+        buffer[i] = RECENT[index % RECENT_MESSAGES].load(Ordering::Relaxed);
+    }
+    &buffer[..count]
+}
+
+/// A crash report, as written to `<crash dir>/<node>-<pid>-<kind>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub node: String,
+    /// `panic` or `signal`.
+    pub kind: String,
+    pub config: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal_name: Option<String>,
+    pub recent_message_ids: Vec<u64>,
+}
+
+/// Installs the crash handlers of a node.
+#[derive(Debug, Clone)]
+pub struct Reporter {
+    node: String,
+    dir: PathBuf,
+    config: BTreeMap<String, String>,
+}
+
+impl Reporter {
+    /// Writes reports to `CRASH_DIR`, or to `out/crashes` if it's unset.
+    pub fn new(node: impl Into<String>) -> Self {
+        let dir = std::env::var_os("CRASH_DIR").unwrap_or_else(|| "out/crashes".into());
+        Self {
+            node: node.into(),
+            dir: dir.into(),
+            config: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a configuration value to the reports.
+    pub fn config(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.config.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// Installs the panic hook and, on Linux, the signal handlers. Panics still run the
+    /// previous hook and signals still terminate the node after the report is written.
+    pub fn install(self) -> eyre::Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create crash dir {}", self.dir.display()))?;
+        let path = |kind: &str| {
+            self.dir
+                .join(format!("{}-{}-{kind}.json", self.node, std::process::id()))
+        };
+
+        #[cfg(target_os = "linux")]
+        signals::install(&path("signal"), &self.node, &self.config)?;
+
+        let panic_path = path("panic");
+        let Self { node, config, .. } = self;
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned());
+            let mut buffer = [0; RECENT_MESSAGES];
+            let report = CrashReport {
+                node: node.clone(),
+                kind: "panic".to_owned(),
+                config: config.clone(),
+                message,
+                location: info
+                    .location()
+                    .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+                thread: std::thread::current().name().map(str::to_owned),
+                backtrace: Some(std::backtrace::Backtrace::force_capture().to_string()),
+                signal: None,
+                signal_name: None,
+                recent_message_ids: recent(&mut buffer).to_vec(),
+            };
+            match write_report(&panic_path, &report) {
+                Ok(()) => eprintln!("crash report written to {}", panic_path.display()),
+                Err(err) => eprintln!("failed to write crash report: {err:?}"),
+            }
+            previous(info);
+        }));
+        Ok(())
+    }
+}
+
+fn write_report(path: &Path, report: &CrashReport) -> eyre::Result<()> {
+    let json = serde_json::to_string_pretty(report)?;
+    std::fs::write(path, json).with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(target_os = "linux")]
+mod signals {
+    use super::{CrashReport, RECENT_MESSAGES, recent};
+    use eyre::{Context, bail};
+    use std::{
+        collections::BTreeMap, ffi::CString, os::unix::ffi::OsStrExt, path::Path, sync::OnceLock,
+    };
+
+    const SIGNALS: &[(libc::c_int, &str)] = &[
+        (libc::SIGSEGV, "SIGSEGV"),
+        (libc::SIGBUS, "SIGBUS"),
+        (libc::SIGILL, "SIGILL"),
+        (libc::SIGFPE, "SIGFPE"),
+        (libc::SIGABRT, "SIGABRT"),
+    ];
+
+    struct Prepared {
+        path: CString,
+        /// The report up to the value of `signal`.
+        head: Vec<u8>,
+    }
+
+    static PREPARED: OnceLock<Prepared> = OnceLock::new();
+
+    pub fn install(path: &Path, node: &str, config: &BTreeMap<String, String>) -> eyre::Result<()> {
+        let report = CrashReport {
+            node: node.to_owned(),
+            kind: "signal".to_owned(),
+            config: config.clone(),
+            message: None,
+            location: None,
+            thread: None,
+            backtrace: None,
+            signal: None,
+            signal_name: None,
+            recent_message_ids: Vec::new(),
+        };
+        // `{"node":..,"kind":"signal","config":{..},"recent_message_ids":[]}`, with the ids
+        // written last by the handler
+        let json = serde_json::to_string(&report)?;
+        let Some(head) = json.strip_suffix(r#","recent_message_ids":[]}"#) else {
+            bail!("unexpected serialization of crash report: {json}");
+        };
+        let prepared = Prepared {
+            path: CString::new(path.as_os_str().as_bytes())?,
+            head: format!(r#"{head},"signal":"#).into_bytes(),
+        };
+        if PREPARED.set(prepared).is_err() {
+            bail!("signal handlers are already installed");
+        }
+
+        for &(signal, name) in SIGNALS {
+            // SAFETY: `handle` only calls async-signal-safe functions
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = handle as extern "C" fn(libc::c_int) as libc::sighandler_t;
+                // run on the alternate stack that Rust sets up, so that stack overflows are
+                // reported too, and restore the default action for the re-raised signal
+                action.sa_flags = libc::SA_ONSTACK | libc::SA_RESETHAND;
+                libc::sigemptyset(&mut action.sa_mask);
+                if libc::sigaction(signal, &action, std::ptr::null_mut()) != 0 {
+                    return Err(std::io::Error::last_os_error())
+                        .with_context(|| format!("failed to install handler for {name}"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    extern "C" fn handle(signal: libc::c_int) {
+        if let Some(prepared) = PREPARED.get() {
+            // SAFETY: `open`, `write`, and `close` are async-signal-safe
+            unsafe {
+                let fd = libc::open(
+                    prepared.path.as_ptr(),
+                    libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC | libc::O_CLOEXEC,
+                    0o644,
+                );
+                if fd >= 0 {
+                    let mut number = [0; 20];
+                    write_all(fd, &prepared.head);
+                    write_all(fd, format_u64(signal as u64, &mut number));
+                    write_all(fd, br#","signal_name":""#);
+                    let name = SIGNALS
+                        .iter()
+                        .find(|(s, _)| *s == signal)
+                        .map_or("unknown", |(_, name)| name);
+                    write_all(fd, name.as_bytes());
+                    write_all(fd, br#"","recent_message_ids":["#);
+                    let mut buffer = [0; RECENT_MESSAGES];
+                    for (i, id) in recent(&mut buffer).iter().enumerate() {
+                        if i > 0 {
+                            write_all(fd, b",");
+                        }
+                        write_all(fd, format_u64(*id, &mut number));
+                    }
+                    write_all(fd, b"]}\n");
+                    libc::close(fd);
+                }
+                const MESSAGE: &[u8] = b"fatal signal, crash report written\n";
+                write_all(libc::STDERR_FILENO, MESSAGE);
+            }
+        }
+        // `SA_RESETHAND` restored the default action, so this terminates the node
+        unsafe { libc::raise(signal) };
+    }
+
+    unsafe fn write_all(fd: libc::c_int, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            let written = unsafe { libc::write(fd, bytes.as_ptr().cast(), bytes.len()) };
+            if written <= 0 {
+                return;
+            }
+            bytes = &bytes[written as usize..];
+        }
+    }
+
+    fn format_u64(mut value: u64, buffer: &mut [u8; 20]) -> &[u8] {
+        let mut start = buffer.len();
+        loop {
+            start -= 1;
+            buffer[start] = b'0' + (value % 10) as u8;
+            value /= 10;
+            if value == 0 {
+                return &buffer[start..];
+            }
+        }
+    }
+}
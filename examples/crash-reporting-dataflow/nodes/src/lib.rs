@@ -0,0 +1,24 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{bail, eyre};
+
+pub use example_node_utils::env_or;
+
+pub mod crash;
+
+/// Metadata key of the id of a message, assigned by the source.
+pub const SEQ_KEY: &str = "seq";
+
+pub fn seq_parameters(seq: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+    parameters
+}
+
+pub fn seq(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(SEQ_KEY) {
+        Some(Parameter::Integer(seq)) => {
+            u64::try_from(*seq).map_err(|_| eyre!("negative `{SEQ_KEY}` parameter"))
+        }
+        other => bail!("expected integer `{SEQ_KEY}` parameter, got {other:?}"),
+    }
+}
@@ -0,0 +1,44 @@
+//! Scales readings, and panics on a zero reading once `PANIC_AT` messages were processed.
+
+use crash_reporting_dataflow_nodes::{crash, env_or, seq};
+use dora_node_api::{DoraNode, Event};
+
+fn main() -> eyre::Result<()> {
+    // never panics if unset
+    let panic_at = env_or("PANIC_AT", u64::MAX)?;
+    let gain = env_or("GAIN", 2.5)?;
+
+    let (node, mut events) = DoraNode::init_from_env()?;
+    crash::Reporter::new(node.id().to_string())
+        .config("PANIC_AT", panic_at)
+        .config("GAIN", gain)
+        .install()?;
+
+    let mut scaled = 0.0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "reading" => {
+                    let seq = seq(&metadata.parameters)?;
+                    crash::record(seq);
+                    let reading = f64::try_from(&data)?;
+                    scaled += scale(reading, gain, seq >= panic_at);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("sum of scaled readings: {scaled}");
+    Ok(())
+}
+
+fn scale(reading: f64, gain: f64, strict: bool) -> f64 {
+    if strict && reading == 0.0 {
+        // the controlled crash
+        panic!("calibration failed: reading is zero (gain {gain})");
+    }
+    reading * gain
+}
@@ -0,0 +1,37 @@
+use crash_reporting_dataflow_nodes::{crash, env_or, seq_parameters};
+use dora_node_api::{DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use std::time::{Duration, Instant};
+
+fn main() -> eyre::Result<()> {
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    crash::Reporter::new(node.id().to_string())
+        .config("DURATION_MS", duration.as_millis())
+        .install()?;
+
+    let output = DataId::from("reading".to_owned());
+    let start = Instant::now();
+    let mut seq = 0u64;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    seq += 1;
+                    crash::record(seq);
+                    // a reading that the processor divides by
+                    let reading = (seq % 10) as f64;
+                    node.send_output(output.clone(), seq_parameters(seq), reading.into_arrow())?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
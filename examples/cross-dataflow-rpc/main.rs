@@ -0,0 +1,172 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail, eyre};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path, time::Duration};
+use tokio::{
+    net::TcpStream,
+    process::Child,
+    time::{Instant, sleep},
+};
+
+/// Must match `GOALS` in `dataflow_mission.yml`, of which one is a wall.
+const GOALS: u64 = 7;
+const BLOCKED_GOAL: [usize; 2] = [9, 2];
+/// Of the shortest paths between the other goals in `map.txt`.
+const STEPS: u64 = 94;
+/// The listen endpoint in `config/planner.json5`.
+const PLANNER_ADDRESS: &str = "127.0.0.1:7460";
+
+/// Subset of `ServiceReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ServiceReport {
+    queries: u64,
+    delayed: u64,
+    plans: u64,
+    errors: BTreeMap<String, u64>,
+}
+
+/// Subset of `ClientReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ClientReport {
+    calls: u64,
+    attempts: u64,
+    timeouts: u64,
+    unavailable: u64,
+    retryable_errors: u64,
+    outcomes: BTreeMap<String, u64>,
+    latencies_ms: Vec<u64>,
+}
+
+/// Subset of `MissionReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct MissionReport {
+    reached: Vec<[usize; 2]>,
+    failed: Vec<FailedGoal>,
+    steps: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct FailedGoal {
+    goal: [usize; 2],
+    code: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("cross-dataflow-rpc-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dora = Dora::from_env()?;
+    let planner = Path::new("dataflow_planner.yml");
+    let mission = Path::new("dataflow_mission.yml");
+    dora.build_dataflow(planner).await?;
+    dora.build_dataflow(mission).await?;
+
+    // the two dataflows are deployed independently, each with its own daemon
+    let mut run = dora.cli(["daemon", "--run-dataflow"]);
+    run.arg(planner).kill_on_drop(true);
+    let mut planner_run = run.spawn().context("failed to run planner dataflow")?;
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while TcpStream::connect(PLANNER_ADDRESS).await.is_err() {
+        if Instant::now() > deadline {
+            bail!("the planner is not listening on {PLANNER_ADDRESS}");
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    // until all goals are done
+    dora.run_dataflow(mission).await?;
+    stop_dataflow(&mut planner_run).await?;
+
+    let service: ServiceReport = read_json("out/plan-service.json")?;
+    let client: ClientReport = read_json("out/plan-client.json")?;
+    let mission: MissionReport = read_json("out/mission.json")?;
+    let mut latencies = client.latencies_ms.clone();
+    latencies.sort_unstable();
+    println!(
+        "planner: {} queries, {} plans, errors {:?}, {} delayed",
+        service.queries, service.plans, service.errors, service.delayed
+    );
+    println!(
+        "client: {} calls in {} attempts, {} timeouts, {} unavailable, {} retryable errors, \
+         outcomes {:?}, latency p50 {} ms, max {} ms",
+        client.calls,
+        client.attempts,
+        client.timeouts,
+        client.unavailable,
+        client.retryable_errors,
+        client.outcomes,
+        latencies
+            .get(latencies.len() / 2)
+            .copied()
+            .unwrap_or_default(),
+        latencies.last().copied().unwrap_or_default(),
+    );
+    println!(
+        "mission: reached {} goals in {} steps, failed {:?}",
+        mission.reached.len(),
+        mission.steps,
+        mission.failed
+    );
+
+    let outcome = |name: &str| client.outcomes.get(name).copied().unwrap_or(0);
+    if client.calls != GOALS || outcome("ok") != GOALS - 1 || outcome("error") != 1 {
+        bail!("the client should get {} plans and 1 error", GOALS - 1);
+    }
+    if client.latencies_ms.len() as u64 != GOALS {
+        bail!("every call should have a latency");
+    }
+    // the planner answers every 4th query too late, the client must retry those
+    if client.timeouts == 0 || client.timeouts != service.delayed {
+        bail!(
+            "each of the {} delayed replies should time out once at the client",
+            service.delayed
+        );
+    }
+    if service.queries != client.attempts - client.unavailable {
+        bail!("each attempt that found the planner should be one query");
+    }
+    if service.errors.get("blocked").copied().unwrap_or(0) == 0 {
+        bail!("the planner should reject the goal in the wall");
+    }
+
+    if mission.reached.len() as u64 != GOALS - 1 {
+        bail!("the robot should reach all goals but the one in the wall");
+    }
+    let [failed] = &mission.failed[..] else {
+        bail!("exactly one goal should fail");
+    };
+    if failed.goal != BLOCKED_GOAL || failed.code.as_deref() != Some("blocked") {
+        bail!("{BLOCKED_GOAL:?} should be rejected as blocked, got {failed:?}");
+    }
+    if mission.steps != STEPS {
+        bail!("the planned paths should take {STEPS} steps, not {}", mission.steps);
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Stops the dataflow like ctrl-c, so that the nodes can write their reports.
+///
+/// `cargo run` replaces itself with `dora` on unix, so the child is the dora process.
+async fn stop_dataflow(run: &mut Child) -> eyre::Result<()> {
+    let pid = run.id().ok_or_else(|| eyre!("dataflow exited early"))?;
+    let mut stop = tokio::process::Command::new("kill");
+    stop.args(["-INT", &pid.to_string()]);
+    if !stop.status().await?.success() {
+        bail!("failed to stop dataflow");
+    }
+    if !run.wait().await?.success() {
+        bail!("failed to run dataflow");
+    }
+    Ok(())
+}
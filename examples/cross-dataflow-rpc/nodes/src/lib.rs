@@ -0,0 +1,251 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BinaryHeap},
+    path::Path,
+};
+use zenoh::{Config, Session, Wait};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// The key expression on which `plan-service` answers `PlanRequest`s.
+pub const SERVICE_KEY: &str = "rpc/planner/plan";
+/// Metadata key of the `map` output of `map-server`.
+pub const WIDTH_KEY: &str = "width";
+
+/// A cell of the map, as `[x, y]`.
+pub type Cell = [usize; 2];
+
+/// An occupancy grid, parsed from text with `#` for walls and `.` for free cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row, `true` if the cell is blocked.
+    pub blocked: Vec<bool>,
+}
+
+impl Grid {
+    pub fn parse(text: &str) -> eyre::Result<Self> {
+        let rows: Vec<&str> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        let width = rows.first().map_or(0, |row| row.len());
+        if width == 0 {
+            bail!("the map is empty");
+        }
+        let mut blocked = Vec::with_capacity(width * rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                bail!(
+                    "row {y} of the map has {} cells instead of {width}",
+                    row.len()
+                );
+            }
+            for (x, cell) in row.chars().enumerate() {
+                blocked.push(match cell {
+                    '#' => true,
+                    '.' => false,
+                    other => bail!("unexpected `{other}` at {x},{y} of the map"),
+                });
+            }
+        }
+        Ok(Self {
+            width,
+            height: rows.len(),
+            blocked,
+        })
+    }
+
+    /// The map is sent as a `UInt8` array, 1 for blocked cells, with the width as metadata.
+    pub fn from_arrow(cells: Vec<u8>, parameters: &MetadataParameters) -> eyre::Result<Self> {
+        let width = match parameters.get(WIDTH_KEY) {
+            Some(Parameter::Integer(width)) if *width > 0 => *width as usize,
+            other => bail!("expected a positive `{WIDTH_KEY}` parameter, got {other:?}"),
+        };
+        if !cells.len().is_multiple_of(width) {
+            bail!("{} cells are no multiple of the width {width}", cells.len());
+        }
+        Ok(Self {
+            width,
+            height: cells.len() / width,
+            blocked: cells.into_iter().map(|cell| cell != 0).collect(),
+        })
+    }
+
+    pub fn is_free(&self, [x, y]: Cell) -> bool {
+        x < self.width && y < self.height && !self.blocked[y * self.width + x]
+    }
+
+    /// The shortest 4-connected path from `start` to `goal`, both included, with A*.
+    pub fn plan(&self, start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+        let index = |[x, y]: Cell| y * self.width + x;
+        let heuristic = |[x, y]: Cell| x.abs_diff(goal[0]) + y.abs_diff(goal[1]);
+        let mut cost = vec![usize::MAX; self.blocked.len()];
+        let mut previous = vec![None; self.blocked.len()];
+        // ordered by the smallest estimate first
+        let mut open = BinaryHeap::new();
+        cost[index(start)] = 0;
+        open.push(std::cmp::Reverse((heuristic(start), start)));
+        while let Some(std::cmp::Reverse((_, cell))) = open.pop() {
+            if cell == goal {
+                let mut path = vec![goal];
+                while let Some(cell) = previous[index(*path.last().unwrap())] {
+                    path.push(cell);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let [x, y] = cell;
+            let neighbors = [
+                [x.wrapping_sub(1), y],
+                [x + 1, y],
+                [x, y.wrapping_sub(1)],
+                [x, y + 1],
+            ];
+            for next in neighbors.into_iter().filter(|next| self.is_free(*next)) {
+                let next_cost = cost[index(cell)] + 1;
+                if next_cost < cost[index(next)] {
+                    cost[index(next)] = next_cost;
+                    previous[index(next)] = Some(cell);
+                    open.push(std::cmp::Reverse((next_cost + heuristic(next), next)));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Payload of a query on `SERVICE_KEY`, and of the `request` input of `plan-client`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanRequest {
+    pub id: u64,
+    pub start: Cell,
+    pub goal: Cell,
+}
+
+/// Payload of a successful reply of `plan-service`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanReply {
+    pub id: u64,
+    pub path: Vec<Cell>,
+}
+
+/// Payload of an error reply of `plan-service`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanError {
+    pub id: Option<u64>,
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The planner dataflow did not receive its map yet.
+    NoMap,
+    BadRequest,
+    /// The start or the goal is a wall or outside of the map.
+    Blocked,
+    NoPath,
+}
+
+impl ErrorCode {
+    /// Whether the same request may succeed later. The others fail again on every retry.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::NoMap)
+    }
+}
+
+/// How a call of `plan-client` ended, after all attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Ok,
+    /// The planner answered with an error that is not retryable.
+    Error,
+    /// The last attempt got no reply within the timeout.
+    TimedOut,
+    /// The last attempt found no planner to answer it.
+    Unavailable,
+}
+
+/// Sent by `plan-client` on `response`, as a JSON string, once per `request`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanResponse {
+    pub id: u64,
+    pub outcome: Outcome,
+    pub attempts: u32,
+    /// From the request to the response, including the retries.
+    pub latency_ms: u64,
+    pub reply: Option<PlanReply>,
+    pub error: Option<PlanError>,
+}
+
+/// Written by `plan-service` when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ServiceReport {
+    pub queries: u64,
+    /// Replies that were delayed on purpose, see `SLOW_EVERY`.
+    pub delayed: u64,
+    pub plans: u64,
+    pub errors: BTreeMap<ErrorCode, u64>,
+}
+
+/// Written by `plan-client` when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ClientReport {
+    pub calls: u64,
+    pub attempts: u64,
+    /// Attempts without a reply within `TIMEOUT_MS`.
+    pub timeouts: u64,
+    /// Attempts that found no planner.
+    pub unavailable: u64,
+    /// Error replies that are worth a retry, e.g. while the planner has no map yet.
+    pub retryable_errors: u64,
+    pub outcomes: BTreeMap<Outcome, u64>,
+    /// Of the calls that got a reply.
+    pub latencies_ms: Vec<u64>,
+}
+
+/// Written by `mission-control` when the mission is done.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MissionReport {
+    pub goals: u64,
+    pub reached: Vec<Cell>,
+    pub failed: Vec<FailedGoal>,
+    /// Steps along all planned paths.
+    pub steps: u64,
+}
+
+/// A goal of the mission that could not be planned.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FailedGoal {
+    pub goal: Cell,
+    pub outcome: Outcome,
+    pub code: Option<ErrorCode>,
+}
+
+/// Parses `x,y` cells separated by `;`.
+pub fn parse_cells(text: &str) -> eyre::Result<Vec<Cell>> {
+    text.split(';')
+        .map(str::trim)
+        .filter(|cell| !cell.is_empty())
+        .map(|cell| {
+            let (x, y) = cell
+                .split_once(',')
+                .ok_or_else(|| eyre!("expected `x,y`, got `{cell}`"))?;
+            Ok([x.trim().parse()?, y.trim().parse()?])
+        })
+        .collect()
+}
+
+pub fn open_session(config: &Path) -> eyre::Result<Session> {
+    let config = Config::from_file(config)
+        .map_err(|err| eyre!("failed to load zenoh config {}: {err}", config.display()))?;
+    zenoh::open(config)
+        .wait()
+        .map_err(|err| eyre!("failed to open zenoh session: {err}"))
+}
@@ -0,0 +1,45 @@
+use cross_dataflow_rpc_nodes::{Grid, WIDTH_KEY, env_or};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::UInt8Array,
+    dora_core::config::DataId,
+};
+use eyre::Context;
+use std::{path::PathBuf, time::SystemTime};
+
+/// Sends the occupancy grid in `MAP` on `map`, on the first `tick` and whenever the file
+/// changes, so that the map can be edited while the planner runs.
+fn main() -> eyre::Result<()> {
+    let map_file: PathBuf = env_or("MAP", "map.txt".to_owned())?.into();
+    let output = DataId::from("map".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent: Option<SystemTime> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let modified = std::fs::metadata(&map_file)
+                        .and_then(|metadata| metadata.modified())
+                        .with_context(|| format!("failed to read {}", map_file.display()))?;
+                    if sent == Some(modified) {
+                        continue;
+                    }
+                    let grid = Grid::parse(&std::fs::read_to_string(&map_file)?)
+                        .with_context(|| format!("invalid map in {}", map_file.display()))?;
+                    println!("sending {}x{} map", grid.width, grid.height);
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(WIDTH_KEY.to_owned(), Parameter::Integer(grid.width as i64));
+                    let cells: UInt8Array = grid.blocked.iter().map(|&b| Some(b as u8)).collect();
+                    node.send_output(output.clone(), parameters, cells)?;
+                    sent = Some(modified);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
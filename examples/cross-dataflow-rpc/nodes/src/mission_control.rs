@@ -0,0 +1,111 @@
+use cross_dataflow_rpc_nodes::{
+    FailedGoal, MissionReport, Outcome, PlanRequest, PlanResponse, env_or, parse_cells, write_json,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+};
+use eyre::{Context, bail, eyre};
+use std::path::PathBuf;
+
+/// Drives the robot from `START` through the `GOALS`, one after the other, by sending a
+/// `PlanRequest` from its current cell to the next goal on `request`.
+///
+/// Waits for the `response` to a request before sending the next one on `tick`. The robot
+/// moves to the goal if a path was found, and stays where it is otherwise. Writes a
+/// `MissionReport` to `REPORT_FILE` once all goals are done.
+fn main() -> eyre::Result<()> {
+    let [start] = parse_cells(&env_or("START", "1,1".to_owned())?)?[..] else {
+        bail!("START must be a single `x,y` cell");
+    };
+    let goals = parse_cells(&env_or("GOALS", String::new())?)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/mission.json".to_owned())?.into();
+    let output = DataId::from("request".to_owned());
+    if goals.is_empty() {
+        bail!("no GOALS given");
+    }
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut position = start;
+    let mut pending: Option<PlanRequest> = None;
+    let mut report = MissionReport {
+        goals: goals.len() as u64,
+        ..Default::default()
+    };
+    let mut next_goal = goals.iter();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "tick" => {
+                    if pending.is_some() {
+                        continue;
+                    }
+                    let Some(&goal) = next_goal.next() else {
+                        break;
+                    };
+                    let request = PlanRequest {
+                        id: report.reached.len() as u64 + report.failed.len() as u64,
+                        start: position,
+                        goal,
+                    };
+                    println!("planning {:?} -> {goal:?}", request.start);
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        StringArray::from(vec![serde_json::to_string(&request)?]),
+                    )?;
+                    pending = Some(request);
+                }
+                "response" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_else(|| eyre!("expected JSON strings"))?;
+                    for row in rows.iter().flatten() {
+                        let response: PlanResponse =
+                            serde_json::from_str(row).context("invalid plan response")?;
+                        let Some(request) = pending.take_if(|request| request.id == response.id)
+                        else {
+                            eprintln!("Ignoring response to unknown request {}", response.id);
+                            continue;
+                        };
+                        match (response.outcome, response.reply) {
+                            (Outcome::Ok, Some(reply)) => {
+                                let steps = reply.path.len().saturating_sub(1);
+                                println!("reached {:?} in {steps} steps", request.goal);
+                                report.steps += steps as u64;
+                                report.reached.push(request.goal);
+                                position = request.goal;
+                            }
+                            (outcome, _) => {
+                                let code = response.error.map(|error| error.code);
+                                println!("skipping {:?}: {outcome:?} {code:?}", request.goal);
+                                report.failed.push(FailedGoal {
+                                    goal: request.goal,
+                                    outcome,
+                                    code,
+                                });
+                            }
+                        }
+                    }
+                    if pending.is_none() && next_goal.len() == 0 {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "mission done: reached {} of {} goals in {} steps",
+        report.reached.len(),
+        report.goals,
+        report.steps
+    );
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,161 @@
+use cross_dataflow_rpc_nodes::{
+    ClientReport, Outcome, PlanError, PlanReply, PlanRequest, PlanResponse, SERVICE_KEY, env_or,
+    open_session, write_json,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+};
+use eyre::{Context, eyre};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use zenoh::{Session, Wait, bytes::Encoding};
+
+/// Calls the planner of another dataflow for every JSON `PlanRequest` on `request`, and sends
+/// the result as JSON `PlanResponse` on `response`.
+///
+/// Each attempt waits up to `TIMEOUT_MS` for a reply. Attempts that time out, find no
+/// planner, or get a retryable error are retried up to `MAX_ATTEMPTS` in total, after a
+/// backoff that starts at `BACKOFF_MS` and doubles with every retry. Writes a `ClientReport`
+/// to `REPORT_FILE` when `request` is closed.
+fn main() -> eyre::Result<()> {
+    let config: PathBuf = env_or("ZENOH_CONFIG", "config/mission.json5".to_owned())?.into();
+    let policy = RetryPolicy {
+        timeout: Duration::from_millis(env_or("TIMEOUT_MS", 500)?),
+        max_attempts: env_or("MAX_ATTEMPTS", 4)?,
+        backoff: Duration::from_millis(env_or("BACKOFF_MS", 100)?),
+    };
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/plan-client.json".to_owned())?.into();
+    let output = DataId::from("response".to_owned());
+
+    let session = open_session(&config)?;
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = ClientReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "request" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_else(|| eyre!("expected JSON strings"))?;
+                    for row in rows.iter().flatten() {
+                        let request: PlanRequest =
+                            serde_json::from_str(row).context("invalid plan request")?;
+                        let response = call(&session, &request, &policy, &mut report)?;
+                        println!(
+                            "request {}: {:?} after {} attempts in {} ms",
+                            response.id, response.outcome, response.attempts, response.latency_ms
+                        );
+                        node.send_output(
+                            output.clone(),
+                            Default::default(),
+                            StringArray::from(vec![serde_json::to_string(&response)?]),
+                        )?;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "request" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "{} calls in {} attempts: {:?}",
+        report.calls, report.attempts, report.outcomes
+    );
+    write_json(&report_file, &report)
+}
+
+struct RetryPolicy {
+    timeout: Duration,
+    max_attempts: u32,
+    backoff: Duration,
+}
+
+/// Calls the planner until it answers with a plan or an error that is not retryable, or
+/// until the attempts are used up.
+fn call(
+    session: &Session,
+    request: &PlanRequest,
+    policy: &RetryPolicy,
+    report: &mut ClientReport,
+) -> eyre::Result<PlanResponse> {
+    let payload = serde_json::to_vec(request)?;
+    let start = Instant::now();
+    let mut response = PlanResponse {
+        id: request.id,
+        outcome: Outcome::Unavailable,
+        attempts: 0,
+        latency_ms: 0,
+        reply: None,
+        error: None,
+    };
+    while response.attempts < policy.max_attempts.max(1) {
+        if response.attempts > 0 {
+            std::thread::sleep(policy.backoff * 2u32.pow(response.attempts - 1));
+        }
+        response.attempts += 1;
+        response.error = None;
+        report.attempts += 1;
+
+        let sent = Instant::now();
+        let replies = session
+            .get(SERVICE_KEY)
+            .payload(payload.clone())
+            .encoding(Encoding::APPLICATION_JSON)
+            .timeout(policy.timeout)
+            .wait()
+            .map_err(|err| eyre!("failed to query `{SERVICE_KEY}`: {err}"))?;
+        // the channel is closed without a reply once the query times out, or right away if
+        // there is no queryable on the key
+        let Ok(reply) = replies.recv() else {
+            if sent.elapsed() >= policy.timeout / 2 {
+                report.timeouts += 1;
+                response.outcome = Outcome::TimedOut;
+            } else {
+                report.unavailable += 1;
+                response.outcome = Outcome::Unavailable;
+            }
+            continue;
+        };
+        match reply.result() {
+            Ok(sample) => {
+                let plan: PlanReply = serde_json::from_slice(&sample.payload().to_bytes())
+                    .context("invalid plan reply")?;
+                response.outcome = Outcome::Ok;
+                response.reply = Some(plan);
+                break;
+            }
+            Err(err) => {
+                let error: PlanError = serde_json::from_slice(&err.payload().to_bytes())
+                    .context("invalid error reply")?;
+                let retryable = error.code.is_retryable();
+                response.outcome = Outcome::Error;
+                response.error = Some(error);
+                if !retryable {
+                    break;
+                }
+                report.retryable_errors += 1;
+            }
+        }
+    }
+
+    response.latency_ms = start.elapsed().as_millis() as u64;
+    report.calls += 1;
+    *report.outcomes.entry(response.outcome).or_default() += 1;
+    if matches!(response.outcome, Outcome::Ok | Outcome::Error) {
+        report.latencies_ms.push(response.latency_ms);
+    }
+    Ok(response)
+}
@@ -0,0 +1,127 @@
+use cross_dataflow_rpc_nodes::{
+    ErrorCode, Grid, PlanError, PlanReply, PlanRequest, SERVICE_KEY, ServiceReport, env_or,
+    open_session, write_json,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{array::AsArray, datatypes::UInt8Type},
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::eyre;
+use std::{path::PathBuf, time::Duration};
+use zenoh::{Wait, bytes::Encoding, query::Query};
+
+/// Exposes path planning on the latest `map` to other dataflows, as a zenoh queryable on
+/// `SERVICE_KEY`.
+///
+/// Every query carries a JSON `PlanRequest`, and is answered with a `PlanReply`, or with a
+/// `PlanError` as error reply. To show how callers deal with a slow service, every
+/// `SLOW_EVERY`th query is answered after `SLOW_DELAY_MS`, on another thread so that the
+/// other queries are not held up. Writes a `ServiceReport` to `REPORT_FILE` when stopped.
+fn main() -> eyre::Result<()> {
+    let config: PathBuf = env_or("ZENOH_CONFIG", "config/planner.json5".to_owned())?.into();
+    let slow_every: u64 = env_or("SLOW_EVERY", 0)?;
+    let slow_delay = Duration::from_millis(env_or("SLOW_DELAY_MS", 1500)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/plan-service.json".to_owned())?.into();
+
+    let session = open_session(&config)?;
+    let queryable = session
+        .declare_queryable(SERVICE_KEY)
+        .wait()
+        .map_err(|err| eyre!("failed to declare queryable on `{SERVICE_KEY}`: {err}"))?;
+    println!("serving plans on `{SERVICE_KEY}` as {}", session.zid());
+
+    let (_node, events) = DoraNode::init_from_env()?;
+    let merged = events.merge_external(Box::pin(queryable.stream()));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut grid: Option<Grid> = None;
+    let mut report = ServiceReport::default();
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, metadata, data } => match id.as_str() {
+                    "map" => {
+                        let cells = data
+                            .as_primitive_opt::<UInt8Type>()
+                            .ok_or_else(|| eyre!("expected a UInt8 map"))?;
+                        let map = Grid::from_arrow(cells.values().to_vec(), &metadata.parameters)?;
+                        println!("received {}x{} map", map.width, map.height);
+                        grid = Some(map);
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => println!("Input `{id}` was closed"),
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(query) => {
+                report.queries += 1;
+                let result = plan(grid.as_ref(), &query);
+                match &result {
+                    Ok(_) => report.plans += 1,
+                    Err(error) => *report.errors.entry(error.code).or_default() += 1,
+                }
+                if slow_every > 0 && report.queries % slow_every == 0 {
+                    report.delayed += 1;
+                    std::thread::spawn(move || {
+                        std::thread::sleep(slow_delay);
+                        // the caller may have given up already, which is fine
+                        if let Err(err) = reply(&query, result) {
+                            eprintln!("{err}");
+                        }
+                    });
+                } else {
+                    reply(&query, result)?;
+                }
+            }
+        }
+    }
+
+    println!(
+        "answered {} queries: {} plans, errors {:?}, {} delayed",
+        report.queries, report.plans, report.errors, report.delayed
+    );
+    write_json(&report_file, &report)
+}
+
+fn plan(grid: Option<&Grid>, query: &Query) -> Result<PlanReply, PlanError> {
+    let error = |id, code, message: String| PlanError { id, code, message };
+    let request: PlanRequest = query
+        .payload()
+        .and_then(|payload| serde_json::from_slice(&payload.to_bytes()).ok())
+        .ok_or_else(|| {
+            let message = "expected a JSON plan request as payload".to_owned();
+            error(None, ErrorCode::BadRequest, message)
+        })?;
+    let id = Some(request.id);
+    let grid = grid.ok_or_else(|| error(id, ErrorCode::NoMap, "no map yet".to_owned()))?;
+    for (name, cell) in [("start", request.start), ("goal", request.goal)] {
+        if !grid.is_free(cell) {
+            let message = format!("the {name} {cell:?} is not a free cell of the map");
+            return Err(error(id, ErrorCode::Blocked, message));
+        }
+    }
+    let path = grid.plan(request.start, request.goal).ok_or_else(|| {
+        let message = format!("no path from {:?} to {:?}", request.start, request.goal);
+        error(id, ErrorCode::NoPath, message)
+    })?;
+    Ok(PlanReply {
+        id: request.id,
+        path,
+    })
+}
+
+fn reply(query: &Query, result: Result<PlanReply, PlanError>) -> eyre::Result<()> {
+    match result {
+        Ok(plan) => query
+            .reply(query.key_expr().clone(), serde_json::to_vec(&plan)?)
+            .encoding(Encoding::APPLICATION_JSON)
+            .wait(),
+        Err(error) => query
+            .reply_err(serde_json::to_vec(&error)?)
+            .encoding(Encoding::APPLICATION_JSON)
+            .wait(),
+    }
+    .map_err(|err| eyre!("failed to reply to `{}`: {err}", query.selector()))
+}
@@ -52,6 +52,7 @@ public:
     {
       if (!goal_handle) {
         RCLCPP_ERROR(this->get_logger(), "Goal was rejected by server");
+        rclcpp::shutdown();
       } else {
         RCLCPP_INFO(this->get_logger(), "Goal accepted by server, waiting for result");
       }
@@ -76,12 +77,15 @@ public:
           break;
         case rclcpp_action::ResultCode::ABORTED:
           RCLCPP_ERROR(this->get_logger(), "Goal was aborted");
+          rclcpp::shutdown();
           return;
         case rclcpp_action::ResultCode::CANCELED:
           RCLCPP_ERROR(this->get_logger(), "Goal was canceled");
+          rclcpp::shutdown();
           return;
         default:
           RCLCPP_ERROR(this->get_logger(), "Unknown result code");
+          rclcpp::shutdown();
           return;
       }
       std::stringstream ss;
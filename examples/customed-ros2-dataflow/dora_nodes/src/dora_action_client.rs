@@ -9,6 +9,8 @@ use dora_ros2_bridge::{
     ros2_client::{
         self, NodeOptions,
         action::{ActionClientQosPolicies, GoalId},
+        action_msgs::{CancelGoalResponseEnum, GoalStatusEnum},
+        builtin_interfaces,
     },
     rustdds::{self, policy},
 };
@@ -63,6 +65,13 @@ fn main() -> Result<(), Box<dyn Error>> {
     let action_stream = ActionEventStream::new(rx);
 
     let (node, dora_events) = DoraNode::init_from_env()?;
+    // with a `cancel` input, the goal is canceled on the first `cancel` event after it was
+    // accepted, and the example verifies that the server stops sending feedback
+    let cancel_enabled = node
+        .node_config()
+        .inputs
+        .keys()
+        .any(|id| id.as_str() == "cancel");
 
     println!("ROS2 Fibonacci action client initialized and ready");
 
@@ -70,6 +79,9 @@ fn main() -> Result<(), Box<dyn Error>> {
     let merged = dora_events.merge_external(Box::pin(action_stream));
     let mut events = futures::executor::block_on_stream(merged);
     let mut requesting = false;
+    let mut active_goal = None;
+    let mut cancel = CancelState::NotRequested;
+    let mut cancel_acknowledged = false;
 
     loop {
         let event = match events.next() {
@@ -88,6 +100,10 @@ fn main() -> Result<(), Box<dyn Error>> {
                         match id.as_str() {
                             "tick" => {
                                 if requesting {
+                                    if cancel_enabled {
+                                        // keep the goal running until it's canceled
+                                        continue;
+                                    }
                                     break;
                                 }
 
@@ -153,10 +169,10 @@ fn main() -> Result<(), Box<dyn Error>> {
                                                     match client.async_request_result(goal_id).await
                                                     {
                                                         Ok((status, result)) => {
-                                                            let sequence = result.sequence.clone();
                                                             let _ = tx_clone
                                                                 .clone()
                                                                 .send(FibonacciEvent::Result {
+                                                                    status,
                                                                     result,
                                                                 })
                                                                 .await;
@@ -202,6 +218,63 @@ fn main() -> Result<(), Box<dyn Error>> {
                                         eprintln!("Failed to spawn goal handler task: {:?}", e)
                                     });
                             }
+                            "cancel" => match (&cancel, active_goal) {
+                                (CancelState::NotRequested, Some(goal_id)) => {
+                                    println!("Requesting cancellation of goal {goal_id:?}");
+                                    cancel = CancelState::Requested;
+                                    let client = fib_client.clone();
+                                    let tx_clone = tx.clone();
+                                    pool.spawn(async move {
+                                        // a zero timestamp cancels only the given goal
+                                        let event = match client
+                                            .async_cancel_goal(
+                                                goal_id,
+                                                builtin_interfaces::Time::ZERO,
+                                            )
+                                            .await
+                                        {
+                                            Ok(response) => FibonacciEvent::CancelResponse {
+                                                accepted: response.return_code
+                                                    == CancelGoalResponseEnum::None
+                                                    && response
+                                                        .goals_canceling
+                                                        .iter()
+                                                        .any(|goal| goal.goal_id == goal_id),
+                                                return_code: response.return_code,
+                                            },
+                                            Err(e) => FibonacciEvent::Error {
+                                                message: format!(
+                                                    "Failed to cancel goal: {:#?}",
+                                                    e
+                                                ),
+                                            },
+                                        };
+                                        let _ = tx_clone.send(event).await;
+                                    })
+                                    .unwrap_or_else(|e| {
+                                        eprintln!("Failed to spawn cancel task: {:?}", e)
+                                    });
+                                }
+                                (CancelState::Finished { feedback_after }, _) => {
+                                    // a full feedback period passed since the goal was canceled
+                                    if !cancel_acknowledged {
+                                        return Err(eyre!(
+                                            "goal was canceled without a cancel response"
+                                        )
+                                        .into());
+                                    }
+                                    if *feedback_after > 0 {
+                                        return Err(eyre!(
+                                            "received {feedback_after} feedback messages after \
+                                             the goal was canceled"
+                                        )
+                                        .into());
+                                    }
+                                    println!("No feedback after cancellation, done");
+                                    break;
+                                }
+                                _ => {}
+                            },
                             other => eprintln!("Ignoring unexpected input `{other}`"),
                         }
                     }
@@ -215,6 +288,7 @@ fn main() -> Result<(), Box<dyn Error>> {
             MergedEvent::External(event) => match event {
                 FibonacciEvent::Accepted { goal_id, order } => {
                     requesting = true;
+                    active_goal = Some(goal_id);
                     println!(
                         "Fibonacci calculation started for order {}, goal_id: {:#?}",
                         order, goal_id
@@ -222,13 +296,43 @@ fn main() -> Result<(), Box<dyn Error>> {
                 }
                 FibonacciEvent::Feedback { feedback } => {
                     println!("Received Fibonacci feedback: {:#?}", feedback);
+                    if let CancelState::Finished { feedback_after } = &mut cancel {
+                        *feedback_after += 1;
+                    }
+                }
+                FibonacciEvent::CancelResponse {
+                    accepted,
+                    return_code,
+                } => {
+                    if !accepted {
+                        return Err(eyre!(
+                            "action server did not accept the cancellation: {return_code:?}"
+                        )
+                        .into());
+                    }
+                    println!("Action server acknowledged the cancellation");
+                    cancel_acknowledged = true;
                 }
-                FibonacciEvent::Result { result } => {
+                FibonacciEvent::Result { status, result } => {
+                    if cancel == CancelState::NotRequested {
+                        println!(
+                            "Fibonacci calculation completed. Final result is {:#?}",
+                            result
+                        );
+                        break;
+                    }
+                    if status != GoalStatusEnum::Canceled {
+                        return Err(eyre!(
+                            "expected the goal to be canceled, but it ended as {status:?}"
+                        )
+                        .into());
+                    }
                     println!(
-                        "Fibonacci calculation completed. Final result is {:#?}",
-                        result
+                        "Fibonacci goal canceled with partial result {:?}",
+                        result.sequence
                     );
-                    break;
+                    // wait for the next `cancel` event to check that no feedback follows
+                    cancel = CancelState::Finished { feedback_after: 0 };
                 }
                 FibonacciEvent::Error { message } => {
                     eprintln!("Fibonacci action error: {}", message);
@@ -257,10 +361,25 @@ fn init_ros_node() -> eyre::Result<ros2_client::Node> {
 enum FibonacciEvent {
     Accepted { goal_id: GoalId, order: i32 },
     Feedback { feedback: FibonacciFeedback },
-    Result { result: FibonacciResult },
+    CancelResponse {
+        accepted: bool,
+        return_code: CancelGoalResponseEnum,
+    },
+    Result {
+        status: GoalStatusEnum,
+        result: FibonacciResult,
+    },
     Error { message: String },
 }
 
+#[derive(Debug, PartialEq)]
+enum CancelState {
+    NotRequested,
+    Requested,
+    /// The goal ended as canceled, counting feedback messages that arrive afterwards.
+    Finished { feedback_after: usize },
+}
+
 // Stream adapter for Fibonacci events
 struct ActionEventStream {
     receiver: mpsc::Receiver<FibonacciEvent>,
@@ -0,0 +1,292 @@
+use dora_node_api::{
+    DoraNode, Event,
+    merged::{MergeExternal, MergedEvent},
+};
+use dora_ros2_bridge::{
+    messages::customed_interfaces::action::{Fibonacci, FibonacciFeedback, FibonacciResult},
+    ros2_client::{
+        self, NodeOptions,
+        action::{ActionServerQosPolicies, AsyncActionServer, GoalEndStatus, GoalId},
+    },
+    rustdds::{self, policy},
+};
+use eyre::{Context, eyre};
+use futures::{
+    FutureExt, StreamExt,
+    channel::mpsc::{self, Receiver, UnboundedSender},
+    task::SpawnExt,
+};
+use std::error::Error;
+
+/// Larger orders overflow the `int32` numbers of the sequence.
+const MAX_ORDER: i32 = 46;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // if set, the node fails on stop unless it completed exactly this many goals
+    let expected_goals = match std::env::var("EXPECTED_GOALS") {
+        Ok(value) => Some(value.parse::<usize>().context("invalid EXPECTED_GOALS")?),
+        Err(_) => None,
+    };
+
+    let mut ros_node = init_ros_node()?;
+
+    // spawn a background spinner task that handles service discovery (and other things)
+    let pool = futures::executor::ThreadPool::new()?;
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre::eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+
+    let qos = rustdds::QosPolicyBuilder::new()
+        .reliability(policy::Reliability::Reliable {
+            max_blocking_time: rustdds::Duration::from_millis(100),
+        })
+        .history(policy::History::KeepLast { depth: 1 })
+        .build();
+    let action_qos = ActionServerQosPolicies {
+        goal_service: qos.clone(),
+        result_service: qos.clone(),
+        cancel_service: qos.clone(),
+        feedback_publisher: qos.clone(),
+        status_publisher: qos.clone(),
+    };
+    let fib_server = ros_node.create_action_server::<Fibonacci>(
+        ros2_client::ServiceMapping::Enhanced,
+        &ros2_client::Name::new("/", "fibonacci").unwrap(),
+        &ros2_client::ActionTypeName::new("customed_interfaces", "Fibonacci"),
+        action_qos,
+    )?;
+
+    // the action server runs in its own task, and computes the next number of the sequence
+    // whenever the dora loop forwards a `tick` to it
+    let (tick_tx, tick_rx) = mpsc::channel(1);
+    let (event_tx, event_rx) = mpsc::unbounded();
+    pool.spawn(serve(AsyncActionServer::new(fib_server), tick_rx, event_tx))
+        .context("failed to spawn action server task")?;
+
+    let (_node, dora_events) = DoraNode::init_from_env()?;
+
+    println!("ROS2 Fibonacci action server initialized and ready");
+
+    let merged = dora_events.merge_external(Box::pin(event_rx));
+    let mut events = futures::executor::block_on_stream(merged);
+    let mut tick_tx = Some(tick_tx);
+    let mut completed = 0;
+
+    loop {
+        let event = match events.next() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input {
+                    id,
+                    metadata: _,
+                    data: _,
+                } => match id.as_str() {
+                    "tick" => {
+                        if let Some(tx) = &mut tick_tx {
+                            // the server only waits for ticks while it executes a goal, so
+                            // ticks in between are dropped instead of piling up
+                            let _ = tx.try_send(());
+                        }
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    if id.as_str() == "tick" {
+                        // aborts the goal that is executing, if any
+                        tick_tx = None;
+                    }
+                }
+                Event::Stop(_) => {
+                    println!("Received stop");
+                    break;
+                }
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(event) => match event {
+                ServerEvent::Rejected { order } => {
+                    println!("Rejected Fibonacci goal with order {order}");
+                }
+                ServerEvent::Accepted { goal_id, order } => {
+                    println!("Executing Fibonacci goal with order {order}, goal_id: {goal_id:?}");
+                }
+                ServerEvent::Feedback { sequence } => {
+                    println!("Sent Fibonacci feedback: {sequence:?}");
+                }
+                ServerEvent::Finished { end, sequence } => {
+                    println!("Fibonacci goal ended as {end:?} with {sequence:?}");
+                    if end == GoalEnd::Succeeded {
+                        completed += 1;
+                    }
+                }
+                ServerEvent::Error { message } => {
+                    eprintln!("Fibonacci action error: {message}");
+                }
+            },
+        }
+    }
+
+    println!("completed {completed} Fibonacci goals");
+    if let Some(expected) = expected_goals
+        && completed != expected
+    {
+        return Err(eyre!("expected {expected} completed goals, got {completed}").into());
+    }
+
+    Ok(())
+}
+
+/// Serves one goal after the other. The sequence grows by one number per tick, and every step
+/// is published as feedback, until the goal is done or canceled.
+async fn serve(
+    mut server: AsyncActionServer<Fibonacci>,
+    mut ticks: Receiver<()>,
+    events: UnboundedSender<ServerEvent>,
+) {
+    let send = |event| {
+        let _ = events.unbounded_send(event);
+    };
+    loop {
+        let new_goal = match server.receive_new_goal().await {
+            Ok(new_goal) => new_goal,
+            Err(e) => {
+                send(ServerEvent::Error {
+                    message: format!("Failed to receive goal: {e:?}"),
+                });
+                continue;
+            }
+        };
+        let order = new_goal.goal().order;
+        if !(1..=MAX_ORDER).contains(&order) {
+            if let Err(e) = server.reject_goal(new_goal).await {
+                send(ServerEvent::Error {
+                    message: format!("Failed to reject goal: {e:?}"),
+                });
+            }
+            send(ServerEvent::Rejected { order });
+            continue;
+        }
+        let goal = match server.accept_goal(new_goal).await {
+            Ok(accepted) => server.start_executing_goal(accepted).await,
+            Err(e) => Err(e),
+        };
+        let goal = match goal {
+            Ok(goal) => goal,
+            Err(e) => {
+                send(ServerEvent::Error {
+                    message: format!("Failed to accept goal: {e:?}"),
+                });
+                continue;
+            }
+        };
+        let goal_id = goal.goal_id();
+        send(ServerEvent::Accepted { goal_id, order });
+
+        // start counting with the next tick, not with one from before the goal
+        while let Ok(Some(())) = ticks.try_next() {}
+
+        let mut sequence = vec![0, 1];
+        let end = loop {
+            if sequence.len() > order as usize {
+                break GoalEnd::Succeeded;
+            }
+            let step = futures::select! {
+                tick = ticks.next() => Step::Tick(tick),
+                cancel = server.receive_cancel_request().fuse() => Step::Cancel(cancel),
+            };
+            match step {
+                Step::Tick(Some(())) => {
+                    let next = sequence[sequence.len() - 1] + sequence[sequence.len() - 2];
+                    sequence.push(next);
+                    let feedback = FibonacciFeedback {
+                        partial_sequence: sequence.clone(),
+                    };
+                    if let Err(e) = server.publish_feedback(goal.clone(), feedback).await {
+                        send(ServerEvent::Error {
+                            message: format!("Failed to publish feedback: {e:?}"),
+                        });
+                    }
+                    send(ServerEvent::Feedback {
+                        sequence: sequence.clone(),
+                    });
+                }
+                // the dataflow stops, the goal can't be finished anymore
+                Step::Tick(None) => break GoalEnd::Aborted,
+                Step::Cancel(Ok(cancel)) => {
+                    let canceled = cancel.contains_goal(&goal_id);
+                    let goals = canceled.then_some(goal_id).into_iter();
+                    if let Err(e) = server.respond_to_cancel_requests(&cancel, goals).await {
+                        send(ServerEvent::Error {
+                            message: format!("Failed to respond to cancel request: {e:?}"),
+                        });
+                    }
+                    if canceled {
+                        break GoalEnd::Canceled;
+                    }
+                }
+                Step::Cancel(Err(e)) => send(ServerEvent::Error {
+                    message: format!("Failed to receive cancel request: {e:?}"),
+                }),
+            }
+        };
+
+        let result = FibonacciResult {
+            sequence: sequence.clone(),
+        };
+        let status = match end {
+            GoalEnd::Succeeded => GoalEndStatus::Succeeded,
+            GoalEnd::Aborted => GoalEndStatus::Aborted,
+            GoalEnd::Canceled => GoalEndStatus::Canceled,
+        };
+        if let Err(e) = server.send_result_response(goal, status, result).await {
+            send(ServerEvent::Error {
+                message: format!("Failed to send result: {e:?}"),
+            });
+        }
+        send(ServerEvent::Finished { end, sequence });
+    }
+}
+
+fn init_ros_node() -> eyre::Result<ros2_client::Node> {
+    let ros_context = ros2_client::Context::new()
+        .map_err(|e| eyre::eyre!("failed to create ROS2 context: {e:?}"))?;
+
+    ros_context
+        .new_node(
+            ros2_client::NodeName::new("/dora", "fibonacci_action_server")
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre::eyre!("failed to create ros2 node: {e:?}"))
+}
+
+// Events of the action server task, for the dora loop
+enum ServerEvent {
+    Rejected { order: i32 },
+    Accepted { goal_id: GoalId, order: i32 },
+    Feedback { sequence: Vec<i32> },
+    Finished { end: GoalEnd, sequence: Vec<i32> },
+    Error { message: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GoalEnd {
+    Succeeded,
+    Aborted,
+    Canceled,
+}
+
+enum Step<C> {
+    Tick(Option<()>),
+    Cancel(C),
+}
@@ -14,7 +14,22 @@ use futures::task::SpawnExt;
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut ros_node = init_ros_node()?;
+    // the ROS2 namespace and service name can be set from the dataflow YAML, which allows
+    // running one instance per robot, e.g. `/robot1/add_three_ints` and `/robot2/add_three_ints`
+    let namespace = std::env::var("SERVICE_NAMESPACE").unwrap_or_else(|_| "/dora".to_owned());
+    let service_name =
+        std::env::var("SERVICE_NAME").unwrap_or_else(|_| "add_three_ints".to_owned());
+    // if set, the node fails on stop unless it served exactly this many requests
+    let expected_requests = match std::env::var("EXPECTED_REQUESTS") {
+        Ok(value) => Some(
+            value
+                .parse::<usize>()
+                .context("invalid EXPECTED_REQUESTS")?,
+        ),
+        Err(_) => None,
+    };
+
+    let mut ros_node = init_ros_node(&namespace)?;
 
     // spawn a background spinner task that is handles service discovery (and other things)
     let pool = futures::executor::ThreadPool::new()?;
@@ -39,7 +54,8 @@ fn main() -> Result<(), Box<dyn Error>> {
     };
     let add_server = ros_node.create_server::<AddThreeInts>(
         ros2_client::ServiceMapping::Enhanced,
-        &ros2_client::Name::new("/dora", "add_three_ints").unwrap(),
+        &ros2_client::Name::new(&namespace, &service_name)
+            .map_err(|e| eyre!("invalid service name `{namespace}/{service_name}`: {e}"))?,
         &ros2_client::ServiceTypeName::new("customed_interfaces", "AddThreeInts"),
         service_qos.clone(),
         service_qos.clone(),
@@ -49,6 +65,8 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let merged = dora_events.merge_external(Box::pin(add_server.receive_request_stream()));
     let mut events = futures::executor::block_on_stream(merged);
+    println!("serving `{namespace}/{service_name}`");
+    let mut served = 0;
 
     loop {
         let event = match events.next() {
@@ -82,20 +100,31 @@ fn main() -> Result<(), Box<dyn Error>> {
                     if let Err(e) = sr {
                         println!("Failed to send error {e:?}");
                     }
+                    served += 1;
                 }
             }
         }
     }
 
+    println!("served {served} requests on `{namespace}/{service_name}`");
+    if let Some(expected) = expected_requests
+        && served != expected
+    {
+        return Err(eyre!(
+            "expected {expected} requests on `{namespace}/{service_name}`, got {served}"
+        )
+        .into());
+    }
+
     Ok(())
 }
 
-fn init_ros_node() -> eyre::Result<ros2_client::Node> {
+fn init_ros_node(namespace: &str) -> eyre::Result<ros2_client::Node> {
     let ros_context = ros2_client::Context::new().unwrap();
 
     ros_context
         .new_node(
-            ros2_client::NodeName::new("/dora", "add_three_ints_server")
+            ros2_client::NodeName::new(namespace, "add_three_ints_server")
                 .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
             NodeOptions::new().enable_rosout(true),
         )
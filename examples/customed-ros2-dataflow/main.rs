@@ -1,5 +1,6 @@
 use dora_tracing::set_up_tracing;
-use eyre::{Context, bail};
+use example_runner_utils::Dora;
+use eyre::{Context, OptionExt, bail};
 use std::{env, path::Path};
 use tokio::process::Child;
 
@@ -12,11 +13,18 @@ async fn main() -> eyre::Result<()> {
         .wrap_err("failed to set working dir")?;
 
     // Get argument for which example to run
-    let args: Vec<String> = env::args().collect();
-    let (dataflow_file, ros_pkg, dora_is_server) = if args.len() > 1 {
-        match args[1].as_str() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    // pass `--skip-install` to build the ROS packages without installing their dependencies
+    // through `rosdep`, which needs sudo, e.g. in CI where they are installed already
+    let skip_install = args.iter().any(|arg| arg == "--skip-install");
+    let example = args.iter().find(|arg| !arg.starts_with("--"));
+    let (dataflow_file, ros_pkg, dora_is_server) = if let Some(example) = example {
+        match example.as_str() {
             "service" => ("dataflow.yml", "add_client", true),
             "action" => ("dataflow_action.yml", "fibonacci_server", false),
+            "action-cancel" => ("dataflow_action_cancel.yml", "fibonacci_server", false),
+            "action-server" => ("dataflow_action_server.yml", "fibonacci_client", true),
+            "namespaces" => ("dataflow_namespaces.yml", "add_client", true),
             other => {
                 println!("Unknown example: {}. Using default service example.", other);
                 ("dataflow.yml", "add_client", true)
@@ -33,7 +41,7 @@ async fn main() -> eyre::Result<()> {
 
     // Install ROS packages
     println!("Installing ROS packages...");
-    install_ros_pkg().await?;
+    install_ros_pkg(skip_install).await?;
 
     // Check if dataflow file exists
     let dataflow = Path::new(dataflow_file);
@@ -42,11 +50,26 @@ async fn main() -> eyre::Result<()> {
     }
 
     println!("Building dataflow: {}", dataflow.display());
-    build_dataflow(dataflow).await?;
-    let mut dataflow_process = run_dataflow(dataflow).await?;
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+    println!("Dataflow built successfully");
+    let mut dataflow_process = run_dataflow(&dora, dataflow).await?;
+
+    // the namespaced servers are isolated from each other, so the client is remapped to
+    // the `/robot1` instance only
+    let namespaces = dataflow_file == "dataflow_namespaces.yml";
+    let ros_args: &[&str] = if namespaces {
+        &[
+            "--ros-args",
+            "-r",
+            "/dora/add_three_ints:=/robot1/add_three_ints",
+        ]
+    } else {
+        &[]
+    };
 
     println!("Running ROS package: {}", ros_pkg);
-    let mut ros_node = run_ros_pkg(ros_pkg).await?;
+    let mut ros_node = run_ros_pkg(ros_pkg, ros_args).await?;
 
     // Different shutdown sequence based on whether Dora is server or client
     if dora_is_server {
@@ -55,24 +78,53 @@ async fn main() -> eyre::Result<()> {
         ros_node.wait().await?;
         println!("ROS client finished successfully");
 
-        // Clean shutdown of Dora server
-        println!("Shutting down Dora dataflow process...");
-        dataflow_process.kill().await?;
+        // these servers check what they served when they stop
+        let served_check = if namespaces {
+            Some("namespaced servers were not isolated from each other")
+        } else if dataflow_file == "dataflow_action_server.yml" {
+            Some("dora action server did not complete the goal")
+        } else {
+            None
+        };
+        if let Some(error) = served_check {
+            // stop the dataflow gracefully, so that the servers can check what they served
+            println!("Stopping Dora dataflow process...");
+            let pid = dataflow_process
+                .id()
+                .ok_or_eyre("dataflow process already exited")?;
+            let mut interrupt = tokio::process::Command::new("kill");
+            interrupt.args(["-INT", &pid.to_string()]);
+            if !interrupt.status().await?.success() {
+                bail!("failed to stop dataflow");
+            }
+            if !dataflow_process.wait().await?.success() {
+                bail!("{error}");
+            }
+        } else {
+            // Clean shutdown of Dora server
+            println!("Shutting down Dora dataflow process...");
+            dataflow_process.kill().await?;
+        }
     } else {
         // When Dora is client, we need to wait for ROS server to complete
         println!("Dora acting as client, waiting for ROS server to finish...");
 
-        dataflow_process.wait().await?;
+        let status = dataflow_process.wait().await?;
 
         println!("Shutting down ROS node...");
         ros_node.kill().await?;
+
+        // the cancellation example verifies the server's behavior in the dora node
+        if dataflow_file == "dataflow_action_cancel.yml" && !status.success() {
+            bail!("action cancellation check failed");
+        }
     }
 
     println!("Everything Done");
     Ok(())
 }
 
-async fn run_ros_pkg(node_name: &str) -> eyre::Result<Child> {
+async fn run_ros_pkg(node_name: &str, ros_args: &[&str]) -> eyre::Result<Child> {
     let ros_path = if let Ok(path) = std::env::var("ROS") {
         path
     } else {
@@ -81,7 +133,8 @@ async fn run_ros_pkg(node_name: &str) -> eyre::Result<Child> {
 
     println!("Executing ROS node: {}", node_name);
     let command = format!(
-        "source {ros_path}; source ./install/setup.bash; ros2 run customed_nodes {node_name}"
+        "source {ros_path}; source ./install/setup.bash; ros2 run customed_nodes {node_name} {}",
+        ros_args.join(" ")
     );
 
     let child = tokio::process::Command::new("bash")
@@ -92,17 +145,23 @@ async fn run_ros_pkg(node_name: &str) -> eyre::Result<Child> {
     Ok(child)
 }
 
-async fn install_ros_pkg() -> eyre::Result<()> {
+async fn install_ros_pkg(skip_install: bool) -> eyre::Result<()> {
     let ros_path = if let Ok(path) = std::env::var("ROS") {
         path
     } else {
         String::from("/opt/ros/jazzy/setup.bash")
     };
 
+    let install = if skip_install {
+        ""
+    } else {
+        "rosdep install --from-paths ./ -y --ignore-src; "
+    };
     println!("Installing ROS packages...");
     let mut cmd = tokio::process::Command::new("bash");
-    cmd.args(["-c",
-        &format!("source {ros_path}; rosdep install --from-paths ./ -y --ignore-src; colcon build --symlink-install"),
+    cmd.args([
+        "-c",
+        &format!("source {ros_path}; {install}colcon build --symlink-install"),
     ]);
     if !cmd.status().await?.success() {
         bail!("failed to install related package");
@@ -111,43 +170,12 @@ async fn install_ros_pkg() -> eyre::Result<()> {
     Ok(())
 }
 
-async fn build_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
-    let dora = std::env::var("DORA").context("DORA environment variable not set")?;
-
-    println!("Building dataflow: {}", dataflow.display());
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("build").arg(dataflow);
-
-    if !cmd.status().await?.success() {
-        bail!("failed to build dataflow");
-    };
-    println!("Dataflow built successfully");
-    Ok(())
-}
-
-async fn run_dataflow(dataflow: &Path) -> eyre::Result<Child> {
-    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
-    let dora = std::env::var("DORA").context("DORA environment variable not set")?;
-
+async fn run_dataflow(dora: &Dora, dataflow: &Path) -> eyre::Result<Child> {
     println!("Running dataflow: {}", dataflow.display());
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--")
-        .arg("daemon")
-        .arg("--run-dataflow")
-        .arg(dataflow);
-
-    let child = cmd.spawn()?;
+    let child = dora
+        .cli(["daemon", "--run-dataflow"])
+        .arg(dataflow)
+        .spawn()?;
     println!("Dataflow process started");
     Ok(child)
 }
@@ -0,0 +1,233 @@
+// RAII wrappers over the C++ API of dora, i.e. the `dora-node-api.h` that cxx generates.
+//
+// The generated API mirrors the Rust functions one to one: it hands out raw bytes, reports
+// send errors in a `DoraResult` that is easy to ignore, and leaves the lifetime of the node to
+// the caller. The wrappers below give it a C++ shape instead:
+//
+// - `dora::ScopedNode` owns the node, and closes its outputs when it goes out of scope.
+// - `dora::Span` is a typed view into the data of an input, without copying it.
+// - `dora::OutputBuilder` collects typed values and sends them as one output.
+//
+// Errors are thrown as exceptions: `dora::Error` for the wrappers, and `rust::Error` for the
+// functions of the bridge that return a `Result` in Rust.
+//
+// Header-only and C++17, copy it into your project next to the generated header.
+#pragma once
+
+#include "build/dora-node-api.h"
+
+#include <cstddef>
+#include <cstdint>
+#include <optional>
+#include <stdexcept>
+#include <string>
+#include <type_traits>
+#include <utility>
+#include <vector>
+
+namespace dora
+{
+
+    class Error : public std::runtime_error
+    {
+    public:
+        using std::runtime_error::runtime_error;
+    };
+
+    // A read-only view of `size` contiguous values, like the `std::span` of C++20.
+    template <typename T>
+    class Span
+    {
+    public:
+        Span() = default;
+        Span(const T *data, std::size_t size) : data_(data), size_(size) {}
+        Span(const std::vector<T> &values) : data_(values.data()), size_(values.size()) {}
+
+        const T *data() const { return data_; }
+        std::size_t size() const { return size_; }
+        bool empty() const { return size_ == 0; }
+
+        const T *begin() const { return data_; }
+        const T *end() const { return data_ + size_; }
+
+        const T &operator[](std::size_t i) const { return data_[i]; }
+        const T &front() const { return data_[0]; }
+        const T &back() const { return data_[size_ - 1]; }
+
+        // Bounds-checked access, throws `dora::Error` past the end.
+        const T &at(std::size_t i) const
+        {
+            if (i >= size_)
+            {
+                throw Error("index " + std::to_string(i) + " out of range for a span of " +
+                            std::to_string(size_) + " values");
+            }
+            return data_[i];
+        }
+
+    private:
+        const T *data_ = nullptr;
+        std::size_t size_ = 0;
+    };
+
+    // An input of the node. Owns its data, so the views returned by `bytes` and `values` are
+    // valid for as long as the input lives.
+    class Input
+    {
+    public:
+        explicit Input(DoraInput input) : input_(std::move(input)), id_(std::string(input_.id)) {}
+
+        const std::string &id() const { return id_; }
+
+        Span<uint8_t> bytes() const { return Span<uint8_t>(input_.data.data(), input_.data.size()); }
+
+        // Views the data as values of `T`, which the sender has to have sent in the byte order
+        // of this machine.
+        //
+        // Throws `dora::Error` if the data isn't a whole number of values, or isn't aligned for
+        // `T`; copy the bytes out of `bytes()` for data with an unknown alignment.
+        template <typename T>
+        Span<T> values() const
+        {
+            static_assert(std::is_trivially_copyable_v<T>, "inputs can only be viewed as plain values");
+            auto raw = bytes();
+            if (raw.size() % sizeof(T) != 0)
+            {
+                throw Error("input `" + id_ + "` has " + std::to_string(raw.size()) +
+                            " bytes, not a multiple of " + std::to_string(sizeof(T)));
+            }
+            if (reinterpret_cast<std::uintptr_t>(raw.data()) % alignof(T) != 0)
+            {
+                throw Error("input `" + id_ + "` is not aligned to " + std::to_string(alignof(T)) +
+                            " bytes");
+            }
+            return Span<T>(reinterpret_cast<const T *>(raw.data()), raw.size() / sizeof(T));
+        }
+
+    private:
+        DoraInput input_;
+        std::string id_;
+    };
+
+    // An event of the node, other than the end of the event stream.
+    class Event
+    {
+    public:
+        explicit Event(rust::Box<DoraEvent> event) : event_(std::move(event)), type_(event_type(event_)) {}
+
+        DoraEventType type() const { return type_; }
+        bool is_input() const { return type_ == DoraEventType::Input; }
+
+        // Takes the input out of the event, throws `dora::Error` if it's no input.
+        Input into_input() &&
+        {
+            if (!is_input())
+            {
+                throw Error("event of type " + std::to_string(static_cast<int>(type_)) +
+                            " is no input");
+            }
+            return Input(event_as_input(std::move(event_)));
+        }
+
+    private:
+        rust::Box<DoraEvent> event_;
+        DoraEventType type_;
+    };
+
+    // Collects values of `T` for output `id`, and sends them as one message.
+    //
+    // Refers to the output sender of the node it was created by, so it must not outlive it.
+    // Values that were added but not sent are dropped with the builder.
+    template <typename T>
+    class OutputBuilder
+    {
+        static_assert(std::is_trivially_copyable_v<T>, "outputs can only contain plain values");
+
+    public:
+        OutputBuilder(rust::Box<OutputSender> &sender, std::string id)
+            : sender_(&sender), id_(std::move(id))
+        {
+        }
+
+        const std::string &id() const { return id_; }
+        std::size_t size() const { return values_.size(); }
+        bool empty() const { return values_.empty(); }
+
+        OutputBuilder &push(const T &value)
+        {
+            values_.push_back(value);
+            return *this;
+        }
+
+        OutputBuilder &extend(Span<T> values)
+        {
+            values_.insert(values_.end(), values.begin(), values.end());
+            return *this;
+        }
+
+        void clear() { values_.clear(); }
+
+        // Sends the collected values, also if there are none, and starts over with an empty
+        // message. Throws `dora::Error` if sending failed.
+        void send()
+        {
+            rust::Slice<const uint8_t> data{reinterpret_cast<const uint8_t *>(values_.data()),
+                                            values_.size() * sizeof(T)};
+            auto result = send_output(*sender_, id_, data);
+            values_.clear();
+            auto error = std::string(result.error);
+            if (!error.empty())
+            {
+                throw Error("failed to send `" + id_ + "`: " + error);
+            }
+        }
+
+    private:
+        rust::Box<OutputSender> *sender_;
+        std::string id_;
+        std::vector<T> values_;
+    };
+
+    // A dora node, initialized from the environment that the daemon sets.
+    //
+    // Owns the event stream and the output sender of the node. They are dropped when the node
+    // goes out of scope, which closes its outputs, also when it's left through an exception.
+    class ScopedNode
+    {
+    public:
+        // Throws `rust::Error` if the node wasn't started by dora.
+        ScopedNode() : node_(init_dora_node()) {}
+
+        ScopedNode(const ScopedNode &) = delete;
+        ScopedNode &operator=(const ScopedNode &) = delete;
+
+        // Waits for the next event. Returns nothing once the event stream has ended, i.e.
+        // after the dataflow was stopped or all inputs were closed.
+        std::optional<Event> next()
+        {
+            if (ended_)
+            {
+                return std::nullopt;
+            }
+            Event event(node_.events->next());
+            if (event.type() == DoraEventType::Stop ||
+                event.type() == DoraEventType::AllInputsClosed)
+            {
+                ended_ = true;
+                return std::nullopt;
+            }
+            return event;
+        }
+
+        template <typename T>
+        OutputBuilder<T> output(std::string id)
+        {
+            return OutputBuilder<T>(node_.send_output, std::move(id));
+        }
+
+    private:
+        DoraNode node_;
+        bool ended_ = false;
+    };
+
+} // namespace dora
@@ -1,25 +1,18 @@
 use dora_tracing::set_up_tracing;
-use eyre::{Context, bail};
-use std::{
-    env::consts::{DLL_PREFIX, DLL_SUFFIX, EXE_SUFFIX},
-    path::Path,
-};
+use example_runner_utils::{Dora, NativeNode};
+use eyre::Context;
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     set_up_tracing("c++-dataflow-runner").wrap_err("failed to set up tracing")?;
 
-    if cfg!(windows) {
-        tracing::error!(
-            "The c++ example does not work on Windows currently because of a linker error"
-        );
-        return Ok(());
-    }
-
+    // pass `--cmake` to build the nodes with the `CMakeLists.txt` of the example instead
+    let cmake = std::env::args().any(|arg| arg == "--cmake");
     let root = Path::new(env!("CARGO_MANIFEST_DIR"));
-    let dora = std::path::PathBuf::from(std::env::var("DORA").unwrap());
+    let dora = Dora::from_env()?;
 
-    let target = dora.join("target");
+    let target = dora.target_dir();
     let target_triple = target.join(std::env::var("TARGET").unwrap_or_else(|_| {
         let os = match std::env::consts::OS {
             "macos" => "apple-darwin",
@@ -35,7 +28,7 @@ async fn main() -> eyre::Result<()> {
     tokio::fs::create_dir_all("build").await?;
     let build_dir = Path::new("build");
 
-    build_package("dora-node-api-cxx").await?;
+    dora.package("dora-node-api-cxx").build().await?;
     let node_cxxbridge = target_triple
         .join("cxxbridge")
         .join("dora-node-api-cxx")
@@ -51,151 +44,53 @@ async fn main() -> eyre::Result<()> {
     )
     .await?;
 
-    build_package("dora-node-api-c").await?;
+    dora.package("dora-node-api-c").build().await?;
 
     tokio::fs::copy(
-        dora.join("apis/c/node/node_api.h"),
+        dora.root().join("apis/c/node/node_api.h"),
         build_dir.join("node_api.h"),
     )
     .await?;
 
     let target_release = target_triple.join("release");
-    build_cxx_node(
-        &target_release,
-        &[
-            &dunce::canonicalize(Path::new("node-rust-api").join("main.cc"))?,
-            &dunce::canonicalize(build_dir.join("node-bridge.cc"))?,
-        ],
-        "node_rust_api",
-        &["-l", "dora_node_api_cxx"],
-    )
-    .await?;
-    build_cxx_node(
-        &target_release,
-        &[&dunce::canonicalize(
-            Path::new("node-c-api").join("main.cc"),
-        )?],
-        "node_c_api",
-        &["-l", "dora_node_api_c"],
-    )
-    .await?;
-
-    let dataflow = Path::new("dataflow.yml").to_owned();
-    run_dataflow(&dataflow).await?;
-
-    Ok(())
-}
-
-async fn build_package(package: &str) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new("bash");
-    let manifest = std::path::PathBuf::from(dora).join("Cargo.toml");
-    let manifest = manifest.to_str().unwrap();
-    cmd.args([
-        "-c",
-        &format!("cargo build --release --manifest-path {manifest} --package {package}",),
-    ]);
-    if !cmd.status().await?.success() {
-        bail!("failed to compile {package}");
-    };
-    Ok(())
-}
-
-async fn run_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--")
-        .arg("daemon")
-        .arg("--run-dataflow")
-        .arg(dataflow);
-    if !cmd.status().await?.success() {
-        bail!("failed to run dataflow");
-    };
-    Ok(())
-}
-
-async fn build_cxx_node(
-    target_release: &Path,
-    paths: &[&Path],
-    out_name: &str,
-    args: &[&str],
-) -> eyre::Result<()> {
-    let mut clang = tokio::process::Command::new("clang++");
-    clang.args(paths);
-    clang.arg("-std=c++17");
-    let arch = match std::env::consts::ARCH {
-        "aarch64" => "arm64",
-        other => other,
-    };
-    clang.arg("-arch").arg(arch);
-    #[cfg(target_os = "linux")]
-    {
-        clang.arg("-l").arg("m");
-        clang.arg("-l").arg("rt");
-        clang.arg("-l").arg("dl");
-        clang.arg("-l").arg("z");
-        clang.arg("-pthread");
+    if cmake {
+        dora.cmake(".")
+            .define("DORA_LIB_DIR", &target_release)
+            .build()
+            .await?;
+    } else {
+        NativeNode::cxx("node_rust_api")
+            .source(Path::new("node-rust-api").join("main.cc"))
+            .source(build_dir.join("node-bridge.cc"))
+            .link("dora_node_api_cxx")
+            .lib_dir(&target_release)
+            .build()
+            .await?;
+        NativeNode::cxx("node_async")
+            .source(Path::new("node-async").join("main.cc"))
+            .source(build_dir.join("node-bridge.cc"))
+            .link("dora_node_api_cxx")
+            .lib_dir(&target_release)
+            .build()
+            .await?;
+        NativeNode::cxx("node_raii")
+            .source(Path::new("node-raii").join("main.cc"))
+            .source(build_dir.join("node-bridge.cc"))
+            .link("dora_node_api_cxx")
+            .lib_dir(&target_release)
+            .build()
+            .await?;
+        NativeNode::cxx("node_c_api")
+            .source(Path::new("node-c-api").join("main.cc"))
+            .link("dora_node_api_c")
+            .lib_dir(&target_release)
+            .build()
+            .await?;
     }
-    #[cfg(target_os = "windows")]
-    {
-        clang.arg("-ladvapi32");
-        clang.arg("-luserenv");
-        clang.arg("-lkernel32");
-        clang.arg("-lws2_32");
-        clang.arg("-lbcrypt");
-        clang.arg("-lncrypt");
-        clang.arg("-lschannel");
-        clang.arg("-lntdll");
-        clang.arg("-liphlpapi");
-
-        clang.arg("-lcfgmgr32");
-        clang.arg("-lcredui");
-        clang.arg("-lcrypt32");
-        clang.arg("-lcryptnet");
-        clang.arg("-lfwpuclnt");
-        clang.arg("-lgdi32");
-        clang.arg("-lmsimg32");
-        clang.arg("-lmswsock");
-        clang.arg("-lole32");
-        clang.arg("-lopengl32");
-        clang.arg("-lsecur32");
-        clang.arg("-lshell32");
-        clang.arg("-lsynchronization");
-        clang.arg("-luser32");
-        clang.arg("-lwinspool");
 
-        clang.arg("-Wl,-nodefaultlib:libcmt");
-        clang.arg("-D_DLL");
-        clang.arg("-lmsvcrt");
-    }
-    #[cfg(target_os = "macos")]
-    {
-        clang.arg("-framework").arg("CoreServices");
-        clang.arg("-framework").arg("Security");
-        clang.arg("-l").arg("System");
-        clang.arg("-l").arg("resolv");
-        clang.arg("-l").arg("pthread");
-        clang.arg("-l").arg("c");
-        clang.arg("-l").arg("m");
-    }
-    clang.args(args);
-    clang.arg("-L").arg(target_release);
-    clang
-        .arg("--output")
-        .arg(Path::new("../build").join(format!("{out_name}{EXE_SUFFIX}")));
-    if let Some(parent) = paths[0].parent() {
-        clang.current_dir(parent);
-    }
+    let dataflow = Path::new("dataflow.yml");
+    validate_dataflows::check(dataflow)?;
+    dora.run_dataflow(dataflow).await?;
 
-    if !clang.status().await?.success() {
-        bail!("failed to compile c++ node");
-    };
     Ok(())
 }
@@ -0,0 +1,195 @@
+#include "../build/dora-node-api.h"
+
+#include <atomic>
+#include <chrono>
+#include <condition_variable>
+#include <deque>
+#include <functional>
+#include <iostream>
+#include <map>
+#include <mutex>
+#include <optional>
+#include <string>
+#include <thread>
+#include <vector>
+
+using namespace std::chrono_literals;
+
+// Receives the events of the node on a background thread, so that they can be polled with a
+// timeout.
+//
+// The C++ API only has a blocking `next()`, which would stall a thread that also has other
+// work to do. The receiver thread blocks on it instead, and hands the events over through a
+// queue.
+class EventQueue
+{
+public:
+    explicit EventQueue(rust::Box<Events> events)
+        : receiver([this, events = std::move(events)]() mutable
+                   { receive(*events); })
+    {
+    }
+
+    ~EventQueue()
+    {
+        receiver.join();
+    }
+
+    // Waits up to `timeout` for the next event, returns nothing if none arrived.
+    std::optional<rust::Box<DoraEvent>> poll(std::chrono::milliseconds timeout)
+    {
+        std::unique_lock lock(mutex);
+        if (!available.wait_for(lock, timeout, [this]
+                                { return !queue.empty(); }))
+        {
+            return std::nullopt;
+        }
+        auto event = std::move(queue.front());
+        queue.pop_front();
+        return event;
+    }
+
+private:
+    void receive(Events &events)
+    {
+        while (true)
+        {
+            auto event = events.next();
+            auto ty = event_type(event);
+            {
+                std::lock_guard lock(mutex);
+                queue.push_back(std::move(event));
+            }
+            available.notify_one();
+            // no events follow, return so that the destructor can join
+            if (ty == DoraEventType::AllInputsClosed || ty == DoraEventType::Stop)
+            {
+                return;
+            }
+        }
+    }
+
+    std::mutex mutex;
+    std::condition_variable available;
+    std::deque<rust::Box<DoraEvent>> queue;
+    // declared last, so that the queue exists when the thread starts
+    std::thread receiver;
+};
+
+// Stands in for a hardware loop, e.g. of a motor driver: every 20 ms, moves the position one
+// step towards the setpoint and sends it on `position`, whether or not inputs arrive.
+void hardware_loop(rust::Box<OutputSender> &sender, std::mutex &send_mutex,
+                   const std::atomic<unsigned int> &setpoint, const std::atomic<bool> &running)
+{
+    unsigned char position = 0;
+    while (running)
+    {
+        auto target = setpoint.load();
+        if (position < target)
+        {
+            position += 1;
+        }
+        else if (position > target)
+        {
+            position -= 1;
+        }
+
+        std::vector<unsigned char> out_vec{position};
+        rust::Slice<const uint8_t> out_slice{out_vec.data(), out_vec.size()};
+        DoraResult result;
+        {
+            // the sender is shared with the event loop, which sends on `status`
+            std::lock_guard lock(send_mutex);
+            result = send_output(sender, "position", out_slice);
+        }
+        auto error = std::string(result.error);
+        if (!error.empty())
+        {
+            std::cerr << "Error: " << error << std::endl;
+        }
+        std::this_thread::sleep_for(20ms);
+    }
+}
+
+int main()
+{
+    std::cout << "HELLO FROM C++ (async node)" << std::endl;
+
+    auto dora_node = init_dora_node();
+    EventQueue events(std::move(dora_node.events));
+
+    std::mutex send_mutex;
+    std::atomic<unsigned int> setpoint{0};
+    std::atomic<bool> running{true};
+    std::thread worker(hardware_loop, std::ref(dora_node.send_output), std::ref(send_mutex),
+                       std::cref(setpoint), std::cref(running));
+
+    // callback style: one handler per input id, called from the event loop below
+    std::map<std::string, std::function<void(DoraInput &)>> handlers;
+    handlers["setpoint"] = [&](DoraInput &input)
+    {
+        if (input.data.empty())
+        {
+            return;
+        }
+        // the counter of the other node, scaled to a position that takes a while to reach
+        setpoint = static_cast<unsigned int>(input.data[0]) * 10;
+        std::cout << "New setpoint " << setpoint << std::endl;
+
+        std::vector<unsigned char> out_vec{static_cast<unsigned char>(setpoint)};
+        rust::Slice<const uint8_t> out_slice{out_vec.data(), out_vec.size()};
+        std::lock_guard lock(send_mutex);
+        auto result = send_output(dora_node.send_output, "status", out_slice);
+        auto error = std::string(result.error);
+        if (!error.empty())
+        {
+            std::cerr << "Error: " << error << std::endl;
+        }
+    };
+
+    unsigned int idle_polls = 0;
+    bool done = false;
+    while (!done)
+    {
+        auto event = events.poll(100ms);
+        if (!event)
+        {
+            // no input for 100 ms, the hardware loop keeps running meanwhile; a node would do
+            // its periodic housekeeping here
+            idle_polls += 1;
+            continue;
+        }
+
+        auto ty = event_type(*event);
+        if (ty == DoraEventType::Input)
+        {
+            auto input = event_as_input(std::move(*event));
+            auto id = std::string(input.id);
+            auto handler = handlers.find(id);
+            if (handler != handlers.end())
+            {
+                handler->second(input);
+            }
+            else
+            {
+                std::cerr << "Ignoring unexpected input " << id << std::endl;
+            }
+        }
+        else if (ty == DoraEventType::AllInputsClosed || ty == DoraEventType::Stop)
+        {
+            done = true;
+        }
+        else
+        {
+            std::cerr << "Unknown event type " << static_cast<int>(ty) << std::endl;
+        }
+    }
+
+    running = false;
+    worker.join();
+
+    std::cout << "Polled " << idle_polls << " times without an event" << std::endl;
+    std::cout << "GOODBYE FROM C++ node (async)" << std::endl;
+
+    return 0;
+}
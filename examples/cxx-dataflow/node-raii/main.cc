@@ -0,0 +1,85 @@
+#include "../dora-cxx-helpers.h"
+
+#include <algorithm>
+#include <cstdint>
+#include <exception>
+#include <iostream>
+#include <numeric>
+#include <vector>
+
+// Summarizes the `position` of the async node between two ticks of the `counter` of the
+// node-rust-api node: sends the positions of each interval on `positions`, and their minimum,
+// maximum and mean on `stats`.
+//
+// The same kind of event loop as the other nodes, written against the wrappers of
+// `dora-cxx-helpers.h` instead of the generated API.
+int main()
+{
+    std::cout << "HELLO FROM C++ (RAII node)" << std::endl;
+
+    try
+    {
+        // closes the outputs at the end of the scope, also on an exception
+        dora::ScopedNode node;
+        auto positions = node.output<uint8_t>("positions");
+        auto stats = node.output<float>("stats");
+
+        std::vector<uint8_t> interval;
+        unsigned int intervals = 0;
+        while (auto event = node.next())
+        {
+            if (event->type() == DoraEventType::InputClosed)
+            {
+                // the node ends once all of its inputs are closed
+                continue;
+            }
+            if (!event->is_input())
+            {
+                std::cerr << "Unknown event type " << static_cast<int>(event->type()) << std::endl;
+                continue;
+            }
+            auto input = std::move(*event).into_input();
+
+            if (input.id() == "position")
+            {
+                // a view into the data of the input, valid as long as `input`
+                auto values = input.values<uint8_t>();
+                interval.insert(interval.end(), values.begin(), values.end());
+            }
+            else if (input.id() == "counter")
+            {
+                if (interval.empty())
+                {
+                    continue;
+                }
+                auto [min, max] = std::minmax_element(interval.begin(), interval.end());
+                auto sum = std::accumulate(interval.begin(), interval.end(), 0u);
+                auto mean = static_cast<float>(sum) / interval.size();
+
+                positions.extend(interval).send();
+                stats.push(*min).push(*max).push(mean).send();
+
+                std::cout << "Interval " << intervals << ": " << interval.size()
+                          << " positions from " << static_cast<unsigned int>(*min) << " to "
+                          << static_cast<unsigned int>(*max) << ", mean " << mean << std::endl;
+                interval.clear();
+                intervals += 1;
+            }
+            else
+            {
+                std::cerr << "Ignoring unexpected input " << input.id() << std::endl;
+            }
+        }
+
+        std::cout << "Summarized " << intervals << " intervals" << std::endl;
+    }
+    catch (const std::exception &error)
+    {
+        std::cerr << "Error: " << error.what() << std::endl;
+        return -1;
+    }
+
+    std::cout << "GOODBYE FROM C++ node (RAII)" << std::endl;
+
+    return 0;
+}
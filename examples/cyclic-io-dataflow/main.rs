@@ -0,0 +1,77 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Number of cycles that the master runs, see `dataflow.yml`.
+const CYCLES: u64 = 2400;
+/// The device that stalls every 500 cycles.
+const FAULTY_DEVICE: &str = "drive-3";
+/// The faulty device stalls in cycles 500, 1000, 1500, and 2000.
+const EXPECTED_TRIPS: u64 = 4;
+/// Healthy devices may miss a few cycles due to scheduling on a loaded machine.
+const MAX_MISSED_RATIO: f64 = 0.01;
+
+/// Subset of `CycleReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct CycleReport {
+    cycles: u64,
+    devices: Vec<DeviceReport>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceReport {
+    name: String,
+    missed: u64,
+    watchdog_trips: u64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("cyclic-io-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: CycleReport = serde_json::from_str(
+        &std::fs::read_to_string("out/cycles.json").context("master did not write a report")?,
+    )?;
+    if report.cycles != CYCLES {
+        bail!("expected {CYCLES} cycles, the master ran {}", report.cycles);
+    }
+    for device in &report.devices {
+        let name = &device.name;
+        if name == FAULTY_DEVICE {
+            if device.watchdog_trips < EXPECTED_TRIPS {
+                bail!(
+                    "watchdog of `{name}` tripped {} times, expected at least {EXPECTED_TRIPS}",
+                    device.watchdog_trips
+                );
+            }
+            continue;
+        }
+        if device.watchdog_trips > 0 {
+            bail!("watchdog of healthy device `{name}` tripped");
+        }
+        let missed_ratio = device.missed as f64 / report.cycles as f64;
+        if missed_ratio > MAX_MISSED_RATIO {
+            bail!(
+                "`{name}` missed {} of {} cycles",
+                device.missed,
+                report.cycles
+            );
+        }
+    }
+    println!(
+        "only the watchdog of `{FAULTY_DEVICE}` tripped, all other devices met their deadlines"
+    );
+
+    Ok(())
+}
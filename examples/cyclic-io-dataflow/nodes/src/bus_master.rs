@@ -0,0 +1,244 @@
+use cyclic_io_dataflow_nodes::{
+    CONTROL_DISABLE_VOLTAGE, CONTROL_ENABLE_OPERATION, CycleReport, DeviceReport, RxPdo,
+    STATUS_OPERATION_ENABLED, TxPdo, cycle_number, cycle_parameters, env_or,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, UInt8Array},
+        datatypes::UInt8Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::{OptionExt, bail, eyre};
+use std::{
+    f64::consts::TAU,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Amplitude of the velocity profile that the application commands, in counts/s.
+const VELOCITY_AMPLITUDE: f64 = 10_000.0;
+/// Period of the velocity profile.
+const VELOCITY_PERIOD: Duration = Duration::from_secs(2);
+
+/// A device on the bus, as seen by the master.
+struct Device {
+    report: DeviceReport,
+    /// The last inputs that arrived in time.
+    inputs: TxPdo,
+    answered: bool,
+    missed_in_row: u32,
+    /// The watchdog tripped and the outputs of the device are disabled.
+    tripped: bool,
+    round_trips_us: Vec<u64>,
+}
+
+impl Device {
+    /// Evaluates the cycle that just ended, like the working counter of an EtherCAT frame.
+    fn close_cycle(&mut self, cycle: u64, watchdog_cycles: u32) {
+        if self.answered {
+            self.report.on_time += 1;
+            self.missed_in_row = 0;
+            if self.tripped {
+                println!("cycle {cycle}: `{}` is back, enabling it", self.report.name);
+                self.tripped = false;
+            }
+        } else {
+            self.report.missed += 1;
+            self.missed_in_row += 1;
+            if self.missed_in_row == watchdog_cycles {
+                eprintln!(
+                    "cycle {cycle}: watchdog of `{}` tripped after {watchdog_cycles} missed \
+                     cycles, disabling its outputs",
+                    self.report.name
+                );
+                self.report.watchdog_trips += 1;
+                self.tripped = true;
+            }
+        }
+        self.answered = false;
+    }
+}
+
+/// Exchanges process images with all devices on every `cycle` tick, like a fieldbus master.
+///
+/// Each cycle, the master evaluates the answers to the previous cycle, runs the application
+/// on the latest inputs, and sends the new output image. Answers must arrive within the
+/// deadline, late answers are discarded. A device that misses `WATCHDOG_CYCLES` cycles in a
+/// row gets its outputs disabled until it answers in time again.
+fn main() -> eyre::Result<()> {
+    let device_names: Vec<String> = env_or("DEVICES", String::new())?
+        .split(',')
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+        .collect();
+    if device_names.is_empty() {
+        bail!("DEVICES must list the input ids of the devices, in slot order");
+    }
+    let cycle_time = Duration::from_micros(env_or("CYCLE_TIME_US", 2000)?);
+    let deadline = Duration::from_micros(env_or("DEADLINE_US", cycle_time.as_micros() as u64)?);
+    let cycles: u64 = env_or("CYCLES", 2400)?;
+    let watchdog_cycles: u32 = env_or("WATCHDOG_CYCLES", 3)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/cycles.json".to_owned())?.into();
+    let output = DataId::from("outputs".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut devices: Vec<Device> = device_names
+        .into_iter()
+        .enumerate()
+        .map(|(slot, name)| Device {
+            report: DeviceReport {
+                name,
+                slot,
+                ..Default::default()
+            },
+            inputs: TxPdo::default(),
+            answered: false,
+            missed_in_row: 0,
+            tripped: false,
+            round_trips_us: Vec::new(),
+        })
+        .collect();
+    let mut image = vec![0; devices.len() * RxPdo::SIZE];
+    let mut report = CycleReport {
+        cycle_time_us: cycle_time.as_micros() as u64,
+        deadline_us: deadline.as_micros() as u64,
+        ..Default::default()
+    };
+
+    let mut current = 0;
+    let mut cycle_start: Option<Instant> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "cycle" => {
+                    let now = Instant::now();
+                    if let Some(start) = cycle_start {
+                        let working_counter = devices.iter().filter(|d| d.answered).count();
+                        if working_counter < devices.len() {
+                            report.overruns += 1;
+                            eprintln!(
+                                "cycle {current}: overrun, working counter {working_counter}/{}",
+                                devices.len()
+                            );
+                        }
+                        for device in &mut devices {
+                            device.close_cycle(current, watchdog_cycles);
+                        }
+
+                        let jitter = (now - start).abs_diff(cycle_time);
+                        report.max_start_jitter_us =
+                            report.max_start_jitter_us.max(jitter.as_micros() as u64);
+                        if now - start > cycle_time * 3 / 2 {
+                            report.late_starts += 1;
+                        }
+                    }
+                    if current == cycles {
+                        break;
+                    }
+
+                    current += 1;
+                    for (slot, device) in devices.iter().enumerate() {
+                        let outputs = if device.tripped {
+                            RxPdo {
+                                control_word: CONTROL_DISABLE_VOLTAGE,
+                                target_velocity: 0,
+                            }
+                        } else {
+                            application(current, slot, &device.inputs, cycle_time)
+                        };
+                        outputs.write(&mut image[slot * RxPdo::SIZE..(slot + 1) * RxPdo::SIZE]);
+                    }
+                    cycle_start = Some(Instant::now());
+                    node.send_output(
+                        output.clone(),
+                        cycle_parameters(current),
+                        UInt8Array::from(image.clone()),
+                    )?;
+                }
+                name => {
+                    let device = devices
+                        .iter_mut()
+                        .find(|device| device.report.name == name)
+                        .ok_or_else(|| eyre!("input `{name}` is not listed in DEVICES"))?;
+                    let round_trip = cycle_start.map(|start| start.elapsed()).unwrap_or_default();
+                    if cycle_number(&metadata.parameters)? != current || round_trip > deadline {
+                        device.report.late += 1;
+                        continue;
+                    }
+                    let inputs = data
+                        .as_primitive_opt::<UInt8Type>()
+                        .ok_or_eyre("expected the input image as UInt8 array")?;
+                    device.inputs = TxPdo::read(inputs.values())?;
+                    device.answered = true;
+                    device.round_trips_us.push(round_trip.as_micros() as u64);
+                }
+            },
+            Event::InputClosed { id } => eprintln!("device `{id}` left the bus"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    report.cycles = current;
+    for device in &mut devices {
+        let round_trips = &mut device.round_trips_us;
+        round_trips.sort_unstable();
+        if !round_trips.is_empty() {
+            device.report.mean_round_trip_us =
+                round_trips.iter().sum::<u64>() as f64 / round_trips.len() as f64;
+            device.report.p99_round_trip_us = round_trips[round_trips.len() * 99 / 100];
+            device.report.max_round_trip_us = round_trips[round_trips.len() - 1];
+        }
+    }
+    report.devices = devices.into_iter().map(|device| device.report).collect();
+
+    println!(
+        "{} cycles of {} us, {} overruns, {} late starts, max start jitter {} us",
+        report.cycles,
+        report.cycle_time_us,
+        report.overruns,
+        report.late_starts,
+        report.max_start_jitter_us
+    );
+    for device in &report.devices {
+        println!(
+            "  {}: {} on time, {} missed, {} late, {} watchdog trips, round trip mean {:.0} us, \
+             p99 {} us, max {} us",
+            device.name,
+            device.on_time,
+            device.missed,
+            device.late,
+            device.watchdog_trips,
+            device.mean_round_trip_us,
+            device.p99_round_trip_us,
+            device.max_round_trip_us
+        );
+    }
+    if let Some(parent) = report_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
+
+/// The application logic, replace it with your own.
+///
+/// Runs once per cycle on the latest inputs of a drive and computes its next outputs. It
+/// enables the drive, and commands a sine velocity profile once the drive reports that its
+/// operation is enabled.
+fn application(cycle: u64, slot: usize, inputs: &TxPdo, cycle_time: Duration) -> RxPdo {
+    let target_velocity = if inputs.status_word == STATUS_OPERATION_ENABLED {
+        let t = cycle as f64 * cycle_time.as_secs_f64();
+        let phase = TAU * t / VELOCITY_PERIOD.as_secs_f64() + slot as f64;
+        (VELOCITY_AMPLITUDE * phase.sin()) as i32
+    } else {
+        0
+    };
+    RxPdo {
+        control_word: CONTROL_ENABLE_OPERATION,
+        target_velocity,
+    }
+}
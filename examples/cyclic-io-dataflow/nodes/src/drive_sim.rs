@@ -0,0 +1,98 @@
+use cyclic_io_dataflow_nodes::{
+    CONTROL_ENABLE_OPERATION, RxPdo, STATUS_OPERATION_ENABLED, STATUS_SWITCH_ON_DISABLED, TxPdo,
+    cycle_number, cycle_parameters, env_or,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, UInt8Array},
+        datatypes::UInt8Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::{OptionExt, eyre};
+use std::time::{Duration, Instant};
+
+/// Emulates a velocity-controlled drive on the fieldbus.
+///
+/// On every output image, the drive reads the RxPDO in its `SLOT`, updates its state, and
+/// answers with its TxPDO and the cycle counter of the image. With `STALL_EVERY`, it sleeps
+/// for `STALL_MS` before answering every n-th cycle, like a device that misses its deadline.
+fn main() -> eyre::Result<()> {
+    let slot: usize = env_or("SLOT", 0)?;
+    let time_constant = env_or::<f64>("TIME_CONSTANT_MS", 20.0)? / 1000.0;
+    let stall_every: u64 = env_or("STALL_EVERY", 0)?;
+    let stall = Duration::from_millis(env_or("STALL_MS", 0)?);
+    let output = DataId::from("inputs".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut velocity = 0.0;
+    let mut position = 0.0;
+    let mut last_update: Option<Instant> = None;
+    let mut answered = 0;
+    let mut stalls = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "outputs" => {
+                    let cycle = cycle_number(&metadata.parameters)?;
+                    let image = data
+                        .as_primitive_opt::<UInt8Type>()
+                        .ok_or_eyre("expected the output image as UInt8 array")?;
+                    let outputs = RxPdo::read(
+                        image
+                            .values()
+                            .get(slot * RxPdo::SIZE..(slot + 1) * RxPdo::SIZE)
+                            .ok_or_else(|| eyre!("slot {slot} is outside of the output image"))?,
+                    )?;
+
+                    // integrate over the elapsed time, since images may be dropped
+                    let now = Instant::now();
+                    let dt = last_update.map_or(0.0, |last| (now - last).as_secs_f64());
+                    last_update = Some(now);
+                    let status_word = if outputs.control_word == CONTROL_ENABLE_OPERATION {
+                        let target = f64::from(outputs.target_velocity);
+                        velocity += (target - velocity) * (dt / time_constant).min(1.0);
+                        STATUS_OPERATION_ENABLED
+                    } else {
+                        // simplified, a real drive would ramp down before disabling
+                        velocity = 0.0;
+                        STATUS_SWITCH_ON_DISABLED
+                    };
+                    position += velocity * dt;
+
+                    if stall_every > 0 && cycle % stall_every == 0 {
+                        stalls += 1;
+                        std::thread::sleep(stall);
+                    }
+
+                    let inputs = TxPdo {
+                        status_word,
+                        actual_velocity: velocity.round() as i32,
+                        actual_position: position.round() as i32,
+                    };
+                    let mut image = vec![0; TxPdo::SIZE];
+                    inputs.write(&mut image);
+                    node.send_output(
+                        output.clone(),
+                        cycle_parameters(cycle),
+                        UInt8Array::from(image),
+                    )?;
+                    answered += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "outputs" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("answered {answered} output images, stalled {stalls} times");
+    Ok(())
+}
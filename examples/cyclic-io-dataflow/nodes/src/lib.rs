@@ -0,0 +1,141 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the cycle counter, sent with the output image and echoed by the devices.
+pub const CYCLE_KEY: &str = "cycle";
+
+/// CiA 402 control word that enables the operation of a drive.
+pub const CONTROL_ENABLE_OPERATION: u16 = 0x000f;
+/// CiA 402 control word that disables the voltage of a drive.
+pub const CONTROL_DISABLE_VOLTAGE: u16 = 0x0000;
+/// CiA 402 status word of a drive in "operation enabled".
+pub const STATUS_OPERATION_ENABLED: u16 = 0x0027;
+/// CiA 402 status word of a drive in "switch on disabled".
+pub const STATUS_SWITCH_ON_DISABLED: u16 = 0x0040;
+
+/// Process data that the master writes to a drive every cycle, from the drive's point of view.
+///
+/// The layout is fixed, like a PDO mapping configured at startup: a real EtherCAT or PROFINET
+/// stack maps the same bytes into the frame on the wire.
+///
+/// | Offset | Type | Field |
+/// |---|---|---|
+/// | 0 | `u16` | control word |
+/// | 2 | `u16` | reserved |
+/// | 4 | `i32` | target velocity, in counts/s |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RxPdo {
+    pub control_word: u16,
+    pub target_velocity: i32,
+}
+
+impl RxPdo {
+    /// Size of the slot of each drive in the output image.
+    pub const SIZE: usize = 8;
+
+    pub fn write(&self, slot: &mut [u8]) {
+        slot[0..2].copy_from_slice(&self.control_word.to_le_bytes());
+        slot[2..4].fill(0);
+        slot[4..8].copy_from_slice(&self.target_velocity.to_le_bytes());
+    }
+
+    pub fn read(slot: &[u8]) -> eyre::Result<Self> {
+        if slot.len() != Self::SIZE {
+            bail!("expected {} bytes of RxPDO, got {}", Self::SIZE, slot.len());
+        }
+        Ok(Self {
+            control_word: u16::from_le_bytes(slot[0..2].try_into()?),
+            target_velocity: i32::from_le_bytes(slot[4..8].try_into()?),
+        })
+    }
+}
+
+/// Process data that a drive reports to the master every cycle.
+///
+/// | Offset | Type | Field |
+/// |---|---|---|
+/// | 0 | `u16` | status word |
+/// | 2 | `u16` | reserved |
+/// | 4 | `i32` | actual velocity, in counts/s |
+/// | 8 | `i32` | actual position, in counts |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxPdo {
+    pub status_word: u16,
+    pub actual_velocity: i32,
+    pub actual_position: i32,
+}
+
+impl TxPdo {
+    /// Size of the input image of each drive.
+    pub const SIZE: usize = 12;
+
+    pub fn write(&self, slot: &mut [u8]) {
+        slot[0..2].copy_from_slice(&self.status_word.to_le_bytes());
+        slot[2..4].fill(0);
+        slot[4..8].copy_from_slice(&self.actual_velocity.to_le_bytes());
+        slot[8..12].copy_from_slice(&self.actual_position.to_le_bytes());
+    }
+
+    pub fn read(slot: &[u8]) -> eyre::Result<Self> {
+        if slot.len() != Self::SIZE {
+            bail!("expected {} bytes of TxPDO, got {}", Self::SIZE, slot.len());
+        }
+        Ok(Self {
+            status_word: u16::from_le_bytes(slot[0..2].try_into()?),
+            actual_velocity: i32::from_le_bytes(slot[4..8].try_into()?),
+            actual_position: i32::from_le_bytes(slot[8..12].try_into()?),
+        })
+    }
+}
+
+/// Statistics of one device, as seen by the master.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeviceReport {
+    pub name: String,
+    pub slot: usize,
+    /// Cycles in which the device answered before the deadline.
+    pub on_time: u64,
+    /// Cycles in which the device didn't answer before the deadline.
+    pub missed: u64,
+    /// Answers that arrived after the deadline and were discarded.
+    pub late: u64,
+    /// How often the master disabled the outputs of the device after consecutive misses.
+    pub watchdog_trips: u64,
+    pub mean_round_trip_us: f64,
+    pub p99_round_trip_us: u64,
+    pub max_round_trip_us: u64,
+}
+
+/// Written by the master when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CycleReport {
+    pub cycles: u64,
+    pub cycle_time_us: u64,
+    pub deadline_us: u64,
+    /// Cycles in which at least one device missed the deadline, i.e. the working counter
+    /// didn't match the number of devices.
+    pub overruns: u64,
+    /// Cycles that started more than half a cycle time too late.
+    pub late_starts: u64,
+    pub max_start_jitter_us: u64,
+    pub devices: Vec<DeviceReport>,
+}
+
+pub fn cycle_parameters(cycle: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(CYCLE_KEY.into(), Parameter::Integer(cycle as i64));
+    parameters
+}
+
+pub fn cycle_number(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(CYCLE_KEY) {
+        Some(Parameter::Integer(value)) => {
+            u64::try_from(*value).map_err(|_| eyre!("negative `{CYCLE_KEY}` parameter"))
+        }
+        Some(other) => bail!("expected integer `{CYCLE_KEY}` parameter, got {other:?}"),
+        None => bail!("missing `{CYCLE_KEY}` parameter"),
+    }
+}
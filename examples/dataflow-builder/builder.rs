@@ -0,0 +1,186 @@
+//! A small builder for dataflow descriptors, serialized to the YAML format of `dataflow.yml`.
+//!
+//! Only covers custom nodes with `path`, `build`, `inputs`, `outputs`, and `env`.
+//! Sources of inputs are taken from the nodes that declare the outputs, so that a generated
+//! dataflow can't refer to outputs or nodes that don't exist.
+
+use eyre::{Context, bail};
+use serde::{Serialize, Serializer, ser::SerializeMap};
+use std::{collections::BTreeMap, fmt, path::Path, time::Duration};
+
+#[derive(Debug, Default, Serialize)]
+pub struct Dataflow {
+    nodes: Vec<Node>,
+}
+
+impl Dataflow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `node`, failing if the dataflow already has a node with the same id.
+    pub fn add(&mut self, node: Node) -> eyre::Result<()> {
+        if self.nodes.iter().any(|other| other.id == node.id) {
+            bail!("duplicate node id `{}`", node.id);
+        }
+        self.nodes.push(node);
+        Ok(())
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Checks that every input comes from a node of this dataflow.
+    pub fn check(&self) -> eyre::Result<()> {
+        for node in &self.nodes {
+            for (input, source) in &node.inputs {
+                if let Source::Output { node: from, .. } = &source.source
+                    && !self.nodes.iter().any(|other| &other.id == from)
+                {
+                    bail!(
+                        "input `{input}` of node `{}` comes from node `{from}`, which is not \
+                         part of the dataflow",
+                        node.id
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_yaml(&self) -> eyre::Result<String> {
+        self.check()?;
+        serde_yaml::to_string(self).context("failed to serialize dataflow")
+    }
+
+    /// Writes the dataflow to `path`. Relative paths of the nodes are resolved against the
+    /// directory of `path` by dora.
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        let yaml = self.to_yaml()?;
+        std::fs::write(path, yaml).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// A custom node, with an executable `path`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    build: Option<String>,
+    path: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    inputs: BTreeMap<String, Input>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    outputs: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+}
+
+impl Node {
+    pub fn new(id: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            build: None,
+            path: path.into(),
+            inputs: BTreeMap::new(),
+            outputs: Vec::new(),
+            env: BTreeMap::new(),
+        }
+    }
+
+    /// A command that `dora build` runs before the dataflow starts.
+    pub fn build(mut self, command: impl Into<String>) -> Self {
+        self.build = Some(command.into());
+        self
+    }
+
+    /// Adds an input, replacing an earlier input with the same id.
+    pub fn input(mut self, id: impl Into<String>, input: impl Into<Input>) -> Self {
+        self.inputs.insert(id.into(), input.into());
+        self
+    }
+
+    pub fn output(mut self, id: impl Into<String>) -> Self {
+        let id = id.into();
+        if !self.outputs.contains(&id) {
+            self.outputs.push(id);
+        }
+        self
+    }
+
+    pub fn env(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.env.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// The source of an input that receives `output` of this node.
+    pub fn source(&self, output: &str) -> eyre::Result<Source> {
+        if !self.outputs.iter().any(|o| o == output) {
+            bail!("node `{}` has no output `{output}`", self.id);
+        }
+        Ok(Source::Output {
+            node: self.id.clone(),
+            output: output.to_owned(),
+        })
+    }
+}
+
+/// Where the messages of an input come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// A `dora/timer/millis/<N>` input. Durations are rounded down to whole milliseconds.
+    Timer(Duration),
+    Output {
+        node: String,
+        output: String,
+    },
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Timer(interval) => write!(f, "dora/timer/millis/{}", interval.as_millis()),
+            Source::Output { node, output } => write!(f, "{node}/{output}"),
+        }
+    }
+}
+
+/// An input of a node: its source and an optional queue size.
+#[derive(Debug, Clone)]
+pub struct Input {
+    source: Source,
+    queue_size: Option<usize>,
+}
+
+impl Input {
+    /// Keeps at most `queue_size` messages of this input, dropping the oldest ones.
+    pub fn queue_size(mut self, queue_size: usize) -> Self {
+        self.queue_size = Some(queue_size);
+        self
+    }
+}
+
+impl From<Source> for Input {
+    fn from(source: Source) -> Self {
+        Self {
+            source,
+            queue_size: None,
+        }
+    }
+}
+
+/// `<source>`, or `{ source: <source>, queue_size: <N> }` if a queue size is set.
+impl Serialize for Input {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.queue_size {
+            None => serializer.collect_str(&self.source),
+            Some(queue_size) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("source", &self.source.to_string())?;
+                map.serialize_entry("queue_size", &queue_size)?;
+                map.end()
+            }
+        }
+    }
+}
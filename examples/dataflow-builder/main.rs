@@ -0,0 +1,153 @@
+use builder::{Dataflow, Input, Node, Source};
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+mod builder;
+
+/// Stands in for a configuration database: the production lines of a plant and their sensors.
+#[derive(Debug, Deserialize)]
+struct Plant {
+    /// Readings that every sensor sends before it stops.
+    samples: u64,
+    lines: Vec<Line>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Line {
+    id: String,
+    sensors: Vec<Sensor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sensor {
+    id: String,
+    period_ms: u64,
+    baseline: f64,
+    amplitude: f64,
+    limit: f64,
+}
+
+/// Subset of `SensorStats` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SensorStats {
+    count: u64,
+    alarms: u64,
+}
+
+/// Subset of `LineSummary` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct LineSummary {
+    sensors: BTreeMap<String, SensorStats>,
+}
+
+/// Must match `reading` in `nodes/src/lib.rs`.
+fn reading(baseline: f64, amplitude: f64, index: u64) -> f64 {
+    baseline + amplitude * (index as f64 * 0.1).sin()
+}
+
+/// One `sensor` node per sensor, one `line-monitor` per line, and a `recorder` for the plant.
+fn plant_dataflow(plant: &Plant) -> eyre::Result<Dataflow> {
+    let mut dataflow = Dataflow::new();
+    let mut recorder = Node::new("recorder", "nodes/target/release/recorder")
+        .build("cargo build --release --manifest-path nodes/Cargo.toml")
+        .env("REPORT_FILE", "out/report.json");
+    for line in &plant.lines {
+        let mut monitor = Node::new(
+            format!("{}-monitor", line.id),
+            "nodes/target/release/line-monitor",
+        )
+        .output("summary")
+        .env("LINE_ID", &line.id);
+        let mut limits = Vec::new();
+        for sensor in &line.sensors {
+            let node = Node::new(&sensor.id, "nodes/target/release/sensor")
+                .input(
+                    "tick",
+                    Source::Timer(Duration::from_millis(sensor.period_ms)),
+                )
+                .output("reading")
+                .env("BASELINE", sensor.baseline)
+                .env("AMPLITUDE", sensor.amplitude)
+                .env("SAMPLES", plant.samples);
+            // the monitor must see every reading to count the alarms
+            monitor = monitor.input(
+                &sensor.id,
+                Input::from(node.source("reading")?).queue_size(plant.samples as usize),
+            );
+            limits.push(format!("{}={}", sensor.id, sensor.limit));
+            dataflow.add(node)?;
+        }
+        monitor = monitor.env("LIMITS", limits.join(","));
+        recorder = recorder.input(&line.id, monitor.source("summary")?);
+        dataflow.add(monitor)?;
+    }
+    dataflow.add(recorder)?;
+    Ok(dataflow)
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("dataflow-builder-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let plant: Plant = serde_json::from_str(
+        &std::fs::read_to_string("plant.json").context("failed to read plant.json")?,
+    )
+    .context("invalid plant.json")?;
+    let generated = plant_dataflow(&plant)?;
+    let dataflow = Path::new("dataflow.generated.yml");
+    generated.write(dataflow)?;
+    println!(
+        "generated {} with {} nodes",
+        dataflow.display(),
+        generated.nodes().len()
+    );
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: BTreeMap<String, LineSummary> = serde_json::from_str(
+        &std::fs::read_to_string("out/report.json").context("recorder did not write a report")?,
+    )?;
+    for line in &plant.lines {
+        let Some(summary) = report.get(&line.id) else {
+            bail!("no summary of `{}`", line.id);
+        };
+        for sensor in &line.sensors {
+            let Some(stats) = summary.sensors.get(&sensor.id) else {
+                bail!("`{}` has no readings of `{}`", line.id, sensor.id);
+            };
+            if stats.count != plant.samples {
+                bail!(
+                    "expected {} readings of `{}`, got {}",
+                    plant.samples,
+                    sensor.id,
+                    stats.count
+                );
+            }
+            let expected_alarms = (0..plant.samples)
+                .filter(|&i| reading(sensor.baseline, sensor.amplitude, i) > sensor.limit)
+                .count() as u64;
+            if stats.alarms != expected_alarms {
+                bail!(
+                    "expected {expected_alarms} alarms of `{}`, got {}",
+                    sensor.id,
+                    stats.alarms
+                );
+            }
+        }
+    }
+    println!(
+        "all {} lines reported every reading of their sensors",
+        plant.lines.len()
+    );
+
+    Ok(())
+}
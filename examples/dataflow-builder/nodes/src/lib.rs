@@ -0,0 +1,45 @@
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub use example_node_utils::{env_or, write_json};
+
+/// The `index`th reading of a sensor, a slow sine wave around `baseline`.
+pub fn reading(baseline: f64, amplitude: f64, index: u64) -> f64 {
+    baseline + amplitude * (index as f64 * 0.1).sin()
+}
+
+/// Statistics of the readings of one sensor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorStats {
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Readings above the limit of the sensor.
+    pub alarms: u64,
+}
+
+/// Sent by a `line-monitor` on `summary` when all of its sensors are done.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineSummary {
+    pub line: String,
+    pub sensors: BTreeMap<String, SensorStats>,
+}
+
+/// Parses `LIMITS` of a `line-monitor`, e.g. `a-temperature=68,a-pressure=5`.
+pub fn parse_limits(limits: &str) -> eyre::Result<BTreeMap<String, f64>> {
+    limits
+        .split(',')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (sensor, limit) = entry
+                .split_once('=')
+                .ok_or_else(|| eyre!("expected `<sensor>=<limit>`, got `{entry}`"))?;
+            let limit = limit
+                .parse()
+                .map_err(|err| eyre!("invalid limit for `{sensor}`: {err}"))?;
+            Ok((sensor.to_owned(), limit))
+        })
+        .collect()
+}
@@ -0,0 +1,83 @@
+use dataflow_builder_nodes::{LineSummary, SensorStats, env_or, parse_limits};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::{AsArray, StringArray, types::Float64Type},
+    dora_core::config::DataId,
+};
+use eyre::{OptionExt, bail};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Collects statistics of the readings of the sensors of one production line. Every input is
+/// a sensor, named by its id, with its alarm limit in `LIMITS`.
+///
+/// Sends a [`LineSummary`] on `summary` once the inputs of all sensors are closed.
+fn main() -> eyre::Result<()> {
+    let line: String = env_or("LINE_ID", "line".to_owned())?;
+    let limits = parse_limits(&env_or("LIMITS", String::new())?)?;
+    if limits.is_empty() {
+        bail!("LIMITS must list at least one sensor");
+    }
+    let output = DataId::from("summary".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sensors: BTreeMap<String, SensorStats> = BTreeMap::new();
+    let mut sums: BTreeMap<String, f64> = BTreeMap::new();
+    let mut open: BTreeSet<&str> = limits.keys().map(String::as_str).collect();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => {
+                let Some(&limit) = limits.get(id.as_str()) else {
+                    eprintln!("Ignoring unexpected input `{id}`");
+                    continue;
+                };
+                let values = data
+                    .as_primitive_opt::<Float64Type>()
+                    .ok_or_eyre("expected a Float64 array of readings")?;
+                for value in values.values().iter().copied() {
+                    let stats = sensors.entry(id.to_string()).or_insert(SensorStats {
+                        count: 0,
+                        min: f64::INFINITY,
+                        max: f64::NEG_INFINITY,
+                        mean: 0.0,
+                        alarms: 0,
+                    });
+                    let sum = sums.entry(id.to_string()).or_default();
+                    stats.count += 1;
+                    stats.min = stats.min.min(value);
+                    stats.max = stats.max.max(value);
+                    *sum += value;
+                    stats.mean = *sum / stats.count as f64;
+                    if value > limit {
+                        stats.alarms += 1;
+                        println!("{line}: `{id}` above limit {limit}: {value:.2}");
+                    }
+                }
+            }
+            Event::InputClosed { id } => {
+                open.remove(id.as_str());
+                if open.is_empty() {
+                    let summary = LineSummary {
+                        line: line.clone(),
+                        sensors: sensors.clone(),
+                    };
+                    let row = serde_json::to_string(&summary)?;
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        StringArray::from(vec![row]),
+                    )?;
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use dataflow_builder_nodes::{LineSummary, env_or, write_json};
+use dora_node_api::{self, DoraNode, Event, arrow::array::AsArray};
+use eyre::OptionExt;
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Receives the summaries of all line monitors, one input per line, and writes them to
+/// `REPORT_FILE` when the dataflow is done.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/report.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut lines: BTreeMap<String, LineSummary> = BTreeMap::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => {
+                let rows = data
+                    .as_string_opt::<i32>()
+                    .ok_or_eyre("expected a Utf8 array of summaries")?;
+                for row in rows.iter() {
+                    let summary: LineSummary =
+                        serde_json::from_str(row.ok_or_eyre("null summary")?)?;
+                    let alarms: u64 = summary.sensors.values().map(|s| s.alarms).sum();
+                    println!("{id}: {} sensors, {alarms} alarms", summary.sensors.len());
+                    lines.insert(id.to_string(), summary);
+                }
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    write_json(&report_file, &lines)
+}
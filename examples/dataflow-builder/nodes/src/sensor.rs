@@ -0,0 +1,41 @@
+use dataflow_builder_nodes::{env_or, reading};
+use dora_node_api::{self, DoraNode, Event, arrow::array::Float64Array, dora_core::config::DataId};
+
+/// Sends `SAMPLES` readings on `reading`, one per `tick`, and then stops.
+fn main() -> eyre::Result<()> {
+    let baseline: f64 = env_or("BASELINE", 0.0)?;
+    let amplitude: f64 = env_or("AMPLITUDE", 1.0)?;
+    let samples: u64 = env_or("SAMPLES", 100)?;
+    let output = DataId::from("reading".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent = 0;
+    while sent < samples {
+        let Some(event) = events.recv() else {
+            break;
+        };
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    let value = reading(baseline, amplitude, sent);
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        Float64Array::from(vec![value]),
+                    )?;
+                    sent += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Control, Dora};
+use eyre::{Context, OptionExt, bail};
+use serde::Deserialize;
+use std::{
+    fmt::Write as _,
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// How often to ask the coordinator whether the daemon has connected.
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// Number of messages that the source sends, see `dataflow.yml`.
+const MESSAGES: u64 = 5000;
+
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    /// `dora run`: a single process runs the daemon, there is no coordinator.
+    QuickRun,
+    /// A separate coordinator and daemon, the dataflow is started with `dora start`.
+    CoordinatorDaemon,
+}
+
+impl Mode {
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::QuickRun => "dora run",
+            Mode::CoordinatorDaemon => "coordinator + daemon",
+        }
+    }
+}
+
+/// Subset of `LatencyReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct LatencyReport {
+    messages: u64,
+    p50_us: u64,
+    p99_us: u64,
+    max_us: u64,
+}
+
+/// Measurements of a single run.
+struct Sample {
+    latency: LatencyReport,
+    /// CPU time of all dora processes and nodes of the run.
+    cpu: Option<Duration>,
+    wall: Duration,
+}
+
+/// A column of the report, and how to read it from a sample.
+type Metric = (&'static str, fn(&Sample) -> Option<f64>);
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("deployment-comparison-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let runs = match std::env::args().skip(1).collect::<Vec<_>>().as_slice() {
+        [] => 3,
+        [flag, runs] if flag == "--runs" => runs.parse().wrap_err("invalid --runs")?,
+        _ => bail!("usage: deployment-comparison [--runs <n>]"),
+    };
+
+    // measure the `dora` binary directly, `cargo run` would add its own CPU time
+    let checkout = Dora::from_env()?;
+    let dora = checkout.build_cli().await?;
+    let dataflow = PathBuf::from("dataflow.yml");
+    checkout.build_dataflow(&dataflow).await?;
+
+    let modes = [Mode::QuickRun, Mode::CoordinatorDaemon];
+    let mut samples: Vec<Vec<Sample>> = modes.iter().map(|_| Vec::new()).collect();
+    // alternate the modes, so that caches and background load affect both alike
+    for run in 0..runs {
+        for (mode, samples) in modes.iter().zip(&mut samples) {
+            let (dora, dataflow, mode) = (dora.clone(), dataflow.clone(), *mode);
+            let sample =
+                tokio::task::spawn_blocking(move || measure(&dora, &dataflow, mode)).await??;
+            if sample.latency.messages != MESSAGES {
+                bail!(
+                    "{} run {run}: sink received {} of {MESSAGES} messages",
+                    mode.name(),
+                    sample.latency.messages
+                );
+            }
+            tracing::info!(
+                "{} run {run}: p50 {} us, wall {:.2} s",
+                mode.name(),
+                sample.latency.p50_us,
+                sample.wall.as_secs_f64()
+            );
+            samples.push(sample);
+        }
+    }
+
+    let metrics: [Metric; 5] = [
+        ("latency p50 (us)", |s| Some(s.latency.p50_us as f64)),
+        ("latency p99 (us)", |s| Some(s.latency.p99_us as f64)),
+        ("latency max (us)", |s| Some(s.latency.max_us as f64)),
+        ("CPU time (s)", |s| s.cpu.map(|cpu| cpu.as_secs_f64())),
+        ("wall time (s)", |s| Some(s.wall.as_secs_f64())),
+    ];
+    let mut report = String::new();
+    write!(report, "{:<20}", format!("median of {runs} runs"))?;
+    for mode in modes {
+        write!(report, " {:>22}", mode.name())?;
+    }
+    writeln!(report)?;
+    for (name, metric) in metrics {
+        write!(report, "{name:<20}")?;
+        for samples in &samples {
+            match median(samples.iter().filter_map(metric).collect()) {
+                Some(value) => write!(report, " {value:>22.2}")?,
+                None => write!(report, " {:>22}", "n/a")?,
+            }
+        }
+        writeln!(report)?;
+    }
+
+    println!("\n{report}");
+    std::fs::write("out/report.txt", report)?;
+    Ok(())
+}
+
+/// Runs the dataflow once in the given mode and collects the measurements.
+fn measure(dora: &Path, dataflow: &Path, mode: Mode) -> eyre::Result<Sample> {
+    let latency_file = Path::new("out/latency.json");
+    if latency_file.exists() {
+        std::fs::remove_file(latency_file)?;
+    }
+
+    // all processes of the run are children of this process, and have exited when the run
+    // returns, so the difference covers exactly this run
+    let cpu_before = children_cpu_time();
+    let start = Instant::now();
+    match mode {
+        Mode::QuickRun => run_quick(dora, dataflow)?,
+        Mode::CoordinatorDaemon => run_with_coordinator(dora, dataflow)?,
+    }
+    let wall = start.elapsed();
+    let cpu = children_cpu_time()
+        .zip(cpu_before)
+        .map(|(after, before)| after - before);
+
+    let latency = serde_json::from_str(
+        &std::fs::read_to_string(latency_file).context("sink did not write a report")?,
+    )?;
+    Ok(Sample { latency, cpu, wall })
+}
+
+fn run_quick(dora: &Path, dataflow: &Path) -> eyre::Result<()> {
+    let status = Command::new(dora)
+        .arg("run")
+        .arg(dataflow)
+        .stdout(Stdio::null())
+        .status()
+        .context("failed to spawn `dora run`")?;
+    if !status.success() {
+        bail!("`dora run` failed");
+    }
+    Ok(())
+}
+
+fn run_with_coordinator(dora: &Path, dataflow: &Path) -> eyre::Result<()> {
+    let interface_port =
+        port_check::free_local_ipv4_port_in_range(10000..=15000).ok_or_eyre("No available port")?;
+    let control_port = port_check::free_local_ipv4_port_in_range((interface_port + 1)..=15000)
+        .ok_or_eyre("No available port")?;
+    let localhost = Ipv4Addr::LOCALHOST.to_string();
+
+    let mut processes = Processes(Vec::new());
+    processes.0.push(
+        Command::new(dora)
+            .arg("coordinator")
+            .args(["--interface", &localhost, "--control-interface", &localhost])
+            .args(["--port", &interface_port.to_string()])
+            .args(["--control-port", &control_port.to_string()])
+            .stdout(Stdio::null())
+            .spawn()
+            .context("failed to spawn coordinator")?,
+    );
+    let mut control = Control::connect(
+        SocketAddr::from((Ipv4Addr::LOCALHOST, control_port)),
+        Duration::from_secs(30),
+    )?;
+    processes.0.push(
+        Command::new(dora)
+            .arg("daemon")
+            .args(["--coordinator-addr", &localhost])
+            .args(["--coordinator-port", &interface_port.to_string()])
+            .stdout(Stdio::null())
+            .spawn()
+            .context("failed to spawn daemon")?,
+    );
+    control
+        .wait_until(Duration::from_secs(30), POLL_INTERVAL, |c| {
+            Ok(c.connected_daemons()?.len() == 1)
+        })
+        .wrap_err("daemon did not connect to the coordinator")?;
+
+    // `--attach` returns once the dataflow has finished
+    let status = Command::new(dora)
+        .arg("start")
+        .arg(dataflow)
+        .arg("--attach")
+        .args(["--coordinator-addr", &localhost])
+        .args(["--coordinator-port", &interface_port.to_string()])
+        .stdout(Stdio::null())
+        .status()
+        .context("failed to spawn `dora start`")?;
+    if !status.success() {
+        bail!("`dora start` failed");
+    }
+    control.destroy()?;
+    processes.wait()
+}
+
+/// Kills the coordinator and daemon if a run fails half-way.
+struct Processes(Vec<Child>);
+
+impl Processes {
+    fn wait(&mut self) -> eyre::Result<()> {
+        for mut child in self.0.drain(..) {
+            child.wait()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Processes {
+    fn drop(&mut self) {
+        for child in &mut self.0 {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// User and system CPU time of all child processes that have exited, including the children
+/// that they waited for in turn, e.g. the nodes spawned by a daemon.
+#[cfg(unix)]
+fn children_cpu_time() -> Option<Duration> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    // SAFETY: `getrusage` fills `usage` if it returns 0
+    if unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, usage.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let usage = unsafe { usage.assume_init() };
+    let duration = |time: libc::timeval| {
+        Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+    };
+    Some(duration(usage.ru_utime) + duration(usage.ru_stime))
+}
+
+#[cfg(not(unix))]
+fn children_cpu_time() -> Option<Duration> {
+    None
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+    values.get(values.len() / 2).copied()
+}
@@ -0,0 +1,27 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, now_us};
+
+/// Metadata key of the wall-clock time at which the source sent a message, in microseconds.
+pub const SENT_AT_KEY: &str = "sent_at_us";
+
+/// Written by the sink when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LatencyReport {
+    pub messages: u64,
+    pub p50_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<u64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => {
+            u64::try_from(*value).map_err(|_| eyre!("negative `{key}` parameter"))
+        }
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
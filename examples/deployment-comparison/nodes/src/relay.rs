@@ -0,0 +1,26 @@
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+
+/// Forwards every input unchanged, together with its metadata.
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("data".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "data" => node.send_output(output.clone(), metadata.parameters, data.0)?,
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "data" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use deployment_comparison_nodes::{LatencyReport, SENT_AT_KEY, env_or, integer_parameter, now_us};
+use dora_node_api::{self, DoraNode, Event};
+use std::path::PathBuf;
+
+/// Measures the latency from the source to this node and writes percentiles to
+/// `REPORT_FILE` when its input closes.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/latency.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut latencies_us = Vec::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata,
+                data: _,
+            } => match id.as_str() {
+                "data" => {
+                    let sent_at = integer_parameter(&metadata.parameters, SENT_AT_KEY)?;
+                    latencies_us.push(now_us().saturating_sub(sent_at));
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "data" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    latencies_us.sort_unstable();
+    let mut report = LatencyReport {
+        messages: latencies_us.len() as u64,
+        ..Default::default()
+    };
+    if let Some(max) = latencies_us.last() {
+        report.p50_us = latencies_us[latencies_us.len() / 2];
+        report.p99_us = latencies_us[latencies_us.len() * 99 / 100];
+        report.max_us = *max;
+    }
+    println!(
+        "received {} messages, latency p50 {} us, p99 {} us, max {} us",
+        report.messages, report.p50_us, report.p99_us, report.max_us
+    );
+    if let Some(parent) = report_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use deployment_comparison_nodes::{SENT_AT_KEY, env_or, now_us};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::UInt8Array,
+    dora_core::config::DataId,
+};
+
+/// Sends a message of `PAYLOAD_BYTES` on every `tick`, `COUNT` times.
+fn main() -> eyre::Result<()> {
+    let payload_bytes: usize = env_or("PAYLOAD_BYTES", 4096)?;
+    let count: u64 = env_or("COUNT", 5000)?;
+    let output = DataId::from("data".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if sent == count {
+                        break;
+                    }
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(SENT_AT_KEY.into(), Parameter::Integer(now_us() as i64));
+                    node.send_output(
+                        output.clone(),
+                        parameters,
+                        UInt8Array::from(vec![sent as u8; payload_bytes]),
+                    )?;
+                    sent += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {sent} messages");
+    Ok(())
+}
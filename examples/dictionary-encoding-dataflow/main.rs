@@ -0,0 +1,61 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Number of batches that the producer sends, see `dataflow.yml`.
+const BATCHES: u64 = 100;
+/// The dictionary encoding should at least halve the transferred bytes for this data.
+const MAX_SIZE_RATIO: f64 = 0.5;
+
+/// Subset of `Report` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct Report {
+    batches: u64,
+    dictionary_bytes: u64,
+    plain_bytes: u64,
+    mismatches: u64,
+    stale_dictionary_errors: u64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("dictionary-encoding-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: Report = serde_json::from_str(
+        &std::fs::read_to_string("out/report.json").context("consumer did not write a report")?,
+    )?;
+    if report.batches != BATCHES {
+        bail!("expected {BATCHES} batches, got {}", report.batches);
+    }
+    if report.mismatches > 0 {
+        bail!("{} decoded dictionary values differ", report.mismatches);
+    }
+    let ratio = report.dictionary_bytes as f64 / report.plain_bytes as f64;
+    if ratio > MAX_SIZE_RATIO {
+        bail!(
+            "dictionary batches are {:.0}% of the plain size",
+            ratio * 100.0
+        );
+    }
+    if report.stale_dictionary_errors == 0 {
+        bail!("expected the dictionaries to differ between batches");
+    }
+    println!(
+        "dictionary encoding transferred {:.0}% of the plain bytes",
+        ratio * 100.0
+    );
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use dictionary_encoding_dataflow_nodes::{
+    Report, SEQ_KEY, env_or, integer_parameter, payload_bytes,
+};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{Array, ArrayRef, AsArray, StringArray},
+        datatypes::Int16Type,
+    },
+};
+use eyre::{OptionExt, bail};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+/// Receives each batch in both encodings, decodes the dictionary batch, and checks it against
+/// the plain batch.
+///
+/// The keys of a dictionary array are only meaningful together with the dictionary of the
+/// same message. To show what goes wrong otherwise, the consumer also decodes every batch with
+/// the dictionary of the first batch and counts the wrong labels.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/report.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = Report::default();
+    // the two encodings of a batch arrive on different inputs, keep whichever comes first
+    let mut pending: HashMap<(u64, bool), ArrayRef> = HashMap::new();
+    let mut first_dictionary: Option<Vec<String>> = None;
+    let mut label_counts: BTreeMap<String, u64> = BTreeMap::new();
+    let mut dictionary_entries = 0;
+    let mut open_inputs = 2;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let is_dictionary = match id.as_str() {
+                    "dictionary" => true,
+                    "plain" => false,
+                    other => {
+                        eprintln!("Ignoring unexpected input `{other}`");
+                        continue;
+                    }
+                };
+                let seq = integer_parameter(&metadata.parameters, SEQ_KEY)?;
+                let Some(other) = pending.remove(&(seq, !is_dictionary)) else {
+                    pending.insert((seq, is_dictionary), data.0);
+                    continue;
+                };
+                let (dictionary, plain) = if is_dictionary {
+                    (data.0, other)
+                } else {
+                    (other, data.0)
+                };
+
+                let dictionary = dictionary
+                    .as_dictionary_opt::<Int16Type>()
+                    .ok_or_eyre("expected a dictionary array with Int16 keys")?;
+                let plain = plain
+                    .as_string_opt::<i32>()
+                    .ok_or_eyre("expected a plain Utf8 array")?;
+                if dictionary.len() != plain.len() {
+                    bail!(
+                        "batch {seq} has {} and {} rows",
+                        dictionary.len(),
+                        plain.len()
+                    );
+                }
+                report.batches += 1;
+                report.rows += plain.len() as u64;
+                report.dictionary_bytes += payload_bytes(&dictionary.to_data()) as u64;
+                report.plain_bytes += payload_bytes(&plain.to_data()) as u64;
+                dictionary_entries += dictionary.values().len();
+
+                // correct: resolve the keys with the dictionary of this message
+                let labels = dictionary
+                    .downcast_dict::<StringArray>()
+                    .ok_or_eyre("expected Utf8 dictionary values")?;
+                for (label, expected) in labels.into_iter().zip(plain) {
+                    if label != expected {
+                        report.mismatches += 1;
+                    }
+                }
+
+                // aggregations can work on the keys and only look at each label once
+                let mut key_counts = vec![0u64; dictionary.values().len()];
+                for key in dictionary.keys().iter().flatten() {
+                    key_counts[key as usize] += 1;
+                }
+                let values = dictionary
+                    .values()
+                    .as_string_opt::<i32>()
+                    .ok_or_eyre("expected Utf8 dictionary values")?;
+                for (label, count) in values.iter().zip(key_counts) {
+                    if let Some(label) = label {
+                        *label_counts.entry(label.to_owned()).or_default() += count;
+                    }
+                }
+
+                // wrong: reuse the dictionary of an earlier message
+                let stale = first_dictionary.get_or_insert_with(|| {
+                    values
+                        .iter()
+                        .map(|v| v.unwrap_or_default().to_owned())
+                        .collect()
+                });
+                for (key, expected) in dictionary.keys().iter().zip(plain) {
+                    let decoded = key.and_then(|key| stale.get(key as usize));
+                    if decoded.map(String::as_str) != expected {
+                        report.stale_dictionary_errors += 1;
+                    }
+                }
+            }
+            Event::InputClosed { .. } => {
+                open_inputs -= 1;
+                if open_inputs == 0 {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    if !pending.is_empty() {
+        bail!("{} batches arrived in only one encoding", pending.len());
+    }
+
+    if report.batches > 0 {
+        report.mean_dictionary_len = dictionary_entries as f64 / report.batches as f64;
+    }
+    let mut most_common: Vec<_> = label_counts.iter().collect();
+    most_common.sort_by(|a, b| b.1.cmp(a.1));
+    for (label, count) in most_common.iter().take(3) {
+        println!("{count:>8}  {label}");
+    }
+    println!(
+        "{} batches, {} rows: dictionary {} KiB, plain {} KiB, {} mismatches, \
+         {} rows wrong with a stale dictionary",
+        report.batches,
+        report.rows,
+        report.dictionary_bytes / 1024,
+        report.plain_bytes / 1024,
+        report.mismatches,
+        report.stale_dictionary_errors
+    );
+    if let Some(parent) = report_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use dora_node_api::{MetadataParameters, Parameter, arrow::array::ArrayData};
+use eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the sequence number of a batch, the same on both encodings.
+pub const SEQ_KEY: &str = "seq";
+
+const STATES: [&str; 4] = ["ok", "warning", "fault", "maintenance"];
+
+/// Returns `size` distinct labels like `site-03/line-07/station-112/warning`, the kind of
+/// long, repetitive strings that logs and telemetry are full of.
+pub fn vocabulary(size: usize) -> Vec<String> {
+    (0..size)
+        .map(|i| {
+            format!(
+                "site-{:02}/line-{:02}/station-{:03}/{}",
+                i % 7,
+                i % 13,
+                i / 4,
+                STATES[i % STATES.len()]
+            )
+        })
+        .collect()
+}
+
+/// Number of bytes in the buffers of an array, including its children and dictionary. This
+/// is what a message of the array transfers, apart from the metadata.
+pub fn payload_bytes(data: &ArrayData) -> usize {
+    let buffers: usize = data.buffers().iter().map(|buffer| buffer.len()).sum();
+    let nulls = data.nulls().map_or(0, |nulls| nulls.buffer().len());
+    let children: usize = data.child_data().iter().map(payload_bytes).sum();
+    buffers + nulls + children
+}
+
+/// Written by the consumer when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Report {
+    pub batches: u64,
+    pub rows: u64,
+    pub dictionary_bytes: u64,
+    pub plain_bytes: u64,
+    /// Mean number of entries in the dictionary of a batch.
+    pub mean_dictionary_len: f64,
+    /// Rows whose decoded dictionary value differed from the plain value.
+    pub mismatches: u64,
+    /// Rows that would have been decoded wrongly by reusing the dictionary of the first batch.
+    pub stale_dictionary_errors: u64,
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<u64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => {
+            u64::try_from(*value).map_err(|_| eyre!("negative `{key}` parameter"))
+        }
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
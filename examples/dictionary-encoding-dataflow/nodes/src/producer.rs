@@ -0,0 +1,70 @@
+use dictionary_encoding_dataflow_nodes::{SEQ_KEY, env_or, vocabulary};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter,
+    arrow::{
+        array::{StringArray, StringDictionaryBuilder},
+        datatypes::Int16Type,
+    },
+    dora_core::config::DataId,
+};
+use rand::Rng;
+
+/// Sends batches of `ROWS` labels from a vocabulary of `VOCABULARY` distinct strings, once as
+/// a dictionary array on `dictionary` and once as a plain `Utf8` array on `plain`.
+///
+/// Each dictionary only contains the labels of its own batch, in order of first appearance,
+/// so the same label has different keys in different batches.
+fn main() -> eyre::Result<()> {
+    let vocabulary = vocabulary(env_or("VOCABULARY", 2000)?);
+    let rows: usize = env_or("ROWS", 5000)?;
+    let batches: u64 = env_or("BATCHES", 100)?;
+    let dictionary_output = DataId::from("dictionary".to_owned());
+    let plain_output = DataId::from("plain".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut rng = rand::thread_rng();
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if seq == batches {
+                        break;
+                    }
+                    // skewed towards the start of the vocabulary, like real labels: a few
+                    // are very common, most are rare
+                    let labels: Vec<&str> = (0..rows)
+                        .map(|_| {
+                            let skewed: f64 = rng.gen_range(0.0..1.0_f64).powi(3);
+                            vocabulary[(skewed * vocabulary.len() as f64) as usize].as_str()
+                        })
+                        .collect();
+
+                    let mut builder = StringDictionaryBuilder::<Int16Type>::new();
+                    for label in &labels {
+                        builder.append(label)?;
+                    }
+                    let dictionary = builder.finish();
+                    let plain = StringArray::from_iter_values(&labels);
+
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+                    node.send_output(dictionary_output.clone(), parameters.clone(), dictionary)?;
+                    node.send_output(plain_output.clone(), parameters, plain)?;
+                    seq += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} batches in both encodings");
+    Ok(())
+}
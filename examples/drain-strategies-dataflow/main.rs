@@ -0,0 +1,97 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path};
+
+/// Must match the consumers in `dataflow.yml`.
+const STRATEGIES: [&str; 3] = ["fifo", "round-robin", "newest-first"];
+
+/// Subset of `InputReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct InputReport {
+    received: u64,
+    processed: u64,
+    skipped: u64,
+    lost: u64,
+    p50_ms: f64,
+    p95_ms: f64,
+    max_ms: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("drain-strategies-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let mut reports = BTreeMap::new();
+    for strategy in STRATEGIES {
+        let path = format!("out/{strategy}.json");
+        let report: BTreeMap<String, InputReport> = serde_json::from_str(
+            &std::fs::read_to_string(&path)
+                .with_context(|| format!("`{strategy}` did not write {path}"))?,
+        )?;
+        reports.insert(strategy, report);
+    }
+
+    println!(
+        "{:<13} {:<8} {:>9} {:>8} {:>5} {:>9} {:>9} {:>9}",
+        "strategy", "input", "processed", "skipped", "lost", "p50 ms", "p95 ms", "max ms"
+    );
+    for strategy in STRATEGIES {
+        for (input, report) in &reports[strategy] {
+            println!(
+                "{strategy:<13} {input:<8} {:>9} {:>8} {:>5} {:>9.1} {:>9.1} {:>9.1}",
+                format!("{}/{}", report.processed, report.received),
+                report.skipped,
+                report.lost,
+                report.p50_ms,
+                report.p95_ms,
+                report.max_ms
+            );
+        }
+    }
+
+    let input = |strategy: &str, input: &str| -> eyre::Result<&InputReport> {
+        reports[strategy]
+            .get(input)
+            .ok_or_else(|| eyre::eyre!("`{strategy}` has no report of `{input}`"))
+    };
+    let fifo_commands = input("fifo", "command")?;
+    let newest_commands = input("newest-first", "command")?;
+    if newest_commands.processed == 0 || newest_commands.processed != newest_commands.received {
+        bail!("`newest-first` must process every command it receives");
+    }
+    if newest_commands.p95_ms >= fifo_commands.p95_ms {
+        bail!(
+            "expected commands to wait less with `newest-first` than with `fifo` \
+             (p95 {:.1} ms vs. {:.1} ms)",
+            newest_commands.p95_ms,
+            fifo_commands.p95_ms
+        );
+    }
+    let fifo_images = input("fifo", "image")?;
+    let newest_images = input("newest-first", "image")?;
+    if newest_images.skipped == 0 {
+        bail!("expected `newest-first` to skip images under overload");
+    }
+    if newest_images.p50_ms >= fifo_images.p50_ms {
+        bail!(
+            "expected fresher images with `newest-first` than with `fifo` \
+             (p50 {:.1} ms vs. {:.1} ms)",
+            newest_images.p50_ms,
+            fifo_images.p50_ms
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use dora_node_api::{self, DoraNode, Event, EventStream};
+use drain_strategies_dataflow_nodes::{
+    InputReport, SENT_AT_KEY, SEQ_KEY, StrategyReport, env_or, integer_parameter, now_us,
+};
+use eyre::{bail, eyre};
+use std::{collections::VecDeque, path::PathBuf, str::FromStr, time::Duration};
+
+/// In which order the consumer processes the messages that are waiting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strategy {
+    /// One event at a time, in the order in which they arrive, across all inputs. Waiting
+    /// messages stay in the input queues of dora, which drop the oldest when they are full.
+    Fifo,
+    /// Takes all waiting messages, and alternates between the inputs.
+    RoundRobin,
+    /// Takes all waiting messages. Processes commands first, in order, then only the newest
+    /// image, dropping the older ones.
+    NewestFirst,
+}
+
+impl FromStr for Strategy {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fifo" => Ok(Self::Fifo),
+            "round-robin" => Ok(Self::RoundRobin),
+            "newest-first" => Ok(Self::NewestFirst),
+            other => Err(eyre!(
+                "unknown strategy `{other}`, expected `fifo`, `round-robin`, or `newest-first`"
+            )),
+        }
+    }
+}
+
+const INPUTS: [&str; 2] = ["image", "command"];
+
+#[derive(Debug)]
+struct Message {
+    /// Index into `INPUTS`.
+    input: usize,
+    sent_at_us: u64,
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    received: u64,
+    skipped: u64,
+    lost: u64,
+    next_seq: u64,
+    latencies_us: Vec<u64>,
+}
+
+/// Processes `image` and `command` messages with the given `STRATEGY`, simulating the
+/// processing time with `IMAGE_COST_MS` and `COMMAND_COST_MS`.
+///
+/// Writes the latency of each input, from the source until the message was processed, to
+/// `REPORT_FILE` when all inputs are closed.
+fn main() -> eyre::Result<()> {
+    let strategy: Strategy = env_or("STRATEGY", Strategy::Fifo)?;
+    let costs = [
+        Duration::from_millis(env_or("IMAGE_COST_MS", 45)?),
+        Duration::from_millis(env_or("COMMAND_COST_MS", 2)?),
+    ];
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/report.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut stats: [Stats; 2] = Default::default();
+    let mut pending: VecDeque<Message> = VecDeque::new();
+    let mut open = true;
+    let mut next_input = 0;
+    loop {
+        if pending.is_empty() {
+            if !open {
+                break;
+            }
+            match events.recv() {
+                Some(event) => open = accept(event, &mut pending, &mut stats)?,
+                None => open = false,
+            }
+        }
+        if strategy != Strategy::Fifo {
+            open = open && drain(&mut events, &mut pending, &mut stats)?;
+        }
+
+        let next = match strategy {
+            Strategy::Fifo => pending.pop_front(),
+            Strategy::RoundRobin => {
+                let position = pending
+                    .iter()
+                    .position(|message| message.input == next_input)
+                    .or_else(|| (!pending.is_empty()).then_some(0));
+                let message = position.and_then(|position| pending.remove(position));
+                if let Some(message) = &message {
+                    next_input = (message.input + 1) % INPUTS.len();
+                }
+                message
+            }
+            Strategy::NewestFirst => {
+                let command = pending.iter().position(|message| message.input == 1);
+                match command {
+                    Some(position) => pending.remove(position),
+                    None => {
+                        let newest = pending.pop_back();
+                        stats[0].skipped += pending.len() as u64;
+                        pending.clear();
+                        newest
+                    }
+                }
+            }
+        };
+        let Some(message) = next else {
+            continue;
+        };
+        std::thread::sleep(costs[message.input]);
+        stats[message.input]
+            .latencies_us
+            .push(now_us().saturating_sub(message.sent_at_us));
+    }
+
+    let mut report = StrategyReport::new();
+    for (input, mut stats) in INPUTS.into_iter().zip(stats) {
+        stats.latencies_us.sort_unstable();
+        let percentile = |p: usize| {
+            let index = (stats.latencies_us.len() * p / 100).min(stats.latencies_us.len() - 1);
+            stats.latencies_us[index] as f64 / 1000.0
+        };
+        let mut input_report = InputReport {
+            received: stats.received,
+            processed: stats.latencies_us.len() as u64,
+            skipped: stats.skipped,
+            lost: stats.lost,
+            ..Default::default()
+        };
+        if !stats.latencies_us.is_empty() {
+            input_report.p50_ms = percentile(50);
+            input_report.p95_ms = percentile(95);
+            input_report.max_ms = percentile(100);
+        }
+        println!(
+            "{strategy:?} {input}: processed {} of {} received, {} skipped, {} lost, latency \
+             p50 {:.1} ms, p95 {:.1} ms, max {:.1} ms",
+            input_report.processed,
+            input_report.received,
+            input_report.skipped,
+            input_report.lost,
+            input_report.p50_ms,
+            input_report.p95_ms,
+            input_report.max_ms
+        );
+        report.insert(input.to_owned(), input_report);
+    }
+    if let Some(parent) = report_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
+
+/// Moves all events that have already arrived to `pending`, without waiting. Returns `false`
+/// when the dataflow is stopping.
+fn drain(
+    events: &mut EventStream,
+    pending: &mut VecDeque<Message>,
+    stats: &mut [Stats; 2],
+) -> eyre::Result<bool> {
+    while let Ok(event) = events.try_recv() {
+        if !accept(event, pending, stats)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Queues the message of an input event. Returns `false` when the dataflow is stopping.
+fn accept(
+    event: Event,
+    pending: &mut VecDeque<Message>,
+    stats: &mut [Stats; 2],
+) -> eyre::Result<bool> {
+    match event {
+        Event::Input {
+            id,
+            metadata,
+            data: _,
+        } => {
+            let Some(input) = INPUTS.iter().position(|input| *input == id.as_str()) else {
+                eprintln!("Ignoring unexpected input `{id}`");
+                return Ok(true);
+            };
+            let seq = integer_parameter(&metadata.parameters, SEQ_KEY)?;
+            let stats = &mut stats[input];
+            if seq < stats.next_seq {
+                bail!("`{id}` went backwards from {} to {seq}", stats.next_seq - 1);
+            }
+            stats.lost += seq - stats.next_seq;
+            stats.next_seq = seq + 1;
+            stats.received += 1;
+            pending.push_back(Message {
+                input,
+                sent_at_us: integer_parameter(&metadata.parameters, SENT_AT_KEY)?,
+            });
+        }
+        Event::InputClosed { id } => println!("Input `{id}` was closed"),
+        Event::Stop(_) => return Ok(false),
+        other => eprintln!("Received unexpected input: {other:?}"),
+    }
+    Ok(true)
+}
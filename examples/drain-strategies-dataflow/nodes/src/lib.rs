@@ -0,0 +1,45 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub use example_node_utils::{env_or, now_us};
+
+/// Metadata key of the sequence number of a message, per source.
+pub const SEQ_KEY: &str = "seq";
+/// Metadata key of the wall-clock time at which the source sent a message, in microseconds.
+pub const SENT_AT_KEY: &str = "sent_at_us";
+
+/// Latency of one input of a consumer, from the source until the message was processed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InputReport {
+    pub received: u64,
+    pub processed: u64,
+    /// Messages that the strategy dropped in favor of newer ones.
+    pub skipped: u64,
+    /// Messages that never arrived, because the input queue was full.
+    pub lost: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Written by each consumer when its inputs are closed, by input id.
+pub type StrategyReport = BTreeMap<String, InputReport>;
+
+pub fn parameters(seq: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+    parameters.insert(SENT_AT_KEY.into(), Parameter::Integer(now_us() as i64));
+    parameters
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<u64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => {
+            u64::try_from(*value).map_err(|_| eyre!("negative `{key}` parameter"))
+        }
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
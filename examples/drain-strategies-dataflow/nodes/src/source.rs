@@ -0,0 +1,42 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::UInt8Array, dora_core::config::DataId};
+use drain_strategies_dataflow_nodes::{env_or, parameters};
+use std::time::{Duration, Instant};
+
+/// Sends a payload of `PAYLOAD_BYTES` on `data` on every `tick`, for `DURATION_MS`.
+///
+/// Every message carries its sequence number and the time it was sent.
+fn main() -> eyre::Result<()> {
+    let payload_bytes: usize = env_or("PAYLOAD_BYTES", 16)?;
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let output = DataId::from("data".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    let payload = UInt8Array::from(vec![seq as u8; payload_bytes]);
+                    node.send_output(output.clone(), parameters(seq), payload)?;
+                    seq += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} messages");
+    Ok(())
+}
@@ -0,0 +1,38 @@
+#!/usr/bin/env python3
+"""
+Lane-keeping controller.
+- Consumes `lane_state` ([speed, lateral_offset, heading_error, curvature])
+- Steers with the Stanley control law plus a curvature feed-forward term
+- Holds `TARGET_SPEED` with a proportional throttle controller
+- Publishes `control` ([steer_rad, throttle]) and stops when `lane_state` closes
+"""
+
+import math
+import os
+
+import pyarrow as pa
+from dora import Node
+
+TARGET_SPEED = float(os.getenv("TARGET_SPEED", "8.0"))
+STANLEY_GAIN = float(os.getenv("STANLEY_GAIN", "1.2"))
+WHEELBASE = float(os.getenv("WHEELBASE", "2.7"))
+SPEED_GAIN = 0.3
+
+
+def main():
+    node = Node()
+    for event in node:
+        if event["type"] == "INPUT_CLOSED" and event["id"] == "lane_state":
+            break
+        if event["type"] != "INPUT" or event["id"] != "lane_state":
+            continue
+        speed, offset, heading_error, curvature = event["value"].to_pylist()
+
+        feed_forward = math.atan(WHEELBASE * curvature)
+        steer = feed_forward - heading_error - math.atan2(STANLEY_GAIN * offset, speed + 1.0)
+        throttle = max(-1.0, min(1.0, SPEED_GAIN * (TARGET_SPEED - speed)))
+        node.send_output("control", pa.array([steer, throttle]), event["metadata"])
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,32 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::WrapErr;
+use std::path::Path;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("driving-sim-dataflow-runner")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // pass `--carla` to install the CARLA client; the server needs to be started separately
+    let carla = std::env::args().any(|arg| arg == "--carla");
+
+    let dora = Dora::from_env()?.uv();
+    let venv = dora.uv_venv("3.10");
+    venv.create().await?;
+
+    if carla {
+        venv.pip_install(["carla"])
+            .await
+            .context("failed to install the CARLA python client")?;
+    }
+
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+#!/usr/bin/env python3
+"""
+Telemetry recorder.
+- Writes every `pose`, `lane_state` and `control` message as a row of `TELEMETRY_FILE` (CSV)
+- Stops after `DURATION_TICKS` lane states, publishes `done` and prints a summary
+- Fails if the RMS lateral offset exceeds `MAX_RMS_OFFSET` once the car has settled
+"""
+
+import csv
+import math
+import os
+
+import pyarrow as pa
+from dora import Node
+
+TELEMETRY_FILE = os.getenv("TELEMETRY_FILE", "telemetry.csv")
+DURATION_TICKS = int(os.getenv("DURATION_TICKS", "600"))
+SETTLE_TICKS = int(os.getenv("SETTLE_TICKS", "100"))
+MAX_RMS_OFFSET = float(os.getenv("MAX_RMS_OFFSET", "0.5"))
+
+
+def main():
+    node = Node()
+    offsets = []
+    backend = None
+
+    with open(TELEMETRY_FILE, "w", newline="") as file:
+        writer = csv.writer(file)
+        writer.writerow(["input", "v0", "v1", "v2", "v3"])
+        for event in node:
+            if event["type"] != "INPUT":
+                continue
+            values = event["value"].to_pylist()
+            writer.writerow([event["id"], *values])
+            if event["id"] == "lane_state":
+                backend = event["metadata"].get("backend")
+                offsets.append(values[1])
+                if len(offsets) >= DURATION_TICKS:
+                    node.send_output("done", pa.array([True]))
+                    break
+
+    settled = offsets[SETTLE_TICKS:]
+    if not settled:
+        raise RuntimeError(f"only {len(offsets)} lane states received")
+    rms = math.sqrt(sum(o * o for o in settled) / len(settled))
+    print(
+        f"[{backend}] recorded {len(offsets)} lane states to {TELEMETRY_FILE}, "
+        f"RMS lateral offset {rms:.3f} m, max {max(abs(o) for o in settled):.3f} m",
+        flush=True,
+    )
+    if rms > MAX_RMS_OFFSET:
+        raise RuntimeError(f"RMS lateral offset {rms:.3f} m exceeds {MAX_RMS_OFFSET} m")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,159 @@
+#!/usr/bin/env python3
+"""
+Simulator client node for the driving example.
+- Steps either a CARLA simulation (if the `carla` package is installed and a server answers)
+  or a bundled 2D kinematic bicycle model driving on a sinusoidal road
+- Applies the latest `control` input ([steer_rad, throttle]) on every tick
+- Publishes `lane_state` ([speed, lateral_offset, heading_error, curvature]) and `pose` ([x, y, yaw])
+- Stops on the `done` input of the recorder, or when `control` or `done` close
+
+All quantities use a right-handed frame: x forward, y left, yaw counter-clockwise, positive
+steering turns left and a positive lateral offset means the car is left of the lane center.
+"""
+
+import math
+import os
+
+import pyarrow as pa
+from dora import Node
+
+BACKEND = os.getenv("SIM_BACKEND", "auto")
+TICK_DT = float(os.getenv("TICK_DT", "0.05"))
+MAX_STEER = float(os.getenv("MAX_STEER", "0.5"))
+CARLA_HOST = os.getenv("CARLA_HOST", "127.0.0.1")
+CARLA_PORT = int(os.getenv("CARLA_PORT", "2000"))
+
+
+def wrap_angle(angle):
+    return math.atan2(math.sin(angle), math.cos(angle))
+
+
+class KinematicSim:
+    """Kinematic bicycle model on the road `y = amplitude * sin(2 pi x / wavelength)`."""
+
+    name = "kinematic"
+
+    def __init__(self, wheelbase=2.7, amplitude=3.0, wavelength=80.0):
+        self.wheelbase = wheelbase
+        self.amplitude = amplitude
+        self.k = 2.0 * math.pi / wavelength
+        # start slightly off the lane center so the controller has something to do
+        self.x, self.y, self.yaw, self.v = 0.0, 1.0, 0.0, 0.0
+
+    def step(self, steer, throttle, dt):
+        accel = 3.0 * throttle - 0.02 * self.v * self.v
+        self.v = max(0.0, self.v + accel * dt)
+        self.x += self.v * math.cos(self.yaw) * dt
+        self.y += self.v * math.sin(self.yaw) * dt
+        self.yaw = wrap_angle(self.yaw + self.v / self.wheelbase * math.tan(steer) * dt)
+
+        slope = self.amplitude * self.k * math.cos(self.k * self.x)
+        road_heading = math.atan(slope)
+        second = -self.amplitude * self.k * self.k * math.sin(self.k * self.x)
+        curvature = second / (1.0 + slope * slope) ** 1.5
+        offset = (self.y - self.amplitude * math.sin(self.k * self.x)) * math.cos(road_heading)
+        return {
+            "pose": [self.x, self.y, self.yaw],
+            "lane": [self.v, offset, wrap_angle(self.yaw - road_heading), curvature],
+        }
+
+    def close(self):
+        pass
+
+
+class CarlaSim:
+    """Client for a running CARLA server, driving a spawned vehicle in synchronous mode."""
+
+    name = "carla"
+
+    def __init__(self, carla):
+        self.carla = carla
+        client = carla.Client(CARLA_HOST, CARLA_PORT)
+        client.set_timeout(5.0)
+        self.world = client.get_world()
+        settings = self.world.get_settings()
+        self.original_settings = self.world.get_settings()
+        settings.synchronous_mode = True
+        settings.fixed_delta_seconds = TICK_DT
+        self.world.apply_settings(settings)
+
+        blueprint = self.world.get_blueprint_library().filter("vehicle.tesla.model3")[0]
+        spawn_point = self.world.get_map().get_spawn_points()[0]
+        self.vehicle = self.world.spawn_actor(blueprint, spawn_point)
+
+    def step(self, steer, throttle, _dt):
+        control = self.carla.VehicleControl(
+            # CARLA steers right for positive values, normalized to [-1, 1]
+            steer=max(-1.0, min(1.0, -steer / MAX_STEER)),
+            throttle=max(0.0, throttle),
+            brake=max(0.0, -throttle),
+        )
+        self.vehicle.apply_control(control)
+        self.world.tick()
+
+        transform = self.vehicle.get_transform()
+        velocity = self.vehicle.get_velocity()
+        waypoint = self.world.get_map().get_waypoint(transform.location)
+        # CARLA is left-handed (y right, yaw clockwise): mirror y and yaw
+        x, y = transform.location.x, -transform.location.y
+        yaw = -math.radians(transform.rotation.yaw)
+        lane_x, lane_y = waypoint.transform.location.x, -waypoint.transform.location.y
+        lane_yaw = -math.radians(waypoint.transform.rotation.yaw)
+        offset = -math.sin(lane_yaw) * (x - lane_x) + math.cos(lane_yaw) * (y - lane_y)
+        ahead = waypoint.next(2.0)
+        curvature = 0.0
+        if ahead:
+            next_yaw = -math.radians(ahead[0].transform.rotation.yaw)
+            curvature = wrap_angle(next_yaw - lane_yaw) / 2.0
+        speed = math.hypot(velocity.x, velocity.y)
+        return {
+            "pose": [x, y, yaw],
+            "lane": [speed, offset, wrap_angle(yaw - lane_yaw), curvature],
+        }
+
+    def close(self):
+        self.vehicle.destroy()
+        self.world.apply_settings(self.original_settings)
+
+
+def create_sim():
+    if BACKEND in ("auto", "carla"):
+        try:
+            import carla
+
+            return CarlaSim(carla)
+        except Exception as err:
+            if BACKEND == "carla":
+                raise
+            print(f"CARLA not available ({err}), using the kinematic simulator", flush=True)
+    return KinematicSim()
+
+
+def main():
+    sim = create_sim()
+    print(f"Simulator backend: {sim.name}", flush=True)
+    node = Node()
+
+    steer, throttle = 0.0, 0.0
+    try:
+        for event in node:
+            if event["type"] == "INPUT_CLOSED" and event["id"] in ("control", "done"):
+                break
+            if event["type"] != "INPUT":
+                continue
+            if event["id"] == "done":
+                break
+            if event["id"] == "control":
+                steer, throttle = event["value"].to_pylist()
+                steer = max(-MAX_STEER, min(MAX_STEER, steer))
+            elif event["id"] == "tick":
+                state = sim.step(steer, throttle, TICK_DT)
+                metadata = {"backend": sim.name}
+                node.send_output("pose", pa.array(state["pose"]), metadata)
+                node.send_output("lane_state", pa.array(state["lane"]), metadata)
+    finally:
+        sim.close()
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,209 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, OptionExt, bail};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path, process::Stdio, time::Duration};
+use tokio::process::{Child, Command};
+
+/// How long each version of the detector runs.
+const RUN_FOR: Duration = Duration::from_secs(4);
+/// Longest allowed time between two frames at the recorder. The camera sends one every 50 ms.
+const MAX_INTERVAL_MS: u64 = 500;
+/// The edit: a new model, which only runs on every second frame.
+const EDITED_ENV: [(&str, &str); 2] = [("MODEL", "models/detector-v2.onnx"), ("EVERY_NTH", "2")];
+
+/// Subset of `RecorderReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct RecorderReport {
+    frames: u64,
+    missing: u64,
+    max_interval_ms: u64,
+    detections: BTreeMap<String, u64>,
+}
+
+/// Subset of `DetectorRun` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct DetectorRun {
+    model: String,
+    every_nth: u64,
+    frames: u64,
+    detections: u64,
+    first_seq: Option<u64>,
+    last_seq: Option<u64>,
+}
+
+fn node_mut<'a>(
+    dataflow: &'a mut serde_yaml::Value,
+    id: &str,
+) -> eyre::Result<&'a mut serde_yaml::Value> {
+    dataflow["nodes"]
+        .as_sequence_mut()
+        .and_then(|nodes| nodes.iter_mut().find(|node| node["id"] == id))
+        .ok_or_else(|| eyre::eyre!("dataflow has no node `{id}`"))
+}
+
+/// The `env` of node `id`, with all values as strings.
+fn node_env(dataflow: &serde_yaml::Value, id: &str) -> eyre::Result<Vec<(String, String)>> {
+    let node = dataflow["nodes"]
+        .as_sequence()
+        .and_then(|nodes| nodes.iter().find(|node| node["id"] == id))
+        .ok_or_else(|| eyre::eyre!("dataflow has no node `{id}`"))?;
+    let Some(env) = node["env"].as_mapping() else {
+        return Ok(Vec::new());
+    };
+    env.iter()
+        .map(|(key, value)| {
+            let key = key.as_str().ok_or_eyre("env keys must be strings")?;
+            let value = match value {
+                serde_yaml::Value::String(value) => value.clone(),
+                serde_yaml::Value::Number(value) => value.to_string(),
+                serde_yaml::Value::Bool(value) => value.to_string(),
+                _ => bail!("unsupported value of `{key}` in the env of `{id}`"),
+            };
+            Ok((key.to_owned(), value))
+        })
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("edit-while-running-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dataflow = Path::new("dataflow.yml");
+    let mut descriptor: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let count: u64 = node_env(&descriptor, "camera")?
+        .into_iter()
+        .find(|(key, _)| key == "COUNT")
+        .ok_or_eyre("`camera` has no `COUNT`")?
+        .1
+        .parse()?;
+    // the detector appends to its report, start with an empty one
+    let detector_report = Path::new("out/detector.jsonl");
+    if detector_report.exists() {
+        std::fs::remove_file(detector_report)?;
+    }
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+
+    let mut dataflow_proc = dora
+        .cli(["daemon", "--run-dataflow"])
+        .arg(dataflow)
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let detector = start_detector(&descriptor).await?;
+    tokio::time::sleep(RUN_FOR).await;
+    stop_detector(detector).await?;
+
+    // edit the env of the detector, as one would in `dataflow.yml`, but keep the original
+    let edited = Path::new("out/dataflow.edited.yml");
+    let env = &mut node_mut(&mut descriptor, "detector")?["env"];
+    for (key, value) in EDITED_ENV {
+        println!("editing `{key}` of `detector`: {:?} -> {value:?}", env[key]);
+        env[key] = value.into();
+    }
+    std::fs::write(edited, serde_yaml::to_string(&descriptor)?)?;
+    println!("wrote the edited dataflow to {}", edited.display());
+
+    let detector = start_detector(&descriptor).await?;
+    tokio::time::sleep(RUN_FOR).await;
+    stop_detector(detector).await?;
+
+    let status = dataflow_proc.wait().await?;
+    if !status.success() {
+        bail!("dataflow failed with {status}");
+    }
+
+    let recorder: RecorderReport = serde_json::from_str(
+        &std::fs::read_to_string("out/recorder.json")
+            .context("recorder did not write a report")?,
+    )?;
+    println!(
+        "recorder: {} frames, {} missing, at most {} ms apart",
+        recorder.frames, recorder.missing, recorder.max_interval_ms
+    );
+    for (model, detections) in &recorder.detections {
+        println!("recorder: {detections} detections of {model}");
+    }
+    if recorder.frames != count || recorder.missing != 0 {
+        bail!(
+            "the recorder should receive all {count} frames while the detector is restarted, \
+             received {} and missed {}",
+            recorder.frames,
+            recorder.missing
+        );
+    }
+    if recorder.max_interval_ms > MAX_INTERVAL_MS {
+        bail!(
+            "the frames stalled for {} ms, more than {MAX_INTERVAL_MS} ms",
+            recorder.max_interval_ms
+        );
+    }
+
+    let runs = std::fs::read_to_string(detector_report)
+        .context("the detector did not write a report")?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<DetectorRun>, _>>()?;
+    for run in &runs {
+        println!(
+            "detector: {} on every {}. frame, frames {:?}..={:?}, {} frames, {} detections",
+            run.model, run.every_nth, run.first_seq, run.last_seq, run.frames, run.detections
+        );
+    }
+    let [before, after] = runs.as_slice() else {
+        bail!("expected two runs of the detector, got {}", runs.len());
+    };
+    if before.model == after.model || after.model != EDITED_ENV[0].1 || after.every_nth != 2 {
+        bail!("the restarted detector did not use the edited env");
+    }
+    if before.frames == 0 || after.frames == 0 {
+        bail!("both versions of the detector should receive frames");
+    }
+    if after.first_seq <= before.last_seq {
+        bail!("the restarted detector should continue with newer frames");
+    }
+    println!("the detector was replaced without interrupting the camera and the recorder");
+    Ok(())
+}
+
+/// Starts the dynamic detector with the `env` of its node in `dataflow`, like one would from a
+/// terminal.
+///
+/// A dynamic node can only connect to a running dataflow, so this retries until the daemon has
+/// started it.
+async fn start_detector(dataflow: &serde_yaml::Value) -> eyre::Result<Child> {
+    let env = node_env(dataflow, "detector")?;
+    for _ in 0..30 {
+        let mut detector = Command::new("nodes/target/release/detector")
+            .envs(env.iter().cloned())
+            .stdin(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to start detector")?;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        match detector.try_wait()? {
+            None => return Ok(detector),
+            Some(status) => println!("detector exited with {status}, retrying"),
+        }
+    }
+    bail!("the detector could not connect to the dataflow");
+}
+
+/// Closes the stdin of the detector, which makes it stop.
+async fn stop_detector(mut detector: Child) -> eyre::Result<()> {
+    drop(detector.stdin.take());
+    let status = tokio::time::timeout(Duration::from_secs(10), detector.wait())
+        .await
+        .context("the detector did not stop")??;
+    if !status.success() {
+        bail!("the detector failed with {status}");
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::UInt64Array, dora_core::config::DataId};
+use edit_while_running_nodes::env_or;
+
+/// Sends the sequence number of a frame on `frame` on every tick, `COUNT` times.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 400)?;
+    let output = DataId::from("frame".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        UInt64Array::from(vec![seq]),
+                    )?;
+                    seq += 1;
+                    if seq == count {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("sent {seq} frames");
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, StringArray},
+        datatypes::UInt64Type,
+    },
+    dora_core::config::{DataId, NodeId},
+    merged::{MergeExternal, MergedEvent},
+};
+use edit_while_running_nodes::{Detection, DetectorRun, append_jsonl, env_or};
+use eyre::{OptionExt, bail};
+use std::{io::Read, path::PathBuf};
+
+/// Runs the `MODEL` on every `EVERY_NTH` frame, and sends a [`Detection`] for it on
+/// `detection`.
+///
+/// This is the dynamic `detector` node of `dataflow.yml`. It's started by hand, or by the
+/// runner, while the dataflow is running, and connects to it by its node id. The daemon doesn't
+/// spawn dynamic nodes, so it doesn't set their `env` either, whoever starts the detector
+/// passes it instead.
+///
+/// Stops when its stdin is closed, e.g. with Ctrl+D, or when the `frame` input is closed.
+/// Appends a [`DetectorRun`] to `REPORT_FILE` when it stops.
+fn main() -> eyre::Result<()> {
+    let model: String = env_or("MODEL", "models/detector-v1.onnx".to_owned())?;
+    let every_nth: u64 = env_or("EVERY_NTH", 1)?;
+    if every_nth == 0 {
+        bail!("EVERY_NTH must be at least 1");
+    }
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/detector.jsonl".to_owned())?.into();
+    let output = DataId::from("detection".to_owned());
+
+    let (mut node, events) = DoraNode::init_from_node_id(NodeId::from("detector".to_owned()))?;
+    println!("running {model} on every {every_nth}. frame");
+
+    // yields once when stdin is closed
+    let (stdin_closed, stdin_closed_rx) = futures::channel::oneshot::channel::<()>();
+    std::thread::spawn(move || {
+        let _ = std::io::stdin().lock().read_to_end(&mut Vec::new());
+        let _ = stdin_closed.send(());
+    });
+    let merged = events.merge_external(Box::pin(futures::stream::once(stdin_closed_rx)));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut run = DetectorRun {
+        model: model.clone(),
+        every_nth,
+        frames: 0,
+        detections: 0,
+        first_seq: None,
+        last_seq: None,
+    };
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, data, .. } => match id.as_str() {
+                    "frame" => {
+                        let seqs = data
+                            .as_primitive_opt::<UInt64Type>()
+                            .ok_or_eyre("expected a UInt64 array")?;
+                        for seq in seqs.iter().flatten() {
+                            run.frames += 1;
+                            run.first_seq.get_or_insert(seq);
+                            run.last_seq = Some(seq);
+                            if seq % every_nth != 0 {
+                                continue;
+                            }
+                            let detection = Detection {
+                                seq,
+                                model: model.clone(),
+                            };
+                            node.send_output(
+                                output.clone(),
+                                Default::default(),
+                                StringArray::from(vec![serde_json::to_string(&detection)?]),
+                            )?;
+                            run.detections += 1;
+                        }
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    break;
+                }
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(_) => {
+                println!("stdin was closed, stopping");
+                break;
+            }
+        }
+    }
+
+    println!(
+        "processed {} frames with {model}, sent {} detections",
+        run.frames, run.detections
+    );
+    append_jsonl(&report_file, &run)
+}
@@ -0,0 +1,51 @@
+use eyre::Context;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io::Write, path::Path};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Sent by the detector for every frame that it processed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Detection {
+    pub seq: u64,
+    /// The `MODEL` that the detector was started with.
+    pub model: String,
+}
+
+/// Appended to the report file by the detector whenever it stops, one line per run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectorRun {
+    pub model: String,
+    pub every_nth: u64,
+    pub frames: u64,
+    pub detections: u64,
+    pub first_seq: Option<u64>,
+    pub last_seq: Option<u64>,
+}
+
+/// Written by the recorder when the `frame` input is closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecorderReport {
+    pub frames: u64,
+    /// Frames that the recorder never received, judged by the gaps in `seq`.
+    pub missing: u64,
+    /// The longest time between two frames.
+    pub max_interval_ms: u64,
+    /// Received detections by model.
+    pub detections: BTreeMap<String, u64>,
+}
+
+/// Appends `value` as a line to the JSON Lines file at `path`, which keeps the lines of
+/// earlier runs.
+pub fn append_jsonl<T: Serialize>(path: &Path, value: &T) -> eyre::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(value)?)?;
+    Ok(())
+}
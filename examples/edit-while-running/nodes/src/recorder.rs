@@ -0,0 +1,77 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{array::AsArray, datatypes::UInt64Type},
+};
+use edit_while_running_nodes::{Detection, RecorderReport, env_or, write_json};
+use eyre::OptionExt;
+use std::{collections::BTreeMap, path::PathBuf, time::Instant};
+
+/// Records the `frame`s of the camera and the `detection`s of the detector.
+///
+/// The detector is restarted while the dataflow runs, so its input is closed and reopened in
+/// between. The frames come straight from the camera and must not be interrupted by that. The
+/// recorder checks them for gaps in `seq` and measures the longest time between two of them.
+///
+/// Writes a [`RecorderReport`] to `REPORT_FILE` when the `frame` input is closed.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/recorder.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = RecorderReport {
+        frames: 0,
+        missing: 0,
+        max_interval_ms: 0,
+        detections: BTreeMap::new(),
+    };
+    let mut next_seq = 0;
+    let mut last_frame: Option<Instant> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "frame" => {
+                    let seqs = data
+                        .as_primitive_opt::<UInt64Type>()
+                        .ok_or_eyre("expected a UInt64 array")?;
+                    for seq in seqs.iter().flatten() {
+                        if seq > next_seq {
+                            println!("missing frames {next_seq}..{seq}");
+                            report.missing += seq - next_seq;
+                        }
+                        next_seq = next_seq.max(seq + 1);
+                        report.frames += 1;
+                    }
+                    if let Some(last_frame) = last_frame {
+                        let interval = last_frame.elapsed().as_millis() as u64;
+                        report.max_interval_ms = report.max_interval_ms.max(interval);
+                    }
+                    last_frame = Some(Instant::now());
+                }
+                "detection" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    for row in rows.iter().flatten() {
+                        let detection: Detection = serde_json::from_str(row)?;
+                        *report.detections.entry(detection.model).or_default() += 1;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "frame" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "recorded {} frames, {} missing, at most {} ms apart",
+        report.frames, report.missing, report.max_interval_ms
+    );
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,127 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path};
+
+/// Must match the `env` of the sensor in `dataflow.yml`.
+const COUNT: u64 = 100;
+const NAN_EVERY: u64 = 17;
+const TIMEOUT_EVERY: u64 = 40;
+const SPIKE_EVERY: u64 = 25;
+
+/// Subset of `ErrorReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ErrorReport {
+    errors: BTreeMap<String, ErrorCount>,
+    total: u64,
+    misattributed: u64,
+}
+
+/// Subset of `ErrorCount` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ErrorCount {
+    count: u64,
+    severity: String,
+}
+
+/// Subset of `SinkReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkReport {
+    received: Vec<u64>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("error-channel-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let errors: ErrorReport = read_json("out/errors.json")?;
+    let sink: SinkReport = read_json("out/sink.json")?;
+
+    // the faults that the sensor simulates, by the 1-based number of the reading, see
+    // `nodes/src/sensor.rs`
+    let mut expected: BTreeMap<&str, (u64, &str)> = BTreeMap::new();
+    let mut expected_received = Vec::new();
+    for seq in 0..COUNT {
+        let nth = seq + 1;
+        let fault = if nth % TIMEOUT_EVERY == 0 {
+            Some(("sensor/read_timeout", "error"))
+        } else if nth % NAN_EVERY == 0 {
+            Some(("sensor/nan_reading", "warning"))
+        } else if nth % SPIKE_EVERY == 0 {
+            Some(("filter/out_of_range", "warning"))
+        } else {
+            None
+        };
+        match fault {
+            Some((key, severity)) => expected.entry(key).or_insert((0, severity)).0 += 1,
+            None => expected_received.push(seq),
+        }
+    }
+
+    for (key, count) in &errors.errors {
+        println!("{key}: {} ({})", count.count, count.severity);
+    }
+    for (key, (count, severity)) in &expected {
+        match errors.errors.get(*key) {
+            Some(actual) if actual.count == *count && actual.severity == *severity => {}
+            Some(actual) => bail!(
+                "expected {count} errors `{key}` of severity {severity}, the error handler \
+                 counted {} of severity {}",
+                actual.count,
+                actual.severity
+            ),
+            None => bail!("the error `{key}` never reached the error handler"),
+        }
+    }
+    if let Some(unexpected) = errors
+        .errors
+        .keys()
+        .find(|key| !expected.contains_key(key.as_str()))
+    {
+        bail!("the error handler received the unexpected error `{unexpected}`");
+    }
+    let expected_total: u64 = expected.values().map(|(count, _)| count).sum();
+    if errors.total != expected_total {
+        bail!(
+            "expected {expected_total} errors in total, the error handler counted {}",
+            errors.total
+        );
+    }
+    if errors.misattributed > 0 {
+        bail!(
+            "{} errors arrived on the input of another node than the one that reported them",
+            errors.misattributed
+        );
+    }
+    println!("all {expected_total} errors were routed to the error handler and counted");
+
+    if sink.received != expected_received {
+        bail!(
+            "the sink should receive the {} readings without a fault, received {}",
+            expected_received.len(),
+            sink.received.len()
+        );
+    }
+    println!(
+        "the sink received the {} readings without a fault",
+        sink.received.len()
+    );
+
+    println!("Everything Done");
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use dora_node_api::{self, DoraNode, Event};
+use error_channel_dataflow_nodes::{
+    ErrorCount, ErrorEvent, ErrorReport, Severity, env_or, write_json,
+};
+use eyre::Context;
+use std::path::PathBuf;
+
+/// The central error handler: receives the `errors` outputs of all nodes, one input per node
+/// named after it.
+///
+/// Logs every error in one format, and counts them by node and code. When all inputs are
+/// closed, it writes the counts to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", PathBuf::from("out/errors.json"))?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = ErrorReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => {
+                let json: &str =
+                    TryFrom::try_from(&data).context("expected an error event as JSON string")?;
+                let error: ErrorEvent = serde_json::from_str(json)
+                    .with_context(|| format!("invalid error event from `{id}`: {json}"))?;
+                // the input id is the node whose `errors` output it is
+                if error.node != id.as_str() {
+                    report.misattributed += 1;
+                }
+                let severity = match error.severity {
+                    Severity::Warning => "WARN",
+                    Severity::Error => "ERROR",
+                    Severity::Fatal => "FATAL",
+                };
+                println!(
+                    "[{severity}] {}/{}: {} {}",
+                    error.node, error.code, error.message, error.context
+                );
+
+                let count = report
+                    .errors
+                    .entry(format!("{}/{}", error.node, error.code))
+                    .or_insert(ErrorCount {
+                        count: 0,
+                        severity: error.severity,
+                        last_message: String::new(),
+                    });
+                count.count += 1;
+                count.severity = count.severity.max(error.severity);
+                count.last_message = error.message;
+                report.total += 1;
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            // the error handler is the end of the line, so it prints its own problems
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    for (key, count) in &report.errors {
+        println!("{key}: {} ({:?})", count.count, count.severity);
+    }
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,63 @@
+use dora_node_api::{self, DoraNode, Event, EventStream, IntoArrow, dora_core::config::DataId};
+use error_channel_dataflow_nodes::{ErrorReporter, Severity, env_or, seq, seq_parameters};
+use serde_json::json;
+
+/// Forwards the readings within `MIN`..=`MAX` on `filtered`.
+///
+/// Readings out of range are dropped and reported on `errors` as `out_of_range` warnings,
+/// readings that aren't a single float as `invalid_data` errors.
+fn main() -> eyre::Result<()> {
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let errors = ErrorReporter::new(&node);
+    let result = run(&mut node, events, &errors);
+    errors.fatal_on_error(&mut node, result)
+}
+
+fn run(node: &mut DoraNode, mut events: EventStream, errors: &ErrorReporter) -> eyre::Result<()> {
+    let min: f64 = env_or("MIN", -40.0)?;
+    let max: f64 = env_or("MAX", 125.0)?;
+    let output = DataId::from("filtered".to_owned());
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "reading" => {
+                    let seq = seq(&metadata.parameters)?;
+                    let value = match f64::try_from(&data) {
+                        Ok(value) => value,
+                        Err(err) => {
+                            errors.report(
+                                node,
+                                Severity::Error,
+                                "invalid_data",
+                                format!("reading {seq} is not a float: {err}"),
+                                json!({ "seq": seq, "data_type": data.data_type().to_string() }),
+                            )?;
+                            continue;
+                        }
+                    };
+                    if !(min..=max).contains(&value) {
+                        errors.report(
+                            node,
+                            Severity::Warning,
+                            "out_of_range",
+                            format!("reading {seq} is {value}, outside of {min}..={max}"),
+                            json!({ "seq": seq, "value": value, "min": min, "max": max }),
+                        )?;
+                        continue;
+                    }
+                    node.send_output(output.clone(), seq_parameters(seq), value.into_arrow())?;
+                }
+                other => errors.unexpected_event(node, &format!("input `{other}`"))?,
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "reading" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => errors.unexpected_event(node, &format!("{other:?}"))?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use dora_node_api::{
+    DoraNode, MetadataParameters, Parameter, arrow::array::StringArray, dora_core::config::DataId,
+};
+use eyre::{Context, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub use example_node_utils::{env_or, write_json};
+
+/// The output on which every node publishes its errors, see [`ErrorReporter`].
+pub const ERRORS_OUTPUT: &str = "errors";
+
+/// Metadata key of the sequence number of a reading.
+pub const SEQ_KEY: &str = "seq";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The node handled the problem, e.g. by skipping a message, and keeps running.
+    Warning,
+    /// An operation failed and its result is missing, but the node keeps running.
+    Error,
+    /// The node can't continue and exits with an error.
+    Fatal,
+}
+
+/// One message on an `errors` output, as one JSON string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorEvent {
+    /// The id of the node that reported the error.
+    pub node: String,
+    /// A stable identifier of the kind of error, e.g. `nan_reading`, for counting and alerts.
+    pub code: String,
+    pub severity: Severity,
+    /// A human readable description.
+    pub message: String,
+    /// Structured details, e.g. the sequence number of the affected message.
+    #[serde(default)]
+    pub context: serde_json::Value,
+}
+
+/// Publishes the errors of a node on its `errors` output, instead of printing them.
+///
+/// The output must be declared in `dataflow.yml`, and is consumed by the error handler, which
+/// logs and counts the errors of all nodes in one place.
+pub struct ErrorReporter {
+    node_id: String,
+    output: DataId,
+}
+
+impl ErrorReporter {
+    pub fn new(node: &DoraNode) -> Self {
+        Self {
+            node_id: node.id().to_string(),
+            output: DataId::from(ERRORS_OUTPUT.to_owned()),
+        }
+    }
+
+    pub fn report(
+        &self,
+        node: &mut DoraNode,
+        severity: Severity,
+        code: &str,
+        message: impl Into<String>,
+        context: serde_json::Value,
+    ) -> eyre::Result<()> {
+        let event = ErrorEvent {
+            node: self.node_id.clone(),
+            code: code.to_owned(),
+            severity,
+            message: message.into(),
+            context,
+        };
+        node.send_output(
+            self.output.clone(),
+            Default::default(),
+            StringArray::from(vec![serde_json::to_string(&event)?]),
+        )
+        .context("failed to report error")
+    }
+
+    /// Reports an unexpected event of the event loop, the usual `eprintln!` of a node.
+    pub fn unexpected_event(&self, node: &mut DoraNode, event: &str) -> eyre::Result<()> {
+        self.report(
+            node,
+            Severity::Warning,
+            "unexpected_event",
+            format!("received unexpected event {event}"),
+            serde_json::Value::Null,
+        )
+    }
+
+    /// Reports the error that ends a node as fatal, and passes it on.
+    ///
+    /// Call it with the result of the node's event loop, so that the error handler sees why a
+    /// node exited, and not only the daemon log.
+    pub fn fatal_on_error(
+        &self,
+        node: &mut DoraNode,
+        result: eyre::Result<()>,
+    ) -> eyre::Result<()> {
+        if let Err(err) = &result {
+            // the node fails with the original error anyway
+            let _ = self.report(
+                node,
+                Severity::Fatal,
+                "node_failed",
+                format!("{err:#}"),
+                serde_json::Value::Null,
+            );
+        }
+        result
+    }
+}
+
+pub fn seq(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(SEQ_KEY) {
+        Some(Parameter::Integer(seq)) => Ok(u64::try_from(*seq)?),
+        Some(other) => bail!("unexpected `{SEQ_KEY}` parameter {other:?}"),
+        None => bail!("input has no `{SEQ_KEY}` parameter"),
+    }
+}
+
+pub fn seq_parameters(seq: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+    parameters
+}
+
+/// The errors of one code of one node, as counted by the error handler.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorCount {
+    pub count: u64,
+    /// The highest severity of these errors.
+    pub severity: Severity,
+    pub last_message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ErrorReport {
+    /// Counts by `<node>/<code>`.
+    pub errors: BTreeMap<String, ErrorCount>,
+    pub total: u64,
+    /// Errors whose `node` didn't match the node of the input they arrived on.
+    pub misattributed: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SinkReport {
+    pub received: Vec<u64>,
+}
@@ -0,0 +1,71 @@
+use dora_node_api::{self, DoraNode, Event, EventStream, IntoArrow, dora_core::config::DataId};
+use error_channel_dataflow_nodes::{ErrorReporter, Severity, env_or, seq_parameters};
+use serde_json::json;
+
+/// Sends `COUNT` temperature readings on `reading`, one per `tick`, with their `seq` in the
+/// metadata.
+///
+/// Simulates the faults of a real sensor, and reports them on `errors` instead of printing
+/// them:
+///
+/// - Every `NAN_EVERY`th reading is NaN. The sensor skips it, a `nan_reading` warning.
+/// - Every `TIMEOUT_EVERY`th read times out, so there is no reading, a `read_timeout` error.
+/// - Every `SPIKE_EVERY`th reading is a spike far out of range, which the sensor doesn't
+///   notice. The filter reports it.
+fn main() -> eyre::Result<()> {
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let errors = ErrorReporter::new(&node);
+    let result = run(&mut node, events, &errors);
+    errors.fatal_on_error(&mut node, result)
+}
+
+fn run(node: &mut DoraNode, mut events: EventStream, errors: &ErrorReporter) -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 100)?;
+    let nan_every: u64 = env_or("NAN_EVERY", 17)?;
+    let timeout_every: u64 = env_or("TIMEOUT_EVERY", 40)?;
+    let spike_every: u64 = env_or("SPIKE_EVERY", 25)?;
+    let output = DataId::from("reading".to_owned());
+
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let nth = seq + 1;
+                    if nth % timeout_every == 0 {
+                        errors.report(
+                            node,
+                            Severity::Error,
+                            "read_timeout",
+                            "the sensor did not answer within 50 ms",
+                            json!({ "seq": seq, "timeout_ms": 50 }),
+                        )?;
+                    } else if nth % nan_every == 0 {
+                        errors.report(
+                            node,
+                            Severity::Warning,
+                            "nan_reading",
+                            format!("reading {seq} is NaN, skipping it"),
+                            json!({ "seq": seq }),
+                        )?;
+                    } else {
+                        let value = if nth % spike_every == 0 {
+                            500.0
+                        } else {
+                            20.0 + (seq as f64 * 0.3).sin()
+                        };
+                        node.send_output(output.clone(), seq_parameters(seq), value.into_arrow())?;
+                    }
+                    seq += 1;
+                    if seq == count {
+                        break;
+                    }
+                }
+                other => errors.unexpected_event(node, &format!("input `{other}`"))?,
+            },
+            Event::Stop(_) => break,
+            other => errors.unexpected_event(node, &format!("{other:?}"))?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use dora_node_api::{self, DoraNode, Event, EventStream};
+use error_channel_dataflow_nodes::{ErrorReporter, SinkReport, env_or, seq, write_json};
+use std::path::PathBuf;
+
+/// Records the sequence numbers of the `filtered` readings, and writes them to
+/// `REPORT_FILE` when the input closes.
+fn main() -> eyre::Result<()> {
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let errors = ErrorReporter::new(&node);
+    let result = run(&mut node, events, &errors);
+    errors.fatal_on_error(&mut node, result)
+}
+
+fn run(node: &mut DoraNode, mut events: EventStream, errors: &ErrorReporter) -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", PathBuf::from("out/sink.json"))?;
+
+    let mut report = SinkReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, .. } => match id.as_str() {
+                "filtered" => report.received.push(seq(&metadata.parameters)?),
+                other => errors.unexpected_event(node, &format!("input `{other}`"))?,
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "filtered" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => errors.unexpected_event(node, &format!("{other:?}"))?,
+        }
+    }
+
+    println!("received {} readings", report.received.len());
+    write_json(&report_file, &report)
+}
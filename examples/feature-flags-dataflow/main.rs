@@ -0,0 +1,179 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{path::Path, time::Duration};
+
+/// Must match `COUNT` of the camera in `dataflow.yml`.
+const FRAMES: u64 = 500;
+/// When the runner flips the flags, after starting the dataflow. The camera sends a frame every
+/// 20 ms, so this is about halfway through.
+const FLIP_AFTER: Duration = Duration::from_secs(5);
+/// Port of `flag-server` with `--http`.
+const FLAG_SERVER_PORT: &str = "18090";
+/// Must match `debug_frames` in `flags.json`.
+const DEBUG_PERCENT_BEFORE: u64 = 20;
+/// The flip turns denoising on, and sends more frames on `debug`.
+const DEBUG_PERCENT_AFTER: u64 = 80;
+
+/// Subset of `SinkReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkReport {
+    frames: Vec<FrameRecord>,
+}
+
+/// Subset of `FrameRecord` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct FrameRecord {
+    seq: u64,
+    flags_version: u64,
+    overlay: bool,
+    denoised: bool,
+    roughness: f64,
+    debug: bool,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("feature-flags-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    // pass `--http` to serve the flags with `flag-server` instead of reading the file directly
+    let http = std::env::args().any(|arg| arg == "--http");
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    std::fs::create_dir_all("out")?;
+    let flags_file = Path::new("out/flags.json");
+    let mut flags: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string("flags.json")?)?;
+    write_flags(flags_file, &flags)?;
+
+    let mut dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let mut flag_server = None;
+    if http {
+        let server = tokio::process::Command::new("nodes/target/release/flag-server")
+            .args(["--port", FLAG_SERVER_PORT])
+            .arg("--file")
+            .arg(flags_file)
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to start flag-server")?;
+        flag_server = Some(server);
+        // the flags node reads `FLAGS_SOURCE` from the environment that the nodes inherit
+        dora = dora.env(
+            "FLAGS_SOURCE",
+            format!("http://127.0.0.1:{FLAG_SERVER_PORT}/flags"),
+        );
+    }
+
+    let run = tokio::spawn(dora.run_dataflow(dataflow));
+    tokio::time::sleep(FLIP_AFTER).await;
+    // flip the flags in place, the nodes keep running
+    flags["flags"]["processor.denoise"]["enabled"] = true.into();
+    flags["flags"]["debug_frames"]["percent"] = DEBUG_PERCENT_AFTER.into();
+    write_flags(flags_file, &flags)?;
+    println!(
+        "flipped `processor.denoise` on and `debug_frames` to {DEBUG_PERCENT_AFTER} % while \
+         the dataflow is running"
+    );
+    run.await??;
+    if let Some(mut server) = flag_server {
+        server.kill().await?;
+    }
+
+    let report: SinkReport = serde_json::from_str(
+        &std::fs::read_to_string("out/sink.json").context("sink did not write a report")?,
+    )?;
+    let seqs: Vec<u64> = report.frames.iter().map(|frame| frame.seq).collect();
+    if seqs != (0..FRAMES).collect::<Vec<_>>() {
+        bail!(
+            "the sink should receive all {FRAMES} frames in order, while the flags change, \
+             received {}",
+            seqs.len()
+        );
+    }
+    if !report.frames.is_sorted_by_key(|frame| frame.flags_version) {
+        bail!("the flags versions of the frames should only increase");
+    }
+
+    // version 0 are the defaults before the first flags arrived, 1 the initial flags, and 2
+    // the flipped ones
+    let phase = |version: u64| -> Vec<&FrameRecord> {
+        report
+            .frames
+            .iter()
+            .filter(|frame| frame.flags_version == version)
+            .collect()
+    };
+    let (defaults, before, after) = (phase(0), phase(1), phase(2));
+    if defaults.len() + before.len() + after.len() != report.frames.len() {
+        bail!("the frames should only use the flags versions 0 to 2");
+    }
+    if before.len() < 50 || after.len() < 50 {
+        bail!(
+            "expected at least 50 frames before and after the flip, got {} and {}",
+            before.len(),
+            after.len()
+        );
+    }
+    println!(
+        "{} frames with the default flags, {} before the flip, {} after it",
+        defaults.len(),
+        before.len(),
+        after.len()
+    );
+
+    // the versions are those of the processor, the camera applies the flags independently
+    if defaults.iter().any(|frame| frame.denoised || frame.debug) {
+        bail!("all flags should be off before the first flags arrived");
+    }
+    // the flipped flags changed the processor, the flag of the camera stayed on
+    if after.iter().any(|frame| !frame.overlay) {
+        bail!("the camera should keep drawing its overlay after the flip");
+    }
+    if before.iter().any(|frame| frame.denoised) || after.iter().any(|frame| !frame.denoised) {
+        bail!("the processor should denoise exactly the frames after the flip");
+    }
+    let mean_roughness = |frames: &[&FrameRecord]| {
+        frames.iter().map(|frame| frame.roughness).sum::<f64>() / frames.len() as f64
+    };
+    let (rough_before, rough_after) = (mean_roughness(&before), mean_roughness(&after));
+    println!("mean roughness: {rough_before:.3} before the flip, {rough_after:.3} after it");
+    if rough_after > rough_before / 2.0 {
+        bail!("denoising should at least halve the roughness of the frames");
+    }
+
+    // a percentage flag selects a stable set of frames, so the shares are only roughly the
+    // percentage
+    for (frames, percent) in [
+        (&before, DEBUG_PERCENT_BEFORE),
+        (&after, DEBUG_PERCENT_AFTER),
+    ] {
+        let share =
+            100 * frames.iter().filter(|frame| frame.debug).count() as u64 / frames.len() as u64;
+        println!("{share} % of the frames sent on `debug` with `debug_frames` at {percent} %");
+        if share.abs_diff(percent) > 15 {
+            bail!("expected about {percent} % of the frames on `debug`, got {share} %");
+        }
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Replaces the flags file at once, so that the flags node never reads half of it.
+fn write_flags(path: &Path, flags: &serde_json::Value) -> eyre::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(flags)?)?;
+    std::fs::rename(&tmp, path).with_context(|| format!("failed to write {}", path.display()))
+}
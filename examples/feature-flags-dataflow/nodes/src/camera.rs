@@ -0,0 +1,60 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter, arrow::array::Float64Array, dora_core::config::DataId,
+};
+use feature_flags_dataflow_nodes::{NodeFlags, OVERLAY_KEY, SEQ_KEY, env_or};
+use std::f64::consts::TAU;
+
+/// Samples per frame.
+const FRAME_LEN: usize = 64;
+
+/// Sends `COUNT` frames on `frame`, one per `tick`: a sine wave with a noise pattern, which
+/// the processor can smooth.
+///
+/// Gated by the flag `overlay`: when it is on, the camera stamps its overlay into the frame,
+/// here only a metadata parameter.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 500)?;
+    let output = DataId::from("frame".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    let mut flags = NodeFlags::default();
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "flags" => flags.update(&metadata.parameters, &data)?,
+                "tick" => {
+                    let mut parameters = flags.parameters();
+                    parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+                    if flags.flags.enabled(&node_id, "overlay", seq) {
+                        parameters.insert(OVERLAY_KEY.into(), Parameter::Bool(true));
+                    }
+                    node.send_output(output.clone(), parameters, frame(seq))?;
+                    seq += 1;
+                    if seq == count {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} frames");
+    Ok(())
+}
+
+fn frame(seq: u64) -> Float64Array {
+    (0..FRAME_LEN)
+        .map(|i| {
+            let phase = TAU * i as f64 / FRAME_LEN as f64 + seq as f64 * 0.1;
+            let noise = if (i as u64 + seq).is_multiple_of(2) { 0.2 } else { -0.2 };
+            phase.sin() + noise
+        })
+        .collect()
+}
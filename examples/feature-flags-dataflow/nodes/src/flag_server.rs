@@ -0,0 +1,88 @@
+//! A minimal flag service that serves the flags in a file over HTTP, like a remote
+//! configuration service would.
+//!
+//! Usage: `flag-server --port <port> --file <path>`
+//!
+//! Answers every `GET` with the current content of `--file`, which it reads on each request,
+//! so that edits of the file are served right away. Runs until it is killed.
+
+use eyre::{Context, bail};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    time::Duration,
+};
+
+struct Args {
+    port: u16,
+    file: PathBuf,
+}
+
+fn main() -> eyre::Result<()> {
+    let args = parse_args()?;
+    let listener = TcpListener::bind(("127.0.0.1", args.port))
+        .with_context(|| format!("failed to listen on port {}", args.port))?;
+    println!("serving {} on port {}", args.file.display(), args.port);
+
+    for stream in listener.incoming() {
+        let stream = stream?;
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+        if let Err(err) = serve(&stream, &args) {
+            eprintln!("failed to serve request: {err:#}");
+        }
+    }
+    Ok(())
+}
+
+fn serve(mut stream: &TcpStream, args: &Args) -> eyre::Result<()> {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(stream);
+    reader.read_line(&mut request_line)?;
+    // skip the headers
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let (status, body) = if !request_line.starts_with("GET ") {
+        ("405 Method Not Allowed", String::new())
+    } else {
+        match std::fs::read_to_string(&args.file) {
+            Ok(body) => ("200 OK", body),
+            Err(err) => {
+                eprintln!("failed to read {}: {err}", args.file.display());
+                ("503 Service Unavailable", String::new())
+            }
+        }
+    };
+    let mut response = format!("HTTP/1.1 {status}\r\nConnection: close\r\n");
+    if !body.is_empty() {
+        response.push_str("Content-Type: application/json\r\n");
+    }
+    response.push_str(&format!("Content-Length: {}\r\n\r\n{body}", body.len()));
+    stream.write_all(response.as_bytes())?;
+    Ok(())
+}
+
+fn parse_args() -> eyre::Result<Args> {
+    let mut args = Args {
+        port: 18090,
+        file: PathBuf::from("out/flags.json"),
+    };
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = || {
+            argv.next()
+                .ok_or_else(|| eyre::eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--port" => args.port = value()?.parse()?,
+            "--file" => args.file = value()?.into(),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    Ok(args)
+}
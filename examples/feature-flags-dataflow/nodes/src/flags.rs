@@ -0,0 +1,93 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::StringArray,
+    dora_core::config::DataId,
+};
+use eyre::{Context, bail};
+use feature_flags_dataflow_nodes::{FLAGS_VERSION_KEY, FlagSet, env_or};
+use std::time::Duration;
+
+/// Serves the feature flags of the dataflow on `flags`.
+///
+/// Reads the flags from `FLAGS_SOURCE` on every `tick`, either a file or an `http://` URL
+/// that serves the same JSON. Whenever they change, it sends them as one JSON string, with
+/// a new `flags_version` in the metadata. Invalid flags and unreachable sources are
+/// reported, and the nodes keep the last valid flags.
+///
+/// Serves until the sink reports on `done` that the frames ended, or exits.
+fn main() -> eyre::Result<()> {
+    let source: String = env_or("FLAGS_SOURCE", "out/flags.json".to_owned())?;
+    let output = DataId::from("flags".to_owned());
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(1))
+        .build();
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut current: Option<FlagSet> = None;
+    let mut version = 0;
+    let mut last_error = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let flags = read(&agent, &source).and_then(|json| FlagSet::parse(&json));
+                    let flags = match flags {
+                        Ok(flags) => {
+                            last_error = None;
+                            flags
+                        }
+                        Err(err) => {
+                            // only report new errors, the source is read on every tick
+                            let err = format!("{err:#}");
+                            if last_error.as_ref() != Some(&err) {
+                                eprintln!("keeping the last valid flags: {err}");
+                                last_error = Some(err);
+                            }
+                            continue;
+                        }
+                    };
+                    if current.as_ref() == Some(&flags) {
+                        continue;
+                    }
+                    version += 1;
+                    println!("sending flags version {version}");
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(FLAGS_VERSION_KEY.into(), Parameter::Integer(version as i64));
+                    node.send_output(
+                        output.clone(),
+                        parameters,
+                        StringArray::from(vec![serde_json::to_string(&flags)?]),
+                    )?;
+                    current = Some(flags);
+                }
+                "done" => break,
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "done" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if current.is_none() {
+        bail!("never read valid flags from `{source}`");
+    }
+    Ok(())
+}
+
+fn read(agent: &ureq::Agent, source: &str) -> eyre::Result<String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = agent
+            .get(source)
+            .call()
+            .with_context(|| format!("failed to request {source}"))?;
+        Ok(response.into_string()?)
+    } else {
+        std::fs::read_to_string(source).with_context(|| format!("failed to read {source}"))
+    }
+}
@@ -0,0 +1,173 @@
+use dora_node_api::{ArrowData, MetadataParameters, Parameter};
+use eyre::{Context, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Metadata key of the version of the flags, on `flags` messages and on every message that
+/// was produced under a version of them. Version 0 are the defaults, before the first
+/// `flags` message arrived.
+pub const FLAGS_VERSION_KEY: &str = "flags_version";
+
+/// Metadata key of the sequence number of a frame.
+pub const SEQ_KEY: &str = "seq";
+
+/// Metadata key that the camera sets when it drew its overlay into a frame.
+pub const OVERLAY_KEY: &str = "overlay";
+
+/// Metadata key that the processor sets when it denoised a frame.
+pub const DENOISED_KEY: &str = "denoised";
+
+/// A feature flag, as written in `flags.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Flag {
+    Boolean {
+        enabled: bool,
+    },
+    /// On for `percent` of the keys, e.g. frames, selected by a stable hash of the flag name
+    /// and the key. Raising the percentage keeps the keys that were on before.
+    Percentage {
+        percent: u8,
+    },
+}
+
+/// The flags of all nodes, the content of `flags.json` and of the `flags` messages.
+///
+/// A flag named `<node>.<flag>` applies to that node only, and takes precedence over a flag
+/// named `<flag>`, which applies to all nodes. Unknown flags are off.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FlagSet {
+    pub flags: BTreeMap<String, Flag>,
+}
+
+impl FlagSet {
+    pub fn parse(json: &str) -> eyre::Result<Self> {
+        let flags: Self = serde_json::from_str(json).context("invalid flags")?;
+        for (name, flag) in &flags.flags {
+            if let Flag::Percentage { percent } = flag
+                && *percent > 100
+            {
+                bail!("flag `{name}` has a percentage of {percent}");
+            }
+        }
+        Ok(flags)
+    }
+
+    /// Whether the flag `name` is on for `key` in the node `node_id`.
+    ///
+    /// The key only matters for percentage flags. Pass the same key for the same unit of work
+    /// in all nodes, e.g. the sequence number of a frame, so that they agree on it.
+    pub fn enabled(&self, node_id: &str, name: &str, key: u64) -> bool {
+        let flag = self
+            .flags
+            .get(&format!("{node_id}.{name}"))
+            .or_else(|| self.flags.get(name));
+        match flag {
+            Some(Flag::Boolean { enabled }) => *enabled,
+            Some(Flag::Percentage { percent }) => bucket(name, key) < u64::from(*percent),
+            None => false,
+        }
+    }
+}
+
+/// The flags that a node currently uses, with their version.
+///
+/// Starts with the defaults, all flags off, and follows the `flags` input of the node, so
+/// that a change applies from the next message on, without a restart.
+#[derive(Debug, Default)]
+pub struct NodeFlags {
+    pub version: u64,
+    pub flags: FlagSet,
+}
+
+impl NodeFlags {
+    /// Applies a `flags` message.
+    pub fn update(
+        &mut self,
+        parameters: &MetadataParameters,
+        data: &ArrowData,
+    ) -> eyre::Result<()> {
+        let version = flags_version(parameters)?;
+        let json: &str = TryFrom::try_from(data).context("expected the flags as JSON string")?;
+        let flags = FlagSet::parse(json)?;
+        if version <= self.version {
+            // dora keeps the order of the messages of one output, so this is a bug
+            bail!(
+                "flags version {version} arrived after version {}",
+                self.version
+            );
+        }
+        println!("applying flags version {version}: {flags:?}");
+        self.version = version;
+        self.flags = flags;
+        Ok(())
+    }
+
+    /// Metadata parameters with the version of the flags.
+    pub fn parameters(&self) -> MetadataParameters {
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(
+            FLAGS_VERSION_KEY.into(),
+            Parameter::Integer(self.version as i64),
+        );
+        parameters
+    }
+}
+
+pub fn flags_version(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(FLAGS_VERSION_KEY) {
+        Some(Parameter::Integer(version)) => Ok(u64::try_from(*version)?),
+        Some(other) => bail!("unexpected `{FLAGS_VERSION_KEY}` parameter {other:?}"),
+        None => bail!("input has no `{FLAGS_VERSION_KEY}` parameter"),
+    }
+}
+
+pub fn seq(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(SEQ_KEY) {
+        Some(Parameter::Integer(seq)) => Ok(u64::try_from(*seq)?),
+        Some(other) => bail!("unexpected `{SEQ_KEY}` parameter {other:?}"),
+        None => bail!("input has no `{SEQ_KEY}` parameter"),
+    }
+}
+
+/// A boolean parameter, `false` if it is missing.
+pub fn bool_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<bool> {
+    match parameters.get(key) {
+        Some(Parameter::Bool(value)) => Ok(*value),
+        Some(other) => bail!("unexpected `{key}` parameter {other:?}"),
+        None => Ok(false),
+    }
+}
+
+/// The bucket of `key` in 0..100 for the flag `name`. A stable FNV-1a hash, so that all
+/// nodes and all runs agree on it, unlike the `DefaultHasher` of the standard library.
+fn bucket(name: &str, key: u64) -> u64 {
+    let hash = name
+        .bytes()
+        .chain(key.to_le_bytes())
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+    hash % 100
+}
+
+/// What the sink received for one frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameRecord {
+    pub seq: u64,
+    /// The version of the flags that the processor used for the frame.
+    pub flags_version: u64,
+    pub overlay: bool,
+    pub denoised: bool,
+    /// Mean absolute difference of neighboring samples, which denoising reduces.
+    pub roughness: f64,
+    /// Whether the processor also sent the frame on `debug`.
+    pub debug: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SinkReport {
+    pub frames: Vec<FrameRecord>,
+}
@@ -0,0 +1,84 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::{ContextCompat, bail};
+use feature_flags_dataflow_nodes::{
+    DENOISED_KEY, NodeFlags, OVERLAY_KEY, SEQ_KEY, bool_parameter, seq,
+};
+
+/// Forwards each `frame` on `result`, gated by two flags:
+///
+/// - `denoise`: smooths the frame with a moving average of three samples first.
+/// - `debug_frames`: a percentage flag, also sends the frames that it selects on `debug`,
+///   e.g. for a recording that would be too large for every frame.
+///
+/// The flags apply from the next frame after a `flags` message on, and each result carries
+/// the version of the flags that it was produced with.
+fn main() -> eyre::Result<()> {
+    let result_output = DataId::from("result".to_owned());
+    let debug_output = DataId::from("debug".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    let mut flags = NodeFlags::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "flags" => flags.update(&metadata.parameters, &data)?,
+                "frame" => {
+                    let seq = seq(&metadata.parameters)?;
+                    let frame = data
+                        .as_primitive_opt::<Float64Type>()
+                        .context("expected a Float64 frame")?;
+                    let mut parameters = flags.parameters();
+                    parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+                    if bool_parameter(&metadata.parameters, OVERLAY_KEY)? {
+                        parameters.insert(OVERLAY_KEY.into(), Parameter::Bool(true));
+                    }
+
+                    let denoise = flags.flags.enabled(&node_id, "denoise", seq);
+                    let result = if denoise {
+                        smooth(frame.values())
+                    } else {
+                        frame.clone()
+                    };
+                    parameters.insert(DENOISED_KEY.into(), Parameter::Bool(denoise));
+                    if flags.flags.enabled(&node_id, "debug_frames", seq) {
+                        node.send_output(debug_output.clone(), parameters.clone(), result.clone())?;
+                    }
+                    node.send_output(result_output.clone(), parameters, result)?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "frame" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if flags.version == 0 {
+        bail!("never received the flags");
+    }
+    Ok(())
+}
+
+/// Moving average over three samples, with the edges averaged over two.
+fn smooth(values: &[f64]) -> Float64Array {
+    (0..values.len())
+        .map(|i| {
+            let window = &values[i.saturating_sub(1)..(i + 2).min(values.len())];
+            window.iter().sum::<f64>() / window.len() as f64
+        })
+        .collect()
+}
@@ -0,0 +1,87 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters,
+    arrow::{
+        array::{AsArray, NullArray},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::ContextCompat;
+use feature_flags_dataflow_nodes::{
+    DENOISED_KEY, FrameRecord, OVERLAY_KEY, SinkReport, bool_parameter, env_or, flags_version, seq,
+    write_json,
+};
+use std::{collections::BTreeSet, path::PathBuf};
+
+/// Records the flags that each `result` was produced with, how rough it is, and whether it
+/// also arrived on `debug`. Writes the records to `REPORT_FILE` when both inputs end, and
+/// then sends `done`, which stops the flags node.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", PathBuf::from("out/sink.json"))?;
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = SinkReport::default();
+    let mut debug = BTreeSet::new();
+    // dora doesn't order the messages of different outputs, so wait for both to end
+    let mut open = BTreeSet::from(["result", "debug"]);
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "result" => {
+                    let frame = data
+                        .as_primitive_opt::<Float64Type>()
+                        .context("expected a Float64 frame")?;
+                    report
+                        .frames
+                        .push(record(&metadata.parameters, frame.values())?);
+                }
+                "debug" => {
+                    debug.insert(seq(&metadata.parameters)?);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                open.remove(id.as_str());
+                if open.is_empty() {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    for frame in &mut report.frames {
+        frame.debug = debug.contains(&frame.seq);
+    }
+    println!(
+        "received {} frames, {} of them on `debug`",
+        report.frames.len(),
+        debug.len()
+    );
+    write_json(&report_file, &report)?;
+    node.send_output(
+        DataId::from("done".to_owned()),
+        Default::default(),
+        NullArray::new(0),
+    )?;
+    Ok(())
+}
+
+fn record(parameters: &MetadataParameters, values: &[f64]) -> eyre::Result<FrameRecord> {
+    let roughness = values
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).abs())
+        .sum::<f64>()
+        / (values.len() - 1) as f64;
+    Ok(FrameRecord {
+        seq: seq(parameters)?,
+        flags_version: flags_version(parameters)?,
+        overlay: bool_parameter(parameters, OVERLAY_KEY)?,
+        denoised: bool_parameter(parameters, DENOISED_KEY)?,
+        roughness,
+        debug: false,
+    })
+}
@@ -0,0 +1,201 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Must match `FILE` of the tailers in `dataflow.yml`.
+const CSV_FILE: &str = "out/logs/sensors.csv";
+const JSONL_FILE: &str = "out/logs/events.jsonl";
+/// In another order than `COLUMNS`, the tailer maps them by name.
+const CSV_HEADER: &str = "sensor,timestamp,value";
+/// Rows before the dataflow starts, appended while it runs, and after the rotation.
+const INITIAL_ROWS: i64 = 20;
+const APPENDED_ROWS: i64 = 30;
+const ROTATED_ROWS: i64 = 25;
+
+/// Subset of `TailerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct TailerReport {
+    rows: u64,
+    skipped: u64,
+    rotations: u64,
+}
+
+/// Subset of `InputReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct InputReport {
+    rows: u64,
+    batches: u64,
+    timestamps: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Progress {
+    input: String,
+    total: u64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("file-tailer-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    std::fs::create_dir_all("out/logs")?;
+    // the legacy loggers, with some history before the dataflow starts
+    let mut csv = open_log(CSV_FILE)?;
+    let mut jsonl = open_log(JSONL_FILE)?;
+    writeln!(csv, "{CSV_HEADER}")?;
+    append_rows(&mut csv, &mut jsonl, 0..INITIAL_ROWS)?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    let mut run = dora.cli(["daemon", "--run-dataflow"]);
+    run.arg(dataflow).kill_on_drop(true);
+    let mut run = run.spawn().context("failed to run dataflow")?;
+
+    wait_for_rows(INITIAL_ROWS as u64, Duration::from_secs(120)).await?;
+    println!("the initial rows arrived, appending while the dataflow runs");
+
+    // in a few writes, like a logger would
+    let appended = INITIAL_ROWS..INITIAL_ROWS + APPENDED_ROWS;
+    for start in appended.clone().step_by(10) {
+        append_rows(&mut csv, &mut jsonl, start..(start + 10).min(appended.end))?;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+    }
+    // a line that the tailers must skip
+    writeln!(csv, "s0,not-a-timestamp,1.0")?;
+    writeln!(
+        jsonl,
+        r#"{{"timestamp": "not-a-timestamp", "sensor": "s0"}}"#
+    )?;
+    // a line that arrives in two writes, which the tailers must not split
+    let next = appended.end;
+    write!(csv, "s{},{next}", next % 3)?;
+    write!(jsonl, r#"{{"timestamp": {next}, "#)?;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    writeln!(csv, ",{}", next as f64 * 0.5)?;
+    writeln!(
+        jsonl,
+        r#""sensor": "s{}", "value": {}}}"#,
+        next % 3,
+        next as f64 * 0.5
+    )?;
+    let rotated_start = next + 1;
+    wait_for_rows(rotated_start as u64, Duration::from_secs(10)).await?;
+
+    // the CSV logger rotates by renaming, the JSONL one by copying and truncating
+    std::fs::rename(CSV_FILE, format!("{CSV_FILE}.1"))?;
+    let mut csv = open_log(CSV_FILE)?;
+    writeln!(csv, "{CSV_HEADER}")?;
+    std::fs::copy(JSONL_FILE, format!("{JSONL_FILE}.1"))?;
+    jsonl.set_len(0)?;
+    // a truncation is only noticed if the file is shorter than what was read
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let end = rotated_start + ROTATED_ROWS;
+    append_rows(&mut csv, &mut jsonl, rotated_start..end)?;
+    let total = end as u64;
+
+    // the tailers stop once the files are idle
+    let status = tokio::time::timeout(Duration::from_secs(60), run.wait())
+        .await
+        .context("the tailers did not stop")??;
+    if !status.success() {
+        bail!("failed to run dataflow");
+    }
+
+    for tailer in ["csv-tailer", "jsonl-tailer"] {
+        let report: TailerReport = read_json(format!("out/{tailer}.json"))?;
+        println!(
+            "{tailer}: {} rows, {} skipped, {} rotations",
+            report.rows, report.skipped, report.rotations
+        );
+        if report.rows != total || report.skipped != 1 || report.rotations != 1 {
+            bail!("{tailer} should send {total} rows, skip 1 line, and notice 1 rotation");
+        }
+    }
+    let consumer: BTreeMap<String, InputReport> = read_json("out/consumer.json")?;
+    for input in ["csv", "jsonl"] {
+        let Some(report) = consumer.get(input) else {
+            bail!("the consumer received nothing on `{input}`");
+        };
+        println!(
+            "consumer `{input}`: {} rows in {} batches",
+            report.rows, report.batches
+        );
+        if report.timestamps != (0..total as i64).collect::<Vec<_>>() {
+            bail!("the consumer should receive every row once and in order on `{input}`");
+        }
+        // the initial rows, the appended ones, and the ones after the rotation
+        if report.batches < 3 {
+            bail!("the rows on `{input}` should arrive in several batches while the files grow");
+        }
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Opens a log file for appending, like a logger does.
+fn open_log(path: &str) -> eyre::Result<std::fs::File> {
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {path}"))
+}
+
+fn append_rows(
+    csv: &mut std::fs::File,
+    jsonl: &mut std::fs::File,
+    timestamps: std::ops::Range<i64>,
+) -> eyre::Result<()> {
+    for timestamp in timestamps {
+        let (sensor, value) = (format!("s{}", timestamp % 3), timestamp as f64 * 0.5);
+        writeln!(csv, "{sensor},{timestamp},{value}")?;
+        let row = serde_json::json!({ "timestamp": timestamp, "sensor": sensor, "value": value });
+        writeln!(jsonl, "{row}")?;
+    }
+    Ok(())
+}
+
+/// Waits until the consumer received `rows` rows on both inputs.
+async fn wait_for_rows(rows: u64, timeout: Duration) -> eyre::Result<()> {
+    let start = Instant::now();
+    loop {
+        let mut totals = BTreeMap::new();
+        if let Ok(progress) = std::fs::read_to_string("out/progress.jsonl") {
+            // the last line may be incomplete
+            for progress in progress
+                .lines()
+                .map_while(|line| serde_json::from_str::<Progress>(line).ok())
+            {
+                totals.insert(progress.input, progress.total);
+            }
+        }
+        if ["csv", "jsonl"]
+            .iter()
+            .all(|input| totals.get(*input).is_some_and(|total| *total >= rows))
+        {
+            return Ok(());
+        }
+        if start.elapsed() > timeout {
+            bail!("the consumer did not receive {rows} rows per input in time: {totals:?}");
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
@@ -0,0 +1,73 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{Array, AsArray},
+        datatypes::{Float64Type, Int64Type},
+    },
+};
+use eyre::{OptionExt, eyre};
+use file_tailer_dataflow_nodes::{InputReport, env_or, write_json};
+use std::{collections::BTreeMap, io::Write, path::PathBuf};
+
+/// Receives the rows of the tailers on any input, as struct arrays with the columns
+/// `timestamp`, `sensor`, and `value`.
+///
+/// Appends a line per batch to `PROGRESS_FILE`, which the runner follows, and writes an
+/// [`InputReport`] per input to `REPORT_FILE` when all inputs are closed.
+fn main() -> eyre::Result<()> {
+    let progress_file: PathBuf = env_or("PROGRESS_FILE", "out/progress.jsonl".to_owned())?.into();
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/consumer.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    if let Some(parent) = progress_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut progress = std::fs::File::create(&progress_file)?;
+    let mut reports: BTreeMap<String, InputReport> = BTreeMap::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => {
+                let rows = data.as_struct_opt().ok_or_eyre("expected a struct array")?;
+                let column = |name: &str| {
+                    rows.column_by_name(name)
+                        .ok_or_else(|| eyre!("missing column `{name}`"))
+                };
+                let timestamps = column("timestamp")?
+                    .as_primitive_opt::<Int64Type>()
+                    .ok_or_eyre("`timestamp` is not an Int64 array")?;
+                let sensors = column("sensor")?
+                    .as_string_opt::<i32>()
+                    .ok_or_eyre("`sensor` is not a Utf8 array")?;
+                column("value")?
+                    .as_primitive_opt::<Float64Type>()
+                    .ok_or_eyre("`value` is not a Float64 array")?;
+
+                let report = reports.entry(id.to_string()).or_default();
+                report.rows += rows.len() as u64;
+                report.batches += 1;
+                report.timestamps.extend(timestamps.values().iter());
+                for sensor in sensors.iter().flatten() {
+                    *report.rows_per_sensor.entry(sensor.to_owned()).or_default() += 1;
+                }
+                let line = serde_json::json!({
+                    "input": id.as_str(),
+                    "rows": rows.len(),
+                    "total": report.rows,
+                });
+                writeln!(progress, "{line}")?;
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    for (input, report) in &reports {
+        println!(
+            "{input}: {} rows in {} batches, {:?}",
+            report.rows, report.batches, report.rows_per_sensor
+        );
+    }
+    write_json(&report_file, &reports)
+}
@@ -0,0 +1,204 @@
+use dora_node_api::arrow::{
+    array::{ArrayRef, Float64Array, Int64Array, StringArray, StructArray},
+    datatypes::{DataType, Field},
+};
+use eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+
+pub use example_node_utils::{env_or, write_json};
+
+pub mod tail;
+
+/// Written by a tailer when it stops.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TailerReport {
+    pub file: String,
+    pub rows: u64,
+    pub batches: u64,
+    /// Lines that didn't match the columns.
+    pub skipped: u64,
+    pub rotations: u64,
+}
+
+/// What the consumer received on one input.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputReport {
+    pub rows: u64,
+    pub batches: u64,
+    pub timestamps: Vec<i64>,
+    pub rows_per_sensor: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Int64,
+    Float64,
+    Utf8,
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    pub ty: ColumnType,
+}
+
+/// A parsed field of a row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int64(i64),
+    Float64(f64),
+    Utf8(String),
+}
+
+/// Parses columns like `timestamp:int64,sensor:utf8,value:float64`.
+pub fn parse_columns(spec: &str) -> eyre::Result<Vec<Column>> {
+    spec.split(',')
+        .map(|column| {
+            let (name, ty) = column
+                .trim()
+                .split_once(':')
+                .ok_or_else(|| eyre!("column `{column}` has no type"))?;
+            let ty = match ty {
+                "int64" => ColumnType::Int64,
+                "float64" => ColumnType::Float64,
+                "utf8" => ColumnType::Utf8,
+                other => bail!("unsupported type `{other}`, expected int64, float64, or utf8"),
+            };
+            Ok(Column {
+                name: name.to_owned(),
+                ty,
+            })
+        })
+        .collect()
+}
+
+impl ColumnType {
+    fn parse_text(self, text: &str) -> Result<Value, String> {
+        match self {
+            ColumnType::Int64 => text.parse().map(Value::Int64).map_err(|e| e.to_string()),
+            ColumnType::Float64 => text.parse().map(Value::Float64).map_err(|e| e.to_string()),
+            ColumnType::Utf8 => Ok(Value::Utf8(text.to_owned())),
+        }
+    }
+
+    fn parse_json(self, value: &serde_json::Value) -> Result<Value, String> {
+        match (self, value) {
+            (ColumnType::Int64, serde_json::Value::Number(n)) => n
+                .as_i64()
+                .map(Value::Int64)
+                .ok_or("not an int64".to_owned()),
+            (ColumnType::Float64, serde_json::Value::Number(n)) => n
+                .as_f64()
+                .map(Value::Float64)
+                .ok_or("not a float64".to_owned()),
+            (ColumnType::Utf8, serde_json::Value::String(s)) => Ok(Value::Utf8(s.clone())),
+            (ty, value) => Err(format!("expected {ty:?}, got `{value}`")),
+        }
+    }
+}
+
+/// The position of each column in the header of a CSV file.
+pub fn csv_header_index(columns: &[Column], header: &str) -> eyre::Result<Vec<usize>> {
+    let fields = split_csv(header);
+    columns
+        .iter()
+        .map(|column| {
+            fields
+                .iter()
+                .position(|field| *field == column.name)
+                .ok_or_else(|| eyre!("the header `{header}` has no column `{}`", column.name))
+        })
+        .collect()
+}
+
+pub fn parse_csv_row(
+    columns: &[Column],
+    index: &[usize],
+    line: &str,
+) -> Result<Vec<Value>, String> {
+    let fields = split_csv(line);
+    columns
+        .iter()
+        .zip(index)
+        .map(|(column, &i)| {
+            let field = fields
+                .get(i)
+                .ok_or_else(|| format!("missing `{}`", column.name))?;
+            column
+                .ty
+                .parse_text(field)
+                .map_err(|err| format!("`{}`: {err}", column.name))
+        })
+        .collect()
+}
+
+pub fn parse_json_row(columns: &[Column], line: &str) -> Result<Vec<Value>, String> {
+    let object: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(line).map_err(|err| err.to_string())?;
+    columns
+        .iter()
+        .map(|column| {
+            let value = object
+                .get(&column.name)
+                .ok_or_else(|| format!("missing `{}`", column.name))?;
+            column
+                .ty
+                .parse_json(value)
+                .map_err(|err| format!("`{}`: {err}", column.name))
+        })
+        .collect()
+}
+
+/// Splits a CSV line at commas, except in double-quoted fields, in which `""` is a quote.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(String::new()),
+            (c, _) => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// Encodes `rows` as a struct array with one child array per column.
+pub fn rows_to_arrow(columns: &[Column], rows: &[Vec<Value>]) -> StructArray {
+    let children = columns.iter().enumerate().map(|(i, column)| {
+        let array: ArrayRef =
+            match column.ty {
+                ColumnType::Int64 => Arc::new(Int64Array::from_iter_values(rows.iter().map(
+                    |row| match &row[i] {
+                        Value::Int64(value) => *value,
+                        _ => unreachable!("rows are parsed with the same columns"),
+                    },
+                ))),
+                ColumnType::Float64 => Arc::new(Float64Array::from_iter_values(rows.iter().map(
+                    |row| match &row[i] {
+                        Value::Float64(value) => *value,
+                        _ => unreachable!("rows are parsed with the same columns"),
+                    },
+                ))),
+                ColumnType::Utf8 => Arc::new(StringArray::from_iter_values(rows.iter().map(
+                    |row| match &row[i] {
+                        Value::Utf8(value) => value.as_str(),
+                        _ => unreachable!("rows are parsed with the same columns"),
+                    },
+                ))),
+            };
+        let data_type = match column.ty {
+            ColumnType::Int64 => DataType::Int64,
+            ColumnType::Float64 => DataType::Float64,
+            ColumnType::Utf8 => DataType::Utf8,
+        };
+        (Arc::new(Field::new(&column.name, data_type, false)), array)
+    });
+    StructArray::from(children.collect::<Vec<_>>())
+}
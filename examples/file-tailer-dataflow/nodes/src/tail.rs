@@ -0,0 +1,142 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::PathBuf,
+};
+
+/// A complete line that was appended to the tailed file.
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub text: String,
+    /// Whether it's the first line of the file, e.g. the header of a CSV file. This is also
+    /// set for the first line after a rotation.
+    pub first_in_file: bool,
+}
+
+/// Follows a file that is appended to, like `tail -F`, by polling it.
+///
+/// Handles the two common kinds of log rotation:
+///
+/// - The file is renamed and a new one is created at the path. The rest of the old file is
+///   read to the end before switching to the new one.
+/// - The file is truncated in place (`copytruncate`). It's read from the start again.
+///
+/// Only complete lines are returned, a partially written line is kept until its newline
+/// arrives.
+pub struct Tailer {
+    path: PathBuf,
+    file: Option<File>,
+    offset: u64,
+    pending: Vec<u8>,
+    lines_in_file: u64,
+    pub rotations: u64,
+}
+
+impl Tailer {
+    /// Starts at the beginning of the file at `path`, or at its end with `from_end`. The file
+    /// doesn't need to exist yet.
+    pub fn open(path: impl Into<PathBuf>, from_end: bool) -> io::Result<Self> {
+        let mut tailer = Self {
+            path: path.into(),
+            file: None,
+            offset: 0,
+            pending: Vec::new(),
+            lines_in_file: 0,
+            rotations: 0,
+        };
+        tailer.reopen()?;
+        if from_end && let Some(file) = &mut tailer.file {
+            tailer.offset = file.seek(SeekFrom::End(0))?;
+            // the header of a CSV file was written long before
+            tailer.lines_in_file = 1;
+        }
+        Ok(tailer)
+    }
+
+    /// Returns the lines that were completed since the last poll.
+    pub fn poll(&mut self) -> io::Result<Vec<Line>> {
+        let mut lines = Vec::new();
+        if self.file.is_none() {
+            self.reopen()?;
+        }
+        let Some(file) = &self.file else {
+            return Ok(lines);
+        };
+
+        match std::fs::metadata(&self.path) {
+            Ok(metadata) if is_same_file(file, &metadata)? => {
+                if metadata.len() < self.offset {
+                    // truncated in place, start over
+                    self.restart_at(0)?;
+                    self.rotations += 1;
+                }
+                self.read_new(&mut lines)?;
+            }
+            // renamed away, and maybe already replaced by a new file
+            Ok(_) | Err(_) => {
+                self.read_new(&mut lines)?;
+                self.rotations += 1;
+                // the new file is picked up by the next poll if it doesn't exist yet
+                self.reopen()?;
+                self.read_new(&mut lines)?;
+            }
+        }
+        Ok(lines)
+    }
+
+    fn reopen(&mut self) -> io::Result<()> {
+        match File::open(&self.path) {
+            Ok(file) => self.file = Some(file),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => self.file = None,
+            Err(err) => return Err(err),
+        }
+        self.restart_at(0)
+    }
+
+    fn restart_at(&mut self, offset: u64) -> io::Result<()> {
+        if let Some(file) = &mut self.file {
+            file.seek(SeekFrom::Start(offset))?;
+        }
+        self.offset = offset;
+        self.pending.clear();
+        self.lines_in_file = 0;
+        Ok(())
+    }
+
+    fn read_new(&mut self, lines: &mut Vec<Line>) -> io::Result<()> {
+        let Some(file) = &mut self.file else {
+            return Ok(());
+        };
+        let read = file.read_to_end(&mut self.pending)?;
+        self.offset += read as u64;
+
+        let complete = match self.pending.iter().rposition(|&byte| byte == b'\n') {
+            Some(end) => end + 1,
+            None => return Ok(()),
+        };
+        let rest = self.pending.split_off(complete);
+        for text in String::from_utf8_lossy(&self.pending).lines() {
+            lines.push(Line {
+                text: text.to_owned(),
+                first_in_file: self.lines_in_file == 0,
+            });
+            self.lines_in_file += 1;
+        }
+        self.pending = rest;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, metadata: &std::fs::Metadata) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let open = file.metadata()?;
+    Ok(open.dev() == metadata.dev() && open.ino() == metadata.ino())
+}
+
+/// Without inodes, only truncation is detected as a rotation.
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, _metadata: &std::fs::Metadata) -> io::Result<bool> {
+    Ok(true)
+}
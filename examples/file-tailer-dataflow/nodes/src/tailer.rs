@@ -0,0 +1,110 @@
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+use eyre::bail;
+use file_tailer_dataflow_nodes::{
+    TailerReport, csv_header_index, env_or, parse_columns, parse_csv_row, parse_json_row,
+    rows_to_arrow, tail::Tailer, write_json,
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Follows the CSV or JSONL file at `FILE`, and sends the rows that are appended to it on
+/// `rows`, as struct arrays with the `COLUMNS`.
+///
+/// Polls the file on every `tick`, and survives rotations, see [`Tailer`]. Each CSV file
+/// starts with a header that maps its fields to the columns. Lines that don't match the
+/// columns are skipped with a warning. `FORMAT` is `csv` or `jsonl`, and defaults to the
+/// extension of `FILE`.
+///
+/// Stops after `IDLE_TIMEOUT_MS` without new lines, unless it's 0, and writes a
+/// [`TailerReport`] to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let file: PathBuf = env_or("FILE", "out/logs/sensors.csv".to_owned())?.into();
+    let columns = parse_columns(&env_or(
+        "COLUMNS",
+        "timestamp:int64,sensor:utf8,value:float64".to_owned(),
+    )?)?;
+    let extension = file.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let format: String = env_or("FORMAT", extension.to_owned())?;
+    let csv = match format.as_str() {
+        "csv" => true,
+        "jsonl" | "ndjson" => false,
+        other => bail!("unknown FORMAT `{other}`, expected csv or jsonl"),
+    };
+    let from_end = env_or("START_AT", "beginning".to_owned())? == "end";
+    let max_batch_rows: usize = env_or("MAX_BATCH_ROWS", 1000)?;
+    let idle_timeout = Duration::from_millis(env_or("IDLE_TIMEOUT_MS", 0)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/tailer.json".to_owned())?.into();
+    let output = DataId::from("rows".to_owned());
+
+    let mut tailer = Tailer::open(&file, from_end)?;
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    println!("tailing {} as {format}", file.display());
+
+    let mut report = TailerReport {
+        file: file.display().to_string(),
+        ..Default::default()
+    };
+    let mut header_index = None;
+    let mut last_line = Instant::now();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let lines = tailer.poll()?;
+                    if lines.is_empty() {
+                        if !idle_timeout.is_zero() && last_line.elapsed() >= idle_timeout {
+                            println!("no new lines for {idle_timeout:?}, stopping");
+                            break;
+                        }
+                        continue;
+                    }
+                    last_line = Instant::now();
+
+                    let mut rows = Vec::new();
+                    for line in lines {
+                        if csv && line.first_in_file {
+                            header_index = Some(csv_header_index(&columns, &line.text)?);
+                            continue;
+                        }
+                        if line.text.trim().is_empty() {
+                            continue;
+                        }
+                        let row = match &header_index {
+                            Some(index) => parse_csv_row(&columns, index, &line.text),
+                            None if csv => Err("no header yet".to_owned()),
+                            None => parse_json_row(&columns, &line.text),
+                        };
+                        match row {
+                            Ok(row) => rows.push(row),
+                            Err(err) => {
+                                eprintln!("skipping line `{}`: {err}", line.text);
+                                report.skipped += 1;
+                            }
+                        }
+                    }
+                    for batch in rows.chunks(max_batch_rows) {
+                        node.send_output(
+                            output.clone(),
+                            Default::default(),
+                            rows_to_arrow(&columns, batch),
+                        )?;
+                        report.rows += batch.len() as u64;
+                        report.batches += 1;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    report.rotations = tailer.rotations;
+    println!(
+        "sent {} rows in {} batches, skipped {} lines, {} rotations",
+        report.rows, report.batches, report.skipped, report.rotations
+    );
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,61 @@
+#!/usr/bin/env python3
+"""
+Drives the simulated rover and checks that the simulation reacts.
+- Sends `cmd_vel` commands ([linear, angular]) on every tick
+- Tracks the rover pose and counts received camera frames
+- Exits after `DRIVE_TICKS` ticks and fails if the rover did not move or no frame arrived
+"""
+
+import math
+import os
+
+import pyarrow as pa
+from dora import Node
+
+DRIVE_TICKS = int(os.getenv("DRIVE_TICKS", "100"))
+LINEAR_SPEED = float(os.getenv("LINEAR_SPEED", "0.5"))
+ANGULAR_SPEED = float(os.getenv("ANGULAR_SPEED", "0.2"))
+MIN_DISTANCE = float(os.getenv("MIN_DISTANCE", "1.0"))
+
+
+def main():
+    node = Node()
+
+    start = None
+    pose = None
+    frames = 0
+    ticks = 0
+
+    for event in node:
+        if event["type"] != "INPUT":
+            continue
+
+        if event["id"] == "tick":
+            ticks += 1
+            if ticks > DRIVE_TICKS:
+                break
+            node.send_output("cmd_vel", pa.array([LINEAR_SPEED, ANGULAR_SPEED]))
+        elif event["id"] == "pose":
+            pose = event["value"].to_pylist()
+            if start is None:
+                start = pose
+            if ticks % 10 == 0:
+                print(f"pose: x={pose[0]:.2f} y={pose[1]:.2f} yaw={pose[2]:.2f}", flush=True)
+        elif event["id"] == "image":
+            frames += 1
+            metadata = event["metadata"]
+            if frames == 1:
+                print(f"first camera frame: {metadata['width']}x{metadata['height']}", flush=True)
+
+    if start is None or pose is None:
+        raise RuntimeError("never received a pose from the simulation")
+    distance = math.hypot(pose[0] - start[0], pose[1] - start[1])
+    print(f"rover moved {distance:.2f} m, received {frames} camera frames", flush=True)
+    if distance < MIN_DISTANCE:
+        raise RuntimeError(f"rover moved only {distance:.2f} m (expected >= {MIN_DISTANCE} m)")
+    if frames == 0:
+        raise RuntimeError("no camera frame received")
+
+
+if __name__ == "__main__":
+    main()
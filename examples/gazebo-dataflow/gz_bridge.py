@@ -0,0 +1,94 @@
+#!/usr/bin/env python3
+"""
+Bridge between gz-transport topics of a Gazebo Harmonic simulation and dora.
+- Subscribes to the rover pose and front camera topics of the simulation
+- Publishes them as `pose` ([x, y, yaw]) and `image` (flat uint8 RGB buffer) outputs
+- Forwards `cmd_vel` inputs ([linear, angular]) to the rover's diff-drive controller
+
+gz-transport invokes subscription callbacks on its own threads, so incoming messages
+are queued and published from the dora event loop.
+"""
+
+import math
+import os
+import queue
+
+import numpy as np
+import pyarrow as pa
+from dora import Node
+from gz.msgs10.image_pb2 import Image
+from gz.msgs10.pose_pb2 import Pose
+from gz.msgs10.twist_pb2 import Twist
+from gz.transport13 import Node as GzNode
+
+POSE_TOPIC = os.getenv("GZ_POSE_TOPIC", "/model/rover/pose")
+CAMERA_TOPIC = os.getenv("GZ_CAMERA_TOPIC", "/rover/camera")
+CMD_VEL_TOPIC = os.getenv("GZ_CMD_VEL_TOPIC", "/model/rover/cmd_vel")
+MODEL_NAME = os.getenv("GZ_MODEL_NAME", "rover")
+
+
+def yaw_from_quaternion(q):
+    return math.atan2(2.0 * (q.w * q.z + q.x * q.y), 1.0 - 2.0 * (q.y * q.y + q.z * q.z))
+
+
+def main():
+    pending = queue.Queue(maxsize=100)
+
+    def on_pose(msg: Pose):
+        if msg.name and msg.name != MODEL_NAME:
+            return
+        pose = [msg.position.x, msg.position.y, yaw_from_quaternion(msg.orientation)]
+        try:
+            pending.put_nowait(("pose", pose))
+        except queue.Full:
+            pass
+
+    def on_image(msg: Image):
+        try:
+            pending.put_nowait(("image", msg))
+        except queue.Full:
+            pass
+
+    print("Connecting to gz-transport...", flush=True)
+    gz_node = GzNode()
+    cmd_vel = gz_node.advertise(CMD_VEL_TOPIC, Twist)
+    if not gz_node.subscribe(Pose, POSE_TOPIC, on_pose):
+        raise RuntimeError(f"failed to subscribe to {POSE_TOPIC}")
+    if not gz_node.subscribe(Image, CAMERA_TOPIC, on_image):
+        raise RuntimeError(f"failed to subscribe to {CAMERA_TOPIC}")
+
+    node = Node()
+
+    while True:
+        event = node.next(timeout=0.02)
+        if event is not None:
+            if event["type"] == "INPUT" and event["id"] == "cmd_vel":
+                linear, angular = event["value"].to_numpy()
+                twist = Twist()
+                twist.linear.x = float(linear)
+                twist.angular.z = float(angular)
+                cmd_vel.publish(twist)
+            elif event["type"] == "STOP":
+                break
+            elif event["type"] == "INPUT_CLOSED":
+                print(f"Input `{event['id']}` was closed", flush=True)
+                break
+
+        while not pending.empty():
+            kind, value = pending.get_nowait()
+            if kind == "pose":
+                node.send_output("pose", pa.array(value, type=pa.float64()))
+            else:
+                image = np.frombuffer(value.data, dtype=np.uint8)
+                node.send_output(
+                    "image",
+                    pa.array(image),
+                    {"width": value.width, "height": value.height, "encoding": "rgb8"},
+                )
+
+    # leave the rover standing still when the dataflow ends
+    cmd_vel.publish(Twist())
+
+
+if __name__ == "__main__":
+    main()
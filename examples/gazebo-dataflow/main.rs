@@ -0,0 +1,49 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::WrapErr;
+use std::path::Path;
+use tokio::process::Child;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("gazebo-dataflow-runner")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // pass `--external-sim` when a Gazebo world is already running, e.g. with the GUI
+    let external_sim = std::env::args().any(|arg| arg == "--external-sim");
+
+    let dora = Dora::from_env()?.uv();
+    // the gz-transport python bindings are installed system-wide by the Gazebo packages
+    dora.uv_venv("3.12").system_site_packages().create().await?;
+
+    let mut gazebo = if external_sim {
+        None
+    } else {
+        Some(run_gazebo().await?)
+    };
+
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    let result = dora.run_dataflow(dataflow).await;
+
+    if let Some(gazebo) = &mut gazebo {
+        gazebo.kill().await?;
+    }
+
+    result
+}
+
+async fn run_gazebo() -> eyre::Result<Child> {
+    let gz = which::which("gz").context(
+        "failed to find `gz`. Install Gazebo Harmonic: https://gazebosim.org/docs/harmonic/install",
+    )?;
+    let mut cmd = tokio::process::Command::new(gz);
+    // server only (`-s`), start unpaused (`-r`), render the camera without a display
+    cmd.args(["sim", "-s", "-r", "--headless-rendering", "world.sdf"]);
+    let child = cmd.spawn().context("failed to start gazebo")?;
+    tracing::info!("started headless gazebo world");
+    Ok(child)
+}
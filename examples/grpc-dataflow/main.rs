@@ -0,0 +1,116 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{
+    collections::BTreeSet,
+    env::consts::EXE_SUFFIX,
+    path::Path,
+    time::{Duration, Instant},
+};
+use tokio::process::Command;
+
+/// Must match `GRPC_ADDR` in `dataflow.yml`.
+const GRPC_ADDR: &str = "127.0.0.1:50051";
+
+/// The JSON that `nodes/src/grpc_client.rs` prints.
+#[derive(Debug, Deserialize)]
+struct Reply {
+    request_id: u64,
+    count: u32,
+    mean: f64,
+    min: f64,
+    max: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("grpc-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    if which::which("protoc").is_err() {
+        bail!(
+            "`protoc` is needed to compile `nodes/proto/summary.proto`, install it with e.g. \
+             `apt install protobuf-compiler` or `brew install protobuf`"
+        );
+    }
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let dataflow_task = tokio::spawn(dora.run_dataflow(dataflow));
+    let session = round_trips().await;
+    // the gateway stops after `DURATION_MS`, which ends the dataflow
+    dataflow_task.await??;
+    session
+}
+
+async fn round_trips() -> eyre::Result<()> {
+    // the gateway starts listening when the dataflow is up
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while !port_check::is_port_reachable(GRPC_ADDR) {
+        if Instant::now() > deadline {
+            bail!("gRPC gateway never became reachable on {GRPC_ADDR}");
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let reply = summarize(&[1.0, 2.0, 3.0, 4.0]).await?;
+    println!("summary of [1, 2, 3, 4]: {reply:?}");
+    if (reply.count, reply.mean, reply.min, reply.max) != (4, 2.5, 1.0, 4.0) {
+        bail!("wrong summary of [1, 2, 3, 4]: {reply:?}");
+    }
+
+    // concurrent requests are in flight in the dataflow at the same time, and must still get
+    // their own replies
+    let requests = (1..=10).map(|i| async move {
+        let i = f64::from(i);
+        let reply = summarize(&[i, 2.0 * i, 3.0 * i]).await?;
+        if (reply.count, reply.mean, reply.min, reply.max) != (3, 2.0 * i, i, 3.0 * i) {
+            bail!("request {i} got the wrong reply: {reply:?}");
+        }
+        Ok(reply.request_id)
+    });
+    let ids: BTreeSet<u64> = futures::future::try_join_all(requests)
+        .await?
+        .into_iter()
+        .collect();
+    if ids.len() != 10 {
+        bail!("expected 10 distinct request ids, got {ids:?}");
+    }
+    println!("10 concurrent requests got their own replies");
+
+    // errors of the service reach the client as gRPC status
+    let output = client(&[]).output().await?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() || !stderr.contains("no values to summarize") {
+        bail!("expected an empty request to be rejected, got: {stderr}");
+    }
+    println!("empty request was rejected with `InvalidArgument`");
+    Ok(())
+}
+
+fn client(values: &[f64]) -> Command {
+    let mut cmd = Command::new(format!("nodes/target/release/grpc-client{EXE_SUFFIX}"));
+    cmd.arg("--addr").arg(format!("http://{GRPC_ADDR}"));
+    cmd.args(values.iter().map(f64::to_string));
+    cmd
+}
+
+async fn summarize(values: &[f64]) -> eyre::Result<Reply> {
+    let output = client(values)
+        .output()
+        .await
+        .context("failed to run grpc-client")?;
+    if !output.status.success() {
+        bail!(
+            "grpc-client failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    serde_json::from_slice(&output.stdout).context("invalid output of grpc-client")
+}
@@ -0,0 +1,5 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // needs `protoc`, see the README
+    tonic_build::compile_protos("proto/summary.proto")?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use eyre::{Context, bail};
+use grpc_dataflow_nodes::summary::{SummarizeRequest, summarizer_client::SummarizerClient};
+
+/// Sends one `Summarize` request to the gateway and prints the reply as JSON.
+///
+/// Usage: `grpc-client [--addr <URL>] <VALUE>...`
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let mut addr = "http://127.0.0.1:50051".to_owned();
+    let mut values = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--addr" => {
+                let Some(value) = args.next() else {
+                    bail!("missing value for --addr");
+                };
+                addr = value;
+            }
+            value => values.push(
+                value
+                    .parse::<f64>()
+                    .with_context(|| format!("invalid value `{value}`"))?,
+            ),
+        }
+    }
+
+    let mut client = SummarizerClient::connect(addr.clone())
+        .await
+        .with_context(|| format!("failed to connect to {addr}"))?;
+    let reply = client
+        .summarize(SummarizeRequest { values })
+        .await
+        .context("request failed")?
+        .into_inner();
+    let json = serde_json::json!({
+        "request_id": reply.request_id,
+        "count": reply.count,
+        "mean": reply.mean,
+        "min": reply.min,
+        "max": reply.max,
+    });
+    println!("{json}");
+    Ok(())
+}
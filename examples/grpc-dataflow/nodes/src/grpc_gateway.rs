@@ -0,0 +1,158 @@
+use dora_node_api::{
+    DoraNode, Event,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, OptionExt, bail};
+use futures::{StreamExt, channel::mpsc::UnboundedSender};
+use grpc_dataflow_nodes::{
+    env_or, request_id, request_parameters,
+    summary::{
+        SummarizeReply, SummarizeRequest,
+        summarizer_server::{Summarizer, SummarizerServer},
+    },
+};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+use tokio::sync::oneshot;
+use tonic::{Request, Response, Status};
+
+/// A gRPC request that waits for the dataflow to answer it.
+#[derive(Debug)]
+struct Pending {
+    values: Vec<f64>,
+    reply: oneshot::Sender<SummarizeReply>,
+}
+
+/// Hands requests to the event loop of the node.
+struct Gateway {
+    requests: UnboundedSender<Pending>,
+    timeout: Duration,
+}
+
+#[tonic::async_trait]
+impl Summarizer for Gateway {
+    async fn summarize(
+        &self,
+        request: Request<SummarizeRequest>,
+    ) -> Result<Response<SummarizeReply>, Status> {
+        let values = request.into_inner().values;
+        if values.is_empty() {
+            return Err(Status::invalid_argument("no values to summarize"));
+        }
+        let (reply, replied) = oneshot::channel();
+        self.requests
+            .unbounded_send(Pending { values, reply })
+            .map_err(|_| Status::unavailable("the dataflow is stopping"))?;
+        match tokio::time::timeout(self.timeout, replied).await {
+            Ok(Ok(reply)) => Ok(Response::new(reply)),
+            Ok(Err(_)) => Err(Status::unavailable("the dataflow stopped")),
+            Err(_) => Err(Status::deadline_exceeded(format!(
+                "the dataflow didn't reply within {} ms",
+                self.timeout.as_millis()
+            ))),
+        }
+    }
+}
+
+/// Serves the `Summarizer` gRPC service on `GRPC_ADDR`.
+///
+/// Requests are merged into the event loop as external events, and their values are sent on
+/// `request` with an id in the `request_id` metadata parameter. A `summary` input with the same
+/// id completes the request.
+///
+/// Stops after `DURATION_MS`, checked on every `tick`.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let addr: SocketAddr = env_or("GRPC_ADDR", "127.0.0.1:50051".to_owned())?
+        .parse()
+        .context("invalid GRPC_ADDR")?;
+    let timeout = Duration::from_millis(env_or("REPLY_TIMEOUT_MS", 5000)?);
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let output = DataId::from("request".to_owned());
+
+    let (requests_tx, requests_rx) = futures::channel::mpsc::unbounded();
+    let service = SummarizerServer::new(Gateway {
+        requests: requests_tx,
+        timeout,
+    });
+    tokio::spawn(async move {
+        let server = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve(addr);
+        if let Err(err) = server.await {
+            eprintln!("gRPC server failed: {err}");
+        }
+    });
+    println!("gRPC gateway listening on {addr}");
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let mut events = events.merge_external(Box::pin(requests_rx));
+
+    let start = Instant::now();
+    let mut next_id = 1;
+    let mut pending = HashMap::new();
+    while let Some(event) = events.next().await {
+        match event {
+            MergedEvent::External(Pending { values, reply }) => {
+                let id = next_id;
+                next_id += 1;
+                let count = values.len() as u32;
+                node.send_output(
+                    output.clone(),
+                    request_parameters(id),
+                    Float64Array::from(values),
+                )?;
+                pending.insert(id, (count, reply));
+            }
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, metadata, data } => match id.as_str() {
+                    "summary" => {
+                        let request_id = request_id(&metadata.parameters)?;
+                        let summary = data
+                            .as_primitive_opt::<Float64Type>()
+                            .ok_or_eyre("expected a Float64 array")?;
+                        let &[mean, min, max] = summary.values().as_ref() else {
+                            bail!("expected [mean, min, max], got {summary:?}");
+                        };
+                        let Some((count, reply)) = pending.remove(&request_id) else {
+                            eprintln!("summary of unknown request {request_id}");
+                            continue;
+                        };
+                        // fails if the client has timed out already
+                        let _ = reply.send(SummarizeReply {
+                            request_id,
+                            count,
+                            mean,
+                            min,
+                            max,
+                        });
+                    }
+                    "tick" => {
+                        if start.elapsed() >= duration {
+                            break;
+                        }
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => println!("Input `{id}` was closed"),
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+        }
+    }
+
+    // dropping the senders fails the requests that are still waiting
+    if !pending.is_empty() {
+        println!("{} requests were not answered", pending.len());
+    }
+    Ok(())
+}
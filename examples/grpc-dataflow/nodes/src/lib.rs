@@ -0,0 +1,27 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{bail, eyre};
+
+pub use example_node_utils::env_or;
+
+/// The gRPC service, generated from `proto/summary.proto` by `build.rs`.
+pub mod summary {
+    tonic::include_proto!("summary");
+}
+
+/// Metadata key of the id of the gRPC request that a message belongs to.
+pub const REQUEST_ID_KEY: &str = "request_id";
+
+pub fn request_parameters(request_id: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(REQUEST_ID_KEY.into(), Parameter::Integer(request_id as i64));
+    parameters
+}
+
+pub fn request_id(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(REQUEST_ID_KEY) {
+        Some(Parameter::Integer(id)) => {
+            u64::try_from(*id).map_err(|_| eyre!("negative `{REQUEST_ID_KEY}` parameter"))
+        }
+        other => bail!("expected integer `{REQUEST_ID_KEY}` parameter, got {other:?}"),
+    }
+}
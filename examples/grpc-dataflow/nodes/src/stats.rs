@@ -0,0 +1,43 @@
+use dora_node_api::{
+    DoraNode, Event,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+
+/// Summarizes the values of every `request` as `[mean, min, max]`, keeping its metadata
+/// parameters so that the gateway can match the summary to its request.
+fn main() -> eyre::Result<()> {
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let output = DataId::from("summary".to_owned());
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "request" => {
+                    let values = data
+                        .as_primitive_opt::<Float64Type>()
+                        .ok_or_eyre("expected a Float64 array")?
+                        .values();
+                    let count = values.len() as f64;
+                    let mean = values.iter().sum::<f64>() / count;
+                    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+                    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                    node.send_output(
+                        output.clone(),
+                        metadata.parameters,
+                        Float64Array::from(vec![mean, min, max]),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, OptionExt, bail};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Must match the `num-buffers` of the `videotestsrc` of `gst-source` in `dataflow.yml`.
+const FRAMES: u64 = 150;
+/// Must match the caps of the `videotestsrc` of `gst-source` in `dataflow.yml`.
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+
+/// The elements that every variant of the dataflow needs, all in gst-plugins-base.
+const BASE_ELEMENTS: &[&str] = &["videotestsrc", "videoconvert", "appsrc", "appsink"];
+/// The elements of the H.264 round trip of `dataflow.yml`, from gst-plugins-ugly, -bad, -good,
+/// and gst-libav.
+const H264_ELEMENTS: &[&str] = &["x264enc", "h264parse", "avdec_h264", "matroskamux"];
+/// The elements of `dataflow.raw.yml`, all in gst-plugins-good.
+const RAW_ELEMENTS: &[&str] = &["jpegenc", "jpegdec", "avimux", "avidemux"];
+
+/// `dataflow.raw.yml`: the test pattern goes straight to the appsink, and the sink writes
+/// Motion JPEG, for machines without the H.264 plugins.
+const RAW_SOURCE_PIPELINE: &str = "videotestsrc num-buffers=150 is-live=true pattern=ball \
+    ! video/x-raw,width=320,height=240,framerate=30/1 ! videoconvert ! appsink name=sink";
+const RAW_SINK_PIPELINE: &str =
+    "appsrc name=src ! videoconvert ! jpegenc ! avimux ! filesink location=out/processed.avi";
+
+/// Subset of `SourceReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SourceReport {
+    frames: u64,
+    width: u32,
+    height: u32,
+    eos: bool,
+}
+
+/// Subset of `TrackerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct TrackerReport {
+    frames: u64,
+    detected: u64,
+    min_x: Option<u32>,
+    max_x: Option<u32>,
+    min_y: Option<u32>,
+    max_y: Option<u32>,
+}
+
+/// Subset of `SinkReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkReport {
+    frames: u64,
+    eos: bool,
+}
+
+/// The output of the sink pipeline, and how to decode it again.
+struct Output {
+    file: &'static str,
+    decoder: &'static str,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("gstreamer-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    which::which("gst-inspect-1.0").context(
+        "no `gst-inspect-1.0`, install GStreamer, e.g. using: \
+         sudo apt install libgstreamer1.0-dev libgstreamer-plugins-base1.0-dev \
+         gstreamer1.0-tools gstreamer1.0-plugins-good",
+    )?;
+    let missing = missing_elements(BASE_ELEMENTS).await?;
+    if !missing.is_empty() {
+        bail!("GStreamer has no {missing:?}, install gstreamer1.0-plugins-base");
+    }
+    let missing = missing_elements(H264_ELEMENTS).await?;
+    let (dataflow, output) = if missing.is_empty() {
+        let output = Output {
+            file: "out/processed.mkv",
+            decoder: "matroskademux ! h264parse ! avdec_h264",
+        };
+        (PathBuf::from("dataflow.yml"), output)
+    } else {
+        println!("GStreamer has no {missing:?}, running the dataflow without H.264");
+        let missing = missing_elements(RAW_ELEMENTS).await?;
+        if !missing.is_empty() {
+            bail!("GStreamer has no {missing:?} either, install gstreamer1.0-plugins-good");
+        }
+        let output = Output {
+            file: "out/processed.avi",
+            decoder: "avidemux ! jpegdec",
+        };
+        (write_raw_dataflow()?, output)
+    };
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(&dataflow).await?;
+    dora.run_dataflow(&dataflow).await?;
+
+    let source: SourceReport = read_json("out/source.json")?;
+    println!(
+        "source: pulled {} frames of {}x{}",
+        source.frames, source.width, source.height
+    );
+    if source.frames != FRAMES || !source.eos {
+        bail!("expected the source pipeline to end after {FRAMES} frames");
+    }
+    if (source.width, source.height) != (WIDTH, HEIGHT) {
+        bail!("expected frames of {WIDTH}x{HEIGHT}");
+    }
+
+    let tracker: TrackerReport = read_json("out/tracker.json")?;
+    println!(
+        "tracker: found the ball in {} of {} frames, x in {:?}..={:?}, y in {:?}..={:?}",
+        tracker.detected,
+        tracker.frames,
+        tracker.min_x,
+        tracker.max_x,
+        tracker.min_y,
+        tracker.max_y
+    );
+    if tracker.frames != FRAMES {
+        bail!("expected the tracker to receive all {FRAMES} frames");
+    }
+    if tracker.detected * 10 < tracker.frames * 9 {
+        bail!("expected the tracker to find the ball in at least 90% of the frames");
+    }
+    let (Some(min_x), Some(max_x)) = (tracker.min_x, tracker.max_x) else {
+        bail!("the tracker did not find the ball");
+    };
+    if max_x - min_x < 50 {
+        bail!("expected the ball to move across the frame");
+    }
+
+    let sink: SinkReport = read_json("out/sink.json")?;
+    println!("sink: pushed {} frames", sink.frames);
+    if sink.frames != FRAMES {
+        bail!("expected the sink to push all {FRAMES} frames");
+    }
+    if !sink.eos {
+        bail!("the sink pipeline did not finish before it was stopped");
+    }
+    check_output(&output).await?;
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// The `elements` that GStreamer doesn't know, according to `gst-inspect-1.0`.
+async fn missing_elements(elements: &[&'static str]) -> eyre::Result<Vec<&'static str>> {
+    let mut missing = Vec::new();
+    for &element in elements {
+        let status = Command::new("gst-inspect-1.0")
+            .arg(element)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .await
+            .context("failed to run gst-inspect-1.0")?;
+        if !status.success() {
+            missing.push(element);
+        }
+    }
+    Ok(missing)
+}
+
+/// Writes `dataflow.raw.yml`, which is `dataflow.yml` with the raw pipelines. It's written next
+/// to `dataflow.yml`, since dora resolves node paths relative to the dataflow file.
+fn write_raw_dataflow() -> eyre::Result<PathBuf> {
+    let mut dataflow: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string("dataflow.yml")?)?;
+    let nodes = dataflow["nodes"]
+        .as_sequence_mut()
+        .ok_or_eyre("dataflow.yml has no nodes")?;
+    for (id, pipeline) in [
+        ("gst-source", RAW_SOURCE_PIPELINE),
+        ("gst-sink", RAW_SINK_PIPELINE),
+    ] {
+        let node = nodes
+            .iter_mut()
+            .find(|node| node["id"] == id)
+            .ok_or_else(|| eyre::eyre!("dataflow.yml has no `{id}` node"))?;
+        node["env"]["PIPELINE"] = pipeline.into();
+    }
+
+    let path = PathBuf::from("dataflow.raw.yml");
+    std::fs::write(&path, serde_yaml::to_string(&dataflow)?)?;
+    Ok(path)
+}
+
+/// Checks that the file that the sink pipeline wrote decodes, i.e. that its muxer finished it.
+async fn check_output(output: &Output) -> eyre::Result<()> {
+    let len = std::fs::metadata(output.file)
+        .with_context(|| format!("{} was not written", output.file))?
+        .len();
+    if len == 0 {
+        bail!("{} is empty", output.file);
+    }
+    let pipeline = format!(
+        "filesrc location={} ! {} ! fakesink",
+        output.file, output.decoder
+    );
+    let status = Command::new("gst-launch-1.0")
+        .arg("-q")
+        .args(pipeline.split_whitespace())
+        .status()
+        .await
+        .context("failed to run gst-launch-1.0")?;
+    if !status.success() {
+        bail!("failed to decode {}", output.file);
+    }
+    println!("{}: {len} bytes, decodes", output.file);
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter, arrow::array::UInt8Array, dora_core::config::DataId,
+};
+use gstreamer_dataflow_nodes::{Frame, TrackerReport, env_or, write_json};
+use std::path::PathBuf;
+
+/// Pixels brighter than this in every channel belong to the ball.
+const THRESHOLD: u8 = 200;
+/// Fewer bright pixels than this are noise, e.g. of the video codec.
+const MIN_PIXELS: usize = 20;
+const BOX_COLOR: [u8; 3] = [255, 0, 0];
+const BOX_THICKNESS: u32 = 2;
+
+/// Finds the bright ball of the `videotestsrc pattern=ball` test pattern in every `frame`, draws
+/// a box around it, and sends the frame on `frame`, with the center of the ball as `ball_x` and
+/// `ball_y` in its metadata.
+///
+/// Frames without a ball are passed on unchanged. Writes a `TrackerReport` to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/tracker.json".to_owned())?.into();
+    let output = DataId::from("frame".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let mut report = TrackerReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "frame" => {
+                    let mut frame = Frame::from_input(&metadata.parameters, &data.0)?;
+                    let mut parameters = frame.parameters();
+                    report.frames += 1;
+                    if let Some(ball) = find_ball(&frame) {
+                        draw_box(&mut frame, &ball);
+                        let (x, y) = ball.center();
+                        parameters.insert("ball_x".into(), Parameter::Integer(x.into()));
+                        parameters.insert("ball_y".into(), Parameter::Integer(y.into()));
+                        report.detected += 1;
+                        report.min_x = Some(report.min_x.map_or(x, |min| min.min(x)));
+                        report.max_x = Some(report.max_x.map_or(x, |max| max.max(x)));
+                        report.min_y = Some(report.min_y.map_or(y, |min| min.min(y)));
+                        report.max_y = Some(report.max_y.map_or(y, |max| max.max(y)));
+                    }
+                    node.send_output(output.clone(), parameters, UInt8Array::from(frame.pixels))?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "found the ball in {} of {} frames",
+        report.detected, report.frames
+    );
+    write_json(&report_file, &report)
+}
+
+/// The bounding box of the bright pixels, inclusive.
+struct BoundingBox {
+    left: u32,
+    top: u32,
+    right: u32,
+    bottom: u32,
+}
+
+impl BoundingBox {
+    fn center(&self) -> (u32, u32) {
+        ((self.left + self.right) / 2, (self.top + self.bottom) / 2)
+    }
+}
+
+fn find_ball(frame: &Frame) -> Option<BoundingBox> {
+    let mut ball: Option<BoundingBox> = None;
+    let mut pixels = 0;
+    for (i, rgb) in frame.pixels.chunks_exact(3).enumerate() {
+        if rgb.iter().any(|&channel| channel <= THRESHOLD) {
+            continue;
+        }
+        let (x, y) = (i as u32 % frame.width, i as u32 / frame.width);
+        pixels += 1;
+        ball = Some(match ball {
+            None => BoundingBox {
+                left: x,
+                top: y,
+                right: x,
+                bottom: y,
+            },
+            Some(b) => BoundingBox {
+                left: b.left.min(x),
+                top: b.top.min(y),
+                right: b.right.max(x),
+                bottom: b.bottom.max(y),
+            },
+        });
+    }
+    ball.filter(|_| pixels >= MIN_PIXELS)
+}
+
+fn draw_box(frame: &mut Frame, ball: &BoundingBox) {
+    let (width, height) = (frame.width, frame.height);
+    let mut set = |x: u32, y: u32| {
+        if x < width && y < height {
+            let i = (y * width + x) as usize * 3;
+            frame.pixels[i..i + 3].copy_from_slice(&BOX_COLOR);
+        }
+    };
+    // just outside of the ball, so that it stays visible
+    let left = ball.left.saturating_sub(BOX_THICKNESS);
+    let top = ball.top.saturating_sub(BOX_THICKNESS);
+    let (right, bottom) = (ball.right + BOX_THICKNESS, ball.bottom + BOX_THICKNESS);
+    for t in 0..BOX_THICKNESS {
+        for x in left..=right {
+            set(x, top + t);
+            set(x, bottom - t);
+        }
+        for y in top..=bottom {
+            set(left + t, y);
+            set(right - t, y);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::{Context, bail, eyre};
+use gst::prelude::*;
+use gstreamer_dataflow_nodes::{
+    Frame, SinkReport, describe_error, element, env_or, launch, write_json,
+};
+use std::path::PathBuf;
+
+/// Pushes every `frame` into the appsrc `APPSRC` of the GStreamer pipeline `PIPELINE`, e.g. to
+/// display it, to re-stream it, or to encode it to a file:
+///
+/// ```text
+/// appsrc name=src ! videoconvert ! autovideosink
+/// appsrc name=src ! videoconvert ! x264enc tune=zerolatency ! rtph264pay
+///     ! udpsink host=127.0.0.1 port=5000
+/// ```
+///
+/// The appsrc gets the size of the first frame and `FRAMERATE` as its caps, and the frames
+/// keep their timestamps of the source pipeline. When the input closes, it ends the stream and
+/// waits up to `EOS_TIMEOUT_MS` for the pipeline to process it, so that a muxer can finish its
+/// file. Writes a `SinkReport` to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let description: String = env_or("PIPELINE", String::new())?;
+    if description.is_empty() {
+        bail!("set `PIPELINE` to a GStreamer pipeline that starts with an appsrc");
+    }
+    let appsrc_name: String = env_or("APPSRC", "src".to_owned())?;
+    let framerate: i32 = env_or("FRAMERATE", 30)?;
+    let eos_timeout = gst::ClockTime::from_mseconds(env_or("EOS_TIMEOUT_MS", 10_000)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/sink.json".to_owned())?.into();
+
+    gst::init()?;
+    let pipeline = launch(&description)?;
+    let appsrc: gst_app::AppSrc = element(&pipeline, &appsrc_name)?;
+    appsrc.set_format(gst::Format::Time);
+    // back pressure instead of an unbounded queue, when the pipeline is slower than the frames
+    appsrc.set_block(true);
+    let bus = pipeline.bus().expect("a pipeline has a bus");
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+    let mut report = SinkReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "frame" => {
+                    let frame = Frame::from_input(&metadata.parameters, &data.0)?;
+                    if report.frames == 0 {
+                        let caps = gst_video::VideoInfo::builder(
+                            gst_video::VideoFormat::Rgb,
+                            frame.width,
+                            frame.height,
+                        )
+                        .fps(gst::Fraction::new(framerate, 1))
+                        .build()?
+                        .to_caps()?;
+                        appsrc.set_caps(Some(&caps));
+                        pipeline
+                            .set_state(gst::State::Playing)
+                            .context("failed to start the pipeline")?;
+                        println!("playing `{description}` with {caps}");
+                        (report.width, report.height) = (frame.width, frame.height);
+                    } else if (frame.width, frame.height) != (report.width, report.height) {
+                        bail!(
+                            "frame {} is {}x{}, the pipeline was started with {}x{}",
+                            frame.seq,
+                            frame.width,
+                            frame.height,
+                            report.width,
+                            report.height
+                        );
+                    }
+
+                    let pts = frame.pts_ns.map(gst::ClockTime::from_nseconds);
+                    let mut buffer = gst::Buffer::from_mut_slice(frame.pixels);
+                    buffer
+                        .get_mut()
+                        .expect("a new buffer is writable")
+                        .set_pts(pts);
+                    if let Err(flow) = appsrc.push_buffer(buffer) {
+                        bail!(
+                            "failed to push frame {}: {}",
+                            frame.seq,
+                            pipeline_error(&bus, flow)
+                        );
+                    }
+                    report.frames += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "frame" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if report.frames > 0 {
+        appsrc
+            .end_of_stream()
+            .map_err(|flow| eyre!("failed to end the stream: {flow:?}"))?;
+        let message = bus.timed_pop_filtered(
+            eos_timeout,
+            &[gst::MessageType::Eos, gst::MessageType::Error],
+        );
+        match message.as_ref().map(|message| message.view()) {
+            Some(gst::MessageView::Eos(_)) => report.eos = true,
+            Some(gst::MessageView::Error(err)) => {
+                bail!("pipeline failed: {}", describe_error(err))
+            }
+            _ => eprintln!("the pipeline did not finish within {eos_timeout}"),
+        }
+    }
+    pipeline
+        .set_state(gst::State::Null)
+        .context("failed to stop the pipeline")?;
+    println!(
+        "pushed {} frames of {}x{}",
+        report.frames, report.width, report.height
+    );
+    write_json(&report_file, &report)
+}
+
+/// Why the pipeline stopped accepting frames, from its bus if it reported an error.
+fn pipeline_error(bus: &gst::Bus, flow: gst::FlowError) -> String {
+    match bus.pop_filtered(&[gst::MessageType::Error]) {
+        Some(message) => match message.view() {
+            gst::MessageView::Error(err) => describe_error(err),
+            _ => format!("{flow:?}"),
+        },
+        None => format!("{flow:?}"),
+    }
+}
@@ -0,0 +1,169 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::UInt8Array,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, OptionExt, bail, eyre};
+use futures::channel::mpsc::UnboundedSender;
+use gst::prelude::*;
+use gstreamer_dataflow_nodes::{
+    Frame, SourceReport, describe_error, element, env_or, launch, write_json,
+};
+use std::path::PathBuf;
+
+/// What the pipeline hands to the dora event loop.
+enum Pulled {
+    Frame(Frame),
+    Eos,
+    Error(String),
+}
+
+/// Runs the GStreamer pipeline `PIPELINE`, and sends every frame that reaches its appsink
+/// `APPSINK` on `frame`, as `rgb8`.
+///
+/// The appsink only accepts RGB, so the pipeline converts to it, e.g. to pull an H.264 stream
+/// of an RTSP camera:
+///
+/// ```text
+/// rtspsrc location=rtsp://camera/stream latency=100 ! rtph264depay ! h264parse ! avdec_h264
+///     ! videoconvert ! appsink name=sink
+/// ```
+///
+/// Stops when the pipeline ends, and fails when it reports an error. Writes a `SourceReport`
+/// to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let description: String = env_or("PIPELINE", String::new())?;
+    if description.is_empty() {
+        bail!("set `PIPELINE` to a GStreamer pipeline that ends in an appsink");
+    }
+    let appsink_name: String = env_or("APPSINK", "sink".to_owned())?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/source.json".to_owned())?.into();
+    let output = DataId::from("frame".to_owned());
+
+    gst::init()?;
+    let pipeline = launch(&description)?;
+    let appsink: gst_app::AppSink = element(&pipeline, &appsink_name)?;
+    appsink.set_caps(Some(
+        &gst_video::VideoCapsBuilder::new()
+            .format(gst_video::VideoFormat::Rgb)
+            .build(),
+    ));
+
+    // GStreamer pushes the samples and the bus messages on its own threads, they are merged
+    // into the dora event loop
+    let (pulled_tx, pulled_rx) = futures::channel::mpsc::unbounded();
+    appsink.set_callbacks(
+        gst_app::AppSinkCallbacks::builder()
+            .new_sample({
+                let pulled_tx = pulled_tx.clone();
+                let mut seq = 0;
+                move |appsink| {
+                    let sample = appsink.pull_sample().map_err(|_| gst::FlowError::Eos)?;
+                    let frame = to_frame(seq, &sample).map_err(|err| {
+                        eprintln!("failed to read frame {seq}: {err:?}");
+                        gst::FlowError::Error
+                    })?;
+                    seq += 1;
+                    pulled_tx
+                        .unbounded_send(Pulled::Frame(frame))
+                        // the node stopped
+                        .map_err(|_| gst::FlowError::Flushing)?;
+                    Ok(gst::FlowSuccess::Ok)
+                }
+            })
+            .build(),
+    );
+    let bus = pipeline.bus().expect("a pipeline has a bus");
+    std::thread::spawn(move || forward_bus(bus, pulled_tx));
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    pipeline
+        .set_state(gst::State::Playing)
+        .context("failed to start the pipeline")?;
+    println!("playing `{description}`");
+
+    let merged = events.merge_external(Box::pin(pulled_rx));
+    let events = futures::executor::block_on_stream(merged);
+    let mut report = SourceReport::default();
+    let mut result = Ok(());
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(Pulled::Frame(frame)) => {
+                report.frames += 1;
+                report.width = frame.width;
+                report.height = frame.height;
+                let parameters = frame.parameters();
+                node.send_output(output.clone(), parameters, UInt8Array::from(frame.pixels))?;
+            }
+            MergedEvent::External(Pulled::Eos) => {
+                report.eos = true;
+                break;
+            }
+            MergedEvent::External(Pulled::Error(err)) => {
+                result = Err(eyre!("pipeline failed: {err}"));
+                break;
+            }
+        }
+    }
+
+    pipeline
+        .set_state(gst::State::Null)
+        .context("failed to stop the pipeline")?;
+    println!(
+        "sent {} frames of {}x{}",
+        report.frames, report.width, report.height
+    );
+    write_json(&report_file, &report)?;
+    result
+}
+
+/// Copies the pixels of `sample` into a `Frame`, row by row, since GStreamer may pad the rows.
+fn to_frame(seq: u64, sample: &gst::Sample) -> eyre::Result<Frame> {
+    let caps = sample.caps().ok_or_eyre("sample has no caps")?;
+    let info = gst_video::VideoInfo::from_caps(caps)?;
+    let buffer = sample.buffer().ok_or_eyre("sample has no buffer")?;
+    let map = buffer.map_readable()?;
+    let (width, height) = (info.width(), info.height());
+    let stride = info.stride()[0] as usize;
+    let row_bytes = width as usize * 3;
+    let mut pixels = Vec::with_capacity(row_bytes * height as usize);
+    for row in map.as_slice().chunks(stride).take(height as usize) {
+        let Some(row) = row.get(..row_bytes) else {
+            break;
+        };
+        pixels.extend_from_slice(row);
+    }
+    if pixels.len() != row_bytes * height as usize {
+        bail!("buffer is too small for {width}x{height} RGB");
+    }
+    Ok(Frame {
+        seq,
+        width,
+        height,
+        pts_ns: buffer.pts().map(|pts| pts.nseconds()),
+        pixels,
+    })
+}
+
+/// Forwards the end of the stream and errors of the pipeline, until either happens.
+fn forward_bus(bus: gst::Bus, pulled_tx: UnboundedSender<Pulled>) {
+    for message in bus.iter_timed(gst::ClockTime::NONE) {
+        let pulled = match message.view() {
+            gst::MessageView::Eos(_) => Pulled::Eos,
+            gst::MessageView::Error(err) => Pulled::Error(describe_error(err)),
+            gst::MessageView::Warning(warning) => {
+                eprintln!("pipeline warning: {}", warning.error());
+                continue;
+            }
+            _ => continue,
+        };
+        let _ = pulled_tx.unbounded_send(pulled);
+        return;
+    }
+}
@@ -0,0 +1,154 @@
+use dora_node_api::{
+    MetadataParameters, Parameter,
+    arrow::{
+        array::{Array, AsArray},
+        datatypes::UInt8Type,
+    },
+};
+use eyre::{OptionExt, bail, eyre};
+use gst::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Metadata keys of the `frame` outputs.
+pub const WIDTH_KEY: &str = "width";
+pub const HEIGHT_KEY: &str = "height";
+pub const ENCODING_KEY: &str = "encoding";
+/// The number of the frame, counted by `gst-source`.
+pub const FRAME_KEY: &str = "frame";
+/// The presentation timestamp of the frame in its GStreamer pipeline, in nanoseconds.
+pub const PTS_KEY: &str = "pts_ns";
+
+/// An `rgb8` video frame, without the row padding that GStreamer may add.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub seq: u64,
+    pub width: u32,
+    pub height: u32,
+    pub pts_ns: Option<u64>,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Decodes a `frame` input: the pixels as a `UInt8` array, the rest in the metadata.
+    pub fn from_input(parameters: &MetadataParameters, data: &dyn Array) -> eyre::Result<Self> {
+        match parameters.get(ENCODING_KEY) {
+            Some(Parameter::String(encoding)) if encoding == "rgb8" => {}
+            other => bail!("expected an rgb8 frame, got encoding {other:?}"),
+        }
+        let frame = Self {
+            seq: integer_parameter(parameters, FRAME_KEY)? as u64,
+            width: integer_parameter(parameters, WIDTH_KEY)?.try_into()?,
+            height: integer_parameter(parameters, HEIGHT_KEY)?.try_into()?,
+            pts_ns: integer_parameter(parameters, PTS_KEY)
+                .ok()
+                .map(|pts| pts as u64),
+            pixels: data
+                .as_primitive_opt::<UInt8Type>()
+                .ok_or_eyre("expected a UInt8 array")?
+                .values()
+                .to_vec(),
+        };
+        if frame.pixels.len() != frame.width as usize * frame.height as usize * 3 {
+            bail!(
+                "frame {} has {} bytes, expected {}x{} rgb8",
+                frame.seq,
+                frame.pixels.len(),
+                frame.width,
+                frame.height
+            );
+        }
+        Ok(frame)
+    }
+
+    /// The metadata of the frame, `from_input` reads it back.
+    pub fn parameters(&self) -> MetadataParameters {
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(FRAME_KEY.into(), Parameter::Integer(self.seq as i64));
+        parameters.insert(WIDTH_KEY.into(), Parameter::Integer(self.width.into()));
+        parameters.insert(HEIGHT_KEY.into(), Parameter::Integer(self.height.into()));
+        parameters.insert(ENCODING_KEY.into(), Parameter::String("rgb8".into()));
+        if let Some(pts_ns) = self.pts_ns {
+            parameters.insert(PTS_KEY.into(), Parameter::Integer(pts_ns as i64));
+        }
+        parameters
+    }
+}
+
+/// Written by `gst-source` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SourceReport {
+    pub frames: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Whether the pipeline ended, rather than the dataflow stopping it.
+    pub eos: bool,
+}
+
+/// Written by `ball-tracker` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TrackerReport {
+    pub frames: u64,
+    /// Frames in which the ball was found.
+    pub detected: u64,
+    /// The range of the center of the ball, in pixels.
+    pub min_x: Option<u32>,
+    pub max_x: Option<u32>,
+    pub min_y: Option<u32>,
+    pub max_y: Option<u32>,
+}
+
+/// Written by `gst-sink` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SinkReport {
+    pub frames: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Whether the pipeline processed all frames before it was shut down, e.g. so that a
+    /// muxer finished its file.
+    pub eos: bool,
+}
+
+/// Parses a pipeline in the syntax of `gst-launch-1.0`.
+pub fn launch(description: &str) -> eyre::Result<gst::Pipeline> {
+    gst::parse::launch(description)
+        .map_err(|err| eyre!("invalid pipeline `{description}`: {err}"))?
+        .downcast::<gst::Pipeline>()
+        .map_err(|_| eyre!("`{description}` is not a pipeline"))
+}
+
+/// The element `name` of `pipeline`, e.g. the `appsink name=sink` of its description.
+pub fn element<T: IsA<gst::Element>>(pipeline: &gst::Pipeline, name: &str) -> eyre::Result<T> {
+    pipeline
+        .by_name(name)
+        .ok_or_else(|| eyre!("the pipeline has no element named `{name}`"))?
+        .downcast::<T>()
+        .map_err(|element| {
+            eyre!(
+                "`{name}` is a {}, not a {}",
+                element.type_().name(),
+                T::static_type().name()
+            )
+        })
+}
+
+/// A readable description of an error message of a pipeline.
+pub fn describe_error(err: &gst::message::Error) -> String {
+    let source = err
+        .src()
+        .map(|src| src.path_string().to_string())
+        .unwrap_or_default();
+    match err.debug() {
+        Some(debug) => format!("{source}: {} ({debug})", err.error()),
+        None => format!("{source}: {}", err.error()),
+    }
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
@@ -0,0 +1,133 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, OptionExt, bail};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The profile that checks that the contract catches a drifted driver.
+const DRIFT_PROFILE: &str = "drift";
+
+/// Subset of `ControllerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ControllerReport {
+    states: u64,
+    final_error: f64,
+    violations: Vec<String>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("hil-toggle-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let profile = std::env::var("HIL_PROFILE").unwrap_or_else(|_| "sim".to_owned());
+    if profile == DRIFT_PROFILE {
+        bail!("`{DRIFT_PROFILE}` is checked after every run, select `sim` or `real`");
+    }
+    let dora = Dora::from_env()?;
+
+    let (dataflow, driver) = select_profile(&profile)?;
+    dora.build_dataflow(&dataflow).await?;
+    let _device = if profile == "real" {
+        start_mock_device_if_absent(&driver).await?
+    } else {
+        None
+    };
+    dora.run_dataflow(&dataflow).await?;
+
+    let report = read_report()?;
+    println!(
+        "profile `{profile}`: {} states, final error {:.3} rad/s",
+        report.states, report.final_error
+    );
+    if !report.violations.is_empty() {
+        bail!("the driver broke the contract: {:?}", report.violations);
+    }
+    if report.states == 0 {
+        bail!("the controller received no states");
+    }
+    if report.final_error > 1.0 {
+        bail!("the motor did not settle at the setpoint");
+    }
+
+    // the same controller against a driver that drifted from the contract
+    let (dataflow, _) = select_profile(DRIFT_PROFILE)?;
+    if dora.run_dataflow(&dataflow).await.is_ok() {
+        bail!("the dataflow should fail when the driver breaks the contract");
+    }
+    let report = read_report()?;
+    let Some(violation) = report.violations.first() else {
+        bail!("the controller did not report the drifted states");
+    };
+    println!("profile `{DRIFT_PROFILE}`: rejected as expected: {violation}");
+    if !violation.contains("velocity_rpm") {
+        bail!("expected the renamed `velocity_rpm` field to be rejected");
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Writes `dataflow.<profile>.yml` with the driver of `profiles/<profile>.yml`, next to
+/// `dataflow.yml` since dora resolves node paths relative to the dataflow file.
+fn select_profile(profile: &str) -> eyre::Result<(PathBuf, serde_yaml::Value)> {
+    let driver: serde_yaml::Value = serde_yaml::from_str(
+        &std::fs::read_to_string(format!("profiles/{profile}.yml"))
+            .with_context(|| format!("unknown profile `{profile}`"))?,
+    )?;
+    let mut dataflow: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string("dataflow.yml")?)?;
+    let node = dataflow["nodes"]
+        .as_sequence_mut()
+        .and_then(|nodes| nodes.iter_mut().find(|node| node["id"] == "driver"))
+        .ok_or_eyre("dataflow.yml has no `driver` node")?;
+    // the inputs and outputs are the contract, and stay the same
+    node["path"] = driver["path"].clone();
+    node["env"] = driver["env"].clone();
+
+    let path = PathBuf::from(format!("dataflow.{profile}.yml"));
+    std::fs::write(&path, serde_yaml::to_string(&dataflow)?)?;
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    Ok((path, driver))
+}
+
+/// Starts `mock-device` at the `DEVICE_ADDR` of the driver, unless a device already listens
+/// there.
+async fn start_mock_device_if_absent(
+    driver: &serde_yaml::Value,
+) -> eyre::Result<Option<tokio::process::Child>> {
+    let addr = driver["env"]["DEVICE_ADDR"]
+        .as_str()
+        .ok_or_eyre("the `real` profile has no DEVICE_ADDR")?;
+    if port_check::is_port_reachable(addr) {
+        println!("using the device at {addr}");
+        return Ok(None);
+    }
+    let (_, port) = addr.rsplit_once(':').ok_or_eyre("invalid DEVICE_ADDR")?;
+    println!("no device at {addr}, starting mock-device in its place");
+    let device = tokio::process::Command::new("nodes/target/release/mock-device")
+        .args(["--port", port])
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start mock device")?;
+    for _ in 0..50 {
+        if port_check::is_port_reachable(addr) {
+            return Ok(Some(device));
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    bail!("mock device did not start listening on {addr}");
+}
+
+fn read_report() -> eyre::Result<ControllerReport> {
+    Ok(serde_json::from_str(
+        &std::fs::read_to_string("out/controller.json")
+            .context("controller did not write a report")?,
+    )?)
+}
@@ -0,0 +1,102 @@
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+use eyre::bail;
+use hil_toggle_dataflow_nodes::{ControllerReport, env_or, write_json};
+use hil_toggle_schema::{MotorCommand, MotorState, SCHEMA_VERSION, decode, encode};
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// The window of `final_error` in the report.
+const FINAL_WINDOW: Duration = Duration::from_secs(1);
+
+/// Drives the motor to `SETPOINT` rad/s with a PI controller, answering every `state` with a
+/// `command` that is limited to `MAX_VOLTAGE`.
+///
+/// It doesn't know whether the driver is the simulator or the real device, both speak the
+/// contract in the `hil-toggle-schema` crate. Stops after `DURATION_S`, or at the first
+/// message that breaks the contract, and writes a [`ControllerReport`] to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let setpoint: f64 = env_or("SETPOINT", 50.0)?;
+    let kp: f64 = env_or("KP", 0.05)?;
+    let ki: f64 = env_or("KI", 0.5)?;
+    let max_voltage: f64 = env_or("MAX_VOLTAGE", 12.0)?;
+    let duration = Duration::from_secs_f64(env_or("DURATION_S", 5.0)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/controller.json".to_owned())?.into();
+    let output = DataId::from("command".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = ControllerReport::default();
+    let mut integral = 0.0;
+    let mut started: Option<Instant> = None;
+    let mut last_state: Option<Instant> = None;
+    let mut recent_errors = VecDeque::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "state" => {
+                    let states = match decode::<MotorState>(&data) {
+                        Ok(states) => states,
+                        Err(violation) => {
+                            eprintln!("{violation}");
+                            report.violations.push(violation.to_string());
+                            break;
+                        }
+                    };
+                    let now = Instant::now();
+                    let dt = last_state.map_or(0.0, |last| (now - last).as_secs_f64());
+                    last_state = Some(now);
+                    let started = *started.get_or_insert(now);
+
+                    let mut commands = Vec::with_capacity(states.len());
+                    for state in states {
+                        report.states += 1;
+                        let error = setpoint - state.velocity_rad_s;
+                        // anti-windup: the integral alone never exceeds the voltage limit
+                        integral =
+                            (integral + error * dt).clamp(-max_voltage / ki, max_voltage / ki);
+                        let voltage = (kp * error + ki * integral).clamp(-max_voltage, max_voltage);
+                        commands.push(MotorCommand {
+                            schema_version: SCHEMA_VERSION,
+                            seq: state.seq,
+                            voltage,
+                        });
+                        recent_errors.push_back((now, error.abs()));
+                    }
+                    while recent_errors
+                        .front()
+                        .is_some_and(|(at, _)| now - *at > FINAL_WINDOW)
+                    {
+                        recent_errors.pop_front();
+                    }
+                    report.commands += commands.len() as u64;
+                    node.send_output(output.clone(), Default::default(), encode(&commands)?)?;
+
+                    if now - started >= duration {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if !recent_errors.is_empty() {
+        report.final_error =
+            recent_errors.iter().map(|(_, error)| error).sum::<f64>() / recent_errors.len() as f64;
+    }
+    println!(
+        "{} states, {} commands, final error {:.3} rad/s",
+        report.states, report.commands, report.final_error
+    );
+    write_json(&report_file, &report)?;
+    if let Some(violation) = report.violations.first() {
+        bail!("the driver broke the contract: {violation}");
+    }
+    Ok(())
+}
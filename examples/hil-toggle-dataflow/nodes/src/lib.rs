@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Written by the controller when it stops.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ControllerReport {
+    pub states: u64,
+    pub commands: u64,
+    /// Mean absolute velocity error over the last second, in rad/s.
+    pub final_error: f64,
+    /// Messages of the driver that broke the contract. The controller stops at the first one.
+    pub violations: Vec<String>,
+}
+
+/// A first-order model of a DC motor, shared by the simulator and the mock device.
+#[derive(Debug, Clone)]
+pub struct MotorModel {
+    /// Velocity per volt at steady state, in rad/s/V.
+    pub gain: f64,
+    /// Time constant, in seconds.
+    pub tau_s: f64,
+    /// Winding resistance, in ohms.
+    pub resistance: f64,
+    pub velocity: f64,
+}
+
+impl MotorModel {
+    pub fn new(gain: f64, tau_s: f64, resistance: f64) -> Self {
+        Self {
+            gain,
+            tau_s,
+            resistance,
+            velocity: 0.0,
+        }
+    }
+
+    /// Advances the model by `dt_s` at `voltage`, and returns the current that it draws.
+    pub fn step(&mut self, voltage: f64, dt_s: f64) -> f64 {
+        let dt_s = dt_s.min(self.tau_s);
+        self.velocity += (self.gain * voltage - self.velocity) / self.tau_s * dt_s;
+        (voltage - self.velocity / self.gain) / self.resistance
+    }
+}
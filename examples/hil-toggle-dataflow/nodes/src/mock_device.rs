@@ -0,0 +1,48 @@
+use eyre::{OptionExt, bail};
+use hil_toggle_dataflow_nodes::MotorModel;
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    time::Instant,
+};
+
+/// Stands in for the motor controller that `real-driver` talks to, for trying the `real`
+/// profile without hardware.
+///
+/// Usage: `mock-device [--port <port>]`. Serves one connection at a time, and answers every
+/// `SET <voltage>` with `<velocity in rad/s> <current in A>` of a [`MotorModel`] that runs in
+/// real time.
+fn main() -> eyre::Result<()> {
+    let mut port = "18100".to_owned();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => port = args.next().ok_or_eyre("--port requires a value")?,
+            other => bail!("unknown argument `{other}`"),
+        }
+    }
+
+    let listener = TcpListener::bind(format!("127.0.0.1:{port}"))?;
+    println!("mock motor controller listening on port {port}");
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let mut writer = stream.try_clone()?;
+        let mut motor = MotorModel::new(10.0, 0.2, 2.0);
+        let mut last_set = Instant::now();
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            let Some(voltage) = line
+                .strip_prefix("SET ")
+                .and_then(|v| v.parse::<f64>().ok())
+            else {
+                eprintln!("ignoring invalid request `{line}`");
+                continue;
+            };
+            let current = motor.step(voltage, last_set.elapsed().as_secs_f64());
+            last_set = Instant::now();
+            writeln!(writer, "{:.5} {current:.5}", motor.velocity)?;
+        }
+        println!("driver disconnected");
+    }
+    Ok(())
+}
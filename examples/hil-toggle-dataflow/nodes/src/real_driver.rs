@@ -0,0 +1,74 @@
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+use eyre::{Context, OptionExt, eyre};
+use hil_toggle_dataflow_nodes::env_or;
+use hil_toggle_schema::{MotorCommand, MotorState, SCHEMA_VERSION, decode, encode};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+};
+
+/// Drives the real motor controller at `DEVICE_ADDR`: on every `tick`, it sets the voltage of
+/// the last `command` and sends the velocity and current that the device measured on `state`.
+///
+/// The device speaks a line protocol over TCP: `SET <voltage>` is answered with
+/// `<velocity in rad/s> <current in A>`. `mock-device` implements it for trying this driver
+/// without hardware.
+fn main() -> eyre::Result<()> {
+    let addr: String = env_or("DEVICE_ADDR", "127.0.0.1:18100".to_owned())?;
+    let output = DataId::from("state".to_owned());
+
+    let stream =
+        TcpStream::connect(&addr).with_context(|| format!("failed to connect to {addr}"))?;
+    stream.set_nodelay(true)?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    println!("connected to the motor controller at {addr}");
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut voltage = 0.0;
+    let mut seq = 0;
+    let mut line = String::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "tick" => {
+                    writeln!(writer, "SET {voltage}")?;
+                    line.clear();
+                    if reader.read_line(&mut line)? == 0 {
+                        return Err(eyre!("the device at {addr} closed the connection"));
+                    }
+                    let (velocity, current) = line
+                        .trim()
+                        .split_once(' ')
+                        .ok_or_eyre("invalid reply of the device")?;
+                    let state = MotorState {
+                        schema_version: SCHEMA_VERSION,
+                        seq,
+                        velocity_rad_s: velocity.parse()?,
+                        current_a: current.parse()?,
+                    };
+                    node.send_output(output.clone(), Default::default(), encode(&[state])?)?;
+                    seq += 1;
+                }
+                "command" => {
+                    if let Some(command) = decode::<MotorCommand>(&data)?.last() {
+                        voltage = command.voltage;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } if id.as_str() == "command" => {
+                println!("Input `{id}` was closed, the controller stopped");
+                break;
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    // leave the motor unpowered
+    writeln!(writer, "SET 0")?;
+    println!("sent {seq} states");
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::StringArray, dora_core::config::DataId};
+use eyre::bail;
+use hil_toggle_dataflow_nodes::{MotorModel, env_or};
+use hil_toggle_schema::{MotorCommand, MotorState, SCHEMA_VERSION, decode, encode};
+use std::time::Instant;
+
+/// Simulates the motor: on every `tick`, advances a [`MotorModel`] at the voltage of the last
+/// `command` and sends the measured `state`, with up to `NOISE` rad/s of measurement noise.
+///
+/// `DRIFT` makes it send states that break the contract, like a driver that was built against
+/// an outdated copy of the messages:
+///
+/// - `renamed-field`: the velocity is sent as `velocity_rpm`.
+/// - `old-version`: the states have the previous schema version.
+fn main() -> eyre::Result<()> {
+    let gain: f64 = env_or("GAIN", 10.0)?;
+    let tau_s: f64 = env_or("TAU_S", 0.2)?;
+    let resistance: f64 = env_or("RESISTANCE", 2.0)?;
+    let noise: f64 = env_or("NOISE", 0.2)?;
+    let drift: String = env_or("DRIFT", String::new())?;
+    if !["", "renamed-field", "old-version"].contains(&drift.as_str()) {
+        bail!("unknown DRIFT `{drift}`");
+    }
+    let output = DataId::from("state".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut motor = MotorModel::new(gain, tau_s, resistance);
+    let mut voltage = 0.0;
+    let mut last_tick: Option<Instant> = None;
+    let mut rng = 0x2545_f491_4f6c_dd1d_u64;
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "tick" => {
+                    let now = Instant::now();
+                    let dt = last_tick.map_or(0.0, |last| (now - last).as_secs_f64());
+                    last_tick = Some(now);
+                    let current_a = motor.step(voltage, dt);
+
+                    // xorshift, in -1..1
+                    rng ^= rng << 13;
+                    rng ^= rng >> 7;
+                    rng ^= rng << 17;
+                    let unit = (rng >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0;
+                    let state = MotorState {
+                        schema_version: SCHEMA_VERSION,
+                        seq,
+                        velocity_rad_s: motor.velocity + unit * noise,
+                        current_a,
+                    };
+                    let array = match drift.as_str() {
+                        "" => encode(&[state])?,
+                        _ => StringArray::from(vec![drifted(&state, &drift).to_string()]),
+                    };
+                    node.send_output(output.clone(), Default::default(), array)?;
+                    seq += 1;
+                }
+                "command" => {
+                    if let Some(command) = decode::<MotorCommand>(&data)?.last() {
+                        voltage = command.voltage;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } if id.as_str() == "command" => {
+                println!("Input `{id}` was closed, the controller stopped");
+                break;
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("sent {seq} states");
+    Ok(())
+}
+
+/// The state as a driver with an outdated copy of the messages would send it.
+fn drifted(state: &MotorState, drift: &str) -> serde_json::Value {
+    match drift {
+        "renamed-field" => serde_json::json!({
+            "schema_version": state.schema_version,
+            "seq": state.seq,
+            "velocity_rpm": state.velocity_rad_s * 60.0 / std::f64::consts::TAU,
+            "current_a": state.current_a,
+        }),
+        _ => serde_json::json!({
+            "schema_version": SCHEMA_VERSION - 1,
+            "seq": state.seq,
+            "velocity_rad_s": state.velocity_rad_s,
+            "current_a": state.current_a,
+        }),
+    }
+}
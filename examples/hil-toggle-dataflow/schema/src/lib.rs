@@ -0,0 +1,150 @@
+//! The message contracts between the controller and the motor drivers.
+//!
+//! The controller and both drivers encode and decode their messages only through this crate.
+//! A driver that drifted from the contract, e.g. one built against an outdated copy of the
+//! messages with a renamed field, is rejected with a [`ContractViolation`] instead of being
+//! misread.
+
+use dora_node_api::{
+    ArrowData,
+    arrow::array::{Array, AsArray, StringArray},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::fmt;
+
+/// Bumped on every change of the messages. Both sides must use the same version.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Sent by the controller on `command`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MotorCommand {
+    pub schema_version: u32,
+    /// The `seq` of the [`MotorState`] that the command answers.
+    pub seq: u64,
+    pub voltage: f64,
+}
+
+/// Sent by the drivers on `state`, once per tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MotorState {
+    pub schema_version: u32,
+    pub seq: u64,
+    pub velocity_rad_s: f64,
+    pub current_a: f64,
+}
+
+/// A message of the contract.
+pub trait Message: Serialize + DeserializeOwned {
+    const NAME: &'static str;
+
+    fn schema_version(&self) -> u32;
+
+    /// Checks the values, beyond what deserializing already checks.
+    fn validate(&self) -> Result<(), String>;
+}
+
+impl Message for MotorCommand {
+    const NAME: &'static str = "MotorCommand";
+
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.voltage.is_finite() {
+            return Err(format!("`voltage` is {}", self.voltage));
+        }
+        Ok(())
+    }
+}
+
+impl Message for MotorState {
+    const NAME: &'static str = "MotorState";
+
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.velocity_rad_s.is_finite() || !self.current_a.is_finite() {
+            return Err("`velocity_rad_s` and `current_a` must be finite".to_owned());
+        }
+        Ok(())
+    }
+}
+
+/// A message that doesn't match the contract.
+#[derive(Debug, Clone)]
+pub struct ContractViolation {
+    pub message: &'static str,
+    pub reason: String,
+    /// The message as it was received, or as it would have been sent.
+    pub row: String,
+}
+
+impl fmt::Display for ContractViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {}: {} in `{}`",
+            self.message, self.reason, self.row
+        )
+    }
+}
+
+impl std::error::Error for ContractViolation {}
+
+fn check<M: Message>(message: &M, row: &str) -> Result<(), ContractViolation> {
+    let violation = |reason| ContractViolation {
+        message: M::NAME,
+        reason,
+        row: row.to_owned(),
+    };
+    if message.schema_version() != SCHEMA_VERSION {
+        return Err(violation(format!(
+            "schema version {}, expected {SCHEMA_VERSION}",
+            message.schema_version()
+        )));
+    }
+    message.validate().map_err(violation)
+}
+
+/// Encodes `messages` as one JSON string per array element, after checking them.
+pub fn encode<M: Message>(messages: &[M]) -> Result<StringArray, ContractViolation> {
+    let mut rows = Vec::with_capacity(messages.len());
+    for message in messages {
+        let row = serde_json::to_string(message).map_err(|err| ContractViolation {
+            message: M::NAME,
+            reason: err.to_string(),
+            row: String::new(),
+        })?;
+        check(message, &row)?;
+        rows.push(row);
+    }
+    Ok(StringArray::from(rows))
+}
+
+/// Decodes and checks the messages of an input, see [`encode`].
+pub fn decode<M: Message>(data: &ArrowData) -> Result<Vec<M>, ContractViolation> {
+    let Some(rows) = data.as_string_opt::<i32>() else {
+        return Err(ContractViolation {
+            message: M::NAME,
+            reason: format!("expected a Utf8 array, got {}", data.data_type()),
+            row: String::new(),
+        });
+    };
+    rows.iter()
+        .flatten()
+        .map(|row| {
+            let message: M = serde_json::from_str(row).map_err(|err| ContractViolation {
+                message: M::NAME,
+                reason: err.to_string(),
+                row: row.to_owned(),
+            })?;
+            check(&message, row)?;
+            Ok(message)
+        })
+        .collect()
+}
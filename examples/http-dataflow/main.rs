@@ -0,0 +1,150 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    time::{Duration, Instant},
+};
+use tokio::process::Command;
+
+/// Must match `INGEST_ADDR` in `dataflow.yml`.
+const INGEST_ADDR: &str = "127.0.0.1:8081";
+/// Must match `LATEST_ADDR` in `dataflow.yml`.
+const LATEST_ADDR: &str = "127.0.0.1:8082";
+
+/// Subset of `Reading` in `nodes/src/lib.rs`.
+#[derive(Debug, PartialEq, Deserialize)]
+struct Reading {
+    sensor: String,
+    value: f64,
+    unit: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("http-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    if which::which("curl").is_err() {
+        bail!("the runner uses `curl` to call the endpoints, please install it");
+    }
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let dataflow_task = tokio::spawn(dora.run_dataflow(dataflow));
+    let session = exercise_endpoints().await;
+    // `http-ingest` stops after `DURATION_MS`, which ends the dataflow
+    dataflow_task.await??;
+    session
+}
+
+async fn exercise_endpoints() -> eyre::Result<()> {
+    // the servers start when the dataflow is up
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while !(port_check::is_port_reachable(INGEST_ADDR)
+        && port_check::is_port_reachable(LATEST_ADDR))
+    {
+        if Instant::now() > deadline {
+            bail!("HTTP endpoints never became reachable on {INGEST_ADDR} and {LATEST_ADDR}");
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let (status, body) = post(r#"{"sensor": "temp-1", "value": 21.5, "unit": "C"}"#).await?;
+    expect_status("POST of one reading", status, 202, &body)?;
+    let (status, body) = post(
+        r#"[{"sensor": "humidity", "value": 40.0, "unit": "%"}, {"sensor": "temp-1", "value": 22.0, "unit": "C"}]"#,
+    )
+    .await?;
+    expect_status("POST of two readings", status, 202, &body)?;
+    println!("POST /readings accepted 3 readings: {body}");
+
+    let (status, body) = post(r#"{"value": 1.0}"#).await?;
+    if !(400..500).contains(&status) {
+        bail!("expected a reading without sensor to be rejected, got {status}: {body}");
+    }
+    println!("POST /readings rejected a reading without sensor with {status}");
+
+    // the readings are sent through the dataflow, so the sink gets them a bit later
+    let expected = Reading {
+        sensor: "temp-1".to_owned(),
+        value: 22.0,
+        unit: Some("C".to_owned()),
+    };
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let (status, body) = get("/latest/temp-1").await?;
+        if status == 200 && serde_json::from_str::<Reading>(&body)? == expected {
+            println!("GET /latest/temp-1 returned the latest reading: {body}");
+            break;
+        }
+        if Instant::now() > deadline {
+            bail!(
+                "GET /latest/temp-1 didn't return {expected:?} within 5 s, last got {status}: {body}"
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    let (status, body) = get("/latest").await?;
+    expect_status("GET /latest", status, 200, &body)?;
+    let latest: BTreeMap<String, Reading> = serde_json::from_str(&body)?;
+    if latest.len() != 2 || latest.get("humidity").map(|r| r.value) != Some(40.0) {
+        bail!("expected the latest readings of `temp-1` and `humidity`, got {body}");
+    }
+    println!("GET /latest returned both sensors");
+
+    let (status, body) = get("/latest/pressure").await?;
+    expect_status("GET of an unknown sensor", status, 404, &body)?;
+    println!("GET /latest/pressure returned 404");
+    Ok(())
+}
+
+async fn post(json: &str) -> eyre::Result<(u16, String)> {
+    curl(&[
+        "-X",
+        "POST",
+        "-H",
+        "content-type: application/json",
+        "-d",
+        json,
+        &format!("http://{INGEST_ADDR}/readings"),
+    ])
+    .await
+}
+
+async fn get(path: &str) -> eyre::Result<(u16, String)> {
+    curl(&[&format!("http://{LATEST_ADDR}{path}")]).await
+}
+
+/// Runs `curl` with `args`, and returns the status code and body of the response.
+async fn curl(args: &[&str]) -> eyre::Result<(u16, String)> {
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--write-out", "\n%{http_code}"])
+        .args(args)
+        .output()
+        .await
+        .context("failed to run curl")?;
+    if !output.status.success() {
+        bail!("curl failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+    let Some((body, status)) = stdout.rsplit_once('\n') else {
+        bail!("unexpected output of curl: {stdout}");
+    };
+    Ok((status.trim().parse()?, body.to_owned()))
+}
+
+fn expect_status(request: &str, status: u16, expected: u16, body: &str) -> eyre::Result<()> {
+    if status != expected {
+        bail!("{request}: expected status {expected}, got {status}: {body}");
+    }
+    Ok(())
+}
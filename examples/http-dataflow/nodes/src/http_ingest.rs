@@ -0,0 +1,111 @@
+use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+use dora_node_api::{
+    DoraNode, Event, MetadataParameters,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::Context;
+use futures::{StreamExt, channel::mpsc::UnboundedSender};
+use http_dataflow_nodes::{Reading, env_or, readings_to_arrow};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// The body of `POST /readings`: one reading, or a list of them.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Body {
+    One(Reading),
+    Many(Vec<Reading>),
+}
+
+#[derive(Clone)]
+struct AppState {
+    readings: UnboundedSender<Vec<Reading>>,
+}
+
+/// Accepts readings as JSON on `POST /readings` on `INGEST_ADDR`, and sends each request as
+/// one struct array on `readings`.
+///
+/// Replies `202 Accepted` once the readings are queued for the dataflow, and `400`/`422` for
+/// invalid bodies. Stops after `DURATION_MS`, checked on every `tick`.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let addr: String = env_or("INGEST_ADDR", "127.0.0.1:8081".to_owned())?;
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let output = DataId::from("readings".to_owned());
+
+    let (readings_tx, readings_rx) = futures::channel::mpsc::unbounded();
+    let app = Router::new()
+        .route("/readings", post(post_readings))
+        .with_state(AppState {
+            readings: readings_tx,
+        });
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to listen on {addr}"))?;
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            eprintln!("HTTP server failed: {err}");
+        }
+    });
+    println!("accepting readings on http://{addr}/readings");
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let mut events = events.merge_external(Box::pin(readings_rx));
+
+    let start = Instant::now();
+    while let Some(event) = events.next().await {
+        match event {
+            MergedEvent::External(readings) => {
+                node.send_output(
+                    output.clone(),
+                    MetadataParameters::default(),
+                    readings_to_arrow(&readings),
+                )?;
+            }
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, .. } => match id.as_str() {
+                    "tick" => {
+                        if start.elapsed() >= duration {
+                            break;
+                        }
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+        }
+    }
+    Ok(())
+}
+
+async fn post_readings(
+    State(state): State<AppState>,
+    Json(body): Json<Body>,
+) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    let readings = match body {
+        Body::One(reading) => vec![reading],
+        Body::Many(readings) => readings,
+    };
+    if let Some(reading) = readings.iter().find(|r| r.sensor.is_empty()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("empty sensor name in {reading:?}"),
+        ));
+    }
+    let accepted = readings.len();
+    if accepted > 0 {
+        state.readings.unbounded_send(readings).map_err(|_| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "the dataflow is stopping".to_owned(),
+            )
+        })?;
+    }
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "accepted": accepted })),
+    ))
+}
@@ -0,0 +1,75 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+};
+use dora_node_api::{DoraNode, Event};
+use eyre::Context;
+use http_dataflow_nodes::{Reading, env_or, readings_from_arrow};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// The latest reading of each sensor.
+type Latest = Arc<Mutex<BTreeMap<String, Reading>>>;
+
+/// Keeps the latest reading of each sensor from `readings`, and serves them on `LATEST_ADDR`:
+/// all of them on `GET /latest`, and the one of a sensor on `GET /latest/{sensor}`.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let addr: String = env_or("LATEST_ADDR", "127.0.0.1:8082".to_owned())?;
+
+    let latest = Latest::default();
+    let app = Router::new()
+        .route("/latest", get(all))
+        .route("/latest/{sensor}", get(one))
+        .with_state(latest.clone());
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to listen on {addr}"))?;
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            eprintln!("HTTP server failed: {err}");
+        }
+    });
+    println!("serving the latest readings on http://{addr}/latest");
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+    while let Some(event) = events.recv_async().await {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "readings" => {
+                    let readings = readings_from_arrow(&data)?;
+                    let mut latest = latest.lock().unwrap();
+                    for reading in readings {
+                        latest.insert(reading.sensor.clone(), reading);
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
+
+async fn all(State(latest): State<Latest>) -> Json<BTreeMap<String, Reading>> {
+    Json(latest.lock().unwrap().clone())
+}
+
+async fn one(
+    State(latest): State<Latest>,
+    Path(sensor): Path<String>,
+) -> Result<Json<Reading>, (StatusCode, String)> {
+    match latest.lock().unwrap().get(&sensor) {
+        Some(reading) => Ok(Json(reading.clone())),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            format!("no reading of sensor `{sensor}` yet"),
+        )),
+    }
+}
@@ -0,0 +1,67 @@
+use dora_node_api::arrow::{
+    array::{Array, ArrayRef, AsArray, Float64Array, StringArray, StructArray},
+    datatypes::{DataType, Field, Float64Type},
+};
+use eyre::{OptionExt, eyre};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub use example_node_utils::env_or;
+
+/// A reading that is POSTed to the ingest node as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reading {
+    pub sensor: String,
+    pub value: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+/// Encodes readings as a struct array with the columns `sensor`, `value`, and `unit`, one row
+/// per reading.
+pub fn readings_to_arrow(readings: &[Reading]) -> StructArray {
+    let sensors: StringArray = readings.iter().map(|r| Some(r.sensor.as_str())).collect();
+    let values: Float64Array = readings.iter().map(|r| r.value).collect();
+    let units: StringArray = readings.iter().map(|r| r.unit.as_deref()).collect();
+    StructArray::from(vec![
+        (
+            Arc::new(Field::new("sensor", DataType::Utf8, false)),
+            Arc::new(sensors) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("value", DataType::Float64, false)),
+            Arc::new(values) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("unit", DataType::Utf8, true)),
+            Arc::new(units) as ArrayRef,
+        ),
+    ])
+}
+
+pub fn readings_from_arrow(array: &dyn Array) -> eyre::Result<Vec<Reading>> {
+    let array = array
+        .as_struct_opt()
+        .ok_or_eyre("expected a struct array")?;
+    let column = |name: &str| {
+        array
+            .column_by_name(name)
+            .ok_or_else(|| eyre!("missing column `{name}`"))
+    };
+    let sensors = column("sensor")?
+        .as_string_opt::<i32>()
+        .ok_or_eyre("`sensor` is not a Utf8 array")?;
+    let values = column("value")?
+        .as_primitive_opt::<Float64Type>()
+        .ok_or_eyre("`value` is not a Float64 array")?;
+    let units = column("unit")?
+        .as_string_opt::<i32>()
+        .ok_or_eyre("`unit` is not a Utf8 array")?;
+    Ok((0..array.len())
+        .map(|i| Reading {
+            sensor: sensors.value(i).to_owned(),
+            value: values.value(i),
+            unit: units.is_valid(i).then(|| units.value(i).to_owned()),
+        })
+        .collect())
+}
@@ -0,0 +1,178 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{path::Path, process::Stdio, time::Duration};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+const DORA_INJECT: &str = "nodes/target/release/dora-inject";
+
+/// Subset of `ControllerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ControllerReport {
+    received: Vec<Received>,
+    state: ControllerState,
+}
+
+/// Subset of `Received` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct Received {
+    input: String,
+    value: String,
+    sender: Option<String>,
+    rejected: Option<String>,
+}
+
+/// Subset of `ControllerState` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ControllerState {
+    mode: String,
+    gain: f64,
+    setpoint: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("inject-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let mut dataflow_proc = dora
+        .cli(["daemon", "--run-dataflow"])
+        .arg(dataflow)
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // the scripted session: a single message, the messages of `session.txt` from stdin, and
+    // the shutdown, each by its own `dora-inject`, like a developer would type them
+    inject(
+        &[
+            "--input",
+            "speed",
+            "--float",
+            "2.5",
+            "--meta",
+            "sender=runner",
+        ],
+        None,
+    )
+    .await?;
+    let session = std::fs::read_to_string("session.txt")?;
+    inject(&[], Some(&session)).await?;
+    inject(&["--input", "cmd", "--json", r#"{"shutdown": true}"#], None).await?;
+
+    let status = tokio::time::timeout(Duration::from_secs(30), dataflow_proc.wait())
+        .await
+        .context("the controller did not shut down")??;
+    if !status.success() {
+        bail!("dataflow failed with {status}");
+    }
+
+    let report: ControllerReport = serde_json::from_str(
+        &std::fs::read_to_string("out/controller.json")
+            .context("the controller did not write a report")?,
+    )?;
+    for received in &report.received {
+        match &received.rejected {
+            None => println!("applied {} {}", received.input, received.value),
+            Some(reason) => println!("rejected {} {}: {reason}", received.input, received.value),
+        }
+    }
+
+    // the order of the messages is only kept per input, so compare them per input
+    let of_input = |input: &str| -> Vec<(&str, Option<&str>, bool)> {
+        report
+            .received
+            .iter()
+            .filter(|received| received.input == input)
+            .map(|received| {
+                (
+                    received.value.as_str(),
+                    received.sender.as_deref(),
+                    received.rejected.is_some(),
+                )
+            })
+            .collect()
+    };
+    let expected_cmd = [
+        (r#"{"mode": "manual"}"#, None, false),
+        (r#"{"gain": 0.5}"#, None, false),
+        (r#"{"gain": "fast"}"#, None, true),
+        (r#"{"shutdown": true}"#, None, false),
+    ];
+    if of_input("cmd") != expected_cmd {
+        bail!(
+            "expected the commands {expected_cmd:?} (value, sender, rejected), the controller \
+             received {:?}",
+            of_input("cmd")
+        );
+    }
+    let expected_speed = [
+        ("2.5", Some("runner"), false),
+        ("4", Some("session"), false),
+    ];
+    if of_input("speed") != expected_speed {
+        bail!(
+            "expected the setpoints {expected_speed:?} (value, sender, rejected), the \
+             controller received {:?}",
+            of_input("speed")
+        );
+    }
+    let state = &report.state;
+    if state.mode != "manual" || state.gain != 0.5 || state.setpoint != 4.0 {
+        bail!(
+            "the injected messages should set manual mode, gain 0.5 and setpoint 4, got {state:?}"
+        );
+    }
+    println!("the controller received every injected message, and rejected the invalid one");
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Runs `dora-inject` with `args`, and writes `stdin` to it, if any.
+///
+/// `dora-inject` can only connect while the dataflow is running, and while no other process is
+/// connected as the `inject` node, so this retries until it connects.
+async fn inject(args: &[&str], stdin: Option<&str>) -> eyre::Result<()> {
+    for _ in 0..30 {
+        let mut inject = Command::new(DORA_INJECT)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to start dora-inject")?;
+        let mut input = inject.stdin.take().unwrap();
+        // fails when dora-inject exited because it couldn't connect
+        let _ = input.write_all(stdin.unwrap_or_default().as_bytes()).await;
+        drop(input);
+
+        let output = tokio::time::timeout(Duration::from_secs(10), inject.wait_with_output())
+            .await
+            .context("dora-inject did not exit")??;
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.contains("failed to connect") {
+            bail!(
+                "dora-inject {args:?} failed with {}: {stderr}",
+                output.status
+            );
+        }
+        println!("dora-inject could not connect, retrying");
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    bail!("dora-inject could not connect to the dataflow");
+}
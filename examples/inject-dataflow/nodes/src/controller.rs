@@ -0,0 +1,110 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter, arrow::array::StringArray, dora_core::config::DataId,
+};
+use eyre::Context;
+use inject_dataflow_nodes::{
+    Command, ControllerReport, ControllerState, Mode, Received, env_or, write_json,
+};
+use std::path::PathBuf;
+
+/// A controller to poke at with `dora-inject`.
+///
+/// Applies [`Command`]s from `cmd` and setpoints from `speed`, and sends its
+/// [`ControllerState`] on `state` every tick. Stops on the command `{"shutdown": true}`, and
+/// writes everything it received to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", PathBuf::from("out/controller.json"))?;
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let output = DataId::from("state".to_owned());
+
+    let mut report = ControllerReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let sender = match metadata.parameters.get("sender") {
+                    Some(Parameter::String(sender)) => Some(sender.clone()),
+                    _ => None,
+                };
+                match id.as_str() {
+                    "tick" => {
+                        let state = &mut report.state;
+                        if state.mode == Mode::Auto {
+                            state.output = state.gain * state.setpoint;
+                        }
+                        node.send_output(
+                            output.clone(),
+                            Default::default(),
+                            StringArray::from(vec![serde_json::to_string(state)?]),
+                        )?;
+                    }
+                    "cmd" => {
+                        let json: &str =
+                            TryFrom::try_from(&data).context("expected a JSON string")?;
+                        let result = serde_json::from_str(json)
+                            .map_err(|err| err.to_string())
+                            .map(|command| apply(&mut report.state, command));
+                        report.received.push(received(&id, json, sender, &result));
+                        if result == Ok(true) {
+                            println!("shutting down");
+                            break;
+                        }
+                    }
+                    "speed" => {
+                        let (value, result) = match f64::try_from(&data) {
+                            Ok(setpoint) => {
+                                report.state.setpoint = setpoint;
+                                (setpoint.to_string(), Ok(false))
+                            }
+                            Err(err) => (
+                                data.data_type().to_string(),
+                                Err(format!("expected a float: {err}")),
+                            ),
+                        };
+                        report.received.push(received(&id, &value, sender, &result));
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                }
+            }
+            Event::InputClosed { id } => {
+                // `dora-inject` closes its outputs whenever it exits, keep running for the next
+                // session
+                println!("Input `{id}` was closed");
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("final state: {:?}", report.state);
+    write_json(&report_file, &report)
+}
+
+/// Applies `command`, and returns whether it is a shutdown.
+fn apply(state: &mut ControllerState, command: Command) -> bool {
+    if let Some(mode) = command.mode {
+        state.mode = mode;
+    }
+    if let Some(gain) = command.gain {
+        state.gain = gain;
+    }
+    command.shutdown
+}
+
+fn received(
+    input: &DataId,
+    value: &str,
+    sender: Option<String>,
+    result: &Result<bool, String>,
+) -> Received {
+    match result {
+        Ok(_) => println!("applied {input} {value}"),
+        Err(err) => eprintln!("rejected {input} {value}: {err}"),
+    }
+    Received {
+        input: input.to_string(),
+        value: value.to_owned(),
+        sender,
+        rejected: result.as_ref().err().cloned(),
+    }
+}
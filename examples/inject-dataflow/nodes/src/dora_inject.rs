@@ -0,0 +1,174 @@
+use clap::{Args, Parser};
+use dora_node_api::{
+    self, DoraNode, MetadataParameters, Parameter,
+    arrow::array::{Float64Array, Int64Array, StringArray},
+    dora_core::config::{DataId, NodeId},
+};
+use eyre::{Context, bail, eyre};
+use std::io::{BufRead, IsTerminal, Write};
+
+/// Sends inputs to a running dataflow from the command line.
+///
+/// Connects to the dataflow as the dynamic node NODE, and sends one message on its output
+/// INPUT, e.g. `dora-inject --input cmd --json '{"gain": 0.5}'`. Without INPUT, it reads
+/// messages from stdin instead, one per line with the same arguments, e.g.
+/// `--input speed --float 2.5`.
+#[derive(Debug, Parser)]
+#[command(name = "dora-inject")]
+struct Cli {
+    /// Id of the dynamic node to connect as. Its outputs are the inputs that can be sent.
+    #[arg(long, default_value = "inject")]
+    node: String,
+    #[command(flatten)]
+    message: Message,
+}
+
+/// A line of stdin, without the options of the connection.
+#[derive(Debug, Parser)]
+#[command(name = "dora-inject", no_binary_name = true)]
+struct Line {
+    #[command(flatten)]
+    message: Message,
+}
+
+/// One message to send, from the command line or from a line of stdin.
+#[derive(Debug, Args)]
+struct Message {
+    /// Output of NODE to send on, which is connected to the input to inject into.
+    #[arg(long)]
+    input: Option<String>,
+    #[command(flatten)]
+    value: Value,
+    /// Adds a string metadata parameter. Can be repeated.
+    #[arg(long = "meta", value_name = "KEY=VALUE", value_parser = parse_parameter)]
+    parameters: Vec<(String, String)>,
+}
+
+#[derive(Debug, Args)]
+#[group(multiple = false)]
+struct Value {
+    /// Sends a JSON string, after checking that it is valid JSON.
+    #[arg(long)]
+    json: Option<String>,
+    /// Sends a string.
+    #[arg(long)]
+    string: Option<String>,
+    /// Sends a 64-bit float.
+    #[arg(long, allow_negative_numbers = true)]
+    float: Option<f64>,
+    /// Sends a 64-bit integer.
+    #[arg(long, allow_negative_numbers = true)]
+    int: Option<i64>,
+}
+
+fn parse_parameter(parameter: &str) -> Result<(String, String), String> {
+    match parameter.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _ => Err(format!("expected KEY=VALUE, got `{parameter}`")),
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let cli = Cli::parse();
+
+    // connecting fails while the dataflow isn't running, or when another process is already
+    // connected as the node
+    let (mut node, _events) = DoraNode::init_from_node_id(NodeId::from(cli.node.clone()))
+        .with_context(|| format!("failed to connect as dynamic node `{}`", cli.node))?;
+
+    if cli.message.input.is_some() {
+        return send(&mut node, &cli.message);
+    }
+    if !is_empty(&cli.message) {
+        bail!("--input is required to send a value");
+    }
+
+    // a session: one message per line of stdin
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        println!(
+            "connected as `{}`, enter one message per line, e.g.",
+            cli.node
+        );
+        println!("  --input cmd --json '{{\"gain\": 0.5}}'");
+        println!("Ctrl+D to quit");
+        print!("> ");
+        std::io::stdout().flush()?;
+    }
+    let mut sent = 0;
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let result = parse_line(line).and_then(|message| send(&mut node, &message));
+        match result {
+            Ok(()) => sent += 1,
+            // a typo shouldn't end an interactive session, but it should fail a script
+            Err(err) if interactive => eprintln!("error: {err:#}"),
+            Err(err) => return Err(err.wrap_err(format!("failed to send `{line}`"))),
+        }
+        if interactive {
+            print!("> ");
+            std::io::stdout().flush()?;
+        }
+    }
+    println!("sent {sent} messages");
+    Ok(())
+}
+
+fn parse_line(line: &str) -> eyre::Result<Message> {
+    let words = shlex::split(line).ok_or_else(|| eyre!("unbalanced quotes"))?;
+    let Line { message } = Line::try_parse_from(words)?;
+    if message.input.is_none() || is_empty(&message) {
+        bail!("expected --input and a value, e.g. `--input speed --float 2.5`");
+    }
+    Ok(message)
+}
+
+fn is_empty(message: &Message) -> bool {
+    let Value {
+        json,
+        string,
+        float,
+        int,
+    } = &message.value;
+    json.is_none() && string.is_none() && float.is_none() && int.is_none()
+}
+
+fn send(node: &mut DoraNode, message: &Message) -> eyre::Result<()> {
+    let Some(input) = &message.input else {
+        bail!("no --input");
+    };
+    let output = DataId::from(input.clone());
+    let outputs = &node.node_config().outputs;
+    if !outputs.contains(&output) {
+        let declared: Vec<_> = outputs.iter().map(|output| output.to_string()).collect();
+        bail!(
+            "node `{}` has no output `{input}` in the dataflow, it has {declared:?}",
+            node.id()
+        );
+    }
+
+    let mut parameters = MetadataParameters::default();
+    for (key, value) in &message.parameters {
+        parameters.insert(key.clone(), Parameter::String(value.clone()));
+    }
+
+    let value = &message.value;
+    if let Some(json) = &value.json {
+        serde_json::from_str::<serde_json::Value>(json).context("invalid JSON")?;
+        node.send_output(output, parameters, StringArray::from(vec![json.as_str()]))?;
+    } else if let Some(string) = &value.string {
+        node.send_output(output, parameters, StringArray::from(vec![string.as_str()]))?;
+    } else if let Some(float) = value.float {
+        node.send_output(output, parameters, Float64Array::from(vec![float]))?;
+    } else if let Some(int) = value.int {
+        node.send_output(output, parameters, Int64Array::from(vec![int]))?;
+    } else {
+        bail!("no value to send on `{input}`");
+    }
+    println!("sent on `{input}`");
+    Ok(())
+}
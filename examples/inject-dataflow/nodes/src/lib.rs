@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// A command on the `cmd` input of the controller, e.g. `{"gain": 0.5}`.
+///
+/// All fields are optional, so one command can change several settings. Unknown fields are an
+/// error, so that a typo doesn't go unnoticed.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Command {
+    pub mode: Option<Mode>,
+    pub gain: Option<f64>,
+    #[serde(default)]
+    pub shutdown: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// The output follows `gain * setpoint`.
+    Auto,
+    /// The output keeps its last value.
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControllerState {
+    pub mode: Mode,
+    pub gain: f64,
+    pub setpoint: f64,
+    pub output: f64,
+}
+
+impl Default for ControllerState {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Auto,
+            gain: 1.0,
+            setpoint: 0.0,
+            output: 0.0,
+        }
+    }
+}
+
+/// A message that the controller received from `dora-inject`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Received {
+    pub input: String,
+    /// The value as text, e.g. the JSON of a command.
+    pub value: String,
+    /// The `sender` metadata parameter, if set, e.g. with `--meta sender=me`.
+    pub sender: Option<String>,
+    /// Why the controller rejected the message, if it did.
+    pub rejected: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ControllerReport {
+    pub received: Vec<Received>,
+    pub state: ControllerState,
+}
@@ -0,0 +1,79 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter,
+    arrow::{
+        array::{AsArray, Float32Array},
+        datatypes::Float32Type,
+    },
+    dora_core::config::DataId,
+};
+use example_node_utils::env_or;
+use eyre::{Context, OptionExt, bail};
+use ort::{
+    session::{Session, builder::GraphOptimizationLevel},
+    value::Tensor,
+};
+use std::time::Instant;
+
+/// The values per reading, one per channel of the pump.
+const CHANNELS: usize = 4;
+
+/// Scores every reading of `readings` with the ONNX model `MODEL`, and sends the probability
+/// that the pump misbehaves on `predictions`, as a `Float32` array of one value.
+///
+/// The metadata of the reading is passed on, with `anomaly` set when the score reached
+/// `THRESHOLD`, and the time that the model took in `inference_us`. The model takes a
+/// `reading` of shape `[N, 4]` and returns a `score` of shape `[N, 1]`, see
+/// `model/make_model.py`.
+fn main() -> eyre::Result<()> {
+    let model = env_or("MODEL", "models/anomaly.onnx".to_owned())?;
+    let threshold: f32 = env_or("THRESHOLD", 0.5)?;
+    let intra_threads: usize = env_or("INTRA_THREADS", 1)?;
+    let output = DataId::from("predictions".to_owned());
+
+    let mut session = Session::builder()?
+        .with_optimization_level(GraphOptimizationLevel::Level3)?
+        .with_intra_threads(intra_threads)?
+        .commit_from_file(&model)
+        .with_context(|| format!("failed to load the model {model}"))?;
+    println!("loaded {model}");
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let mut predictions = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "readings" => {
+                    let values = data
+                        .as_primitive_opt::<Float32Type>()
+                        .ok_or_eyre("expected a Float32 array")?;
+                    if values.len() != CHANNELS {
+                        bail!("expected {CHANNELS} values, got {}", values.len());
+                    }
+                    let reading = Tensor::from_array(([1, CHANNELS], values.values().to_vec()))?;
+
+                    let start = Instant::now();
+                    let outputs = session.run(ort::inputs!["reading" => reading])?;
+                    let (_, scores) = outputs["score"].try_extract_tensor::<f32>()?;
+                    let inference_us = start.elapsed().as_micros();
+                    let score = scores[0];
+
+                    let mut parameters = metadata.parameters;
+                    parameters.insert("anomaly".into(), Parameter::Bool(score >= threshold));
+                    parameters.insert(
+                        "inference_us".into(),
+                        Parameter::Integer(inference_us as i64),
+                    );
+                    node.send_output(output.clone(), parameters, Float32Array::from(vec![score]))?;
+                    predictions += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("scored {predictions} readings");
+    Ok(())
+}
@@ -0,0 +1,471 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, RosDistro, read_json};
+use eyre::{Context, OptionExt, bail};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{
+    process::{Child, Command},
+    time::{Instant, sleep},
+};
+
+/// What `sensor-gateway` publishes, see `nodes/src/sensor_gateway.rs`.
+const SAMPLES: u64 = 600;
+const FAULT_START: u64 = 300;
+const FAULT_LEN: u64 = 120;
+/// The listen endpoint in `config/gateway.json5`.
+const GATEWAY_ADDRESS: &str = "127.0.0.1:7463";
+/// Must match the `MODEL` of `inference` in `dataflow.yml`.
+const MODEL: &str = "models/anomaly.onnx";
+/// Must match the `DASHBOARD_ADDR` of `dashboard` in `dataflow.yml`.
+const DASHBOARD_ADDR: &str = "127.0.0.1:8095";
+
+/// The optional parts of the dataflow. The core, zenoh ingress, recording, and health
+/// monitoring, always runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    Onnx,
+    Ros2,
+    Dashboard,
+}
+
+impl Stage {
+    const ALL: [Stage; 3] = [Stage::Onnx, Stage::Ros2, Stage::Dashboard];
+
+    fn name(self) -> &'static str {
+        match self {
+            Stage::Onnx => "onnx",
+            Stage::Ros2 => "ros2",
+            Stage::Dashboard => "dashboard",
+        }
+    }
+
+    /// The nodes of `dataflow.yml` that only run with this stage.
+    fn node(self) -> &'static str {
+        match self {
+            Stage::Onnx => "inference",
+            Stage::Ros2 => "ros2-egress",
+            Stage::Dashboard => "dashboard",
+        }
+    }
+}
+
+/// Subset of `Health` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct Health {
+    streams: BTreeMap<String, StreamHealth>,
+    anomalies: u64,
+}
+
+/// Subset of `StreamHealth` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct StreamHealth {
+    status: String,
+    messages: u64,
+    max_latency_ms: u64,
+    stale_events: u64,
+}
+
+/// Subset of `RecorderReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct RecorderReport {
+    readings: u64,
+    predictions: u64,
+}
+
+/// Subset of `EgressReport` in `ros2-egress/src/main.rs`.
+#[derive(Debug, Deserialize)]
+struct EgressReport {
+    published: u64,
+    anomalies: u64,
+}
+
+/// Subset of `Status` in `nodes/src/dashboard.rs`.
+#[derive(Debug, Deserialize)]
+struct DashboardStatus {
+    health: Option<serde_json::Value>,
+    reading: Option<serde_json::Value>,
+    predictions: Vec<serde_json::Value>,
+}
+
+/// Runs as much of the dataflow as this machine supports.
+///
+/// Usage: `cargo run --example kitchen-sink-dataflow [-- [--skip <stage>]... [--require-all]]`
+///
+/// Every stage whose requirements are missing is left out, with its nodes and the inputs
+/// that they fed, see `Stage`. `--skip` leaves a stage out on purpose, and `--require-all`
+/// fails instead of leaving a stage out, e.g. in CI machines that are set up for all of them.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("kitchen-sink-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let mut skipped = BTreeSet::new();
+    let mut require_all = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--skip" => {
+                let name = args.next().ok_or_eyre("missing stage after `--skip`")?;
+                let stage = Stage::ALL
+                    .into_iter()
+                    .find(|stage| stage.name() == name)
+                    .ok_or_else(|| eyre::eyre!("unknown stage `{name}`"))?;
+                skipped.insert(stage);
+            }
+            "--require-all" => require_all = true,
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    // which stages run, and why the others don't
+    let mut missing = BTreeMap::new();
+    let mut ros = None;
+    for stage in Stage::ALL {
+        if skipped.contains(&stage) {
+            missing.insert(stage, "skipped with `--skip`".to_owned());
+            continue;
+        }
+        let probe = match stage {
+            Stage::Onnx => probe_onnx(),
+            Stage::Ros2 => probe_ros2().await.map(|distro| {
+                ros = Some(distro);
+            }),
+            Stage::Dashboard => probe_dashboard(),
+        };
+        if let Err(reason) = probe {
+            if require_all {
+                bail!("the {} stage can't run: {reason:#}", stage.name());
+            }
+            missing.insert(stage, format!("{reason:#}"));
+        }
+    }
+    // `ros2-egress` publishes the predictions
+    if missing.contains_key(&Stage::Onnx) && !missing.contains_key(&Stage::Ros2) {
+        if require_all {
+            bail!("the ros2 stage can't run without the onnx stage");
+        }
+        missing.insert(Stage::Ros2, "needs the onnx stage".to_owned());
+        ros = None;
+    }
+    let enabled = |stage: Stage| !missing.contains_key(&stage);
+    for (stage, reason) in &missing {
+        println!("leaving out the {} stage: {reason}", stage.name());
+    }
+
+    if enabled(Stage::Onnx) && !Path::new(MODEL).exists() {
+        generate_model().await?;
+    }
+    let removed: Vec<_> = missing.keys().map(|stage| stage.node()).collect();
+    let dataflow = write_staged_dataflow(&removed)?;
+
+    let mut dora = Dora::from_env()?;
+    if let Some(ros) = &ros {
+        // the build command of `ros2-egress` sources `ROS`
+        dora = dora.env("ROS", ros.setup_script());
+    }
+    dora.build_dataflow(&dataflow).await?;
+
+    // the sensors start publishing once `zenoh-ingress` is up
+    let mut gateway = start_gateway().await?;
+    let dataflow_task = tokio::spawn(dora.run_dataflow(&dataflow));
+    let (dashboard, ros2) = tokio::join!(
+        async {
+            if enabled(Stage::Dashboard) {
+                check_dashboard(enabled(Stage::Onnx)).await
+            } else {
+                Ok(())
+            }
+        },
+        async {
+            match &ros {
+                Some(ros) => check_ros2_topic(ros).await,
+                None => Ok(()),
+            }
+        },
+    );
+    dataflow_task.await??;
+    if !gateway.wait().await?.success() {
+        bail!("sensor gateway failed");
+    }
+    dashboard?;
+    ros2?;
+
+    // the recording is complete, and the model found the fault
+    let mut verify = Command::new("nodes/target/release/verify-recording");
+    verify
+        .arg("out/recording")
+        .args(["--samples", &SAMPLES.to_string()])
+        .args(["--fault-start", &FAULT_START.to_string()])
+        .args(["--fault-len", &FAULT_LEN.to_string()]);
+    if enabled(Stage::Onnx) {
+        verify.arg("--predictions");
+    }
+    if !verify.status().await?.success() {
+        bail!("recording verification failed");
+    }
+    let recorder: RecorderReport = read_json("out/recorder.json")?;
+    let predictions = if enabled(Stage::Onnx) { SAMPLES } else { 0 };
+    if recorder.readings != SAMPLES || recorder.predictions != predictions {
+        bail!(
+            "expected {SAMPLES} readings and {predictions} predictions to be recorded, got {} \
+             and {}",
+            recorder.readings,
+            recorder.predictions
+        );
+    }
+
+    // the health node saw exactly the streams of the enabled stages, until they closed
+    let health: Health = read_json("out/health.json")?;
+    let mut expected = BTreeMap::from([("readings", Some(SAMPLES)), ("recorded", None)]);
+    if enabled(Stage::Onnx) {
+        expected.insert("predictions", Some(SAMPLES));
+    }
+    if enabled(Stage::Ros2) {
+        expected.insert("published", Some(SAMPLES));
+    }
+    if !health
+        .streams
+        .keys()
+        .map(String::as_str)
+        .eq(expected.keys().copied())
+    {
+        bail!(
+            "the health node should monitor {:?}, it monitored {:?}",
+            expected.keys(),
+            health.streams.keys()
+        );
+    }
+    for (id, stream) in &health.streams {
+        println!(
+            "{id:<12} {:>6} messages, max latency {:>4} ms, stale {} times",
+            stream.messages, stream.max_latency_ms, stream.stale_events
+        );
+        if stream.status != "closed" {
+            bail!(
+                "`{id}` should be closed at the end, it is {}",
+                stream.status
+            );
+        }
+        match expected[id.as_str()] {
+            Some(messages) if stream.messages != messages => {
+                bail!(
+                    "expected {messages} messages on `{id}`, got {}",
+                    stream.messages
+                )
+            }
+            None if stream.messages == 0 => bail!("no messages on `{id}`"),
+            _ => {}
+        }
+    }
+    if enabled(Stage::Onnx) && health.anomalies == 0 {
+        bail!("the health node counted no anomalies");
+    }
+
+    if enabled(Stage::Ros2) {
+        let egress: EgressReport = read_json("out/ros2-egress.json")?;
+        if egress.published != SAMPLES || egress.anomalies != health.anomalies {
+            bail!(
+                "expected {SAMPLES} predictions with {} anomalies to be published to ROS2, got \
+                 {} with {}",
+                health.anomalies,
+                egress.published,
+                egress.anomalies
+            );
+        }
+    }
+
+    for stage in Stage::ALL {
+        match missing.get(&stage) {
+            None => println!("{:<10} ran", stage.name()),
+            Some(reason) => println!("{:<10} left out: {reason}", stage.name()),
+        }
+    }
+    println!("Everything Done");
+    Ok(())
+}
+
+/// The model is generated if it's missing, which needs `uv`.
+fn probe_onnx() -> eyre::Result<()> {
+    if Path::new(MODEL).exists() {
+        return Ok(());
+    }
+    which::which("uv").context(
+        "no `uv` to generate the model, install it using: \
+         https://docs.astral.sh/uv/getting-started/installation/",
+    )?;
+    Ok(())
+}
+
+/// A ROS 2 installation with `std_msgs`, which `ros2-egress` publishes.
+async fn probe_ros2() -> eyre::Result<RosDistro> {
+    let ros = RosDistro::detect()?;
+    if !ros.has_package("std_msgs").await? {
+        bail!("ROS 2 {} has no `std_msgs`", ros.name());
+    }
+    Ok(ros)
+}
+
+/// A free port for the dashboard, and `curl` to check it.
+fn probe_dashboard() -> eyre::Result<()> {
+    which::which("curl").context("no `curl` to check the dashboard")?;
+    if port_check::is_port_reachable(DASHBOARD_ADDR) {
+        bail!("{DASHBOARD_ADDR} is already in use");
+    }
+    Ok(())
+}
+
+async fn generate_model() -> eyre::Result<()> {
+    let status = Command::new("uv")
+        .args(["run", "--no-project", "--with", "onnx>=1.16"])
+        .args(["model/make_model.py", MODEL])
+        .status()
+        .await
+        .context("failed to run uv")?;
+    if !status.success() {
+        bail!("failed to generate {MODEL}");
+    }
+    Ok(())
+}
+
+/// Writes `dataflow.staged.yml`, which is `dataflow.yml` without the nodes `removed`.
+///
+/// Inputs from removed nodes are removed too, and so are the nodes that are left without any
+/// input, e.g. `ros2-egress` without `inference`. It's written next to `dataflow.yml`, since
+/// dora resolves node paths relative to the dataflow file.
+fn write_staged_dataflow(removed: &[&str]) -> eyre::Result<PathBuf> {
+    let mut dataflow: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string("dataflow.yml")?)?;
+    let nodes = dataflow["nodes"]
+        .as_sequence_mut()
+        .ok_or_eyre("dataflow.yml has no nodes")?;
+    let mut removed: BTreeSet<String> = removed.iter().map(|id| id.to_string()).collect();
+    loop {
+        nodes.retain(|node| !removed.contains(node["id"].as_str().unwrap_or_default()));
+        let mut orphaned = Vec::new();
+        for node in nodes.iter_mut() {
+            let id = node["id"].as_str().unwrap_or_default().to_owned();
+            let Some(inputs) = node["inputs"].as_mapping_mut() else {
+                continue;
+            };
+            let before = inputs.len();
+            inputs.retain(|_, input| {
+                // `<node>/<output>`, or a mapping with a `source` of that form
+                let source = input.as_str().or_else(|| input["source"].as_str());
+                let sender = source.and_then(|source| source.split_once('/'));
+                !sender.is_some_and(|(sender, _)| removed.contains(sender))
+            });
+            if before > 0 && inputs.is_empty() {
+                orphaned.push(id);
+            }
+        }
+        if orphaned.is_empty() {
+            break;
+        }
+        removed.extend(orphaned);
+    }
+
+    let path = PathBuf::from("dataflow.staged.yml");
+    std::fs::write(&path, serde_yaml::to_string(&dataflow)?)?;
+    Ok(path)
+}
+
+async fn start_gateway() -> eyre::Result<Child> {
+    let gateway = Command::new("nodes/target/release/sensor-gateway")
+        .args(["--config", "config/gateway.json5"])
+        .args(["--samples", &SAMPLES.to_string()])
+        .args(["--fault-start", &FAULT_START.to_string()])
+        .args(["--fault-len", &FAULT_LEN.to_string()])
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start sensor gateway")?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !port_check::is_port_reachable(GATEWAY_ADDRESS) {
+        if Instant::now() > deadline {
+            bail!("the sensor gateway is not listening on {GATEWAY_ADDRESS}");
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    Ok(gateway)
+}
+
+/// The page is served, and the status shows the data of the running dataflow.
+async fn check_dashboard(predictions: bool) -> eyre::Result<()> {
+    let deadline = Instant::now() + Duration::from_secs(60);
+    loop {
+        if port_check::is_port_reachable(DASHBOARD_ADDR) {
+            let (status, body) = curl(&format!("http://{DASHBOARD_ADDR}/api/status")).await?;
+            if status == 200 {
+                let current: DashboardStatus = serde_json::from_str(&body)?;
+                if current.health.is_some()
+                    && current.reading.is_some()
+                    && (!predictions || !current.predictions.is_empty())
+                {
+                    break;
+                }
+            }
+        }
+        if Instant::now() > deadline {
+            bail!("the dashboard on {DASHBOARD_ADDR} never showed live data");
+        }
+        sleep(Duration::from_millis(250)).await;
+    }
+    let (status, body) = curl(&format!("http://{DASHBOARD_ADDR}/")).await?;
+    if status != 200 || !body.contains("/api/status") {
+        bail!("GET / should serve the dashboard page, got {status}");
+    }
+    println!("the dashboard on http://{DASHBOARD_ADDR}/ shows live data");
+    Ok(())
+}
+
+/// ROS 2 tools see the scores that `ros2-egress` publishes.
+async fn check_ros2_topic(ros: &RosDistro) -> eyre::Result<()> {
+    let echo = ros
+        .command("ros2 topic echo --once /kitchen_sink/anomaly_score std_msgs/msg/Float32")
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(Duration::from_secs(60), echo)
+        .await
+        .context("no message on /kitchen_sink/anomaly_score within 60 s")??;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() || !stdout.contains("data:") {
+        bail!("`ros2 topic echo` failed: {stdout}");
+    }
+    println!("ROS 2 received {}", stdout.trim().replace('\n', " "));
+    Ok(())
+}
+
+async fn curl(url: &str) -> eyre::Result<(u16, String)> {
+    let output = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--write-out",
+            "\n%{http_code}",
+            url,
+        ])
+        .output()
+        .await
+        .context("failed to run curl")?;
+    if !output.status.success() {
+        bail!("curl failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+    let Some((body, status)) = stdout.rsplit_once('\n') else {
+        bail!("unexpected output of curl: {stdout}");
+    };
+    Ok((status.trim().parse()?, body.to_owned()))
+}
@@ -0,0 +1,61 @@
+"""Writes the anomaly model of the `inference` node as ONNX.
+
+Usage: uv run --no-project --with "onnx>=1.16" model/make_model.py <output.onnx>
+
+A logistic regression over the readings of a pump: each channel is normalized with the mean and
+standard deviation of a healthy pump, weighted, and squashed into a score between 0 and 1. The
+weights follow what the simulated bearing fault of `sensor-gateway` does: it vibrates more,
+heats up, draws more current, and loses pressure. Healthy readings score far below 0.5, a fully
+developed fault far above.
+"""
+
+import os
+import sys
+
+import numpy as np
+import onnx
+from onnx import TensorProto, helper, numpy_helper
+
+# must match `NOMINAL_MEAN` and `NOMINAL_STD` in `nodes/src/sensor_gateway.rs`
+MEAN = [2.0, 45.0, 12.0, 3.0]
+STD = [0.3, 1.0, 0.5, 0.1]
+WEIGHTS = [1.2, 0.8, 0.3, -0.6]
+BIAS = -8.0
+
+
+def make_model() -> onnx.ModelProto:
+    initializers = [
+        numpy_helper.from_array(np.array(MEAN, dtype=np.float32), "mean"),
+        numpy_helper.from_array(np.array(STD, dtype=np.float32), "std"),
+        numpy_helper.from_array(
+            np.array(WEIGHTS, dtype=np.float32).reshape(4, 1), "weights"
+        ),
+        numpy_helper.from_array(np.array([BIAS], dtype=np.float32), "bias"),
+    ]
+    nodes = [
+        helper.make_node("Sub", ["reading", "mean"], ["centered"]),
+        helper.make_node("Div", ["centered", "std"], ["normalized"]),
+        helper.make_node("MatMul", ["normalized", "weights"], ["weighted"]),
+        helper.make_node("Add", ["weighted", "bias"], ["logit"]),
+        helper.make_node("Sigmoid", ["logit"], ["score"]),
+    ]
+    graph = helper.make_graph(
+        nodes,
+        "pump-anomaly",
+        [helper.make_tensor_value_info("reading", TensorProto.FLOAT, ["N", 4])],
+        [helper.make_tensor_value_info("score", TensorProto.FLOAT, ["N", 1])],
+        initializers,
+    )
+    # the opset and IR version that the onnxruntime of `ort` supports
+    model = helper.make_model(graph, opset_imports=[helper.make_opsetid("", 17)])
+    model.ir_version = 8
+    onnx.checker.check_model(model)
+    return model
+
+
+if __name__ == "__main__":
+    if len(sys.argv) != 2:
+        sys.exit(__doc__)
+    os.makedirs(os.path.dirname(sys.argv[1]) or ".", exist_ok=True)
+    onnx.save(make_model(), sys.argv[1])
+    print(f"wrote {sys.argv[1]}")
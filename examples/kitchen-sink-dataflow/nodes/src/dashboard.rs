@@ -0,0 +1,100 @@
+use axum::{Json, Router, extract::State, response::Html, routing::get};
+use dora_node_api::{DoraNode, Event, arrow::array::AsArray};
+use eyre::{Context, OptionExt};
+use kitchen_sink_dataflow_nodes::{Health, Prediction, Reading, env_or};
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+const INDEX: &str = include_str!("../../web/index.html");
+
+/// The predictions that the page plots.
+const RECENT_PREDICTIONS: usize = 250;
+
+/// What `GET /api/status` returns.
+#[derive(Debug, Default, Clone, Serialize)]
+struct Status {
+    /// The last report of the health node.
+    health: Option<Health>,
+    reading: Option<Reading>,
+    /// The last `RECENT_PREDICTIONS` predictions, oldest first.
+    predictions: VecDeque<Prediction>,
+    anomalies: u64,
+    last_anomaly: Option<Prediction>,
+}
+
+type Shared = Arc<Mutex<Status>>;
+
+/// Serves a live view of the dataflow on `DASHBOARD_ADDR`: a page on `GET /` that polls
+/// `GET /api/status`, which returns the last `health` report, the last reading, and the recent
+/// predictions with the anomalies among them.
+///
+/// Works without `predictions` too, when the dataflow runs without the inference node. Stops
+/// once all of its inputs are closed.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let addr: String = env_or("DASHBOARD_ADDR", "127.0.0.1:8095".to_owned())?;
+
+    let status = Shared::default();
+    let app = Router::new()
+        .route("/", get(page))
+        .route("/api/status", get(api_status))
+        .with_state(status.clone());
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to listen on {addr}"))?;
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            eprintln!("HTTP server failed: {err}");
+        }
+    });
+    println!("serving the dashboard on http://{addr}/");
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+    while let Some(event) = events.recv_async().await {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "health" => {
+                    let json = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a string array")?
+                        .value(0);
+                    let health: Health =
+                        serde_json::from_str(json).context("invalid health report")?;
+                    status.lock().unwrap().health = Some(health);
+                }
+                "readings" => {
+                    let reading = Reading::from_input(&metadata, &data.0)?;
+                    status.lock().unwrap().reading = Some(reading);
+                }
+                "predictions" => {
+                    let prediction = Prediction::from_input(&metadata, &data.0)?;
+                    let mut status = status.lock().unwrap();
+                    if prediction.anomaly {
+                        status.anomalies += 1;
+                        status.last_anomaly = Some(prediction.clone());
+                    }
+                    if status.predictions.len() == RECENT_PREDICTIONS {
+                        status.predictions.pop_front();
+                    }
+                    status.predictions.push_back(prediction);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
+
+async fn page() -> Html<&'static str> {
+    Html(INDEX)
+}
+
+async fn api_status(State(status): State<Shared>) -> Json<Status> {
+    Json(status.lock().unwrap().clone())
+}
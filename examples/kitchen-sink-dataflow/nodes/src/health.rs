@@ -0,0 +1,178 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::StringArray, dora_core::config::DataId};
+use kitchen_sink_dataflow_nodes::{
+    ANOMALY_KEY, Health, SEQ_KEY, StreamHealth, StreamStatus, TIMESTAMP_KEY, bool_parameter,
+    env_or, integer_parameter, now_ms, write_json,
+};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// The input that triggers a health report, every other input is a monitored stream.
+const TICK_INPUT: &str = "tick";
+
+/// Monitors every stream of the dataflow that it receives as an input, and sends a `Health`
+/// report as JSON on `health` on every `tick`.
+///
+/// A stream is `waiting` until its first message, `ok` while messages arrive, `stale` when the
+/// last one is older than `STALE_MS`, and `closed` once its sender finished. For messages with
+/// a `seq` and a sensor `timestamp_ms` in their metadata, it also tracks the last sequence
+/// number and the latency since the sensor. Counts the predictions that were flagged as
+/// anomalies.
+///
+/// Stops once all monitored streams are closed, and writes the last report to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let stale_after = Duration::from_millis(env_or("STALE_MS", 1000)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/health.json".to_owned())?.into();
+    let output = DataId::from("health".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    // the streams that the dataflow connected, the tick timer never closes
+    let mut streams: BTreeMap<String, Stream> = node
+        .node_config()
+        .inputs
+        .keys()
+        .filter(|id| id.as_str() != TICK_INPUT)
+        .map(|id| (id.to_string(), Stream::default()))
+        .collect();
+    let mut open = streams.len();
+    println!(
+        "monitoring {}",
+        streams.keys().cloned().collect::<Vec<_>>().join(", ")
+    );
+
+    let mut anomalies = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, .. } => {
+                if id.as_str() == TICK_INPUT {
+                    let health = report(&mut streams, anomalies, stale_after);
+                    let json = serde_json::to_string(&health)?;
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        StringArray::from(vec![json]),
+                    )?;
+                    continue;
+                }
+                let Some(stream) = streams.get_mut(id.as_str()) else {
+                    eprintln!("Ignoring unexpected input `{id}`");
+                    continue;
+                };
+                let parameters = &metadata.parameters;
+                stream.received(
+                    integer_parameter(parameters, SEQ_KEY).ok(),
+                    integer_parameter(parameters, TIMESTAMP_KEY).ok(),
+                );
+                if id.as_str() == "predictions" && bool_parameter(parameters, ANOMALY_KEY)? {
+                    anomalies += 1;
+                }
+            }
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if let Some(stream) = streams.get_mut(id.as_str()) {
+                    stream.health.status = StreamStatus::Closed;
+                    open -= 1;
+                    if open == 0 {
+                        break;
+                    }
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let health = report(&mut streams, anomalies, stale_after);
+    for (id, stream) in &health.streams {
+        println!(
+            "{id}: {:?}, {} messages, max latency {} ms, stale {} times",
+            stream.status, stream.messages, stream.max_latency_ms, stream.stale_events
+        );
+    }
+    write_json(&report_file, &health)
+}
+
+struct Stream {
+    health: StreamHealth,
+    last_at: Option<Instant>,
+    /// The arrival times of the messages of the last second.
+    recent: VecDeque<Instant>,
+}
+
+impl Default for Stream {
+    fn default() -> Self {
+        Self {
+            health: StreamHealth {
+                status: StreamStatus::Waiting,
+                messages: 0,
+                rate_hz: 0.0,
+                age_ms: None,
+                last_seq: None,
+                latency_ms: None,
+                max_latency_ms: 0,
+                stale_events: 0,
+            },
+            last_at: None,
+            recent: VecDeque::new(),
+        }
+    }
+}
+
+impl Stream {
+    fn received(&mut self, seq: Option<i64>, timestamp_ms: Option<i64>) {
+        let now = Instant::now();
+        self.health.messages += 1;
+        self.last_at = Some(now);
+        self.recent.push_back(now);
+        if let Some(seq) = seq {
+            self.health.last_seq = Some(seq as u64);
+        }
+        if let Some(timestamp_ms) = timestamp_ms {
+            let latency = now_ms().saturating_sub(timestamp_ms as u64);
+            self.health.latency_ms = Some(latency);
+            self.health.max_latency_ms = self.health.max_latency_ms.max(latency);
+        }
+    }
+
+    fn update(&mut self, stale_after: Duration) {
+        let now = Instant::now();
+        while self
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > Duration::from_secs(1))
+        {
+            self.recent.pop_front();
+        }
+        self.health.rate_hz = self.recent.len() as f64;
+        let Some(last_at) = self.last_at else {
+            return;
+        };
+        let age = now.duration_since(last_at);
+        self.health.age_ms = Some(age.as_millis() as u64);
+        self.health.status = match self.health.status {
+            StreamStatus::Closed => StreamStatus::Closed,
+            StreamStatus::Stale if age > stale_after => StreamStatus::Stale,
+            _ if age > stale_after => {
+                self.health.stale_events += 1;
+                StreamStatus::Stale
+            }
+            _ => StreamStatus::Ok,
+        };
+    }
+}
+
+fn report(streams: &mut BTreeMap<String, Stream>, anomalies: u64, stale_after: Duration) -> Health {
+    Health {
+        at_ms: now_ms(),
+        streams: streams
+            .iter_mut()
+            .map(|(id, stream)| {
+                stream.update(stale_after);
+                (id.clone(), stream.health.clone())
+            })
+            .collect(),
+        anomalies,
+    }
+}
@@ -0,0 +1,360 @@
+use dora_node_api::{
+    Metadata, MetadataParameters, Parameter,
+    arrow::{
+        array::{Array, ArrayRef, AsArray, BooleanArray, Float32Array, StringArray, UInt64Array},
+        datatypes::{DataType, Field, Float32Type, Schema, UInt64Type},
+        record_batch::RecordBatch,
+    },
+};
+use eyre::{Context, OptionExt, bail, eyre};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use zenoh::{Config, Session, Wait};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// The measured channels of a pump, in the order of the values of a reading.
+pub const CHANNELS: [&str; 4] = [
+    "vibration_mm_s",
+    "temperature_c",
+    "current_a",
+    "pressure_bar",
+];
+
+/// The readings over which a fault of the simulated pump develops, during which the model may
+/// not flag it yet.
+pub const FAULT_RAMP: u64 = 25;
+
+/// The zenoh key of the liveliness token of `zenoh-ingress`. The sensor gateway starts to
+/// publish once it appears, so that no reading is published before anyone subscribed.
+pub const INGRESS_TOKEN: &str = "kitchen-sink/ingress";
+
+/// Metadata keys of the `readings` of `zenoh-ingress` and the `predictions` of `inference`.
+pub const SEQ_KEY: &str = "seq";
+/// Wall time at the sensor, in milliseconds since the Unix epoch.
+pub const TIMESTAMP_KEY: &str = "timestamp_ms";
+pub const SENSOR_KEY: &str = "sensor";
+pub const ANOMALY_KEY: &str = "anomaly";
+pub const INFERENCE_US_KEY: &str = "inference_us";
+
+/// Published by `sensor-gateway` as JSON on `sensors/<sensor>`, one message per reading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorMessage {
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    /// The values of the `CHANNELS`.
+    pub values: [f32; CHANNELS.len()],
+    /// Set on the last reading, after which `zenoh-ingress` stops.
+    #[serde(default)]
+    pub last: bool,
+}
+
+/// A reading of a sensor, as sent on `readings` and recorded in `readings.parquet`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reading {
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    pub sensor: String,
+    pub values: [f32; CHANNELS.len()],
+}
+
+impl Reading {
+    /// Decodes a `readings` input: the values as a `Float32` array, the rest in the metadata.
+    pub fn from_input(metadata: &Metadata, data: &dyn Array) -> eyre::Result<Self> {
+        let values = data
+            .as_primitive_opt::<Float32Type>()
+            .ok_or_eyre("expected a Float32 array")?
+            .values();
+        Ok(Self {
+            seq: integer_parameter(&metadata.parameters, SEQ_KEY)? as u64,
+            timestamp_ms: integer_parameter(&metadata.parameters, TIMESTAMP_KEY)? as u64,
+            sensor: string_parameter(&metadata.parameters, SENSOR_KEY)?.to_owned(),
+            values: values.as_ref().try_into().map_err(|_| {
+                eyre!(
+                    "expected {} values, one per channel, got {}",
+                    CHANNELS.len(),
+                    values.len()
+                )
+            })?,
+        })
+    }
+}
+
+/// The anomaly score of a reading, as sent on `predictions` and recorded in
+/// `predictions.parquet`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Prediction {
+    /// The `seq` of the reading.
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    /// The probability that the pump misbehaves, between 0 and 1.
+    pub score: f32,
+    /// Whether the score reached the threshold of the inference node.
+    pub anomaly: bool,
+    pub inference_us: u64,
+}
+
+impl Prediction {
+    /// Decodes a `predictions` input: the score as a `Float32` array of one value, the rest in
+    /// the metadata.
+    pub fn from_input(metadata: &Metadata, data: &dyn Array) -> eyre::Result<Self> {
+        let score = data
+            .as_primitive_opt::<Float32Type>()
+            .ok_or_eyre("expected a Float32 array")?;
+        if score.len() != 1 {
+            bail!("expected one score, got {}", score.len());
+        }
+        Ok(Self {
+            seq: integer_parameter(&metadata.parameters, SEQ_KEY)? as u64,
+            timestamp_ms: integer_parameter(&metadata.parameters, TIMESTAMP_KEY)? as u64,
+            score: score.value(0),
+            anomaly: bool_parameter(&metadata.parameters, ANOMALY_KEY)?,
+            inference_us: integer_parameter(&metadata.parameters, INFERENCE_US_KEY)? as u64,
+        })
+    }
+}
+
+pub fn readings_schema() -> Arc<Schema> {
+    let mut fields = vec![
+        Field::new("seq", DataType::UInt64, false),
+        Field::new("timestamp_ms", DataType::UInt64, false),
+        Field::new("sensor", DataType::Utf8, false),
+    ];
+    fields.extend(
+        CHANNELS
+            .into_iter()
+            .map(|channel| Field::new(channel, DataType::Float32, false)),
+    );
+    Arc::new(Schema::new(fields))
+}
+
+pub fn predictions_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("seq", DataType::UInt64, false),
+        Field::new("timestamp_ms", DataType::UInt64, false),
+        Field::new("score", DataType::Float32, false),
+        Field::new("anomaly", DataType::Boolean, false),
+        Field::new("inference_us", DataType::UInt64, false),
+    ]))
+}
+
+/// One row per reading, with one `Float32` column per channel.
+pub fn readings_to_batch(readings: &[Reading]) -> eyre::Result<RecordBatch> {
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(readings.iter().map(|r| r.seq).collect::<UInt64Array>()),
+        Arc::new(
+            readings
+                .iter()
+                .map(|r| r.timestamp_ms)
+                .collect::<UInt64Array>(),
+        ),
+        Arc::new(
+            readings
+                .iter()
+                .map(|r| Some(r.sensor.as_str()))
+                .collect::<StringArray>(),
+        ),
+    ];
+    for i in 0..CHANNELS.len() {
+        columns.push(Arc::new(
+            readings
+                .iter()
+                .map(|r| r.values[i])
+                .collect::<Float32Array>(),
+        ));
+    }
+    RecordBatch::try_new(readings_schema(), columns).context("failed to encode readings")
+}
+
+pub fn readings_from_batch(batch: &RecordBatch) -> eyre::Result<Vec<Reading>> {
+    let column = |name: &str| {
+        batch
+            .column_by_name(name)
+            .ok_or_else(|| eyre!("missing column `{name}`"))
+    };
+    let seq = column("seq")?
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or_eyre("`seq` is not a UInt64 array")?;
+    let timestamps = column("timestamp_ms")?
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or_eyre("`timestamp_ms` is not a UInt64 array")?;
+    let sensors = column("sensor")?
+        .as_string_opt::<i32>()
+        .ok_or_eyre("`sensor` is not a Utf8 array")?;
+    let channels = CHANNELS
+        .into_iter()
+        .map(|channel| {
+            column(channel)?
+                .as_primitive_opt::<Float32Type>()
+                .ok_or_else(|| eyre!("`{channel}` is not a Float32 array"))
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    Ok((0..batch.num_rows())
+        .map(|row| Reading {
+            seq: seq.value(row),
+            timestamp_ms: timestamps.value(row),
+            sensor: sensors.value(row).to_owned(),
+            values: std::array::from_fn(|i| channels[i].value(row)),
+        })
+        .collect())
+}
+
+pub fn predictions_to_batch(predictions: &[Prediction]) -> eyre::Result<RecordBatch> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(predictions.iter().map(|p| p.seq).collect::<UInt64Array>()),
+        Arc::new(
+            predictions
+                .iter()
+                .map(|p| p.timestamp_ms)
+                .collect::<UInt64Array>(),
+        ),
+        Arc::new(
+            predictions
+                .iter()
+                .map(|p| p.score)
+                .collect::<Float32Array>(),
+        ),
+        Arc::new(
+            predictions
+                .iter()
+                .map(|p| Some(p.anomaly))
+                .collect::<BooleanArray>(),
+        ),
+        Arc::new(
+            predictions
+                .iter()
+                .map(|p| p.inference_us)
+                .collect::<UInt64Array>(),
+        ),
+    ];
+    RecordBatch::try_new(predictions_schema(), columns).context("failed to encode predictions")
+}
+
+pub fn predictions_from_batch(batch: &RecordBatch) -> eyre::Result<Vec<Prediction>> {
+    let column = |name: &str| {
+        batch
+            .column_by_name(name)
+            .ok_or_else(|| eyre!("missing column `{name}`"))
+    };
+    let seq = column("seq")?
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or_eyre("`seq` is not a UInt64 array")?;
+    let timestamps = column("timestamp_ms")?
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or_eyre("`timestamp_ms` is not a UInt64 array")?;
+    let scores = column("score")?
+        .as_primitive_opt::<Float32Type>()
+        .ok_or_eyre("`score` is not a Float32 array")?;
+    let anomalies = column("anomaly")?
+        .as_boolean_opt()
+        .ok_or_eyre("`anomaly` is not a Boolean array")?;
+    let inference_us = column("inference_us")?
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or_eyre("`inference_us` is not a UInt64 array")?;
+    Ok((0..batch.num_rows())
+        .map(|row| Prediction {
+            seq: seq.value(row),
+            timestamp_ms: timestamps.value(row),
+            score: scores.value(row),
+            anomaly: anomalies.value(row),
+            inference_us: inference_us.value(row),
+        })
+        .collect())
+}
+
+/// The state of an input stream of the health node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamStatus {
+    /// No message yet.
+    Waiting,
+    Ok,
+    /// No message for longer than `STALE_MS`.
+    Stale,
+    /// The sender finished.
+    Closed,
+}
+
+/// What the health node knows about one of its inputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamHealth {
+    pub status: StreamStatus,
+    pub messages: u64,
+    /// Messages per second, over the last second.
+    pub rate_hz: f64,
+    /// Milliseconds since the last message.
+    pub age_ms: Option<u64>,
+    /// The `seq` of the last message, if it had one.
+    pub last_seq: Option<u64>,
+    /// Milliseconds from the sensor to the health node, of the last message that had a
+    /// timestamp.
+    pub latency_ms: Option<u64>,
+    pub max_latency_ms: u64,
+    /// How often the stream went stale.
+    pub stale_events: u64,
+}
+
+/// Sent by `health` as JSON on `health`, and written to its `REPORT_FILE` at the end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Health {
+    pub at_ms: u64,
+    pub streams: BTreeMap<String, StreamHealth>,
+    /// Predictions flagged as anomalies so far.
+    pub anomalies: u64,
+}
+
+/// Written by `recorder` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize)]
+pub struct RecorderReport {
+    pub readings: u64,
+    pub predictions: u64,
+    pub files: Vec<String>,
+}
+
+/// Wall time in milliseconds since the Unix epoch, comparable between processes.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+pub fn open_session(config: &Path) -> eyre::Result<Session> {
+    let config = Config::from_file(config)
+        .map_err(|err| eyre!("failed to load zenoh config {}: {err}", config.display()))?;
+    zenoh::open(config)
+        .wait()
+        .map_err(|err| eyre!("failed to open zenoh session: {err}"))
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
+
+pub fn bool_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<bool> {
+    match parameters.get(key) {
+        Some(Parameter::Bool(value)) => Ok(*value),
+        Some(other) => bail!("expected bool `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
+
+pub fn string_parameter<'a>(
+    parameters: &'a MetadataParameters,
+    key: &str,
+) -> eyre::Result<&'a str> {
+    match parameters.get(key) {
+        Some(Parameter::String(value)) => Ok(value),
+        Some(other) => bail!("expected string `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
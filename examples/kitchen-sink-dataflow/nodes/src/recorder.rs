@@ -0,0 +1,117 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, arrow::datatypes::Schema, dora_core::config::DataId,
+};
+use eyre::Context;
+use kitchen_sink_dataflow_nodes::{
+    Prediction, Reading, RecorderReport, env_or, predictions_schema, predictions_to_batch,
+    readings_schema, readings_to_batch, write_json,
+};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use std::{fs::File, path::PathBuf, sync::Arc};
+
+/// Records the `readings` and `predictions` to `readings.parquet` and `predictions.parquet` in
+/// `OUTPUT_DIR`, compressed with zstd.
+///
+/// A file is only created once its input sent something, so a dataflow without the inference
+/// node records the readings only. Sends the number of rows recorded so far on `recorded`
+/// every `RECORDED_EVERY` rows, for the health node. Writes a `RecorderReport` to
+/// `REPORT_FILE` once the files are closed.
+fn main() -> eyre::Result<()> {
+    let output_dir: PathBuf = env_or("OUTPUT_DIR", "out/recording".to_owned())?.into();
+    let row_group_rows: usize = env_or("ROW_GROUP_ROWS", 200)?;
+    let recorded_every: u64 = env_or("RECORDED_EVERY", 25)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/recorder.json".to_owned())?.into();
+    let output = DataId::from("recorded".to_owned());
+
+    std::fs::create_dir_all(&output_dir)
+        .with_context(|| format!("failed to create {}", output_dir.display()))?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .set_max_row_group_size(row_group_rows)
+        .build();
+    let create = |name: &str, schema: Arc<Schema>| -> eyre::Result<ArrowWriter<File>> {
+        let path = output_dir.join(name);
+        let file =
+            File::create(&path).with_context(|| format!("failed to create {}", path.display()))?;
+        Ok(ArrowWriter::try_new(
+            file,
+            schema,
+            Some(properties.clone()),
+        )?)
+    };
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut readings: Option<ArrowWriter<File>> = None;
+    let mut predictions: Option<ArrowWriter<File>> = None;
+    let mut report = RecorderReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                match id.as_str() {
+                    "readings" => {
+                        let reading = Reading::from_input(&metadata, &data.0)?;
+                        let writer = match readings.take() {
+                            Some(writer) => writer,
+                            None => create("readings.parquet", readings_schema())?,
+                        };
+                        let writer = readings.insert(writer);
+                        writer
+                            .write(&readings_to_batch(&[reading])?)
+                            .context("failed to write reading")?;
+                        report.readings += 1;
+                    }
+                    "predictions" => {
+                        let prediction = Prediction::from_input(&metadata, &data.0)?;
+                        let writer = match predictions.take() {
+                            Some(writer) => writer,
+                            None => create("predictions.parquet", predictions_schema())?,
+                        };
+                        let writer = predictions.insert(writer);
+                        writer
+                            .write(&predictions_to_batch(&[prediction])?)
+                            .context("failed to write prediction")?;
+                        report.predictions += 1;
+                    }
+                    other => {
+                        eprintln!("Ignoring unexpected input `{other}`");
+                        continue;
+                    }
+                }
+                let rows = report.readings + report.predictions;
+                if rows % recorded_every == 0 {
+                    node.send_output(output.clone(), Default::default(), rows.into_arrow())?;
+                }
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    // writes the last row group and the footer, without which a file can't be read
+    for (name, writer) in [
+        ("readings.parquet", readings),
+        ("predictions.parquet", predictions),
+    ] {
+        if let Some(writer) = writer {
+            writer
+                .close()
+                .with_context(|| format!("failed to close {name}"))?;
+            report
+                .files
+                .push(output_dir.join(name).display().to_string());
+        }
+    }
+    println!(
+        "recorded {} readings and {} predictions to {}",
+        report.readings,
+        report.predictions,
+        report.files.join(", ")
+    );
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,130 @@
+//! A gateway of pump sensors on the machine, a zenoh application outside of the dataflow.
+//!
+//! Usage: `sensor-gateway --config <path> [--sensor <name>] [--samples <n>] [--period-ms <ms>]
+//! [--fault-start <seq>] [--fault-len <n>] [--seed <n>] [--wait-secs <n>]`
+//!
+//! Waits until `zenoh-ingress` declared its liveliness token, then publishes `--samples`
+//! readings of the simulated pump `--sensor` on `sensors/<sensor>`, one every `--period-ms`.
+//! From reading `--fault-start` on, for `--fault-len` readings, the pump runs into a bearing
+//! fault: over `FAULT_RAMP` readings, it vibrates more, heats up, draws more current, and loses
+//! pressure. The last reading is marked as `last`.
+
+use eyre::{bail, eyre};
+use kitchen_sink_dataflow_nodes::{FAULT_RAMP, INGRESS_TOKEN, SensorMessage, now_ms, open_session};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use zenoh::{Wait, qos::CongestionControl};
+
+/// The mean and standard deviation of each channel of a healthy pump, which
+/// `model/make_model.py` normalizes the readings with.
+const NOMINAL_MEAN: [f32; 4] = [2.0, 45.0, 12.0, 3.0];
+const NOMINAL_STD: [f32; 4] = [0.3, 1.0, 0.5, 0.1];
+/// How far a fully developed fault moves each channel.
+const FAULT_OFFSET: [f32; 4] = [3.0, 6.0, 1.0, -0.3];
+
+fn main() -> eyre::Result<()> {
+    let mut config = None;
+    let mut sensor = "pump-1".to_owned();
+    let mut samples: u64 = 600;
+    let mut period = Duration::from_millis(20);
+    let mut fault_start: u64 = 300;
+    let mut fault_len: u64 = 120;
+    let mut seed: u64 = 7;
+    let mut wait = Duration::from_secs(120);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--config" => config = Some(PathBuf::from(value()?)),
+            "--sensor" => sensor = value()?,
+            "--samples" => samples = value()?.parse()?,
+            "--period-ms" => period = Duration::from_millis(value()?.parse()?),
+            "--fault-start" => fault_start = value()?.parse()?,
+            "--fault-len" => fault_len = value()?.parse()?,
+            "--seed" => seed = value()?.parse()?,
+            "--wait-secs" => wait = Duration::from_secs(value()?.parse()?),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    let config = config.ok_or_else(|| eyre!("usage: sensor-gateway --config <path> [...]"))?;
+
+    let session = open_session(&config)?;
+    println!("sensor gateway started as {}", session.zid());
+
+    // also sees a token that was declared before the subscriber
+    let ingress = session
+        .liveliness()
+        .declare_subscriber(INGRESS_TOKEN)
+        .history(true)
+        .wait()
+        .map_err(|err| eyre!("failed to subscribe to liveliness: {err}"))?;
+    match ingress.recv_timeout(wait) {
+        Ok(Some(_)) => println!("ingress is up, publishing {samples} readings of {sensor}"),
+        Ok(None) => bail!("no ingress appeared within {wait:?}"),
+        Err(err) => bail!("failed to wait for the ingress: {err}"),
+    }
+
+    let publisher = session
+        .declare_publisher(format!("sensors/{sensor}"))
+        .congestion_control(CongestionControl::Block)
+        .wait()
+        .map_err(|err| eyre!("failed to declare publisher: {err}"))?;
+    let mut noise = Noise::new(seed);
+    let start = Instant::now();
+    for seq in 0..samples {
+        // the share of the fault that has developed
+        let fault = if (fault_start..fault_start + fault_len).contains(&seq) {
+            ((seq - fault_start + 1) as f32 / FAULT_RAMP as f32).min(1.0)
+        } else {
+            0.0
+        };
+        let values = std::array::from_fn(|i| {
+            NOMINAL_MEAN[i] + NOMINAL_STD[i] * noise.gaussian() + fault * FAULT_OFFSET[i]
+        });
+        let message = SensorMessage {
+            seq,
+            timestamp_ms: now_ms(),
+            values,
+            last: seq + 1 == samples,
+        };
+        publisher
+            .put(serde_json::to_vec(&message)?)
+            .wait()
+            .map_err(|err| eyre!("failed to publish reading: {err}"))?;
+
+        // on a fixed schedule, so that slow puts don't add up
+        let next = start + period * (seq + 1) as u32;
+        std::thread::sleep(next.saturating_duration_since(Instant::now()));
+    }
+    println!("published {samples} readings");
+    Ok(())
+}
+
+/// Deterministic measurement noise, so that every run sees the same readings.
+struct Noise(u64);
+
+impl Noise {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck at 0
+        Self(seed.max(1))
+    }
+
+    /// Uniform in (0, 1].
+    fn uniform(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        ((self.0 >> 40) + 1) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Standard normal, with the Box-Muller transform.
+    fn gaussian(&mut self) -> f32 {
+        let (u, v) = (self.uniform(), self.uniform());
+        (-2.0 * u.ln()).sqrt() * (std::f32::consts::TAU * v).cos()
+    }
+}
@@ -0,0 +1,123 @@
+//! Checks the recording of `recorder` against what `sensor-gateway` published.
+//!
+//! Usage: `verify-recording <dir> --samples <n> [--predictions] [--fault-start <seq>]
+//! [--fault-len <n>]`
+//!
+//! The readings must be complete and in order. With `--predictions`, there must be one
+//! prediction per reading, which flag at least `MIN_RECALL` of the faulty readings once the
+//! fault developed, and at most `MAX_FALSE_ALARMS` of the healthy ones.
+
+use dora_node_api::arrow::record_batch::RecordBatch;
+use eyre::{Context, bail, eyre};
+use kitchen_sink_dataflow_nodes::{FAULT_RAMP, predictions_from_batch, readings_from_batch};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+const MIN_RECALL: f64 = 0.95;
+const MAX_FALSE_ALARMS: f64 = 0.01;
+
+fn main() -> eyre::Result<()> {
+    let mut dir = None;
+    let mut samples = None;
+    let mut predictions = false;
+    let mut fault_start: u64 = 300;
+    let mut fault_len: u64 = 120;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--samples" => samples = Some(value()?.parse::<u64>()?),
+            "--predictions" => predictions = true,
+            "--fault-start" => fault_start = value()?.parse()?,
+            "--fault-len" => fault_len = value()?.parse()?,
+            other if dir.is_none() => dir = Some(PathBuf::from(other)),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    let (Some(dir), Some(samples)) = (dir, samples) else {
+        bail!("usage: verify-recording <dir> --samples <n> [--predictions] [...]");
+    };
+
+    // complete: every reading once, in order
+    let readings = read(&dir.join("readings.parquet"), readings_from_batch)?;
+    println!("readings: {} recorded", readings.len());
+    check_sequence(readings.iter().map(|r| r.seq), samples, "reading")?;
+    if readings
+        .windows(2)
+        .any(|pair| pair[1].timestamp_ms < pair[0].timestamp_ms)
+    {
+        bail!("the timestamps of the readings are decreasing");
+    }
+
+    if !predictions {
+        return Ok(());
+    }
+    let predictions = read(&dir.join("predictions.parquet"), predictions_from_batch)?;
+    check_sequence(predictions.iter().map(|p| p.seq), samples, "prediction")?;
+
+    // the fault is only visible once it developed, the readings of the ramp count for neither
+    let developed = fault_start + FAULT_RAMP..fault_start + fault_len;
+    let ramp = fault_start..fault_start + FAULT_RAMP;
+    let (mut faulty, mut detected, mut healthy, mut false_alarms) = (0, 0, 0, 0);
+    for prediction in &predictions {
+        if developed.contains(&prediction.seq) {
+            faulty += 1;
+            detected += prediction.anomaly as u64;
+        } else if !ramp.contains(&prediction.seq) {
+            healthy += 1;
+            false_alarms += prediction.anomaly as u64;
+        }
+    }
+    let recall = detected as f64 / faulty.max(1) as f64;
+    let false_alarm_rate = false_alarms as f64 / healthy.max(1) as f64;
+    let mut inference_us: Vec<_> = predictions.iter().map(|p| p.inference_us).collect();
+    inference_us.sort_unstable();
+    println!(
+        "predictions: detected {detected} of {faulty} faulty readings, \
+         {false_alarms} false alarms in {healthy} healthy ones, median inference {} µs",
+        inference_us[inference_us.len() / 2]
+    );
+    if recall < MIN_RECALL {
+        bail!("recall {recall:.3} is below {MIN_RECALL}");
+    }
+    if false_alarm_rate > MAX_FALSE_ALARMS {
+        bail!("false alarm rate {false_alarm_rate:.3} is above {MAX_FALSE_ALARMS}");
+    }
+    Ok(())
+}
+
+fn read<T>(
+    path: &Path,
+    from_batch: fn(&RecordBatch) -> eyre::Result<Vec<T>>,
+) -> eyre::Result<Vec<T>> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(
+        File::open(path).with_context(|| format!("failed to open {}", path.display()))?,
+    )?;
+    let mut rows = Vec::new();
+    for batch in reader.build()? {
+        rows.extend(from_batch(&batch?)?);
+    }
+    Ok(rows)
+}
+
+fn check_sequence(
+    seqs: impl ExactSizeIterator<Item = u64>,
+    expected: u64,
+    what: &str,
+) -> eyre::Result<()> {
+    if seqs.len() as u64 != expected {
+        bail!("expected {expected} {what}s, found {}", seqs.len());
+    }
+    for (i, seq) in seqs.enumerate() {
+        if seq != i as u64 {
+            bail!("{what} {i} has seq {seq}, {what}s are missing or out of order");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter,
+    arrow::array::Float32Array,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, eyre};
+use kitchen_sink_dataflow_nodes::{
+    INGRESS_TOKEN, SENSOR_KEY, SEQ_KEY, SensorMessage, TIMESTAMP_KEY, env_or, open_session,
+};
+use std::path::PathBuf;
+use zenoh::Wait;
+
+/// Brings the sensor readings that arrive over zenoh into the dataflow.
+///
+/// Subscribes to `KEY_EXPR` with the zenoh config `ZENOH_CONFIG`, and sends every
+/// `SensorMessage` on `readings`: the values of the channels as a `Float32` array, and the
+/// `seq`, the `timestamp_ms` at the sensor, and the `sensor`, the last chunk of the key, in
+/// the metadata. Stops after the reading that is marked as `last`.
+///
+/// Declares the liveliness token `INGRESS_TOKEN` once it subscribed, which the sensor gateway
+/// waits for before it publishes.
+fn main() -> eyre::Result<()> {
+    let config: PathBuf = env_or("ZENOH_CONFIG", "config/ingress.json5".to_owned())?.into();
+    let key_expr = env_or("KEY_EXPR", "sensors/**".to_owned())?;
+    let output = DataId::from("readings".to_owned());
+
+    let session = open_session(&config)?;
+    // zenoh calls back on its own threads, the samples are merged into the dora event loop
+    let (samples_tx, samples_rx) = futures::channel::mpsc::unbounded();
+    let _subscriber = session
+        .declare_subscriber(&key_expr)
+        .callback(move |sample| {
+            let sensor = sample
+                .key_expr()
+                .as_str()
+                .rsplit('/')
+                .next()
+                .map(str::to_owned);
+            let _ = samples_tx.unbounded_send((sensor, sample.payload().to_bytes().into_owned()));
+        })
+        .wait()
+        .map_err(|err| eyre!("failed to subscribe to `{key_expr}`: {err}"))?;
+    let _token = session
+        .liveliness()
+        .declare_token(INGRESS_TOKEN)
+        .wait()
+        .map_err(|err| eyre!("failed to declare liveliness token: {err}"))?;
+    println!("subscribed to `{key_expr}` as {}", session.zid());
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let merged = events.merge_external(Box::pin(samples_rx));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut readings = 0;
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External((sensor, payload)) => {
+                let message: SensorMessage =
+                    serde_json::from_slice(&payload).context("invalid sensor message")?;
+                let mut parameters = MetadataParameters::default();
+                parameters.insert(SEQ_KEY.into(), Parameter::Integer(message.seq as i64));
+                parameters.insert(
+                    TIMESTAMP_KEY.into(),
+                    Parameter::Integer(message.timestamp_ms as i64),
+                );
+                parameters.insert(
+                    SENSOR_KEY.into(),
+                    Parameter::String(sensor.unwrap_or_default()),
+                );
+                node.send_output(
+                    output.clone(),
+                    parameters,
+                    Float32Array::from(message.values.to_vec()),
+                )?;
+                readings += 1;
+                if message.last {
+                    break;
+                }
+            }
+        }
+    }
+
+    println!("forwarded {readings} readings");
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, Parameter,
+    arrow::{array::AsArray, datatypes::Float32Type},
+    dora_core::config::DataId,
+};
+use dora_ros2_bridge::{
+    messages::std_msgs::msg::{Bool, Float32},
+    ros2_client::{self, NodeOptions},
+    rustdds::{self, policy},
+};
+use eyre::{Context, OptionExt, bail, eyre};
+use futures::task::SpawnExt;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Written to `REPORT_FILE` when the node stops.
+#[derive(Debug, Default, Serialize)]
+struct EgressReport {
+    published: u64,
+    anomalies: u64,
+}
+
+/// Publishes the `predictions` to ROS2: the score as `std_msgs/Float32` on
+/// `/kitchen_sink/anomaly_score`, and whether it is an anomaly as `std_msgs/Bool` on
+/// `/kitchen_sink/anomaly`.
+///
+/// Sends the number of predictions published so far on `published`, with the metadata of the
+/// prediction, so that the health node sees the latency up to ROS2.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = std::env::var("REPORT_FILE")
+        .unwrap_or_else(|_| "out/ros2-egress.json".to_owned())
+        .into();
+    let output = DataId::from("published".to_owned());
+
+    let mut ros_node = init_ros_node()?;
+    let scores = ros_node
+        .create_publisher::<Float32>(
+            &create_topic(&mut ros_node, "anomaly_score", "Float32")?,
+            None,
+        )
+        .context("failed to create publisher")?;
+    let anomalies = ros_node
+        .create_publisher::<Bool>(&create_topic(&mut ros_node, "anomaly", "Bool")?, None)
+        .context("failed to create publisher")?;
+
+    // spawn a background spinner task that handles service discovery (and other things)
+    let pool = futures::executor::ThreadPool::new()?;
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let mut report = EgressReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "predictions" => {
+                    let score = data
+                        .as_primitive_opt::<Float32Type>()
+                        .ok_or_eyre("expected a Float32 array")?
+                        .value(0);
+                    let anomaly = match metadata.parameters.get("anomaly") {
+                        Some(Parameter::Bool(anomaly)) => *anomaly,
+                        _ => bail!("missing boolean metadata `anomaly`"),
+                    };
+                    scores
+                        .publish(Float32 { data: score })
+                        .map_err(|e| eyre!("failed to publish score: {e:?}"))?;
+                    anomalies
+                        .publish(Bool { data: anomaly })
+                        .map_err(|e| eyre!("failed to publish anomaly: {e:?}"))?;
+
+                    report.published += 1;
+                    report.anomalies += anomaly as u64;
+                    node.send_output(
+                        output.clone(),
+                        metadata.parameters,
+                        report.published.into_arrow(),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "published {} predictions, {} anomalies",
+        report.published, report.anomalies
+    );
+    if let Some(parent) = report_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("failed to write {}", report_file.display()))
+}
+
+fn init_ros_node() -> eyre::Result<ros2_client::Node> {
+    let ros_context = ros2_client::Context::new().unwrap();
+
+    ros_context
+        .new_node(
+            ros2_client::NodeName::new("/kitchen_sink", "ros2_egress")
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre!("failed to create ros2 node: {e:?}"))
+}
+
+/// A reliable `std_msgs` topic in the `/kitchen_sink` namespace.
+fn create_topic(
+    ros_node: &mut ros2_client::Node,
+    name: &str,
+    message_type: &str,
+) -> eyre::Result<rustdds::Topic> {
+    let topic_qos: rustdds::QosPolicies = rustdds::QosPolicyBuilder::new()
+        .durability(policy::Durability::Volatile)
+        .reliability(policy::Reliability::Reliable {
+            max_blocking_time: rustdds::Duration::from_millis(100),
+        })
+        .history(policy::History::KeepLast { depth: 10 })
+        .build();
+
+    ros_node
+        .create_topic(
+            &ros2_client::Name::new("/kitchen_sink", name)
+                .map_err(|e| eyre!("failed to create ROS2 name: {e}"))?,
+            ros2_client::MessageTypeName::new("std_msgs", message_type),
+            &topic_qos,
+        )
+        .context("failed to create topic")
+}
@@ -0,0 +1,27 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::Context;
+use std::path::Path;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("linalg-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let args: Vec<String> = std::env::args().collect();
+    let dora = Dora::from_env()?;
+    let dataflow = if args.len() > 1 {
+        Path::new(&args[1])
+    } else {
+        Path::new("dataflow.yml")
+    };
+
+    dora.build_dataflow(dataflow).await?;
+
+    dora.run_dataflow(dataflow).await?;
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::Float64Array,
+    dora_core::config::DataId,
+};
+use example_node_utils::env_or;
+use rand::Rng;
+
+/// Sends batches of random matrix pairs as tensors of shape `[2, BATCH, SIZE, SIZE]`.
+fn main() -> eyre::Result<()> {
+    let size: usize = env_or("MATRIX_SIZE", 256)?;
+    let batch: usize = env_or("BATCH", 8)?;
+    let batches: usize = env_or("BATCHES", 20)?;
+    let output = DataId::from("matrices".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut rng = rand::thread_rng();
+    let mut sent = 0;
+    while sent < batches {
+        let event = match events.recv() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            Event::Input { id, .. } if id.as_str() == "tick" => {
+                let values: Vec<f64> = (0..2 * batch * size * size)
+                    .map(|_| rng.gen_range(-1.0..1.0))
+                    .collect();
+                let mut parameters = MetadataParameters::default();
+                parameters.insert(
+                    "shape".into(),
+                    Parameter::ListInt(vec![2, batch as i64, size as i64, size as i64]),
+                );
+                node.send_output(output.clone(), parameters, Float64Array::from(values))?;
+                sent += 1;
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+// link the BLAS implementation selected through the cargo features
+#[cfg(any(
+    feature = "openblas",
+    feature = "netlib",
+    feature = "intel-mkl",
+    feature = "accelerate"
+))]
+extern crate blas_src;
+
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::{bail, eyre};
+use ndarray::{Array3, ArrayView4, Axis};
+use std::time::Instant;
+
+const BACKEND: &str = if cfg!(feature = "openblas") {
+    "openblas"
+} else if cfg!(feature = "netlib") {
+    "netlib"
+} else if cfg!(feature = "intel-mkl") {
+    "intel-mkl"
+} else if cfg!(feature = "accelerate") {
+    "accelerate"
+} else {
+    "matrixmultiply"
+};
+
+/// Multiplies the matrix pairs of every incoming batch and reports the achieved GFLOP/s.
+fn main() -> eyre::Result<()> {
+    let result_output = DataId::from("products".to_owned());
+    let stats_output = DataId::from("gflops".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    println!("multiplying matrices using the `{BACKEND}` backend");
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "matrices" => {
+                    let Some(Parameter::ListInt(shape)) = metadata.parameters.get("shape") else {
+                        bail!("expected `shape` parameter");
+                    };
+                    let &[2, batch, n, m] = &shape[..] else {
+                        bail!("expected shape `[2, batch, n, n]`, got {shape:?}");
+                    };
+                    if n != m {
+                        bail!("expected square matrices, got {n}x{m}");
+                    }
+                    let (batch, n) = (batch as usize, n as usize);
+                    let values = data
+                        .as_primitive_opt::<Float64Type>()
+                        .ok_or_else(|| eyre!("expected float64 matrices"))?
+                        .values();
+                    let operands = ArrayView4::from_shape((2, batch, n, n), &values[..])?;
+                    let (lhs, rhs) = (
+                        operands.index_axis(Axis(0), 0),
+                        operands.index_axis(Axis(0), 1),
+                    );
+
+                    let start = Instant::now();
+                    let mut products = Array3::<f64>::zeros((batch, n, n));
+                    for ((a, b), mut c) in lhs
+                        .outer_iter()
+                        .zip(rhs.outer_iter())
+                        .zip(products.outer_iter_mut())
+                    {
+                        c.assign(&a.dot(&b));
+                    }
+                    let elapsed = start.elapsed();
+
+                    // spot check one entry against a naive dot product
+                    let expected: f64 = (0..n).map(|k| lhs[[0, n - 1, k]] * rhs[[0, k, 0]]).sum();
+                    if (products[[0, n - 1, 0]] - expected).abs() > 1e-9 * n as f64 {
+                        bail!("wrong product: {} != {expected}", products[[0, n - 1, 0]]);
+                    }
+
+                    let flops = 2.0 * (n as f64).powi(3) * batch as f64;
+                    let gflops = flops / elapsed.as_secs_f64() / 1e9;
+
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(
+                        "shape".into(),
+                        Parameter::ListInt(vec![batch as i64, n as i64, n as i64]),
+                    );
+                    node.send_output(
+                        result_output.clone(),
+                        parameters,
+                        Float64Array::from(products.into_raw_vec_and_offset().0),
+                    )?;
+
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert("backend".into(), Parameter::String(BACKEND.into()));
+                    parameters.insert("size".into(), Parameter::Integer(n as i64));
+                    parameters.insert("batch".into(), Parameter::Integer(batch as i64));
+                    parameters.insert(
+                        "elapsed_us".into(),
+                        Parameter::Integer(elapsed.as_micros() as i64),
+                    );
+                    node.send_output(
+                        stats_output.clone(),
+                        parameters,
+                        Float64Array::from(vec![gflops]),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use dora_node_api::{self, DoraNode, Event, Parameter};
+use eyre::{Context, bail};
+
+fn main() -> eyre::Result<()> {
+    let min_gflops: f64 = std::env::var("MIN_GFLOPS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0.0);
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut measurements = Vec::new();
+    let mut backend = String::new();
+    let mut products = 0;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "gflops" => {
+                    let gflops = f64::try_from(&data).context("expected float64 GFLOP/s")?;
+                    let param = |key| match metadata.parameters.get(key) {
+                        Some(Parameter::Integer(value)) => *value,
+                        _ => 0,
+                    };
+                    if let Some(Parameter::String(name)) = metadata.parameters.get("backend") {
+                        backend.clone_from(name);
+                    }
+                    println!(
+                        "batch of {} {n}x{n} products in {:>7} µs: {gflops:>7.2} GFLOP/s",
+                        param("batch"),
+                        param("elapsed_us"),
+                        n = param("size"),
+                    );
+                    measurements.push(gflops);
+                }
+                "products" => products += 1,
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if measurements.is_empty() || products != measurements.len() {
+        bail!(
+            "received {products} results for {} measurements",
+            measurements.len()
+        );
+    }
+    // the first batch includes warm-up effects like thread pool creation
+    let steady = if measurements.len() > 1 {
+        &measurements[1..]
+    } else {
+        &measurements[..]
+    };
+    let mean = steady.iter().sum::<f64>() / steady.len() as f64;
+    let best = steady.iter().copied().fold(f64::MIN, f64::max);
+    println!("backend `{backend}`: mean {mean:.2} GFLOP/s, best {best:.2} GFLOP/s");
+    if mean < min_gflops {
+        bail!("mean throughput {mean:.2} GFLOP/s is below the required {min_gflops}");
+    }
+    Ok(())
+}
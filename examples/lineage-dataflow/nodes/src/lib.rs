@@ -0,0 +1,118 @@
+use dora_node_api::{DoraNode, MetadataParameters, Parameter};
+use eyre::{Context, bail};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub use example_node_utils::now_us;
+
+/// Metadata key under which the lineage list is stored.
+pub const LINEAGE_KEY: &str = "lineage";
+
+/// One processing step that contributed to a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hop {
+    /// Unique id of this step, `<node>/<counter>`.
+    pub id: String,
+    pub node: String,
+    pub version: String,
+    /// Time between receiving the triggering input and sending the output.
+    pub processing_us: u64,
+    /// Time at which the output was sent, in microseconds since the Unix epoch.
+    pub sent_at_us: u64,
+    /// Ids of the hops of all inputs that this step combined.
+    pub parents: Vec<String>,
+}
+
+/// The provenance of a message: every hop that contributed to it, in the order in which they
+/// were appended. The last hop is the node that sent the message.
+#[derive(Debug, Clone, Default)]
+pub struct Lineage {
+    pub hops: Vec<Hop>,
+}
+
+impl Lineage {
+    pub fn from_parameters(parameters: &MetadataParameters) -> eyre::Result<Self> {
+        let hops = match parameters.get(LINEAGE_KEY) {
+            Some(Parameter::ListString(hops)) => hops
+                .iter()
+                .map(|hop| serde_json::from_str(hop).context("invalid lineage hop"))
+                .collect::<eyre::Result<_>>()?,
+            Some(other) => bail!("unexpected lineage parameter {other:?}"),
+            None => Vec::new(),
+        };
+        Ok(Self { hops })
+    }
+
+    pub fn last(&self) -> Option<&Hop> {
+        self.hops.last()
+    }
+
+    /// Combines the lineage of several inputs, keeping every hop only once.
+    pub fn merge(&mut self, other: &Lineage) {
+        for hop in &other.hops {
+            if !self.hops.iter().any(|existing| existing.id == hop.id) {
+                self.hops.push(hop.clone());
+            }
+        }
+    }
+
+    pub fn to_parameters(&self) -> eyre::Result<MetadataParameters> {
+        let hops = self
+            .hops
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<_, _>>()?;
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(LINEAGE_KEY.into(), Parameter::ListString(hops));
+        Ok(parameters)
+    }
+}
+
+/// Appends hops for the current node, identified by its node id and its `NODE_VERSION`.
+pub struct Recorder {
+    node: String,
+    version: String,
+    counter: u64,
+}
+
+impl Recorder {
+    pub fn new(node: &DoraNode) -> Self {
+        Self {
+            node: node.id().to_string(),
+            version: std::env::var("NODE_VERSION")
+                .unwrap_or_else(|_| env!("CARGO_PKG_VERSION").to_owned()),
+            counter: 0,
+        }
+    }
+
+    /// Appends a hop that was derived from the last hops of all `inputs`.
+    pub fn record(&mut self, inputs: &[&Lineage], processing: Duration) -> Lineage {
+        let mut lineage = Lineage::default();
+        for input in inputs {
+            lineage.merge(input);
+        }
+        self.counter += 1;
+        lineage.hops.push(Hop {
+            id: format!("{}/{}", self.node, self.counter),
+            node: self.node.clone(),
+            version: self.version.clone(),
+            processing_us: processing.as_micros() as u64,
+            sent_at_us: now_us(),
+            parents: inputs
+                .iter()
+                .filter_map(|input| input.last())
+                .map(|hop| hop.id.clone())
+                .collect(),
+        });
+        lineage
+    }
+}
+
+/// Simulates the processing cost of a node, configured through `WORK_MS`.
+pub fn simulate_work() {
+    let work_ms = std::env::var("WORK_MS")
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .unwrap_or(0);
+    std::thread::sleep(Duration::from_millis(work_ms));
+}
@@ -0,0 +1,113 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::{Context, bail};
+use lineage_dataflow_nodes::{Hop, Lineage, now_us};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Reconstructs the provenance chain of every received message and checks that it reaches
+/// all expected source nodes.
+fn main() -> eyre::Result<()> {
+    let expected_sources: BTreeSet<String> = std::env::var("EXPECTED_SOURCES")
+        .context("EXPECTED_SOURCES env variable is required")?
+        .split(',')
+        .map(|source| source.trim().to_owned())
+        .collect();
+    let print_every: u64 = std::env::var("PRINT_EVERY")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(25);
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut received = 0;
+    let mut versions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata,
+                data: _,
+            } => {
+                let lineage = Lineage::from_parameters(&metadata.parameters)?;
+                let Some(last) = lineage.last() else {
+                    bail!("message on `{id}` has no lineage");
+                };
+                let hops: BTreeMap<&str, &Hop> = lineage
+                    .hops
+                    .iter()
+                    .map(|hop| (hop.id.as_str(), hop))
+                    .collect();
+
+                let mut sources = BTreeSet::new();
+                check_chain(last, &hops, &mut sources)?;
+                if sources != expected_sources {
+                    bail!(
+                        "message from `{}` originates from {sources:?}, expected \
+                         {expected_sources:?}",
+                        last.id
+                    );
+                }
+                for hop in &lineage.hops {
+                    versions
+                        .entry(hop.node.clone())
+                        .or_default()
+                        .insert(hop.version.clone());
+                }
+
+                if received % print_every == 0 {
+                    let origin = lineage.hops.iter().map(|hop| hop.sent_at_us).min();
+                    let age_us = origin.map_or(0, |origin| now_us().saturating_sub(origin));
+                    println!("provenance of message on `{id}` (end-to-end {age_us} µs):");
+                    print_chain(last, &hops, 1);
+                }
+                received += 1;
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if received == 0 {
+        bail!("no messages received");
+    }
+    println!("verified the lineage of {received} messages, node versions involved:");
+    for (node, versions) in versions {
+        println!("  {node}: {}", Vec::from_iter(versions).join(", "));
+    }
+    Ok(())
+}
+
+/// Walks from `hop` back to the sources, collecting the nodes without parents.
+fn check_chain<'a>(
+    hop: &'a Hop,
+    hops: &BTreeMap<&str, &'a Hop>,
+    sources: &mut BTreeSet<String>,
+) -> eyre::Result<()> {
+    if hop.parents.is_empty() {
+        sources.insert(hop.node.clone());
+    }
+    for parent in &hop.parents {
+        let Some(parent) = hops.get(parent.as_str()) else {
+            bail!("lineage of `{}` references unknown hop `{parent}`", hop.id);
+        };
+        check_chain(parent, hops, sources)?;
+    }
+    Ok(())
+}
+
+fn print_chain(hop: &Hop, hops: &BTreeMap<&str, &Hop>, depth: usize) {
+    println!(
+        "{:indent$}{} (node `{}` v{}, {} µs)",
+        "",
+        hop.id,
+        hop.node,
+        hop.version,
+        hop.processing_us,
+        indent = depth * 2
+    );
+    for parent in &hop.parents {
+        if let Some(parent) = hops.get(parent.as_str()) {
+            print_chain(parent, hops, depth + 1);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use lineage_dataflow_nodes::{Recorder, simulate_work};
+use std::time::Instant;
+
+fn main() -> eyre::Result<()> {
+    let messages: u64 = std::env::var("MESSAGES")
+        .ok()
+        .and_then(|messages| messages.parse().ok())
+        .unwrap_or(100);
+    let output = DataId::from("data".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let mut recorder = Recorder::new(&node);
+
+    let mut sent = 0;
+    while sent < messages {
+        let event = match events.recv() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            Event::Input { id, .. } if id.as_str() == "tick" => {
+                let start = Instant::now();
+                simulate_work();
+                // sources start a new lineage without parents
+                let lineage = recorder.record(&[], start.elapsed());
+                node.send_output(output.clone(), lineage.to_parameters()?, sent.into_arrow())?;
+                sent += 1;
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
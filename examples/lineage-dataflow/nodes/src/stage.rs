@@ -0,0 +1,50 @@
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+use lineage_dataflow_nodes::{Lineage, Recorder, simulate_work};
+use std::{collections::BTreeMap, time::Instant};
+
+/// Generic processing node: forwards the data of its `trigger` input and combines the
+/// lineage of the trigger with the lineage of the latest message of every other input.
+///
+/// Triggers are skipped until every other input has received at least one message.
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("data".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let mut recorder = Recorder::new(&node);
+    let secondary_inputs = node
+        .node_config()
+        .inputs
+        .keys()
+        .filter(|id| id.as_str() != "trigger")
+        .count();
+
+    let mut latest: BTreeMap<String, Lineage> = BTreeMap::new();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let start = Instant::now();
+                let lineage = Lineage::from_parameters(&metadata.parameters)?;
+                if id.as_str() != "trigger" {
+                    latest.insert(id.to_string(), lineage);
+                    continue;
+                }
+
+                if latest.len() < secondary_inputs {
+                    println!("waiting for the first message of all inputs, skipping trigger");
+                    continue;
+                }
+
+                simulate_work();
+                let inputs: Vec<&Lineage> =
+                    std::iter::once(&lineage).chain(latest.values()).collect();
+                let lineage = recorder.record(&inputs, start.elapsed());
+                node.send_output(output.clone(), lineage.to_parameters()?, data.0)?;
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, run};
+use eyre::{Context, OptionExt, bail};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("log-correlation-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    std::fs::create_dir_all("out")?;
+    let rosout_log = Path::new("out/rosout.log");
+    let daemon_log = Path::new("out/daemon.log");
+
+    // record everything that ROS2 nodes log while the dataflow runs
+    let ros_path = std::env::var("ROS").unwrap_or_else(|_| "/opt/ros/jazzy/setup.bash".into());
+    let mut rosout = tokio::process::Command::new("bash")
+        .args([
+            "-c",
+            &format!(
+                "source {ros_path}; exec ros2 topic echo --full-length /rosout rcl_interfaces/msg/Log"
+            ),
+        ])
+        .stdout(File::create(rosout_log)?)
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start `ros2 topic echo /rosout`")?;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    run_dataflow(&dora, dataflow, daemon_log).await?;
+
+    // give the echo some time to receive the last rosout entries
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    rosout.kill().await?;
+
+    trace(rosout_log, daemon_log)
+}
+
+/// Picks the last message that made it through to ROS2 and prints every log line that
+/// mentions its correlation id, grouped by the system that wrote it.
+fn trace(rosout_log: &Path, daemon_log: &Path) -> eyre::Result<()> {
+    let node_logs = latest_dataflow_logs()?;
+    let bridge_log = std::fs::read_to_string(node_logs.join("log_ros-bridge.txt"))
+        .context("failed to read ros-bridge log")?;
+    let cid = bridge_log
+        .lines()
+        .rev()
+        .find_map(|line| {
+            let start = line.find("[cid=")? + 1;
+            let end = start + line[start..].find(']')?;
+            Some(line[start..end].to_owned())
+        })
+        .ok_or_eyre("ros-bridge did not log any correlation id")?;
+
+    println!("tracing `{cid}` across the system:");
+    let sources = [
+        ("producer node", node_logs.join("log_producer.txt")),
+        ("processor node", node_logs.join("log_processor.txt")),
+        ("ros-bridge node", node_logs.join("log_ros-bridge.txt")),
+        ("dora daemon", daemon_log.to_owned()),
+        ("ROS2 /rosout", rosout_log.to_owned()),
+    ];
+    let mut missing = Vec::new();
+    for (name, path) in sources {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let matches: Vec<_> = content.lines().filter(|line| line.contains(&cid)).collect();
+        println!("\n== {name} ({}) ==", path.display());
+        for line in &matches {
+            println!("{}", line.trim());
+        }
+        if matches.is_empty() {
+            missing.push(name);
+        }
+    }
+
+    if !missing.is_empty() {
+        bail!(
+            "`{cid}` does not appear in the logs of: {}",
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Returns the `out/<dataflow id>` directory of the most recent run.
+fn latest_dataflow_logs() -> eyre::Result<PathBuf> {
+    let mut latest = None;
+    for entry in std::fs::read_dir("out")? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let modified = entry.metadata()?.modified()?;
+        if latest.as_ref().is_none_or(|(time, _)| modified > *time) {
+            latest = Some((modified, entry.path()));
+        }
+    }
+    latest
+        .map(|(_, path)| path)
+        .ok_or_eyre("no dataflow logs found in `out`")
+}
+
+async fn run_dataflow(dora: &Dora, dataflow: &Path, log: &Path) -> eyre::Result<()> {
+    let mut cmd = dora.cli(["daemon", "--run-dataflow"]);
+    cmd.arg(dataflow);
+    // keep a copy of the daemon output, it includes the stdout of all nodes
+    let log = File::create(log)?;
+    cmd.stdout(Stdio::from(log.try_clone()?));
+    cmd.stderr(Stdio::from(log));
+    run(&mut cmd, "failed to run dataflow").await
+}
@@ -0,0 +1,64 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::bail;
+use std::fmt;
+
+/// Metadata key under which the correlation id travels along with a message.
+pub const CORRELATION_KEY: &str = "correlation_id";
+
+/// An id that is attached to a message when it enters the dataflow and then copied into every
+/// message and log line derived from it. Grepping for it in the dora node logs, the daemon
+/// log, and ROS2 `/rosout` shows everything that happened to that message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrelationId(String);
+
+impl CorrelationId {
+    /// Creates a fresh id for a message that enters the dataflow at this node.
+    pub fn new() -> Self {
+        let uuid = uuid::Uuid::new_v4().simple().to_string();
+        // a shortened id is still unique enough for a log search and easier to read
+        Self(uuid[..12].to_owned())
+    }
+
+    /// Reads the id that an upstream node attached to the input.
+    pub fn from_parameters(parameters: &MetadataParameters) -> eyre::Result<Self> {
+        match parameters.get(CORRELATION_KEY) {
+            Some(Parameter::String(id)) => Ok(Self(id.clone())),
+            Some(other) => bail!("unexpected `{CORRELATION_KEY}` parameter {other:?}"),
+            None => bail!("input has no `{CORRELATION_KEY}` parameter"),
+        }
+    }
+
+    /// Attaches the id to the metadata of an outgoing message.
+    pub fn attach(&self, parameters: &mut MetadataParameters) {
+        parameters.insert(CORRELATION_KEY.into(), Parameter::String(self.0.clone()));
+    }
+
+    pub fn to_parameters(&self) -> MetadataParameters {
+        let mut parameters = MetadataParameters::default();
+        self.attach(&mut parameters);
+        parameters
+    }
+}
+
+impl Default for CorrelationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cid={}", self.0)
+    }
+}
+
+/// Prints a log line prefixed with `[cid=<id>]`.
+///
+/// Node stdout ends up both in the node's log file in `out/` and in the daemon log, so the
+/// prefix makes the line show up when searching either of them for the id.
+#[macro_export]
+macro_rules! clog {
+    ($cid:expr, $($arg:tt)+) => {
+        println!("[{}] {}", $cid, format_args!($($arg)+))
+    };
+}
@@ -0,0 +1,34 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use eyre::Context;
+use log_correlation_dataflow_nodes::{CorrelationId, clog};
+
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("processed".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "reading" => {
+                    let cid = CorrelationId::from_parameters(&metadata.parameters)?;
+                    let reading = u64::try_from(&data).context("unexpected data type")?;
+                    clog!(cid, "received reading {reading}");
+                    let processed = reading * 2 + 1;
+                    clog!(cid, "forwarding processed value {processed}");
+                    // the outgoing message keeps the id of the input it was derived from
+                    node.send_output(
+                        output.clone(),
+                        cid.to_parameters(),
+                        processed.into_arrow(),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
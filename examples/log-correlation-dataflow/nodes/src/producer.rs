@@ -0,0 +1,41 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use eyre::Context;
+use log_correlation_dataflow_nodes::{CorrelationId, clog};
+
+fn main() -> eyre::Result<()> {
+    let messages: u64 = std::env::var("MESSAGES")
+        .unwrap_or_else(|_| "50".to_owned())
+        .parse()
+        .context("invalid MESSAGES")?;
+    let output = DataId::from("reading".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    let cid = CorrelationId::new();
+                    let reading = sent * 10;
+                    clog!(cid, "produced reading {reading} (message {sent})");
+                    node.send_output(output.clone(), cid.to_parameters(), reading.into_arrow())?;
+                    sent += 1;
+                    if sent >= messages {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {sent} readings");
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use dora_node_api::{self, DoraNode, Event};
+use dora_ros2_bridge::{
+    messages::std_msgs::msg::String as StringMsg,
+    ros2_client::{self, NodeOptions, ros2, rosout},
+    rustdds::{self, policy},
+};
+use eyre::{Context, eyre};
+use futures::task::SpawnExt;
+use log_correlation_dataflow_nodes::{CorrelationId, clog};
+
+fn main() -> eyre::Result<()> {
+    let mut ros_node = init_ros_node()?;
+    let publisher = create_publisher(&mut ros_node)?;
+
+    // spawn a background spinner task that is handles service discovery (and other things)
+    let pool = futures::executor::ThreadPool::new()?;
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre::eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "processed" => {
+                    let cid = CorrelationId::from_parameters(&metadata.parameters)?;
+                    let value = u64::try_from(&data).context("unexpected data type")?;
+                    clog!(cid, "publishing value {value} to /dora/processed");
+                    // the id is part of the ROS2 message and of the `/rosout` entry, so ROS2
+                    // tools that only see the topic can still be correlated with dora logs
+                    publisher
+                        .publish(StringMsg {
+                            data: format!("[{cid}] {value}"),
+                        })
+                        .map_err(|e| eyre!("failed to publish: {e:?}"))?;
+                    rosout!(
+                        ros_node,
+                        ros2::LogLevel::Info,
+                        "[{cid}] published value {value} to /dora/processed"
+                    );
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn init_ros_node() -> eyre::Result<ros2_client::Node> {
+    let ros_context = ros2_client::Context::new().unwrap();
+
+    ros_context
+        .new_node(
+            ros2_client::NodeName::new("/dora", "log_correlation_bridge")
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre::eyre!("failed to create ros2 node: {e:?}"))
+}
+
+fn create_publisher(
+    ros_node: &mut ros2_client::Node,
+) -> eyre::Result<ros2_client::Publisher<StringMsg>> {
+    let topic_qos: rustdds::QosPolicies = {
+        rustdds::QosPolicyBuilder::new()
+            .durability(policy::Durability::Volatile)
+            .reliability(policy::Reliability::Reliable {
+                max_blocking_time: ros2::Duration::from_millis(100),
+            })
+            .history(policy::History::KeepLast { depth: 10 })
+            .build()
+    };
+
+    let topic = ros_node
+        .create_topic(
+            &ros2_client::Name::new("/dora", "processed")
+                .map_err(|e| eyre!("failed to create ROS2 name: {e}"))?,
+            ros2_client::MessageTypeName::new("std_msgs", "String"),
+            &topic_qos,
+        )
+        .context("failed to create topic")?;
+
+    ros_node
+        .create_publisher::<StringMsg>(&topic, None)
+        .context("failed to create publisher")
+}
@@ -0,0 +1,195 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::{Deserialize, de::DeserializeOwned};
+use std::{fs::OpenOptions, path::Path};
+
+/// The log of the mapper, in `MAP_DIR` of `dataflow.yml`.
+const PATCH_LOG: &str = "state/map/patches.log";
+/// Bytes cut off the log between the runs, like a patch that was only partly written when the
+/// machine lost power.
+const TORN_BYTES: u64 = 3;
+
+/// Subset of `MapperReport` in `nodes/src/mapper.rs`.
+#[derive(Debug, Deserialize)]
+struct MapperReport {
+    restored: Restored,
+    version: u64,
+    checksum: u64,
+    known_cells: usize,
+    patches: Vec<VersionChecksum>,
+    snapshots: u64,
+    served: u64,
+}
+
+/// Subset of `Restored` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct Restored {
+    version: u64,
+    checksum: u64,
+    known_cells: usize,
+    snapshot_version: Option<u64>,
+    replayed: u64,
+    discarded_bytes: u64,
+}
+
+/// Subset of `VersionChecksum` in `nodes/src/mapper.rs`.
+#[derive(Debug, Deserialize)]
+struct VersionChecksum {
+    version: u64,
+    checksum: u64,
+}
+
+/// Subset of `ViewerReport` in `nodes/src/viewer.rs`.
+#[derive(Debug, Deserialize)]
+struct ViewerReport {
+    snapshot_version: Option<u64>,
+    snapshot_known_cells: usize,
+    resyncs: u64,
+    version: u64,
+    checksum: u64,
+}
+
+fn read_report<T: DeserializeOwned>(path: &str) -> eyre::Result<T> {
+    let json = std::fs::read_to_string(path).with_context(|| format!("missing report {path}"))?;
+    serde_json::from_str(&json).with_context(|| format!("invalid report {path}"))
+}
+
+async fn run(dora: &Dora, dataflow: &Path) -> eyre::Result<(MapperReport, ViewerReport)> {
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    dora.run_dataflow(dataflow).await?;
+
+    let mapper: MapperReport = read_report("out/mapper.json")?;
+    let viewer: ViewerReport = read_report("out/viewer.json")?;
+    println!(
+        "mapper: restored version {} (snapshot {:?}, {} patches replayed, {} bytes discarded), \
+         ended at version {} with {} known cells, {} snapshots, {} full maps served",
+        mapper.restored.version,
+        mapper.restored.snapshot_version,
+        mapper.restored.replayed,
+        mapper.restored.discarded_bytes,
+        mapper.version,
+        mapper.known_cells,
+        mapper.snapshots,
+        mapper.served
+    );
+    println!(
+        "viewer: joined with the full map of version {:?}, ended at version {}, {} resyncs",
+        viewer.snapshot_version, viewer.version, viewer.resyncs
+    );
+
+    // the late subscriber has to end up with exactly the mapper's map
+    if viewer.snapshot_version.is_none() {
+        bail!("the viewer never received the full map");
+    }
+    if (viewer.version, viewer.checksum) != (mapper.version, mapper.checksum) {
+        bail!(
+            "the viewer ended at version {} with checksum {:#x}, the mapper at version {} with \
+             checksum {:#x}",
+            viewer.version,
+            viewer.checksum,
+            mapper.version,
+            mapper.checksum
+        );
+    }
+    Ok((mapper, viewer))
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("map-persistence-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // start without a stored map
+    match std::fs::remove_dir_all("state") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dataflow = Path::new("dataflow.yml");
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+
+    println!("first run");
+    let (first, _) = run(&dora, dataflow).await?;
+    if first.restored.version != 0 {
+        bail!(
+            "expected the first run to start with an empty map, it restored version {}",
+            first.restored.version
+        );
+    }
+
+    // tear the last patch of the log
+    let log = OpenOptions::new()
+        .write(true)
+        .open(PATCH_LOG)
+        .with_context(|| format!("the mapper did not write {PATCH_LOG}"))?;
+    let len = log.metadata()?.len();
+    if len < TORN_BYTES {
+        bail!("expected patches in {PATCH_LOG} since the last snapshot, it has {len} bytes");
+    }
+    log.set_len(len - TORN_BYTES)?;
+    drop(log);
+    println!("cut {TORN_BYTES} bytes off {PATCH_LOG}");
+
+    println!("second run");
+    let (second, viewer) = run(&dora, dataflow).await?;
+
+    // the restart continues from the last complete patch
+    let restored = &second.restored;
+    if restored.discarded_bytes == 0 {
+        bail!("expected the mapper to discard the torn patch");
+    }
+    if restored.version + 1 != first.version {
+        bail!(
+            "expected the mapper to restore version {}, the one before the torn patch, got {}",
+            first.version - 1,
+            restored.version
+        );
+    }
+    let Some(expected) = first
+        .patches
+        .iter()
+        .find(|patch| patch.version == restored.version)
+    else {
+        bail!("the first run has no patch {}", restored.version);
+    };
+    if restored.checksum != expected.checksum {
+        bail!(
+            "the restored map of version {} has checksum {:#x}, the first run had {:#x}",
+            restored.version,
+            restored.checksum,
+            expected.checksum
+        );
+    }
+    if second.known_cells <= restored.known_cells {
+        bail!(
+            "expected the second run to extend the restored map of {} known cells, it ended \
+             with {}",
+            restored.known_cells,
+            second.known_cells
+        );
+    }
+    // a subscriber of the second run starts from the restored map, not from an empty one
+    if viewer.snapshot_known_cells < restored.known_cells {
+        bail!(
+            "the viewer joined with {} known cells, fewer than the {} that were restored",
+            viewer.snapshot_known_cells,
+            restored.known_cells
+        );
+    }
+    println!(
+        "restored version {} after the torn patch, and continued to version {} with {} known \
+         cells",
+        restored.version, second.version, second.known_cells
+    );
+
+    println!("Everything Done");
+    Ok(())
+}
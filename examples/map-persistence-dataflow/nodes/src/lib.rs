@@ -0,0 +1,396 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{Context, bail, eyre};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Cell values, like in a ROS `nav_msgs/OccupancyGrid`.
+pub const UNKNOWN: i8 = -1;
+pub const FREE: i8 = 0;
+pub const OCCUPIED: i8 = 100;
+
+/// Metadata keys of the `scan` output of the robot.
+pub const STEP_KEY: &str = "step";
+pub const X_KEY: &str = "x";
+pub const Y_KEY: &str = "y";
+pub const THETA_KEY: &str = "theta";
+pub const MAX_RANGE_KEY: &str = "max_range";
+/// Metadata keys of the `patch` and `map` outputs of the mapper. The checksum is the `u64`
+/// of [`OccupancyGrid::checksum`], stored in the bits of an integer parameter.
+pub const VERSION_KEY: &str = "version";
+pub const CHECKSUM_KEY: &str = "checksum";
+pub const WIDTH_KEY: &str = "width";
+pub const HEIGHT_KEY: &str = "height";
+pub const RESOLUTION_KEY: &str = "resolution";
+
+/// A cell index and its new value.
+pub type Change = (u32, i8);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OccupancyGrid {
+    pub width: u32,
+    pub height: u32,
+    /// Meters per cell. The origin is the corner of cell 0.
+    pub resolution: f64,
+    /// Row by row, starting at the origin.
+    pub cells: Vec<i8>,
+}
+
+impl OccupancyGrid {
+    pub fn new(width: u32, height: u32, resolution: f64) -> Self {
+        Self {
+            width,
+            height,
+            resolution,
+            cells: vec![UNKNOWN; width as usize * height as usize],
+        }
+    }
+
+    pub fn index(&self, x: f64, y: f64) -> Option<u32> {
+        let (col, row) = (x / self.resolution, y / self.resolution);
+        if col < 0.0 || row < 0.0 || col >= self.width as f64 || row >= self.height as f64 {
+            return None;
+        }
+        Some(row as u32 * self.width + col as u32)
+    }
+
+    /// Checksum of all cells, to compare maps without sending them.
+    pub fn checksum(&self) -> u64 {
+        fnv1a(self.cells.iter().map(|cell| *cell as u8))
+    }
+
+    pub fn known_cells(&self) -> usize {
+        self.cells.iter().filter(|cell| **cell != UNKNOWN).count()
+    }
+
+    /// The changes that a scan from `pose` (x, y, theta) makes to the map, without applying
+    /// them. `ranges` are evenly spread over a full turn, starting at `theta`, and a range of
+    /// `max_range` hit nothing.
+    ///
+    /// The cells along each ray are free, and the cell at its end is occupied. Walls don't move
+    /// in this world, so an occupied cell stays occupied, and scanning the same place twice
+    /// doesn't change the map.
+    pub fn integrate(&self, pose: (f64, f64, f64), ranges: &[f32], max_range: f64) -> Vec<Change> {
+        let (x, y, theta) = pose;
+        let mut scan: BTreeMap<u32, i8> = BTreeMap::new();
+        for (i, range) in ranges.iter().enumerate() {
+            let range = *range as f64;
+            let angle = theta + i as f64 * std::f64::consts::TAU / ranges.len() as f64;
+            let (dx, dy) = (angle.cos(), angle.sin());
+            let step = self.resolution / 2.0;
+            let mut t = 0.0;
+            while t < range {
+                if let Some(index) = self.index(x + t * dx, y + t * dy) {
+                    scan.entry(index).or_insert(FREE);
+                }
+                t += step;
+            }
+            if range < max_range {
+                // just behind the surface, in the cell of the obstacle
+                let t = range + step / 2.0;
+                if let Some(index) = self.index(x + t * dx, y + t * dy) {
+                    scan.insert(index, OCCUPIED);
+                }
+            }
+        }
+        scan.into_iter()
+            .filter(|(index, value)| {
+                let current = self.cells[*index as usize];
+                current != *value && current != OCCUPIED
+            })
+            .collect()
+    }
+
+    pub fn apply(&mut self, changes: &[Change]) -> eyre::Result<()> {
+        for (index, value) in changes {
+            let cell = self
+                .cells
+                .get_mut(*index as usize)
+                .ok_or_else(|| eyre!("cell {index} is outside of the map"))?;
+            *cell = *value;
+        }
+        Ok(())
+    }
+}
+
+/// A map and the number of patches that it was built from.
+#[derive(Debug, Clone)]
+pub struct VersionedMap {
+    pub version: u64,
+    pub grid: OccupancyGrid,
+}
+
+/// What [`MapStore::open`] found on disk.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Restored {
+    /// The version of the restored map, 0 if there was none.
+    pub version: u64,
+    pub checksum: u64,
+    pub known_cells: usize,
+    pub snapshot_version: Option<u64>,
+    /// Patches applied on top of the snapshot.
+    pub replayed: u64,
+    /// Bytes of an incomplete or corrupted patch at the end of the log, which were cut off.
+    pub discarded_bytes: u64,
+}
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"DORAMAP1";
+const SNAPSHOT_FILE: &str = "snapshot.bin";
+const LOG_FILE: &str = "patches.log";
+
+/// Persists a map as a snapshot and a log of the patches since then.
+///
+/// Every patch is appended to the log and flushed before it's sent, which is cheap, as a patch
+/// is only the cells that changed. Every few patches, the whole map is written as a new
+/// snapshot, and the log starts over, so that a restart never has to replay many patches.
+pub struct MapStore {
+    dir: PathBuf,
+    log: File,
+    patches_since_snapshot: u64,
+}
+
+impl MapStore {
+    /// Opens the store in `dir`, and restores the map from its snapshot and log. Starts an
+    /// empty map if `dir` has none.
+    pub fn open(dir: &Path, empty: OccupancyGrid) -> eyre::Result<(Self, VersionedMap, Restored)> {
+        std::fs::create_dir_all(dir)?;
+        let mut map = VersionedMap {
+            version: 0,
+            grid: empty,
+        };
+        let mut restored = Restored::default();
+
+        let snapshot_path = dir.join(SNAPSHOT_FILE);
+        if snapshot_path.exists() {
+            let snapshot = decode_snapshot(&std::fs::read(&snapshot_path)?)
+                .with_context(|| format!("invalid snapshot {}", snapshot_path.display()))?;
+            if (snapshot.grid.width, snapshot.grid.height) != (map.grid.width, map.grid.height)
+                || snapshot.grid.resolution != map.grid.resolution
+            {
+                bail!(
+                    "the stored map is {}x{} cells of {} m, expected {}x{} cells of {} m",
+                    snapshot.grid.width,
+                    snapshot.grid.height,
+                    snapshot.grid.resolution,
+                    map.grid.width,
+                    map.grid.height,
+                    map.grid.resolution
+                );
+            }
+            restored.snapshot_version = Some(snapshot.version);
+            map = snapshot;
+        }
+
+        let log_path = dir.join(LOG_FILE);
+        let mut bytes = Vec::new();
+        if log_path.exists() {
+            File::open(&log_path)?.read_to_end(&mut bytes)?;
+        }
+        let mut valid = 0;
+        while let Some((record, len)) = decode_record(&bytes[valid..]) {
+            valid += len;
+            // patches that are already part of the snapshot, if the process stopped between
+            // writing the snapshot and clearing the log
+            if record.version <= map.version {
+                continue;
+            }
+            if record.version != map.version + 1 {
+                bail!(
+                    "patch {} follows version {} in {}",
+                    record.version,
+                    map.version,
+                    log_path.display()
+                );
+            }
+            map.grid.apply(&record.changes)?;
+            if map.grid.checksum() != record.checksum {
+                bail!("the map differs after replaying patch {}", record.version);
+            }
+            map.version = record.version;
+            restored.replayed += 1;
+        }
+        // a patch that was only partly written when the process died
+        restored.discarded_bytes = (bytes.len() - valid) as u64;
+
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .with_context(|| format!("failed to open {}", log_path.display()))?;
+        log.set_len(valid as u64)?;
+        restored.version = map.version;
+        restored.checksum = map.grid.checksum();
+        restored.known_cells = map.grid.known_cells();
+        let store = Self {
+            dir: dir.to_owned(),
+            log,
+            patches_since_snapshot: restored.replayed,
+        };
+        Ok((store, map, restored))
+    }
+
+    /// Appends the patch that turned the map into `map`, and flushes it to disk.
+    pub fn append(&mut self, map: &VersionedMap, changes: &[Change]) -> eyre::Result<()> {
+        self.log
+            .write_all(&encode_record(map.version, map.grid.checksum(), changes))?;
+        self.log.sync_data()?;
+        self.patches_since_snapshot += 1;
+        Ok(())
+    }
+
+    pub fn patches_since_snapshot(&self) -> u64 {
+        self.patches_since_snapshot
+    }
+
+    /// Writes `map` as the new snapshot and clears the log.
+    ///
+    /// The snapshot is written to a temporary file and renamed, so a crash leaves either the
+    /// old or the new snapshot, and the log is only cleared once the new one is on disk.
+    pub fn snapshot(&mut self, map: &VersionedMap) -> eyre::Result<()> {
+        let path = self.dir.join(SNAPSHOT_FILE);
+        let temp = path.with_extension("tmp");
+        let mut file =
+            File::create(&temp).with_context(|| format!("failed to create {}", temp.display()))?;
+        file.write_all(&encode_snapshot(map))?;
+        file.sync_all()?;
+        std::fs::rename(&temp, &path)?;
+        #[cfg(unix)]
+        File::open(&self.dir)?.sync_all()?;
+
+        self.log.set_len(0)?;
+        self.log.sync_all()?;
+        self.patches_since_snapshot = 0;
+        Ok(())
+    }
+}
+
+/// Magic, version, width, height, resolution, the cells, and a checksum of everything before.
+fn encode_snapshot(map: &VersionedMap) -> Vec<u8> {
+    let grid = &map.grid;
+    let mut bytes = Vec::with_capacity(40 + grid.cells.len());
+    bytes.extend_from_slice(SNAPSHOT_MAGIC);
+    bytes.extend_from_slice(&map.version.to_le_bytes());
+    bytes.extend_from_slice(&grid.width.to_le_bytes());
+    bytes.extend_from_slice(&grid.height.to_le_bytes());
+    bytes.extend_from_slice(&grid.resolution.to_le_bytes());
+    bytes.extend(grid.cells.iter().map(|cell| *cell as u8));
+    let checksum = fnv1a(bytes.iter().copied());
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    bytes
+}
+
+fn decode_snapshot(bytes: &[u8]) -> eyre::Result<VersionedMap> {
+    let Some((content, checksum)) = bytes.split_last_chunk::<8>() else {
+        bail!("file too short");
+    };
+    if fnv1a(content.iter().copied()) != u64::from_le_bytes(*checksum) {
+        bail!("checksum mismatch");
+    }
+    if content.len() < 32 || &content[..8] != SNAPSHOT_MAGIC {
+        bail!("not a map snapshot");
+    }
+    let version = u64::from_le_bytes(content[8..16].try_into()?);
+    let width = u32::from_le_bytes(content[16..20].try_into()?);
+    let height = u32::from_le_bytes(content[20..24].try_into()?);
+    let resolution = f64::from_le_bytes(content[24..32].try_into()?);
+    let cells: Vec<i8> = content[32..].iter().map(|cell| *cell as i8).collect();
+    if cells.len() != width as usize * height as usize {
+        bail!("expected {width}x{height} cells");
+    }
+    Ok(VersionedMap {
+        version,
+        grid: OccupancyGrid {
+            width,
+            height,
+            resolution,
+            cells,
+        },
+    })
+}
+
+struct Record {
+    version: u64,
+    /// Of the map after the patch.
+    checksum: u64,
+    changes: Vec<Change>,
+}
+
+/// Length, then version, map checksum, number of changes, the changes, and a checksum of the
+/// record.
+fn encode_record(version: u64, checksum: u64, changes: &[Change]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(20 + changes.len() * 5);
+    body.extend_from_slice(&version.to_le_bytes());
+    body.extend_from_slice(&checksum.to_le_bytes());
+    body.extend_from_slice(&(changes.len() as u32).to_le_bytes());
+    for (index, value) in changes {
+        body.extend_from_slice(&index.to_le_bytes());
+        body.push(*value as u8);
+    }
+    let mut record = Vec::with_capacity(body.len() + 12);
+    record.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    record.extend_from_slice(&body);
+    record.extend_from_slice(&fnv1a(body.iter().copied()).to_le_bytes());
+    record
+}
+
+/// The record at the start of `bytes` and its length, or `None` if it's incomplete or
+/// corrupted.
+fn decode_record(bytes: &[u8]) -> Option<(Record, usize)> {
+    let len = u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as usize;
+    let body = bytes.get(4..4 + len)?;
+    let checksum = u64::from_le_bytes(bytes.get(4 + len..12 + len)?.try_into().ok()?);
+    if fnv1a(body.iter().copied()) != checksum || body.len() < 20 {
+        return None;
+    }
+    let count = u32::from_le_bytes(body[16..20].try_into().ok()?) as usize;
+    let entries = &body[20..];
+    if entries.len() != count * 5 {
+        return None;
+    }
+    let changes = entries
+        .chunks_exact(5)
+        .map(|entry| {
+            (
+                u32::from_le_bytes(entry[..4].try_into().unwrap()),
+                entry[4] as i8,
+            )
+        })
+        .collect();
+    let record = Record {
+        version: u64::from_le_bytes(body[..8].try_into().ok()?),
+        checksum: u64::from_le_bytes(body[8..16].try_into().ok()?),
+        changes,
+    };
+    Some((record, 12 + len))
+}
+
+fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
+
+pub fn float_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<f64> {
+    match parameters.get(key) {
+        Some(Parameter::Float(value)) => Ok(*value),
+        Some(other) => bail!("expected float `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
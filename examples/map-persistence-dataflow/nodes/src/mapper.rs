@@ -0,0 +1,185 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter,
+    arrow::{
+        array::{ArrayRef, AsArray, Int8Array, StructArray, UInt32Array},
+        datatypes::{DataType, Field, Float32Type},
+    },
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use map_persistence_dataflow_nodes::{
+    CHECKSUM_KEY, Change, HEIGHT_KEY, MAX_RANGE_KEY, MapStore, OccupancyGrid, RESOLUTION_KEY,
+    Restored, THETA_KEY, VERSION_KEY, VersionedMap, WIDTH_KEY, X_KEY, Y_KEY, env_or,
+    float_parameter, write_json,
+};
+use serde::Serialize;
+use std::{path::PathBuf, sync::Arc};
+
+#[derive(Debug, Serialize)]
+struct VersionChecksum {
+    version: u64,
+    checksum: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct MapperReport {
+    restored: Restored,
+    version: u64,
+    checksum: u64,
+    known_cells: usize,
+    /// The checksum after every patch of this run.
+    patches: Vec<VersionChecksum>,
+    snapshots: u64,
+    /// Full maps sent to late subscribers.
+    served: u64,
+}
+
+/// Builds an occupancy grid from the `scan`s of the robot, and keeps it across restarts.
+///
+/// On start, restores the map from `MAP_DIR`, and continues from its version. Every scan that
+/// changes the map is a patch: it's appended to the log in `MAP_DIR`, and sent on `patch`,
+/// with the version and checksum of the resulting map. Every `SNAPSHOT_EVERY` patches, the
+/// whole map is written as a snapshot. On every `sync` request, the whole map is sent on
+/// `map`, for subscribers that missed the earlier patches.
+///
+/// Stops when `scan` is closed, and writes what it did to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let map_dir: PathBuf = env_or("MAP_DIR", "state/map".into())?;
+    let empty = OccupancyGrid::new(
+        env_or("WIDTH", 80)?,
+        env_or("HEIGHT", 60)?,
+        env_or("RESOLUTION", 0.1)?,
+    );
+    let snapshot_every: u64 = env_or("SNAPSHOT_EVERY", 40)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/mapper.json".into())?;
+    let patch_output = DataId::from("patch".to_owned());
+    let map_output = DataId::from("map".to_owned());
+
+    let (mut store, mut map, restored) = MapStore::open(&map_dir, empty)?;
+    println!(
+        "restored version {} with {} known cells: snapshot {:?}, {} patches replayed, {} bytes \
+         discarded",
+        restored.version,
+        restored.known_cells,
+        restored.snapshot_version,
+        restored.replayed,
+        restored.discarded_bytes
+    );
+    let mut report = MapperReport {
+        restored,
+        version: map.version,
+        checksum: map.grid.checksum(),
+        known_cells: map.grid.known_cells(),
+        patches: Vec::new(),
+        snapshots: 0,
+        served: 0,
+    };
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "scan" => {
+                    let parameters = &metadata.parameters;
+                    let pose = (
+                        float_parameter(parameters, X_KEY)?,
+                        float_parameter(parameters, Y_KEY)?,
+                        float_parameter(parameters, THETA_KEY)?,
+                    );
+                    let max_range = float_parameter(parameters, MAX_RANGE_KEY)?;
+                    let ranges = data
+                        .as_primitive_opt::<Float32Type>()
+                        .ok_or_eyre("expected a Float32 array")?
+                        .values();
+                    let changes = map.grid.integrate(pose, ranges, max_range);
+                    if changes.is_empty() {
+                        continue;
+                    }
+
+                    map.grid.apply(&changes)?;
+                    map.version += 1;
+                    // persisted before it's sent, so that no subscriber has a newer map than
+                    // the one a restart restores
+                    store.append(&map, &changes)?;
+                    if store.patches_since_snapshot() >= snapshot_every {
+                        store.snapshot(&map)?;
+                        report.snapshots += 1;
+                    }
+                    let checksum = map.grid.checksum();
+                    node.send_output(
+                        patch_output.clone(),
+                        map_parameters(&map, checksum),
+                        patch_array(&changes),
+                    )?;
+                    report.patches.push(VersionChecksum {
+                        version: map.version,
+                        checksum,
+                    });
+                }
+                "sync" => {
+                    let subscribers = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    for subscriber in subscribers.iter().flatten() {
+                        println!("sending version {} to `{subscriber}`", map.version);
+                    }
+                    node.send_output(
+                        map_output.clone(),
+                        map_parameters(&map, map.grid.checksum()),
+                        Int8Array::from(map.grid.cells.clone()),
+                    )?;
+                    report.served += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "scan" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    report.version = map.version;
+    report.checksum = map.grid.checksum();
+    report.known_cells = map.grid.known_cells();
+    println!(
+        "version {} with {} known cells",
+        report.version, report.known_cells
+    );
+    write_json(&report_file, &report)?;
+    Ok(())
+}
+
+fn map_parameters(map: &VersionedMap, checksum: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(VERSION_KEY.into(), Parameter::Integer(map.version as i64));
+    parameters.insert(CHECKSUM_KEY.into(), Parameter::Integer(checksum as i64));
+    parameters.insert(WIDTH_KEY.into(), Parameter::Integer(map.grid.width.into()));
+    parameters.insert(
+        HEIGHT_KEY.into(),
+        Parameter::Integer(map.grid.height.into()),
+    );
+    parameters.insert(RESOLUTION_KEY.into(), Parameter::Float(map.grid.resolution));
+    parameters
+}
+
+/// One row per changed cell.
+fn patch_array(changes: &[Change]) -> StructArray {
+    let index = UInt32Array::from_iter_values(changes.iter().map(|(index, _)| *index));
+    let value = Int8Array::from_iter_values(changes.iter().map(|(_, value)| *value));
+    StructArray::from(vec![
+        (
+            Arc::new(Field::new("index", DataType::UInt32, false)),
+            Arc::new(index) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("value", DataType::Int8, false)),
+            Arc::new(value) as ArrayRef,
+        ),
+    ])
+}
@@ -0,0 +1,138 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::Float32Array,
+    dora_core::config::DataId,
+};
+use map_persistence_dataflow_nodes::{
+    MAX_RANGE_KEY, STEP_KEY, THETA_KEY, X_KEY, Y_KEY, env_or, write_json,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// An 8 x 6 m room with a wall that splits it into two, with a gap at the bottom, and a box in
+/// each half. Obstacles are `(x_min, y_min, x_max, y_max)` in meters.
+const OBSTACLES: [(f64, f64, f64, f64); 7] = [
+    // the outer walls
+    (0.0, 0.0, 8.0, 0.2),
+    (0.0, 5.8, 8.0, 6.0),
+    (0.0, 0.0, 0.2, 6.0),
+    (7.8, 0.0, 8.0, 6.0),
+    // the dividing wall
+    (4.0, 2.0, 4.2, 6.0),
+    // the boxes
+    (2.0, 2.0, 2.6, 4.0),
+    (5.5, 2.5, 6.3, 3.3),
+];
+/// Through the left half, under the dividing wall, and through the right half.
+const ROUTE: [(f64, f64); 7] = [
+    (1.0, 1.0),
+    (1.0, 5.0),
+    (3.2, 5.0),
+    (3.2, 1.0),
+    (7.0, 1.0),
+    (7.0, 5.0),
+    (5.0, 5.0),
+];
+/// Meters per step.
+const STEP_LENGTH: f64 = 0.1;
+
+/// Where the robot is on its route, kept across runs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RobotState {
+    step: u64,
+}
+
+/// Simulates a robot with a 2D laser scanner driving along its route.
+///
+/// Moves one step on every `tick` and sends the ranges of `RAYS` rays around it on `scan`,
+/// with its pose in the metadata. Stops at the end of its route, or after `STEPS_PER_RUN`
+/// steps. It keeps its step in `STATE_FILE`, so that the next run continues where it stopped,
+/// like a real robot that stays where it is while its software restarts.
+fn main() -> eyre::Result<()> {
+    let steps_per_run: u64 = env_or("STEPS_PER_RUN", 110)?;
+    let rays: usize = env_or("RAYS", 90)?;
+    let max_range: f64 = env_or("MAX_RANGE", 3.0)?;
+    let state_file: PathBuf = env_or("STATE_FILE", "state/robot.json".into())?;
+    let output = DataId::from("scan".to_owned());
+
+    let mut state: RobotState = match std::fs::read_to_string(&state_file) {
+        Ok(json) => serde_json::from_str(&json)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => RobotState::default(),
+        Err(err) => return Err(err.into()),
+    };
+    let first_step = state.step;
+    println!("starting at step {first_step}");
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let Some((x, y, theta)) = pose(state.step) else {
+                        println!("reached the end of the route");
+                        break;
+                    };
+                    if state.step == first_step + steps_per_run {
+                        break;
+                    }
+                    let ranges: Vec<f32> = (0..rays)
+                        .map(|i| {
+                            let angle = theta + i as f64 * std::f64::consts::TAU / rays as f64;
+                            cast(x, y, angle, max_range) as f32
+                        })
+                        .collect();
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(STEP_KEY.into(), Parameter::Integer(state.step as i64));
+                    parameters.insert(X_KEY.into(), Parameter::Float(x));
+                    parameters.insert(Y_KEY.into(), Parameter::Float(y));
+                    parameters.insert(THETA_KEY.into(), Parameter::Float(theta));
+                    parameters.insert(MAX_RANGE_KEY.into(), Parameter::Float(max_range));
+                    node.send_output(output.clone(), parameters, Float32Array::from(ranges))?;
+
+                    state.step += 1;
+                    write_json(&state_file, &state)?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("drove from step {first_step} to step {}", state.step);
+    Ok(())
+}
+
+/// The position and heading after `step` steps, or `None` after the end of the route.
+fn pose(step: u64) -> Option<(f64, f64, f64)> {
+    let mut distance = step as f64 * STEP_LENGTH;
+    for segment in ROUTE.windows(2) {
+        let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+        let length = (x1 - x0).hypot(y1 - y0);
+        if distance < length {
+            let t = distance / length;
+            let heading = (y1 - y0).atan2(x1 - x0);
+            return Some((x0 + t * (x1 - x0), y0 + t * (y1 - y0), heading));
+        }
+        distance -= length;
+    }
+    None
+}
+
+/// The distance to the nearest obstacle in the direction of `angle`, or `max_range`.
+fn cast(x: f64, y: f64, angle: f64, max_range: f64) -> f64 {
+    let (dx, dy) = (angle.cos(), angle.sin());
+    let mut t = 0.0;
+    while t < max_range {
+        let (px, py) = (x + t * dx, y + t * dy);
+        if OBSTACLES
+            .iter()
+            .any(|(x0, y0, x1, y1)| (*x0..*x1).contains(&px) && (*y0..*y1).contains(&py))
+        {
+            return t;
+        }
+        t += 0.01;
+    }
+    max_range
+}
@@ -0,0 +1,172 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, StringArray},
+        datatypes::{Int8Type, UInt32Type},
+    },
+    dora_core::config::DataId,
+};
+use eyre::{OptionExt, bail};
+use map_persistence_dataflow_nodes::{
+    CHECKSUM_KEY, Change, FREE, HEIGHT_KEY, OCCUPIED, OccupancyGrid, RESOLUTION_KEY, VERSION_KEY,
+    VersionedMap, WIDTH_KEY, env_or, float_parameter, integer_parameter, write_json,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Serialize)]
+struct ViewerReport {
+    /// Patches that arrived before the viewer had a map.
+    ignored: u64,
+    /// The version and known cells of the first full map.
+    snapshot_version: Option<u64>,
+    snapshot_known_cells: usize,
+    /// Full maps requested because a patch was missing.
+    resyncs: u64,
+    version: u64,
+    checksum: u64,
+    known_cells: usize,
+}
+
+/// A subscriber that joins late, like a monitoring UI that an operator opens while the robot
+/// is already mapping.
+///
+/// Ignores the first `JOIN_AFTER` patches, then requests the full map on `sync`, and keeps it
+/// up to date with the patches that follow. Requests the full map again if a patch is missing.
+/// Once `patch` is closed, writes the map as an image to `IMAGE_FILE`, and what it received
+/// to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let join_after: u64 = env_or("JOIN_AFTER", 30)?;
+    let image_file: PathBuf = env_or("IMAGE_FILE", "out/map.pgm".into())?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/viewer.json".into())?;
+    let sync_output = DataId::from("sync".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let request = StringArray::from(vec![node.id().to_string()]);
+
+    let mut map: Option<VersionedMap> = None;
+    let mut report = ViewerReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "patch" => {
+                    let version = integer_parameter(&metadata.parameters, VERSION_KEY)? as u64;
+                    let Some(current) = &mut map else {
+                        report.ignored += 1;
+                        if report.ignored == join_after {
+                            println!("joining at version {version}");
+                            node.send_output(
+                                sync_output.clone(),
+                                Default::default(),
+                                request.clone(),
+                            )?;
+                        }
+                        continue;
+                    };
+                    if version <= current.version {
+                        // already part of the full map
+                        continue;
+                    }
+                    if version != current.version + 1 {
+                        println!(
+                            "missed the patches between version {} and {version}, requesting \
+                             the full map",
+                            current.version
+                        );
+                        map = None;
+                        report.resyncs += 1;
+                        node.send_output(sync_output.clone(), Default::default(), request.clone())?;
+                        continue;
+                    }
+                    let rows = data.as_struct_opt().ok_or_eyre("expected a struct array")?;
+                    let indices = rows
+                        .column_by_name("index")
+                        .and_then(|column| column.as_primitive_opt::<UInt32Type>())
+                        .ok_or_eyre("expected a UInt32 `index` column")?;
+                    let values = rows
+                        .column_by_name("value")
+                        .and_then(|column| column.as_primitive_opt::<Int8Type>())
+                        .ok_or_eyre("expected an Int8 `value` column")?;
+                    let changes: Vec<Change> = indices
+                        .values()
+                        .iter()
+                        .copied()
+                        .zip(values.values().iter().copied())
+                        .collect();
+                    current.grid.apply(&changes)?;
+                    current.version = version;
+                    let checksum = integer_parameter(&metadata.parameters, CHECKSUM_KEY)? as u64;
+                    if current.grid.checksum() != checksum {
+                        bail!("the map differs from the mapper's after patch {version}");
+                    }
+                }
+                "map" => {
+                    let version = integer_parameter(&metadata.parameters, VERSION_KEY)? as u64;
+                    if map.as_ref().is_some_and(|map| map.version >= version) {
+                        continue;
+                    }
+                    let cells = data
+                        .as_primitive_opt::<Int8Type>()
+                        .ok_or_eyre("expected an Int8 array")?
+                        .values()
+                        .to_vec();
+                    let grid = OccupancyGrid {
+                        width: integer_parameter(&metadata.parameters, WIDTH_KEY)?.try_into()?,
+                        height: integer_parameter(&metadata.parameters, HEIGHT_KEY)?.try_into()?,
+                        resolution: float_parameter(&metadata.parameters, RESOLUTION_KEY)?,
+                        cells,
+                    };
+                    let checksum = integer_parameter(&metadata.parameters, CHECKSUM_KEY)? as u64;
+                    if grid.checksum() != checksum {
+                        bail!("the full map of version {version} doesn't match its checksum");
+                    }
+                    println!(
+                        "received the full map of version {version}, {} known cells",
+                        grid.known_cells()
+                    );
+                    if report.snapshot_version.is_none() {
+                        report.snapshot_version = Some(version);
+                        report.snapshot_known_cells = grid.known_cells();
+                    }
+                    map = Some(VersionedMap { version, grid });
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "patch" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if let Some(map) = &map {
+        report.version = map.version;
+        report.checksum = map.grid.checksum();
+        report.known_cells = map.grid.known_cells();
+        write_pgm(&image_file, &map.grid)?;
+    }
+    write_json(&report_file, &report)?;
+    Ok(())
+}
+
+/// Writes the map as a grayscale PGM image, with free cells white, occupied cells black, and
+/// unknown cells gray, and the origin at the bottom left.
+fn write_pgm(path: &Path, grid: &OccupancyGrid) -> eyre::Result<()> {
+    let mut image = format!("P5\n{} {}\n255\n", grid.width, grid.height).into_bytes();
+    for row in grid.cells.chunks(grid.width as usize).rev() {
+        image.extend(row.iter().map(|cell| match *cell {
+            FREE => 255,
+            OCCUPIED => 0,
+            _ => 128,
+        }));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, image)?;
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use std::{path::Path, process::Stdio, time::Duration};
+use tokio::io::AsyncWriteExt;
+
+const FIRST_PORT: u16 = 47101;
+const SECOND_PORT: u16 = 47102;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("mdns-discovery-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    // the sensor app is not part of the dataflow, the bridge only knows its service type
+    let mut sensor = tokio::process::Command::new("nodes/target/release/sensor-app")
+        .args(["--port", &FIRST_PORT.to_string()])
+        .args(["--instance", "demo-sensor"])
+        .stdin(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start sensor app")?;
+    let mut commands = sensor.stdin.take().unwrap();
+
+    let script = async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        println!("moving the sensor app to port {SECOND_PORT}");
+        commands
+            .write_all(format!("move {SECOND_PORT}\n").as_bytes())
+            .await?;
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        commands.write_all(b"quit\n").await?;
+        eyre::Ok(())
+    };
+    let (run, script) = tokio::join!(dora.run_dataflow(dataflow), script);
+    script.context("failed to control the sensor app")?;
+    run?;
+
+    if !sensor.wait().await?.success() {
+        bail!("sensor app failed");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, MetadataParameters, Parameter, dora_core::config::DataId,
+};
+use eyre::{Context, bail};
+use mdns_discovery_dataflow_nodes::{
+    GOODBYE, SEQ_KEY, SERVICE_TYPE, SOURCE_ADDR_KEY, parse_reading,
+};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::{
+    io::{BufRead, BufReader, ErrorKind},
+    net::{SocketAddr, TcpStream},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+/// Delay between connection attempts to an announced address that doesn't accept connections.
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+enum Update {
+    Connected(SocketAddr),
+    Disconnected(SocketAddr),
+    Reading {
+        seq: u64,
+        value: f64,
+        addr: SocketAddr,
+    },
+    /// The sensor app shut down for good.
+    Goodbye,
+}
+
+struct Connection {
+    addr: SocketAddr,
+    reader: BufReader<TcpStream>,
+    /// Kept across reads, a read that times out may have consumed part of a line.
+    line: String,
+}
+
+/// Forwards the readings of a sensor app that is found through mDNS.
+///
+/// The address of the sensor app is never configured. The bridge browses for `SERVICE_TYPE`
+/// and connects to whatever address is announced. If the announcement changes, or the
+/// connection is lost, it connects to the newly announced address.
+fn main() -> eyre::Result<()> {
+    let instance = std::env::var("INSTANCE").ok();
+    let output = DataId::from("reading".to_owned());
+
+    let (updates_tx, updates) = mpsc::channel();
+    std::thread::spawn(move || {
+        if let Err(err) = discover(instance.as_deref(), updates_tx) {
+            eprintln!("discovery failed: {err:?}");
+        }
+    });
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut disconnected_at: Option<Instant> = None;
+    let mut finished = false;
+    let mut readings = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => loop {
+                    let update = match updates.try_recv() {
+                        Ok(update) => update,
+                        Err(mpsc::TryRecvError::Empty) => break,
+                        Err(mpsc::TryRecvError::Disconnected) => {
+                            bail!("discovery thread stopped")
+                        }
+                    };
+                    match update {
+                        Update::Reading { seq, value, addr } => {
+                            let mut parameters = MetadataParameters::default();
+                            parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+                            parameters.insert(
+                                SOURCE_ADDR_KEY.into(),
+                                Parameter::String(addr.to_string()),
+                            );
+                            node.send_output(output.clone(), parameters, value.into_arrow())?;
+                            readings += 1;
+                        }
+                        Update::Connected(addr) => match disconnected_at.take() {
+                            Some(time) => {
+                                println!("reconnected to {addr} after {:?}", time.elapsed())
+                            }
+                            None => println!("connected to {addr}"),
+                        },
+                        Update::Disconnected(addr) => {
+                            println!("lost connection to {addr}, waiting for an announcement");
+                            disconnected_at = Some(Instant::now());
+                        }
+                        Update::Goodbye => {
+                            println!("sensor app shut down");
+                            finished = true;
+                        }
+                    }
+                },
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+        if finished {
+            break;
+        }
+    }
+
+    println!("forwarded {readings} readings");
+    Ok(())
+}
+
+/// Browses for the sensor app and reads from the announced address until it says goodbye.
+fn discover(instance: Option<&str>, updates: mpsc::Sender<Update>) -> eyre::Result<()> {
+    let mdns = ServiceDaemon::new().context("failed to start mDNS daemon")?;
+    let browser = mdns.browse(SERVICE_TYPE)?;
+    let is_sensor =
+        |fullname: &str| instance.is_none_or(|i| fullname.starts_with(&format!("{i}.")));
+
+    let mut announced: Option<SocketAddr> = None;
+    let mut connection: Option<Connection> = None;
+    let mut retry_at = Instant::now();
+    loop {
+        while let Ok(event) = browser.try_recv() {
+            match event {
+                ServiceEvent::ServiceResolved(info) if is_sensor(info.get_fullname()) => {
+                    let Some(addr) = socket_addr(&info) else {
+                        continue;
+                    };
+                    if announced != Some(addr) {
+                        println!("`{}` is announced at {addr}", info.get_fullname());
+                        announced = Some(addr);
+                        retry_at = Instant::now();
+                    }
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) if is_sensor(&fullname) => {
+                    println!("`{fullname}` was removed");
+                    announced = None;
+                }
+                _ => {}
+            }
+        }
+
+        // a connection to an address that is no longer announced points to a stale endpoint
+        if let Some(current) = &connection
+            && announced.is_some_and(|addr| addr != current.addr)
+        {
+            let _ = updates.send(Update::Disconnected(current.addr));
+            connection = None;
+        }
+
+        let Some(current) = &mut connection else {
+            match announced {
+                Some(addr) if Instant::now() >= retry_at => {
+                    match TcpStream::connect_timeout(&addr, Duration::from_secs(1)) {
+                        Ok(stream) => {
+                            stream.set_read_timeout(Some(Duration::from_millis(100)))?;
+                            connection = Some(Connection {
+                                addr,
+                                reader: BufReader::new(stream),
+                                line: String::new(),
+                            });
+                            updates.send(Update::Connected(addr))?;
+                        }
+                        Err(err) => {
+                            eprintln!("failed to connect to {addr}: {err}");
+                            retry_at = Instant::now() + RETRY_INTERVAL;
+                        }
+                    }
+                }
+                _ => std::thread::sleep(Duration::from_millis(50)),
+            }
+            continue;
+        };
+
+        match current.reader.read_line(&mut current.line) {
+            Ok(0) => {
+                updates.send(Update::Disconnected(current.addr))?;
+                connection = None;
+                retry_at = Instant::now() + RETRY_INTERVAL;
+            }
+            Ok(_) => {
+                if current.line.trim() == GOODBYE {
+                    updates.send(Update::Goodbye)?;
+                    let _ = mdns.shutdown();
+                    return Ok(());
+                }
+                let (seq, value) = parse_reading(&current.line)?;
+                current.line.clear();
+                updates.send(Update::Reading {
+                    seq,
+                    value,
+                    addr: current.addr,
+                })?;
+            }
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(err) => {
+                eprintln!("failed to read from {}: {err}", current.addr);
+                updates.send(Update::Disconnected(current.addr))?;
+                connection = None;
+                retry_at = Instant::now() + RETRY_INTERVAL;
+            }
+        }
+    }
+}
+
+/// Picks the address to connect to, preferring IPv4.
+fn socket_addr(info: &ServiceInfo) -> Option<SocketAddr> {
+    let addresses = info.get_addresses();
+    let ip = addresses
+        .iter()
+        .filter(|ip| ip.is_ipv4())
+        .min()
+        .or_else(|| addresses.iter().min())?;
+    Some(SocketAddr::new(*ip, info.get_port()))
+}
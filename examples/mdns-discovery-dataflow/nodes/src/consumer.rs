@@ -0,0 +1,60 @@
+use dora_node_api::{self, DoraNode, Event, Parameter};
+use eyre::{Context, bail};
+use mdns_discovery_dataflow_nodes::{SEQ_KEY, SOURCE_ADDR_KEY, env_or};
+use std::collections::BTreeMap;
+
+/// Counts the readings per source address, and the readings that were lost while the bridge
+/// was looking for the sensor app.
+fn main() -> eyre::Result<()> {
+    let expected_addresses: usize = env_or("EXPECTED_ADDRESSES", 1)?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut per_address: BTreeMap<String, u64> = BTreeMap::new();
+    let mut last_seq = None;
+    let mut lost = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                f64::try_from(&data).context("expected a float64 reading")?;
+                let (Some(Parameter::Integer(seq)), Some(Parameter::String(addr))) = (
+                    metadata.parameters.get(SEQ_KEY),
+                    metadata.parameters.get(SOURCE_ADDR_KEY),
+                ) else {
+                    bail!("`{id}` reading without `{SEQ_KEY}` and `{SOURCE_ADDR_KEY}`");
+                };
+                let seq = *seq as u64;
+                if let Some(last_seq) = last_seq {
+                    if seq <= last_seq {
+                        bail!("reading {seq} arrived after {last_seq}");
+                    }
+                    if seq > last_seq + 1 {
+                        println!("lost readings {}..{seq} while reconnecting", last_seq + 1);
+                        lost += seq - last_seq - 1;
+                    }
+                }
+                last_seq = Some(seq);
+
+                let count = per_address.entry(addr.clone()).or_default();
+                if *count == 0 {
+                    println!("receiving readings from {addr}");
+                }
+                *count += 1;
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    for (addr, count) in &per_address {
+        println!("{addr}: {count} readings");
+    }
+    println!("lost {lost} readings while reconnecting");
+    if per_address.len() < expected_addresses {
+        bail!(
+            "received readings from {} addresses, expected {expected_addresses}",
+            per_address.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use eyre::eyre;
+
+pub use example_node_utils::env_or;
+
+/// mDNS service type that the sensor app announces and the bridge browses for.
+pub const SERVICE_TYPE: &str = "_dora-sensor._tcp.local.";
+
+/// Line that the sensor app sends before it shuts down for good, as opposed to moving.
+pub const GOODBYE: &str = "bye";
+
+/// Metadata key of the address that a reading was received from.
+pub const SOURCE_ADDR_KEY: &str = "source_addr";
+/// Metadata key of the sequence number that the sensor app assigned to a reading.
+pub const SEQ_KEY: &str = "seq";
+
+/// Formats a reading as a line of the sensor protocol.
+pub fn format_reading(seq: u64, value: f64) -> String {
+    format!("{seq} {value}\n")
+}
+
+pub fn parse_reading(line: &str) -> eyre::Result<(u64, f64)> {
+    let (seq, value) = line
+        .trim()
+        .split_once(' ')
+        .ok_or_else(|| eyre!("invalid reading `{line}`"))?;
+    Ok((seq.parse()?, value.parse()?))
+}
@@ -0,0 +1,153 @@
+//! A sensor app outside of dora that announces its TCP endpoint over mDNS.
+//!
+//! Usage: `sensor-app --port <port> [--instance <name>]`
+//!
+//! Commands on stdin:
+//! - `move <port>`: closes the current endpoint and announces a new one on `<port>`,
+//! - `quit`: says goodbye to the connected client and exits. Closing stdin does the same.
+
+use eyre::{Context, bail};
+use mdns_discovery_dataflow_nodes::{GOODBYE, SERVICE_TYPE, format_reading};
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use std::{
+    io::{BufRead, ErrorKind, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+/// Interval between two readings.
+const PERIOD: Duration = Duration::from_millis(20);
+
+enum Command {
+    Move(u16),
+    Quit,
+}
+
+/// The currently announced endpoint.
+struct Endpoint {
+    listener: TcpListener,
+    fullname: String,
+}
+
+impl Endpoint {
+    fn open(mdns: &ServiceDaemon, instance: &str, port: u16) -> eyre::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .with_context(|| format!("failed to listen on port {port}"))?;
+        listener.set_nonblocking(true)?;
+
+        // announce all addresses of this host, so that clients on other machines find us too
+        let info = ServiceInfo::new(
+            SERVICE_TYPE,
+            instance,
+            &format!("{instance}.local."),
+            "",
+            port,
+            &[("protocol", "lines")][..],
+        )?
+        .enable_addr_auto();
+        let fullname = info.get_fullname().to_owned();
+        mdns.register(info)
+            .context("failed to register mDNS service")?;
+        println!("announced `{fullname}` on port {port}");
+        Ok(Self { listener, fullname })
+    }
+
+    fn close(self, mdns: &ServiceDaemon) -> eyre::Result<()> {
+        // a goodbye packet tells browsers to forget the address right away, instead of
+        // waiting for the record to expire
+        let status = mdns.unregister(&self.fullname)?;
+        let _ = status.recv_timeout(Duration::from_secs(1));
+        Ok(())
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let mut port = None;
+    let mut instance = "demo-sensor".to_owned();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => port = args.next().map(|v| v.parse::<u16>()).transpose()?,
+            "--instance" => instance = args.next().unwrap_or(instance),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    let Some(port) = port else {
+        bail!("usage: sensor-app --port <port> [--instance <name>]");
+    };
+
+    let (commands_tx, commands) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            let command = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                ["move", port] => match port.parse() {
+                    Ok(port) => Command::Move(port),
+                    Err(err) => {
+                        eprintln!("invalid port `{port}`: {err}");
+                        continue;
+                    }
+                },
+                ["quit"] => Command::Quit,
+                _ => {
+                    eprintln!("unknown command `{line}`");
+                    continue;
+                }
+            };
+            if commands_tx.send(command).is_err() {
+                break;
+            }
+        }
+        let _ = commands_tx.send(Command::Quit);
+    });
+
+    let mdns = ServiceDaemon::new().context("failed to start mDNS daemon")?;
+    let mut endpoint = Endpoint::open(&mdns, &instance, port)?;
+    let mut client: Option<TcpStream> = None;
+    let mut seq = 0u64;
+    let start = Instant::now();
+
+    loop {
+        match commands.try_recv() {
+            Ok(Command::Move(port)) => {
+                println!("moving to port {port}");
+                // drop the client, it has to find the new endpoint through mDNS
+                client = None;
+                endpoint.close(&mdns)?;
+                endpoint = Endpoint::open(&mdns, &instance, port)?;
+            }
+            Ok(Command::Quit) | Err(mpsc::TryRecvError::Disconnected) => break,
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+
+        match endpoint.listener.accept() {
+            Ok((stream, addr)) => {
+                println!("client connected from {addr}");
+                stream.set_nodelay(true)?;
+                client = Some(stream);
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err).context("failed to accept client"),
+        }
+
+        // readings are produced whether or not a client is connected, like a real sensor
+        let value = (start.elapsed().as_secs_f64() * 2.0).sin();
+        if let Some(stream) = &mut client
+            && let Err(err) = stream.write_all(format_reading(seq, value).as_bytes())
+        {
+            println!("client disconnected: {err}");
+            client = None;
+        }
+        seq += 1;
+        std::thread::sleep(PERIOD);
+    }
+
+    if let Some(mut stream) = client {
+        let _ = writeln!(stream, "{GOODBYE}");
+    }
+    endpoint.close(&mdns)?;
+    let _ = mdns.shutdown();
+    println!("sent {seq} readings");
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, OptionExt, bail, eyre};
+use std::{path::Path, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+/// Must match `HTTP_ADDR`, `WIDTH`, and `HEIGHT` in `dataflow.yml`.
+const HTTP_ADDR: &str = "127.0.0.1:8090";
+const WIDTH: u16 = 320;
+const HEIGHT: u16 = 240;
+/// Frames of the MJPEG stream to check.
+const FRAMES: usize = 5;
+/// Must match `BAR_SCALE` and the 5 pixels high font of the overlay.
+const BAR_HEIGHT: usize = 14;
+/// Pixels in the colors of the annotations that a frame must have at least. The smallest box
+/// of a label has about 400 pixels of outline, and the telemetry text about 900.
+const MIN_BOX_PIXELS: usize = 150;
+const MIN_TEXT_PIXELS: usize = 50;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("mjpeg-preview-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let dataflow_task = tokio::spawn(dora.run_dataflow(dataflow));
+    let preview = check_preview().await;
+    // the scene stops after `FRAMES`, which ends the dataflow
+    dataflow_task.await??;
+    preview?;
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Reads frames from the MJPEG stream like a browser would, and checks that they are
+/// complete JPEG images with the annotations of the overlay.
+async fn check_preview() -> eyre::Result<()> {
+    let start = tokio::time::Instant::now();
+    while !port_check::is_port_reachable(HTTP_ADDR) {
+        if start.elapsed() > Duration::from_secs(60) {
+            bail!("the overlay did not listen on {HTTP_ADDR}");
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    let index = get("/").await?;
+    if !String::from_utf8_lossy(&index).contains("/stream.mjpg") {
+        bail!("the preview page should show /stream.mjpg");
+    }
+
+    let mut stream = BufReader::new(TcpStream::connect(HTTP_ADDR).await?);
+    stream
+        .get_mut()
+        .write_all(b"GET /stream.mjpg HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await?;
+    let headers = read_headers(&mut stream).await?;
+    if !headers.starts_with("HTTP/1.1 200")
+        || !headers.contains("Content-Type: multipart/x-mixed-replace; boundary=frame")
+    {
+        bail!("/stream.mjpg should be a multipart stream with the `frame` boundary:\n{headers}");
+    }
+
+    let mut previous: Option<Vec<u8>> = None;
+    for i in 0..FRAMES {
+        let jpeg = tokio::time::timeout(Duration::from_secs(5), read_part(&mut stream))
+            .await
+            .wrap_err("timeout waiting for an MJPEG frame")??;
+        if previous.as_ref() == Some(&jpeg) {
+            bail!("frame {i} repeats the previous one");
+        }
+        let (boxes, text) = count_annotations(&jpeg)?;
+        println!(
+            "frame {i}: {} bytes, {boxes:?} box pixels (box, ball), {text} text pixels",
+            jpeg.len()
+        );
+        if boxes.iter().any(|&pixels| pixels < MIN_BOX_PIXELS) {
+            bail!("frame {i} should have the boxes of both labels");
+        }
+        if text < MIN_TEXT_PIXELS {
+            bail!("frame {i} should have the telemetry at the top");
+        }
+        previous = Some(jpeg);
+    }
+
+    let snapshot = get("/snapshot.jpg").await?;
+    if !snapshot.starts_with(&[0xff, 0xd8]) {
+        bail!("/snapshot.jpg did not return a JPEG image");
+    }
+    Ok(())
+}
+
+/// Reads one part of the MJPEG stream, and returns its JPEG image.
+async fn read_part(stream: &mut BufReader<TcpStream>) -> eyre::Result<Vec<u8>> {
+    let headers = read_headers(stream).await?;
+    let mut lines = headers.lines();
+    if lines.next() != Some("--frame") {
+        bail!("expected the `--frame` boundary, got:\n{headers}");
+    }
+    let mut length = None;
+    for line in lines {
+        match line.split_once(':') {
+            Some((name, value))
+                if name.eq_ignore_ascii_case("content-type") && value.trim() != "image/jpeg" =>
+            {
+                bail!("expected a JPEG part, got `{}`", value.trim());
+            }
+            Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                length = Some(value.trim().parse::<usize>()?);
+            }
+            _ => {}
+        }
+    }
+    let mut jpeg = vec![0; length.ok_or_eyre("part without Content-Length")?];
+    stream.read_exact(&mut jpeg).await?;
+    let mut end = [0; 2];
+    stream.read_exact(&mut end).await?;
+    if &end != b"\r\n" {
+        bail!("the part is longer than its Content-Length");
+    }
+    // start and end of image markers
+    if !jpeg.starts_with(&[0xff, 0xd8]) || !jpeg.ends_with(&[0xff, 0xd9]) {
+        bail!("the part is not a complete JPEG image");
+    }
+    Ok(jpeg)
+}
+
+/// Reads lines up to the next empty one, and returns them.
+async fn read_headers(stream: &mut BufReader<TcpStream>) -> eyre::Result<String> {
+    let mut headers = String::new();
+    loop {
+        let len = stream.read_line(&mut headers).await?;
+        if len == 0 {
+            bail!("the stream closed");
+        }
+        if headers.ends_with("\r\n\r\n") {
+            return Ok(headers.trim_end().to_owned());
+        }
+    }
+}
+
+/// Counts the pixels in the colors of the `box` and `ball` labels, and of the telemetry text
+/// in the bar at the top. The scene itself is gray.
+fn count_annotations(jpeg: &[u8]) -> eyre::Result<([usize; 2], usize)> {
+    let mut decoder = jpeg_decoder::Decoder::new(jpeg);
+    let pixels = decoder.decode().context("invalid JPEG image")?;
+    let info = decoder.info().ok_or_eyre("JPEG image without info")?;
+    if (info.width, info.height) != (WIDTH, HEIGHT)
+        || info.pixel_format != jpeg_decoder::PixelFormat::RGB24
+    {
+        bail!(
+            "expected a {WIDTH}x{HEIGHT} RGB image, got {}x{} {:?}",
+            info.width,
+            info.height,
+            info.pixel_format
+        );
+    }
+
+    let (mut boxes, mut text) = ([0; 2], 0);
+    for (i, pixel) in pixels.chunks_exact(3).enumerate() {
+        let [r, g, b] = [pixel[0], pixel[1], pixel[2]];
+        if i / WIDTH as usize >= BAR_HEIGHT {
+            if g > 150 && r < 100 && b < 100 {
+                boxes[0] += 1;
+            } else if r > 150 && b > 150 && g < 100 {
+                boxes[1] += 1;
+            }
+        } else if r > 150 && g > 150 && b < 100 {
+            text += 1;
+        }
+    }
+    Ok((boxes, text))
+}
+
+async fn get(path: &str) -> eyre::Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(HTTP_ADDR).await?;
+    stream
+        .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+        .await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    let body_start = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| eyre!("invalid response to {path}"))?
+        + 4;
+    if !response.starts_with(b"HTTP/1.1 200") {
+        bail!("{path} failed");
+    }
+    Ok(response.split_off(body_start))
+}
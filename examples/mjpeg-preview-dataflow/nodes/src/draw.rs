@@ -0,0 +1,132 @@
+//! Just enough drawing on `rgb8` images for the overlay, without an image library.
+
+pub type Color = [u8; 3];
+
+/// Width and height of the glyphs of [`FONT`], before scaling.
+pub const GLYPH_WIDTH: u32 = 3;
+pub const GLYPH_HEIGHT: u32 = 5;
+
+/// A 3x5 pixel font with the characters that the overlay needs. Each row is 3 bits, the
+/// highest bit is the left column. Lowercase letters are drawn as uppercase.
+const FONT: &[(char, [u8; 5])] = &[
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b001, 0b001, 0b001]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('A', [0b010, 0b101, 0b111, 0b101, 0b101]),
+    ('B', [0b110, 0b101, 0b110, 0b101, 0b110]),
+    ('C', [0b011, 0b100, 0b100, 0b100, 0b011]),
+    ('D', [0b110, 0b101, 0b101, 0b101, 0b110]),
+    ('E', [0b111, 0b100, 0b110, 0b100, 0b111]),
+    ('F', [0b111, 0b100, 0b110, 0b100, 0b100]),
+    ('G', [0b011, 0b100, 0b101, 0b101, 0b011]),
+    ('H', [0b101, 0b101, 0b111, 0b101, 0b101]),
+    ('I', [0b111, 0b010, 0b010, 0b010, 0b111]),
+    ('J', [0b001, 0b001, 0b001, 0b101, 0b010]),
+    ('K', [0b101, 0b101, 0b110, 0b101, 0b101]),
+    ('L', [0b100, 0b100, 0b100, 0b100, 0b111]),
+    ('M', [0b101, 0b111, 0b111, 0b101, 0b101]),
+    ('N', [0b110, 0b101, 0b101, 0b101, 0b101]),
+    ('O', [0b010, 0b101, 0b101, 0b101, 0b010]),
+    ('P', [0b110, 0b101, 0b110, 0b100, 0b100]),
+    ('Q', [0b010, 0b101, 0b101, 0b110, 0b011]),
+    ('R', [0b110, 0b101, 0b110, 0b101, 0b101]),
+    ('S', [0b011, 0b100, 0b010, 0b001, 0b110]),
+    ('T', [0b111, 0b010, 0b010, 0b010, 0b010]),
+    ('U', [0b101, 0b101, 0b101, 0b101, 0b111]),
+    ('V', [0b101, 0b101, 0b101, 0b101, 0b010]),
+    ('W', [0b101, 0b101, 0b111, 0b111, 0b101]),
+    ('X', [0b101, 0b101, 0b010, 0b101, 0b101]),
+    ('Y', [0b101, 0b101, 0b010, 0b010, 0b010]),
+    ('Z', [0b111, 0b001, 0b010, 0b100, 0b111]),
+    ('.', [0b000, 0b000, 0b000, 0b000, 0b010]),
+    (':', [0b000, 0b010, 0b000, 0b010, 0b000]),
+    ('%', [0b101, 0b001, 0b010, 0b100, 0b101]),
+    ('-', [0b000, 0b000, 0b111, 0b000, 0b000]),
+    ('/', [0b001, 0b001, 0b010, 0b100, 0b100]),
+];
+
+/// An `rgb8` image with tightly packed rows. Everything outside of it is clipped.
+pub struct Canvas<'a> {
+    pixels: &'a mut [u8],
+    width: u32,
+    height: u32,
+}
+
+impl<'a> Canvas<'a> {
+    /// Returns `None` if `pixels` doesn't have the size of a `width` x `height` image.
+    pub fn new(pixels: &'a mut [u8], width: u32, height: u32) -> Option<Self> {
+        (pixels.len() == width as usize * height as usize * 3).then_some(Self {
+            pixels,
+            width,
+            height,
+        })
+    }
+
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
+        let (x_end, y_end) = (
+            x.saturating_add(width).min(self.width),
+            y.saturating_add(height).min(self.height),
+        );
+        for row in y.min(y_end)..y_end {
+            let start = (row * self.width + x.min(x_end)) as usize * 3;
+            let end = (row * self.width + x_end) as usize * 3;
+            for pixel in self.pixels[start..end].chunks_exact_mut(3) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Draws the outline of a rectangle, `thickness` pixels inside of its bounds.
+    pub fn stroke_rect(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        thickness: u32,
+        color: Color,
+    ) {
+        let thickness = thickness.min(width).min(height);
+        self.fill_rect(x, y, width, thickness, color);
+        self.fill_rect(x, y + height - thickness, width, thickness, color);
+        self.fill_rect(x, y, thickness, height, color);
+        self.fill_rect(x + width - thickness, y, thickness, height, color);
+    }
+
+    /// Draws `text` with its top left corner at `x`, `y`, with every pixel of the font as a
+    /// `scale` x `scale` square. Characters that the font doesn't have are left blank.
+    pub fn text(&mut self, x: u32, y: u32, text: &str, scale: u32, color: Color) {
+        for (i, c) in text.chars().enumerate() {
+            let c = c.to_ascii_uppercase();
+            let Some((_, rows)) = FONT.iter().find(|(glyph, _)| *glyph == c) else {
+                continue;
+            };
+            let left = x + i as u32 * (GLYPH_WIDTH + 1) * scale;
+            for (row, bits) in rows.iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (0b100 >> column) != 0 {
+                        self.fill_rect(
+                            left + column * scale,
+                            y + row as u32 * scale,
+                            scale,
+                            scale,
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The width of `text` drawn with [`Canvas::text`].
+pub fn text_width(text: &str, scale: u32) -> u32 {
+    (text.chars().count() as u32 * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale
+}
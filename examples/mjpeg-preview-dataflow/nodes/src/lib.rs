@@ -0,0 +1,40 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::bail;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::env_or;
+
+pub mod draw;
+
+/// Metadata keys of the `image` output of the scene.
+pub const WIDTH_KEY: &str = "width";
+pub const HEIGHT_KEY: &str = "height";
+pub const ENCODING_KEY: &str = "encoding";
+/// Metadata key of the frame number, on `image`, `detections`, and `telemetry`.
+pub const FRAME_KEY: &str = "frame";
+
+/// A bounding box in pixels, sent as one JSON row per object on `detections`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Detection {
+    pub label: String,
+    pub score: f32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The state of the robot that carries the camera, sent as a JSON row on `telemetry`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Telemetry {
+    pub speed_mps: f64,
+    pub battery_pct: f64,
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
@@ -0,0 +1,301 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter,
+    arrow::{array::AsArray, datatypes::UInt8Type},
+};
+use eyre::{Context, OptionExt, bail};
+use jpeg_encoder::{ColorType, Encoder, SamplingFactor};
+use mjpeg_preview_dataflow_nodes::{
+    Detection, ENCODING_KEY, FRAME_KEY, HEIGHT_KEY, Telemetry, WIDTH_KEY,
+    draw::{Canvas, Color, GLYPH_HEIGHT, text_width},
+    env_or, integer_parameter,
+};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+const INDEX: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>dora preview</title></head>
+<body style="margin: 0; background: #222">
+<img src="/stream.mjpg" style="display: block; margin: auto; height: 100vh">
+</body>
+</html>
+"#;
+
+/// Colors of the annotations, which the scene never has.
+const BAR_COLOR: Color = [0, 0, 0];
+const TEXT_COLOR: Color = [255, 220, 0];
+const LABEL_TEXT_COLOR: Color = [0, 0, 0];
+const BOX_THICKNESS: u32 = 3;
+const BAR_SCALE: u32 = 2;
+
+/// The latest encoded frame, shared with the threads serving the HTTP clients.
+#[derive(Default)]
+struct Latest {
+    frame: Mutex<Frame>,
+    updated: Condvar,
+}
+
+#[derive(Default)]
+struct Frame {
+    seq: u64,
+    jpeg: Option<Arc<Vec<u8>>>,
+    closed: bool,
+}
+
+/// Draws the latest `detections` and `telemetry` onto every `image`, and serves the annotated
+/// frames on `HTTP_ADDR`, so that a browser can preview the dataflow without any plugin.
+///
+/// - `GET /` returns a page that shows the stream.
+/// - `GET /stream.mjpg` streams every frame as JPEG.
+/// - `GET /snapshot.jpg` returns the latest frame.
+///
+/// Stops when the `image` input closes.
+fn main() -> eyre::Result<()> {
+    let addr: String = env_or("HTTP_ADDR", "127.0.0.1:8090".to_owned())?;
+    let quality: u8 = env_or("JPEG_QUALITY", 85)?;
+
+    let latest = Arc::new(Latest::default());
+    let listener =
+        TcpListener::bind(&addr).with_context(|| format!("failed to listen on {addr}"))?;
+    println!("preview at http://{addr}/");
+    std::thread::spawn({
+        let latest = latest.clone();
+        move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        eprintln!("failed to accept connection: {err}");
+                        continue;
+                    }
+                };
+                let latest = latest.clone();
+                std::thread::spawn(move || {
+                    // errors are mostly browsers closing the stream
+                    if let Err(err) = serve(stream, &latest) {
+                        eprintln!("HTTP client failed: {err}");
+                    }
+                });
+            }
+        }
+    });
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut detections: Vec<Detection> = Vec::new();
+    let mut telemetry: Option<Telemetry> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let frame = integer_parameter(&metadata.parameters, FRAME_KEY)?;
+                    let width: u32 =
+                        integer_parameter(&metadata.parameters, WIDTH_KEY)?.try_into()?;
+                    let height: u32 =
+                        integer_parameter(&metadata.parameters, HEIGHT_KEY)?.try_into()?;
+                    match metadata.parameters.get(ENCODING_KEY) {
+                        Some(Parameter::String(encoding)) if encoding == "rgb8" => {}
+                        other => bail!("expected rgb8 image, got encoding {other:?}"),
+                    }
+                    let mut pixels = data
+                        .as_primitive_opt::<UInt8Type>()
+                        .ok_or_eyre("expected UInt8 image")?
+                        .values()
+                        .to_vec();
+                    let mut canvas = Canvas::new(&mut pixels, width, height)
+                        .ok_or_eyre("image size doesn't match its width and height")?;
+                    annotate(&mut canvas, frame, &detections, telemetry.as_ref());
+
+                    let mut jpeg = Vec::new();
+                    let mut encoder = Encoder::new(&mut jpeg, quality);
+                    // without chroma subsampling, the thin lines keep their colors
+                    encoder.set_sampling_factor(SamplingFactor::R_4_4_4);
+                    encoder.encode(
+                        &pixels,
+                        width.try_into()?,
+                        height.try_into()?,
+                        ColorType::Rgb,
+                    )?;
+
+                    let mut frame = latest.frame.lock().unwrap();
+                    frame.seq += 1;
+                    frame.jpeg = Some(Arc::new(jpeg));
+                    latest.updated.notify_all();
+                }
+                "detections" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    detections = rows
+                        .iter()
+                        .flatten()
+                        .map(serde_json::from_str)
+                        .collect::<Result<_, _>>()?;
+                }
+                "telemetry" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    if let Some(row) = rows.iter().flatten().last() {
+                        telemetry = Some(serde_json::from_str(row)?);
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "image" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let mut frame = latest.frame.lock().unwrap();
+    frame.closed = true;
+    latest.updated.notify_all();
+    println!("annotated {} frames", frame.seq);
+    Ok(())
+}
+
+/// Draws a box with a label per detection, and a bar with the telemetry at the top.
+fn annotate(
+    canvas: &mut Canvas,
+    frame: i64,
+    detections: &[Detection],
+    telemetry: Option<&Telemetry>,
+) {
+    for detection in detections {
+        let color = label_color(&detection.label);
+        canvas.stroke_rect(
+            detection.x,
+            detection.y,
+            detection.width,
+            detection.height,
+            BOX_THICKNESS,
+            color,
+        );
+        // above the box, or inside of it at the top of the image
+        let label = format!("{} {:.2}", detection.label, detection.score);
+        let tag_height = GLYPH_HEIGHT + 2;
+        let tag_y = detection.y.checked_sub(tag_height).unwrap_or(detection.y);
+        canvas.fill_rect(
+            detection.x,
+            tag_y,
+            text_width(&label, 1) + 2,
+            tag_height,
+            color,
+        );
+        canvas.text(detection.x + 1, tag_y + 1, &label, 1, LABEL_TEXT_COLOR);
+    }
+
+    let mut text = format!("frame {frame}");
+    if let Some(telemetry) = telemetry {
+        text += &format!(
+            "  speed {:.1} m/s  battery {:.0}%",
+            telemetry.speed_mps, telemetry.battery_pct
+        );
+    }
+    let bar_height = (GLYPH_HEIGHT + 2) * BAR_SCALE;
+    canvas.fill_rect(0, 0, u32::MAX, bar_height, BAR_COLOR);
+    canvas.text(BAR_SCALE, BAR_SCALE, &text, BAR_SCALE, TEXT_COLOR);
+}
+
+fn label_color(label: &str) -> Color {
+    match label {
+        "box" => [0, 220, 0],
+        "ball" => [230, 0, 230],
+        _ => [0, 200, 230],
+    }
+}
+
+fn serve(mut stream: TcpStream, latest: &Latest) -> eyre::Result<()> {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(&stream);
+    reader.read_line(&mut request_line)?;
+    // the headers are not needed
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+
+    match path {
+        "/" => respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            INDEX.as_bytes(),
+        ),
+        "/snapshot.jpg" => {
+            let jpeg = latest.frame.lock().unwrap().jpeg.clone();
+            match jpeg {
+                Some(jpeg) => respond(&mut stream, "200 OK", "image/jpeg", &jpeg),
+                None => respond(
+                    &mut stream,
+                    "503 Service Unavailable",
+                    "text/plain",
+                    b"no frame yet",
+                ),
+            }
+        }
+        "/stream.mjpg" => {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary=frame\r\n\
+                 Cache-Control: no-cache\r\nConnection: close\r\n\r\n"
+            )?;
+            let mut sent = 0;
+            loop {
+                let (seq, jpeg) = {
+                    let (frame, _) = latest
+                        .updated
+                        .wait_timeout_while(
+                            latest.frame.lock().unwrap(),
+                            Duration::from_secs(1),
+                            |frame| frame.seq == sent && !frame.closed,
+                        )
+                        .unwrap();
+                    if frame.closed {
+                        return Ok(());
+                    }
+                    (frame.seq, frame.jpeg.clone())
+                };
+                // a slow client skips frames instead of falling behind
+                let Some(jpeg) = jpeg.filter(|_| seq != sent) else {
+                    continue;
+                };
+                sent = seq;
+                write!(
+                    stream,
+                    "--frame\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                    jpeg.len()
+                )?;
+                stream.write_all(&jpeg)?;
+                stream.write_all(b"\r\n")?;
+            }
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", b"not found"),
+    }
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> eyre::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter,
+    arrow::array::{StringArray, UInt8Array},
+    dora_core::config::DataId,
+};
+use mjpeg_preview_dataflow_nodes::{
+    Detection, ENCODING_KEY, FRAME_KEY, HEIGHT_KEY, Telemetry, WIDTH_KEY, env_or,
+};
+
+/// An object that moves across the scene and bounces off its edges.
+struct Object {
+    label: &'static str,
+    size: f64,
+    shade: u8,
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+}
+
+impl Object {
+    fn step(&mut self, width: f64, height: f64) {
+        self.x += self.vx;
+        self.y += self.vy;
+        if self.x < 0.0 || self.x + self.size > width {
+            self.vx = -self.vx;
+            self.x = self.x.clamp(0.0, width - self.size);
+        }
+        if self.y < 0.0 || self.y + self.size > height {
+            self.vy = -self.vy;
+            self.y = self.y.clamp(0.0, height - self.size);
+        }
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        let (dx, dy) = (x - self.x, y - self.y);
+        let inside = (0.0..self.size).contains(&dx) && (0.0..self.size).contains(&dy);
+        match self.label {
+            "ball" => {
+                let radius = self.size / 2.0;
+                inside && (dx - radius).hypot(dy - radius) <= radius
+            }
+            _ => inside,
+        }
+    }
+}
+
+/// Renders a grayscale scene with objects moving across it on every `tick`, like a camera
+/// with a detector behind it, and stops after `FRAMES` frames.
+///
+/// Sends the bounding boxes of the objects on `detections`, the state of a simulated robot on
+/// `telemetry`, and then the frame as `WIDTH` x `HEIGHT` `rgb8` image on `image`, all with
+/// the same `frame` number. The scene has no colors, so that colors in the preview can only
+/// come from the overlay.
+fn main() -> eyre::Result<()> {
+    let width: u32 = env_or("WIDTH", 320)?;
+    let height: u32 = env_or("HEIGHT", 240)?;
+    let frames: u64 = env_or("FRAMES", 150)?;
+    let detections_output = DataId::from("detections".to_owned());
+    let telemetry_output = DataId::from("telemetry".to_owned());
+    let image_output = DataId::from("image".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let (w, h) = (width as f64, height as f64);
+    let mut objects = [
+        Object {
+            label: "box",
+            size: 48.0,
+            shade: 210,
+            x: w * 0.1,
+            y: h * 0.3,
+            vx: 3.0,
+            vy: 2.0,
+        },
+        Object {
+            label: "ball",
+            size: 40.0,
+            shade: 235,
+            x: w * 0.6,
+            y: h * 0.5,
+            vx: -2.5,
+            vy: 3.5,
+        },
+        Object {
+            label: "box",
+            size: 32.0,
+            shade: 20,
+            x: w * 0.4,
+            y: h * 0.7,
+            vx: 1.5,
+            vy: -1.0,
+        },
+    ];
+    let mut frame = 0u64;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    if frame == frames {
+                        break;
+                    }
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(FRAME_KEY.into(), Parameter::Integer(frame as i64));
+
+                    let detections = objects
+                        .iter()
+                        .enumerate()
+                        .map(|(i, object)| {
+                            serde_json::to_string(&Detection {
+                                label: object.label.to_owned(),
+                                // a detector is never quite sure
+                                score: 0.9 - 0.1 * i as f32 + 0.05 * (frame as f32 * 0.3).sin(),
+                                x: object.x as u32,
+                                y: object.y as u32,
+                                width: object.size as u32,
+                                height: object.size as u32,
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    node.send_output(
+                        detections_output.clone(),
+                        parameters.clone(),
+                        StringArray::from(detections),
+                    )?;
+                    let telemetry = Telemetry {
+                        speed_mps: 1.2 + 0.4 * (frame as f64 * 0.05).sin(),
+                        battery_pct: (100.0 - frame as f64 * 0.05).max(0.0),
+                    };
+                    node.send_output(
+                        telemetry_output.clone(),
+                        parameters.clone(),
+                        StringArray::from(vec![serde_json::to_string(&telemetry)?]),
+                    )?;
+
+                    parameters.insert(WIDTH_KEY.into(), Parameter::Integer(width.into()));
+                    parameters.insert(HEIGHT_KEY.into(), Parameter::Integer(height.into()));
+                    parameters.insert(ENCODING_KEY.into(), Parameter::String("rgb8".into()));
+                    node.send_output(
+                        image_output.clone(),
+                        parameters,
+                        UInt8Array::from(render(&objects, width, height)),
+                    )?;
+
+                    for object in &mut objects {
+                        object.step(w, h);
+                    }
+                    frame += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("rendered {frame} frames");
+    Ok(())
+}
+
+/// A vertical gradient with the objects on top, in gray only.
+fn render(objects: &[Object], width: u32, height: u32) -> Vec<u8> {
+    let mut image = Vec::with_capacity(width as usize * height as usize * 3);
+    for y in 0..height {
+        let background = (60 + 80 * y / height.max(1)) as u8;
+        for x in 0..width {
+            let shade = objects
+                .iter()
+                .rev()
+                .find(|object| object.contains(x as f64, y as f64))
+                .map_or(background, |object| object.shade);
+            image.extend_from_slice(&[shade; 3]);
+        }
+    }
+    image
+}
@@ -0,0 +1,43 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::{Context, bail};
+use multi_tenant_dataflow_nodes::{tenant, tenant_of};
+
+fn main() -> eyre::Result<()> {
+    let expect_rejections = std::env::var("EXPECT_REJECTIONS").is_ok_and(|value| value == "true");
+    let (node, mut events) = DoraNode::init_from_env()?;
+    let tenant = tenant(&node)?;
+
+    let (mut received, mut rejected) = (0, 0);
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "data" => {
+                    let owner = tenant_of(&metadata.parameters)?;
+                    if owner != tenant {
+                        bail!("tenant `{tenant}` received data of tenant `{owner}`");
+                    }
+                    received += 1;
+                }
+                "rejected" => {
+                    let reason: &str =
+                        TryFrom::try_from(&data).context("expected string reason")?;
+                    println!("gateway rejected {reason}");
+                    rejected += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("tenant `{tenant}`: processed {received} messages, {rejected} rejected");
+    if received == 0 {
+        bail!("tenant `{tenant}` did not receive any data");
+    }
+    if expect_rejections && rejected == 0 {
+        bail!("expected the gateway to reject leaked cross-tenant messages");
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, MetadataParameters, Parameter,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::eyre;
+use multi_tenant_dataflow_nodes::{Reading, TENANT_KEY, key_prefix, tenant};
+use std::time::Duration;
+use zenoh::{Wait, sample::Sample};
+
+/// Enforcement node of a tenant: subscribes to the tenant's zenoh key prefix and forwards
+/// only messages that belong to the tenant into its pipeline. Everything else is rejected
+/// and reported on the `rejected` output.
+struct Gateway {
+    node: DoraNode,
+    tenant: String,
+    accepted: u64,
+    rejected: u64,
+}
+
+impl Gateway {
+    fn handle(&mut self, sample: &Sample) -> eyre::Result<()> {
+        let key = sample.key_expr().as_str();
+        let reason = match serde_json::from_slice::<Reading>(&sample.payload().to_bytes()) {
+            Ok(reading) if reading.tenant == self.tenant => {
+                self.accepted += 1;
+                let mut parameters = MetadataParameters::default();
+                parameters.insert(TENANT_KEY.into(), Parameter::String(reading.tenant));
+                parameters.insert("seq".into(), Parameter::Integer(reading.seq as i64));
+                self.node.send_output(
+                    DataId::from("data".to_owned()),
+                    parameters,
+                    reading.value.into_arrow(),
+                )?;
+                return Ok(());
+            }
+            Ok(reading) => format!(
+                "message {} of tenant `{}` from `{}` on `{key}`",
+                reading.seq, reading.tenant, reading.source
+            ),
+            Err(err) => format!("malformed message on `{key}`: {err}"),
+        };
+
+        eprintln!("rejected cross-tenant traffic: {reason}");
+        self.rejected += 1;
+        self.node.send_output(
+            DataId::from("rejected".to_owned()),
+            Default::default(),
+            reason.into_arrow(),
+        )?;
+        Ok(())
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let (node, events) = DoraNode::init_from_env()?;
+    let tenant = tenant(&node)?;
+
+    let session = zenoh::open(zenoh::Config::default())
+        .wait()
+        .map_err(|err| eyre!("failed to open zenoh session: {err}"))?;
+    let key_expr = format!("{}/**", key_prefix(&tenant));
+    let subscriber = session
+        .declare_subscriber(&key_expr)
+        .wait()
+        .map_err(|err| eyre!("failed to subscribe to `{key_expr}`: {err}"))?;
+    println!("enforcing tenant `{tenant}` on `{key_expr}`");
+
+    let mut gateway = Gateway {
+        node,
+        tenant,
+        accepted: 0,
+        rejected: 0,
+    };
+
+    let merged = events.merge_external(Box::pin(subscriber.stream()));
+    let events = futures::executor::block_on_stream(merged);
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                // the uplink is done, process the samples that are still in flight
+                Event::InputClosed { id } if id.as_str() == "uplink" => {
+                    std::thread::sleep(Duration::from_millis(500));
+                    while let Ok(Some(sample)) = subscriber.try_recv() {
+                        gateway.handle(&sample)?;
+                    }
+                    break;
+                }
+                Event::Input { .. } => {}
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(sample) => gateway.handle(&sample)?,
+        }
+    }
+
+    println!(
+        "tenant `{}`: accepted {}, rejected {}",
+        gateway.tenant, gateway.accepted, gateway.rejected
+    );
+    Ok(())
+}
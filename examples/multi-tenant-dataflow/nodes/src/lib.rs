@@ -0,0 +1,43 @@
+use dora_node_api::{DoraNode, MetadataParameters, Parameter};
+use eyre::{Context, bail, eyre};
+use serde::{Deserialize, Serialize};
+
+/// Metadata key that carries the tenant a message belongs to.
+pub const TENANT_KEY: &str = "tenant";
+
+/// The tenant of the current node, configured through the `TENANT` env variable.
+///
+/// Node ids must follow the `<tenant>-<role>` naming convention, so that the owner of every
+/// node is visible in the dataflow YAML and in the logs.
+pub fn tenant(node: &DoraNode) -> eyre::Result<String> {
+    let tenant = std::env::var("TENANT").context("TENANT env variable is required")?;
+    let node_id = node.id().to_string();
+    if !node_id.starts_with(&format!("{tenant}-")) {
+        bail!("node `{node_id}` of tenant `{tenant}` must be named `{tenant}-<role>`");
+    }
+    Ok(tenant)
+}
+
+pub fn tenant_of(parameters: &MetadataParameters) -> eyre::Result<&str> {
+    match parameters.get(TENANT_KEY) {
+        Some(Parameter::String(tenant)) => Ok(tenant),
+        other => Err(eyre!(
+            "expected string `{TENANT_KEY}` parameter, got {other:?}"
+        )),
+    }
+}
+
+/// Zenoh key expression of all data of a tenant, e.g. `tenants/alpha/data`.
+pub fn key_prefix(tenant: &str) -> String {
+    let root = std::env::var("KEY_ROOT").unwrap_or_else(|_| "tenants".to_owned());
+    format!("{root}/{tenant}")
+}
+
+/// Message format on zenoh.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Reading {
+    pub tenant: String,
+    pub source: String,
+    pub seq: u64,
+    pub value: f64,
+}
@@ -0,0 +1,41 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, Parameter, dora_core::config::DataId};
+use multi_tenant_dataflow_nodes::{TENANT_KEY, tenant};
+
+fn main() -> eyre::Result<()> {
+    let messages: u64 = std::env::var("MESSAGES")
+        .ok()
+        .and_then(|messages| messages.parse().ok())
+        .unwrap_or(100);
+    let output = DataId::from("data".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let tenant = tenant(&node)?;
+
+    let mut seq = 0;
+    while seq < messages {
+        let event = match events.recv() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            Event::Input {
+                id, mut metadata, ..
+            } if id.as_str() == "tick" => {
+                metadata
+                    .parameters
+                    .insert(TENANT_KEY.into(), Parameter::String(tenant.clone()));
+                metadata
+                    .parameters
+                    .insert("seq".into(), Parameter::Integer(seq as i64));
+                let value = seq as f64 * 0.5;
+                node.send_output(output.clone(), metadata.parameters, value.into_arrow())?;
+                seq += 1;
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
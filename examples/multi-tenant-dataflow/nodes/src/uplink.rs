@@ -0,0 +1,65 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, Parameter, dora_core::config::DataId};
+use eyre::{Context, eyre};
+use multi_tenant_dataflow_nodes::{Reading, key_prefix, tenant_of};
+use std::collections::BTreeMap;
+use zenoh::Wait;
+
+/// Shared infrastructure node that publishes the data of all tenants to zenoh, each under
+/// the key prefix of the tenant that the message belongs to.
+///
+/// `LEAK_EVERY` simulates a misconfiguration: every n-th message of each tenant is published
+/// under the key prefix of another tenant.
+fn main() -> eyre::Result<()> {
+    let leak_every: u64 = std::env::var("LEAK_EVERY")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0);
+    let output = DataId::from("published".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let session = zenoh::open(zenoh::Config::default())
+        .wait()
+        .map_err(|err| eyre!("failed to open zenoh session: {err}"))?;
+
+    let mut published = 0u64;
+    let mut per_tenant: BTreeMap<String, u64> = BTreeMap::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let tenant = tenant_of(&metadata.parameters)?;
+                let seq = match metadata.parameters.get("seq") {
+                    Some(Parameter::Integer(seq)) => *seq as u64,
+                    _ => published,
+                };
+                let reading = Reading {
+                    tenant: tenant.to_owned(),
+                    source: id.to_string(),
+                    seq,
+                    value: f64::try_from(&data).context("expected float64 value")?,
+                };
+
+                published += 1;
+                let count = per_tenant.entry(tenant.to_owned()).or_default();
+                *count += 1;
+                let key_tenant = if leak_every != 0 && *count % leak_every == 0 {
+                    // the bug: route the message to the other tenant
+                    if tenant == "alpha" { "beta" } else { "alpha" }
+                } else {
+                    tenant
+                };
+                let key = format!("{}/data", key_prefix(key_tenant));
+                session
+                    .put(&key, serde_json::to_vec(&reading)?)
+                    .wait()
+                    .map_err(|err| eyre!("failed to publish on `{key}`: {err}"))?;
+                node.send_output(output.clone(), Default::default(), published.into_arrow())?;
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("published {published} messages");
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use dora_tracing::TracingBuilder;
-use eyre::{Context, OptionExt, bail};
+use example_runner_utils::{Dora, run};
+use eyre::{Context, OptionExt};
 
 use std::{net::Ipv4Addr, path::Path};
 use tokio::task::JoinSet;
@@ -14,17 +15,33 @@ async fn main() -> eyre::Result<()> {
     std::env::set_current_dir(root.join(file!()).parent().unwrap())
         .wrap_err("failed to set working dir")?;
 
+    let dora = Dora::from_env()?;
     let dataflow = Path::new("dataflow.yml");
-    build_dataflow(dataflow).await?;
+    dora.build_dataflow(dataflow).await?;
 
     let coordinator_addr = Ipv4Addr::LOCALHOST;
     let interface_port =
         port_check::free_local_ipv4_port_in_range(10000..=15000).ok_or_eyre("No available port")?;
     let control_port = port_check::free_local_ipv4_port_in_range((interface_port + 1)..=15000)
         .ok_or_eyre("No available port")?;
-    let coordinator = run_coordinator(coordinator_addr.to_string(), interface_port, control_port);
-    let daemon_a = run_daemon(coordinator_addr.to_string(), "A", interface_port);
-    let daemon_b = run_daemon(coordinator_addr.to_string(), "B", interface_port);
+    let coordinator = run_coordinator(
+        dora.clone(),
+        coordinator_addr.to_string(),
+        interface_port,
+        control_port,
+    );
+    let daemon_a = run_daemon(
+        dora.clone(),
+        coordinator_addr.to_string(),
+        "A",
+        interface_port,
+    );
+    let daemon_b = run_daemon(
+        dora.clone(),
+        coordinator_addr.to_string(),
+        "B",
+        interface_port,
+    );
 
     tracing::info!("Spawning coordinator and daemons");
     let mut tasks = JoinSet::new();
@@ -35,7 +52,8 @@ async fn main() -> eyre::Result<()> {
     // tracing::info!("waiting until daemons are connected to coordinator");
 
     tracing::info!("starting dataflow");
-    let dataflow_task = start_dataflow(dataflow, coordinator_addr.to_string(), interface_port);
+    let dataflow_task =
+        start_dataflow(dora, dataflow, coordinator_addr.to_string(), interface_port);
 
     tasks.spawn(dataflow_task);
 
@@ -49,60 +67,29 @@ async fn main() -> eyre::Result<()> {
 }
 
 async fn start_dataflow(
+    dora: Dora,
     dataflow: &Path,
     coordinator_addr: String,
     coordinator_port: u16,
 ) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("start").arg(dataflow).args([
+    let mut cmd = dora.cli(["start"]);
+    cmd.arg(dataflow).args([
         "--coordinator-addr",
         &coordinator_addr,
         "--coordinator-port",
         &coordinator_port.to_string(),
     ]);
-    if !cmd.status().await?.success() {
-        bail!("failed to build dataflow");
-    };
-    Ok(())
-}
-
-async fn build_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("build").arg(dataflow);
-    if !cmd.status().await?.success() {
-        bail!("failed to build dataflow");
-    };
-    Ok(())
+    run(&mut cmd, "failed to start dataflow").await
 }
 
 async fn run_coordinator(
+    dora: Dora,
     interface: String,
     interface_port: u16,
     control_port: u16,
 ) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("coordinator").args([
+    let mut cmd = dora.cli(["coordinator"]);
+    cmd.args([
         "--interface",
         &interface,
         "--control-interface",
@@ -112,30 +99,19 @@ async fn run_coordinator(
         "--control-port",
         &control_port.to_string(),
     ]);
-    if !cmd.status().await?.success() {
-        bail!("failed to run dataflow");
-    };
-    Ok(())
+    run(&mut cmd, "failed to run coordinator").await
 }
 
 async fn run_daemon(
+    dora: Dora,
     coordinator: String,
     machine_id: &str,
     interface_port: u16,
 ) -> eyre::Result<()> {
     let daemon_port =
         port_check::free_local_ipv4_port_in_range(11000..=15000).ok_or_eyre("No available port")?;
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--")
-        .arg("daemon")
-        .arg("--machine-id")
+    let mut cmd = dora.cli(["daemon"]);
+    cmd.arg("--machine-id")
         .arg(machine_id)
         .arg("--coordinator-addr")
         .arg(coordinator)
@@ -143,8 +119,5 @@ async fn run_daemon(
         .arg(interface_port.to_string())
         .arg("--local-listen-port")
         .arg(daemon_port.to_string()); // random port
-    if !cmd.status().await?.success() {
-        bail!("failed to run dataflow");
-    };
-    Ok(())
+    run(&mut cmd, "failed to run daemon").await
 }
@@ -0,0 +1,203 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, OptionExt, bail, eyre};
+use serde::Deserialize;
+use std::{path::Path, time::Duration};
+use tokio::process::Child;
+
+/// Where `tb3_simulation_launch.py` spawns the robot, in the `map` frame.
+const INITIAL_POSE: (f64, f64) = (-2.0, -0.5);
+/// How long Nav2 and Gazebo may take to come up.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+/// Largest distance to a reached goal in the last feedback, in meters. The goal checker of
+/// Nav2 accepts 0.25 m, and the last feedback may be older than the result.
+const MAX_DISTANCE_REMAINING: f64 = 0.5;
+
+/// Subset of `GoalReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct GoalReport {
+    goal_id: u64,
+    status: Option<String>,
+    feedback: u64,
+    last_feedback: Option<Feedback>,
+    duration_s: Option<f64>,
+}
+
+/// Subset of `Feedback` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct Feedback {
+    distance_remaining: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("nav2-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // pass `--external-nav2` when Nav2 is already running, e.g. on a real robot
+    let external_nav2 = std::env::args().any(|arg| arg == "--external-nav2");
+
+    let dataflow = Path::new("dataflow.yml");
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let waypoints = descriptor["nodes"][0]["env"]["WAYPOINTS"]
+        .as_str()
+        .ok_or_eyre("the goal planner has no `WAYPOINTS`")?
+        .split(';')
+        .filter(|waypoint| !waypoint.trim().is_empty())
+        .count();
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+
+    let mut nav2 = if external_nav2 {
+        None
+    } else {
+        Some(launch_nav2().await?)
+    };
+    let result = async {
+        wait_for_nav2().await?;
+        if !external_nav2 {
+            set_initial_pose().await?;
+        }
+        dora.run_dataflow(dataflow).await
+    }
+    .await;
+    if let Some(nav2) = &mut nav2 {
+        stop_launch(nav2).await?;
+    }
+    result?;
+
+    let reports: Vec<GoalReport> = serde_json::from_str(
+        &std::fs::read_to_string("out/mission.json")
+            .context("goal planner did not write a report")?,
+    )?;
+    for report in &reports {
+        println!(
+            "goal {}: {} after {:.1} s, {} feedback messages, {:.2} m remaining",
+            report.goal_id,
+            report.status.as_deref().unwrap_or("unknown"),
+            report.duration_s.unwrap_or_default(),
+            report.feedback,
+            report
+                .last_feedback
+                .as_ref()
+                .map_or(f64::NAN, |feedback| feedback.distance_remaining)
+        );
+    }
+    if reports.len() != waypoints {
+        bail!("expected {waypoints} goals, got {}", reports.len());
+    }
+    let (unreachable, reachable) = reports.split_last().ok_or_eyre("no goals")?;
+    for report in reachable {
+        if report.status.as_deref() != Some("succeeded") {
+            bail!("goal {} should have succeeded", report.goal_id);
+        }
+        match &report.last_feedback {
+            Some(feedback) if feedback.distance_remaining <= MAX_DISTANCE_REMAINING => {}
+            _ => bail!(
+                "the feedback of goal {} should end close to the goal",
+                report.goal_id
+            ),
+        }
+    }
+    if unreachable.status.as_deref() != Some("aborted") {
+        bail!(
+            "the last goal is outside of the map, and Nav2 should have aborted it, got {:?}",
+            unreachable.status
+        );
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Starts Nav2 with a turtlebot3 in Gazebo, without any GUI.
+async fn launch_nav2() -> eyre::Result<Child> {
+    tokio::process::Command::new("bash")
+        .args([
+            "-c",
+            &format!(
+                "source {}; exec ros2 launch nav2_bringup tb3_simulation_launch.py \
+                 headless:=True use_rviz:=False",
+                ros_path()
+            ),
+        ])
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to launch Nav2")
+}
+
+/// Waits until the `bt_navigator`, which serves `navigate_to_pose`, is active.
+async fn wait_for_nav2() -> eyre::Result<()> {
+    let start = std::time::Instant::now();
+    loop {
+        if let Ok(state) = ros2("ros2 lifecycle get /bt_navigator").await
+            && state.starts_with("active")
+        {
+            println!("Nav2 is active");
+            return Ok(());
+        }
+        if start.elapsed() > STARTUP_TIMEOUT {
+            bail!("Nav2 did not become active in time");
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Tells AMCL where the robot starts, so that it publishes the `map` frame.
+async fn set_initial_pose() -> eyre::Result<()> {
+    let (x, y) = INITIAL_POSE;
+    ros2(&format!(
+        "ros2 topic pub --once /initialpose geometry_msgs/msg/PoseWithCovarianceStamped \
+         '{{header: {{frame_id: map}}, pose: {{pose: {{position: {{x: {x}, y: {y}}}, \
+         orientation: {{w: 1.0}}}}}}}}'"
+    ))
+    .await?;
+    // until AMCL has localized the robot
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    Ok(())
+}
+
+/// Stops `ros2 launch` like ctrl-c, so that it shuts down the nodes and Gazebo that it
+/// started, instead of leaving them behind.
+async fn stop_launch(launch: &mut Child) -> eyre::Result<()> {
+    let pid = launch.id().ok_or_else(|| eyre!("Nav2 exited early"))?;
+    tokio::process::Command::new("kill")
+        .args(["-INT", &pid.to_string()])
+        .status()
+        .await?;
+    if tokio::time::timeout(Duration::from_secs(30), launch.wait())
+        .await
+        .is_err()
+    {
+        launch.kill().await?;
+    }
+    Ok(())
+}
+
+/// Runs a `ros2` CLI command in a shell with the sourced ROS2 installation, and returns its
+/// stdout.
+async fn ros2(command: &str) -> eyre::Result<String> {
+    let output = tokio::process::Command::new("bash")
+        .args(["-c", &format!("source {}; {command}", ros_path())])
+        .output()
+        .await?;
+    if !output.status.success() {
+        bail!(
+            "`{command}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn ros_path() -> String {
+    std::env::var("ROS").unwrap_or_else(|_| "/opt/ros/jazzy/setup.bash".into())
+}
@@ -0,0 +1,108 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::AsArray, dora_core::config::DataId};
+use eyre::{OptionExt, bail};
+use nav2_dataflow_nodes::{Feedback, GoalReport, GoalStatus, env_or, parse_waypoints, write_json};
+use std::{path::PathBuf, time::Instant};
+
+/// Sends the `WAYPOINTS` as goals one after the other, and waits for the final `status` of
+/// each goal before sending the next one, whether it succeeded or not.
+///
+/// Logs the `feedback` of the active goal, and writes a [`GoalReport`] per goal to
+/// `REPORT_FILE` when all goals are over. Fails if a goal takes longer than
+/// `GOAL_TIMEOUT_S`.
+fn main() -> eyre::Result<()> {
+    let waypoints = parse_waypoints(&env_or("WAYPOINTS", String::new())?)?;
+    let timeout: f64 = env_or("GOAL_TIMEOUT_S", 120.0)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/mission.json".to_owned())?.into();
+    let output = DataId::from("goal".to_owned());
+    if waypoints.is_empty() {
+        bail!("no WAYPOINTS given");
+    }
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut reports: Vec<GoalReport> = Vec::new();
+    let mut active: Option<Instant> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "tick" => {
+                    if let Some(sent) = active {
+                        if sent.elapsed().as_secs_f64() > timeout {
+                            write_json(&report_file, &reports)?;
+                            bail!("goal {} took longer than {timeout} s", reports.len() - 1);
+                        }
+                        continue;
+                    }
+                    let goal_id = reports.len() as u64;
+                    let Some(&waypoint) = waypoints.get(goal_id as usize) else {
+                        break;
+                    };
+                    println!(
+                        "sending goal {goal_id}: x {:.2}, y {:.2}, yaw {:.2}",
+                        waypoint.x, waypoint.y, waypoint.yaw
+                    );
+                    let (parameters, array) = waypoint.to_arrow(goal_id);
+                    node.send_output(output.clone(), parameters, array)?;
+                    reports.push(GoalReport {
+                        goal_id,
+                        waypoint,
+                        status: None,
+                        feedback: 0,
+                        last_feedback: None,
+                        duration_s: None,
+                    });
+                    active = Some(Instant::now());
+                }
+                "feedback" => {
+                    let (goal_id, feedback) = Feedback::from_arrow(&metadata.parameters, &data.0)?;
+                    let Some(report) = reports.get_mut(goal_id as usize) else {
+                        bail!("feedback for unknown goal {goal_id}");
+                    };
+                    // about once per second at the default feedback rate of Nav2
+                    if report.feedback % 10 == 0 {
+                        println!(
+                            "goal {goal_id}: at ({:.2}, {:.2}), {:.2} m remaining, {} recoveries",
+                            feedback.x,
+                            feedback.y,
+                            feedback.distance_remaining,
+                            feedback.recoveries
+                        );
+                    }
+                    report.feedback += 1;
+                    report.last_feedback = Some(feedback);
+                }
+                "status" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    for row in rows.iter().flatten() {
+                        let status: GoalStatus = serde_json::from_str(row)?;
+                        let Some(report) = reports.get_mut(status.goal_id as usize) else {
+                            bail!("status of unknown goal {}", status.goal_id);
+                        };
+                        println!(
+                            "goal {}: {:?}{}",
+                            status.goal_id,
+                            status.status,
+                            status
+                                .message
+                                .map(|message| format!(": {message}"))
+                                .unwrap_or_default()
+                        );
+                        report.status = Some(status.status);
+                        if status.status.is_final() {
+                            report.duration_s = active.map(|sent| sent.elapsed().as_secs_f64());
+                            active = None;
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    write_json(&report_file, &reports)
+}
@@ -0,0 +1,169 @@
+use dora_node_api::{
+    MetadataParameters, Parameter,
+    arrow::{
+        array::{Array, AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+};
+use eyre::{OptionExt, bail, eyre};
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Metadata key of the id of a goal, also attached to its feedback.
+pub const GOAL_ID_KEY: &str = "goal_id";
+
+/// A pose in the `map` frame that the robot should navigate to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Waypoint {
+    pub x: f64,
+    pub y: f64,
+    /// Heading in radians, counterclockwise from the x axis.
+    pub yaw: f64,
+}
+
+impl Waypoint {
+    /// Sent on `goal` as a `Float64` array of `[x, y, yaw]`, with the goal id in the
+    /// metadata.
+    pub fn to_arrow(&self, goal_id: u64) -> (MetadataParameters, Float64Array) {
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(GOAL_ID_KEY.into(), Parameter::Integer(goal_id as i64));
+        (
+            parameters,
+            Float64Array::from(vec![self.x, self.y, self.yaw]),
+        )
+    }
+
+    pub fn from_arrow(
+        parameters: &MetadataParameters,
+        data: &dyn Array,
+    ) -> eyre::Result<(u64, Self)> {
+        let values = data
+            .as_primitive_opt::<Float64Type>()
+            .ok_or_eyre("expected a Float64 array")?
+            .values();
+        let &[x, y, yaw] = values.as_ref() else {
+            bail!("expected `[x, y, yaw]`, got {} values", values.len());
+        };
+        Ok((goal_id(parameters)?, Self { x, y, yaw }))
+    }
+}
+
+/// Parses waypoints like `1.0,2.0,0.0; -1.5,0.5,3.14`.
+pub fn parse_waypoints(waypoints: &str) -> eyre::Result<Vec<Waypoint>> {
+    waypoints
+        .split(';')
+        .map(str::trim)
+        .filter(|waypoint| !waypoint.is_empty())
+        .map(|waypoint| {
+            let values = waypoint
+                .split(',')
+                .map(|value| value.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| eyre!("invalid waypoint `{waypoint}`: {err}"))?;
+            match values[..] {
+                [x, y, yaw] => Ok(Waypoint { x, y, yaw }),
+                _ => bail!("waypoint `{waypoint}` should be `x,y,yaw`"),
+            }
+        })
+        .collect()
+}
+
+pub fn goal_id(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(GOAL_ID_KEY) {
+        Some(Parameter::Integer(id)) => Ok(*id as u64),
+        other => bail!("expected an integer `{GOAL_ID_KEY}`, got {other:?}"),
+    }
+}
+
+/// Progress of a goal, from the `NavigateToPose` feedback. Sent on `feedback` as a `Float64`
+/// array in the order of the fields, with the goal id in the metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Feedback {
+    pub x: f64,
+    pub y: f64,
+    pub distance_remaining: f64,
+    pub navigation_time_s: f64,
+    pub recoveries: f64,
+}
+
+impl Feedback {
+    pub fn to_arrow(&self, goal_id: u64) -> (MetadataParameters, Float64Array) {
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(GOAL_ID_KEY.into(), Parameter::Integer(goal_id as i64));
+        (
+            parameters,
+            Float64Array::from(vec![
+                self.x,
+                self.y,
+                self.distance_remaining,
+                self.navigation_time_s,
+                self.recoveries,
+            ]),
+        )
+    }
+
+    pub fn from_arrow(
+        parameters: &MetadataParameters,
+        data: &dyn Array,
+    ) -> eyre::Result<(u64, Self)> {
+        let values = data
+            .as_primitive_opt::<Float64Type>()
+            .ok_or_eyre("expected a Float64 array")?
+            .values();
+        let &[x, y, distance_remaining, navigation_time_s, recoveries] = values.as_ref() else {
+            bail!("expected 5 feedback values, got {}", values.len());
+        };
+        Ok((
+            goal_id(parameters)?,
+            Self {
+                x,
+                y,
+                distance_remaining,
+                navigation_time_s,
+                recoveries,
+            },
+        ))
+    }
+}
+
+/// A change of the state of a goal, sent on `status` as a JSON row.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalStatus {
+    pub goal_id: u64,
+    pub status: Status,
+    /// Why the goal was rejected or failed, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Accepted,
+    Rejected,
+    Succeeded,
+    Canceled,
+    Aborted,
+    /// The action failed, e.g. because Nav2 was not reachable.
+    Failed,
+}
+
+impl Status {
+    /// Whether the goal is over, and the next one can be sent.
+    pub fn is_final(self) -> bool {
+        self != Status::Accepted
+    }
+}
+
+/// One entry per goal in the report of the planner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalReport {
+    pub goal_id: u64,
+    pub waypoint: Waypoint,
+    pub status: Option<Status>,
+    pub feedback: u64,
+    pub last_feedback: Option<Feedback>,
+    /// From sending the goal to its final status.
+    pub duration_s: Option<f64>,
+}
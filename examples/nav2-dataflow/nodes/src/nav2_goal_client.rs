@@ -0,0 +1,281 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::StringArray,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use dora_ros2_bridge::{
+    messages::{
+        geometry_msgs::msg::{Point, Pose, PoseStamped, Quaternion},
+        nav2_msgs::action::{NavigateToPose, NavigateToPoseFeedback, NavigateToPoseGoal},
+        std_msgs::msg::Header,
+    },
+    ros2_client::{
+        self, NodeOptions,
+        action::{ActionClientQosPolicies, GoalId},
+        action_msgs::GoalStatusEnum,
+    },
+    rustdds::{self, policy},
+};
+use eyre::{Context, eyre};
+use futures::{StreamExt, channel::mpsc, pin_mut, task::SpawnExt};
+use nav2_dataflow_nodes::{Feedback, GoalStatus, Status, Waypoint, env_or};
+use std::sync::Arc;
+
+enum GoalEvent {
+    Accepted {
+        goal_id: u64,
+        ros_goal_id: GoalId,
+    },
+    Feedback {
+        goal_id: u64,
+        feedback: NavigateToPoseFeedback,
+    },
+    Done {
+        goal_id: u64,
+        status: Status,
+        message: Option<String>,
+    },
+}
+
+/// Sends every `goal` to Nav2 through the `nav2_msgs/NavigateToPose` action, and streams the
+/// action feedback on `feedback` and the state of the goal on `status` back into the
+/// dataflow.
+///
+/// A new goal doesn't cancel the active one on the dora side, but Nav2 preempts it, and
+/// reports it as aborted or canceled. Stops when `goal` is closed and no goal is active.
+fn main() -> eyre::Result<()> {
+    let action_namespace: String = env_or("ACTION_NAMESPACE", "/".to_owned())?;
+    let action_name: String = env_or("ACTION_NAME", "navigate_to_pose".to_owned())?;
+    let frame_id: String = env_or("FRAME_ID", "map".to_owned())?;
+    // empty for the default behavior tree of the `bt_navigator`
+    let behavior_tree: String = env_or("BEHAVIOR_TREE", String::new())?;
+    let feedback_output = DataId::from("feedback".to_owned());
+    let status_output = DataId::from("status".to_owned());
+
+    let mut ros_node = init_ros_node()?;
+    let pool = futures::executor::ThreadPool::new()?;
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+
+    let qos = rustdds::QosPolicyBuilder::new()
+        .reliability(policy::Reliability::Reliable {
+            max_blocking_time: rustdds::Duration::from_millis(100),
+        })
+        .history(policy::History::KeepLast { depth: 1 })
+        .build();
+    let client = Arc::new(
+        ros_node
+            .create_action_client::<NavigateToPose>(
+                ros2_client::ServiceMapping::Enhanced,
+                &ros2_client::Name::new(&action_namespace, &action_name)
+                    .map_err(|e| eyre!("invalid action name: {e}"))?,
+                &ros2_client::ActionTypeName::new("nav2_msgs", "NavigateToPose"),
+                ActionClientQosPolicies {
+                    goal_service: qos.clone(),
+                    result_service: qos.clone(),
+                    cancel_service: qos.clone(),
+                    feedback_subscription: qos.clone(),
+                    status_subscription: qos,
+                },
+            )
+            .map_err(|e| eyre!("failed to create action client: {e:?}"))?,
+    );
+
+    let (goal_events_tx, goal_events) = mpsc::unbounded();
+    let (mut node, dora_events) = DoraNode::init_from_env()?;
+    let merged = dora_events.merge_external(Box::pin(goal_events));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut active = 0usize;
+    let mut goals_closed = false;
+    for event in events {
+        match event {
+            MergedEvent::Dora(Event::Input { id, metadata, data }) => match id.as_str() {
+                "goal" => {
+                    let (goal_id, waypoint) = Waypoint::from_arrow(&metadata.parameters, &data.0)?;
+                    let goal = to_ros(&waypoint, &frame_id, &behavior_tree);
+                    active += 1;
+                    let client = client.clone();
+                    let events = goal_events_tx.clone();
+                    let pool_clone = pool.clone();
+                    pool.spawn(async move {
+                        let event =
+                            navigate(client, goal_id, goal, events.clone(), pool_clone).await;
+                        let _ = events.unbounded_send(event);
+                    })
+                    .context("failed to spawn goal task")?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            MergedEvent::Dora(Event::InputClosed { id }) => {
+                if id.as_str() == "goal" {
+                    goals_closed = true;
+                    if active == 0 {
+                        break;
+                    }
+                }
+            }
+            MergedEvent::Dora(Event::Stop(_)) => break,
+            MergedEvent::Dora(other) => eprintln!("Received unexpected input: {other:?}"),
+            MergedEvent::External(GoalEvent::Accepted {
+                goal_id,
+                ros_goal_id,
+            }) => {
+                println!("Nav2 accepted goal {goal_id} as {ros_goal_id:?}");
+                send_status(&mut node, &status_output, goal_id, Status::Accepted, None)?;
+            }
+            MergedEvent::External(GoalEvent::Feedback { goal_id, feedback }) => {
+                let position = &feedback.current_pose.pose.position;
+                let navigation_time = &feedback.navigation_time;
+                let feedback = Feedback {
+                    x: position.x,
+                    y: position.y,
+                    distance_remaining: feedback.distance_remaining.into(),
+                    navigation_time_s: navigation_time.sec as f64
+                        + navigation_time.nanosec as f64 / 1e9,
+                    recoveries: feedback.number_of_recoveries.into(),
+                };
+                let (parameters, array) = feedback.to_arrow(goal_id);
+                node.send_output(feedback_output.clone(), parameters, array)?;
+            }
+            MergedEvent::External(GoalEvent::Done {
+                goal_id,
+                status,
+                message,
+            }) => {
+                send_status(&mut node, &status_output, goal_id, status, message)?;
+                active -= 1;
+                if goals_closed && active == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends the goal, forwards its feedback, and returns its final status.
+async fn navigate(
+    client: Arc<ros2_client::action::ActionClient<NavigateToPose>>,
+    goal_id: u64,
+    goal: NavigateToPoseGoal,
+    events: mpsc::UnboundedSender<GoalEvent>,
+    pool: futures::executor::ThreadPool,
+) -> GoalEvent {
+    let done = |status, message: Option<String>| GoalEvent::Done {
+        goal_id,
+        status,
+        message,
+    };
+    let ros_goal_id = match client.async_send_goal(goal).await {
+        Ok((ros_goal_id, response)) if response.accepted => ros_goal_id,
+        Ok(_) => return done(Status::Rejected, None),
+        Err(err) => {
+            return done(
+                Status::Failed,
+                Some(format!("failed to send goal: {err:?}")),
+            );
+        }
+    };
+    let _ = events.unbounded_send(GoalEvent::Accepted {
+        goal_id,
+        ros_goal_id,
+    });
+
+    let feedback_client = client.clone();
+    let feedback_events = events.clone();
+    let _ = pool.spawn(async move {
+        let feedback = feedback_client.feedback_stream(ros_goal_id);
+        pin_mut!(feedback);
+        while let Some(Ok(feedback)) = feedback.next().await {
+            if feedback_events
+                .unbounded_send(GoalEvent::Feedback { goal_id, feedback })
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    match client.async_request_result(ros_goal_id).await {
+        Ok((GoalStatusEnum::Succeeded, _)) => done(Status::Succeeded, None),
+        Ok((GoalStatusEnum::Canceled, _)) => done(Status::Canceled, None),
+        Ok((GoalStatusEnum::Aborted, _)) => done(Status::Aborted, None),
+        Ok((status, _)) => done(
+            Status::Failed,
+            Some(format!("unexpected final status {status:?}")),
+        ),
+        Err(err) => done(
+            Status::Failed,
+            Some(format!("failed to get result: {err:?}")),
+        ),
+    }
+}
+
+fn send_status(
+    node: &mut DoraNode,
+    output: &DataId,
+    goal_id: u64,
+    status: Status,
+    message: Option<String>,
+) -> eyre::Result<()> {
+    let row = serde_json::to_string(&GoalStatus {
+        goal_id,
+        status,
+        message,
+    })?;
+    node.send_output(
+        output.clone(),
+        Default::default(),
+        StringArray::from(vec![row]),
+    )?;
+    Ok(())
+}
+
+fn to_ros(waypoint: &Waypoint, frame_id: &str, behavior_tree: &str) -> NavigateToPoseGoal {
+    NavigateToPoseGoal {
+        pose: PoseStamped {
+            // a zero stamp makes Nav2 use the latest transform
+            header: Header {
+                frame_id: frame_id.to_owned(),
+                ..Default::default()
+            },
+            pose: Pose {
+                position: Point {
+                    x: waypoint.x,
+                    y: waypoint.y,
+                    z: 0.0,
+                },
+                orientation: Quaternion {
+                    x: 0.0,
+                    y: 0.0,
+                    z: (waypoint.yaw / 2.0).sin(),
+                    w: (waypoint.yaw / 2.0).cos(),
+                },
+            },
+        },
+        behavior_tree: behavior_tree.to_owned(),
+    }
+}
+
+fn init_ros_node() -> eyre::Result<ros2_client::Node> {
+    let ros_context =
+        ros2_client::Context::new().map_err(|e| eyre!("failed to create ROS2 context: {e:?}"))?;
+
+    ros_context
+        .new_node(
+            ros2_client::NodeName::new("/dora", "nav2_goal_client")
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre!("failed to create ros2 node: {e:?}"))
+}
@@ -0,0 +1,47 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use std::path::Path;
+
+/// Must match the `ntrip-client` configuration in `dataflow.yml`.
+const CASTER_PORT: &str = "2101";
+const MOUNTPOINT: &str = "RTK01";
+const USER: &str = "rover";
+const PASSWORD: &str = "secret";
+
+/// GGA fix quality of an RTK fixed solution.
+const RTK_FIXED: &str = "4";
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("ntrip-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    // stands in for a public caster like rtk2go.com or a correction service of your provider
+    let mut caster = tokio::process::Command::new("nodes/target/release/mock-caster")
+        .args(["--port", CASTER_PORT])
+        .args(["--mountpoint", MOUNTPOINT])
+        .args(["--user", USER])
+        .args(["--password", PASSWORD])
+        .args(["--duration-secs", "15"])
+        .args(["--corrupt-every", "7"])
+        .args(["--expect-fix", RTK_FIXED])
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start mock caster")?;
+
+    dora.run_dataflow(dataflow).await?;
+
+    if !caster.wait().await?.success() {
+        bail!("the rover did not report an RTK fixed solution to the caster");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use eyre::{Context, bail};
+use ntrip_dataflow_nodes::{
+    FIX_RTK_FIXED, FIX_RTK_FLOAT, FIX_SINGLE, RtcmParser, env_or, gga_sentence,
+};
+use serialport::{SerialPort, TTYPort};
+use std::{
+    io::{ErrorKind, Read, Write},
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Corrections older than this are useless for RTK.
+const MAX_CORRECTION_AGE: Duration = Duration::from_secs(5);
+/// Station position message, sent once per epoch by the mock caster.
+const STATION_MESSAGE: u16 = 1005;
+
+/// Simulates a GNSS receiver that is connected over a serial port.
+///
+/// The receiver creates a pseudo-terminal and links its device path to `SERIAL_LINK`, like
+/// a USB receiver that shows up as `/dev/ttyACM0`. It parses the RTCM corrections written to
+/// the port, derives its fix quality from them, and writes a `GGA` sentence every
+/// `GGA_INTERVAL_MS`. The node stops when the other side of the port is closed.
+fn main() -> eyre::Result<()> {
+    let link: PathBuf = env_or("SERIAL_LINK", "out/gnss-serial".to_owned())?.into();
+    let fix_after_epochs: u32 = env_or("FIX_AFTER_EPOCHS", 5)?;
+    let gga_interval = Duration::from_millis(env_or("GGA_INTERVAL_MS", 1000)?);
+    let (latitude, longitude) = (47.3769, 8.5417);
+    let output = DataId::from("fix".to_owned());
+
+    let (mut port, device) = TTYPort::pair().context("failed to create pseudo-terminal")?;
+    let device_path = device
+        .name()
+        .ok_or_else(|| eyre::eyre!("pseudo-terminal has no name"))?;
+    if let Some(parent) = link.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let _ = std::fs::remove_file(&link);
+    std::os::unix::fs::symlink(&device_path, &link)
+        .with_context(|| format!("failed to link {device_path} to {}", link.display()))?;
+    port.set_timeout(Duration::ZERO)?;
+    println!("receiver serial port at {} ({device_path})", link.display());
+    // keep our handle of the device side open until the forwarder opened it, otherwise reads
+    // fail right away because nobody is connected
+    let mut device = Some(device);
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut parser = RtcmParser::default();
+    let mut last_correction: Option<Instant> = None;
+    let mut epochs_with_corrections = 0;
+    let mut quality = FIX_SINGLE;
+    let mut last_gga = Instant::now();
+    let mut time_to_fix = None;
+    let start = Instant::now();
+    let mut buffer = [0; 4096];
+    'events: while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    loop {
+                        match port.read(&mut buffer) {
+                            Ok(0) => break 'events,
+                            Ok(read) => {
+                                device = None;
+                                for frame in parser.push(&buffer[..read]) {
+                                    last_correction = Some(Instant::now());
+                                    if frame.message_type == STATION_MESSAGE {
+                                        epochs_with_corrections += 1;
+                                    }
+                                }
+                            }
+                            Err(err) if err.kind() == ErrorKind::TimedOut => break,
+                            // the forwarder closed the port, like an unplugged cable
+                            Err(_) if device.is_none() => break 'events,
+                            Err(err) => bail!("failed to read from serial port: {err}"),
+                        }
+                    }
+
+                    let new_quality = match last_correction {
+                        Some(time) if time.elapsed() <= MAX_CORRECTION_AGE => {
+                            if epochs_with_corrections >= fix_after_epochs {
+                                FIX_RTK_FIXED
+                            } else {
+                                FIX_RTK_FLOAT
+                            }
+                        }
+                        _ => {
+                            epochs_with_corrections = 0;
+                            FIX_SINGLE
+                        }
+                    };
+                    if new_quality != quality {
+                        quality = new_quality;
+                        println!("fix quality changed to {}", fix_name(quality));
+                        if quality == FIX_RTK_FIXED && time_to_fix.is_none() {
+                            time_to_fix = Some(start.elapsed());
+                        }
+                        node.send_output(
+                            output.clone(),
+                            Default::default(),
+                            fix_name(quality).into_arrow(),
+                        )?;
+                    }
+
+                    if last_gga.elapsed() >= gga_interval {
+                        last_gga = Instant::now();
+                        let seconds_of_day = SystemTime::now()
+                            .duration_since(SystemTime::UNIX_EPOCH)?
+                            .as_secs_f64()
+                            % 86_400.0;
+                        let age = last_correction.map(|time| time.elapsed().as_secs_f64());
+                        let sentence =
+                            gga_sentence(seconds_of_day, latitude, longitude, quality, 18, age);
+                        if let Err(err) = port.write_all(sentence.as_bytes()) {
+                            if device.is_none() {
+                                break 'events;
+                            }
+                            eprintln!("failed to write GGA: {err}");
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let _ = std::fs::remove_file(&link);
+    println!(
+        "serial port closed, {} CRC errors, {} bytes skipped, time to RTK fixed: {}",
+        parser.crc_errors,
+        parser.skipped_bytes,
+        time_to_fix.map_or("never".to_owned(), |time| format!("{time:.1?}"))
+    );
+    Ok(())
+}
+
+fn fix_name(quality: u8) -> &'static str {
+    match quality {
+        FIX_SINGLE => "single",
+        FIX_RTK_FLOAT => "rtk-float",
+        FIX_RTK_FIXED => "rtk-fixed",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,229 @@
+use std::io::{self, BufRead, Read};
+
+pub use example_node_utils::env_or;
+
+/// First byte of every RTCM 3 frame.
+pub const RTCM_PREAMBLE: u8 = 0xD3;
+/// Metadata key of the RTCM message type of a frame, e.g. `1077` for GPS MSM7.
+pub const RTCM_TYPE_KEY: &str = "rtcm_type";
+
+/// GGA fix quality of a position without corrections.
+pub const FIX_SINGLE: u8 = 1;
+/// GGA fix quality of an RTK solution with fixed ambiguities, accurate to centimeters.
+pub const FIX_RTK_FIXED: u8 = 4;
+/// GGA fix quality of an RTK solution with float ambiguities, accurate to decimeters.
+pub const FIX_RTK_FLOAT: u8 = 5;
+
+/// CRC-24Q checksum, as used by RTCM 3.
+pub fn crc24q(data: &[u8]) -> u32 {
+    let mut crc = 0u32;
+    for &byte in data {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= 0x0186_4CFB;
+            }
+        }
+    }
+    crc & 0x00FF_FFFF
+}
+
+/// Wraps `payload` into an RTCM 3 frame: preamble, 10-bit length, payload, CRC-24Q.
+pub fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    assert!(payload.len() < 1024, "RTCM payloads are at most 1023 bytes");
+    let mut frame = vec![
+        RTCM_PREAMBLE,
+        (payload.len() >> 8) as u8,
+        payload.len() as u8,
+    ];
+    frame.extend_from_slice(payload);
+    let crc = crc24q(&frame);
+    frame.extend_from_slice(&crc.to_be_bytes()[1..]);
+    frame
+}
+
+#[derive(Debug, Clone)]
+pub struct RtcmFrame {
+    /// The first 12 bits of the payload.
+    pub message_type: u16,
+    /// The complete frame, including preamble and CRC.
+    pub bytes: Vec<u8>,
+}
+
+/// Splits a byte stream into RTCM 3 frames.
+///
+/// Streams from a caster can start in the middle of a frame and can contain corrupted bytes.
+/// The parser drops everything up to the next preamble whose frame has a valid CRC.
+#[derive(Debug, Default)]
+pub struct RtcmParser {
+    buffer: Vec<u8>,
+    pub crc_errors: u64,
+    pub skipped_bytes: u64,
+}
+
+impl RtcmParser {
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<RtcmFrame> {
+        self.buffer.extend_from_slice(bytes);
+        let mut frames = Vec::new();
+        loop {
+            match self.buffer.iter().position(|&b| b == RTCM_PREAMBLE) {
+                Some(0) => {}
+                Some(start) => {
+                    self.skipped_bytes += start as u64;
+                    self.buffer.drain(..start);
+                }
+                None => {
+                    self.skipped_bytes += self.buffer.len() as u64;
+                    self.buffer.clear();
+                    break;
+                }
+            }
+            if self.buffer.len() < 3 {
+                break;
+            }
+            // the 6 bits after the preamble are reserved and always zero
+            if self.buffer[1] & 0xFC != 0 {
+                self.skip_preamble();
+                continue;
+            }
+            let len = ((self.buffer[1] as usize & 0x03) << 8) | self.buffer[2] as usize;
+            let total = 3 + len + 3;
+            if self.buffer.len() < total {
+                break;
+            }
+            let crc = u32::from_be_bytes([
+                0,
+                self.buffer[3 + len],
+                self.buffer[4 + len],
+                self.buffer[5 + len],
+            ]);
+            if crc24q(&self.buffer[..3 + len]) != crc {
+                self.crc_errors += 1;
+                self.skip_preamble();
+                continue;
+            }
+            let bytes: Vec<u8> = self.buffer.drain(..total).collect();
+            let message_type = if len >= 2 {
+                ((bytes[3] as u16) << 4) | (bytes[4] as u16 >> 4)
+            } else {
+                0
+            };
+            frames.push(RtcmFrame {
+                message_type,
+                bytes,
+            });
+        }
+        frames
+    }
+
+    /// Drops buffered bytes of an incomplete frame, e.g. after reconnecting.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// The byte at the start of the buffer looked like a preamble, but wasn't one.
+    fn skip_preamble(&mut self) {
+        self.skipped_bytes += 1;
+        self.buffer.drain(..1);
+    }
+}
+
+/// Reads the body of an HTTP response with `Transfer-Encoding: chunked`, as sent by NTRIP 2
+/// casters.
+pub struct ChunkedReader<R> {
+    inner: R,
+    remaining: usize,
+    done: bool,
+}
+
+impl<R: BufRead> ChunkedReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            remaining: 0,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.done || buf.is_empty() {
+            return Ok(0);
+        }
+        if self.remaining == 0 {
+            let mut line = String::new();
+            self.inner.read_line(&mut line)?;
+            let size = line.trim().split(';').next().unwrap_or_default();
+            let size = usize::from_str_radix(size, 16)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            if size == 0 {
+                self.done = true;
+                return Ok(0);
+            }
+            self.remaining = size;
+        }
+        let len = buf.len().min(self.remaining);
+        let read = self.inner.read(&mut buf[..len])?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.remaining -= read;
+        if self.remaining == 0 {
+            let mut crlf = [0; 2];
+            self.inner.read_exact(&mut crlf)?;
+        }
+        Ok(read)
+    }
+}
+
+/// Formats a `$GPGGA` sentence, terminated by `\r\n`.
+pub fn gga_sentence(
+    seconds_of_day: f64,
+    latitude: f64,
+    longitude: f64,
+    quality: u8,
+    satellites: u8,
+    correction_age: Option<f64>,
+) -> String {
+    let hours = (seconds_of_day / 3600.0) as u32;
+    let minutes = (seconds_of_day % 3600.0 / 60.0) as u32;
+    let seconds = seconds_of_day % 60.0;
+    let body = format!(
+        "GPGGA,{hours:02}{minutes:02}{seconds:05.2},{},{},{},{},{quality},{satellites:02},0.8,\
+         408.2,M,47.4,M,{},0000",
+        degrees_minutes(latitude.abs(), 2),
+        if latitude >= 0.0 { 'N' } else { 'S' },
+        degrees_minutes(longitude.abs(), 3),
+        if longitude >= 0.0 { 'E' } else { 'W' },
+        correction_age
+            .map(|age| format!("{age:.1}"))
+            .unwrap_or_default(),
+    );
+    format!("${body}*{:02X}\r\n", nmea_checksum(&body))
+}
+
+/// Returns the fix quality of a `GGA` sentence with a valid checksum.
+pub fn gga_quality(sentence: &str) -> Option<u8> {
+    let (body, checksum) = sentence.trim().strip_prefix('$')?.split_once('*')?;
+    if u8::from_str_radix(checksum, 16).ok()? != nmea_checksum(body) {
+        return None;
+    }
+    let fields: Vec<_> = body.split(',').collect();
+    if !fields[0].ends_with("GGA") {
+        return None;
+    }
+    fields.get(6)?.parse().ok()
+}
+
+fn nmea_checksum(body: &str) -> u8 {
+    body.bytes().fold(0, |checksum, byte| checksum ^ byte)
+}
+
+/// Formats an angle as NMEA `dddmm.mmmm`.
+fn degrees_minutes(angle: f64, degree_digits: usize) -> String {
+    let degrees = angle.trunc();
+    let minutes = (angle - degrees) * 60.0;
+    format!("{:0degree_digits$}{minutes:07.4}", degrees as u32)
+}
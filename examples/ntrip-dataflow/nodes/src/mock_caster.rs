@@ -0,0 +1,244 @@
+//! A minimal NTRIP caster that streams synthetic RTCM 3 corrections to a single rover.
+//!
+//! Usage: `mock-caster --port <port> --mountpoint <name> --user <user> --password <password>
+//! [--duration-secs <n>] [--corrupt-every <n>] [--expect-fix <quality>]`
+//!
+//! It answers requests for other mountpoints with the sourcetable and rejects wrong
+//! credentials. The rover's position is read from the `GGA` sentences that it sends back.
+//! With `--expect-fix`, the caster fails unless the last reported fix quality matches.
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use eyre::{Context, bail};
+use ntrip_dataflow_nodes::{encode_frame, gga_quality};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc,
+        atomic::{AtomicU8, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Interval between two correction epochs, as sent by most reference stations.
+const EPOCH: Duration = Duration::from_secs(1);
+
+/// Station position (1005) followed by MSM7 observations of GPS, GLONASS, and Galileo.
+const MESSAGE_TYPES: [(u16, usize); 4] = [(1005, 19), (1077, 420), (1087, 310), (1097, 380)];
+
+struct Args {
+    port: u16,
+    mountpoint: String,
+    user: String,
+    password: String,
+    duration: Duration,
+    corrupt_every: Option<u64>,
+    expect_fix: Option<u8>,
+}
+
+fn main() -> eyre::Result<()> {
+    let args = parse_args()?;
+    let listener = TcpListener::bind(("127.0.0.1", args.port))
+        .with_context(|| format!("failed to listen on port {}", args.port))?;
+    println!("caster listening on port {}", args.port);
+
+    // serve exactly one rover session, then exit
+    let (stream, addr, version) = loop {
+        let (stream, addr) = listener.accept()?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let request = read_request(&mut reader)?;
+        match check_request(&args, &request) {
+            Ok(version) => {
+                println!(
+                    "streaming `{}` to {addr} (NTRIP {version})",
+                    args.mountpoint
+                );
+                send_header(&stream, version)?;
+                break (stream, addr, version);
+            }
+            Err(response) => {
+                println!(
+                    "rejecting request from {addr}: {}",
+                    request.first().map_or("", |l| l.as_str())
+                );
+                (&stream).write_all(response.as_bytes())?;
+            }
+        }
+    };
+
+    let ggas = Arc::new(AtomicU64::new(0));
+    let last_quality = Arc::new(AtomicU8::new(0));
+    {
+        let ggas = ggas.clone();
+        let last_quality = last_quality.clone();
+        let reader = BufReader::new(stream.try_clone()?);
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else { break };
+                if let Some(quality) = gga_quality(&line) {
+                    ggas.fetch_add(1, Ordering::Relaxed);
+                    if last_quality.swap(quality, Ordering::Relaxed) != quality {
+                        println!("rover reports fix quality {quality}");
+                    }
+                }
+            }
+        });
+    }
+
+    // NTRIP 2 streams are HTTP/1.1 bodies with chunked transfer encoding
+    let chunked = version == 2;
+    let start = Instant::now();
+    let mut frames = 0u64;
+    let mut epoch = 0u64;
+    while start.elapsed() < args.duration {
+        for (message_type, len) in MESSAGE_TYPES {
+            let mut frame = encode_frame(&payload(message_type, len, epoch));
+            frames += 1;
+            if args.corrupt_every.is_some_and(|n| frames % n == 0) {
+                // flip a bit in the payload, so that the CRC no longer matches
+                let middle = frame.len() / 2;
+                frame[middle] ^= 0x10;
+            }
+            if let Err(err) = send_body(&stream, &frame, chunked) {
+                bail!("rover {addr} disconnected: {err}");
+            }
+        }
+        epoch += 1;
+        std::thread::sleep(EPOCH);
+    }
+    if chunked {
+        let _ = (&stream).write_all(b"0\r\n\r\n");
+    }
+    let _ = stream.shutdown(std::net::Shutdown::Both);
+
+    let ggas = ggas.load(Ordering::Relaxed);
+    let last_quality = last_quality.load(Ordering::Relaxed);
+    println!("sent {frames} frames in {epoch} epochs, received {ggas} GGA sentences");
+    if let Some(expected) = args.expect_fix
+        && last_quality != expected
+    {
+        bail!("rover reported fix quality {last_quality} at the end, expected {expected}");
+    }
+    Ok(())
+}
+
+fn parse_args() -> eyre::Result<Args> {
+    let mut args = Args {
+        port: 2101,
+        mountpoint: "RTK01".to_owned(),
+        user: "rover".to_owned(),
+        password: "secret".to_owned(),
+        duration: Duration::from_secs(15),
+        corrupt_every: None,
+        expect_fix: None,
+    };
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = || {
+            argv.next()
+                .ok_or_else(|| eyre::eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--port" => args.port = value()?.parse()?,
+            "--mountpoint" => args.mountpoint = value()?,
+            "--user" => args.user = value()?,
+            "--password" => args.password = value()?,
+            "--duration-secs" => args.duration = Duration::from_secs(value()?.parse()?),
+            "--corrupt-every" => args.corrupt_every = Some(value()?.parse()?),
+            "--expect-fix" => args.expect_fix = Some(value()?.parse()?),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    Ok(args)
+}
+
+/// Reads the request line and headers.
+fn read_request(reader: &mut impl BufRead) -> eyre::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            bail!("connection closed during request");
+        }
+        let line = line.trim_end().to_owned();
+        if line.is_empty() {
+            return Ok(lines);
+        }
+        lines.push(line);
+    }
+}
+
+/// Returns the NTRIP version of a valid request, or the response to send otherwise.
+fn check_request(args: &Args, request: &[String]) -> Result<u8, String> {
+    let header = |name: &str| {
+        request.iter().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    };
+    let path = request
+        .first()
+        .and_then(|line| line.strip_prefix("GET "))
+        .and_then(|rest| rest.split_whitespace().next());
+    if path != Some(format!("/{}", args.mountpoint).as_str()) {
+        return Err(sourcetable(&args.mountpoint));
+    }
+    let credentials = STANDARD.encode(format!("{}:{}", args.user, args.password));
+    if header("Authorization") != Some(format!("Basic {credentials}").as_str()) {
+        return Err("HTTP/1.1 401 Unauthorized\r\nConnection: close\r\n\r\n".to_owned());
+    }
+    Ok(
+        if header("Ntrip-Version").is_some_and(|v| v.contains("2.0")) {
+            2
+        } else {
+            1
+        },
+    )
+}
+
+fn sourcetable(mountpoint: &str) -> String {
+    let table = format!(
+        "STR;{mountpoint};Zurich;RTCM 3.3;1005(10),1077(1),1087(1),1097(1);2;GPS+GLO+GAL;\
+         SNIP;CHE;47.38;8.54;1;0;sNTRIP;none;B;N;9600;\r\nENDSOURCETABLE\r\n"
+    );
+    format!(
+        "SOURCETABLE 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{table}",
+        table.len()
+    )
+}
+
+fn send_header(mut stream: &TcpStream, version: u8) -> eyre::Result<()> {
+    let header = if version == 2 {
+        "HTTP/1.1 200 OK\r\nNtrip-Version: Ntrip/2.0\r\nContent-Type: gnss/data\r\n\
+         Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+    } else {
+        "ICY 200 OK\r\n\r\n"
+    };
+    stream.write_all(header.as_bytes())?;
+    Ok(())
+}
+
+fn send_body(mut stream: &TcpStream, data: &[u8], chunked: bool) -> std::io::Result<()> {
+    if chunked {
+        write!(stream, "{:X}\r\n", data.len())?;
+        stream.write_all(data)?;
+        stream.write_all(b"\r\n")
+    } else {
+        stream.write_all(data)
+    }
+}
+
+/// A payload that starts with the 12-bit message type, followed by pseudo-random bytes.
+fn payload(message_type: u16, len: usize, epoch: u64) -> Vec<u8> {
+    let mut payload = vec![0; len];
+    payload[0] = (message_type >> 4) as u8;
+    payload[1] = ((message_type & 0x0F) << 4) as u8;
+    let mut state = epoch.wrapping_mul(6364136223846793005) ^ message_type as u64;
+    for byte in &mut payload[2..] {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        *byte = (state >> 56) as u8;
+    }
+    payload
+}
@@ -0,0 +1,230 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::UInt8Array,
+    dora_core::config::DataId,
+};
+use eyre::{Context, bail, eyre};
+use ntrip_dataflow_nodes::{ChunkedReader, RTCM_TYPE_KEY, RtcmFrame, RtcmParser, env_or};
+use std::{
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    net::TcpStream,
+    sync::mpsc,
+    time::Duration,
+};
+
+/// A caster that sends nothing for this long is considered dead.
+const STALE_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Config {
+    caster: String,
+    mountpoint: String,
+    user: String,
+    password: String,
+    max_retries: u32,
+}
+
+enum Update {
+    /// A write handle to the caster, used to send the rover position upstream.
+    Connected(TcpStream),
+    Frames(Vec<RtcmFrame>),
+    Disconnected,
+    /// Retries are exhausted or the caster rejected the request.
+    Finished(eyre::Result<()>),
+}
+
+/// Streams RTCM corrections from an NTRIP caster into the dataflow.
+///
+/// Incoming `gga` sentences are sent to the caster, which needs the rover position for
+/// network RTK (VRS) corrections.
+fn main() -> eyre::Result<()> {
+    let config = Config {
+        caster: env_or("CASTER", "127.0.0.1:2101".to_owned())?,
+        mountpoint: env_or("MOUNTPOINT", "RTK01".to_owned())?,
+        user: env_or("NTRIP_USER", String::new())?,
+        password: env_or("NTRIP_PASSWORD", String::new())?,
+        max_retries: env_or("MAX_RETRIES", u32::MAX)?,
+    };
+    let output = DataId::from("rtcm".to_owned());
+
+    let (updates_tx, updates) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = stream_corrections(&config, &updates_tx);
+        let _ = updates_tx.send(Update::Finished(result));
+    });
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut upstream: Option<TcpStream> = None;
+    let mut frames_sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "tick" => loop {
+                    let update = match updates.try_recv() {
+                        Ok(update) => update,
+                        Err(mpsc::TryRecvError::Empty) => break,
+                        Err(mpsc::TryRecvError::Disconnected) => bail!("caster thread stopped"),
+                    };
+                    match update {
+                        Update::Connected(stream) => upstream = Some(stream),
+                        Update::Disconnected => upstream = None,
+                        Update::Frames(frames) => {
+                            for frame in frames {
+                                let mut parameters = MetadataParameters::default();
+                                parameters.insert(
+                                    RTCM_TYPE_KEY.into(),
+                                    Parameter::Integer(frame.message_type.into()),
+                                );
+                                node.send_output(
+                                    output.clone(),
+                                    parameters,
+                                    UInt8Array::from(frame.bytes),
+                                )?;
+                                frames_sent += 1;
+                            }
+                        }
+                        Update::Finished(result) => {
+                            println!("forwarded {frames_sent} RTCM frames");
+                            return result;
+                        }
+                    }
+                },
+                "gga" => {
+                    let sentence = <&str>::try_from(&data).context("expected a GGA string")?;
+                    if let Some(stream) = &mut upstream {
+                        // errors are noticed and handled by the reading thread
+                        let _ = stream.write_all(format!("{}\r\n", sentence.trim()).as_bytes());
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "gga" {
+                    upstream = None;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("forwarded {frames_sent} RTCM frames");
+    Ok(())
+}
+
+/// Connects to the caster and reads corrections, reconnecting with backoff on errors.
+fn stream_corrections(config: &Config, updates: &mpsc::Sender<Update>) -> eyre::Result<()> {
+    let mut parser = RtcmParser::default();
+    let mut failures = 0;
+    loop {
+        let result = connect(config).and_then(|(stream, mut body)| -> eyre::Result<()> {
+            updates.send(Update::Connected(stream))?;
+            println!("connected to {}/{}", config.caster, config.mountpoint);
+            failures = 0;
+            // a partial frame of the previous connection will never be completed
+            parser.reset();
+            let mut buffer = [0; 4096];
+            loop {
+                let read = body.read(&mut buffer).map_err(|err| match err.kind() {
+                    ErrorKind::WouldBlock | ErrorKind::TimedOut => {
+                        eyre!("no data for {STALE_TIMEOUT:?}")
+                    }
+                    _ => eyre!(err),
+                })?;
+                if read == 0 {
+                    bail!("caster closed the stream");
+                }
+                let frames = parser.push(&buffer[..read]);
+                if !frames.is_empty() {
+                    updates.send(Update::Frames(frames))?;
+                }
+            }
+        });
+        let err = result.unwrap_err();
+        let _ = updates.send(Update::Disconnected);
+        if err.downcast_ref::<Rejected>().is_some() {
+            return Err(err);
+        }
+
+        failures += 1;
+        if failures > config.max_retries {
+            println!(
+                "giving up after {failures} failed attempts ({} CRC errors, {} bytes skipped)",
+                parser.crc_errors, parser.skipped_bytes
+            );
+            return Ok(());
+        }
+        let backoff = Duration::from_secs(1 << failures.min(5));
+        eprintln!("{err:#}, reconnecting in {backoff:?}");
+        std::thread::sleep(backoff);
+    }
+}
+
+/// The caster rejected the request, retrying won't help.
+#[derive(Debug)]
+struct Rejected(String);
+
+impl std::fmt::Display for Rejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "caster rejected the request: {}", self.0)
+    }
+}
+
+impl std::error::Error for Rejected {}
+
+/// Sends the NTRIP 2 request and returns a write handle and the body of the response.
+fn connect(config: &Config) -> eyre::Result<(TcpStream, Box<dyn Read + Send>)> {
+    let stream = TcpStream::connect(&config.caster)
+        .with_context(|| format!("failed to connect to {}", config.caster))?;
+    stream.set_read_timeout(Some(STALE_TIMEOUT))?;
+    let credentials = STANDARD.encode(format!("{}:{}", config.user, config.password));
+    write!(
+        &stream,
+        "GET /{} HTTP/1.1\r\nHost: {}\r\nNtrip-Version: Ntrip/2.0\r\n\
+         User-Agent: NTRIP dora-ntrip-client/0.1\r\nAuthorization: Basic {credentials}\r\n\
+         Connection: close\r\n\r\n",
+        config.mountpoint, config.caster
+    )?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut status = String::new();
+    reader.read_line(&mut status)?;
+    let status = status.trim().to_owned();
+    // NTRIP 1 casters answer with `ICY 200 OK` and send the raw stream
+    if status == "ICY 200 OK" {
+        return Ok((stream, Box::new(reader)));
+    }
+    if status.starts_with("SOURCETABLE") {
+        return Err(Rejected(format!("mountpoint `{}` does not exist", config.mountpoint)).into());
+    }
+    if !(status.starts_with("HTTP/1.") && status.contains(" 200 ")) {
+        return Err(Rejected(status).into());
+    }
+
+    let mut chunked = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            bail!("connection closed during response header");
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':')
+            && key.trim().eq_ignore_ascii_case("Transfer-Encoding")
+            && value.trim().eq_ignore_ascii_case("chunked")
+        {
+            chunked = true;
+        }
+    }
+    if chunked {
+        Ok((stream, Box::new(ChunkedReader::new(reader))))
+    } else {
+        Ok((stream, Box::new(reader)))
+    }
+}
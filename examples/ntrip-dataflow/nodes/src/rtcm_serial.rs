@@ -0,0 +1,115 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow,
+    arrow::{array::AsArray, datatypes::UInt8Type},
+    dora_core::config::DataId,
+};
+use eyre::{Context, OptionExt, bail};
+use ntrip_dataflow_nodes::{env_or, gga_quality};
+use std::{
+    io::{BufRead, BufReader, ErrorKind, Write},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+/// Writes RTCM frames to the serial port of a GNSS receiver, and publishes the `GGA`
+/// sentences that the receiver sends back on the same port.
+fn main() -> eyre::Result<()> {
+    let path: String = env_or("SERIAL_PORT", "/dev/ttyACM0".to_owned())?;
+    let baud_rate: u32 = env_or("BAUD_RATE", 115_200)?;
+    let open_timeout = Duration::from_secs(env_or("OPEN_TIMEOUT_SECS", 10)?);
+    let output = DataId::from("gga".to_owned());
+
+    let mut port = open_port(&path, baud_rate, open_timeout)?;
+    println!("opened {path} at {baud_rate} baud");
+
+    let (sentences_tx, sentences) = mpsc::channel();
+    let reader = port.try_clone().context("failed to clone serial port")?;
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        loop {
+            match reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => {
+                    if gga_quality(&line).is_some() && sentences_tx.send(line.clone()).is_err() {
+                        break;
+                    }
+                    line.clear();
+                }
+                // a timeout may leave a partial line in `line`, keep it
+                Err(err) if err.kind() == ErrorKind::TimedOut => {}
+                Err(err) => {
+                    eprintln!("failed to read from serial port: {err}");
+                    break;
+                }
+            }
+        }
+    });
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut frames = 0;
+    let mut bytes = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "rtcm" => {
+                    let frame = data
+                        .as_primitive_opt::<UInt8Type>()
+                        .ok_or_eyre("expected an RTCM frame as UInt8 array")?;
+                    port.write_all(frame.values())
+                        .context("failed to write to serial port")?;
+                    frames += 1;
+                    bytes += frame.len();
+                }
+                "tick" => {
+                    while let Ok(sentence) = sentences.try_recv() {
+                        node.send_output(
+                            output.clone(),
+                            Default::default(),
+                            sentence.trim().into_arrow(),
+                        )?;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                // the tick timer never closes, so stop once the corrections end
+                if id.as_str() == "rtcm" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("wrote {frames} RTCM frames ({bytes} bytes) to {path}");
+    Ok(())
+}
+
+/// Opens the serial port, waiting for the device to appear, e.g. after it was plugged in.
+fn open_port(
+    path: &str,
+    baud_rate: u32,
+    timeout: Duration,
+) -> eyre::Result<Box<dyn serialport::SerialPort>> {
+    let start = Instant::now();
+    loop {
+        match serialport::new(path, baud_rate)
+            .timeout(Duration::from_millis(100))
+            .open()
+        {
+            Ok(port) => return Ok(port),
+            Err(err) if start.elapsed() < timeout => {
+                eprintln!("waiting for {path}: {err}");
+                std::thread::sleep(Duration::from_millis(500));
+            }
+            Err(err) => bail!("failed to open {path}: {err}"),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Control, Dora};
+use eyre::{Context, OptionExt, bail};
+use mission::{Kind, Orchestrator, Outcome, POLL_INTERVAL, Stage};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::Path,
+    process::{Child, Command},
+    time::Duration,
+};
+
+mod mission;
+
+const TELEMETRY: Stage = Stage {
+    name: "telemetry",
+    dataflow: "telemetry.yml",
+    kind: Kind::Service,
+    timeout: Duration::from_secs(30),
+};
+const CALIBRATION: Stage = Stage {
+    name: "calibration",
+    dataflow: "calibration.yml",
+    kind: Kind::Task,
+    timeout: Duration::from_secs(60),
+};
+const SURVEY: Stage = Stage {
+    name: "survey",
+    dataflow: "survey.yml",
+    kind: Kind::Task,
+    timeout: Duration::from_secs(60),
+};
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("orchestration-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let nominal = [TELEMETRY, CALIBRATION, SURVEY];
+    // the same mission, but the survey fails half-way
+    let faulty = [
+        TELEMETRY,
+        CALIBRATION,
+        Stage {
+            dataflow: "survey-faulty.yml",
+            ..SURVEY
+        },
+    ];
+
+    let dora = Dora::from_env()?;
+    let dora_cli = dora.build_cli().await?;
+    for dataflow in [
+        "telemetry.yml",
+        "calibration.yml",
+        "survey.yml",
+        "survey-faulty.yml",
+    ] {
+        dora.build_dataflow(Path::new(dataflow)).await?;
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let (mut processes, control_addr) = spawn_coordinator_and_daemon(&dora_cli)?;
+        let mut control = Control::connect(control_addr, Duration::from_secs(30))?;
+        control
+            .wait_until(Duration::from_secs(30), POLL_INTERVAL, |c| {
+                Ok(c.connected_daemons()?.len() == 1)
+            })
+            .wrap_err("daemon did not connect to the coordinator")?;
+        let mut orchestrator = Orchestrator {
+            dora: &dora_cli,
+            control_addr,
+            control: &mut control,
+        };
+
+        let outcome = orchestrator.run("nominal", &nominal)?;
+        print_outcome("nominal", &outcome);
+        check_outcome(&outcome, &["telemetry", "calibration", "survey"], None)?;
+
+        let outcome = orchestrator.run("faulty", &faulty)?;
+        print_outcome("faulty", &outcome);
+        check_outcome(&outcome, &["telemetry", "calibration"], Some("survey"))?;
+
+        if control.running_dataflows()? != 0 {
+            bail!("dataflows are still running after both missions");
+        }
+        control.destroy()?;
+        processes.wait()
+    })
+    .await??;
+
+    println!("both missions ended with all services stopped");
+    Ok(())
+}
+
+fn print_outcome(mission: &str, outcome: &Outcome) {
+    println!("mission `{mission}`:");
+    println!("  completed: {:?}", outcome.completed);
+    if let Some((stage, reason)) = &outcome.failed {
+        println!("  failed:    {stage} ({reason})");
+    }
+    println!("  stopped:   {:?}", outcome.stopped);
+    for (stage, reason) in &outcome.failed_stops {
+        println!("  NOT stopped: {stage} ({reason})");
+    }
+}
+
+fn check_outcome(outcome: &Outcome, completed: &[&str], failed: Option<&str>) -> eyre::Result<()> {
+    if outcome.completed != completed {
+        bail!(
+            "expected the stages {completed:?} to complete, got {:?}",
+            outcome.completed
+        );
+    }
+    let failed_stage = outcome.failed.as_ref().map(|(stage, _)| *stage);
+    if failed_stage != failed {
+        bail!("expected {failed:?} to fail, got {failed_stage:?}");
+    }
+    // the telemetry service must be stopped in any case
+    if outcome.stopped != ["telemetry"] || !outcome.failed_stops.is_empty() {
+        bail!(
+            "expected `telemetry` to be stopped, got {:?} and failed stops {:?}",
+            outcome.stopped,
+            outcome.failed_stops
+        );
+    }
+    Ok(())
+}
+
+fn spawn_coordinator_and_daemon(dora: &Path) -> eyre::Result<(Processes, SocketAddr)> {
+    let interface_port =
+        port_check::free_local_ipv4_port_in_range(10000..=15000).ok_or_eyre("No available port")?;
+    let control_port = port_check::free_local_ipv4_port_in_range((interface_port + 1)..=15000)
+        .ok_or_eyre("No available port")?;
+    let localhost = Ipv4Addr::LOCALHOST.to_string();
+
+    let mut processes = Processes(Vec::new());
+    processes.0.push(
+        Command::new(dora)
+            .arg("coordinator")
+            .args(["--interface", &localhost, "--control-interface", &localhost])
+            .args(["--port", &interface_port.to_string()])
+            .args(["--control-port", &control_port.to_string()])
+            .spawn()
+            .context("failed to spawn coordinator")?,
+    );
+    processes.0.push(
+        Command::new(dora)
+            .arg("daemon")
+            .args(["--coordinator-addr", &localhost])
+            .args(["--coordinator-port", &interface_port.to_string()])
+            .spawn()
+            .context("failed to spawn daemon")?,
+    );
+    Ok((
+        processes,
+        SocketAddr::from((Ipv4Addr::LOCALHOST, control_port)),
+    ))
+}
+
+/// Kills the coordinator and daemon if the runner fails half-way.
+struct Processes(Vec<Child>);
+
+impl Processes {
+    fn wait(&mut self) -> eyre::Result<()> {
+        for mut child in self.0.drain(..) {
+            child.wait()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Processes {
+    fn drop(&mut self) {
+        for child in &mut self.0 {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use dora_message::coordinator_to_cli::DataflowStatus;
+use example_runner_utils::Control;
+use eyre::{Context, bail};
+use std::{net::SocketAddr, path::Path, process::Command, time::Duration};
+
+/// How often the orchestrator asks the coordinator for the status of the dataflows.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What the orchestrator waits for before it starts the next stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Runs until it's stopped, like telemetry or a driver. Waits until it's running.
+    Service,
+    /// Finishes on its own, like a calibration. Waits until it has finished.
+    Task,
+}
+
+/// A dataflow that a mission starts.
+#[derive(Debug, Clone, Copy)]
+pub struct Stage {
+    pub name: &'static str,
+    pub dataflow: &'static str,
+    pub kind: Kind,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// Stages that reached their goal, in order.
+    pub completed: Vec<&'static str>,
+    /// The stage that failed and why. The mission doesn't start any stages after it.
+    pub failed: Option<(&'static str, String)>,
+    /// Services that were stopped, in the order that they were stopped.
+    pub stopped: Vec<&'static str>,
+    /// Dataflows that didn't stop, and why.
+    pub failed_stops: Vec<(&'static str, String)>,
+}
+
+/// Starts the stages of a mission in order, and stops the services that it started when the
+/// mission ends, or fails half-way.
+pub struct Orchestrator<'a> {
+    /// The `dora` binary, for `dora start` and `dora stop`.
+    pub dora: &'a Path,
+    pub control_addr: SocketAddr,
+    pub control: &'a mut Control,
+}
+
+impl Orchestrator<'_> {
+    /// Runs the stages of `mission` in order, until one fails.
+    ///
+    /// Each stage runs as a dataflow called `<mission>-<stage>`. Once all stages completed or
+    /// one failed, the services that were started are stopped in reverse order. After a
+    /// failure, these are the compensating stops that undo the partial mission.
+    pub fn run(&mut self, mission: &str, stages: &[Stage]) -> eyre::Result<Outcome> {
+        let mut outcome = Outcome::default();
+        let mut services = Vec::new();
+        for stage in stages {
+            let name = format!("{mission}-{}", stage.name);
+            tracing::info!("starting stage `{}` as `{name}`", stage.name);
+            match self.run_stage(stage, &name) {
+                Ok(()) => {
+                    outcome.completed.push(stage.name);
+                    if stage.kind == Kind::Service {
+                        services.push((stage.name, name));
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("stage `{}` failed: {err:#}", stage.name);
+                    // a stage that timed out may still be running
+                    if matches!(self.control.status(&name)?, Some(DataflowStatus::Running))
+                        && let Err(err) = self.stop(&name)
+                    {
+                        outcome.failed_stops.push((stage.name, format!("{err:#}")));
+                    }
+                    outcome.failed = Some((stage.name, format!("{err:#}")));
+                    break;
+                }
+            }
+        }
+
+        // try to stop every service, even if stopping an earlier one failed
+        for (stage, name) in services.into_iter().rev() {
+            tracing::info!("stopping service `{name}`");
+            match self.stop(&name) {
+                Ok(()) => outcome.stopped.push(stage),
+                Err(err) => outcome.failed_stops.push((stage, format!("{err:#}"))),
+            }
+        }
+        Ok(outcome)
+    }
+
+    fn run_stage(&mut self, stage: &Stage, name: &str) -> eyre::Result<()> {
+        let mut start = self.dora_command("start");
+        start.arg(stage.dataflow).args(["--name", name, "--detach"]);
+        run(&mut start).wrap_err_with(|| format!("failed to start `{}`", stage.dataflow))?;
+
+        let mut status = None;
+        self.control
+            .wait_until(stage.timeout, POLL_INTERVAL, |control| {
+                status = control.status(name)?;
+                Ok(match (stage.kind, &status) {
+                    (_, None) => false,
+                    (Kind::Service, Some(_)) => true,
+                    (Kind::Task, Some(status)) => !matches!(status, DataflowStatus::Running),
+                })
+            })
+            .wrap_err_with(|| format!("`{name}` didn't reach its goal, last status {status:?}"))?;
+        match (stage.kind, status) {
+            (Kind::Service, Some(DataflowStatus::Running))
+            | (Kind::Task, Some(DataflowStatus::Finished)) => Ok(()),
+            (_, status) => bail!("`{name}` is {status:?}"),
+        }
+    }
+
+    /// Stops the dataflow called `name`, and waits until the coordinator reports it stopped.
+    fn stop(&mut self, name: &str) -> eyre::Result<()> {
+        let mut stop = self.dora_command("stop");
+        stop.args(["--name", name]);
+        run(&mut stop).wrap_err_with(|| format!("failed to stop `{name}`"))?;
+        self.control
+            .wait_until(Duration::from_secs(30), POLL_INTERVAL, |control| {
+                Ok(!matches!(
+                    control.status(name)?,
+                    Some(DataflowStatus::Running)
+                ))
+            })
+            .wrap_err_with(|| format!("`{name}` is still running"))
+    }
+
+    fn dora_command(&self, subcommand: &str) -> Command {
+        let mut cmd = Command::new(self.dora);
+        cmd.arg(subcommand)
+            .args(["--coordinator-addr", &self.control_addr.ip().to_string()])
+            .args(["--coordinator-port", &self.control_addr.port().to_string()]);
+        cmd
+    }
+}
+
+/// Runs `cmd` and fails if it doesn't succeed.
+fn run(cmd: &mut Command) -> eyre::Result<()> {
+    let status = cmd.status()?;
+    if !status.success() {
+        bail!("{cmd:?} failed with {status}");
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use dora_node_api::{self, DoraNode, Event};
+
+/// A long-running service: counts its ticks until the dataflow is stopped.
+fn main() -> eyre::Result<()> {
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut beats: u64 = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    beats += 1;
+                    if beats % 10 == 0 {
+                        println!("{beats} heartbeats");
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => {
+                println!("stopped after {beats} heartbeats");
+                break;
+            }
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::bail;
+use example_node_utils::env_or;
+
+/// A task that does one of `STEPS` steps per `tick`, and exits when it's done, which finishes
+/// its dataflow. With `FAIL_AT_STEP`, it fails at that step instead, which fails the dataflow.
+fn main() -> eyre::Result<()> {
+    let steps: u64 = env_or("STEPS", 10)?;
+    // never fails if unset
+    let fail_at: u64 = env_or("FAIL_AT_STEP", u64::MAX)?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut step = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    step += 1;
+                    if step == fail_at {
+                        bail!("injected failure at step {step} of {steps}");
+                    }
+                    println!("step {step} of {steps}");
+                    if step == steps {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => bail!("stopped at step {step} of {steps}"),
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
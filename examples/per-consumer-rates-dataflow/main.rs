@@ -0,0 +1,70 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Rate that each consumer should receive, see `dataflow.yml`.
+const EXPECTED_RATES: &[(&str, f64)] = &[("control", 100.0), ("logger", 20.0), ("viz", 5.0)];
+/// Allowed relative deviation from the expected rate, covers timer jitter.
+const RATE_TOLERANCE: f64 = 0.2;
+/// A consumer served at its own rate should see fresh data, even if it is slow.
+const MAX_MEDIAN_LATENCY_MS: f64 = 50.0;
+
+/// Subset of `ConsumerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ConsumerReport {
+    rate_hz: f64,
+    median_latency_ms: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("per-consumer-rates-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    for (consumer, expected_hz) in EXPECTED_RATES {
+        let report = read_report(consumer)?;
+        if (report.rate_hz - expected_hz).abs() > expected_hz * RATE_TOLERANCE {
+            bail!(
+                "`{consumer}` received {:.1} Hz, expected {expected_hz} Hz",
+                report.rate_hz
+            );
+        }
+        if report.median_latency_ms > MAX_MEDIAN_LATENCY_MS {
+            bail!(
+                "`{consumer}` received scans {:.1} ms after they were sent",
+                report.median_latency_ms
+            );
+        }
+    }
+
+    let viz = read_report("viz")?;
+    let direct = read_report("viz-direct")?;
+    println!(
+        "viz at its own rate: {:.1} Hz, median latency {:.1} ms",
+        viz.rate_hz, viz.median_latency_ms
+    );
+    println!(
+        "viz subscribed to the source: {:.1} Hz, median latency {:.1} ms",
+        direct.rate_hz, direct.median_latency_ms
+    );
+
+    Ok(())
+}
+
+fn read_report(consumer: &str) -> eyre::Result<ConsumerReport> {
+    let path = format!("out/{consumer}.json");
+    let report =
+        std::fs::read_to_string(&path).with_context(|| format!("failed to read {path}"))?;
+    serde_json::from_str(&report).with_context(|| format!("invalid report in {path}"))
+}
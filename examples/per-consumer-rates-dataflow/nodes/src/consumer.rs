@@ -0,0 +1,89 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{array::AsArray, datatypes::Float64Type},
+};
+use eyre::OptionExt;
+use per_consumer_rates_dataflow_nodes::{
+    ConsumerReport, SENT_AT_KEY, SEQ_KEY, env_or, integer_parameter, now_us,
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Receives scans on its `scan` input and spends `WORK_MS` on each of them, like a controller,
+/// a logger, or a visualization.
+///
+/// Writes a report with the received rate and the latency to `out/<node id>.json`.
+fn main() -> eyre::Result<()> {
+    let work = Duration::from_millis(env_or("WORK_MS", 0)?);
+
+    let (node, mut events) = DoraNode::init_from_env()?;
+    let report_file = PathBuf::from(format!("out/{}.json", node.id()));
+
+    let mut report = ConsumerReport {
+        consumer: node.id().to_string(),
+        ..Default::default()
+    };
+    let mut latencies_ms = Vec::new();
+    let mut first_received: Option<Instant> = None;
+    let mut last_received = Instant::now();
+    let mut next_seq = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "scan" => {
+                    let sent_at = integer_parameter(&metadata.parameters, SENT_AT_KEY)?;
+                    latencies_ms.push(now_us().saturating_sub(sent_at) as f64 / 1000.0);
+                    last_received = Instant::now();
+                    first_received.get_or_insert(last_received);
+                    report.received += 1;
+
+                    let seq = integer_parameter(&metadata.parameters, SEQ_KEY)?;
+                    if let Some(expected) = next_seq {
+                        report.missed += seq.saturating_sub(expected);
+                    }
+                    next_seq = Some(seq + 1);
+
+                    data.as_primitive_opt::<Float64Type>()
+                        .ok_or_eyre("expected a Float64 scan")?;
+                    // stands in for processing the scan
+                    std::thread::sleep(work);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "scan" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if let Some(first) = first_received {
+        let elapsed = (last_received - first).as_secs_f64();
+        if report.received > 1 && elapsed > 0.0 {
+            report.rate_hz = (report.received - 1) as f64 / elapsed;
+        }
+    }
+    latencies_ms.sort_by(f64::total_cmp);
+    if let Some(max) = latencies_ms.last() {
+        report.median_latency_ms = latencies_ms[latencies_ms.len() / 2];
+        report.max_latency_ms = *max;
+    }
+    println!(
+        "received {} scans at {:.1} Hz, missed {}, latency median {:.1} ms, max {:.1} ms",
+        report.received,
+        report.rate_hz,
+        report.missed,
+        report.median_latency_ms,
+        report.max_latency_ms
+    );
+    if let Some(parent) = report_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use dora_node_api::{self, DoraNode, Event, Parameter, dora_core::config::DataId};
+use eyre::bail;
+use per_consumer_rates_dataflow_nodes::{SKIPPED_KEY, env_or, parse_rates};
+use std::time::{Duration, Instant};
+
+/// An output that is served at a fixed rate.
+struct RateLimited {
+    output: DataId,
+    period: Duration,
+    next_due: Option<Instant>,
+    skipped: u64,
+    sent: u64,
+}
+
+/// Forwards the `data` input to each output of `OUTPUT_RATES` at the rate of that output.
+///
+/// Every output gets the latest message once its period has passed, the messages in between
+/// are skipped. The payload is forwarded as is, together with the metadata of the producer,
+/// and a `skipped` count so that consumers know what they didn't see.
+fn main() -> eyre::Result<()> {
+    let rates = parse_rates(&env_or("OUTPUT_RATES", String::new())?)?;
+    if rates.is_empty() {
+        bail!("OUTPUT_RATES must list at least one `<output>:<hz>`");
+    }
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut outputs: Vec<RateLimited> = rates
+        .into_iter()
+        .map(|(output, period)| RateLimited {
+            output: DataId::from(output),
+            period,
+            next_due: None,
+            skipped: 0,
+            sent: 0,
+        })
+        .collect();
+    let mut received = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "data" => {
+                    received += 1;
+                    let now = Instant::now();
+                    for output in &mut outputs {
+                        if output.next_due.is_some_and(|due| now < due) {
+                            output.skipped += 1;
+                            continue;
+                        }
+                        // keep the phase, but don't send a burst to catch up after a gap
+                        let due = output.next_due.unwrap_or(now) + output.period;
+                        output.next_due = Some(if due <= now { now + output.period } else { due });
+
+                        let mut parameters = metadata.parameters.clone();
+                        parameters.insert(
+                            SKIPPED_KEY.into(),
+                            Parameter::Integer(output.skipped as i64),
+                        );
+                        // cloning only bumps a reference count, the payload is copied once
+                        // per sent message, not once per consumer
+                        node.send_output(output.output.clone(), parameters, data.0.clone())?;
+                        output.skipped = 0;
+                        output.sent += 1;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "data" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    for output in &outputs {
+        println!(
+            "`{}`: forwarded {} of {received} messages",
+            output.output, output.sent
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{Context, bail, eyre};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub use example_node_utils::{env_or, now_us};
+
+/// Metadata key of the sequence number of a scan.
+pub const SEQ_KEY: &str = "seq";
+/// Metadata key of the wall-clock time at which the source sent a scan, in microseconds.
+pub const SENT_AT_KEY: &str = "sent_at_us";
+/// Metadata key of the number of scans that the fan-out skipped on an output since its
+/// previous message.
+pub const SKIPPED_KEY: &str = "skipped";
+
+/// Parses `OUTPUT_RATES` of the form `<output>:<hz>,<output>:<hz>`, e.g. `viz:5,logger:20`.
+pub fn parse_rates(spec: &str) -> eyre::Result<Vec<(String, Duration)>> {
+    spec.split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (output, hz) = entry
+                .split_once(':')
+                .ok_or_else(|| eyre!("expected `<output>:<hz>`, got `{entry}`"))?;
+            let hz: f64 = hz
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid rate in `{entry}`"))?;
+            if hz <= 0.0 {
+                bail!("rate of `{output}` must be positive");
+            }
+            Ok((output.trim().to_owned(), Duration::from_secs_f64(1.0 / hz)))
+        })
+        .collect()
+}
+
+/// Written by each consumer when it stops, to `out/<node id>.json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ConsumerReport {
+    pub consumer: String,
+    pub received: u64,
+    /// Scans that never reached this consumer, skipped by the fan-out or dropped from the
+    /// input queue.
+    pub missed: u64,
+    pub rate_hz: f64,
+    /// Time from sending a scan to its arrival at the consumer.
+    pub median_latency_ms: f64,
+    pub max_latency_ms: f64,
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<u64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => {
+            u64::try_from(*value).map_err(|_| eyre!("negative `{key}` parameter"))
+        }
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
@@ -0,0 +1,46 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::Float64Array,
+    dora_core::config::DataId,
+};
+use per_consumer_rates_dataflow_nodes::{SENT_AT_KEY, SEQ_KEY, env_or, now_us};
+
+/// Sends a scan of `SCAN_SIZE` float values on every `tick`, `COUNT` times.
+fn main() -> eyre::Result<()> {
+    let scan_size: usize = env_or("SCAN_SIZE", 10_000)?;
+    let count: u64 = env_or("COUNT", 1000)?;
+    let output = DataId::from("scan".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    if seq == count {
+                        break;
+                    }
+                    let phase = seq as f64 * 0.05;
+                    let scan = Float64Array::from_iter_values(
+                        (0..scan_size).map(|i| (phase + i as f64 * 0.001).sin()),
+                    );
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+                    parameters.insert(SENT_AT_KEY.into(), Parameter::Integer(now_us() as i64));
+                    node.send_output(output.clone(), parameters, scan)?;
+                    seq += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} scans");
+    Ok(())
+}
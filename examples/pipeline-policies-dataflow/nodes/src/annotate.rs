@@ -0,0 +1,51 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter, arrow::array::UInt8Array, dora_core::config::DataId,
+};
+use pipeline_policies_dataflow_nodes::{Frame, frame_parameters, simulate_work};
+
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("image".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let frame = Frame::from_input(&metadata.parameters, &data)?;
+                    simulate_work();
+
+                    let mut pixels = frame.pixels.to_vec();
+                    if let Some(Parameter::ListInt(bbox)) = metadata.parameters.get("bbox")
+                        && let [x, y, w, h] = bbox[..]
+                    {
+                        draw_rectangle(&mut pixels, frame.width, [x, y, w, h]);
+                    }
+
+                    node.send_output(
+                        output.clone(),
+                        frame_parameters(frame.id, frame.width, frame.height),
+                        UInt8Array::from(pixels),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn draw_rectangle(pixels: &mut [u8], width: usize, [x, y, w, h]: [i64; 4]) {
+    let (x, y, w, h) = (x as usize, y as usize, w as usize, h as usize);
+    for dx in x..x + w {
+        pixels[y * width + dx] = 0;
+        pixels[(y + h - 1) * width + dx] = 0;
+    }
+    for dy in y..y + h {
+        pixels[dy * width + x] = 0;
+        pixels[dy * width + x + w - 1] = 0;
+    }
+}
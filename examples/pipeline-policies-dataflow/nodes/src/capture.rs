@@ -0,0 +1,55 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::UInt8Array, dora_core::config::DataId};
+use pipeline_policies_dataflow_nodes::frame_parameters;
+
+const WIDTH: usize = 640;
+const HEIGHT: usize = 480;
+
+fn main() -> eyre::Result<()> {
+    let frames: i64 = std::env::var("FRAMES")
+        .ok()
+        .and_then(|frames| frames.parse().ok())
+        .unwrap_or(150);
+    let output = DataId::from("image".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut frame_id = 0;
+    while frame_id < frames {
+        let event = match events.recv() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            Event::Input { id, .. } if id.as_str() == "tick" => {
+                let pixels = synthetic_frame(frame_id);
+                node.send_output(
+                    output.clone(),
+                    frame_parameters(frame_id, WIDTH, HEIGHT),
+                    UInt8Array::from(pixels),
+                )?;
+                frame_id += 1;
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("captured {frame_id} frames");
+    Ok(())
+}
+
+/// Dark gradient background with a bright square moving from left to right.
+fn synthetic_frame(frame_id: i64) -> Vec<u8> {
+    let square = 40;
+    let left = (frame_id as usize * 4) % (WIDTH - square);
+    let top = HEIGHT / 2 - square / 2;
+    let mut pixels = Vec::with_capacity(WIDTH * HEIGHT);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let inside = (left..left + square).contains(&x) && (top..top + square).contains(&y);
+            pixels.push(if inside { 255 } else { (x * 64 / WIDTH) as u8 });
+        }
+    }
+    pixels
+}
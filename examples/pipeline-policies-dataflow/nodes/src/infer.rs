@@ -0,0 +1,60 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter, arrow::array::UInt8Array, dora_core::config::DataId,
+};
+use pipeline_policies_dataflow_nodes::{Frame, frame_parameters, simulate_work};
+
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("image".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let frame = Frame::from_input(&metadata.parameters, &data)?;
+                    // stands in for a model forward pass
+                    simulate_work();
+                    let bbox = detect_bright_region(&frame);
+
+                    let mut parameters = frame_parameters(frame.id, frame.width, frame.height);
+                    if let Some(bbox) = bbox {
+                        parameters.insert("bbox".into(), Parameter::ListInt(bbox.to_vec()));
+                    }
+                    node.send_output(
+                        output.clone(),
+                        parameters,
+                        UInt8Array::from(frame.pixels.to_vec()),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the bounding box `[x, y, w, h]` of all pixels brighter than a fixed threshold.
+fn detect_bright_region(frame: &Frame) -> Option<[i64; 4]> {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (usize::MAX, usize::MAX, 0, 0);
+    for (i, pixel) in frame.pixels.iter().enumerate() {
+        if *pixel > 200 {
+            let (x, y) = (i % frame.width, i / frame.width);
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+    }
+    (min_x <= max_x).then(|| {
+        [
+            min_x as i64,
+            min_y as i64,
+            (max_x - min_x + 1) as i64,
+            (max_y - min_y + 1) as i64,
+        ]
+    })
+}
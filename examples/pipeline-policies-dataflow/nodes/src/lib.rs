@@ -0,0 +1,64 @@
+use dora_node_api::{ArrowData, MetadataParameters, Parameter};
+use eyre::{Context, eyre};
+use std::time::Duration;
+
+/// A grayscale frame together with the metadata that travels with it through the pipeline.
+pub struct Frame<'a> {
+    pub id: i64,
+    pub width: usize,
+    pub height: usize,
+    pub pixels: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    pub fn from_input(parameters: &MetadataParameters, data: &'a ArrowData) -> eyre::Result<Self> {
+        let pixels: &[u8] = TryFrom::try_from(data).context("expected uint8 pixel buffer")?;
+        let frame = Self {
+            id: integer(parameters, "frame_id")?,
+            width: integer(parameters, "width")? as usize,
+            height: integer(parameters, "height")? as usize,
+            pixels,
+        };
+        if frame.pixels.len() != frame.width * frame.height {
+            eyre::bail!(
+                "frame {} has {} pixels, expected {}x{}",
+                frame.id,
+                frame.pixels.len(),
+                frame.width,
+                frame.height
+            );
+        }
+        Ok(frame)
+    }
+}
+
+/// Metadata attached to every frame: its id, size, and the queue policy of the input it
+/// was received on (set through the `INPUT_POLICY` env variable).
+pub fn frame_parameters(id: i64, width: usize, height: usize) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert("frame_id".into(), Parameter::Integer(id));
+    parameters.insert("width".into(), Parameter::Integer(width as i64));
+    parameters.insert("height".into(), Parameter::Integer(height as i64));
+    parameters.insert("policy".into(), Parameter::String(input_policy()));
+    parameters
+}
+
+pub fn integer(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        other => Err(eyre!("expected integer metadata `{key}`, got {other:?}")),
+    }
+}
+
+pub fn input_policy() -> String {
+    std::env::var("INPUT_POLICY").unwrap_or_else(|_| "-".to_owned())
+}
+
+/// Simulates the processing cost of a stage, configured through `WORK_MS`.
+pub fn simulate_work() {
+    let work_ms = std::env::var("WORK_MS")
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .unwrap_or(0);
+    std::thread::sleep(Duration::from_millis(work_ms));
+}
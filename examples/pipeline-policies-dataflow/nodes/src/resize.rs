@@ -0,0 +1,42 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::UInt8Array, dora_core::config::DataId};
+use pipeline_policies_dataflow_nodes::{Frame, frame_parameters, simulate_work};
+
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("image".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let frame = Frame::from_input(&metadata.parameters, &data)?;
+                    simulate_work();
+
+                    // 2x2 box filter halving both dimensions
+                    let (width, height) = (frame.width / 2, frame.height / 2);
+                    let mut pixels = Vec::with_capacity(width * height);
+                    for y in 0..height {
+                        for x in 0..width {
+                            let at = |dx, dy| {
+                                frame.pixels[(2 * y + dy) * frame.width + 2 * x + dx] as u16
+                            };
+                            pixels.push(((at(0, 0) + at(1, 0) + at(0, 1) + at(1, 1)) / 4) as u8);
+                        }
+                    }
+
+                    node.send_output(
+                        output.clone(),
+                        frame_parameters(frame.id, width, height),
+                        UInt8Array::from(pixels),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use dora_node_api::{self, DoraNode, Event, Parameter};
+use eyre::bail;
+use pipeline_policies_dataflow_nodes::integer;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Pipeline stages in order, each stage consumes the output of the previous one.
+const STAGES: [&str; 4] = ["captured", "resized", "inferred", "annotated"];
+
+#[derive(Default)]
+struct StageStats {
+    policy: String,
+    frames: BTreeSet<i64>,
+    last: Option<i64>,
+}
+
+fn main() -> eyre::Result<()> {
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut stats: BTreeMap<&str, StageStats> = BTreeMap::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata,
+                data: _,
+            } => {
+                let Some(stage) = STAGES.iter().find(|stage| **stage == id.as_str()) else {
+                    eprintln!("Ignoring unexpected input `{id}`");
+                    continue;
+                };
+                let frame_id = integer(&metadata.parameters, "frame_id")?;
+                let entry = stats.entry(stage).or_default();
+                if let Some(Parameter::String(policy)) = metadata.parameters.get("policy") {
+                    entry.policy.clone_from(policy);
+                }
+                if let Some(last) = entry.last
+                    && frame_id <= last
+                {
+                    bail!("stage `{stage}` reordered frames: got {frame_id} after {last}");
+                }
+                entry.last = Some(frame_id);
+                entry.frames.insert(frame_id);
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "{:<10} {:<12} {:>8} {:>8}",
+        "stage", "policy", "received", "dropped"
+    );
+    let mut upstream: Option<&StageStats> = None;
+    for stage in STAGES {
+        let Some(current) = stats.get(stage) else {
+            bail!("no frames received from stage `{stage}`");
+        };
+        let dropped = upstream.map_or(0, |upstream| upstream.frames.len() - current.frames.len());
+        println!(
+            "{stage:<10} {:<12} {:>8} {dropped:>8}",
+            current.policy,
+            current.frames.len()
+        );
+
+        if let Some(upstream) = upstream {
+            if let Some(unknown) = current.frames.difference(&upstream.frames).next() {
+                bail!("stage `{stage}` emitted frame {unknown} that it never received");
+            }
+            if current.policy == "block" && dropped > 0 {
+                bail!("stage `{stage}` uses the `block` policy but lost {dropped} frame(s)");
+            }
+        }
+        upstream = Some(current);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Composes a dataflow from fragment files.
+//!
+//! Each fragment is a dataflow descriptor with a partial `nodes` list. Nodes are merged by id:
+//! a node that is new is appended, a node that an earlier fragment already defined is updated
+//! field by field, with nested mappings like `env` and `inputs` merged recursively. Other
+//! top-level keys are merged the same way.
+
+use eyre::{Context, OptionExt, bail};
+use serde_yaml::{Mapping, Value};
+use std::{collections::BTreeMap, path::Path};
+
+/// The fragments of each profile, from `profiles.yml`.
+pub fn read_profiles(path: &Path) -> eyre::Result<BTreeMap<String, Vec<String>>> {
+    let yaml = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    serde_yaml::from_str(&yaml).with_context(|| format!("invalid {}", path.display()))
+}
+
+/// Merges `fragments`, relative to `dir`, in order, and checks that every input comes from a
+/// node of the result.
+pub fn compose(dir: &Path, fragments: &[String]) -> eyre::Result<Value> {
+    let mut dataflow = Mapping::new();
+    let mut nodes: Vec<Value> = Vec::new();
+    for fragment in fragments {
+        let path = dir.join(fragment);
+        let yaml = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read fragment {}", path.display()))?;
+        let Value::Mapping(mut fragment_map) = serde_yaml::from_str(&yaml)
+            .with_context(|| format!("invalid fragment {}", path.display()))?
+        else {
+            bail!("fragment {} is not a mapping", path.display());
+        };
+
+        if let Some(fragment_nodes) = fragment_map.remove("nodes") {
+            let Value::Sequence(fragment_nodes) = fragment_nodes else {
+                bail!("`nodes` of fragment {fragment} is not a list");
+            };
+            for node in fragment_nodes {
+                let id = node_id(&node).with_context(|| format!("in fragment {fragment}"))?;
+                match nodes
+                    .iter_mut()
+                    .find(|other| node_id(other).ok() == Some(id))
+                {
+                    Some(existing) => merge(existing, node),
+                    None => nodes.push(node),
+                }
+            }
+        }
+        for (key, value) in fragment_map {
+            match dataflow.get_mut(&key) {
+                Some(existing) => merge(existing, value),
+                None => {
+                    dataflow.insert(key, value);
+                }
+            }
+        }
+    }
+
+    for node in &nodes {
+        let id = node_id(node)?;
+        if node.get("path").is_none() && node.get("operators").is_none() {
+            bail!("node `{id}` has no `path`, it is only updated but never defined");
+        }
+        let Some(Value::Mapping(inputs)) = node.get("inputs") else {
+            continue;
+        };
+        for (input, source) in inputs {
+            let source = match source {
+                Value::Mapping(input) => input.get("source").unwrap_or(&Value::Null),
+                source => source,
+            };
+            let source = source
+                .as_str()
+                .ok_or_eyre(format!("input {input:?} of node `{id}` has no source"))?;
+            let Some((from, _)) = source.split_once('/') else {
+                bail!("invalid source `{source}` of node `{id}`");
+            };
+            if from != "dora" && !nodes.iter().any(|other| node_id(other).ok() == Some(from)) {
+                bail!(
+                    "node `{id}` has an input from node `{from}`, which none of the fragments \
+                     {fragments:?} defines"
+                );
+            }
+        }
+    }
+
+    dataflow.insert("nodes".into(), Value::Sequence(nodes));
+    Ok(Value::Mapping(dataflow))
+}
+
+fn node_id(node: &Value) -> eyre::Result<&str> {
+    node.get("id")
+        .and_then(Value::as_str)
+        .ok_or_eyre("node without an `id`")
+}
+
+/// Merges `patch` into `base`: mappings key by key, everything else is replaced.
+fn merge(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Mapping(base), Value::Mapping(patch)) => {
+            for (key, value) in patch {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
@@ -0,0 +1,145 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, OptionExt, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+mod compose;
+
+/// Must match `ROBOT_ADDR` in `fragments/robot.yml`.
+const ROBOT_PORT: &str = "18090";
+
+/// Subset of `ControllerSummary` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ControllerSummary {
+    commands: u64,
+    max_command: f64,
+    final_error: f64,
+}
+
+/// Subset of `RobotReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct RobotReport {
+    commands: u64,
+    final_position: f64,
+}
+
+fn parse_profile() -> eyre::Result<String> {
+    let mut profile = "sim".to_owned();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--profile" => profile = args.next().ok_or_eyre("--profile requires a value")?,
+            other => bail!("unknown argument `{other}`"),
+        }
+    }
+    Ok(profile)
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("profiles-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let profile = parse_profile()?;
+    let profiles = compose::read_profiles(Path::new("profiles.yml"))?;
+    let Some(fragments) = profiles.get(&profile) else {
+        let names: Vec<_> = profiles.keys().map(String::as_str).collect();
+        bail!(
+            "unknown profile `{profile}`, expected one of: {}",
+            names.join(", ")
+        );
+    };
+    let composed = compose::compose(Path::new("fragments"), fragments)
+        .with_context(|| format!("failed to compose profile `{profile}`"))?;
+    // in the example directory, since dora resolves node paths relative to the dataflow file
+    let dataflow = format!("dataflow.{profile}.yml");
+    let dataflow = Path::new(&dataflow);
+    std::fs::write(dataflow, serde_yaml::to_string(&composed)?)?;
+    println!(
+        "composed {} from {}",
+        dataflow.display(),
+        fragments.join(" + ")
+    );
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+
+    let robot = if profile == "robot" {
+        // stands in for the hardware, the driver connects to it when the dataflow starts
+        let robot = tokio::process::Command::new("nodes/target/release/mock-robot")
+            .args(["--port", ROBOT_PORT])
+            .args(["--report", "out/robot.json"])
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to start mock robot")?;
+        Some(robot)
+    } else {
+        None
+    };
+
+    dora.run_dataflow(dataflow).await?;
+
+    let summary: ControllerSummary = serde_json::from_str(
+        &std::fs::read_to_string("out/summary.json")
+            .context("controller did not write a summary")?,
+    )?;
+    let max_speed = composed["nodes"]
+        .as_sequence()
+        .and_then(|nodes| nodes.iter().find(|node| node["id"] == "controller"))
+        .and_then(|controller| controller["env"]["MAX_SPEED"].as_f64())
+        .ok_or_eyre("composed dataflow has no MAX_SPEED for the controller")?;
+    if summary.commands == 0 {
+        bail!("the controller sent no commands");
+    }
+    if summary.max_command > max_speed {
+        bail!(
+            "the controller commanded {:.2}, above MAX_SPEED {max_speed} of profile `{profile}`",
+            summary.max_command
+        );
+    }
+    if summary.final_error > 0.05 {
+        bail!(
+            "the position is {:.3} away from the last target",
+            summary.final_error
+        );
+    }
+
+    let debug_log = Path::new("out/debug.jsonl").exists();
+    let has_viz = fragments.iter().any(|fragment| fragment == "debug.yml");
+    if debug_log != has_viz {
+        bail!("expected `viz` to run only in profiles with debug.yml");
+    }
+
+    if let Some(mut robot) = robot {
+        if !robot.wait().await?.success() {
+            bail!("mock robot failed");
+        }
+        let report: RobotReport = serde_json::from_str(
+            &std::fs::read_to_string("out/robot.json")
+                .context("mock robot did not write a report")?,
+        )?;
+        if report.commands == 0 {
+            bail!("the robot driver never talked to the robot");
+        }
+        println!(
+            "robot received {} commands, stopped at {:.3}",
+            report.commands, report.final_position
+        );
+    }
+
+    println!(
+        "profile `{profile}`: {} commands, max {:.2} of {max_speed}, final error {:.3}",
+        summary.commands, summary.max_command, summary.final_error
+    );
+
+    Ok(())
+}
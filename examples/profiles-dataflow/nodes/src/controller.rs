@@ -0,0 +1,61 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use profiles_dataflow_nodes::{ControllerSummary, env_or, value, write_json};
+use std::path::PathBuf;
+
+/// Proportional position controller: sends a velocity command on `command` for every `state`,
+/// limited to `MAX_SPEED`.
+///
+/// Stops when the `target` input closes, and writes a [`ControllerSummary`] to `SUMMARY_FILE`.
+fn main() -> eyre::Result<()> {
+    let kp: f64 = env_or("KP", 2.0)?;
+    let max_speed: f64 = env_or("MAX_SPEED", 1.0)?;
+    let summary_file: PathBuf = env_or("SUMMARY_FILE", "out/summary.json".to_owned())?.into();
+    let output = DataId::from("command".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut target = None;
+    let mut position = 0.0;
+    let mut commands = 0;
+    let mut max_command: f64 = 0.0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "target" => target = Some(value(&data)?),
+                "state" => {
+                    position = value(&data)?;
+                    let Some(target) = target else {
+                        continue;
+                    };
+                    let command = (kp * (target - position)).clamp(-max_speed, max_speed);
+                    node.send_output(output.clone(), Default::default(), command.into_arrow())?;
+                    commands += 1;
+                    max_command = max_command.max(command.abs());
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "target" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let summary = ControllerSummary {
+        commands,
+        max_command,
+        final_error: target.map_or(f64::NAN, |target| (target - position).abs()),
+    };
+    println!(
+        "{} commands, max {:.2}, final error {:.3}",
+        summary.commands, summary.max_command, summary.final_error
+    );
+    write_json(&summary_file, &summary)
+}
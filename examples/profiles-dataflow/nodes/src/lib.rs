@@ -0,0 +1,42 @@
+use dora_node_api::ArrowData;
+use eyre::Context;
+use serde::{Deserialize, Serialize};
+use std::{io::Write, path::Path};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Written by the controller when the planner stops.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ControllerSummary {
+    /// Commands sent, one per `state` message.
+    pub commands: u64,
+    /// Largest absolute velocity command.
+    pub max_command: f64,
+    /// Distance between the last target and the last position.
+    pub final_error: f64,
+}
+
+/// Written by the mock robot when the driver disconnects.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RobotReport {
+    pub commands: u64,
+    pub max_command: f64,
+    pub final_position: f64,
+}
+
+/// Targets, positions, and velocity commands are sent as a single `f64`.
+pub fn value(data: &ArrowData) -> eyre::Result<f64> {
+    f64::try_from(data).context("expected a single float64 value")
+}
+
+pub fn create_jsonl(path: &Path) -> eyre::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))
+}
+
+pub fn write_jsonl<T: Serialize>(file: &mut std::fs::File, record: &T) -> eyre::Result<()> {
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
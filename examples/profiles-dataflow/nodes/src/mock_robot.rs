@@ -0,0 +1,77 @@
+//! Stands in for the controller of a robot with a single linear axis.
+//!
+//! Usage: `mock-robot [--port <port>] [--report <path>]`
+//!
+//! Accepts one connection. Every `vel <m/s>` line sets the velocity of the axis and is answered
+//! with its position, `pos <m>`. When the driver disconnects, the robot writes what it has seen
+//! to `--report` and exits.
+
+use eyre::{Context, bail};
+use profiles_dataflow_nodes::{RobotReport, write_json};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::PathBuf,
+    time::Instant,
+};
+
+struct Args {
+    port: u16,
+    report: PathBuf,
+}
+
+fn main() -> eyre::Result<()> {
+    let args = parse_args()?;
+    let listener = TcpListener::bind(("127.0.0.1", args.port))
+        .with_context(|| format!("failed to listen on port {}", args.port))?;
+    println!("robot listening on port {}", args.port);
+
+    let (stream, peer) = listener.accept()?;
+    println!("driver connected from {peer}");
+    stream.set_nodelay(true)?;
+    let mut writer = stream.try_clone()?;
+
+    let mut report = RobotReport::default();
+    let mut velocity = 0.0;
+    let mut last_update = Instant::now();
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let now = Instant::now();
+        report.final_position += velocity * (now - last_update).as_secs_f64();
+        last_update = now;
+
+        velocity = match line.trim().strip_prefix("vel ") {
+            Some(velocity) => velocity.parse()?,
+            None => bail!("unexpected command `{}`", line.trim()),
+        };
+        report.commands += 1;
+        report.max_command = report.max_command.max(f64::abs(velocity));
+        writeln!(writer, "pos {}", report.final_position)?;
+    }
+
+    println!(
+        "driver disconnected after {} commands, at {:.3}",
+        report.commands, report.final_position
+    );
+    write_json(&args.report, &report)
+}
+
+fn parse_args() -> eyre::Result<Args> {
+    let mut args = Args {
+        port: 18090,
+        report: PathBuf::from("out/robot.json"),
+    };
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = || {
+            argv.next()
+                .ok_or_else(|| eyre::eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--port" => args.port = value()?.parse()?,
+            "--report" => args.report = value()?.into(),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    Ok(args)
+}
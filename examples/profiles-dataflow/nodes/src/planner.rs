@@ -0,0 +1,41 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use eyre::Context;
+use profiles_dataflow_nodes::env_or;
+use std::time::{Duration, Instant};
+
+/// Sends the position targets in `TARGETS` on `target`, each one for `HOLD_MS`, and then stops.
+fn main() -> eyre::Result<()> {
+    let targets: Vec<f64> = env_or("TARGETS", "1.0".to_owned())?
+        .split(',')
+        .map(|target| target.trim().parse())
+        .collect::<Result<_, _>>()
+        .context("invalid value for TARGETS")?;
+    let hold = Duration::from_millis(env_or("HOLD_MS", 4000)?);
+    let output = DataId::from("target".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    let index = (start.elapsed().as_millis() / hold.as_millis()) as usize;
+                    let Some(&target) = targets.get(index) else {
+                        break;
+                    };
+                    node.send_output(output.clone(), Default::default(), target.into_arrow())?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
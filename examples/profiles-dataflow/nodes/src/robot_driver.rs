@@ -0,0 +1,62 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use eyre::{Context, bail};
+use profiles_dataflow_nodes::{env_or, value};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+};
+
+/// Drive of the robot, controlled over a line-based TCP protocol at `ROBOT_ADDR`: on every
+/// `tick`, sends `vel <m/s>` with the last velocity `command`, and sends the position of the
+/// `pos <m>` reply on `state`.
+///
+/// Stops when the `command` input closes, which stops the robot.
+fn main() -> eyre::Result<()> {
+    let addr: String = env_or("ROBOT_ADDR", "127.0.0.1:18090".to_owned())?;
+    let output = DataId::from("state".to_owned());
+
+    let stream = TcpStream::connect(&addr)
+        .with_context(|| format!("failed to connect to the robot at {addr}"))?;
+    stream.set_nodelay(true)?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut velocity = 0.0;
+    let mut line = String::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "command" => velocity = value(&data)?,
+                "tick" => {
+                    writeln!(writer, "vel {velocity}")?;
+                    line.clear();
+                    if reader.read_line(&mut line)? == 0 {
+                        bail!("the robot closed the connection");
+                    }
+                    let position: f64 = match line.trim().strip_prefix("pos ") {
+                        Some(position) => position.parse()?,
+                        None => bail!("unexpected reply from the robot: `{}`", line.trim()),
+                    };
+                    node.send_output(output.clone(), Default::default(), position.into_arrow())?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "command" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    writeln!(writer, "vel 0")?;
+    Ok(())
+}
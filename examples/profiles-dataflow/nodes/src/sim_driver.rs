@@ -0,0 +1,46 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use profiles_dataflow_nodes::value;
+use std::time::Instant;
+
+/// Simulated drive: integrates the last velocity `command` on every `tick`, and sends the
+/// resulting position on `state`.
+///
+/// Stops when the `command` input closes.
+fn main() -> eyre::Result<()> {
+    let output = DataId::from("state".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut velocity = 0.0;
+    let mut position = 0.0;
+    let mut last_tick: Option<Instant> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "command" => velocity = value(&data)?,
+                "tick" => {
+                    let now = Instant::now();
+                    if let Some(last_tick) = last_tick {
+                        position += velocity * (now - last_tick).as_secs_f64();
+                    }
+                    last_tick = Some(now);
+                    node.send_output(output.clone(), Default::default(), position.into_arrow())?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "command" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use dora_node_api::{self, DoraNode, Event};
+use profiles_dataflow_nodes::{create_jsonl, env_or, value, write_jsonl};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Half the width of the plot, in characters.
+const HALF_WIDTH: f64 = 30.0;
+/// Positions at the edges of the plot.
+const RANGE: f64 = 1.5;
+
+#[derive(Debug, Serialize)]
+struct LogEntry<'a> {
+    input: &'a str,
+    value: f64,
+}
+
+/// Logs every message to `LOG_FILE`, and plots target (`|`) and position (`*`) in the terminal
+/// for every tenth `state`.
+fn main() -> eyre::Result<()> {
+    let log_file: PathBuf = env_or("LOG_FILE", "out/debug.jsonl".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut log = create_jsonl(&log_file)?;
+    let mut target = None;
+    let mut states = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => {
+                let value = value(&data)?;
+                write_jsonl(
+                    &mut log,
+                    &LogEntry {
+                        input: id.as_str(),
+                        value,
+                    },
+                )?;
+                match id.as_str() {
+                    "target" => target = Some(value),
+                    "state" => {
+                        states += 1;
+                        if states % 10 == 0 {
+                            println!("{}", plot(target, value));
+                        }
+                    }
+                    "command" => {}
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                }
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn plot(target: Option<f64>, position: f64) -> String {
+    let column = |value: f64| {
+        (HALF_WIDTH + value.clamp(-RANGE, RANGE) / RANGE * HALF_WIDTH).round() as usize
+    };
+    let mut line = vec![b' '; 2 * HALF_WIDTH as usize + 1];
+    if let Some(target) = target {
+        line[column(target)] = b'|';
+    }
+    line[column(position)] = b'*';
+    format!("{:+.2} {}", position, String::from_utf8_lossy(&line))
+}
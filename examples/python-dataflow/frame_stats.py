@@ -0,0 +1,89 @@
+"""
+Frame statistics operator.
+- Counts the frames of the camera and the detections of the object detection node
+- Reads the resolution and encoding of each frame from its metadata
+- Sends the statistics on `stats`, and a human readable line on `status` every
+  `STATUS_EVERY` frames
+- Writes the statistics and the Python environment it runs in to `REPORT_FILE`
+
+Unlike a node, an operator doesn't run its own event loop. The runtime node creates the
+`Operator` once and calls `on_event` for every event, so its attributes keep their state from
+one event to the next.
+"""
+
+import json
+import os
+import sys
+import time
+
+import pyarrow as pa
+from dora import DoraStatus
+
+STATUS_EVERY = int(os.getenv("STATUS_EVERY", "30"))
+REPORT_FILE = os.getenv("REPORT_FILE", "out/frame_stats.json")
+
+
+class Operator:
+    def __init__(self):
+        self.started = time.monotonic()
+        self.frames = 0
+        self.detection_messages = 0
+        self.resolution = None
+        self.encoding = None
+        self.sent = {"stats": 0, "status": 0}
+
+    def on_event(self, dora_event, send_output) -> DoraStatus:
+        if dora_event["type"] == "INPUT":
+            if dora_event["id"] == "image":
+                self.on_image(dora_event, send_output)
+            elif dora_event["id"] == "bbox":
+                self.detection_messages += 1
+        elif dora_event["type"] == "INPUT_CLOSED":
+            print(f"Input `{dora_event['id']}` was closed", flush=True)
+        elif dora_event["type"] == "STOP":
+            self.write_report()
+        return DoraStatus.CONTINUE
+
+    def on_image(self, dora_event, send_output):
+        # set by the camera node, e.g. `{"encoding": "bgr8", "width": 640, "height": 480}`
+        metadata = dora_event["metadata"]
+        resolution = (metadata.get("width"), metadata.get("height"))
+        if resolution != self.resolution:
+            print(f"camera resolution: {resolution[0]}x{resolution[1]}", flush=True)
+        self.resolution = resolution
+        self.encoding = metadata.get("encoding")
+        self.frames += 1
+
+        fps = self.frames / max(time.monotonic() - self.started, 1e-9)
+        stats = {
+            "frames": self.frames,
+            "detection_messages": self.detection_messages,
+            "fps": fps,
+        }
+        # passing the metadata of the frame on keeps its tracing context
+        send_output("stats", pa.array([stats]), metadata)
+        self.sent["stats"] += 1
+
+        if self.frames % STATUS_EVERY == 0:
+            status = (
+                f"{self.frames} frames at {fps:.1f} fps, "
+                f"{self.detection_messages} detection results"
+            )
+            send_output("status", pa.array([status]), metadata)
+            self.sent["status"] += 1
+            self.write_report()
+
+    def write_report(self):
+        report = {
+            "frames": self.frames,
+            "detection_messages": self.detection_messages,
+            "resolution": self.resolution,
+            "encoding": self.encoding,
+            "sent": self.sent,
+            # the environment that the runtime node loaded the operator in, e.g. the virtual
+            # environment of `uv` with `--uv`
+            "python": {"executable": sys.executable, "prefix": sys.prefix},
+        }
+        os.makedirs(os.path.dirname(REPORT_FILE) or ".", exist_ok=True)
+        with open(REPORT_FILE, "w") as file:
+            json.dump(report, file, indent=2)
@@ -1,19 +1,7 @@
 use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
 use eyre::{WrapErr, bail};
-use std::path::{Path, PathBuf};
-
-pub async fn run(program: &PathBuf, args: &[&str], pwd: Option<&Path>) -> eyre::Result<()> {
-    let mut run = tokio::process::Command::new(program);
-    run.args(args);
-
-    if let Some(pwd) = pwd {
-        run.current_dir(pwd);
-    }
-    if !run.status().await?.success() {
-        eyre::bail!("failed to run {args:?}");
-    };
-    Ok(())
-}
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
@@ -23,59 +11,52 @@ async fn main() -> eyre::Result<()> {
     std::env::set_current_dir(root.join(file!()).parent().unwrap())
         .wrap_err("failed to set working dir")?;
 
-    let uv = which::which("uv")
-        .context("failed to find `uv`. Make sure to install it using: https://docs.astral.sh/uv/getting-started/installation/")?;
-
-    run(&uv, &["venv", "-p", "3.11", "--seed"], None)
-        .await
-        .context("failed to create venv")?;
-
-    let dora = std::env::var("DORA").unwrap();
-    run(
-        &uv,
-        &[
-            "pip",
-            "install",
-            "-e",
-            &format!("{dora}/apis/python/node"),
-            "--reinstall",
-        ],
-        None,
-    )
-    .await
-    .context("Unable to install develop dora-rs API")?;
+    let dora = Dora::from_env()?.uv();
+    dora.uv_venv("3.11").create().await?;
 
+    // a report of an earlier run would hide a failure of the operator
+    match std::fs::remove_file("out/frame_stats.json") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
     let dataflow = Path::new("dataflow.yml");
-    run_dataflow(dataflow).await?;
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    check_frame_stats().context("the `frame-stats` operator did not work")?;
 
     Ok(())
 }
 
-async fn run_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-
-    // First build the dataflow (install requirements)
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(&dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("build").arg(dataflow).arg("--uv");
-    if !cmd.status().await?.success() {
-        bail!("failed to run dataflow");
-    };
+/// Checks the report of the Python operator in `frame_stats.py`.
+fn check_frame_stats() -> eyre::Result<()> {
+    let report: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("out/frame_stats.json")
+            .context("the operator did not write out/frame_stats.json")?,
+    )?;
+    let frames = report["frames"].as_u64().unwrap_or_default();
+    if frames == 0 {
+        bail!("the operator received no frames: {report}");
+    }
+    if report["sent"]["stats"].as_u64() != Some(frames) {
+        bail!("the operator should send stats for each of its {frames} frames: {report}");
+    }
 
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(&dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("run").arg(dataflow).arg("--uv");
-    if !cmd.status().await?.success() {
-        bail!("failed to run dataflow");
-    };
+    // with `--uv`, the runtime node has to load the operator with the Python of the virtual
+    // environment that `uv venv` created, where the dora API and the dependencies are installed
+    let venv = Path::new(".venv")
+        .canonicalize()
+        .context("no virtual environment")?;
+    let prefix = report["python"]["prefix"].as_str().unwrap_or_default();
+    if Path::new(prefix).canonicalize().ok().as_deref() != Some(venv.as_path()) {
+        bail!(
+            "the operator ran in the Python environment `{prefix}`, not in `{}`",
+            venv.display()
+        );
+    }
+    println!(
+        "the operator processed {frames} frames in {}",
+        venv.display()
+    );
     Ok(())
 }
@@ -0,0 +1,68 @@
+"""Compares the outputs and the processing times of both processor implementations."""
+
+import json
+import os
+import statistics
+import sys
+
+import numpy as np
+from dora import Node
+
+RESULT_FILE = os.getenv("RESULT_FILE", "out/benchmark.json")
+
+node = Node()
+
+outputs = {"python": {}, "rust": {}}
+timings = {"python": [], "rust": []}
+open_inputs = set(outputs)
+
+for event in node:
+    if event["type"] == "INPUT":
+        implementation = event["id"]
+        metadata = event["metadata"]
+        outputs[implementation][metadata["seq"]] = event["value"].to_numpy()
+        timings[implementation].append(metadata["processing_ns"] / 1e6)
+    elif event["type"] == "INPUT_CLOSED":
+        open_inputs.discard(event["id"])
+        if not open_inputs:
+            break
+    elif event["type"] == "STOP":
+        break
+
+# both implementations must compute the same result, otherwise the speedup is meaningless
+mismatches = [
+    seq
+    for seq, expected in outputs["python"].items()
+    if seq in outputs["rust"] and not np.allclose(expected, outputs["rust"][seq])
+]
+
+report = {}
+for implementation, values in timings.items():
+    if not values:
+        continue
+    values.sort()
+    report[implementation] = {
+        "messages": len(values),
+        "mean_ms": statistics.fmean(values),
+        "median_ms": statistics.median(values),
+        "p99_ms": values[min(len(values) - 1, int(len(values) * 0.99))],
+    }
+
+print(f"{'implementation':<16}{'messages':>10}{'mean':>12}{'median':>12}{'p99':>12}")
+for implementation, stats in report.items():
+    print(
+        f"{implementation:<16}{stats['messages']:>10}"
+        f"{stats['mean_ms']:>10.3f}ms{stats['median_ms']:>10.3f}ms{stats['p99_ms']:>10.3f}ms"
+    )
+if "python" in report and "rust" in report:
+    report["speedup"] = report["python"]["median_ms"] / report["rust"]["median_ms"]
+    print(f"rust extension is {report['speedup']:.1f}x faster (median)")
+report["mismatches"] = mismatches
+
+os.makedirs(os.path.dirname(RESULT_FILE), exist_ok=True)
+with open(RESULT_FILE, "w") as f:
+    json.dump(report, f, indent=2)
+
+if mismatches:
+    print(f"outputs differ for {len(mismatches)} messages, e.g. seq {mismatches[0]}")
+    sys.exit(1)
@@ -0,0 +1,16 @@
+"""Pure-Python implementation of the filter, used as the baseline of the benchmark."""
+
+
+def smooth(samples, alpha, spike_threshold):
+    """Remove spikes from `samples` and smooth them with an exponential moving average.
+
+    Same algorithm as `hotloop.smooth` in `hotloop/src/lib.rs`.
+    """
+    average = float(samples[0]) if len(samples) > 0 else 0.0
+    filtered = []
+    for sample in samples.tolist():
+        if abs(sample - average) > spike_threshold:
+            sample = average
+        average += alpha * (sample - average)
+        filtered.append(average)
+    return filtered
@@ -0,0 +1,43 @@
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::prelude::*;
+
+/// Removes spikes from `samples` and smooths them with an exponential moving average.
+///
+/// A sample that deviates from the current average by more than `spike_threshold` is replaced
+/// by the average. This is the same algorithm as `smooth` in `filters.py`, so both return the
+/// same values.
+///
+/// The loop runs without holding the GIL, so other Python threads of the node keep running.
+#[pyfunction]
+fn smooth<'py>(
+    py: Python<'py>,
+    samples: PyReadonlyArray1<'py, f64>,
+    alpha: f64,
+    spike_threshold: f64,
+) -> PyResult<Bound<'py, PyArray1<f64>>> {
+    let samples = samples.as_slice()?;
+    let filtered = py.allow_threads(|| smooth_slice(samples, alpha, spike_threshold));
+    Ok(PyArray1::from_vec(py, filtered))
+}
+
+fn smooth_slice(samples: &[f64], alpha: f64, spike_threshold: f64) -> Vec<f64> {
+    let mut average = samples.first().copied().unwrap_or_default();
+    samples
+        .iter()
+        .map(|&sample| {
+            let sample = if (sample - average).abs() > spike_threshold {
+                average
+            } else {
+                sample
+            };
+            average += alpha * (sample - average);
+            average
+        })
+        .collect()
+}
+
+#[pymodule]
+fn hotloop(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(smooth, m)?)?;
+    Ok(())
+}
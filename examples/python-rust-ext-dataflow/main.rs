@@ -0,0 +1,40 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{WrapErr, bail};
+use std::path::Path;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("python-rust-ext-dataflow-runner")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?.uv();
+    let venv = dora.uv_venv("3.11");
+    venv.create().await?;
+
+    // uv builds the extension through the maturin build backend declared in its pyproject.toml
+    venv.pip_install(["./hotloop", "--reinstall"])
+        .await
+        .context("failed to build and install the `hotloop` Rust extension")?;
+
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("out/benchmark.json")
+            .context("benchmark node did not write out/benchmark.json")?,
+    )?;
+    let Some(speedup) = report["speedup"].as_f64() else {
+        bail!("benchmark has no results for both implementations: {report}");
+    };
+    if speedup < 1.0 {
+        bail!("expected the Rust extension to be faster, got a speedup of {speedup:.2}");
+    }
+    println!("speedup of the Rust extension: {speedup:.1}x");
+
+    Ok(())
+}
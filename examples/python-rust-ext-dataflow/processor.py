@@ -0,0 +1,43 @@
+"""Filters every trace, either in pure Python or in the `hotloop` Rust extension.
+
+`IMPLEMENTATION` selects the filter: `python` (default) or `rust`. The time spent in the
+filter is attached to the output as `processing_ns` metadata.
+"""
+
+import os
+import time
+
+import numpy as np
+import pyarrow as pa
+from dora import Node
+
+IMPLEMENTATION = os.getenv("IMPLEMENTATION", "python")
+ALPHA = float(os.getenv("ALPHA", "0.05"))
+SPIKE_THRESHOLD = float(os.getenv("SPIKE_THRESHOLD", "1.0"))
+
+if IMPLEMENTATION == "rust":
+    from hotloop import smooth
+elif IMPLEMENTATION == "python":
+    from filters import smooth
+else:
+    raise ValueError(f"unknown IMPLEMENTATION `{IMPLEMENTATION}`")
+
+node = Node()
+
+for event in node:
+    if event["type"] == "INPUT":
+        # zero-copy view of the Arrow buffer
+        trace = event["value"].to_numpy(zero_copy_only=True)
+
+        start = time.perf_counter_ns()
+        filtered = smooth(trace, ALPHA, SPIKE_THRESHOLD)
+        elapsed = time.perf_counter_ns() - start
+
+        metadata = {
+            "seq": event["metadata"]["seq"],
+            "processing_ns": elapsed,
+            "implementation": IMPLEMENTATION,
+        }
+        node.send_output("filtered", pa.array(np.asarray(filtered)), metadata)
+    elif event["type"] == "STOP":
+        break
@@ -0,0 +1,27 @@
+"""Sends noisy sensor traces with occasional spikes."""
+
+import os
+
+import numpy as np
+import pyarrow as pa
+from dora import Node
+
+SAMPLES = int(os.getenv("SAMPLES", "20000"))
+MESSAGES = int(os.getenv("MESSAGES", "200"))
+
+node = Node()
+rng = np.random.default_rng(42)
+t = np.linspace(0.0, 2.0 * np.pi, SAMPLES)
+
+seq = 0
+for event in node:
+    if event["type"] == "INPUT":
+        if seq == MESSAGES:
+            break
+        trace = np.sin(t + seq * 0.1) + rng.normal(0.0, 0.05, SAMPLES)
+        spikes = rng.integers(0, SAMPLES, SAMPLES // 100)
+        trace[spikes] += rng.choice([-5.0, 5.0], len(spikes))
+        node.send_output("trace", pa.array(trace), {"seq": seq})
+        seq += 1
+    elif event["type"] == "STOP":
+        break
@@ -0,0 +1,89 @@
+#!/usr/bin/env python3
+"""
+Image classifier.
+- Loads the torchvision classification model `MODEL` with its default ImageNet weights, which
+  torchvision downloads on the first run
+- Classifies every frame of `image`, an `rgb8` or `bgr8` image in the format of the
+  `opencv-video-capture` node, on `DEVICE`
+- Sends the top-1 label of each frame on `label`, with the frame number and the inference time
+
+The frames are handed to PyTorch through DLPack, so the tensor is a view of the Arrow buffer
+that dora received, not a copy of it.
+"""
+
+import os
+import time
+
+import pyarrow as pa
+import torch
+import torchvision
+from dora import Node
+
+MODEL = os.getenv("MODEL", "mobilenet_v3_small")
+DEVICE = os.getenv("DEVICE") or ("cuda" if torch.cuda.is_available() else "cpu")
+
+LABEL = pa.struct(
+    [
+        pa.field("frame", pa.int64(), nullable=False),
+        pa.field("class_index", pa.int64(), nullable=False),
+        pa.field("label", pa.utf8(), nullable=False),
+        pa.field("score", pa.float32(), nullable=False),
+        pa.field("inference_ms", pa.float64(), nullable=False),
+    ]
+)
+
+
+def to_tensor(value, metadata):
+    """The image as a `3 x height x width` RGB tensor, from the flat `uint8` Arrow array."""
+    width, height = metadata["width"], metadata["height"]
+    encoding = metadata.get("encoding", "rgb8")
+    if encoding not in ("rgb8", "bgr8"):
+        raise ValueError(f"unsupported image encoding `{encoding}`")
+    # zero-copy, `value` must be a primitive array without nulls
+    image = torch.from_dlpack(value).reshape(height, width, 3).permute(2, 0, 1)
+    if encoding == "bgr8":
+        image = image.flip(0)
+    return image
+
+
+def main():
+    weights = torchvision.models.get_model_weights(MODEL).DEFAULT
+    model = torchvision.models.get_model(MODEL, weights=weights).eval().to(DEVICE)
+    # resizes, crops and normalizes the images like for the training of the weights
+    preprocess = weights.transforms()
+    categories = weights.meta["categories"]
+    print(f"loaded {MODEL} ({weights}) on {DEVICE}", flush=True)
+
+    node = Node()
+    frames = 0
+    for event in node:
+        if event["type"] == "INPUT" and event["id"] == "image":
+            metadata = event["metadata"]
+            image = to_tensor(event["value"], metadata)
+
+            started = time.perf_counter()
+            with torch.inference_mode():
+                batch = preprocess(image).unsqueeze(0).to(DEVICE)
+                scores = model(batch).softmax(dim=1)[0]
+                score, class_index = scores.max(dim=0)
+            inference_ms = (time.perf_counter() - started) * 1000
+
+            label = {
+                "frame": metadata.get("frame", frames),
+                "class_index": int(class_index),
+                "label": categories[int(class_index)],
+                "score": float(score),
+                "inference_ms": inference_ms,
+            }
+            # passing the metadata of the frame on keeps its tracing context
+            node.send_output("label", pa.array([label], type=LABEL), metadata)
+            frames += 1
+        elif event["type"] == "INPUT_CLOSED":
+            print(f"Input `{event['id']}` was closed", flush=True)
+        elif event["type"] == "STOP":
+            break
+    print(f"classified {frames} frames", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,87 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Must match the `env` of the camera in `dataflow.yml`.
+const FRAMES: i64 = 30;
+/// Number of classes of the ImageNet weights of torchvision.
+const IMAGENET_CLASSES: i64 = 1000;
+
+/// The report that `sink.py` writes.
+#[derive(Debug, Deserialize)]
+struct LabelReport {
+    labels: Vec<Label>,
+}
+
+/// Subset of `LABEL` in `classifier.py`.
+#[derive(Debug, Deserialize)]
+struct Label {
+    frame: i64,
+    class_index: i64,
+    label: String,
+    score: f32,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("python-torch-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let dora = Dora::from_env()?.uv();
+    dora.uv_venv("3.11").create().await?;
+
+    // installs PyTorch and torchvision into the venv
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: LabelReport = serde_json::from_str(
+        &std::fs::read_to_string("out/labels.json").context("the sink did not write a report")?,
+    )?;
+    let Some(last) = report.labels.last() else {
+        bail!("the classifier sent no labels");
+    };
+    // the classifier skips frames when it is slower than the camera, but always classifies
+    // the latest one
+    if report
+        .labels
+        .windows(2)
+        .any(|pair| pair[0].frame >= pair[1].frame)
+    {
+        bail!("the labels are not in the order of the frames");
+    }
+    if last.frame != FRAMES - 1 {
+        bail!(
+            "expected the last label to be of frame {}, got frame {}",
+            FRAMES - 1,
+            last.frame
+        );
+    }
+    for label in &report.labels {
+        if !(0..IMAGENET_CLASSES).contains(&label.class_index)
+            || label.label.is_empty()
+            || !(label.score > 0.0 && label.score <= 1.0)
+        {
+            bail!("invalid label {label:?}");
+        }
+    }
+    println!(
+        "the classifier labeled {} of {FRAMES} frames, the last one as `{}`",
+        report.labels.len(),
+        last.label
+    );
+
+    println!("Everything Done");
+    Ok(())
+}
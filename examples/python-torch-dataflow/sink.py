@@ -0,0 +1,40 @@
+#!/usr/bin/env python3
+"""
+Label sink.
+- Prints the top-1 label of every classified frame
+- Writes all received labels to `REPORT_FILE` when `label` closes
+"""
+
+import json
+import os
+
+from dora import Node
+
+REPORT_FILE = os.getenv("REPORT_FILE", "out/labels.json")
+
+
+def main():
+    node = Node()
+    labels = []
+    for event in node:
+        if event["type"] == "INPUT" and event["id"] == "label":
+            for label in event["value"].to_pylist():
+                print(
+                    f"frame {label['frame']}: {label['label']} ({label['score']:.1%}, "
+                    f"{label['inference_ms']:.1f} ms)",
+                    flush=True,
+                )
+                labels.append(label)
+        elif event["type"] == "INPUT_CLOSED":
+            print(f"Input `{event['id']}` was closed", flush=True)
+        elif event["type"] == "STOP":
+            break
+
+    os.makedirs(os.path.dirname(REPORT_FILE) or ".", exist_ok=True)
+    with open(REPORT_FILE, "w") as file:
+        json.dump({"labels": labels}, file, indent=2)
+    print(f"wrote {len(labels)} labels to {REPORT_FILE}", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,61 @@
+#!/usr/bin/env python3
+"""
+Synthetic camera.
+- Sends an `IMAGE_WIDTH`x`IMAGE_HEIGHT` RGB image on `image` every tick: a color gradient with
+  a bright disc that moves across it
+- Exits after `FRAMES` frames, which closes `image`
+
+The images use the same format as the `opencv-video-capture` node of the node hub: a flat
+`uint8` array with the `width`, `height` and `encoding` of the frame in its metadata, so a real
+camera can replace this node without changing the classifier.
+"""
+
+import os
+
+import numpy as np
+import pyarrow as pa
+from dora import Node
+
+FRAMES = int(os.getenv("FRAMES", "30"))
+IMAGE_WIDTH = int(os.getenv("IMAGE_WIDTH", "640"))
+IMAGE_HEIGHT = int(os.getenv("IMAGE_HEIGHT", "480"))
+
+
+def synthetic_image(frame):
+    y, x = np.mgrid[0:IMAGE_HEIGHT, 0:IMAGE_WIDTH]
+    image = np.empty((IMAGE_HEIGHT, IMAGE_WIDTH, 3), dtype=np.uint8)
+    image[..., 0] = (255 * x / IMAGE_WIDTH).astype(np.uint8)
+    image[..., 1] = (255 * y / IMAGE_HEIGHT).astype(np.uint8)
+    image[..., 2] = (frame * 8) % 256
+
+    center_x = (frame * 20) % IMAGE_WIDTH
+    center_y = IMAGE_HEIGHT // 2
+    radius = min(IMAGE_WIDTH, IMAGE_HEIGHT) // 6
+    disc = (x - center_x) ** 2 + (y - center_y) ** 2 < radius**2
+    image[disc] = (255, 220, 40)
+    return image
+
+
+def main():
+    node = Node()
+    frame = 0
+    for event in node:
+        if event["type"] == "INPUT" and event["id"] == "tick":
+            if frame == FRAMES:
+                break
+            image = synthetic_image(frame)
+            metadata = {
+                "width": IMAGE_WIDTH,
+                "height": IMAGE_HEIGHT,
+                "encoding": "rgb8",
+                "frame": frame,
+            }
+            node.send_output("image", pa.array(image.ravel()), metadata)
+            frame += 1
+        elif event["type"] == "STOP":
+            break
+    print(f"sent {frame} frames", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,131 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{path::Path, process::Stdio, time::Duration};
+
+/// The test pattern that `image_publisher` publishes: a dark left half and a bright right
+/// half, so that the only edge is the vertical line in the middle.
+const WIDTH: usize = 320;
+const HEIGHT: usize = 240;
+const PATTERN_FILE: &str = "out/pattern.ppm";
+const FRAME_ID: &str = "pattern";
+/// The pixels left and right of the line, in all rows but the first and the last, where the
+/// Sobel kernel doesn't fit.
+const EDGE_PIXELS: u64 = 2 * (HEIGHT as u64 - 2);
+
+/// Subset of `ImageReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ImageReport {
+    images: u64,
+    width: u32,
+    height: u32,
+    encoding: String,
+    #[serde(default)]
+    edge_pixels: Vec<u64>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("ros2-image-pipeline-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dataflow = Path::new("dataflow.yml");
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let count = descriptor["nodes"][0]["env"]["COUNT"]
+        .as_u64()
+        .ok_or_else(|| eyre::eyre!("the source has no `COUNT`"))?;
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    std::fs::create_dir_all("out")?;
+    write_pattern(Path::new(PATTERN_FILE))?;
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+
+    let ros = std::env::var("ROS").unwrap_or_else(|_| "/opt/ros/jazzy/setup.bash".into());
+    let mut publisher = tokio::process::Command::new("bash")
+        .arg("-c")
+        .arg(format!(
+            "source {ros}; exec ros2 run image_publisher image_publisher_node {PATTERN_FILE} \
+             --ros-args -p publish_rate:=10.0 -p frame_id:={FRAME_ID} \
+             -r image_raw:=/camera/image_raw"
+        ))
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start image_publisher")?;
+    // a plain ROS2 subscriber of what the dataflow publishes
+    let echo = tokio::process::Command::new("bash")
+        .arg("-c")
+        .arg(format!(
+            "source {ros}; exec ros2 topic echo --once --no-arr /dora/image_edges"
+        ))
+        .stdout(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start `ros2 topic echo`")?;
+
+    let result = dora.run_dataflow(dataflow).await;
+    publisher.kill().await?;
+    result?;
+    let echoed = tokio::time::timeout(Duration::from_secs(10), echo.wait_with_output())
+        .await
+        .context("`ros2 topic echo` received no image")??;
+    let echoed = String::from_utf8_lossy(&echoed.stdout);
+    println!("received on /dora/image_edges:\n{}", echoed.trim());
+    for field in [
+        format!("frame_id: {FRAME_ID}"),
+        format!("height: {HEIGHT}"),
+        format!("width: {WIDTH}"),
+        "encoding: mono8".to_owned(),
+        format!("step: {WIDTH}"),
+    ] {
+        if !echoed.contains(&field) {
+            bail!("the image on /dora/image_edges should have `{field}`");
+        }
+    }
+
+    let source: ImageReport = read_json("out/source.json")?;
+    println!(
+        "source: {} {}x{} {} images",
+        source.images, source.width, source.height, source.encoding
+    );
+    if source.images != count || (source.width, source.height) != (WIDTH as u32, HEIGHT as u32) {
+        bail!("the source should receive {count} {WIDTH}x{HEIGHT} images");
+    }
+    let detector: ImageReport = read_json("out/edge-detector.json")?;
+    println!(
+        "edge-detector: {} images, edge pixels {:?}",
+        detector.images,
+        detector.edge_pixels.first()
+    );
+    if detector.images != count || detector.edge_pixels.iter().any(|&n| n != EDGE_PIXELS) {
+        bail!("every image should have {EDGE_PIXELS} edge pixels, along the line in the middle");
+    }
+    let sink: ImageReport = read_json("out/sink.json")?;
+    println!("sink: published {} {} images", sink.images, sink.encoding);
+    if sink.images != count || sink.encoding != "mono8" {
+        bail!("the sink should publish {count} mono8 images");
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Writes the test pattern as a binary PPM, which `image_publisher` reads through OpenCV.
+fn write_pattern(path: &Path) -> eyre::Result<()> {
+    let mut ppm = format!("P6\n{WIDTH} {HEIGHT}\n255\n").into_bytes();
+    for _ in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let value = if x < WIDTH / 2 { 40 } else { 200 };
+            ppm.extend([value; 3]);
+        }
+    }
+    std::fs::write(path, ppm).with_context(|| format!("failed to write {}", path.display()))
+}
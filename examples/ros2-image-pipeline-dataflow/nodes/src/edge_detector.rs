@@ -0,0 +1,104 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::UInt8Array, dora_core::config::DataId};
+use eyre::bail;
+use ros2_image_pipeline_dataflow_nodes::{ImageMeta, ImageReport, env_or, pixels, write_json};
+use std::path::PathBuf;
+
+/// Converts every `image` to grayscale, and sends the magnitude of its Sobel gradient on
+/// `edges` as a `mono8` image with the header of the input.
+///
+/// Counts the pixels whose magnitude is at least `THRESHOLD` per image, and writes them with
+/// an [`ImageReport`] to `REPORT_FILE` when it stops.
+fn main() -> eyre::Result<()> {
+    let threshold: u8 = env_or("THRESHOLD", 64)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/edge-detector.json".to_owned())?.into();
+    let output = DataId::from("edges".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = ImageReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let meta = ImageMeta::from_parameters(&metadata.parameters)?;
+                    let gray = grayscale(pixels(&data, &meta)?, &meta.encoding)?;
+                    let edges = sobel(&gray, meta.width as usize, meta.height as usize);
+                    report.record(&meta);
+                    report
+                        .edge_pixels
+                        .push(edges.iter().filter(|&&edge| edge >= threshold).count() as u64);
+
+                    let meta = ImageMeta {
+                        encoding: "mono8".to_owned(),
+                        ..meta
+                    };
+                    node.send_output(
+                        output.clone(),
+                        meta.to_parameters(),
+                        UInt8Array::from(edges),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "processed {} images, edge pixels of the last: {:?}",
+        report.images,
+        report.edge_pixels.last()
+    );
+    write_json(&report_file, &report)
+}
+
+/// The luma of every pixel, with the weights of ITU-R BT.601.
+fn grayscale(pixels: &[u8], encoding: &str) -> eyre::Result<Vec<u8>> {
+    let luma = |r: u8, g: u8, b: u8| {
+        ((299 * r as u32 + 587 * g as u32 + 114 * b as u32 + 500) / 1000) as u8
+    };
+    let gray = match encoding {
+        "mono8" => pixels.to_vec(),
+        "rgb8" => pixels
+            .chunks_exact(3)
+            .map(|p| luma(p[0], p[1], p[2]))
+            .collect(),
+        "bgr8" => pixels
+            .chunks_exact(3)
+            .map(|p| luma(p[2], p[1], p[0]))
+            .collect(),
+        "rgba8" => pixels
+            .chunks_exact(4)
+            .map(|p| luma(p[0], p[1], p[2]))
+            .collect(),
+        "bgra8" => pixels
+            .chunks_exact(4)
+            .map(|p| luma(p[2], p[1], p[0]))
+            .collect(),
+        other => bail!("unsupported encoding `{other}`"),
+    };
+    Ok(gray)
+}
+
+/// The magnitude of the Sobel gradient, saturated at 255. The border, where the 3x3 kernel
+/// doesn't fit, is 0.
+fn sobel(gray: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut edges = vec![0; gray.len()];
+    let at = |x: usize, y: usize| gray[y * width + x] as i32;
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let gx = at(x + 1, y - 1) + 2 * at(x + 1, y) + at(x + 1, y + 1)
+                - at(x - 1, y - 1)
+                - 2 * at(x - 1, y)
+                - at(x - 1, y + 1);
+            let gy = at(x - 1, y + 1) + 2 * at(x, y + 1) + at(x + 1, y + 1)
+                - at(x - 1, y - 1)
+                - 2 * at(x, y - 1)
+                - at(x + 1, y - 1);
+            edges[y * width + x] = (gx as f64).hypot(gy as f64).min(255.0) as u8;
+        }
+    }
+    edges
+}
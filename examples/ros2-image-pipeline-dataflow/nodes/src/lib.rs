@@ -0,0 +1,169 @@
+use dora_node_api::{
+    ArrowData, MetadataParameters, Parameter,
+    arrow::{array::AsArray, datatypes::UInt8Type},
+};
+use dora_ros2_bridge::{
+    ros2_client::{self, NodeOptions},
+    rustdds::{self, policy},
+};
+use eyre::{Context, OptionExt, bail, eyre};
+use futures::task::SpawnExt;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// What a `sensor_msgs/Image` carries besides its pixels. It's sent in the metadata
+/// parameters of the image, whose data is a `UInt8Array` of tightly packed rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageMeta {
+    pub width: u32,
+    pub height: u32,
+    /// A `sensor_msgs/image_encodings` value, see [`channels`].
+    pub encoding: String,
+    pub frame_id: String,
+    pub stamp_sec: i32,
+    pub stamp_nanosec: u32,
+}
+
+impl ImageMeta {
+    pub fn to_parameters(&self) -> MetadataParameters {
+        let mut parameters = MetadataParameters::default();
+        parameters.insert("width".into(), Parameter::Integer(self.width.into()));
+        parameters.insert("height".into(), Parameter::Integer(self.height.into()));
+        parameters.insert("encoding".into(), Parameter::String(self.encoding.clone()));
+        parameters.insert("frame_id".into(), Parameter::String(self.frame_id.clone()));
+        parameters.insert(
+            "stamp_sec".into(),
+            Parameter::Integer(self.stamp_sec.into()),
+        );
+        parameters.insert(
+            "stamp_nanosec".into(),
+            Parameter::Integer(self.stamp_nanosec.into()),
+        );
+        parameters
+    }
+
+    pub fn from_parameters(parameters: &MetadataParameters) -> eyre::Result<Self> {
+        let integer = |name: &str| match parameters.get(name) {
+            Some(Parameter::Integer(value)) => Ok(*value),
+            other => Err(eyre!("expected integer `{name}` parameter, got {other:?}")),
+        };
+        let string = |name: &str| match parameters.get(name) {
+            Some(Parameter::String(value)) => Ok(value.clone()),
+            other => Err(eyre!("expected string `{name}` parameter, got {other:?}")),
+        };
+        Ok(Self {
+            width: integer("width")?.try_into()?,
+            height: integer("height")?.try_into()?,
+            encoding: string("encoding")?,
+            frame_id: string("frame_id")?,
+            stamp_sec: integer("stamp_sec")?.try_into()?,
+            stamp_nanosec: integer("stamp_nanosec")?.try_into()?,
+        })
+    }
+
+    /// The size of the tightly packed pixels.
+    pub fn packed_size(&self) -> eyre::Result<usize> {
+        let channels = channels(&self.encoding)
+            .ok_or_else(|| eyre!("unsupported encoding `{}`", self.encoding))?;
+        Ok(self.width as usize * self.height as usize * channels)
+    }
+}
+
+/// The 8-bit channels per pixel of the supported encodings.
+pub fn channels(encoding: &str) -> Option<usize> {
+    match encoding {
+        "mono8" => Some(1),
+        "rgb8" | "bgr8" => Some(3),
+        "rgba8" | "bgra8" => Some(4),
+        _ => None,
+    }
+}
+
+/// The pixels of an image input, checked against its metadata.
+pub fn pixels<'a>(data: &'a ArrowData, meta: &ImageMeta) -> eyre::Result<&'a [u8]> {
+    let pixels = data
+        .as_primitive_opt::<UInt8Type>()
+        .ok_or_eyre("expected a UInt8 array")?
+        .values();
+    if pixels.len() != meta.packed_size()? {
+        bail!(
+            "expected {} bytes for a {}x{} {} image, got {}",
+            meta.packed_size()?,
+            meta.width,
+            meta.height,
+            meta.encoding,
+            pixels.len()
+        );
+    }
+    Ok(pixels)
+}
+
+/// Splits a ROS2 topic like `/camera/image_raw` into its namespace and name.
+pub fn topic_name(topic: &str) -> eyre::Result<ros2_client::Name> {
+    let (namespace, name) = topic
+        .rsplit_once('/')
+        .ok_or_else(|| eyre!("topic `{topic}` must be absolute"))?;
+    let namespace = if namespace.is_empty() { "/" } else { namespace };
+    ros2_client::Name::new(namespace, name).map_err(|e| eyre!("invalid topic `{topic}`: {e}"))
+}
+
+/// Creates a ROS2 node in the `/dora_image` namespace, and spawns its spinner on `pool`,
+/// like in the turtlesim swarm example.
+pub fn init_ros_node(
+    name: &str,
+    pool: &futures::executor::ThreadPool,
+) -> eyre::Result<ros2_client::Node> {
+    let ros_context = ros2_client::Context::new().unwrap();
+    let mut ros_node = ros_context
+        .new_node(
+            ros2_client::NodeName::new("/dora_image", name)
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre!("failed to create ros2 node: {e:?}"))?;
+
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+    Ok(ros_node)
+}
+
+/// Reliable, keeping only the last few images, since they are large.
+pub fn image_qos() -> rustdds::QosPolicies {
+    rustdds::QosPolicyBuilder::new()
+        .durability(policy::Durability::Volatile)
+        .reliability(policy::Reliability::Reliable {
+            max_blocking_time: rustdds::Duration::from_millis(100),
+        })
+        .history(policy::History::KeepLast { depth: 2 })
+        .build()
+}
+
+/// Written by a node when it stops.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageReport {
+    pub images: u64,
+    /// Of the last image.
+    pub width: u32,
+    pub height: u32,
+    pub encoding: String,
+    /// Edge pixels of every image, only for the edge detector.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edge_pixels: Vec<u64>,
+}
+
+impl ImageReport {
+    pub fn record(&mut self, meta: &ImageMeta) {
+        self.images += 1;
+        self.width = meta.width;
+        self.height = meta.height;
+        self.encoding = meta.encoding.clone();
+    }
+}
@@ -0,0 +1,78 @@
+use dora_node_api::{self, DoraNode, Event};
+use dora_ros2_bridge::{
+    messages::{builtin_interfaces::msg::Time, sensor_msgs::msg::Image, std_msgs::msg::Header},
+    ros2_client,
+};
+use eyre::{Context, eyre};
+use ros2_image_pipeline_dataflow_nodes::{
+    ImageMeta, ImageReport, channels, env_or, image_qos, init_ros_node, pixels, topic_name,
+    write_json,
+};
+use std::path::PathBuf;
+
+/// Publishes every `image` as a `sensor_msgs/Image` on `TOPIC`, with the header of the image
+/// that it was computed from, so that ROS2 tools can match them.
+///
+/// Writes an [`ImageReport`] to `REPORT_FILE` when it stops.
+fn main() -> eyre::Result<()> {
+    let topic: String = env_or("TOPIC", "/dora/image_edges".to_owned())?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/sink.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+    let pool = futures::executor::ThreadPool::new()?;
+    let mut ros_node = init_ros_node("image_sink", &pool)?;
+
+    let ros_topic = ros_node
+        .create_topic(
+            &topic_name(&topic)?,
+            ros2_client::MessageTypeName::new("sensor_msgs", "Image"),
+            &image_qos(),
+        )
+        .context("failed to create topic")?;
+    let publisher = ros_node
+        .create_publisher::<Image>(&ros_topic, None)
+        .context("failed to create publisher")?;
+
+    let mut report = ImageReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let meta = ImageMeta::from_parameters(&metadata.parameters)?;
+                    let image = to_ros(&meta, pixels(&data, &meta)?.to_vec());
+                    publisher
+                        .publish(image)
+                        .map_err(|e| eyre!("failed to publish image: {e:?}"))?;
+                    report.record(&meta);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("published {} images on `{topic}`", report.images);
+    write_json(&report_file, &report)
+}
+
+fn to_ros(meta: &ImageMeta, pixels: Vec<u8>) -> Image {
+    // `pixels` checked the encoding
+    let channels = channels(&meta.encoding).unwrap_or(1);
+    Image {
+        header: Header {
+            stamp: Time {
+                sec: meta.stamp_sec,
+                nanosec: meta.stamp_nanosec,
+            },
+            frame_id: meta.frame_id.clone(),
+        },
+        height: meta.height,
+        width: meta.width,
+        encoding: meta.encoding.clone(),
+        is_bigendian: 0,
+        step: meta.width * channels as u32,
+        data: pixels,
+    }
+}
@@ -0,0 +1,115 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::UInt8Array,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use dora_ros2_bridge::{messages::sensor_msgs::msg::Image, ros2_client};
+use eyre::{Context, bail};
+use ros2_image_pipeline_dataflow_nodes::{
+    ImageMeta, ImageReport, channels, env_or, image_qos, init_ros_node, topic_name, write_json,
+};
+use std::path::PathBuf;
+
+/// Subscribes to the `sensor_msgs/Image` topic `TOPIC`, and sends every image on `image` as
+/// a `UInt8Array` of its pixels, with the rest of the message in the metadata, see
+/// [`ImageMeta`].
+///
+/// Stops after `COUNT` images, or never if it's 0, and writes an [`ImageReport`] to
+/// `REPORT_FILE` when it stops.
+fn main() -> eyre::Result<()> {
+    let topic: String = env_or("TOPIC", "/camera/image_raw".to_owned())?;
+    let count: u64 = env_or("COUNT", 0)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/source.json".to_owned())?.into();
+    let output = DataId::from("image".to_owned());
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let pool = futures::executor::ThreadPool::new()?;
+    let mut ros_node = init_ros_node("image_source", &pool)?;
+
+    let ros_topic = ros_node
+        .create_topic(
+            &topic_name(&topic)?,
+            ros2_client::MessageTypeName::new("sensor_msgs", "Image"),
+            &image_qos(),
+        )
+        .context("failed to create topic")?;
+    let subscription = ros_node
+        .create_subscription::<Image>(&ros_topic, None)
+        .context("failed to create subscription")?;
+    println!("subscribed to `{topic}`");
+
+    let merged = events.merge_external(Box::pin(subscription.async_stream()));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut report = ImageReport::default();
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(Ok((image, _))) => {
+                let (meta, pixels) = from_ros(image)?;
+                node.send_output(
+                    output.clone(),
+                    meta.to_parameters(),
+                    UInt8Array::from(pixels),
+                )?;
+                report.record(&meta);
+                if report.images == count {
+                    break;
+                }
+            }
+            MergedEvent::External(Err(err)) => eprintln!("failed to read image: {err:?}"),
+        }
+    }
+
+    println!(
+        "sent {} {}x{} {} images",
+        report.images, report.width, report.height, report.encoding
+    );
+    write_json(&report_file, &report)
+}
+
+/// Splits an image into its metadata and its pixels, without the padding at the end of its
+/// rows.
+fn from_ros(image: Image) -> eyre::Result<(ImageMeta, Vec<u8>)> {
+    let Some(channels) = channels(&image.encoding) else {
+        bail!("unsupported encoding `{}`", image.encoding);
+    };
+    let (height, step) = (image.height as usize, image.step as usize);
+    let row = image.width as usize * channels;
+    if step < row || image.data.len() < step * height {
+        bail!(
+            "a {}x{} {} image with a step of {step} should have {} bytes, got {}",
+            image.width,
+            image.height,
+            image.encoding,
+            step * height,
+            image.data.len()
+        );
+    }
+    let pixels = if step == row {
+        let mut data = image.data;
+        data.truncate(row * height);
+        data
+    } else {
+        image
+            .data
+            .chunks(step)
+            .take(height)
+            .flat_map(|chunk| &chunk[..row])
+            .copied()
+            .collect()
+    };
+    let meta = ImageMeta {
+        width: image.width,
+        height: image.height,
+        encoding: image.encoding,
+        frame_id: image.header.frame_id,
+        stamp_sec: image.header.stamp.sec,
+        stamp_nanosec: image.header.stamp.nanosec,
+    };
+    Ok((meta, pixels))
+}
@@ -0,0 +1,38 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use std::path::Path;
+
+/// Must match the `sink` configuration in `dataflow.yml`.
+const MAX_SEGMENTS: &str = "5";
+const MAX_SEGMENT_BYTES: &str = "32768";
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("rotating-file-sink-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // start from an empty output directory, the sink would otherwise append to old segments
+    let segments = Path::new("out/segments");
+    if segments.exists() {
+        std::fs::remove_dir_all(segments).context("failed to clean up old segments")?;
+    }
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let mut cmd = tokio::process::Command::new("nodes/target/release/verify-segments");
+    cmd.arg(segments)
+        .args(["--max-segments", MAX_SEGMENTS])
+        .args(["--max-bytes", MAX_SEGMENT_BYTES]);
+    if !cmd.status().await?.success() {
+        bail!("segment verification failed");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use eyre::Context;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+pub use example_node_utils::env_or;
+
+/// Name of the index file in the output directory, one JSON object per closed segment.
+pub const INDEX_FILE: &str = "index.jsonl";
+
+/// Why a segment was closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationReason {
+    /// The next record would have exceeded `MAX_SEGMENT_BYTES`.
+    Size,
+    /// The segment was open for longer than `MAX_SEGMENT_SECS`.
+    Age,
+    /// The sink stopped.
+    Stop,
+}
+
+/// Index entry of a closed segment.
+///
+/// Readers can find the segment that contains a record by its sequence number or timestamp
+/// without decompressing any of the segments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// File name of the segment, relative to the output directory.
+    pub file: String,
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub first_timestamp_us: u64,
+    pub last_timestamp_us: u64,
+    pub records: u64,
+    /// Uncompressed size of the segment.
+    pub bytes: u64,
+    pub compressed: bool,
+    pub reason: RotationReason,
+}
+
+pub fn segment_name(number: u64) -> String {
+    format!("segment-{number:06}.log")
+}
+
+/// Parses the number out of `segment-000042.log` or `segment-000042.log.gz`.
+pub fn segment_number(file: &str) -> Option<u64> {
+    file.strip_prefix("segment-")?
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+pub fn read_index(dir: &Path) -> eyre::Result<Vec<IndexEntry>> {
+    let path = dir.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    content
+        .lines()
+        .map(|line| serde_json::from_str(line).context("invalid index entry"))
+        .collect()
+}
+
+/// Replaces the index file, writing to a temporary file first so that readers never see a
+/// partially written index.
+pub fn write_index(dir: &Path, entries: &[IndexEntry]) -> eyre::Result<()> {
+    let tmp: PathBuf = dir.join(format!("{INDEX_FILE}.tmp"));
+    let mut file = std::fs::File::create(&tmp)?;
+    for entry in entries {
+        serde_json::to_writer(&mut file, entry)?;
+        file.write_all(b"\n")?;
+    }
+    file.sync_all()?;
+    std::fs::rename(&tmp, dir.join(INDEX_FILE)).context("failed to replace index file")
+}
+
+/// A record as written to a segment: `<seq>\t<timestamp_us>\t<input>\t<payload>\n`.
+pub fn format_record(seq: u64, timestamp_us: u64, input: &str, payload: &str) -> String {
+    format!("{seq}\t{timestamp_us}\t{input}\t{payload}\n")
+}
+
+/// Returns the sequence number and timestamp of a record line.
+pub fn parse_record(line: &str) -> Option<(u64, u64)> {
+    let mut fields = line.splitn(4, '\t');
+    let seq = fields.next()?.parse().ok()?;
+    let timestamp = fields.next()?.parse().ok()?;
+    Some((seq, timestamp))
+}
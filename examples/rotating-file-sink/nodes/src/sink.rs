@@ -0,0 +1,244 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::{Context, eyre};
+use flate2::{Compression, write::GzEncoder};
+use rotating_file_sink_nodes::{
+    IndexEntry, RotationReason, env_or, format_record, read_index, segment_name, segment_number,
+    write_index,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Input id of the timer that triggers age-based rotation while no data arrives.
+const CHECK_INPUT: &str = "check";
+
+struct Config {
+    dir: PathBuf,
+    max_segment_bytes: u64,
+    max_segment_age: Duration,
+    max_segments: usize,
+    compress: bool,
+}
+
+impl Config {
+    fn from_env() -> eyre::Result<Self> {
+        Ok(Self {
+            dir: env_or("OUTPUT_DIR", "out/segments".to_owned())?.into(),
+            max_segment_bytes: env_or("MAX_SEGMENT_BYTES", 1024 * 1024)?,
+            max_segment_age: Duration::from_secs_f64(env_or("MAX_SEGMENT_SECS", 60.0)?),
+            max_segments: env_or("MAX_SEGMENTS", 10)?,
+            compress: env_or("COMPRESS", true)?,
+        })
+    }
+}
+
+/// The segment that records are currently appended to.
+struct ActiveSegment {
+    number: u64,
+    path: PathBuf,
+    writer: BufWriter<File>,
+    opened: Instant,
+    first_seq: u64,
+    last_seq: u64,
+    first_timestamp_us: u64,
+    last_timestamp_us: u64,
+    records: u64,
+    bytes: u64,
+}
+
+struct RotatingWriter {
+    config: Config,
+    index: Vec<IndexEntry>,
+    active: Option<ActiveSegment>,
+    next_number: u64,
+    next_seq: u64,
+}
+
+impl RotatingWriter {
+    /// Continues after the last segment in the index, so that restarting the sink with the
+    /// same output directory appends new segments instead of overwriting old ones.
+    fn open(config: Config) -> eyre::Result<Self> {
+        std::fs::create_dir_all(&config.dir).context("failed to create output directory")?;
+        let index = read_index(&config.dir)?;
+        let next_number = index
+            .iter()
+            .filter_map(|entry| segment_number(&entry.file))
+            .max()
+            .map_or(0, |n| n + 1);
+        let next_seq = index.last().map_or(0, |entry| entry.last_seq + 1);
+        Ok(Self {
+            config,
+            index,
+            active: None,
+            next_number,
+            next_seq,
+        })
+    }
+
+    fn write(&mut self, input: &str, payload: &str) -> eyre::Result<()> {
+        let timestamp_us = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_micros() as u64;
+        let record = format_record(self.next_seq, timestamp_us, input, payload);
+
+        if let Some(active) = &self.active {
+            // a record that is larger than the limit on its own still gets a segment
+            if active.records > 0
+                && active.bytes + record.len() as u64 > self.config.max_segment_bytes
+            {
+                self.rotate(RotationReason::Size)?;
+            }
+        }
+        let active = match self.active.take() {
+            Some(active) => active,
+            None => self.open_segment(timestamp_us)?,
+        };
+        let active = self.active.insert(active);
+
+        active.writer.write_all(record.as_bytes())?;
+        active.last_seq = self.next_seq;
+        active.last_timestamp_us = timestamp_us;
+        active.records += 1;
+        active.bytes += record.len() as u64;
+        self.next_seq += 1;
+        Ok(())
+    }
+
+    fn check_age(&mut self) -> eyre::Result<()> {
+        if let Some(active) = &self.active
+            && active.opened.elapsed() >= self.config.max_segment_age
+        {
+            self.rotate(RotationReason::Age)?;
+        }
+        Ok(())
+    }
+
+    fn open_segment(&mut self, timestamp_us: u64) -> eyre::Result<ActiveSegment> {
+        let number = self.next_number;
+        self.next_number += 1;
+        let path = self.config.dir.join(segment_name(number));
+        let file =
+            File::create(&path).with_context(|| format!("failed to create {}", path.display()))?;
+        Ok(ActiveSegment {
+            number,
+            path,
+            writer: BufWriter::new(file),
+            opened: Instant::now(),
+            first_seq: self.next_seq,
+            last_seq: self.next_seq,
+            first_timestamp_us: timestamp_us,
+            last_timestamp_us: timestamp_us,
+            records: 0,
+            bytes: 0,
+        })
+    }
+
+    /// Closes the active segment, compresses it, adds it to the index, and deletes the
+    /// oldest segments beyond the retention limit.
+    fn rotate(&mut self, reason: RotationReason) -> eyre::Result<()> {
+        let Some(active) = self.active.take() else {
+            return Ok(());
+        };
+        let file = active
+            .writer
+            .into_inner()
+            .map_err(|err| eyre!("failed to flush segment: {}", err.error()))?;
+        file.sync_all()?;
+        drop(file);
+
+        let file_name = if self.config.compress {
+            compress(&active.path)?;
+            format!("{}.gz", segment_name(active.number))
+        } else {
+            segment_name(active.number)
+        };
+        println!(
+            "closed {file_name} ({reason:?}): records {}..={}, {} bytes",
+            active.first_seq, active.last_seq, active.bytes
+        );
+        self.index.push(IndexEntry {
+            file: file_name,
+            first_seq: active.first_seq,
+            last_seq: active.last_seq,
+            first_timestamp_us: active.first_timestamp_us,
+            last_timestamp_us: active.last_timestamp_us,
+            records: active.records,
+            bytes: active.bytes,
+            compressed: self.config.compress,
+            reason,
+        });
+
+        while self.index.len() > self.config.max_segments {
+            let expired = self.index.remove(0);
+            let path = self.config.dir.join(&expired.file);
+            std::fs::remove_file(&path)
+                .with_context(|| format!("failed to delete {}", path.display()))?;
+            println!("deleted {} (retention limit)", expired.file);
+        }
+
+        write_index(&self.config.dir, &self.index)
+    }
+}
+
+/// Writes `<path>.gz` and removes the uncompressed file.
+fn compress(path: &Path) -> eyre::Result<()> {
+    let mut compressed_path = path.as_os_str().to_owned();
+    compressed_path.push(".gz");
+
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(compressed_path)?, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+fn main() -> eyre::Result<()> {
+    let mut writer = RotatingWriter::open(Config::from_env()?)?;
+
+    let (node, mut events) = DoraNode::init_from_env()?;
+    // the check timer never closes, so stop once all data inputs are closed
+    let mut open_inputs = node
+        .node_config()
+        .inputs
+        .keys()
+        .filter(|id| id.as_str() != CHECK_INPUT)
+        .count();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => {
+                if id.as_str() == CHECK_INPUT {
+                    writer.check_age()?;
+                    continue;
+                }
+                let payload = match <&str>::try_from(&data) {
+                    Ok(text) => text.replace('\n', " "),
+                    Err(_) => format!("{:?}", data.0).replace('\n', " "),
+                };
+                writer.write(id.as_str(), &payload)?;
+            }
+            Event::InputClosed { id } => {
+                if id.as_str() != CHECK_INPUT {
+                    open_inputs -= 1;
+                    if open_inputs == 0 {
+                        break;
+                    }
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    writer.rotate(RotationReason::Stop)?;
+    println!("wrote {} records", writer.next_seq);
+    Ok(())
+}
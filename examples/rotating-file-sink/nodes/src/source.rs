@@ -0,0 +1,49 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use rotating_file_sink_nodes::env_or;
+
+fn main() -> eyre::Result<()> {
+    let ticks: u64 = env_or("TICKS", 2400)?;
+    // alternates between a busy phase that sends on every tick and a quiet phase that only
+    // sends every `QUIET_EVERY` ticks, so that the sink rotates both by size and by age
+    let phase_ticks: u64 = env_or("PHASE_TICKS", 400)?;
+    let quiet_every: u64 = env_or("QUIET_EVERY", 50)?;
+    let output = DataId::from("log".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut tick = 0;
+    let mut sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    let busy = (tick / phase_ticks) % 2 == 0;
+                    if busy || tick % quiet_every == 0 {
+                        let line = format!(
+                            "level=info component=planner tick={tick} busy={busy} \
+                             msg=\"replanned path\" waypoints={} cost={:.3}",
+                            tick % 17 + 3,
+                            (tick as f64 * 0.37).sin().abs() * 100.0
+                        );
+                        node.send_output(output.clone(), Default::default(), line.into_arrow())?;
+                        sent += 1;
+                    }
+                    tick += 1;
+                    if tick >= ticks {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {sent} log lines");
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! Checks the output directory of `rotating-file-sink` against its index.
+//!
+//! Usage: `verify-segments <dir> --max-segments <n> --max-bytes <n>`
+
+use eyre::{Context, bail, eyre};
+use flate2::read::GzDecoder;
+use rotating_file_sink_nodes::{INDEX_FILE, RotationReason, parse_record, read_index};
+use std::{io::Read, path::PathBuf};
+
+fn main() -> eyre::Result<()> {
+    let mut dir = None;
+    let mut max_segments = None;
+    let mut max_bytes = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-segments" => max_segments = args.next().map(|v| v.parse()).transpose()?,
+            "--max-bytes" => max_bytes = args.next().map(|v| v.parse::<u64>()).transpose()?,
+            other if dir.is_none() => dir = Some(PathBuf::from(other)),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    let dir = dir.ok_or_else(|| {
+        eyre!("usage: verify-segments <dir> [--max-segments <n>] [--max-bytes <n>]")
+    })?;
+
+    let index = read_index(&dir)?;
+    if index.is_empty() {
+        bail!("index in {} is empty", dir.display());
+    }
+
+    // retention: no more segments than allowed, and no files that are not in the index
+    if let Some(max_segments) = max_segments
+        && index.len() > max_segments
+    {
+        bail!(
+            "{} segments exceed the retention limit of {max_segments}",
+            index.len()
+        );
+    }
+    for entry in std::fs::read_dir(&dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name != INDEX_FILE && !index.iter().any(|e| e.file == name) {
+            bail!("`{name}` is not referenced by the index (retention cleanup failed?)");
+        }
+    }
+
+    let mut previous_last_seq = None;
+    for entry in &index {
+        let path = dir.join(&entry.file);
+        let mut content = String::new();
+        let mut file = std::fs::File::open(&path)
+            .with_context(|| format!("segment {} is missing", entry.file))?;
+        if entry.compressed {
+            GzDecoder::new(file).read_to_string(&mut content)?;
+        } else {
+            file.read_to_string(&mut content)?;
+        }
+
+        // rotation boundaries: the index matches the content, and the size limit holds
+        if content.len() as u64 != entry.bytes {
+            bail!(
+                "{}: index says {} bytes, found {}",
+                entry.file,
+                entry.bytes,
+                content.len()
+            );
+        }
+        if let Some(max_bytes) = max_bytes
+            && entry.bytes > max_bytes
+            && entry.records > 1
+        {
+            bail!(
+                "{}: {} bytes exceed the limit of {max_bytes}",
+                entry.file,
+                entry.bytes
+            );
+        }
+        let records = content
+            .lines()
+            .map(|line| parse_record(line).ok_or_else(|| eyre!("{}: invalid record", entry.file)))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        let (Some(first), Some(last)) = (records.first(), records.last()) else {
+            bail!("{}: segment is empty", entry.file);
+        };
+        if records.len() as u64 != entry.records
+            || first.0 != entry.first_seq
+            || last.0 != entry.last_seq
+            || first.1 != entry.first_timestamp_us
+            || last.1 != entry.last_timestamp_us
+        {
+            bail!(
+                "{}: content does not match the index entry {entry:?}",
+                entry.file
+            );
+        }
+        if records.windows(2).any(|pair| pair[1].0 != pair[0].0 + 1) {
+            bail!("{}: records are not consecutive", entry.file);
+        }
+        // no record is lost or duplicated at a rotation boundary
+        if let Some(previous) = previous_last_seq
+            && entry.first_seq != previous + 1
+        {
+            bail!(
+                "{}: starts at record {}, expected {}",
+                entry.file,
+                entry.first_seq,
+                previous + 1
+            );
+        }
+        previous_last_seq = Some(entry.last_seq);
+
+        println!(
+            "{}: records {}..={} ({} bytes, rotated by {:?})",
+            entry.file, entry.first_seq, entry.last_seq, entry.bytes, entry.reason
+        );
+    }
+
+    for reason in [RotationReason::Size, RotationReason::Age] {
+        if !index.iter().any(|entry| entry.reason == reason) {
+            bail!("no segment was rotated by {reason:?}");
+        }
+    }
+    if max_segments.is_some() && index[0].first_seq == 0 {
+        bail!("the oldest segment is still present, retention cleanup did not run");
+    }
+
+    println!("verified {} segments in {}", index.len(), dir.display());
+    Ok(())
+}
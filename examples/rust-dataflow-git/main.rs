@@ -1,5 +1,6 @@
 use dora_tracing::set_up_tracing;
-use eyre::{Context, bail};
+use example_runner_utils::Dora;
+use eyre::Context;
 use std::path::Path;
 
 #[tokio::main]
@@ -11,49 +12,15 @@ async fn main() -> eyre::Result<()> {
         .wrap_err("failed to set working dir")?;
 
     let args: Vec<String> = std::env::args().collect();
+    let dora = Dora::from_env()?;
     let dataflow = if args.len() > 1 {
         Path::new(&args[1])
     } else {
         Path::new("dataflow.yml")
     };
-    build_dataflow(dataflow).await?;
+    dora.build_dataflow(dataflow).await?;
 
-    run_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
 
     Ok(())
 }
-
-async fn build_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("build").arg(dataflow);
-    if !cmd.status().await?.success() {
-        bail!("failed to build dataflow");
-    };
-    Ok(())
-}
-
-async fn run_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--")
-        .arg("daemon")
-        .arg("--run-dataflow")
-        .arg(dataflow);
-    if !cmd.status().await?.success() {
-        bail!("failed to run dataflow");
-    };
-    Ok(())
-}
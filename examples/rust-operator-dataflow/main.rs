@@ -0,0 +1,70 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Must match the `env` of the source in `dataflow.yml`.
+const COUNT: u64 = 100;
+/// Must match `WINDOW` in `operator/src/lib.rs`.
+const WINDOW: usize = 5;
+
+/// Subset of `SinkReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkReport {
+    averages: Vec<f64>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("rust-operator-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let content =
+        std::fs::read_to_string("out/sink.json").context("out/sink.json was not written")?;
+    let sink: SinkReport = serde_json::from_str(&content)?;
+
+    // the same sawtooth as `value` in `nodes/src/lib.rs`
+    let values: Vec<f64> = (0..COUNT).map(|seq| (seq % 10) as f64).collect();
+    let expected: Vec<f64> = (0..values.len())
+        .map(|i| {
+            let window = &values[(i + 1).saturating_sub(WINDOW)..=i];
+            window.iter().sum::<f64>() / window.len() as f64
+        })
+        .collect();
+
+    if sink.averages.len() != expected.len() {
+        bail!(
+            "expected {} averages, the sink received {}",
+            expected.len(),
+            sink.averages.len()
+        );
+    }
+    // a wrong average means that the operator lost its window between two events
+    if let Some((i, (actual, expected))) = sink
+        .averages
+        .iter()
+        .zip(&expected)
+        .enumerate()
+        .find(|(_, (actual, expected))| (*actual - *expected).abs() > 1e-9)
+    {
+        bail!("average {i} should be {expected}, the operator sent {actual}");
+    }
+    println!("the operator sent the moving averages over {WINDOW} values of all {COUNT} values");
+
+    println!("Everything Done");
+    Ok(())
+}
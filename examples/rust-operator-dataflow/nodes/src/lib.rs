@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// The `seq`th value of the source, a sawtooth from 0 to 9.
+pub fn value(seq: u64) -> f64 {
+    (seq % 10) as f64
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SinkReport {
+    /// The averages of the operator, in the order they arrived.
+    pub averages: Vec<f64>,
+}
@@ -0,0 +1,36 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::Context;
+use rust_operator_dataflow_nodes::{SinkReport, env_or, write_json};
+use std::path::PathBuf;
+
+/// Collects the `average`s of the operator and writes them to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", PathBuf::from("out/sink.json"))?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = SinkReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "average" => {
+                    let average = f64::try_from(&data).context("unexpected data type")?;
+                    println!("moving average: {average:.2}");
+                    report.averages.push(average);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "average" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("received {} averages", report.averages.len());
+    write_json(&report_file, &report)
+}
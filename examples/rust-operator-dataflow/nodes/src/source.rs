@@ -0,0 +1,30 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use rust_operator_dataflow_nodes::{env_or, value};
+
+/// Sends `COUNT` values on `value`, one per tick, and exits, which closes `value`.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 100)?;
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let output = DataId::from("value".to_owned());
+
+    let mut seq = 0;
+    while seq < count {
+        let Some(event) = events.recv() else {
+            break;
+        };
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    node.send_output(output.clone(), Default::default(), value(seq).into_arrow())?;
+                    seq += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("sent {seq} values");
+    Ok(())
+}
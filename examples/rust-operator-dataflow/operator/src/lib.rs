@@ -0,0 +1,65 @@
+use dora_operator_api::{
+    DoraOperator, DoraOutputSender, DoraStatus, Event, IntoArrow, register_operator,
+};
+use std::collections::VecDeque;
+
+/// Number of values that the moving average spans. Must match `WINDOW` in `main.rs`.
+const WINDOW: usize = 5;
+
+register_operator!(MovingAverage);
+
+/// Sends the average of the last `WINDOW` values on `average`, for every value.
+///
+/// The runtime creates the operator once, with `Default`, and calls `on_event` for every event
+/// of its inputs, so the fields keep their state from one event to the next.
+#[derive(Debug, Default)]
+struct MovingAverage {
+    window: VecDeque<f64>,
+    /// Sum of the values in `window`, to not sum up the window for every value.
+    sum: f64,
+    /// Number of values received so far.
+    received: u64,
+}
+
+impl MovingAverage {
+    fn push(&mut self, value: f64) -> f64 {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > WINDOW {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        self.received += 1;
+        self.sum / self.window.len() as f64
+    }
+}
+
+impl DoraOperator for MovingAverage {
+    fn on_event(
+        &mut self,
+        event: &Event,
+        output_sender: &mut DoraOutputSender,
+    ) -> Result<DoraStatus, String> {
+        match event {
+            Event::Input { id, data } => match *id {
+                "value" => {
+                    let value =
+                        f64::try_from(data).map_err(|err| format!("expected f64 value: {err}"))?;
+                    let average = self.push(value);
+                    output_sender.send("average".into(), average.into_arrow())?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if *id == "value" {
+                    println!("averaged {} values", self.received);
+                    // the runtime node exits once all of its operators stopped
+                    return Ok(DoraStatus::Stop);
+                }
+            }
+            Event::Stop => return Ok(DoraStatus::Stop),
+            other => eprintln!("Received unexpected event: {other:?}"),
+        }
+        Ok(DoraStatus::Continue)
+    }
+}
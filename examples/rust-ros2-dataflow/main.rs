@@ -1,24 +1,78 @@
 use dora_tracing::set_up_tracing;
-use eyre::{Context, bail};
-use std::path::Path;
+use example_runner_utils::{Dora, RosDistro, read_json};
+use eyre::{Context, bail, eyre};
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path, time::Duration};
 use tokio::process::Child;
 
+/// The ROS2 node of `node/src/bin/parameter-node.rs` that serves the parameters.
+const PARAMETER_NODE: &str = "/ros2_demo/rate_publisher";
+
+/// Subset of the report entries in `node/src/bin/rate-monitor.rs`.
+#[derive(Debug, Deserialize)]
+struct RateSegment {
+    period_ms: i64,
+    intervals: u64,
+    mean_interval_ms: Option<f64>,
+}
+
+/// Subset of the report of `node/src/bin/qos-publisher.rs`.
+#[derive(Debug, Deserialize)]
+struct QosPublisherReport {
+    qos: String,
+    sent: u64,
+}
+
+/// Subset of the report of `node/src/bin/qos-subscriber.rs`.
+#[derive(Debug, Deserialize)]
+struct QosSubscriberReport {
+    qos: String,
+    received: u64,
+    first_seq: Option<u64>,
+    last_seq: Option<u64>,
+    deadline_misses: u64,
+}
+
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     set_up_tracing("rust-ros2-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
 
-    install_ros_pkg().await?;
+    let ros = RosDistro::detect()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // pass `--skip-install` where the missing ROS 2 packages can't be installed with apt,
+    // e.g. in CI without sudo
+    let skip_install = args.iter().any(|arg| arg == "--skip-install");
+    let demo = args.iter().find(|arg| !arg.starts_with("--"));
+    match demo.map(String::as_str) {
+        None => {
+            ros.ensure_packages(
+                &["turtlesim", "examples_rclcpp_minimal_service"],
+                skip_install,
+            )
+            .await?
+        }
+        Some("parameters" | "qos") => {}
+        Some(other) => bail!("unknown demo `{other}`, expected `parameters`, `qos`, or nothing"),
+    }
 
     let root = Path::new(env!("CARGO_MANIFEST_DIR"));
     std::env::set_current_dir(root.join(file!()).parent().unwrap())
         .wrap_err("failed to set working dir")?;
 
+    // the dataflows source `ROS` in their build commands
+    let dora = Dora::from_env()?.env("ROS", ros.setup_script());
+    match demo.map(String::as_str) {
+        Some("parameters") => return run_parameters_demo(&dora, &ros).await,
+        Some("qos") => return run_qos_demo(&dora).await,
+        _ => {}
+    }
+
     let dataflow = Path::new("dataflow.yml");
-    build_dataflow(dataflow).await?;
+    dora.build_dataflow(dataflow).await?;
 
-    let ros_node = run_ros_pkg().await?;
+    let ros_node = run_ros_pkg(&ros).await?;
 
-    run_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
 
     for mut node in ros_node {
         node.kill().await?;
@@ -29,77 +83,195 @@ async fn main() -> eyre::Result<()> {
     Ok(())
 }
 
-async fn run_ros_pkg() -> eyre::Result<Vec<Child>> {
-    let mut ros_node = vec![];
-    let ros_path = if let Ok(path) = std::env::var("ROS") {
-        path
-    } else {
-        String::from("/opt/ros/jazzy/setup.bash")
+/// Runs `dataflow_parameters.yml`, and changes the publishing rate of the parameter node
+/// through `ros2 param set` while it runs.
+async fn run_parameters_demo(dora: &Dora, ros: &RosDistro) -> eyre::Result<()> {
+    let dataflow = Path::new("dataflow_parameters.yml");
+    dora.build_dataflow(dataflow).await?;
+    let report_file = Path::new("out/rate.json");
+    if report_file.exists() {
+        std::fs::remove_file(report_file)?;
+    }
+
+    let mut run = dora.cli(["daemon", "--run-dataflow"]);
+    run.arg(dataflow).kill_on_drop(true);
+    let mut run = run.spawn().context("failed to run dataflow")?;
+
+    // the parameter services are up once the node answers
+    let mut attempts = 0;
+    let period = loop {
+        match ros2(ros, &format!("ros2 param get {PARAMETER_NODE} period_ms")).await {
+            Ok(output) => break output,
+            Err(_) if attempts < 60 => {
+                attempts += 1;
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            Err(err) => return Err(err.wrap_err("parameter node did not come up")),
+        }
     };
-    ros_node.push(
-        tokio::process::Command::new("bash")
-            .args([
-                "-c",
-                &format!("source {ros_path}; ros2 run turtlesim turtlesim_node"),
-            ])
-            .spawn()?,
-    );
-    ros_node.push(
-        tokio::process::Command::new("bash")
-            .args([
-                "-c",
-                &format!(
-                    "source {ros_path}; ros2 run examples_rclcpp_minimal_service service_main"
-                ),
-            ])
-            .spawn()?,
-    );
-    Ok(ros_node)
-}
+    println!("{}", period.trim());
+    if !period.contains("500") {
+        bail!("`period_ms` should start at 500");
+    }
+    let list = ros2(ros, &format!("ros2 param list {PARAMETER_NODE}")).await?;
+    if !["period_ms", "label"]
+        .iter()
+        .all(|name| list.contains(name))
+    {
+        bail!("`period_ms` and `label` should be listed, got:\n{list}");
+    }
 
-async fn install_ros_pkg() -> eyre::Result<()> {
-    let mut cmd = tokio::process::Command::new("bash");
-    cmd.args(["-c",
-        "sudo apt update && sudo apt install -y ros-jazzy-turtlesim ros-jazzy-examples-rclcpp-minimal-service
-",
-    ]);
-    if !cmd.status().await?.success() {
-        bail!("failed to install related package");
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let set = ros2(
+        ros,
+        &format!("ros2 param set {PARAMETER_NODE} period_ms 100"),
+    )
+    .await?;
+    println!("{}", set.trim());
+    if !set.contains("Set parameter successful") {
+        bail!("failed to set `period_ms` to 100");
+    }
+    // the node must reject periods outside of its range
+    let rejected = ros2(ros, &format!("ros2 param set {PARAMETER_NODE} period_ms 0")).await;
+    match &rejected {
+        Ok(output) if output.contains("Set parameter successful") => {
+            bail!("`period_ms` 0 should have been rejected")
+        }
+        Ok(output) => println!("{}", output.trim()),
+        Err(err) => println!("{err}"),
+    }
+    tokio::time::sleep(Duration::from_secs(3)).await;
+
+    // to let the rate monitor write its report
+    stop_dataflow(&mut run).await?;
+
+    let segments: Vec<RateSegment> = serde_json::from_str(
+        &std::fs::read_to_string(report_file).context("rate monitor did not write a report")?,
+    )?;
+    for period_ms in [500, 100] {
+        let segment = segments
+            .iter()
+            .find(|segment| segment.period_ms == period_ms && segment.intervals > 0)
+            .ok_or_else(|| eyre!("no messages were measured with a period of {period_ms} ms"))?;
+        let mean = segment.mean_interval_ms.unwrap_or_default();
+        println!(
+            "period {period_ms} ms: mean interval {mean:.1} ms over {} intervals",
+            segment.intervals
+        );
+        if (mean - period_ms as f64).abs() > period_ms as f64 * 0.3 {
+            bail!("the mean interval should be close to {period_ms} ms");
+        }
+    }
+    if segments.iter().any(|segment| segment.period_ms == 0) {
+        bail!("the rejected period was applied");
     }
+
+    println!("Everything Done");
     Ok(())
 }
 
-async fn build_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("build").arg(dataflow);
-    if !cmd.status().await?.success() {
-        bail!("failed to build dataflow");
+/// Runs `dataflow_qos.yml`, in which subscribers with different QoS receive the messages of
+/// the same publisher, and compares what each of them received.
+async fn run_qos_demo(dora: &Dora) -> eyre::Result<()> {
+    let dataflow = Path::new("dataflow_qos.yml");
+    dora.build_dataflow(dataflow).await?;
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let publisher_env = |key: &str| {
+        descriptor["nodes"][0]["env"][key]
+            .as_u64()
+            .ok_or_else(|| eyre!("the publisher has no `{key}`"))
     };
+    let (count, depth) = (publisher_env("COUNT")?, publisher_env("QOS_DEPTH")?);
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let mut run = dora.cli(["daemon", "--run-dataflow"]);
+    run.arg(dataflow).kill_on_drop(true);
+    let mut run = run.spawn().context("failed to run dataflow")?;
+    // until the late subscribers joined, see `START_DELAY_MS`
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    stop_dataflow(&mut run).await?;
+
+    let publisher: QosPublisherReport = read_json("out/qos-publisher.json")?;
+    println!("publisher ({}): sent {}", publisher.qos, publisher.sent);
+    if publisher.sent != count {
+        bail!("the publisher should have sent all {count} messages");
+    }
+    let mut reports = HashMap::new();
+    for name in [
+        "reliable",
+        "best_effort",
+        "deadline",
+        "late_transient_local",
+        "late_volatile",
+    ] {
+        let report: QosSubscriberReport = read_json(format!("out/qos-{name}.json"))?;
+        println!(
+            "{name:>20} ({}): received {}, lost {}, seq {:?}..={:?}, {} deadline misses",
+            report.qos,
+            report.received,
+            count.saturating_sub(report.received),
+            report.first_seq,
+            report.last_seq,
+            report.deadline_misses
+        );
+        reports.insert(name, report);
+    }
+
+    if reports["reliable"].received != count {
+        bail!("the reliable subscriber should have received every message");
+    }
+    // best effort may or may not lose messages, depending on the load of the machine
+    let late = &reports["late_transient_local"];
+    if late.received != depth || late.last_seq != Some(count - 1) {
+        bail!("the late transient-local subscriber should have received the last {depth} messages");
+    }
+    if reports["late_volatile"].received != 0 {
+        bail!("the late volatile subscriber should have received nothing");
+    }
+    if reports["deadline"].deadline_misses == 0 {
+        bail!("the pause of the publisher should have missed the deadline");
+    }
+
+    println!("Everything Done");
     Ok(())
 }
 
-async fn run_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--")
-        .arg("daemon")
-        .arg("--run-dataflow")
-        .arg(dataflow);
-    if !cmd.status().await?.success() {
+/// Stops the dataflow like ctrl-c, so that the nodes can write their reports.
+///
+/// `cargo run` replaces itself with `dora` on unix, so the child is the dora process.
+async fn stop_dataflow(run: &mut Child) -> eyre::Result<()> {
+    let pid = run.id().ok_or_else(|| eyre!("dataflow exited early"))?;
+    let mut stop = tokio::process::Command::new("kill");
+    stop.args(["-INT", &pid.to_string()]);
+    if !stop.status().await?.success() {
+        bail!("failed to stop dataflow");
+    }
+    if !run.wait().await?.success() {
         bail!("failed to run dataflow");
-    };
+    }
     Ok(())
 }
+
+/// Runs a `ros2` CLI command in a shell with the sourced ROS2 installation, and returns its
+/// stdout.
+async fn ros2(ros: &RosDistro, command: &str) -> eyre::Result<String> {
+    let output = ros.command(command).output().await?;
+    if !output.status.success() {
+        bail!(
+            "`{command}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+async fn run_ros_pkg(ros: &RosDistro) -> eyre::Result<Vec<Child>> {
+    Ok(vec![
+        ros.command("ros2 run turtlesim turtlesim_node").spawn()?,
+        ros.command("ros2 run examples_rclcpp_minimal_service service_main")
+            .spawn()?,
+    ])
+}
@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::StringArray,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use dora_ros2_bridge::ros2_client::{self, NodeOptions, ParameterValue};
+use eyre::{Context, eyre};
+use futures::{channel::mpsc::UnboundedSender, task::SpawnExt};
+
+/// The ROS2 node that serves the parameters, e.g. for `ros2 param get`.
+const NAMESPACE: &str = "/ros2_demo";
+const NAME: &str = "rate_publisher";
+/// Default of the `period_ms` parameter.
+const DEFAULT_PERIOD_MS: i64 = 500;
+/// Allowed values of the `period_ms` parameter. The node can't be faster than its `tick`.
+const PERIOD_RANGE_MS: std::ops::RangeInclusive<i64> = 10..=5000;
+
+/// Sends a counter on `counter`, once every `period_ms`.
+///
+/// `period_ms` and `label` are ROS2 parameters of the `/ros2_demo/rate_publisher` node, which
+/// can be listed, read, and changed while the dataflow runs, e.g. with
+/// `ros2 param set /ros2_demo/rate_publisher period_ms 100`. The parameter services of the node
+/// reject periods outside of 10 to 5000 ms. The `tick` input must be at least as fast as the
+/// shortest period.
+fn main() -> eyre::Result<()> {
+    let (parameter_tx, parameter_rx) = futures::channel::mpsc::unbounded();
+    let mut ros_node = init_ros_node(parameter_tx)?;
+
+    // spawn a background spinner task, which also serves the parameter services
+    let pool = futures::executor::ThreadPool::new()?;
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre::eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+
+    let output = DataId::from("counter".to_owned());
+    let (mut node, dora_events) = DoraNode::init_from_env()?;
+
+    // parameter updates arrive on the spinner task, and are applied in the event loop
+    let merged = dora_events.merge_external(Box::pin(parameter_rx));
+    let mut events = futures::executor::block_on_stream(merged);
+
+    let mut period_ms = DEFAULT_PERIOD_MS;
+    let mut label = "dora".to_owned();
+    let mut last_sent: Option<Instant> = None;
+    let mut seq = 0;
+    println!("publishing every {period_ms} ms");
+    loop {
+        let event = match events.next() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input {
+                    id,
+                    metadata: _,
+                    data: _,
+                } => match id.as_str() {
+                    "tick" => {
+                        let period = Duration::from_millis(period_ms as u64);
+                        if last_sent.is_some_and(|sent| sent.elapsed() < period) {
+                            continue;
+                        }
+                        let message = serde_json::json!({
+                            "seq": seq,
+                            "period_ms": period_ms,
+                            "label": label,
+                        });
+                        node.send_output(
+                            output.clone(),
+                            Default::default(),
+                            StringArray::from(vec![message.to_string()]),
+                        )?;
+                        last_sent = Some(Instant::now());
+                        seq += 1;
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::Stop(_) => {
+                    println!("Received stop");
+                    break;
+                }
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External((name, value)) => match (name.as_str(), value) {
+                ("period_ms", ParameterValue::Integer(value)) => {
+                    println!("`period_ms` set to {value}, publishing every {value} ms");
+                    period_ms = value;
+                }
+                ("label", ParameterValue::String(value)) => {
+                    println!("`label` set to {value:?}");
+                    label = value;
+                }
+                (name, value) => eprintln!("Ignoring parameter `{name}` set to {value:?}"),
+            },
+        }
+    }
+
+    println!("sent {seq} messages");
+    Ok(())
+}
+
+fn init_ros_node(
+    parameter_tx: UnboundedSender<(String, ParameterValue)>,
+) -> eyre::Result<ros2_client::Node> {
+    let ros_context = ros2_client::Context::new().unwrap();
+
+    let options = NodeOptions::new()
+        .enable_rosout(true)
+        .declare_parameter("period_ms", ParameterValue::Integer(DEFAULT_PERIOD_MS))
+        .declare_parameter("label", ParameterValue::String("dora".to_owned()))
+        // runs in the parameter services, a rejected value is not set
+        .parameter_validator(Box::new(|name, value| match (name, value) {
+            ("period_ms", ParameterValue::Integer(ms)) if PERIOD_RANGE_MS.contains(ms) => Ok(()),
+            ("period_ms", _) => Err(format!(
+                "`period_ms` must be an integer from {} to {}",
+                PERIOD_RANGE_MS.start(),
+                PERIOD_RANGE_MS.end()
+            )),
+            ("label", ParameterValue::String(_)) => Ok(()),
+            ("label", _) => Err("`label` must be a string".to_owned()),
+            (name, _) => Err(format!("unknown parameter `{name}`")),
+        }))
+        .parameter_set_action(Box::new(move |name, value| {
+            parameter_tx
+                .unbounded_send((name.to_owned(), value.clone()))
+                .map_err(|_| "the node is stopping".to_owned())
+        }));
+
+    ros_context
+        .new_node(
+            ros2_client::NodeName::new(NAMESPACE, NAME)
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            options,
+        )
+        .map_err(|e| eyre::eyre!("failed to create ros2 node: {e:?}"))
+}
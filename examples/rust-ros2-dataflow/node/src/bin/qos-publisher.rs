@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use dora_node_api::{self, DoraNode, Event};
+use dora_ros2_bridge::{
+    messages::example_interfaces::msg::String as RosString,
+    ros2_client::{self, NodeOptions},
+};
+use eyre::{Context, eyre};
+use futures::task::SpawnExt;
+use rust_ros2_dataflow_example_node::{QosConfig, env_or};
+
+/// Publishes `COUNT` messages on `/ros2_demo/qos_demo` with the QoS from the `QOS_*`
+/// variables, see [`QosConfig`]. Each message starts with its sequence number, padded to
+/// `PAYLOAD_BYTES`.
+///
+/// Waits `WARMUP_MS` for the subscribers to be discovered, and then sends `BURST` messages
+/// per `tick`. Halfway through, it pauses for `PAUSE_MS`, which misses the deadline of the
+/// subscribers that request one. It keeps its writer until the dataflow stops, so that late
+/// subscribers can still join.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 400)?;
+    let burst: u64 = env_or("BURST", 20)?;
+    let payload_bytes: usize = env_or("PAYLOAD_BYTES", 32768)?;
+    let warmup = Duration::from_millis(env_or("WARMUP_MS", 2000)?);
+    let pause = Duration::from_millis(env_or("PAUSE_MS", 500)?);
+    let report_file = env_or("REPORT_FILE", "out/qos-publisher.json".to_owned())?;
+    let qos = QosConfig::from_env()?;
+
+    let ros_context = ros2_client::Context::new().unwrap();
+    let mut ros_node = ros_context
+        .new_node(
+            ros2_client::NodeName::new("/ros2_demo", "qos_publisher")
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre::eyre!("failed to create ros2 node: {e:?}"))?;
+    let topic = ros_node
+        .create_topic(
+            &ros2_client::Name::new("/ros2_demo", "qos_demo")
+                .map_err(|e| eyre!("failed to create ROS2 name: {e}"))?,
+            ros2_client::MessageTypeName::new("example_interfaces", "String"),
+            &qos.policies(),
+        )
+        .context("failed to create topic")?;
+    let publisher = ros_node
+        .create_publisher::<RosString>(&topic, None)
+        .context("failed to create publisher")?;
+
+    let pool = futures::executor::ThreadPool::new()?;
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre::eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+    println!("publishing {count} messages with {qos}");
+
+    let started = Instant::now();
+    let padding = "x".repeat(payload_bytes);
+    let mut paused_until: Option<Instant> = None;
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    if seq == count || started.elapsed() < warmup {
+                        continue;
+                    }
+                    if seq >= count / 2 {
+                        let until = *paused_until.get_or_insert_with(|| Instant::now() + pause);
+                        if Instant::now() < until {
+                            continue;
+                        }
+                    }
+                    for _ in 0..burst.min(count - seq) {
+                        let data = format!("{seq:08} {padding}");
+                        if let Err(err) = publisher.publish(RosString { data }) {
+                            eprintln!("failed to publish message {seq}: {err:?}");
+                        }
+                        seq += 1;
+                    }
+                    if seq == count {
+                        println!("published all {count} messages");
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let report = serde_json::json!({ "qos": qos.to_string(), "sent": seq });
+    std::fs::create_dir_all("out")?;
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("failed to write {report_file}"))
+}
@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+use dora_node_api::{
+    self, DoraNode, Event,
+    merged::{MergeExternal, MergedEvent},
+};
+use dora_ros2_bridge::{
+    messages::example_interfaces::msg::String as RosString,
+    ros2_client::{self, NodeOptions},
+};
+use eyre::{Context, eyre};
+use futures::task::SpawnExt;
+use rust_ros2_dataflow_example_node::{QosConfig, env_or};
+
+/// Subscribes to `/ros2_demo/qos_demo` with the QoS from the `QOS_*` variables, see
+/// [`QosConfig`], and reports what it received to `REPORT_FILE` when the dataflow stops.
+///
+/// It joins the topic `START_DELAY_MS` after the dataflow started, to show what a late
+/// subscriber gets with each durability. With a deadline, it also counts the deadline
+/// periods that passed without a message between the first and the last one it received.
+fn main() -> eyre::Result<()> {
+    let name = env_or("NAME", "qos_subscriber".to_owned())?;
+    let start_delay = Duration::from_millis(env_or("START_DELAY_MS", 0)?);
+    let report_file = env_or("REPORT_FILE", format!("out/qos-{name}.json"))?;
+    let qos = QosConfig::from_env()?;
+
+    let ros_context = ros2_client::Context::new().unwrap();
+    let mut ros_node = ros_context
+        .new_node(
+            ros2_client::NodeName::new("/ros2_demo", &format!("qos_{name}"))
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre::eyre!("failed to create ros2 node: {e:?}"))?;
+
+    let pool = futures::executor::ThreadPool::new()?;
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre::eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+
+    let (_node, dora_events) = DoraNode::init_from_env()?;
+
+    // the dataflow only starts once all nodes are initialized, so join late after that
+    std::thread::sleep(start_delay);
+    let topic = ros_node
+        .create_topic(
+            &ros2_client::Name::new("/ros2_demo", "qos_demo")
+                .map_err(|e| eyre!("failed to create ROS2 name: {e}"))?,
+            ros2_client::MessageTypeName::new("example_interfaces", "String"),
+            &qos.policies(),
+        )
+        .context("failed to create topic")?;
+    let subscription = ros_node
+        .create_subscription::<RosString>(&topic, None)
+        .context("failed to create subscription")?;
+    println!("`{name}` subscribed with {qos}");
+
+    let merged = dora_events.merge_external(Box::pin(subscription.async_stream()));
+    let events = futures::executor::block_on_stream(merged);
+
+    let deadline = qos.deadline_ms.map(|ms| Duration::from_millis(ms as u64));
+    let mut received = 0u64;
+    let mut first_seq: Option<u64> = None;
+    let mut last_seq: Option<u64> = None;
+    let mut last_at: Option<Instant> = None;
+    let mut deadline_misses = 0u64;
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(Ok((message, _))) => {
+                let seq: u64 = message
+                    .data
+                    .split(' ')
+                    .next()
+                    .and_then(|seq| seq.parse().ok())
+                    .ok_or_else(|| eyre!("message without a sequence number"))?;
+                let now = Instant::now();
+                if let (Some(deadline), Some(last_at)) = (deadline, last_at) {
+                    let gap = now - last_at;
+                    deadline_misses += (gap.as_nanos() / deadline.as_nanos()) as u64;
+                }
+                first_seq.get_or_insert(seq);
+                last_seq = Some(seq);
+                last_at = Some(now);
+                received += 1;
+            }
+            MergedEvent::External(Err(err)) => eprintln!("failed to read message: {err:?}"),
+        }
+    }
+
+    println!("`{name}` received {received} messages, {deadline_misses} deadline misses");
+    let report = serde_json::json!({
+        "name": name,
+        "qos": qos.to_string(),
+        "received": received,
+        "first_seq": first_seq,
+        "last_seq": last_seq,
+        "deadline_misses": deadline_misses,
+    });
+    std::fs::create_dir_all("out")?;
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("failed to write {report_file}"))
+}
@@ -0,0 +1,68 @@
+use std::time::Instant;
+
+use dora_node_api::{self, DoraNode, Event, arrow::array::AsArray};
+use eyre::{Context, OptionExt};
+
+/// Measures the intervals between the `counter` messages of the parameter node, grouped by the
+/// `period_ms` that the node had when it sent them.
+///
+/// Writes one entry per period, in the order of the parameter changes, to `REPORT_FILE` when
+/// it stops.
+fn main() -> eyre::Result<()> {
+    let report_file = std::env::var("REPORT_FILE").unwrap_or_else(|_| "out/rate.json".to_owned());
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    // (period_ms, number of intervals, sum of the intervals in ms)
+    let mut segments: Vec<(i64, u64, f64)> = Vec::new();
+    let mut last_at: Option<Instant> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "counter" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    for row in rows.iter().flatten() {
+                        let message: serde_json::Value = serde_json::from_str(row)?;
+                        let period_ms = message["period_ms"]
+                            .as_i64()
+                            .ok_or_eyre("message without `period_ms`")?;
+                        let now = Instant::now();
+                        match (segments.last_mut(), last_at) {
+                            (Some(segment), Some(last_at)) if segment.0 == period_ms => {
+                                segment.1 += 1;
+                                segment.2 += (now - last_at).as_secs_f64() * 1000.0;
+                            }
+                            // the first interval after a change mixes both periods
+                            _ => segments.push((period_ms, 0, 0.0)),
+                        }
+                        last_at = Some(now);
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let report: Vec<_> = segments
+        .iter()
+        .map(|&(period_ms, intervals, sum)| {
+            let mean = (intervals > 0).then(|| sum / intervals as f64);
+            println!("period {period_ms} ms: {intervals} intervals, mean {mean:?} ms");
+            serde_json::json!({
+                "period_ms": period_ms,
+                "intervals": intervals,
+                "mean_interval_ms": mean,
+            })
+        })
+        .collect();
+    if let Some(parent) = std::path::Path::new(&report_file).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("failed to write {report_file}"))
+}
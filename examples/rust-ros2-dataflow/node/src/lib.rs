@@ -0,0 +1,95 @@
+use dora_ros2_bridge::{
+    ros2_client::ros2,
+    rustdds::{self, policy},
+};
+use eyre::{bail, eyre};
+
+pub use example_node_utils::env_or;
+
+/// The QoS of a ROS2 topic, read from the environment of the node, so that the dataflow can
+/// configure it per node:
+///
+/// - `QOS_RELIABILITY`: `reliable` (default) or `best_effort`
+/// - `QOS_DURABILITY`: `volatile` (default) or `transient_local`
+/// - `QOS_DEPTH`: depth of the `KeepLast` history, 10 by default
+/// - `QOS_DEADLINE_MS`: deadline period, none by default
+#[derive(Debug, Clone)]
+pub struct QosConfig {
+    pub reliable: bool,
+    pub transient_local: bool,
+    pub depth: i32,
+    pub deadline_ms: Option<i64>,
+}
+
+impl QosConfig {
+    pub fn from_env() -> eyre::Result<Self> {
+        let reliable = match env_or("QOS_RELIABILITY", "reliable".to_owned())?.as_str() {
+            "reliable" => true,
+            "best_effort" => false,
+            other => bail!("invalid QOS_RELIABILITY `{other}`, expected reliable or best_effort"),
+        };
+        let transient_local = match env_or("QOS_DURABILITY", "volatile".to_owned())?.as_str() {
+            "volatile" => false,
+            "transient_local" => true,
+            other => {
+                bail!("invalid QOS_DURABILITY `{other}`, expected volatile or transient_local")
+            }
+        };
+        let depth = env_or("QOS_DEPTH", 10)?;
+        let deadline_ms = match std::env::var("QOS_DEADLINE_MS") {
+            Ok(value) => Some(
+                value
+                    .parse()
+                    .map_err(|err| eyre!("invalid QOS_DEADLINE_MS: {err}"))?,
+            ),
+            Err(_) => None,
+        };
+        Ok(Self {
+            reliable,
+            transient_local,
+            depth,
+            deadline_ms,
+        })
+    }
+
+    pub fn policies(&self) -> rustdds::QosPolicies {
+        let mut builder = rustdds::QosPolicyBuilder::new()
+            .durability(if self.transient_local {
+                policy::Durability::TransientLocal
+            } else {
+                policy::Durability::Volatile
+            })
+            .reliability(if self.reliable {
+                policy::Reliability::Reliable {
+                    max_blocking_time: ros2::Duration::from_millis(100),
+                }
+            } else {
+                policy::Reliability::BestEffort
+            })
+            .history(policy::History::KeepLast { depth: self.depth });
+        if let Some(deadline_ms) = self.deadline_ms {
+            builder = builder.deadline(policy::Deadline(ros2::Duration::from_millis(deadline_ms)));
+        }
+        builder.build()
+    }
+}
+
+impl std::fmt::Display for QosConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reliability = if self.reliable {
+            "reliable"
+        } else {
+            "best_effort"
+        };
+        let durability = if self.transient_local {
+            "transient_local"
+        } else {
+            "volatile"
+        };
+        write!(f, "{reliability}, {durability}, depth {}", self.depth)?;
+        if let Some(deadline_ms) = self.deadline_ms {
+            write!(f, ", deadline {deadline_ms} ms")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use dora_node::zenoh_config;
+use dora_node_api::{self, DoraNode, Event};
+use eyre::eyre;
+use zenoh::bytes::Encoding;
+
+/// The async variant of the node in `main.rs`, with the same behavior.
+///
+/// Instead of merging the zenoh subscriber into the dora event stream and blocking on the
+/// merged stream, it runs on a tokio runtime and awaits both sources with `select!`. The
+/// zenoh calls are awaited as well, so they don't block the runtime.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    println!("Initializing Zenoh session...");
+    let session = zenoh::open(zenoh_config()?)
+        .await
+        .map_err(|e| eyre!("Failed to open Zenoh session: {}", e))?;
+
+    println!("Declaring Zenoh publisher for 'dora/data'...");
+    let publisher = session
+        .declare_publisher("dora/data")
+        .await
+        .map_err(|e| eyre!("Failed to declare publisher: {}", e))?;
+
+    println!("Declaring Zenoh subscriber for 'zenoh/data'...");
+    let subscriber = session
+        .declare_subscriber("zenoh/data")
+        .await
+        .map_err(|e| eyre!("Failed to declare subscriber: {}", e))?;
+
+    println!("Dora node with Zenoh integration started (async)!");
+
+    let mut sent = 0;
+    let mut received = 0;
+    loop {
+        // both receives are cancel safe: the source that loses the race keeps its message for
+        // the next iteration
+        tokio::select! {
+            event = events.recv_async() => {
+                // the event stream ended, no more events follow
+                let Some(event) = event else {
+                    break;
+                };
+                match event {
+                    Event::Input { id, .. } => match id.as_str() {
+                        "tick" => {
+                            sent += 1;
+                            let message = format!("Hello from Dora node! Message #{}", sent);
+                            println!("Publishing message: {}", message);
+                            publisher
+                                .put(message)
+                                .encoding(Encoding::TEXT_PLAIN)
+                                .await
+                                .map_err(|e| eyre!("Failed to publish data: {}", e))?;
+                        }
+                        other => eprintln!("Ignoring unexpected input `{other}`"),
+                    },
+                    Event::Stop(_) => {
+                        println!("Received stop");
+                        break;
+                    }
+                    Event::InputClosed { id } => {
+                        println!("Input `{id}` was closed");
+                    }
+                    other => eprintln!("Received unexpected input: {other:?}"),
+                }
+            }
+            sample = subscriber.recv_async() => {
+                let sample = sample.map_err(|e| eyre!("Zenoh subscriber closed: {}", e))?;
+                let payload = sample
+                    .payload()
+                    .try_to_string()
+                    .unwrap_or_else(|e| e.to_string().into());
+                println!(
+                    ">> [Subscriber] Received {} ('{}': '{}')",
+                    sample.kind(),
+                    sample.key_expr().as_str(),
+                    payload
+                );
+                received += 1;
+                if received > 5 {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use dora_node::env_or;
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+
+/// Sends a temperature reading on `reading` on every tick, `COUNT` times.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 40)?;
+    let output = DataId::from("reading".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    if sent == count {
+                        break;
+                    }
+                    let reading = 20.0 + (sent as f64 / 5.0).sin();
+                    node.send_output(output.clone(), Default::default(), reading.into_arrow())?;
+                    sent += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("sent {sent} readings");
+    Ok(())
+}
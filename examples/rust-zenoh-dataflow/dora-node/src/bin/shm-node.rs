@@ -0,0 +1,126 @@
+use dora_node::{env_or, zenoh_config};
+use dora_node_api::{
+    DoraNode, Event,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::eyre;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use zenoh::{
+    Wait,
+    shm::{BlockOn, GarbageCollect, ShmProviderBuilder},
+};
+
+/// Frames that fit into the shared memory pool at once.
+const POOL_FRAMES: usize = 8;
+
+/// Publishes a camera-sized frame from zenoh shared memory on `dora/frames` on every tick.
+///
+/// The zenoh app echoes each frame back on `zenoh/frames`. Once `FRAMES` frames came back,
+/// the node prints how many of them arrived through shared memory and the mean round trip,
+/// and stops.
+fn main() -> eyre::Result<()> {
+    let width: usize = env_or("FRAME_WIDTH", 640)?;
+    let height: usize = env_or("FRAME_HEIGHT", 480)?;
+    let frames: u64 = env_or("FRAMES", 100)?;
+    let frame_bytes = width * height * 3;
+
+    let (_node, events) = DoraNode::init_from_env()?;
+
+    let session = zenoh::open(zenoh_config()?)
+        .wait()
+        .map_err(|e| eyre!("Failed to open Zenoh session: {}", e))?;
+    let publisher = session
+        .declare_publisher("dora/frames")
+        .wait()
+        .map_err(|e| eyre!("Failed to declare publisher: {}", e))?;
+    let subscriber = session
+        .declare_subscriber("zenoh/frames")
+        .wait()
+        .map_err(|e| eyre!("Failed to declare subscriber: {}", e))?;
+
+    // frames are written into this pool, and only a reference to them is sent to local peers
+    let provider = ShmProviderBuilder::default_backend(POOL_FRAMES * frame_bytes)
+        .wait()
+        .map_err(|e| eyre!("Failed to create SHM provider: {}", e))?;
+    println!(
+        "Publishing {width}x{height} RGB frames ({frame_bytes} bytes) from shared memory \
+         on 'dora/frames'"
+    );
+
+    let merged = events.merge_external(Box::pin(subscriber.stream()));
+    let mut merged_events = futures::executor::block_on_stream(merged);
+
+    let mut seq: u64 = 0;
+    let mut sent = HashMap::new();
+    let mut echoed: u64 = 0;
+    let mut in_shm: u64 = 0;
+    let mut round_trips = Duration::ZERO;
+    while let Some(event) = merged_events.next() {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, .. } => match id.as_str() {
+                    "tick" => {
+                        // blocks until an earlier frame is released if the pool is full
+                        let mut frame = provider
+                            .alloc(frame_bytes)
+                            .with_policy::<BlockOn<GarbageCollect>>()
+                            .wait()
+                            .map_err(|e| eyre!("Failed to allocate frame: {:?}", e))?;
+                        frame[..8].copy_from_slice(&seq.to_le_bytes());
+                        frame[8..].fill(seq as u8);
+                        sent.insert(seq, Instant::now());
+                        publisher
+                            .put(frame)
+                            .wait()
+                            .map_err(|e| eyre!("Failed to publish frame: {}", e))?;
+                        seq += 1;
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::Stop(_) => {
+                    println!("Received stop");
+                    break;
+                }
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                }
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(sample) => {
+                let payload = sample.payload();
+                let bytes = payload.to_bytes();
+                if bytes.len() != frame_bytes {
+                    eprintln!("Ignoring echo of {} bytes", bytes.len());
+                    continue;
+                }
+                let echo_seq = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+                let Some(sent_at) = sent.remove(&echo_seq) else {
+                    eprintln!("Ignoring echo of unknown frame {echo_seq}");
+                    continue;
+                };
+                round_trips += sent_at.elapsed();
+                echoed += 1;
+                if payload.as_shm().is_some() {
+                    in_shm += 1;
+                }
+                if echoed >= frames {
+                    break;
+                }
+            }
+        }
+    }
+
+    if echoed == 0 {
+        println!("No frames came back, is the zenoh app running?");
+    } else {
+        println!(
+            "{echoed} frames came back, {in_shm} of them through shared memory, \
+             mean round trip {:?}",
+            round_trips / echoed as u32
+        );
+    }
+    Ok(())
+}
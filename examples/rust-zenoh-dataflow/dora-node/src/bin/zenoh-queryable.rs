@@ -0,0 +1,78 @@
+use dora_node::zenoh_config;
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{array::AsArray, datatypes::Float64Type},
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::eyre;
+use zenoh::{Wait, bytes::Encoding};
+
+/// Serves the latest `reading` input on the zenoh key `dora/sensor/latest`.
+///
+/// Each `get` on the key is answered with the latest reading and its number as JSON, or with
+/// an error reply if no reading arrived yet. Stops when the `reading` input is closed.
+fn main() -> eyre::Result<()> {
+    let (_node, events) = DoraNode::init_from_env()?;
+
+    println!("Initializing Zenoh session...");
+    let session = zenoh::open(zenoh_config()?)
+        .wait()
+        .map_err(|e| eyre!("Failed to open Zenoh session: {}", e))?;
+
+    println!("Declaring Zenoh queryable for 'dora/sensor/latest'...");
+    let queryable = session
+        .declare_queryable("dora/sensor/latest")
+        .wait()
+        .map_err(|e| eyre!("Failed to declare queryable: {}", e))?;
+
+    let merged = events.merge_external(Box::pin(queryable.stream()));
+    let mut merged_events = futures::executor::block_on_stream(merged);
+
+    // the number of readings so far, and the latest one
+    let mut latest: Option<(u64, f64)> = None;
+    while let Some(event) = merged_events.next() {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, data, .. } => match id.as_str() {
+                    "reading" => {
+                        let values = data
+                            .as_primitive_opt::<Float64Type>()
+                            .ok_or_else(|| eyre!("expected a Float64 array"))?;
+                        for value in values.iter().flatten() {
+                            let seq = latest.map_or(1, |(seq, _)| seq + 1);
+                            latest = Some((seq, value));
+                        }
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::Stop(_) => {
+                    println!("Received stop");
+                    break;
+                }
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    if id.as_str() == "reading" {
+                        break;
+                    }
+                }
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(query) => {
+                println!(">> [Queryable] Received query '{}'", query.selector());
+                let reply = match latest {
+                    Some((seq, value)) => query
+                        .reply(
+                            query.key_expr().clone(),
+                            format!(r#"{{"seq":{seq},"value":{value}}}"#),
+                        )
+                        .encoding(Encoding::APPLICATION_JSON)
+                        .wait(),
+                    None => query.reply_err("no reading yet").wait(),
+                };
+                reply.map_err(|e| eyre!("Failed to reply: {}", e))?;
+            }
+        }
+    }
+
+    Ok(())
+}
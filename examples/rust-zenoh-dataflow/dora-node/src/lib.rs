@@ -0,0 +1,22 @@
+use eyre::eyre;
+use std::path::Path;
+use zenoh::config::Config;
+
+pub use example_node_utils::env_or;
+
+/// Loads the zenoh configuration from the JSON5 file at `ZENOH_CONFIG`.
+///
+/// Without it, the session uses the default configuration, which discovers peers on the local
+/// network through multicast scouting.
+pub fn zenoh_config() -> eyre::Result<Config> {
+    match std::env::var_os("ZENOH_CONFIG") {
+        Some(path) => Config::from_file(&path).map_err(|e| {
+            eyre!(
+                "Failed to load Zenoh config {}: {}",
+                Path::new(&path).display(),
+                e
+            )
+        }),
+        None => Ok(Config::default()),
+    }
+}
@@ -1,10 +1,11 @@
+use dora_node::zenoh_config;
 use dora_node_api::{
     self, DoraNode, Event,
     merged::{MergeExternal, MergedEvent},
 };
 use eyre::eyre;
+use zenoh::Wait;
 use zenoh::bytes::Encoding;
-use zenoh::{Wait, config::Config};
 
 /// The zenoh app receives 5 msgs from dora node first.
 /// Then, zenoh app's publication starts.
@@ -16,8 +17,7 @@ fn main() -> eyre::Result<()> {
 
     // Initialize Zenoh
     println!("Initializing Zenoh session...");
-    let zenoh_config = Config::default();
-    let session = zenoh::open(zenoh_config)
+    let session = zenoh::open(zenoh_config()?)
         .wait()
         .map_err(|e| eyre!("Failed to open Zenoh session: {}", e))?;
 
@@ -1,8 +1,15 @@
 use dora_tracing::set_up_tracing;
+use example_runner_utils::{CargoBuild, Dora};
 use eyre::{Context, bail};
-use std::path::Path;
+use std::{path::Path, time::Duration};
 use tokio::process::Child;
 
+/// The endpoint of the router that `--router` starts, see `zenoh-client.json5`.
+const ROUTER_ENDPOINT: &str = "127.0.0.1:7447";
+
+/// Where the zenoh apps are built, independent of `CARGO_TARGET_DIR`.
+const ZENOH_APP_TARGET: &str = "zenoh-app/target";
+
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     set_up_tracing("rust-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
@@ -11,64 +18,92 @@ async fn main() -> eyre::Result<()> {
     std::env::set_current_dir(root.join(file!()).parent().unwrap())
         .wrap_err("failed to set working dir")?;
 
-    let args: Vec<String> = std::env::args().collect();
-    let dataflow = if args.len() > 1 {
-        Path::new(&args[1])
+    // `--shm` and `--queryable` run the other variants, `--async` the async node of the first
+    // one, `--router` connects them through a `zenohd` router, any other argument is the
+    // dataflow
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let flag = |name: &str| args.iter().any(|arg| arg == name);
+    let (default_dataflow, app) = if flag("--shm") {
+        ("dataflow-shm.yml", "shm-app")
+    } else if flag("--queryable") {
+        ("dataflow-queryable.yml", "get-app")
+    } else if flag("--async") {
+        ("dataflow-async.yml", "zenoh-app")
     } else {
-        Path::new("dataflow.yml")
+        ("dataflow.yml", "zenoh-app")
     };
+    let dataflow = Path::new(
+        args.iter()
+            .find(|arg| !arg.starts_with("--"))
+            .map_or(default_dataflow, String::as_str),
+    );
 
-    build_dataflow(dataflow).await?;
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+    build_zenoh_app().await?;
 
-    let mut dataflow_proc = run_dataflow(dataflow).await?;
-    let mut zenoh_proc = run_zenoh_app().await?;
+    let (mut router, zenoh_config) = if flag("--router") {
+        let router = start_router().await?;
+        let config = std::fs::canonicalize("zenoh-client.json5")?;
+        (Some(router), Some(config))
+    } else {
+        (None, None)
+    };
+
+    let mut dataflow_cmd = dora.cli(["daemon", "--run-dataflow"]);
+    dataflow_cmd.arg(dataflow);
+    if let Some(config) = &zenoh_config {
+        dataflow_cmd.env("ZENOH_CONFIG", config);
+    }
+    let mut dataflow_proc = dataflow_cmd.spawn()?;
+    let mut zenoh_proc = run_zenoh_app(app, zenoh_config.as_deref())?;
 
     dataflow_proc.wait().await?;
     zenoh_proc.kill().await?;
+    if let Some(router) = &mut router {
+        router.kill().await?;
+    }
 
     Ok(())
 }
 
-async fn build_dataflow(dataflow: &Path) -> eyre::Result<()> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--").arg("build").arg(dataflow);
-    if !cmd.status().await?.success() {
-        bail!("failed to build dataflow");
-    };
-    Ok(())
+/// Starts a `zenohd` router on [`ROUTER_ENDPOINT`] and waits until it accepts connections.
+async fn start_router() -> eyre::Result<Child> {
+    let zenohd = which::which("zenohd")
+        .context("`--router` needs `zenohd`, install it with `cargo install zenohd`")?;
+    let router = tokio::process::Command::new(zenohd)
+        .arg("--listen")
+        .arg(format!("tcp/{ROUTER_ENDPOINT}"))
+        .arg("--no-multicast-scouting")
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start zenohd")?;
+    for _ in 0..50 {
+        if port_check::is_port_reachable(ROUTER_ENDPOINT) {
+            return Ok(router);
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    bail!("zenohd did not listen on {ROUTER_ENDPOINT} within 5s");
 }
 
-async fn run_dataflow(dataflow: &Path) -> eyre::Result<Child> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let dora = std::env::var("DORA").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::PathBuf::from(dora).join("Cargo.toml"));
-    cmd.arg("--package").arg("dora-cli");
-    cmd.arg("--release");
-    cmd.arg("--")
-        .arg("daemon")
-        .arg("--run-dataflow")
-        .arg(dataflow);
-    let child = cmd.spawn()?;
-    Ok(child)
+/// Starts the `bin` of `zenoh-app`, which [`build_zenoh_app`] built.
+fn run_zenoh_app(bin: &str, zenoh_config: Option<&Path>) -> eyre::Result<Child> {
+    let program = Path::new(ZENOH_APP_TARGET)
+        .join("release")
+        .join(format!("{bin}{}", std::env::consts::EXE_SUFFIX));
+    let mut cmd = tokio::process::Command::new(&program);
+    if let Some(config) = zenoh_config {
+        cmd.env("ZENOH_CONFIG", config);
+    }
+    cmd.spawn()
+        .with_context(|| format!("failed to start {}", program.display()))
 }
 
-async fn run_zenoh_app() -> eyre::Result<Child> {
-    let cargo = std::env::var("CARGO").unwrap();
-    let mut cmd = tokio::process::Command::new(&cargo);
-    cmd.arg("run");
-    cmd.arg("--manifest-path")
-        .arg(std::path::Path::new("./zenoh-app").join("Cargo.toml"));
-    cmd.arg("--release");
-    let child = cmd.spawn()?;
-    Ok(child)
+async fn build_zenoh_app() -> eyre::Result<()> {
+    CargoBuild::new("zenoh-app")
+        .manifest_path(Path::new("zenoh-app").join("Cargo.toml"))
+        .target_dir(ZENOH_APP_TARGET)
+        .build()
+        .await
 }
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() {
+    let selector = "dora/sensor/latest";
+
+    println!("Opening Zenoh session...");
+    let config = zenoh_app::zenoh_config().unwrap();
+    let session = zenoh::open(config).await.unwrap();
+
+    // asks for the latest reading once per second, the dora node answers on demand
+    loop {
+        println!("Sending query '{}'...", selector);
+        let replies = session
+            .get(selector)
+            .timeout(Duration::from_secs(2))
+            .await
+            .unwrap();
+        while let Ok(reply) = replies.recv_async().await {
+            match reply.result() {
+                Ok(sample) => {
+                    let payload = sample
+                        .payload()
+                        .try_to_string()
+                        .unwrap_or_else(|e| e.to_string().into());
+                    println!(
+                        ">> [Get] Received reply ('{}': '{}')",
+                        sample.key_expr().as_str(),
+                        payload
+                    );
+                }
+                Err(err) => {
+                    let payload = err
+                        .payload()
+                        .try_to_string()
+                        .unwrap_or_else(|e| e.to_string().into());
+                    println!(">> [Get] Received error reply '{}'", payload);
+                }
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
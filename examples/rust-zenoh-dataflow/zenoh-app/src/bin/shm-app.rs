@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+/// Prints a line about every this many frames.
+const REPORT_EVERY: u64 = 30;
+
+#[tokio::main]
+async fn main() {
+    let selector = "dora/frames";
+    let publish_topic = "zenoh/frames";
+
+    println!("Opening Zenoh session...");
+    let config = zenoh_app::zenoh_config().unwrap();
+    let session = zenoh::open(config).await.unwrap();
+
+    println!("Subscribing to {}...", selector);
+    let subscriber = session.declare_subscriber(selector).await.unwrap();
+    println!("Creating publisher for '{}'...", publish_topic);
+    let publisher = session.declare_publisher(publish_topic).await.unwrap();
+
+    let mut count = 0;
+    let mut in_shm = 0;
+    let mut bytes = 0;
+    let mut start = Instant::now();
+    while let Ok(sample) = subscriber.recv_async().await {
+        let payload = sample.payload();
+        // frames that a local peer allocated in shared memory arrive as a reference to it
+        if payload.as_shm().is_some() {
+            in_shm += 1;
+        }
+        bytes += payload.len();
+        count += 1;
+
+        // re-publishing the payload sends the same shared memory buffer back, without a copy
+        publisher.put(payload.clone()).await.unwrap();
+
+        if count % REPORT_EVERY == 0 {
+            let elapsed = start.elapsed().as_secs_f64();
+            println!(
+                ">> [Subscriber] {count} frames, {in_shm} in shared memory, \
+                 {:.1} frames/s, {:.1} MB/s",
+                REPORT_EVERY as f64 / elapsed,
+                bytes as f64 / elapsed / 1e6
+            );
+            bytes = 0;
+            start = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use zenoh::config::Config;
+
+/// Loads the zenoh configuration from the JSON5 file at `ZENOH_CONFIG`, or uses the default
+/// configuration, which discovers peers through multicast scouting.
+pub fn zenoh_config() -> zenoh::Result<Config> {
+    match std::env::var_os("ZENOH_CONFIG") {
+        Some(path) => Config::from_file(path),
+        None => Ok(Config::default()),
+    }
+}
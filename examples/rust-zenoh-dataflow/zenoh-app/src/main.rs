@@ -11,7 +11,7 @@ async fn main() {
 
     // Initialize Zenoh
     println!("Opening Zenoh session...");
-    let config = zenoh::config::Config::default();
+    let config = zenoh_app::zenoh_config().unwrap();
     let session = zenoh::open(config).await.unwrap();
 
     // Subscribe to the topic
@@ -0,0 +1,37 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use std::path::Path;
+
+/// Must match the `gate` configuration in `dataflow.yml`.
+const HEARTBEAT_TIMEOUT_MS: &str = "200";
+const NEUTRAL_BAND: &str = "0.05";
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("safety-interlock-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let mut cmd = tokio::process::Command::new("nodes/target/release/verify-interlocks");
+    cmd.args([
+        "out/actuator.jsonl",
+        "out/estop.jsonl",
+        "out/commander.jsonl",
+    ])
+    .args(["--heartbeat-timeout-ms", HEARTBEAT_TIMEOUT_MS])
+    .args(["--neutral-band", NEUTRAL_BAND]);
+    if !cmd.status().await?.success() {
+        bail!("interlock verification failed");
+    }
+
+    Ok(())
+}
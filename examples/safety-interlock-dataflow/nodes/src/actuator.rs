@@ -0,0 +1,53 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::Context;
+use safety_interlock_dataflow_nodes::{
+    ActuatorRecord, Command, create_jsonl, env_or, now_us, write_jsonl,
+};
+use std::path::PathBuf;
+
+/// Stands in for the drive-by-wire actuators. Applies every command it receives and records
+/// it to `ACTUATOR_FILE`, so that the fail-safe behavior can be checked afterwards.
+fn main() -> eyre::Result<()> {
+    let actuator_file: PathBuf = env_or("ACTUATOR_FILE", "out/actuator.jsonl".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+    let mut records = create_jsonl(&actuator_file)?;
+
+    let mut applied = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "command" => {
+                    let command = Command::from_arrow(&data)?;
+                    write_jsonl(
+                        &mut records,
+                        &ActuatorRecord {
+                            time_us: now_us(),
+                            command,
+                        },
+                    )?;
+                    applied += 1;
+                }
+                "gate_state" => {
+                    let state = <&str>::try_from(&data).context("expected a gate state string")?;
+                    println!("gate {state}");
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "command" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("applied {applied} commands");
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow,
+    arrow::array::{Array, AsArray},
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use safety_interlock_dataflow_nodes::{Command, env_or};
+use std::time::{Duration, Instant};
+
+/// A condition that must hold for commands to pass the gate.
+struct Interlock {
+    name: &'static str,
+    /// The interlock trips if its source is silent for longer than this.
+    timeout: Duration,
+    last_seen: Option<Instant>,
+    active: bool,
+    closed: bool,
+}
+
+impl Interlock {
+    fn new(name: &'static str, timeout: Duration) -> Self {
+        Self {
+            name,
+            timeout,
+            last_seen: None,
+            active: false,
+            closed: false,
+        }
+    }
+
+    fn update(&mut self, active: bool) {
+        self.last_seen = Some(Instant::now());
+        self.active = active;
+    }
+
+    /// Returns why the interlock is not clear, if it isn't.
+    ///
+    /// Missing, stale, or closed sources count as faults: the absence of a signal must never
+    /// be mistaken for "all clear".
+    fn fault(&self, now: Instant) -> Option<String> {
+        let name = self.name;
+        match self.last_seen {
+            _ if self.closed => Some(format!("`{name}` closed")),
+            None => Some(format!("no `{name}` received yet")),
+            Some(last_seen) if now - last_seen > self.timeout => Some(format!("`{name}` is stale")),
+            Some(_) if self.active => Some(format!("`{name}` is active")),
+            Some(_) => None,
+        }
+    }
+}
+
+/// Passes actuator commands only while all interlocks are clear.
+///
+/// When an interlock trips, the gate closes and sends a zero command right away, and then on
+/// every `tick` until it opens again. It only opens again when all interlocks are clear and the
+/// commanded throttle is within `NEUTRAL_BAND`, so the vehicle never jumps back to the
+/// throttle that was commanded before the gate closed.
+fn main() -> eyre::Result<()> {
+    let heartbeat_timeout = Duration::from_millis(env_or("HEARTBEAT_TIMEOUT_MS", 200)?);
+    let estop_timeout = Duration::from_millis(env_or("ESTOP_TIMEOUT_MS", 200)?);
+    let neutral_band: f64 = env_or("NEUTRAL_BAND", 0.05)?;
+    let command_output = DataId::from("actuator_command".to_owned());
+    let state_output = DataId::from("gate_state".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut estop = Interlock::new("estop", estop_timeout);
+    let mut heartbeat = Interlock::new("heartbeat", heartbeat_timeout);
+    let mut open = false;
+    let mut forwarded = 0;
+    let mut dropped = 0;
+    while let Some(event) = events.recv() {
+        let mut command = None;
+        let mut tick = false;
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "estop" => {
+                    let pressed = data
+                        .as_boolean_opt()
+                        .filter(|array| array.len() == 1)
+                        .ok_or_eyre("expected a single boolean e-stop state")?
+                        .value(0);
+                    estop.update(pressed);
+                }
+                "heartbeat" => heartbeat.update(false),
+                "command" => command = Some(Command::from_arrow(&data)?),
+                "tick" => tick = true,
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => match id.as_str() {
+                "estop" => estop.closed = true,
+                "heartbeat" => heartbeat.closed = true,
+                "command" => break,
+                _ => {}
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+
+        // re-evaluated on every event, so that a pressed e-stop closes the gate immediately
+        let now = Instant::now();
+        let fault = estop.fault(now).or_else(|| heartbeat.fault(now));
+        let mut send_zero = false;
+        match (&fault, command) {
+            (Some(reason), _) if open => {
+                open = false;
+                send_zero = true;
+                eprintln!("closing gate: {reason}");
+                node.send_output(
+                    state_output.clone(),
+                    Default::default(),
+                    format!("closed: {reason}").into_arrow(),
+                )?;
+            }
+            (None, Some(command)) if !open && command.is_neutral(neutral_band) => {
+                open = true;
+                println!("all interlocks clear and throttle neutral, opening gate");
+                node.send_output(
+                    state_output.clone(),
+                    Default::default(),
+                    "open".into_arrow(),
+                )?;
+            }
+            _ => {}
+        }
+
+        match command {
+            Some(command) if open => {
+                node.send_output(
+                    command_output.clone(),
+                    Default::default(),
+                    command.to_arrow(),
+                )?;
+                forwarded += 1;
+            }
+            Some(_) => dropped += 1,
+            None => {}
+        }
+        // keep the actuators zeroed while closed, also if they missed a previous zero command
+        if send_zero || (tick && !open) {
+            node.send_output(
+                command_output.clone(),
+                Default::default(),
+                Command::ZERO.to_arrow(),
+            )?;
+        }
+    }
+
+    // the commander is gone, leave the actuators in the fail-safe state
+    node.send_output(
+        command_output.clone(),
+        Default::default(),
+        Command::ZERO.to_arrow(),
+    )?;
+    println!("forwarded {forwarded} commands, dropped {dropped} while closed");
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use safety_interlock_dataflow_nodes::{
+    Command, Hazard, HazardRecord, create_jsonl, env_or, now_us, write_jsonl,
+};
+use std::{
+    f64::consts::TAU,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Period of the throttle profile. The throttle crosses zero every half period.
+const THROTTLE_PERIOD_S: f64 = 4.0;
+const STEERING_PERIOD_S: f64 = 6.0;
+
+/// Stands in for a teleoperation or planning node that drives the vehicle.
+///
+/// Sends a `command` on every `tick` and a `heartbeat` on every `heartbeat_tick`. At
+/// `FREEZE_AT_MS`, it hangs for `FREEZE_MS`, like a node that is stuck in a long computation
+/// or a deadlock, so that both commands and heartbeats stop.
+fn main() -> eyre::Result<()> {
+    let freeze_at = Duration::from_millis(env_or("FREEZE_AT_MS", 8000)?);
+    let freeze = Duration::from_millis(env_or("FREEZE_MS", 1000)?);
+    let duration = Duration::from_millis(env_or("DURATION_MS", 12000)?);
+    let hazard_file: PathBuf = env_or("HAZARD_FILE", "out/commander.jsonl".to_owned())?.into();
+    let command_output = DataId::from("command".to_owned());
+    let heartbeat_output = DataId::from("heartbeat".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let mut hazards = create_jsonl(&hazard_file)?;
+
+    let start = Instant::now();
+    let mut frozen = false;
+    let mut heartbeats = 0u64;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => {
+                let elapsed = start.elapsed();
+                if elapsed >= duration {
+                    break;
+                }
+                if !frozen && elapsed >= freeze_at && !freeze.is_zero() {
+                    frozen = true;
+                    println!("freezing for {} ms", freeze.as_millis());
+                    record_freeze(&mut hazards, true)?;
+                    std::thread::sleep(freeze);
+                    record_freeze(&mut hazards, false)?;
+                    continue;
+                }
+
+                match id.as_str() {
+                    "tick" => {
+                        let t = elapsed.as_secs_f64();
+                        let command = Command {
+                            throttle: 0.5 * (TAU * t / THROTTLE_PERIOD_S).sin(),
+                            steering: 0.2 * (TAU * t / STEERING_PERIOD_S).sin(),
+                        };
+                        node.send_output(
+                            command_output.clone(),
+                            Default::default(),
+                            command.to_arrow(),
+                        )?;
+                    }
+                    "heartbeat_tick" => {
+                        heartbeats += 1;
+                        node.send_output(
+                            heartbeat_output.clone(),
+                            Default::default(),
+                            heartbeats.into_arrow(),
+                        )?;
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn record_freeze(hazards: &mut std::fs::File, active: bool) -> eyre::Result<()> {
+    write_jsonl(
+        hazards,
+        &HazardRecord {
+            time_us: now_us(),
+            hazard: Hazard::Heartbeat,
+            active,
+        },
+    )
+}
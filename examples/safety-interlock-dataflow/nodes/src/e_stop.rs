@@ -0,0 +1,66 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::BooleanArray, dora_core::config::DataId};
+use safety_interlock_dataflow_nodes::{
+    Hazard, HazardRecord, create_jsonl, env_or, now_us, write_jsonl,
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Publishes the state of an emergency stop button on every `tick`.
+///
+/// The button is scripted: it is pressed at `PRESS_AT_MS` and released at `RELEASE_AT_MS`.
+/// The state is sent continuously, not only on changes, so that the gate can tell a released
+/// button from a broken connection.
+fn main() -> eyre::Result<()> {
+    let press_at = Duration::from_millis(env_or("PRESS_AT_MS", 3000)?);
+    let release_at = Duration::from_millis(env_or("RELEASE_AT_MS", 5000)?);
+    let duration = Duration::from_millis(env_or("DURATION_MS", 12000)?);
+    let hazard_file: PathBuf = env_or("HAZARD_FILE", "out/estop.jsonl".to_owned())?.into();
+    let output = DataId::from("pressed".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let mut hazards = create_jsonl(&hazard_file)?;
+
+    let start = Instant::now();
+    let mut pressed = false;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    let elapsed = start.elapsed();
+                    if elapsed >= duration {
+                        break;
+                    }
+                    let now_pressed = elapsed >= press_at && elapsed < release_at;
+                    if now_pressed != pressed {
+                        pressed = now_pressed;
+                        println!("e-stop {}", if pressed { "pressed" } else { "released" });
+                        write_jsonl(
+                            &mut hazards,
+                            &HazardRecord {
+                                time_us: now_us(),
+                                hazard: Hazard::EStop,
+                                active: pressed,
+                            },
+                        )?;
+                    }
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        BooleanArray::from(vec![pressed]),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use dora_node_api::arrow::{
+    array::{Array, AsArray, Float64Array},
+    datatypes::Float64Type,
+};
+use eyre::{Context, OptionExt, bail};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{io::Write, path::Path};
+
+pub use example_node_utils::{env_or, now_us};
+
+/// An actuator command of a drive-by-wire vehicle, sent as a `Float64` array of
+/// `[throttle, steering]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Command {
+    /// Between -1 (full brake) and 1 (full throttle).
+    pub throttle: f64,
+    /// Between -1 (full left) and 1 (full right).
+    pub steering: f64,
+}
+
+impl Command {
+    /// The fail-safe command: no throttle, wheels straight.
+    pub const ZERO: Self = Self {
+        throttle: 0.0,
+        steering: 0.0,
+    };
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    /// Whether the throttle is close enough to zero to re-enable the actuators without a jump.
+    pub fn is_neutral(&self, band: f64) -> bool {
+        self.throttle.abs() <= band
+    }
+
+    pub fn to_arrow(self) -> Float64Array {
+        Float64Array::from(vec![self.throttle, self.steering])
+    }
+
+    pub fn from_arrow(data: &dyn Array) -> eyre::Result<Self> {
+        let values = data
+            .as_primitive_opt::<Float64Type>()
+            .ok_or_eyre("expected a Float64 command")?
+            .values();
+        let [throttle, steering] = values[..] else {
+            bail!("expected [throttle, steering], got {} values", values.len());
+        };
+        Ok(Self { throttle, steering })
+    }
+}
+
+/// A condition that must force the actuators into the fail-safe state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hazard {
+    /// The e-stop is pressed.
+    EStop,
+    /// The commander hangs and stops sending heartbeats.
+    Heartbeat,
+}
+
+/// Written by the nodes that inject hazards, to check the reaction of the gate afterwards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HazardRecord {
+    pub time_us: u64,
+    pub hazard: Hazard,
+    pub active: bool,
+}
+
+/// Written by the actuator for every command that it applies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActuatorRecord {
+    pub time_us: u64,
+    pub command: Command,
+}
+
+/// Creates an empty JSON lines file, replacing the file of a previous run.
+pub fn create_jsonl(path: &Path) -> eyre::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))
+}
+
+pub fn write_jsonl<T: Serialize>(file: &mut std::fs::File, record: &T) -> eyre::Result<()> {
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+pub fn read_jsonl<T: DeserializeOwned>(path: &Path) -> eyre::Result<Vec<T>> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).context("invalid JSON line"))
+        .collect()
+}
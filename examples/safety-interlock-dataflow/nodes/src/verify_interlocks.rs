@@ -0,0 +1,122 @@
+//! Checks the commands recorded by `actuator` against the hazards recorded by `e-stop` and
+//! `commander`.
+//!
+//! Usage: `verify-interlocks <actuator.jsonl> <hazards.jsonl>... --heartbeat-timeout-ms <n>
+//! --neutral-band <x>`
+
+use eyre::{bail, eyre};
+use safety_interlock_dataflow_nodes::{ActuatorRecord, Hazard, HazardRecord, read_jsonl};
+use std::path::PathBuf;
+
+/// How long after pressing the e-stop the actuators may still receive a non-zero command.
+const ESTOP_REACTION_MS: u64 = 100;
+/// How much later than `--heartbeat-timeout-ms` a stale heartbeat may be detected. Covers the
+/// interval of the gate's `tick` timer and scheduling delays.
+const HEARTBEAT_SLACK_MS: u64 = 100;
+
+fn main() -> eyre::Result<()> {
+    let mut files = Vec::new();
+    let mut heartbeat_timeout_ms = None;
+    let mut neutral_band = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--heartbeat-timeout-ms" => {
+                heartbeat_timeout_ms = args.next().map(|v| v.parse::<u64>()).transpose()?
+            }
+            "--neutral-band" => neutral_band = args.next().map(|v| v.parse::<f64>()).transpose()?,
+            other => files.push(PathBuf::from(other)),
+        }
+    }
+    let usage = "usage: verify-interlocks <actuator.jsonl> <hazards.jsonl>... \
+                 --heartbeat-timeout-ms <n> --neutral-band <x>";
+    if files.len() < 2 {
+        bail!(usage);
+    }
+    let heartbeat_timeout_ms = heartbeat_timeout_ms.ok_or_else(|| eyre!(usage))?;
+    let neutral_band = neutral_band.ok_or_else(|| eyre!(usage))?;
+
+    let actuator: Vec<ActuatorRecord> = read_jsonl(&files[0])?;
+    let Some(last) = actuator.last() else {
+        bail!("the actuator received no commands");
+    };
+
+    // pair the records of each file into (hazard, start, end) windows
+    let mut windows = Vec::new();
+    for file in &files[1..] {
+        let mut active = None;
+        for record in read_jsonl::<HazardRecord>(file)? {
+            match (record.active, active.take()) {
+                (true, None) => active = Some((record.hazard, record.time_us)),
+                (false, Some((hazard, start))) => windows.push((hazard, start, record.time_us)),
+                _ => bail!("unpaired {:?} record in {}", record.hazard, file.display()),
+            }
+        }
+        // still active when the dataflow stopped
+        if let Some((hazard, start)) = active {
+            windows.push((hazard, start, last.time_us));
+        }
+    }
+    if windows.is_empty() {
+        bail!("no hazards were injected");
+    }
+    windows.sort_by_key(|(_, start, _)| *start);
+
+    for (hazard, start, end) in &windows {
+        let (start, end) = (*start, *end);
+        let reaction_ms = match hazard {
+            Hazard::EStop => ESTOP_REACTION_MS,
+            Hazard::Heartbeat => heartbeat_timeout_ms + HEARTBEAT_SLACK_MS,
+        };
+        let deadline = start + reaction_ms * 1000;
+
+        // the actuators are zeroed in time...
+        let zeroed_at = actuator
+            .iter()
+            .find(|record| record.time_us >= start && record.command.is_zero())
+            .map(|record| record.time_us)
+            .filter(|time| *time <= deadline)
+            .ok_or_else(|| eyre!("{hazard:?} at {start}: not zeroed within {reaction_ms} ms"))?;
+        // ...and stay zeroed until the hazard is gone
+        let during = actuator
+            .iter()
+            .filter(|record| record.time_us >= deadline && record.time_us <= end);
+        if let Some(record) = during.clone().find(|record| !record.command.is_zero()) {
+            bail!(
+                "{hazard:?} at {start}: non-zero command {:?} reached the actuators {} ms later",
+                record.command,
+                (record.time_us - start) / 1000
+            );
+        }
+        if during.count() == 0 {
+            bail!("{hazard:?} at {start}: the actuators received no zero commands while closed");
+        }
+
+        // re-enabling must not jump to the throttle of before
+        let Some(resumed) = actuator
+            .iter()
+            .find(|record| record.time_us > end && !record.command.is_zero())
+        else {
+            bail!("{hazard:?} at {start}: the gate never opened again");
+        };
+        if !resumed.command.is_neutral(neutral_band) {
+            bail!(
+                "{hazard:?} at {start}: resumed with {:?}, outside of the neutral band",
+                resumed.command
+            );
+        }
+
+        println!(
+            "{hazard:?}: zeroed after {} ms, resumed {} ms after clearing with throttle {:.3}",
+            (zeroed_at - start) / 1000,
+            (resumed.time_us - end) / 1000,
+            resumed.command.throttle
+        );
+    }
+    println!(
+        "all {} hazards were handled fail-safe, {} commands checked",
+        windows.len(),
+        actuator.len()
+    );
+    Ok(())
+}
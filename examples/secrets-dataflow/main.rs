@@ -0,0 +1,211 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, node_env};
+use eyre::{Context, bail, eyre};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::Ordering,
+};
+
+mod mock_service;
+
+/// The credentials that the runner injects into the dataflow. Only these are read from `.env`.
+const SECRETS: [&str; 2] = ["SERVICE_USER", "SERVICE_TOKEN"];
+/// The secret that must not appear in any log or report. The user name is not secret on its
+/// own, and a common one like `dora` appears in the logs anyway.
+const LEAK_CHECKED: &str = "SERVICE_TOKEN";
+
+/// Subset of `SinkReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkReport {
+    authenticated: bool,
+    sent: u64,
+    acked: u64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("secrets-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let secrets = load_secrets(Path::new(".env"))?;
+    let secret = |name: &str| secrets[name].as_str();
+
+    let dataflow = Path::new("dataflow.yml");
+    let yaml = std::fs::read_to_string(dataflow)?;
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&yaml)?;
+    let in_dataflow = |name| node_env(&descriptor, "sink", name).is_ok();
+    if SECRETS.into_iter().any(in_dataflow) || yaml.contains(secret(LEAK_CHECKED)) {
+        bail!(
+            "{} must not contain credentials, they are injected",
+            dataflow.display()
+        );
+    }
+    let count: u64 = node_env(&descriptor, "source", "COUNT")?.parse()?;
+    let addr = node_env(&descriptor, "sink", "SERVICE_ADDR")?;
+
+    let stats = mock_service::start(
+        &addr,
+        secret("SERVICE_USER").to_owned(),
+        secret("SERVICE_TOKEN").to_owned(),
+    )
+    .await?;
+    // the service must not accept just any token
+    let reply = mock_service::try_login(&addr, secret("SERVICE_USER"), "wrong-token").await?;
+    if reply != "DENIED" {
+        bail!("the mock service accepted a wrong token: {reply}");
+    }
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+
+    // only the daemon gets the secrets, and passes them on to the nodes that it spawns
+    let mut run = dora.cli(["daemon", "--run-dataflow"]);
+    run.arg(dataflow).envs(&secrets);
+    if !run.status().await?.success() {
+        bail!("failed to run dataflow");
+    }
+
+    let sink: SinkReport = serde_json::from_str(
+        &std::fs::read_to_string("out/sink.json").context("sink did not write a report")?,
+    )?;
+    let (logins, denied, readings) = (
+        stats.logins.load(Ordering::Relaxed),
+        stats.denied.load(Ordering::Relaxed),
+        stats.readings.load(Ordering::Relaxed),
+    );
+    println!(
+        "sink: authenticated: {}, {} readings sent, {} acknowledged",
+        sink.authenticated, sink.sent, sink.acked
+    );
+    println!("service: {logins} logins, {denied} denied, {readings} readings");
+    if !sink.authenticated || logins != 1 {
+        bail!("the sink should log in once with the injected credentials");
+    }
+    if sink.acked != count || readings != count {
+        bail!("the service should acknowledge all {count} readings");
+    }
+    if denied != 1 {
+        bail!("only the login with the wrong token should be denied, {denied} were");
+    }
+
+    // the logs of the nodes and the daemon, and the reports
+    let leaks = find_in_files(Path::new("out"), secret(LEAK_CHECKED))?;
+    if !leaks.is_empty() {
+        bail!("`{LEAK_CHECKED}` leaked into {leaks:?}");
+    }
+    println!("the sink used the injected credentials, and `{LEAK_CHECKED}` was not logged");
+    Ok(())
+}
+
+/// Reads the [`SECRETS`] from the environment of the runner, or else from `env_file`.
+///
+/// Generates `env_file` with a random token if it doesn't exist and a secret is missing from
+/// the environment.
+fn load_secrets(env_file: &Path) -> eyre::Result<HashMap<&'static str, String>> {
+    let missing = SECRETS.iter().any(|name| std::env::var_os(name).is_none());
+    if missing && !env_file.exists() {
+        generate_env_file(env_file)?;
+        println!("generated {} with a random token", env_file.display());
+    }
+    let from_file = if env_file.exists() {
+        warn_if_readable_by_others(env_file)?;
+        parse_env_file(&std::fs::read_to_string(env_file)?)
+    } else {
+        HashMap::new()
+    };
+    SECRETS
+        .into_iter()
+        .map(|name| {
+            let value = std::env::var(name)
+                .ok()
+                .or_else(|| from_file.get(name).cloned())
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| eyre!("`{name}` is neither set nor in {}", env_file.display()))?;
+            Ok((name, value))
+        })
+        .collect()
+}
+
+/// Parses `KEY=value` lines. Empty lines and comments are skipped, and the value may be
+/// quoted.
+fn parse_env_file(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = ['"', '\'']
+                .into_iter()
+                .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+                .unwrap_or(value);
+            Some((key.trim().to_owned(), value.to_owned()))
+        })
+        .collect()
+}
+
+fn generate_env_file(path: &Path) -> eyre::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    writeln!(file, "SERVICE_USER=dora")?;
+    writeln!(file, "SERVICE_TOKEN={}", random_token()?)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn random_token() -> eyre::Result<String> {
+    let mut bytes = [0; 16];
+    std::fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+#[cfg(not(unix))]
+fn random_token() -> eyre::Result<String> {
+    bail!("create `.env` from `.env.example`");
+}
+
+#[cfg(unix)]
+fn warn_if_readable_by_others(path: &Path) -> eyre::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        tracing::warn!(
+            "{} is accessible by other users, restrict it with `chmod 600`",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn warn_if_readable_by_others(_path: &Path) -> eyre::Result<()> {
+    Ok(())
+}
+
+/// All files in `dir` and its subdirectories that contain `needle`.
+fn find_in_files(dir: &Path, needle: &str) -> eyre::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            found.extend(find_in_files(&path, needle)?);
+        } else if String::from_utf8_lossy(&std::fs::read(&path)?).contains(needle) {
+            found.push(path);
+        }
+    }
+    Ok(found)
+}
@@ -0,0 +1,90 @@
+use eyre::Context;
+use std::sync::{Arc, atomic::AtomicU64, atomic::Ordering};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+/// Counters of the mock service.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub logins: AtomicU64,
+    pub denied: AtomicU64,
+    pub readings: AtomicU64,
+}
+
+/// Starts a mock of a service that requires a login, which the sink uploads its readings to.
+/// It listens on `addr`, and accepts logins with exactly `user` and `token`.
+///
+/// The protocol is line-based, every request gets a one-line reply:
+///
+/// - `AUTH <user> <token>` is answered with `OK`, or with `DENIED` and a closed connection.
+/// - `PUT <seq> <value>` is answered with `ACK <seq>` after a successful login, and with
+///   `DENIED` before.
+pub async fn start(addr: &str, user: String, token: String) -> eyre::Result<Arc<Stats>> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to listen on {addr}"))?;
+    let stats = Arc::new(Stats::default());
+    let credentials = Arc::new((user, token));
+    tokio::spawn({
+        let stats = stats.clone();
+        async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (stats, credentials) = (stats.clone(), credentials.clone());
+                tokio::spawn(async move {
+                    let (user, token) = credentials.as_ref();
+                    if let Err(err) = serve(stream, user, token, &stats).await {
+                        tracing::warn!("mock service connection failed: {err}");
+                    }
+                });
+            }
+        }
+    });
+    Ok(stats)
+}
+
+async fn serve(stream: TcpStream, user: &str, token: &str, stats: &Stats) -> eyre::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut authenticated = false;
+    while let Some(line) = lines.next_line().await? {
+        let (reply, close) = match line.split_once(' ') {
+            Some(("AUTH", credentials)) => {
+                if credentials.split_once(' ') == Some((user, token)) {
+                    authenticated = true;
+                    stats.logins.fetch_add(1, Ordering::Relaxed);
+                    ("OK".to_owned(), false)
+                } else {
+                    stats.denied.fetch_add(1, Ordering::Relaxed);
+                    ("DENIED".to_owned(), true)
+                }
+            }
+            Some(("PUT", reading)) if authenticated => {
+                stats.readings.fetch_add(1, Ordering::Relaxed);
+                let seq = reading.split(' ').next().unwrap_or_default();
+                (format!("ACK {seq}"), false)
+            }
+            _ if !authenticated => ("DENIED".to_owned(), true),
+            _ => ("ERROR unknown request".to_owned(), false),
+        };
+        writer.write_all(format!("{reply}\n").as_bytes()).await?;
+        if close {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Logs in with `user` and `token`, and returns the reply of the service.
+pub async fn try_login(addr: &str, user: &str, token: &str) -> eyre::Result<String> {
+    let stream = TcpStream::connect(addr)
+        .await
+        .with_context(|| format!("failed to connect to {addr}"))?;
+    let (reader, mut writer) = stream.into_split();
+    writer
+        .write_all(format!("AUTH {user} {token}\n").as_bytes())
+        .await?;
+    let reply = BufReader::new(reader).lines().next_line().await?;
+    Ok(reply.unwrap_or_default())
+}
@@ -0,0 +1,53 @@
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub use example_node_utils::{env_or, write_json};
+
+/// A credential that is passed to the node in its environment.
+///
+/// It can't be printed: `Debug` and `Display` only show that there is a value, so it doesn't
+/// end up in the node logs through a log line or an error message by accident.
+pub struct Secret(String);
+
+impl Secret {
+    /// Takes the secret out of the environment variable `name`.
+    ///
+    /// The variable is removed afterwards, so that child processes of the node don't inherit
+    /// it. This must be called before the node starts other threads, which may read the
+    /// environment concurrently.
+    pub fn take_from_env(name: &str) -> eyre::Result<Self> {
+        let value = std::env::var(name).map_err(|_| {
+            eyre!("`{name}` is not set, start the dataflow through the runner, which injects it")
+        })?;
+        // SAFETY: no other threads are running yet, see above
+        unsafe { std::env::remove_var(name) };
+        Ok(Self(value))
+    }
+
+    /// The value of the secret, for the few places that need it.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+/// Written by the sink when its input is closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinkReport {
+    pub authenticated: bool,
+    pub sent: u64,
+    /// Readings that the service acknowledged.
+    pub acked: u64,
+}
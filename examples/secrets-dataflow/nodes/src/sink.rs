@@ -0,0 +1,97 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{array::AsArray, datatypes::Float64Type},
+};
+use eyre::{Context, OptionExt, bail};
+use secrets_dataflow_nodes::{Secret, SinkReport, env_or, write_json};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    path::PathBuf,
+};
+
+/// Uploads the `reading`s to the service at `SERVICE_ADDR`, which requires a login with
+/// `SERVICE_USER` and `SERVICE_TOKEN`.
+///
+/// The credentials are injected by the runner, see the README. The sink takes them out of its
+/// environment on startup, and only ever prints them as `***`.
+///
+/// Writes a [`SinkReport`] to `REPORT_FILE` when its input is closed.
+fn main() -> eyre::Result<()> {
+    // before anything else, while the node is still single-threaded
+    let user = Secret::take_from_env("SERVICE_USER")?;
+    let token = Secret::take_from_env("SERVICE_TOKEN")?;
+    let addr: String = env_or("SERVICE_ADDR", "127.0.0.1:7891".to_owned())?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/sink.json".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    println!("connecting to {addr} as {user} with token {token}");
+    let mut service = Connection::connect(&addr)?;
+    let reply = service.request(&format!("AUTH {} {}", user.expose(), token.expose()))?;
+    if reply != "OK" {
+        bail!("the service at {addr} denied the login: {reply}");
+    }
+    println!("authenticated at {addr}");
+
+    let mut report = SinkReport {
+        authenticated: true,
+        sent: 0,
+        acked: 0,
+    };
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "reading" => {
+                    let values = data
+                        .as_primitive_opt::<Float64Type>()
+                        .ok_or_eyre("expected a Float64 array")?;
+                    for value in values.iter().flatten() {
+                        let seq = report.sent;
+                        let reply = service.request(&format!("PUT {seq} {value}"))?;
+                        report.sent += 1;
+                        if reply == format!("ACK {seq}") {
+                            report.acked += 1;
+                        } else {
+                            eprintln!("the service did not acknowledge reading {seq}: {reply}");
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "uploaded {} readings, {} acknowledged",
+        report.sent, report.acked
+    );
+    write_json(&report_file, &report)
+}
+
+/// A line-based connection to the service, which answers every request with one line.
+struct Connection {
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Connection {
+    fn connect(addr: &str) -> eyre::Result<Self> {
+        let writer =
+            TcpStream::connect(addr).with_context(|| format!("failed to connect to {addr}"))?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self { writer, reader })
+    }
+
+    fn request(&mut self, line: &str) -> eyre::Result<String> {
+        writeln!(self.writer, "{line}")?;
+        let mut reply = String::new();
+        if self.reader.read_line(&mut reply)? == 0 {
+            bail!("the service closed the connection");
+        }
+        Ok(reply.trim_end().to_owned())
+    }
+}
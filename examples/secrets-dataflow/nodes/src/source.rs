@@ -0,0 +1,38 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::Float64Array, dora_core::config::DataId};
+use secrets_dataflow_nodes::env_or;
+
+/// Sends a temperature reading on `reading` on every tick, `COUNT` times.
+///
+/// It doesn't need the credentials of the service, but inherits them from the daemon like
+/// every node of the dataflow.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 100)?;
+    let output = DataId::from("reading".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let value = 20.0 + (sent as f64 / 10.0).sin();
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        Float64Array::from(vec![value]),
+                    )?;
+                    sent += 1;
+                    if sent == count {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    println!("sent {sent} readings");
+    Ok(())
+}
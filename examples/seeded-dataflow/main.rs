@@ -0,0 +1,108 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Must match `COUNT` of the noise generator in `dataflow.yml`.
+const VALUES: u64 = 100;
+/// The root seeds of the runs: the second run repeats the first, the third uses another seed.
+const RUNS: [u64; 3] = [42, 42, 7];
+
+/// Subset of `SinkReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkReport {
+    root_seed: u64,
+    samples: Vec<Sample>,
+}
+
+/// Same as `Sample` in `nodes/src/lib.rs`.
+#[derive(Debug, PartialEq, Deserialize)]
+struct Sample {
+    index: u64,
+    value: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("seeded-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dataflow = Path::new("dataflow.yml");
+    Dora::from_env()?.build_dataflow(dataflow).await?;
+
+    let mut reports = Vec::new();
+    for (run, seed) in (1..).zip(RUNS) {
+        // the seeder reads `ROOT_SEED` from the environment that the nodes inherit
+        let dora = Dora::from_env()?.env("ROOT_SEED", seed.to_string());
+        dora.run_dataflow(dataflow).await?;
+
+        let report: SinkReport = read_json("out/sink.json")?;
+        // keep the output of each run for a comparison by hand
+        std::fs::rename("out/sink.json", format!("out/sink-run-{run}.json"))?;
+        if report.root_seed != seed {
+            bail!(
+                "run {run} should use the root seed {seed}, the sink got values of {}",
+                report.root_seed
+            );
+        }
+        let indices: Vec<_> = report.samples.iter().map(|sample| sample.index).collect();
+        if !indices.is_sorted() || indices.last().is_some_and(|last| *last >= VALUES) {
+            bail!("run {run}: the kept values should be a subsequence of the {VALUES} values");
+        }
+        if report.samples.is_empty() || report.samples.len() as u64 == VALUES {
+            bail!(
+                "run {run}: the sampler kept {} of {VALUES} values, it should keep some",
+                report.samples.len()
+            );
+        }
+        println!(
+            "run {run} with root seed {seed}: kept {} of {VALUES} values, first {:?}",
+            report.samples.len(),
+            report.samples.first().map(|sample| sample.index)
+        );
+        reports.push(report);
+    }
+
+    // the same seed reproduces the noise and the subsample exactly, another seed changes them
+    if reports[0].samples != reports[1].samples {
+        bail!("the runs with the root seed {} differ", RUNS[0]);
+    }
+    let kept = |report: &SinkReport| -> Vec<u64> {
+        report.samples.iter().map(|sample| sample.index).collect()
+    };
+    if kept(&reports[0]) == kept(&reports[2]) {
+        bail!(
+            "the root seeds {} and {} kept the same values",
+            RUNS[0],
+            RUNS[2]
+        );
+    }
+    let common = reports[0]
+        .samples
+        .iter()
+        .filter(|sample| reports[2].samples.iter().any(|other| other == *sample))
+        .count();
+    if common > 0 {
+        bail!(
+            "{common} values are equal in the runs with the root seeds {} and {}, the noise \
+             should differ",
+            RUNS[0],
+            RUNS[2]
+        );
+    }
+    println!(
+        "the root seed {} reproduced its run exactly, the root seed {} changed every value",
+        RUNS[0], RUNS[2]
+    );
+
+    println!("Everything Done");
+    Ok(())
+}
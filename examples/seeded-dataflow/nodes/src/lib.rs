@@ -0,0 +1,91 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::bail;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Metadata key of the root seed, on the message of the seeder and on every message that a
+/// seeded node sends.
+pub const ROOT_SEED_KEY: &str = "root_seed";
+
+/// Metadata key of the position of a value in the stream of the noise generator.
+pub const INDEX_KEY: &str = "index";
+
+/// The random number generator of a node, derived from the root seed of the dataflow.
+///
+/// All nodes start from the same root seed, but each one draws from its own ChaCha stream,
+/// which is selected by a hash of the node id. So the nodes don't repeat each other's numbers,
+/// and adding a node doesn't change the numbers of the others. Renaming a node does.
+pub struct SeededRng {
+    pub root_seed: u64,
+    pub rng: ChaCha8Rng,
+}
+
+impl SeededRng {
+    pub fn new(root_seed: u64, node_id: &str) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(root_seed);
+        rng.set_stream(fnv1a(node_id.as_bytes()));
+        Self { root_seed, rng }
+    }
+
+    /// Seeds the node from the message of the seeder.
+    pub fn from_parameters(parameters: &MetadataParameters, node_id: &str) -> eyre::Result<Self> {
+        let root_seed = root_seed(parameters)?;
+        println!("seeded with root seed {root_seed}");
+        Ok(Self::new(root_seed, node_id))
+    }
+
+    /// Metadata parameters of the value at `index`, with the root seed that it depends on.
+    pub fn parameters(&self, index: u64) -> MetadataParameters {
+        let mut parameters = seed_parameters(self.root_seed);
+        parameters.insert(INDEX_KEY.into(), Parameter::Integer(index as i64));
+        parameters
+    }
+}
+
+/// Metadata parameters that carry `root_seed`. The seed is stored bit for bit in the `i64`
+/// of the `Integer` parameter.
+pub fn seed_parameters(root_seed: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(ROOT_SEED_KEY.into(), Parameter::Integer(root_seed as i64));
+    parameters
+}
+
+pub fn root_seed(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(ROOT_SEED_KEY) {
+        Some(Parameter::Integer(seed)) => Ok(*seed as u64),
+        Some(other) => bail!("unexpected `{ROOT_SEED_KEY}` parameter {other:?}"),
+        None => bail!("input has no `{ROOT_SEED_KEY}` parameter"),
+    }
+}
+
+pub fn index(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(INDEX_KEY) {
+        Some(Parameter::Integer(index)) => Ok(u64::try_from(*index)?),
+        Some(other) => bail!("unexpected `{INDEX_KEY}` parameter {other:?}"),
+        None => bail!("input has no `{INDEX_KEY}` parameter"),
+    }
+}
+
+/// A stable hash, unlike the `DefaultHasher` of the standard library, which may change
+/// between Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A value that reached the sink, with its position in the stream of the noise generator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub index: u64,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SinkReport {
+    pub root_seed: u64,
+    pub samples: Vec<Sample>,
+}
@@ -0,0 +1,50 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use rand::Rng;
+use seeded_dataflow_nodes::{SeededRng, env_or};
+
+/// Sends `COUNT` samples of a sine wave on `value`, one per `tick`, with uniform noise of up
+/// to `NOISE_AMPLITUDE`.
+///
+/// It skips the ticks until the root seed arrives on `seed`, so that a value only depends on
+/// the seed and its index, and not on when the seed arrived.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 100)?;
+    let amplitude: f64 = env_or("NOISE_AMPLITUDE", 0.2)?;
+    let output = DataId::from("value".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    let mut seeded: Option<SeededRng> = None;
+    let mut index = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, .. } => match id.as_str() {
+                "seed" => {
+                    seeded = Some(SeededRng::from_parameters(&metadata.parameters, &node_id)?);
+                }
+                "tick" => {
+                    let Some(seeded) = &mut seeded else {
+                        continue;
+                    };
+                    let noise = seeded.rng.gen_range(-amplitude..=amplitude);
+                    let value = (index as f64 * 0.1).sin() + noise;
+                    node.send_output(output.clone(), seeded.parameters(index), value.into_arrow())?;
+                    index += 1;
+                    if index >= count {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {index} values");
+    Ok(())
+}
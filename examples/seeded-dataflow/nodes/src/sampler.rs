@@ -0,0 +1,76 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, MetadataParameters, dora_core::config::DataId,
+};
+use eyre::{Context, bail};
+use rand::Rng;
+use seeded_dataflow_nodes::{SeededRng, env_or, index, root_seed};
+
+/// Forwards each `value` on `kept` with a probability of `KEEP_PROBABILITY`, a random
+/// subsample of the stream.
+///
+/// dora doesn't order the inputs that come from different nodes, so values can arrive before
+/// the root seed. They are held back until it arrives, so that the sampler draws for the
+/// values in their order in every run.
+fn main() -> eyre::Result<()> {
+    let keep_probability: f64 = env_or("KEEP_PROBABILITY", 0.5)?;
+    let output = DataId::from("kept".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    let mut seeded: Option<SeededRng> = None;
+    let mut pending: Vec<(MetadataParameters, f64)> = Vec::new();
+    let mut sample = |seeded: &mut SeededRng, parameters: MetadataParameters, value: f64| {
+        if root_seed(&parameters)? != seeded.root_seed {
+            bail!("received a value of another root seed");
+        }
+        if seeded.rng.gen_bool(keep_probability) {
+            node.send_output(
+                output.clone(),
+                seeded.parameters(index(&parameters)?),
+                value.into_arrow(),
+            )?;
+        }
+        eyre::Ok(())
+    };
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "seed" => {
+                    let mut rng = SeededRng::from_parameters(&metadata.parameters, &node_id)?;
+                    if !pending.is_empty() {
+                        println!(
+                            "sampling {} values that arrived before the seed",
+                            pending.len()
+                        );
+                    }
+                    for (parameters, value) in pending.drain(..) {
+                        sample(&mut rng, parameters, value)?;
+                    }
+                    seeded = Some(rng);
+                }
+                "value" => {
+                    let value = f64::try_from(&data).context("unexpected data type")?;
+                    match &mut seeded {
+                        Some(seeded) => sample(seeded, metadata.parameters, value)?,
+                        None => pending.push((metadata.parameters, value)),
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "value" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if seeded.is_none() {
+        bail!("the root seed never arrived");
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::NullArray, dora_core::config::DataId};
+use seeded_dataflow_nodes::{env_or, seed_parameters};
+
+/// Sends the root seed of the run, `ROOT_SEED`, once on `seed`, in the metadata of the
+/// message.
+fn main() -> eyre::Result<()> {
+    let root_seed: u64 = env_or("ROOT_SEED", 42)?;
+    let output = DataId::from("seed".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                // the first tick comes once all nodes are running, so none of them misses it
+                "tick" => {
+                    node.send_output(
+                        output.clone(),
+                        seed_parameters(root_seed),
+                        NullArray::new(0),
+                    )?;
+                    println!("sent root seed {root_seed}");
+                    break;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
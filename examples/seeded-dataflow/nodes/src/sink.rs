@@ -0,0 +1,46 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::{Context, bail};
+use seeded_dataflow_nodes::{Sample, SinkReport, env_or, index, root_seed, write_json};
+use std::path::PathBuf;
+
+/// Collects the `kept` values with their index, and writes them with the root seed that they
+/// were drawn with to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", PathBuf::from("out/sink.json"))?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut seed = None;
+    let mut samples = Vec::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "kept" => {
+                    let root_seed = root_seed(&metadata.parameters)?;
+                    if *seed.get_or_insert(root_seed) != root_seed {
+                        bail!("received values of different root seeds");
+                    }
+                    samples.push(Sample {
+                        index: index(&metadata.parameters)?,
+                        value: f64::try_from(&data).context("unexpected data type")?,
+                    });
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "kept" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let Some(root_seed) = seed else {
+        bail!("no values arrived");
+    };
+    println!("received {} values of root seed {root_seed}", samples.len());
+    write_json(&report_file, &SinkReport { root_seed, samples })
+}
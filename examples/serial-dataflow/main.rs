@@ -0,0 +1,376 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Must match `READINGS`, `SAMPLE_PERIOD_MS`, `FAN_ON_ABOVE`, and `FAN_OFF_BELOW` of
+/// `thermostat` in `dataflow.yml`.
+const READINGS: u64 = 400;
+const SAMPLE_PERIOD_MS: u64 = 20;
+const FAN_ON_ABOVE: f32 = 26.0;
+const FAN_OFF_BELOW: f32 = 24.0;
+
+/// How the mock device behaves: it samples every 100 ms until told otherwise, heats up while
+/// its fan is off, and cools down while it's on.
+const DEFAULT_PERIOD: Duration = Duration::from_millis(100);
+const START_TEMPERATURE_C: f32 = 22.0;
+const HEATING_PER_READING: f32 = 0.05;
+const COOLING_PER_READING: f32 = 0.08;
+/// Every 50th line is garbled on the wire, like on a noisy cable.
+const CORRUPT_EVERY: u64 = 50;
+
+/// Subset of `BridgeReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct BridgeReport {
+    readings: u64,
+    replies: u64,
+    invalid_lines: u64,
+    commands: u64,
+}
+
+/// Subset of `ThermostatReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ThermostatReport {
+    readings: u64,
+    first_seq: Option<u64>,
+    last_seq: Option<u64>,
+    missing: u64,
+    fan_switches: u64,
+    rate_acknowledged: bool,
+    naks: Vec<String>,
+    min_temperature_c: Option<f32>,
+    max_temperature_c: Option<f32>,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("serial-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let device = MockDevice::start()?;
+    println!("mock device on {}", device.path.display());
+    // `dataflow.yml` leaves `SERIAL_PORT` to the environment
+    let dora = dora.env("SERIAL_PORT", &device.path);
+    dora.run_dataflow(dataflow).await?;
+    let device = device.stop();
+
+    let thermostat: ThermostatReport = read_json("out/thermostat.json")?;
+    println!(
+        "thermostat: {} readings, {} missing, {} fan switches, {:?}..={:?} °C",
+        thermostat.readings,
+        thermostat.missing,
+        thermostat.fan_switches,
+        thermostat.min_temperature_c,
+        thermostat.max_temperature_c
+    );
+    if thermostat.readings != READINGS {
+        bail!("expected the thermostat to stop after {READINGS} readings");
+    }
+    if !thermostat.rate_acknowledged
+        || !device
+            .commands
+            .contains(&format!("RATE,{SAMPLE_PERIOD_MS}"))
+    {
+        bail!("expected the device to switch to a sample period of {SAMPLE_PERIOD_MS} ms");
+    }
+    if !thermostat.naks.is_empty() {
+        bail!("the device rejected commands: {:?}", thermostat.naks);
+    }
+    // the thermostat may stop before the reply to its last command arrives
+    let fan_commands = device
+        .commands
+        .iter()
+        .filter(|command| command.starts_with("FAN,"))
+        .count() as u64;
+    if thermostat.fan_switches < 4
+        || !(thermostat.fan_switches..=thermostat.fan_switches + 1).contains(&fan_commands)
+    {
+        bail!(
+            "expected the thermostat to switch the fan a few times, it switched {} times, and \
+             the device received {fan_commands} fan commands",
+            thermostat.fan_switches
+        );
+    }
+    let (Some(min), Some(max)) = (thermostat.min_temperature_c, thermostat.max_temperature_c)
+    else {
+        bail!("the thermostat never took control of the temperature");
+    };
+    if min < FAN_OFF_BELOW - 1.0 || max > FAN_ON_ABOVE + 1.0 {
+        bail!("expected the temperature to stay around {FAN_OFF_BELOW}..={FAN_ON_ABOVE} °C");
+    }
+    // the garbled lines are the only readings that may go missing
+    let (Some(first), Some(last)) = (thermostat.first_seq, thermostat.last_seq) else {
+        bail!("the thermostat received no readings");
+    };
+    let garbled = device
+        .corrupted
+        .iter()
+        .filter(|seq| (first..=last).contains(seq))
+        .count() as u64;
+    if thermostat.missing != garbled {
+        bail!(
+            "{} readings went missing, but the device garbled {garbled} of them",
+            thermostat.missing
+        );
+    }
+
+    let bridge: BridgeReport = read_json("out/bridge.json")?;
+    println!(
+        "bridge: {} readings, {} replies, {} invalid lines, {} commands",
+        bridge.readings, bridge.replies, bridge.invalid_lines, bridge.commands
+    );
+    if bridge.invalid_lines < garbled {
+        bail!("expected the bridge to skip the {garbled} garbled lines");
+    }
+    if bridge.commands != device.commands.len() as u64
+        || !(bridge.replies..=bridge.replies + 1).contains(&bridge.commands)
+    {
+        bail!(
+            "the bridge wrote {} commands and forwarded {} replies, the device received {}",
+            bridge.commands,
+            bridge.replies,
+            device.commands.len()
+        );
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// What the mock device did, shared by its threads.
+#[derive(Debug, Clone)]
+struct DeviceLog {
+    seq: u64,
+    temperature_c: f32,
+    fan: bool,
+    period: Duration,
+    /// The commands that it acknowledged.
+    commands: Vec<String>,
+    /// The readings that it garbled.
+    corrupted: Vec<u64>,
+    stopped: bool,
+}
+
+/// Simulates the sensor on the master side of a pty, whose slave the bridge opens like a
+/// serial port: it sends a reading every sample period, and replies to the commands.
+struct MockDevice {
+    path: PathBuf,
+    log: Arc<Mutex<DeviceLog>>,
+    /// Kept open, so that the pty stays up while the bridge opens and closes its side.
+    _slave: File,
+}
+
+impl MockDevice {
+    fn start() -> eyre::Result<Self> {
+        let (master, slave, path) = open_pty()?;
+        let log = Arc::new(Mutex::new(DeviceLog {
+            seq: 0,
+            temperature_c: START_TEMPERATURE_C,
+            fan: false,
+            period: DEFAULT_PERIOD,
+            commands: Vec::new(),
+            corrupted: Vec::new(),
+            stopped: false,
+        }));
+        let reader = master.try_clone()?;
+        // both threads write whole lines, which must not interleave
+        let writer = Arc::new(Mutex::new(master));
+
+        // the threads block on the pty, they end with the runner
+        std::thread::spawn({
+            let log = log.clone();
+            let writer = writer.clone();
+            move || {
+                loop {
+                    let period = {
+                        let log = log.lock().unwrap();
+                        if log.stopped {
+                            break;
+                        }
+                        log.period
+                    };
+                    std::thread::sleep(period);
+                    let line = next_reading(&mut log.lock().unwrap());
+                    if writer.lock().unwrap().write_all(&line).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        std::thread::spawn({
+            let log = log.clone();
+            move || {
+                for line in BufReader::new(reader).split(b'\n') {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    let line = String::from_utf8_lossy(&line);
+                    let reply = handle_command(&mut log.lock().unwrap(), line.trim_end());
+                    if writer
+                        .lock()
+                        .unwrap()
+                        .write_all(frame(&reply).as_bytes())
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            path,
+            log,
+            _slave: slave,
+        })
+    }
+
+    fn stop(self) -> DeviceLog {
+        let mut log = self.log.lock().unwrap();
+        log.stopped = true;
+        log.clone()
+    }
+}
+
+/// The next reading, as a line, garbled every `CORRUPT_EVERY` readings.
+fn next_reading(log: &mut DeviceLog) -> Vec<u8> {
+    log.temperature_c += if log.fan {
+        -COOLING_PER_READING
+    } else {
+        HEATING_PER_READING
+    };
+    let humidity_pct = 45.0 + 5.0 * (log.seq as f32 / 40.0).sin();
+    let payload = format!(
+        "R,{},{:.2},{humidity_pct:.1},{}",
+        log.seq,
+        log.temperature_c,
+        u8::from(log.fan)
+    );
+    let mut line = frame(&payload).into_bytes();
+    if log.seq % CORRUPT_EVERY == CORRUPT_EVERY - 1 {
+        // a flipped byte in the seq, which isn't even UTF-8 anymore
+        line[3] ^= 0x80;
+        log.corrupted.push(log.seq);
+    }
+    log.seq += 1;
+    line
+}
+
+/// Applies the command in the frame `line`, and returns the payload of the reply.
+fn handle_command(log: &mut DeviceLog, line: &str) -> String {
+    let Some((payload, checksum)) = line
+        .strip_prefix('$')
+        .and_then(|frame| frame.rsplit_once('*'))
+    else {
+        return format!("NAK,{line},not a frame");
+    };
+    if u8::from_str_radix(checksum, 16).ok() != Some(xor(payload)) {
+        return format!("NAK,{payload},checksum");
+    }
+    let applied = match payload.split_once(',') {
+        Some(("FAN", "0")) => {
+            log.fan = false;
+            true
+        }
+        Some(("FAN", "1")) => {
+            log.fan = true;
+            true
+        }
+        Some(("RATE", ms)) => match ms.parse::<u64>() {
+            Ok(ms @ 5..=1000) => {
+                log.period = Duration::from_millis(ms);
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    };
+    if applied {
+        log.commands.push(payload.to_owned());
+        format!("ACK,{payload}")
+    } else {
+        format!("NAK,{payload},invalid")
+    }
+}
+
+/// Must match `encode_frame` in `nodes/src/lib.rs`.
+fn frame(payload: &str) -> String {
+    format!("${payload}*{:02X}\r\n", xor(payload))
+}
+
+fn xor(payload: &str) -> u8 {
+    payload.bytes().fold(0, |checksum, byte| checksum ^ byte)
+}
+
+/// Opens a pty in raw mode, and returns its master, its slave, and the path of the slave.
+#[cfg(unix)]
+fn open_pty() -> eyre::Result<(File, File, PathBuf)> {
+    use std::os::{
+        fd::{AsRawFd, FromRawFd},
+        unix::ffi::OsStrExt,
+    };
+
+    let (mut master, mut slave) = (0, 0);
+    // SAFETY: `openpty` only writes the two file descriptors, the other arguments may be null
+    let result = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error()).context("failed to open a pty");
+    }
+    // SAFETY: `openpty` opened both, and nothing else owns them
+    let (master, slave) = unsafe { (File::from_raw_fd(master), File::from_raw_fd(slave)) };
+
+    // raw mode, so that the pty neither echoes the commands nor translates line endings
+    let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+    // SAFETY: `tcgetattr` fills `termios` if it returns 0
+    if unsafe { libc::tcgetattr(slave.as_raw_fd(), termios.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("failed to get the pty attributes");
+    }
+    let mut termios = unsafe { termios.assume_init() };
+    unsafe { libc::cfmakeraw(&mut termios) };
+    if unsafe { libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &termios) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("failed to set the pty attributes");
+    }
+
+    // SAFETY: `ttyname` returns null or a nul-terminated string, which is copied before the
+    // next call
+    let name = unsafe { libc::ttyname(slave.as_raw_fd()) };
+    if name.is_null() {
+        return Err(std::io::Error::last_os_error()).context("the pty has no name");
+    }
+    let path = std::ffi::OsStr::from_bytes(unsafe { std::ffi::CStr::from_ptr(name) }.to_bytes());
+    Ok((master, slave, PathBuf::from(path)))
+}
+
+#[cfg(not(unix))]
+fn open_pty() -> eyre::Result<(File, File, PathBuf)> {
+    bail!(
+        "the mock device needs a pty, connect a device and run `dataflow.yml` with `SERIAL_PORT`"
+    );
+}
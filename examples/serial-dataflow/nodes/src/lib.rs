@@ -0,0 +1,187 @@
+//! The line protocol of the sensor, and the Arrow encoding of its readings.
+//!
+//! Every line is a frame `$<payload>*<checksum>\r\n`, in the style of NMEA 0183: the checksum
+//! is the XOR of the bytes of the payload, as two hex digits. The payload is comma-separated:
+//!
+//! - `R,<seq>,<temperature_c>,<humidity_pct>,<fan>`: a reading, sent every sample period, with
+//!   the fan as `0` or `1`.
+//! - `ACK,<command>` and `NAK,<command>,<reason>`: the reply to a command.
+//!
+//! The sensor accepts the commands `FAN,<0|1>` and `RATE,<sample period in ms>`.
+
+use dora_node_api::arrow::{
+    array::{Array, ArrayRef, AsArray, BooleanArray, Float32Array, StructArray, UInt64Array},
+    datatypes::{DataType, Field, Float32Type, UInt64Type},
+};
+use eyre::{OptionExt, eyre};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub use example_node_utils::{env_or, write_json};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    pub seq: u64,
+    pub temperature_c: f32,
+    pub humidity_pct: f32,
+    pub fan: bool,
+}
+
+/// A line of the sensor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Reading(Reading),
+    Ack { command: String },
+    Nak { command: String, reason: String },
+}
+
+impl Message {
+    /// Parses a payload, without its frame.
+    pub fn parse(payload: &str) -> Result<Self, String> {
+        let fields: Vec<_> = payload.split(',').collect();
+        match fields.as_slice() {
+            ["R", seq, temperature, humidity, fan] => Ok(Message::Reading(Reading {
+                seq: seq.parse().map_err(|err| format!("invalid seq: {err}"))?,
+                temperature_c: temperature
+                    .parse()
+                    .map_err(|err| format!("invalid temperature: {err}"))?,
+                humidity_pct: humidity
+                    .parse()
+                    .map_err(|err| format!("invalid humidity: {err}"))?,
+                fan: match *fan {
+                    "0" => false,
+                    "1" => true,
+                    other => return Err(format!("invalid fan state `{other}`")),
+                },
+            })),
+            ["ACK", command @ ..] if !command.is_empty() => Ok(Message::Ack {
+                command: command.join(","),
+            }),
+            ["NAK", command @ .., reason] if !command.is_empty() => Ok(Message::Nak {
+                command: command.join(","),
+                reason: reason.to_string(),
+            }),
+            _ => Err(format!("unknown message `{payload}`")),
+        }
+    }
+}
+
+/// Frames `payload` as a line: `$<payload>*<checksum>\r\n`.
+pub fn encode_frame(payload: &str) -> String {
+    format!("${payload}*{:02X}\r\n", checksum(payload))
+}
+
+/// The payload of the line `line`, without its line ending, if its checksum matches.
+pub fn decode_frame(line: &str) -> Result<&str, String> {
+    let (payload, expected) = line
+        .strip_prefix('$')
+        .and_then(|frame| frame.rsplit_once('*'))
+        .ok_or("not a `$<payload>*<checksum>` frame")?;
+    let expected =
+        u8::from_str_radix(expected, 16).map_err(|_| format!("invalid checksum `{expected}`"))?;
+    let actual = checksum(payload);
+    if actual != expected {
+        return Err(format!(
+            "checksum is {actual:02X}, the frame says {expected:02X}"
+        ));
+    }
+    Ok(payload)
+}
+
+fn checksum(payload: &str) -> u8 {
+    payload.bytes().fold(0, |checksum, byte| checksum ^ byte)
+}
+
+/// Encodes `readings` as a struct array with one child array per field.
+pub fn readings_to_arrow(readings: &[Reading]) -> StructArray {
+    let column = |name: &str, data_type: DataType, array: ArrayRef| {
+        (Arc::new(Field::new(name, data_type, false)), array)
+    };
+    StructArray::from(vec![
+        column(
+            "seq",
+            DataType::UInt64,
+            Arc::new(UInt64Array::from_iter_values(
+                readings.iter().map(|r| r.seq),
+            )),
+        ),
+        column(
+            "temperature_c",
+            DataType::Float32,
+            Arc::new(Float32Array::from_iter_values(
+                readings.iter().map(|r| r.temperature_c),
+            )),
+        ),
+        column(
+            "humidity_pct",
+            DataType::Float32,
+            Arc::new(Float32Array::from_iter_values(
+                readings.iter().map(|r| r.humidity_pct),
+            )),
+        ),
+        column(
+            "fan",
+            DataType::Boolean,
+            Arc::new(BooleanArray::from(
+                readings.iter().map(|r| r.fan).collect::<Vec<_>>(),
+            )),
+        ),
+    ])
+}
+
+/// Decodes the struct array of `readings_to_arrow`.
+pub fn readings_from_arrow(data: &dyn Array) -> eyre::Result<Vec<Reading>> {
+    let rows = data.as_struct_opt().ok_or_eyre("expected a struct array")?;
+    let column = |name: &str| {
+        rows.column_by_name(name)
+            .ok_or_else(|| eyre!("readings have no `{name}` column"))
+    };
+    let seq = column("seq")?
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or_eyre("`seq` is not a UInt64 array")?;
+    let temperature = column("temperature_c")?
+        .as_primitive_opt::<Float32Type>()
+        .ok_or_eyre("`temperature_c` is not a Float32 array")?;
+    let humidity = column("humidity_pct")?
+        .as_primitive_opt::<Float32Type>()
+        .ok_or_eyre("`humidity_pct` is not a Float32 array")?;
+    let fan = column("fan")?
+        .as_boolean_opt()
+        .ok_or_eyre("`fan` is not a Boolean array")?;
+    Ok((0..rows.len())
+        .map(|i| Reading {
+            seq: seq.value(i),
+            temperature_c: temperature.value(i),
+            humidity_pct: humidity.value(i),
+            fan: fan.value(i),
+        })
+        .collect())
+}
+
+/// Written by `serial-bridge` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BridgeReport {
+    pub lines: u64,
+    pub readings: u64,
+    pub replies: u64,
+    /// Lines without a valid frame or payload, e.g. garbled on the wire.
+    pub invalid_lines: u64,
+    pub commands: u64,
+}
+
+/// Written by `thermostat` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ThermostatReport {
+    pub readings: u64,
+    pub first_seq: Option<u64>,
+    pub last_seq: Option<u64>,
+    /// Readings that never arrived, by the gaps in their `seq`.
+    pub missing: u64,
+    /// Fan commands that the sensor acknowledged.
+    pub fan_switches: u64,
+    pub rate_acknowledged: bool,
+    pub naks: Vec<String>,
+    /// The temperature range once the fan switched for the first time.
+    pub min_temperature_c: Option<f32>,
+    pub max_temperature_c: Option<f32>,
+}
@@ -0,0 +1,116 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, OptionExt, bail};
+use futures::StreamExt;
+use serial_dataflow_nodes::{
+    BridgeReport, Message, decode_frame, encode_frame, env_or, readings_to_arrow, write_json,
+};
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio_serial::SerialPortBuilderExt;
+
+/// Bridges the sensor on the serial port `SERIAL_PORT` into the dataflow.
+///
+/// Reads the lines of the sensor, and sends each reading on `readings`, as a struct array with
+/// one row, and each reply to a command on `replies`, as a string array with its payload, e.g.
+/// `ACK,FAN,1`. Lines with an invalid frame, e.g. garbled on the wire, are skipped. Writes
+/// every string of the `commands` input to the sensor, framed with its checksum.
+///
+/// Stops when `commands` closes. Writes a `BridgeReport` to `REPORT_FILE`.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let path: String = env_or("SERIAL_PORT", String::new())?;
+    if path.is_empty() {
+        bail!("set `SERIAL_PORT` to the serial port of the sensor, e.g. /dev/ttyUSB0");
+    }
+    let baud_rate: u32 = env_or("BAUD_RATE", 115_200)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/bridge.json".to_owned())?.into();
+    let readings_output = DataId::from("readings".to_owned());
+    let replies_output = DataId::from("replies".to_owned());
+
+    let port = tokio_serial::new(&path, baud_rate)
+        .open_native_async()
+        .with_context(|| format!("failed to open {path}"))?;
+    println!("opened {path} at {baud_rate} baud");
+    let (reader, mut writer) = tokio::io::split(port);
+    // split at newlines rather than reading `lines`, so that bytes that aren't UTF-8 only spoil
+    // their line
+    let lines = futures::stream::unfold(BufReader::new(reader).split(b'\n'), |mut lines| async {
+        let line = lines.next_segment().await.transpose()?;
+        Some((line, lines))
+    });
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let mut events = events.merge_external(Box::pin(lines));
+    let mut report = BridgeReport::default();
+    while let Some(event) = events.next().await {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, data, .. } => match id.as_str() {
+                    "commands" => {
+                        let commands = data
+                            .as_string_opt::<i32>()
+                            .ok_or_eyre("expected a string array")?;
+                        for command in commands.iter().flatten() {
+                            writer
+                                .write_all(encode_frame(command).as_bytes())
+                                .await
+                                .with_context(|| format!("failed to write to {path}"))?;
+                            report.commands += 1;
+                        }
+                        writer.flush().await?;
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    if id.as_str() == "commands" {
+                        break;
+                    }
+                }
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(line) => {
+                let line = line.with_context(|| format!("failed to read from {path}"))?;
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim_end_matches('\r');
+                report.lines += 1;
+                let message =
+                    decode_frame(line).and_then(|payload| Ok((payload, Message::parse(payload)?)));
+                match message {
+                    Ok((_, Message::Reading(reading))) => {
+                        node.send_output(
+                            readings_output.clone(),
+                            MetadataParameters::default(),
+                            readings_to_arrow(&[reading]),
+                        )?;
+                        report.readings += 1;
+                    }
+                    Ok((payload, Message::Ack { .. } | Message::Nak { .. })) => {
+                        node.send_output(
+                            replies_output.clone(),
+                            MetadataParameters::default(),
+                            StringArray::from(vec![payload]),
+                        )?;
+                        report.replies += 1;
+                    }
+                    Err(err) => {
+                        eprintln!("Ignoring invalid line `{}`: {err}", line.escape_debug());
+                        report.invalid_lines += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    println!(
+        "forwarded {} readings and {} replies, wrote {} commands, skipped {} invalid lines",
+        report.readings, report.replies, report.commands, report.invalid_lines
+    );
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,105 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use serial_dataflow_nodes::{ThermostatReport, env_or, readings_from_arrow, write_json};
+use std::path::PathBuf;
+
+/// Keeps the temperature of the sensor between `FAN_OFF_BELOW` and `FAN_ON_ABOVE`, by sending
+/// `FAN,1` and `FAN,0` on `commands` when the fan of the sensor is in the wrong state.
+///
+/// On the first reading, sets the sample period of the sensor to `SAMPLE_PERIOD_MS`. Waits for
+/// the reply to a command on `replies` before it sends the next. Stops after `READINGS`
+/// readings, or never if it's 0. Writes a `ThermostatReport` to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let fan_on_above: f32 = env_or("FAN_ON_ABOVE", 26.0)?;
+    let fan_off_below: f32 = env_or("FAN_OFF_BELOW", 24.0)?;
+    let sample_period_ms: u64 = env_or("SAMPLE_PERIOD_MS", 20)?;
+    let max_readings: u64 = env_or("READINGS", 0)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/thermostat.json".to_owned())?.into();
+    let output = DataId::from("commands".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let mut report = ThermostatReport::default();
+    // the command that waits for its reply
+    let mut pending: Option<String> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "readings" => {
+                    for reading in readings_from_arrow(&data.0)? {
+                        if let Some(last_seq) = report.last_seq {
+                            report.missing += reading.seq.saturating_sub(last_seq + 1);
+                        }
+                        report.first_seq.get_or_insert(reading.seq);
+                        report.last_seq = Some(reading.seq);
+                        report.readings += 1;
+                        if report.fan_switches > 0 {
+                            let t = reading.temperature_c;
+                            report.min_temperature_c =
+                                Some(report.min_temperature_c.map_or(t, |min| min.min(t)));
+                            report.max_temperature_c =
+                                Some(report.max_temperature_c.map_or(t, |max| max.max(t)));
+                        }
+
+                        let command = if report.readings == 1 {
+                            Some(format!("RATE,{sample_period_ms}"))
+                        } else if reading.temperature_c > fan_on_above && !reading.fan {
+                            Some("FAN,1".to_owned())
+                        } else if reading.temperature_c < fan_off_below && reading.fan {
+                            Some("FAN,0".to_owned())
+                        } else {
+                            None
+                        };
+                        if let Some(command) = command.filter(|_| pending.is_none()) {
+                            println!("{:.2} °C, sending `{command}`", reading.temperature_c);
+                            node.send_output(
+                                output.clone(),
+                                MetadataParameters::default(),
+                                StringArray::from(vec![command.as_str()]),
+                            )?;
+                            pending = Some(command);
+                        }
+                    }
+                    if max_readings > 0 && report.readings >= max_readings {
+                        break;
+                    }
+                }
+                "replies" => {
+                    let replies = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a string array")?;
+                    for reply in replies.iter().flatten() {
+                        let (status, command) = reply.split_once(',').unwrap_or((reply, ""));
+                        if pending.as_deref().is_some_and(|p| command.starts_with(p)) {
+                            pending = None;
+                        }
+                        match status {
+                            "ACK" if command.starts_with("FAN,") => report.fan_switches += 1,
+                            "ACK" if command.starts_with("RATE,") => {
+                                report.rate_acknowledged = true
+                            }
+                            "ACK" => {}
+                            _ => {
+                                eprintln!("the sensor rejected a command: {reply}");
+                                report.naks.push(reply.to_owned());
+                            }
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "{} readings, {} missing, switched the fan {} times",
+        report.readings, report.missing, report.fan_switches
+    );
+    write_json(&report_file, &report)
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Sent by `sensor` on `reading`, one JSON row per reading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reading {
+    /// Start time of the sensor in ms, since `seq` starts at 0 again on every start.
+    pub run_id: i64,
+    pub seq: i64,
+    pub sensor: String,
+    pub timestamp_ms: i64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Calls go through, and failures are counted.
+    Closed,
+    /// Calls are rejected without trying, until the cooldown is over.
+    Open,
+    /// A single trial call decides whether to close or to open again.
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transition {
+    /// Since the breaker was created.
+    pub at_ms: u64,
+    pub state: BreakerState,
+}
+
+/// Protects a node from waiting on a service that is down, and the service from being flooded
+/// with calls while it recovers.
+///
+/// Opens after `threshold` failures in a row, rejects calls for `cooldown`, and then lets one
+/// trial call through. Its success closes the breaker, its failure opens it again.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    state: BreakerState,
+    failures: u32,
+    threshold: u32,
+    cooldown: Duration,
+    opened_at: Instant,
+    created: Instant,
+    transitions: Vec<Transition>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            state: BreakerState::Closed,
+            failures: 0,
+            threshold: threshold.max(1),
+            cooldown,
+            opened_at: Instant::now(),
+            created: Instant::now(),
+            transitions: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.state
+    }
+
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    /// Whether a call may be made now. Once the cooldown is over, this allows the trial call
+    /// and rejects the others until its outcome is recorded.
+    pub fn allow(&mut self) -> bool {
+        match self.state {
+            BreakerState::Closed => true,
+            BreakerState::Open if self.opened_at.elapsed() >= self.cooldown => {
+                self.transition(BreakerState::HalfOpen);
+                true
+            }
+            BreakerState::Open | BreakerState::HalfOpen => false,
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        match self.state {
+            BreakerState::Closed => self.failures = 0,
+            BreakerState::HalfOpen => {
+                self.failures = 0;
+                self.transition(BreakerState::Closed);
+            }
+            // a late success of a call that was made before the breaker opened, only the
+            // trial call may close it
+            BreakerState::Open => {}
+        }
+    }
+
+    pub fn record_failure(&mut self) {
+        match self.state {
+            BreakerState::Closed => {
+                self.failures += 1;
+                if self.failures >= self.threshold {
+                    self.open();
+                }
+            }
+            BreakerState::HalfOpen => self.open(),
+            // e.g. a late failure of a call that was made before the breaker opened
+            BreakerState::Open => {}
+        }
+    }
+
+    fn open(&mut self) {
+        self.opened_at = Instant::now();
+        self.transition(BreakerState::Open);
+    }
+
+    fn transition(&mut self, state: BreakerState) {
+        println!("circuit breaker {:?} -> {state:?}", self.state);
+        self.state = state;
+        self.transitions.push(Transition {
+            at_ms: self.created.elapsed().as_millis() as u64,
+            state,
+        });
+    }
+}
+
+/// Written by `db-writer` when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WriterReport {
+    pub received: u64,
+    pub written: u64,
+    /// Buffered readings that were dropped because the buffer was full.
+    pub dropped: u64,
+    pub failed_writes: u64,
+    /// Flushes that the open breaker rejected.
+    pub rejected_flushes: u64,
+    pub transitions: Vec<Transition>,
+}
+
+/// Written by `mqtt-publisher` when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PublisherReport {
+    pub received: u64,
+    pub published: u64,
+    pub acked: u64,
+    /// Readings that were not published because the breaker was open.
+    pub shed: u64,
+    pub connection_errors: u64,
+    pub transitions: Vec<Transition>,
+}
@@ -0,0 +1,143 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::AsArray,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, eyre};
+use rumqttc::{Client, MqttOptions, Packet, QoS};
+use service_dependencies_dataflow_nodes::{
+    BreakerState, CircuitBreaker, PublisherReport, env_or, write_json,
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// What the MQTT connection thread reports to the event loop.
+enum Connection {
+    Connected,
+    Acked,
+    Error(String),
+}
+
+/// Publishes each `reading` to `TOPIC` on the MQTT broker at `MQTT_ADDR`, with QoS 1.
+///
+/// A connection error or a full publish queue counts as a failure of the circuit breaker
+/// (`FAILURE_THRESHOLD`, `COOLDOWN_MS`), an acknowledged publish as a success. While the
+/// breaker is open, the readings are not published but shed, since telemetry is only useful
+/// while it's fresh. The trial publish of the half-open breaker must be acknowledged within
+/// `ACK_TIMEOUT_MS`. Writes a `PublisherReport` to `REPORT_FILE` when `reading` is closed.
+fn main() -> eyre::Result<()> {
+    let address = env_or("MQTT_ADDR", "127.0.0.1:11883".to_owned())?;
+    let topic = env_or("TOPIC", "plant/readings".to_owned())?;
+    let mut breaker = CircuitBreaker::new(
+        env_or("FAILURE_THRESHOLD", 3)?,
+        Duration::from_millis(env_or("COOLDOWN_MS", 2000)?),
+    );
+    let ack_timeout = Duration::from_millis(env_or("ACK_TIMEOUT_MS", 1000)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/mqtt-publisher.json".to_owned())?.into();
+
+    let (host, port) = address
+        .rsplit_once(':')
+        .ok_or_else(|| eyre!("MQTT_ADDR must be `host:port`"))?;
+    let mut options = MqttOptions::new("dora-mqtt-publisher", host, port.parse()?);
+    options.set_keep_alive(Duration::from_secs(5));
+    let (client, mut connection) = Client::new(options, 100);
+
+    // the connection only makes progress while it is iterated, which also reconnects after
+    // an error
+    let (status_tx, status_rx) = futures::channel::mpsc::unbounded();
+    std::thread::spawn(move || {
+        for notification in connection.iter() {
+            let status = match notification {
+                Ok(rumqttc::Event::Incoming(Packet::ConnAck(_))) => Connection::Connected,
+                Ok(rumqttc::Event::Incoming(Packet::PubAck(_))) => Connection::Acked,
+                Ok(_) => continue,
+                Err(err) => {
+                    let _ = status_tx.unbounded_send(Connection::Error(err.to_string()));
+                    std::thread::sleep(Duration::from_millis(500));
+                    continue;
+                }
+            };
+            if status_tx.unbounded_send(status).is_err() {
+                break;
+            }
+        }
+    });
+
+    let (_node, events) = DoraNode::init_from_env()?;
+    let merged = events.merge_external(Box::pin(status_rx));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut report = PublisherReport::default();
+    let mut trial: Option<Instant> = None;
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, data, .. } => match id.as_str() {
+                    "reading" => {
+                        let rows = data
+                            .as_string_opt::<i32>()
+                            .ok_or_else(|| eyre!("expected JSON strings"))?;
+                        for row in rows.iter().flatten() {
+                            report.received += 1;
+                            if !breaker.allow() {
+                                report.shed += 1;
+                                continue;
+                            }
+                            match client.try_publish(&topic, QoS::AtLeastOnce, false, row) {
+                                Ok(()) => {
+                                    report.published += 1;
+                                    if breaker.state() == BreakerState::HalfOpen {
+                                        trial = Some(Instant::now());
+                                    }
+                                }
+                                Err(err) => {
+                                    eprintln!("failed to publish: {err}");
+                                    breaker.record_failure();
+                                }
+                            }
+                        }
+                    }
+                    "tick" => {
+                        if trial.is_some_and(|sent| sent.elapsed() > ack_timeout) {
+                            eprintln!("the trial publish was not acknowledged in time");
+                            trial = None;
+                            breaker.record_failure();
+                        }
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    if id.as_str() == "reading" {
+                        break;
+                    }
+                }
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(Connection::Connected) => {
+                println!("connected to the MQTT broker at {address}");
+            }
+            MergedEvent::External(Connection::Acked) => {
+                report.acked += 1;
+                trial = None;
+                breaker.record_success();
+            }
+            MergedEvent::External(Connection::Error(err)) => {
+                eprintln!("MQTT connection error: {err}");
+                report.connection_errors += 1;
+                trial = None;
+                breaker.record_failure();
+            }
+        }
+    }
+
+    report.transitions = breaker.transitions().to_vec();
+    println!(
+        "published {} of {} readings, {} acknowledged, {} shed",
+        report.published, report.received, report.acked, report.shed
+    );
+    write_json(&report_file, &report).context("failed to write report")
+}
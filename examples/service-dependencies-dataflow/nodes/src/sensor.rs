@@ -0,0 +1,47 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::StringArray, dora_core::config::DataId};
+use service_dependencies_dataflow_nodes::{Reading, env_or};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Sends a `Reading` of a simulated temperature sensor on `reading` per `tick`, and stops
+/// after `COUNT` readings.
+fn main() -> eyre::Result<()> {
+    let count: i64 = env_or("COUNT", 600)?;
+    let sensor = env_or("SENSOR", "boiler-1".to_owned())?;
+    let output = DataId::from("reading".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let run_id = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64;
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let reading = Reading {
+                        run_id,
+                        seq,
+                        sensor: sensor.clone(),
+                        timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis()
+                            as i64,
+                        value: 70.0 + 5.0 * (seq as f64 / 50.0).sin(),
+                    };
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        StringArray::from(vec![serde_json::to_string(&reading)?]),
+                    )?;
+                    seq += 1;
+                    if seq >= count {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} readings");
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow,
+    arrow::{array::AsArray, datatypes::Float64Type},
+    dora_core::config::DataId,
+};
+use eyre::{Context, bail, eyre};
+use signed_messages_dataflow_nodes::{Verifier, keys_dir};
+use std::collections::BTreeMap;
+
+fn main() -> eyre::Result<()> {
+    let trusted_signers =
+        std::env::var("TRUSTED_SIGNERS").context("TRUSTED_SIGNERS is required")?;
+    let trusted_signers: Vec<_> = trusted_signers.split(',').map(str::trim).collect();
+    let expected_rejections = match std::env::var("EXPECTED_REJECTIONS") {
+        Ok(value) => Some(
+            value
+                .parse::<usize>()
+                .context("invalid EXPECTED_REJECTIONS")?,
+        ),
+        Err(_) => None,
+    };
+    let verifier = Verifier::load(&keys_dir(), &trusted_signers)?;
+    let alerts = DataId::from("alerts".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut accepted: BTreeMap<String, usize> = BTreeMap::new();
+    let mut rejected: BTreeMap<(String, &'static str), usize> = BTreeMap::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let values = data
+                    .as_primitive_opt::<Float64Type>()
+                    .ok_or_else(|| eyre!("expected float64 data on `{id}`"))?;
+                match verifier.verify(&metadata.parameters, values.values()) {
+                    Ok((signer, _seq)) => {
+                        *accepted.entry(signer).or_default() += 1;
+                    }
+                    Err(rejection) => {
+                        let report = format!("rejected message on `{id}`: {rejection}");
+                        println!("{report}");
+                        node.send_output(alerts.clone(), Default::default(), report.into_arrow())?;
+                        *rejected
+                            .entry((id.to_string(), rejection.kind()))
+                            .or_default() += 1;
+                    }
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("accepted messages:");
+    for (signer, count) in &accepted {
+        println!("  {signer}: {count}");
+    }
+    println!("rejected messages:");
+    for ((input, kind), count) in &rejected {
+        println!("  {input} ({kind}): {count}");
+    }
+
+    let total_rejected: usize = rejected.values().sum();
+    if let Some(expected) = expected_rejections
+        && total_rejected != expected
+    {
+        bail!("expected {expected} rejected messages, got {total_rejected}");
+    }
+    if accepted.is_empty() {
+        bail!("no message passed the signature check");
+    }
+    Ok(())
+}
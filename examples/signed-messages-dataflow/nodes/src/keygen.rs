@@ -0,0 +1,25 @@
+//! Creates an ed25519 key pair per node: `<dir>/<node>.key` holds the private key,
+//! `<dir>/<node>.pub` the public key that is handed to verifying nodes.
+
+use ed25519_dalek::SigningKey;
+use eyre::{Context, bail};
+use std::path::PathBuf;
+
+fn main() -> eyre::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let Some(dir) = args.next().map(PathBuf::from) else {
+        bail!("usage: keygen <dir> <node>...");
+    };
+    std::fs::create_dir_all(&dir).context("failed to create key directory")?;
+
+    for name in args {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        std::fs::write(dir.join(format!("{name}.key")), hex::encode(key.to_bytes()))?;
+        std::fs::write(
+            dir.join(format!("{name}.pub")),
+            hex::encode(key.verifying_key().as_bytes()),
+        )?;
+        println!("created key pair for `{name}` in {}", dir.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier as _, VerifyingKey};
+use eyre::{Context, eyre};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Metadata key of the node that claims to have produced the message.
+pub const SIGNER_KEY: &str = "signer";
+/// Metadata key of the hex encoded public key of the signer.
+pub const PUBLIC_KEY_KEY: &str = "public_key";
+/// Metadata key of the hex encoded ed25519 signature.
+pub const SIGNATURE_KEY: &str = "signature";
+/// Metadata key of the sequence number of the message, part of the signed bytes.
+pub const SEQ_KEY: &str = "seq";
+
+/// Directory that holds the `<node>.key` and `<node>.pub` files created by `keygen`.
+pub fn keys_dir() -> PathBuf {
+    std::env::var("KEYS_DIR")
+        .unwrap_or_else(|_| "keys".to_owned())
+        .into()
+}
+
+pub fn load_signing_key(dir: &Path, name: &str) -> eyre::Result<SigningKey> {
+    let path = dir.join(format!("{name}.key"));
+    let bytes = read_hex_file(&path)?;
+    let bytes = bytes
+        .try_into()
+        .map_err(|_| eyre!("{} does not contain a 32 byte key", path.display()))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+pub fn load_verifying_key(dir: &Path, name: &str) -> eyre::Result<VerifyingKey> {
+    let path = dir.join(format!("{name}.pub"));
+    let bytes = read_hex_file(&path)?;
+    let bytes = bytes
+        .try_into()
+        .map_err(|_| eyre!("{} does not contain a 32 byte key", path.display()))?;
+    VerifyingKey::from_bytes(&bytes).with_context(|| format!("invalid key in {}", path.display()))
+}
+
+fn read_hex_file(path: &Path) -> eyre::Result<Vec<u8>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    hex::decode(content.trim()).with_context(|| format!("invalid hex in {}", path.display()))
+}
+
+/// The bytes covered by the signature: signer, sequence number, and payload.
+///
+/// Including the signer and the sequence number prevents a valid signature from being reused
+/// for a message that claims a different origin or position in the stream.
+pub fn signed_bytes(signer: &str, seq: u64, values: &[f64]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(signer.len() + 1 + 8 + values.len() * 8);
+    bytes.extend_from_slice(signer.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(&seq.to_le_bytes());
+    for value in values {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+/// Signs `values` in the name of `signer` and returns the metadata parameters to send along.
+///
+/// `embedded_key` is the public key that is put into the metadata. An honest producer passes
+/// its own key, the example's forger uses it to lie about its identity.
+pub fn sign(
+    key: &SigningKey,
+    signer: &str,
+    embedded_key: &VerifyingKey,
+    seq: u64,
+    values: &[f64],
+) -> MetadataParameters {
+    let signature = key.sign(&signed_bytes(signer, seq, values));
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(SIGNER_KEY.into(), Parameter::String(signer.to_owned()));
+    parameters.insert(
+        PUBLIC_KEY_KEY.into(),
+        Parameter::String(hex::encode(embedded_key.as_bytes())),
+    );
+    parameters.insert(
+        SIGNATURE_KEY.into(),
+        Parameter::String(hex::encode(signature.to_bytes())),
+    );
+    parameters.insert(SEQ_KEY.into(), Parameter::Integer(seq as i64));
+    parameters
+}
+
+/// Why a message was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rejection {
+    /// A signing parameter is missing or malformed.
+    Malformed(String),
+    /// The signer is not in the list of trusted nodes.
+    UnknownSigner(String),
+    /// The embedded public key is not the one on record for the claimed signer.
+    KeyMismatch(String),
+    /// The signature does not match the payload, e.g. because it was modified in transit.
+    BadSignature(String),
+}
+
+impl Rejection {
+    /// Short name of the rejection reason, used for the summary.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Rejection::Malformed(_) => "malformed",
+            Rejection::UnknownSigner(_) => "unknown signer",
+            Rejection::KeyMismatch(_) => "key mismatch",
+            Rejection::BadSignature(_) => "bad signature",
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Malformed(reason) => write!(f, "malformed signature metadata: {reason}"),
+            Rejection::UnknownSigner(signer) => write!(f, "`{signer}` is not a trusted signer"),
+            Rejection::KeyMismatch(signer) => {
+                write!(f, "embedded public key is not the key of `{signer}`")
+            }
+            Rejection::BadSignature(signer) => {
+                write!(f, "signature of `{signer}` does not match the payload")
+            }
+        }
+    }
+}
+
+/// Checks messages against the public keys of a fixed set of trusted nodes.
+pub struct Verifier {
+    trusted: BTreeMap<String, VerifyingKey>,
+}
+
+impl Verifier {
+    pub fn load(dir: &Path, signers: &[&str]) -> eyre::Result<Self> {
+        let trusted = signers
+            .iter()
+            .map(|name| Ok((name.to_string(), load_verifying_key(dir, name)?)))
+            .collect::<eyre::Result<_>>()?;
+        Ok(Self { trusted })
+    }
+
+    /// Returns the verified signer and sequence number of the message.
+    pub fn verify(
+        &self,
+        parameters: &MetadataParameters,
+        values: &[f64],
+    ) -> Result<(String, u64), Rejection> {
+        let signer = string_parameter(parameters, SIGNER_KEY)?;
+        let embedded_key = string_parameter(parameters, PUBLIC_KEY_KEY)?;
+        let signature = string_parameter(parameters, SIGNATURE_KEY)?;
+        let seq = match parameters.get(SEQ_KEY) {
+            Some(Parameter::Integer(seq)) => *seq as u64,
+            other => {
+                return Err(Rejection::Malformed(format!(
+                    "expected integer `{SEQ_KEY}`, got {other:?}"
+                )));
+            }
+        };
+
+        // the embedded key is only informational, the key on record decides
+        let trusted_key = self
+            .trusted
+            .get(signer)
+            .ok_or_else(|| Rejection::UnknownSigner(signer.to_owned()))?;
+        if embedded_key != hex::encode(trusted_key.as_bytes()) {
+            return Err(Rejection::KeyMismatch(signer.to_owned()));
+        }
+
+        let signature = hex::decode(signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or_else(|| Rejection::Malformed("invalid signature encoding".to_owned()))?;
+        trusted_key
+            .verify(&signed_bytes(signer, seq, values), &signature)
+            .map_err(|_| Rejection::BadSignature(signer.to_owned()))?;
+
+        Ok((signer.to_owned(), seq))
+    }
+}
+
+fn string_parameter<'a>(
+    parameters: &'a MetadataParameters,
+    key: &str,
+) -> Result<&'a str, Rejection> {
+    match parameters.get(key) {
+        Some(Parameter::String(value)) => Ok(value),
+        other => Err(Rejection::Malformed(format!(
+            "expected string `{key}`, got {other:?}"
+        ))),
+    }
+}
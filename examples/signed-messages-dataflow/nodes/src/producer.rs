@@ -0,0 +1,59 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::Float64Array, dora_core::config::DataId};
+use eyre::Context;
+use signed_messages_dataflow_nodes::{keys_dir, load_signing_key, load_verifying_key, sign};
+
+fn main() -> eyre::Result<()> {
+    let messages: u64 = std::env::var("MESSAGES")
+        .unwrap_or_else(|_| "50".to_owned())
+        .parse()
+        .context("invalid MESSAGES")?;
+    // if set, the node acts as a forger that claims to be the given node
+    let impersonate = std::env::var("IMPERSONATE").ok();
+    let output = DataId::from("data".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let name = node.id().to_string();
+    let keys = keys_dir();
+    let key = load_signing_key(&keys, &name)?;
+    let victim_key = impersonate
+        .as_deref()
+        .map(|victim| load_verifying_key(&keys, victim))
+        .transpose()?;
+
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    let values = vec![seq as f64, (seq as f64 * 0.1).sin(), 21.5];
+                    let parameters = match (&impersonate, &victim_key) {
+                        (Some(victim), Some(victim_key)) => match seq % 3 {
+                            // honest about the key, but the key is not trusted
+                            0 => sign(&key, &name, &key.verifying_key(), seq, &values),
+                            // claims to be the victim, but embeds its own key
+                            1 => sign(&key, victim, &key.verifying_key(), seq, &values),
+                            // claims to be the victim and embeds the victim's public key
+                            _ => sign(&key, victim, victim_key, seq, &values),
+                        },
+                        _ => sign(&key, &name, &key.verifying_key(), seq, &values),
+                    };
+                    node.send_output(output.clone(), parameters, Float64Array::from(values))?;
+                    seq += 1;
+                    if seq >= messages {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} signed messages");
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! A compromised forwarding node: passes messages on with their original signature
+//! metadata, but modifies the payload of every `TAMPER_EVERY`-th message.
+
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::{Context, eyre};
+
+fn main() -> eyre::Result<()> {
+    let tamper_every: u64 = std::env::var("TAMPER_EVERY")
+        .unwrap_or_else(|_| "5".to_owned())
+        .parse()
+        .context("invalid TAMPER_EVERY")?;
+    let output = DataId::from("data".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut forwarded = 0;
+    let mut tampered = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "data" => {
+                    let values = data
+                        .as_primitive_opt::<Float64Type>()
+                        .ok_or_else(|| eyre!("expected float64 data"))?;
+                    let mut values = values.values().to_vec();
+                    forwarded += 1;
+                    if forwarded % tamper_every == 0 {
+                        if let Some(value) = values.last_mut() {
+                            *value += 10.0;
+                        }
+                        tampered += 1;
+                        println!("tampering with message {forwarded}");
+                    }
+                    node.send_output(
+                        output.clone(),
+                        metadata.parameters,
+                        Float64Array::from(values),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("forwarded {forwarded} messages, tampered with {tampered}");
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::Context;
+use std::path::Path;
+use tokio::process::Child;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("sim-bridge-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let mut simulator = run_simulator().await?;
+    let result = dora.run_dataflow(dataflow).await;
+    // the bridge says goodbye on exit, this only cleans up after failed runs
+    simulator.kill().await?;
+
+    result
+}
+
+async fn run_simulator() -> eyre::Result<Child> {
+    let python = which::which("python3").context("failed to find `python3`")?;
+    let mut cmd = tokio::process::Command::new(python);
+    cmd.arg("simulator.py").args(["--port", "9870"]);
+    let child = cmd.spawn().context("failed to start simulator")?;
+    Ok(child)
+}
@@ -0,0 +1,63 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+};
+use example_node_utils::env_or;
+use eyre::{bail, eyre};
+
+const KP: f64 = 4.0;
+const KD: f64 = 3.0;
+
+fn main() -> eyre::Result<()> {
+    let target = [env_or("TARGET_X", 5.0)?, env_or("TARGET_Y", 2.0)?];
+    let max_steps: usize = env_or("MAX_STEPS", 500)?;
+    let output = DataId::from("command".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut steps = 0;
+    let mut distance = f64::INFINITY;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "state" => {
+                    let state = data
+                        .as_primitive_opt::<Float64Type>()
+                        .ok_or_else(|| eyre!("expected float64 state"))?;
+                    let [t, x, y, vx, vy] = state.values()[..] else {
+                        bail!("expected state `[t, x, y, vx, vy]`, got {state:?}");
+                    };
+                    let (ex, ey) = (target[0] - x, target[1] - y);
+                    distance = ex.hypot(ey);
+                    let force = Float64Array::from(vec![KP * ex - KD * vx, KP * ey - KD * vy]);
+                    node.send_output(output.clone(), Default::default(), force)?;
+
+                    steps += 1;
+                    if steps % 50 == 0 {
+                        println!("t={t:.2}s position=({x:.3}, {y:.3}) distance={distance:.3}");
+                    }
+                    if steps >= max_steps {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if distance > 0.1 {
+        bail!("simulated body did not reach the target (remaining distance {distance:.3})");
+    }
+    println!("reached target after {steps} steps");
+    Ok(())
+}
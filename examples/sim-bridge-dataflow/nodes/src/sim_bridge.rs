@@ -0,0 +1,169 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, bail, eyre};
+use std::net::{SocketAddr, UdpSocket};
+
+/// Protocol version, must match `PROTOCOL_VERSION` in `simulator.py`.
+const PROTOCOL_VERSION: u8 = 1;
+/// `version: u8, type: u8, payload length: u16, sequence number: u32`, all little endian.
+const HEADER_LEN: usize = 8;
+
+const MSG_STATE: u8 = 1;
+const MSG_COMMAND: u8 = 2;
+const MSG_HELLO: u8 = 3;
+const MSG_BYE: u8 = 4;
+
+/// A single datagram exchanged with the simulator. The payload is a list of `f64` values.
+#[derive(Debug)]
+struct Frame {
+    ty: u8,
+    seq: u32,
+    values: Vec<f64>,
+}
+
+impl Frame {
+    fn encode(&self) -> Vec<u8> {
+        let payload_len = (self.values.len() * 8) as u16;
+        let mut buf = Vec::with_capacity(HEADER_LEN + payload_len as usize);
+        buf.push(PROTOCOL_VERSION);
+        buf.push(self.ty);
+        buf.extend_from_slice(&payload_len.to_le_bytes());
+        buf.extend_from_slice(&self.seq.to_le_bytes());
+        for value in &self.values {
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> eyre::Result<Self> {
+        if buf.len() < HEADER_LEN {
+            bail!("frame too short ({} bytes)", buf.len());
+        }
+        if buf[0] != PROTOCOL_VERSION {
+            bail!("unsupported protocol version {}", buf[0]);
+        }
+        let ty = buf[1];
+        let payload_len = u16::from_le_bytes([buf[2], buf[3]]) as usize;
+        let seq = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]);
+        let payload = &buf[HEADER_LEN..];
+        if payload.len() != payload_len || payload_len % 8 != 0 {
+            bail!(
+                "payload length mismatch: header says {payload_len}, got {}",
+                payload.len()
+            );
+        }
+        let values = payload
+            .chunks_exact(8)
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        Ok(Self { ty, seq, values })
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let sim_addr: SocketAddr = std::env::var("SIM_ADDR")
+        .unwrap_or_else(|_| "127.0.0.1:9870".to_owned())
+        .parse()
+        .context("invalid SIM_ADDR")?;
+    let output = DataId::from("state".to_owned());
+
+    let socket = UdpSocket::bind("127.0.0.1:0").context("failed to bind UDP socket")?;
+    socket.connect(sim_addr)?;
+    let mut seq = 0;
+    let mut send = |ty, values: Vec<f64>| -> eyre::Result<()> {
+        seq += 1;
+        let frame = Frame { ty, seq, values };
+        socket.send(&frame.encode())?;
+        Ok(())
+    };
+    send(MSG_HELLO, Vec::new()).context("failed to register at simulator")?;
+    println!("registered at simulator {sim_addr}");
+
+    // receive datagrams on a separate thread and forward them as a stream of external events
+    let receiver = socket.try_clone()?;
+    let (tx, rx) = futures::channel::mpsc::unbounded();
+    std::thread::spawn(move || {
+        let mut buf = [0; 1500];
+        loop {
+            let frame = match receiver.recv(&mut buf) {
+                Ok(len) => Frame::decode(&buf[..len]),
+                Err(err) => Err(eyre!(err).wrap_err("failed to receive from simulator")),
+            };
+            if tx.unbounded_send(frame).is_err() {
+                break;
+            }
+        }
+    });
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let merged = events.merge_external(Box::pin(rx));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut last_seq = None;
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input {
+                    id,
+                    metadata: _,
+                    data,
+                } => match id.as_str() {
+                    "command" => {
+                        let force = data
+                            .as_primitive_opt::<Float64Type>()
+                            .ok_or_else(|| eyre!("expected float64 command"))?;
+                        send(MSG_COMMAND, force.values().to_vec())?;
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    break;
+                }
+                Event::Stop(_) => {
+                    println!("Received stop");
+                    break;
+                }
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(frame) => {
+                let frame = match frame {
+                    Ok(frame) => frame,
+                    Err(err) => {
+                        eprintln!("dropping invalid frame: {err:?}");
+                        continue;
+                    }
+                };
+                if frame.ty != MSG_STATE {
+                    eprintln!("ignoring frame of unexpected type {}", frame.ty);
+                    continue;
+                }
+                if let Some(last) = last_seq {
+                    if frame.seq <= last {
+                        eprintln!("dropping out-of-order frame {} (last {last})", frame.seq);
+                        continue;
+                    }
+                    if frame.seq > last + 1 {
+                        eprintln!("lost {} state frame(s)", frame.seq - last - 1);
+                    }
+                }
+                last_seq = Some(frame.seq);
+                node.send_output(
+                    output.clone(),
+                    Default::default(),
+                    Float64Array::from(frame.values),
+                )?;
+            }
+        }
+    }
+
+    send(MSG_BYE, Vec::new())?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+#!/usr/bin/env python3
+"""
+Standalone point-mass physics simulator that knows nothing about dora.
+- Listens for a client on a local UDP port
+- Integrates a 2D point mass at a fixed rate, driven by the last received force command
+- Sends the current state `[t, x, y, vx, vy]` to the registered client after every step
+- Exits when the client says goodbye or on Ctrl+C
+
+Frame layout (little endian): version u8, type u8, payload length u16, sequence u32,
+followed by `payload length / 8` float64 values.
+"""
+
+import argparse
+import socket
+import struct
+import time
+
+PROTOCOL_VERSION = 1
+HEADER = struct.Struct("<BBHI")
+
+MSG_STATE = 1
+MSG_COMMAND = 2
+MSG_HELLO = 3
+MSG_BYE = 4
+
+
+def encode(msg_type, seq, values):
+    payload = struct.pack(f"<{len(values)}d", *values)
+    return HEADER.pack(PROTOCOL_VERSION, msg_type, len(payload), seq) + payload
+
+
+def decode(data):
+    version, msg_type, payload_len, seq = HEADER.unpack_from(data)
+    if version != PROTOCOL_VERSION:
+        raise ValueError(f"unsupported protocol version {version}")
+    payload = data[HEADER.size:]
+    if len(payload) != payload_len:
+        raise ValueError(f"payload length mismatch: {payload_len} != {len(payload)}")
+    return msg_type, seq, struct.unpack(f"<{payload_len // 8}d", payload)
+
+
+def main():
+    parser = argparse.ArgumentParser()
+    parser.add_argument("--port", type=int, default=9870)
+    parser.add_argument("--rate", type=float, default=100.0, help="simulation steps per second")
+    parser.add_argument("--mass", type=float, default=1.0)
+    args = parser.parse_args()
+
+    sock = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+    sock.bind(("127.0.0.1", args.port))
+    sock.setblocking(False)
+    print(f"Simulator listening on 127.0.0.1:{args.port}", flush=True)
+
+    dt = 1.0 / args.rate
+    t = 0.0
+    position = [0.0, 0.0]
+    velocity = [0.0, 0.0]
+    force = [0.0, 0.0]
+    client = None
+    seq = 0
+
+    try:
+        while True:
+            # drain all pending datagrams before stepping
+            while True:
+                try:
+                    data, addr = sock.recvfrom(1500)
+                except BlockingIOError:
+                    break
+                try:
+                    msg_type, _, values = decode(data)
+                except (ValueError, struct.error) as err:
+                    print(f"Dropping invalid frame from {addr}: {err}", flush=True)
+                    continue
+                if msg_type == MSG_HELLO:
+                    print(f"Client registered: {addr}", flush=True)
+                    client = addr
+                elif msg_type == MSG_COMMAND and len(values) == 2:
+                    force = list(values)
+                elif msg_type == MSG_BYE:
+                    print("Client said goodbye, exiting", flush=True)
+                    return
+
+            if client is not None:
+                for axis in range(2):
+                    velocity[axis] += force[axis] / args.mass * dt
+                    position[axis] += velocity[axis] * dt
+                t += dt
+                seq += 1
+                sock.sendto(encode(MSG_STATE, seq, [t, *position, *velocity]), client)
+
+            time.sleep(dt)
+    except KeyboardInterrupt:
+        print("\nReceived Ctrl+C, exiting", flush=True)
+    finally:
+        sock.close()
+
+
+if __name__ == "__main__":
+    main()
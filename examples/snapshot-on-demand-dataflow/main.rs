@@ -0,0 +1,114 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::{Deserialize, de::DeserializeOwned};
+use std::{collections::BTreeMap, path::Path};
+
+/// Must match the consumers in `dataflow.yml`.
+const CONSUMERS: [&str; 2] = ["viz", "logger"];
+/// Must match `DROP_VERSION` of `logger` in `dataflow.yml`.
+const DROP_VERSION: u64 = 100;
+
+/// Subset of `MapReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct MapReport {
+    version: u64,
+    snapshots_sent: u64,
+    state: BTreeMap<String, f64>,
+}
+
+/// Subset of `ConsumerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ConsumerReport {
+    joined_at_version: Option<u64>,
+    version: u64,
+    snapshots: u64,
+    resyncs: u64,
+    deltas_applied: u64,
+    stale_deltas: u64,
+    state: BTreeMap<String, f64>,
+}
+
+fn read_report<T: DeserializeOwned>(node: &str) -> eyre::Result<T> {
+    let path = format!("out/{node}.json");
+    let json =
+        std::fs::read_to_string(&path).with_context(|| format!("`{node}` did not write {path}"))?;
+    serde_json::from_str(&json).with_context(|| format!("invalid report {path}"))
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("snapshot-on-demand-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let map: MapReport = read_report("map")?;
+    println!(
+        "map: version {}, {} objects, {} snapshots sent",
+        map.version,
+        map.state.len(),
+        map.snapshots_sent
+    );
+    let mut reports = BTreeMap::new();
+    let mut snapshots = 0;
+    for consumer in CONSUMERS {
+        let report: ConsumerReport = read_report(consumer)?;
+        println!(
+            "{consumer}: joined at version {:?}, {} snapshots, {} resyncs, {} deltas applied, {} stale",
+            report.joined_at_version,
+            report.snapshots,
+            report.resyncs,
+            report.deltas_applied,
+            report.stale_deltas
+        );
+        if report.version != map.version || report.state != map.state {
+            bail!(
+                "`{consumer}` ended at version {} with {:?}, but the map is at version {} with {:?}",
+                report.version,
+                report.state,
+                map.version,
+                map.state
+            );
+        }
+        if report.snapshots != report.resyncs + 1 {
+            bail!("`{consumer}` should need one snapshot to join, and one per resync");
+        }
+        snapshots += report.snapshots;
+        reports.insert(consumer, report);
+    }
+    if snapshots != map.snapshots_sent {
+        bail!(
+            "the consumers received {snapshots} snapshots, but the map sent {}",
+            map.snapshots_sent
+        );
+    }
+
+    let (viz, logger) = (&reports["viz"], &reports["logger"]);
+    if viz.resyncs != 0 {
+        bail!("`viz` loses no delta, but resynced {} times", viz.resyncs);
+    }
+    let (Some(viz_joined), Some(logger_joined)) = (viz.joined_at_version, logger.joined_at_version)
+    else {
+        bail!("both consumers must have joined");
+    };
+    if logger_joined <= viz_joined {
+        bail!("`logger` joins mid-run, but joined at version {logger_joined}");
+    }
+    if logger.resyncs != 1 || logger_joined >= DROP_VERSION {
+        bail!(
+            "`logger` should join before delta {DROP_VERSION} and resync once after losing it, \
+             resynced {} times",
+            logger.resyncs
+        );
+    }
+    println!("both consumers converged to the state of the map");
+    Ok(())
+}
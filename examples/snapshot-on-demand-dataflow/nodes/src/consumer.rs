@@ -0,0 +1,161 @@
+use dora_node_api::{
+    DoraNode, Event,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use snapshot_on_demand_dataflow_nodes::{
+    ConsumerReport, Delta, Snapshot, SnapshotRequest, State, env_or, write_json,
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Where a consumer is in the state-sync protocol.
+#[derive(Debug)]
+enum Phase {
+    /// Hasn't joined yet, ignores all deltas.
+    Detached,
+    /// Requested a snapshot, and keeps the deltas that arrive in the meantime.
+    Waiting { buffered: Vec<Delta> },
+    /// Has the state of `version`.
+    Synced { version: u64 },
+}
+
+/// Mirrors the state of the map node from its `delta` and `snapshot` inputs.
+///
+/// Joins after `JOIN_AFTER_MS` by requesting a snapshot on `request`. Deltas that arrive before
+/// the snapshot are buffered and applied on top of it, deltas that the state already contains
+/// are ignored, and a missing delta triggers a new snapshot request. `DROP_VERSION` simulates
+/// the loss of the delta with that version.
+///
+/// Writes a [`ConsumerReport`] to `REPORT_FILE` when its inputs are closed.
+fn main() -> eyre::Result<()> {
+    let join_after = Duration::from_millis(env_or("JOIN_AFTER_MS", 0)?);
+    // never drops a delta if unset
+    let drop_version: u64 = env_or("DROP_VERSION", u64::MAX)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/consumer.json".to_owned())?.into();
+    let output = DataId::from("request".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let consumer = node.id().to_string();
+
+    let start = Instant::now();
+    let mut phase = Phase::Detached;
+    let mut state = State::new();
+    let mut report = ConsumerReport {
+        consumer: consumer.clone(),
+        joined_at_version: None,
+        version: 0,
+        snapshots: 0,
+        resyncs: 0,
+        deltas_applied: 0,
+        stale_deltas: 0,
+        state: State::new(),
+    };
+    let request_snapshot = |node: &mut DoraNode| -> eyre::Result<()> {
+        let request = SnapshotRequest {
+            consumer: consumer.clone(),
+        };
+        node.send_output(
+            output.clone(),
+            Default::default(),
+            StringArray::from(vec![serde_json::to_string(&request)?]),
+        )
+    };
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => {
+                let rows = data
+                    .as_string_opt::<i32>()
+                    .ok_or_eyre("expected a Utf8 array")?;
+                for row in rows.iter().flatten() {
+                    match id.as_str() {
+                        "delta" => {
+                            let delta: Delta = serde_json::from_str(row)?;
+                            if delta.version == drop_version {
+                                println!(
+                                    "dropping delta {drop_version} to simulate a lost message"
+                                );
+                                continue;
+                            }
+                            match &mut phase {
+                                Phase::Detached => {
+                                    if start.elapsed() >= join_after {
+                                        println!("joining at delta {}", delta.version);
+                                        request_snapshot(&mut node)?;
+                                        phase = Phase::Waiting {
+                                            buffered: vec![delta],
+                                        };
+                                    }
+                                }
+                                Phase::Waiting { buffered } => buffered.push(delta),
+                                Phase::Synced { version } if delta.version <= *version => {
+                                    report.stale_deltas += 1;
+                                }
+                                Phase::Synced { version } if delta.version == *version + 1 => {
+                                    delta.apply(&mut state);
+                                    *version = delta.version;
+                                    report.deltas_applied += 1;
+                                }
+                                Phase::Synced { version } => {
+                                    println!(
+                                        "missing deltas {}..{}, requesting a snapshot",
+                                        *version + 1,
+                                        delta.version
+                                    );
+                                    request_snapshot(&mut node)?;
+                                    report.resyncs += 1;
+                                    phase = Phase::Waiting {
+                                        buffered: vec![delta],
+                                    };
+                                }
+                            }
+                        }
+                        "snapshot" => {
+                            let snapshot: Snapshot = serde_json::from_str(row)?;
+                            let Phase::Waiting { buffered } = &mut phase else {
+                                continue;
+                            };
+                            if snapshot.consumer != consumer {
+                                continue;
+                            }
+                            state = snapshot.state;
+                            let mut version = snapshot.version;
+                            report.snapshots += 1;
+                            report.joined_at_version.get_or_insert(version);
+                            // the deltas may arrive before or after the snapshot
+                            buffered.sort_by_key(|delta| delta.version);
+                            for delta in buffered.drain(..) {
+                                if delta.version <= version {
+                                    report.stale_deltas += 1;
+                                } else if delta.version == version + 1 {
+                                    delta.apply(&mut state);
+                                    version = delta.version;
+                                    report.deltas_applied += 1;
+                                } else {
+                                    // lost while waiting, the next delta triggers a resync
+                                    break;
+                                }
+                            }
+                            println!("synced at version {version}");
+                            phase = Phase::Synced { version };
+                        }
+                        other => eprintln!("Ignoring unexpected input `{other}`"),
+                    }
+                }
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if let Phase::Synced { version } = phase {
+        report.version = version;
+    }
+    report.state = state;
+    write_json(&report_file, &report)
+}
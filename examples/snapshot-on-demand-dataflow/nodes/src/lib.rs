@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub use example_node_utils::{env_or, write_json};
+
+/// The state of the map node: the positions of tracked objects, by id.
+pub type State = BTreeMap<String, f64>;
+
+/// A change of the state. Version `n` turns the state of version `n - 1` into version `n`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delta {
+    pub version: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub set: BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+}
+
+impl Delta {
+    pub fn apply(&self, state: &mut State) {
+        for (key, value) in &self.set {
+            state.insert(key.clone(), *value);
+        }
+        for key in &self.removed {
+            state.remove(key);
+        }
+    }
+}
+
+/// Sent by a consumer that needs the full state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotRequest {
+    pub consumer: String,
+}
+
+/// The full state at `version`, in reply to a [`SnapshotRequest`] of `consumer`.
+///
+/// Snapshots are sent on one output, so every consumer receives all of them and ignores the
+/// ones that it didn't request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u64,
+    pub consumer: String,
+    pub state: State,
+}
+
+/// Written by the map node when it stops.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapReport {
+    pub version: u64,
+    pub snapshots_sent: u64,
+    pub state: State,
+}
+
+/// Written by a consumer when its inputs are closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsumerReport {
+    pub consumer: String,
+    /// The version of the first snapshot, `None` if the consumer never synced.
+    pub joined_at_version: Option<u64>,
+    pub version: u64,
+    pub snapshots: u64,
+    /// Snapshots requested because a delta was missing.
+    pub resyncs: u64,
+    pub deltas_applied: u64,
+    /// Deltas that the consumer received but that were already part of its state.
+    pub stale_deltas: u64,
+    pub state: State,
+}
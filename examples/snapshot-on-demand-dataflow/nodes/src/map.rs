@@ -0,0 +1,105 @@
+use dora_node_api::{
+    DoraNode, Event,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use snapshot_on_demand_dataflow_nodes::{
+    Delta, MapReport, Snapshot, SnapshotRequest, State, env_or, write_json,
+};
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Keeps the positions of tracked objects, and publishes every change as a [`Delta`] on
+/// `delta`, one per `tick`.
+///
+/// Every other input is a channel for [`SnapshotRequest`]s, answered with a [`Snapshot`] of the
+/// current state on `snapshot`. Stops after `DURATION_MS`, and writes a [`MapReport`] to
+/// `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let objects: u64 = env_or("OBJECTS", 7)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/map.json".to_owned())?.into();
+    let delta_output = DataId::from("delta".to_owned());
+    let snapshot_output = DataId::from("snapshot".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    let mut state = State::new();
+    let mut version = 0;
+    let mut snapshots_sent = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    version += 1;
+                    let delta = next_delta(version, objects);
+                    delta.apply(&mut state);
+                    node.send_output(
+                        delta_output.clone(),
+                        Default::default(),
+                        StringArray::from(vec![serde_json::to_string(&delta)?]),
+                    )?;
+                }
+                _ => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array of snapshot requests")?;
+                    for row in rows.iter().flatten() {
+                        let request: SnapshotRequest = serde_json::from_str(row)?;
+                        println!("snapshot of version {version} for `{}`", request.consumer);
+                        let snapshot = Snapshot {
+                            version,
+                            consumer: request.consumer,
+                            state: state.clone(),
+                        };
+                        node.send_output(
+                            snapshot_output.clone(),
+                            Default::default(),
+                            StringArray::from(vec![serde_json::to_string(&snapshot)?]),
+                        )?;
+                        snapshots_sent += 1;
+                    }
+                }
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let report = MapReport {
+        version,
+        snapshots_sent,
+        state,
+    };
+    write_json(&report_file, &report)
+}
+
+/// Moves one object per version, and removes one every 11 versions, so that deltas can't be
+/// applied out of order without changing the result.
+fn next_delta(version: u64, objects: u64) -> Delta {
+    let mut set = BTreeMap::new();
+    set.insert(
+        format!("object-{}", version % objects),
+        version as f64 * 0.5,
+    );
+    let removed = if version % 11 == 0 {
+        vec![format!("object-{}", (version / 11) % objects)]
+    } else {
+        Vec::new()
+    };
+    Delta {
+        version,
+        set,
+        removed,
+    }
+}
@@ -0,0 +1,264 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, OptionExt, bail};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{collections::BTreeMap, path::Path};
+
+/// Must match `COUNT` of the source in `dataflow.yml`.
+const MESSAGES: u64 = 40;
+/// Must match `SLOW_EVERY` of the filter in `dataflow.yml`.
+const SLOW_EVERY: u64 = 10;
+/// The nodes in the order that a message passes them.
+const NODES: [&str; 3] = ["source", "filter", "sink"];
+/// The `TRACE_DIR` of the nodes.
+const TRACE_DIR: &str = "out/traces";
+/// How far the clocks of two traces may be apart after the alignment.
+const CLOCK_TOLERANCE_US: f64 = 1000.0;
+
+/// Subset of `SinkReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkReport {
+    received: Vec<u64>,
+}
+
+/// A span of a node, in microseconds since the Unix epoch.
+#[derive(Debug)]
+struct SpanRecord {
+    name: String,
+    /// The id of the `message` span, or of the one that the span is nested in.
+    message_id: Option<u64>,
+    start_us: f64,
+    end_us: f64,
+}
+
+impl SpanRecord {
+    fn duration_ms(&self) -> f64 {
+        (self.end_us - self.start_us) / 1000.0
+    }
+}
+
+/// The chrome trace of one node.
+struct NodeTrace {
+    node: &'static str,
+    events: Vec<Value>,
+    /// Added to the timestamps of the trace, which count from the start of the node, gives
+    /// the time since the Unix epoch.
+    offset_us: f64,
+    spans: Vec<SpanRecord>,
+}
+
+impl NodeTrace {
+    fn message_span(&self, id: u64) -> Option<&SpanRecord> {
+        self.spans
+            .iter()
+            .find(|span| span.name == "message" && span.message_id == Some(id))
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("span-per-message-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let sink: SinkReport = read_json("out/sink.json")?;
+    if sink.received != (0..MESSAGES).collect::<Vec<_>>() {
+        bail!(
+            "the sink should receive the messages 0 to {} in order, it received {:?}",
+            MESSAGES - 1,
+            sink.received
+        );
+    }
+
+    let traces = NODES
+        .into_iter()
+        .map(load_trace)
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    // every message has a span in every node, and the spans follow the message
+    for id in 0..MESSAGES {
+        let mut previous: Option<(&str, &SpanRecord)> = None;
+        for trace in &traces {
+            let span = trace
+                .message_span(id)
+                .ok_or_else(|| eyre::eyre!("no span for message {id} in `{}`", trace.node))?;
+            if let Some((node, before)) = previous
+                && span.start_us + CLOCK_TOLERANCE_US < before.start_us
+            {
+                bail!(
+                    "the span of message {id} in `{}` starts {:.1} ms before the one in \
+                     `{node}`, the traces are not aligned",
+                    trace.node,
+                    (before.start_us - span.start_us) / 1000.0
+                );
+            }
+            previous = Some((trace.node, span));
+        }
+    }
+
+    // the nested `smooth` spans show which messages took the long path through the filter
+    let filter = &traces[1];
+    let (mut slow, mut fast) = (Vec::new(), Vec::new());
+    for span in filter.spans.iter().filter(|span| span.name == "smooth") {
+        match span.message_id {
+            Some(id) if id % SLOW_EVERY == SLOW_EVERY - 1 => slow.push(span.duration_ms()),
+            Some(_) => fast.push(span.duration_ms()),
+            None => bail!("a `smooth` span of the filter is outside of a message span"),
+        }
+    }
+    let (slow, fast) = (median(&mut slow)?, median(&mut fast)?);
+    println!(
+        "filter: median `smooth` of the slow messages {slow:.3} ms, of the others {fast:.3} ms"
+    );
+    if slow < 5.0 * fast {
+        bail!("the slow messages should stand out in the `smooth` spans of the filter");
+    }
+
+    print_slowest(&traces, 3);
+    bundle(&traces, Path::new("out/trace.json"))?;
+    println!("open out/trace.json in chrome://tracing or https://ui.perfetto.dev");
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Reads the trace of `node` and the spans in it.
+fn load_trace(node: &'static str) -> eyre::Result<NodeTrace> {
+    let path = Path::new(TRACE_DIR).join(format!("{node}.json"));
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("{} was not written", path.display()))?;
+    // the node closes the JSON array when it exits, so the trace of a crashed node lacks the
+    // closing bracket
+    let content = content.trim_end();
+    let events: Vec<Value> = if content.ends_with(']') {
+        serde_json::from_str(content)
+    } else {
+        serde_json::from_str(&format!("{}]", content.trim_end_matches(',')))
+    }
+    .with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let anchor = events
+        .iter()
+        .find(|event| event["ph"] == "B" && event["name"] == "clock_anchor")
+        .ok_or_eyre(format!("`{node}` recorded no clock anchor"))?;
+    let unix_micros = arg_u64(anchor, "unix_micros").ok_or_eyre("clock anchor without time")?;
+    let offset_us = unix_micros as f64 - anchor["ts"].as_f64().unwrap_or_default();
+
+    // the begin and end events of the spans nest per thread
+    let mut open: BTreeMap<String, Vec<SpanRecord>> = BTreeMap::new();
+    let mut spans = Vec::new();
+    for event in &events {
+        let (Some(ts), Some(name)) = (event["ts"].as_f64(), event["name"].as_str()) else {
+            continue;
+        };
+        let stack = open.entry(event["tid"].to_string()).or_default();
+        match event["ph"].as_str() {
+            Some("B") => {
+                let message_id = arg_u64(event, "message_id")
+                    .or_else(|| stack.last().and_then(|parent| parent.message_id));
+                stack.push(SpanRecord {
+                    name: name.to_owned(),
+                    message_id,
+                    start_us: ts + offset_us,
+                    end_us: f64::NAN,
+                });
+            }
+            Some("E") => {
+                let mut span = stack
+                    .pop()
+                    .ok_or_eyre(format!("`{node}` ended span `{name}` that was not entered"))?;
+                span.end_us = ts + offset_us;
+                spans.push(span);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(NodeTrace {
+        node,
+        events,
+        offset_us,
+        spans,
+    })
+}
+
+/// tracing-chrome records the span arguments with their `Debug` output, so numbers are strings.
+fn arg_u64(event: &Value, key: &str) -> Option<u64> {
+    let value = event.get("args")?.get(key)?;
+    value.as_u64().or_else(|| value.as_str()?.parse().ok())
+}
+
+fn median(values: &mut [f64]) -> eyre::Result<f64> {
+    if values.is_empty() {
+        bail!("no spans to compare");
+    }
+    values.sort_by(f64::total_cmp);
+    Ok(values[values.len() / 2])
+}
+
+/// Prints where the `count` messages with the longest way from the source to the sink spent
+/// their time.
+fn print_slowest(traces: &[NodeTrace], count: usize) {
+    let mut messages: Vec<(u64, Vec<&SpanRecord>)> = (0..MESSAGES)
+        .filter_map(|id| {
+            let spans = traces.iter().map(|trace| trace.message_span(id));
+            Some((id, spans.collect::<Option<Vec<_>>>()?))
+        })
+        .collect();
+    let end_to_end = |spans: &[&SpanRecord]| spans[spans.len() - 1].end_us - spans[0].start_us;
+    messages.sort_by(|(_, a), (_, b)| end_to_end(b).total_cmp(&end_to_end(a)));
+
+    for (id, spans) in messages.iter().take(count) {
+        let steps: Vec<_> = traces
+            .iter()
+            .zip(spans)
+            .map(|(trace, span)| format!("{} {:.2} ms", trace.node, span.duration_ms()))
+            .collect();
+        println!(
+            "message {id}: {:.2} ms from source to sink, {}",
+            end_to_end(spans) / 1000.0,
+            steps.join(", ")
+        );
+    }
+}
+
+/// Writes the traces of all nodes into one, with a process per node and the timestamps on a
+/// common clock, so that the timelines of a message line up across the nodes.
+fn bundle(traces: &[NodeTrace], path: &Path) -> eyre::Result<()> {
+    let start_us = traces
+        .iter()
+        .map(|trace| trace.offset_us)
+        .fold(f64::INFINITY, f64::min);
+    let mut events = Vec::new();
+    for (pid, trace) in (1..).zip(traces) {
+        events.push(json!({
+            "ph": "M",
+            "name": "process_name",
+            "pid": pid,
+            "args": { "name": trace.node },
+        }));
+        for event in &trace.events {
+            let mut event = event.clone();
+            event["pid"] = json!(pid);
+            if let Some(ts) = event["ts"].as_f64() {
+                event["ts"] = json!(ts + trace.offset_us - start_us);
+            }
+            events.push(event);
+        }
+    }
+    std::fs::write(path, serde_json::to_string(&events)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
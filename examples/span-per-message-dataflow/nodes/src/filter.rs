@@ -0,0 +1,83 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::{AsArray, Float32Array, types::Float32Type},
+    dora_core::config::DataId,
+};
+use span_per_message_dataflow_nodes::{
+    env_or, message_parameters, process_message, trace_to_chrome,
+};
+
+/// Smooths every `frame` with a box filter and sends the result on `filtered`, with the message
+/// id of the frame.
+///
+/// Every `SLOW_EVERY`th frame is smoothed with `SLOW_PASSES` instead of `PASSES` passes, so that
+/// those messages stand out in the timeline.
+fn main() -> eyre::Result<()> {
+    let passes: usize = env_or("PASSES", 2)?;
+    let slow_passes: usize = env_or("SLOW_PASSES", 40)?;
+    let slow_every: u64 = env_or("SLOW_EVERY", 10)?;
+    let output = DataId::from("filtered".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    trace_to_chrome(&node_id, || {
+        while let Some(event) = events.recv() {
+            match event {
+                Event::Input { id, metadata, data } => match id.as_str() {
+                    "frame" => process_message("frame", &metadata.parameters, |message_id| {
+                        let frame = data
+                            .as_primitive_opt::<Float32Type>()
+                            .ok_or_else(|| eyre::eyre!("expected Float32 frame"))?;
+                        let passes = if message_id % slow_every == slow_every - 1 {
+                            slow_passes
+                        } else {
+                            passes
+                        };
+                        let filtered = smooth(frame.values(), passes);
+                        node.send_output(
+                            output.clone(),
+                            message_parameters(message_id),
+                            normalize(filtered),
+                        )?;
+                        Ok(())
+                    })?,
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    if id.as_str() == "frame" {
+                        break;
+                    }
+                }
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Averages each value with its neighbors, `passes` times.
+#[tracing::instrument(skip(frame), fields(len = frame.len()))]
+fn smooth(frame: &[f32], passes: usize) -> Vec<f32> {
+    let mut current = frame.to_vec();
+    let mut next = current.clone();
+    for _ in 0..passes {
+        for i in 1..current.len().saturating_sub(1) {
+            next[i] = (current[i - 1] + current[i] + current[i + 1]) / 3.0;
+        }
+        std::mem::swap(&mut current, &mut next);
+    }
+    current
+}
+
+/// Scales the values to the range -1 to 1.
+#[tracing::instrument(skip_all)]
+fn normalize(values: Vec<f32>) -> Float32Array {
+    let max = values
+        .iter()
+        .fold(0.0f32, |max, value| max.max(value.abs()));
+    let scale = if max > 0.0 { 1.0 / max } else { 1.0 };
+    values.into_iter().map(|value| value * scale).collect()
+}
@@ -0,0 +1,92 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{Context, bail};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, time::SystemTime};
+use tracing::{Span, info_span};
+use tracing_chrome::ChromeLayerBuilder;
+use tracing_subscriber::{Layer, filter::filter_fn, layer::SubscriberExt};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Metadata key under which the id of a message travels along with it, and every message
+/// derived from it.
+pub const MESSAGE_ID_KEY: &str = "message_id";
+
+/// Name of the span that [`message_span`] creates, which the runner looks for in the traces.
+pub const MESSAGE_SPAN: &str = "message";
+
+/// Name of the span that [`trace_to_chrome`] enters first. Its `unix_micros` argument is the
+/// wall-clock time at which the trace starts, while the timestamps of a trace count from the
+/// start of its node, so that the runner can align the traces of all nodes.
+pub const CLOCK_ANCHOR_SPAN: &str = "clock_anchor";
+
+/// Reads the id that the source attached to the message, or to the message it was derived
+/// from.
+pub fn message_id(parameters: &MetadataParameters) -> eyre::Result<u64> {
+    match parameters.get(MESSAGE_ID_KEY) {
+        Some(Parameter::Integer(id)) => Ok(u64::try_from(*id)?),
+        Some(other) => bail!("unexpected `{MESSAGE_ID_KEY}` parameter {other:?}"),
+        None => bail!("input has no `{MESSAGE_ID_KEY}` parameter"),
+    }
+}
+
+/// Metadata parameters that carry the message id to the next node.
+pub fn message_parameters(id: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(MESSAGE_ID_KEY.into(), Parameter::Integer(id as i64));
+    parameters
+}
+
+/// The span in which a node processes the message `id` that arrived on `input`.
+///
+/// The span name is the same in every node, the node itself is the process of the trace.
+/// Spans that are entered within it, e.g. those of `#[tracing::instrument]` functions, nest
+/// below it in the timeline.
+pub fn message_span(input: &str, id: u64) -> Span {
+    info_span!(MESSAGE_SPAN, input = %input, message_id = id)
+}
+
+/// Runs `process` in the [`message_span`] of the input with the given `parameters`, and
+/// passes it the message id, e.g. to attach it to the outputs.
+pub fn process_message<T>(
+    input: &str,
+    parameters: &MetadataParameters,
+    process: impl FnOnce(u64) -> eyre::Result<T>,
+) -> eyre::Result<T> {
+    let id = message_id(parameters)?;
+    message_span(input, id).in_scope(|| process(id))
+}
+
+/// Runs `f` with a subscriber that records all spans of the current thread to
+/// `<TRACE_DIR>/<node>.json`, in the JSON format of chrome://tracing and Perfetto.
+///
+/// The subscriber is only the default of the current thread, so that it doesn't conflict with
+/// the global one of the dora node API, and it leaves out the spans of dora itself. The trace
+/// is complete once `f` returns.
+pub fn trace_to_chrome<T>(node: &str, f: impl FnOnce() -> eyre::Result<T>) -> eyre::Result<T> {
+    let dir: PathBuf = env_or("TRACE_DIR", PathBuf::from("out/traces"))?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let (chrome, guard) = ChromeLayerBuilder::new()
+        .file(dir.join(format!("{node}.json")))
+        .include_args(true)
+        .build();
+    let subscriber = tracing_subscriber::registry()
+        .with(chrome.with_filter(filter_fn(|metadata| !metadata.target().starts_with("dora"))));
+
+    let result = tracing::subscriber::with_default(subscriber, || {
+        let unix_micros = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_micros() as u64;
+        info_span!(CLOCK_ANCHOR_SPAN, unix_micros).in_scope(|| {});
+        f()
+    });
+    // writes the end of the JSON array
+    drop(guard);
+    result
+}
+
+/// The message ids that reached the sink, in the order of arrival.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SinkReport {
+    pub received: Vec<u64>,
+}
@@ -0,0 +1,60 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::{AsArray, types::Float32Type},
+};
+use span_per_message_dataflow_nodes::{
+    SinkReport, env_or, process_message, trace_to_chrome, write_json,
+};
+use std::path::PathBuf;
+
+/// Checks every `filtered` frame and writes the message ids that arrived to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", PathBuf::from("out/sink.json"))?;
+
+    let (node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    let mut report = SinkReport {
+        received: Vec::new(),
+    };
+    trace_to_chrome(&node_id, || {
+        while let Some(event) = events.recv() {
+            match event {
+                Event::Input { id, metadata, data } => match id.as_str() {
+                    "filtered" => {
+                        process_message("filtered", &metadata.parameters, |message_id| {
+                            let frame = data
+                                .as_primitive_opt::<Float32Type>()
+                                .ok_or_else(|| eyre::eyre!("expected Float32 frame"))?;
+                            check(frame.values())?;
+                            report.received.push(message_id);
+                            Ok(())
+                        })?
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    if id.as_str() == "filtered" {
+                        break;
+                    }
+                }
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            }
+        }
+        Ok(())
+    })?;
+
+    println!("received {} frames", report.received.len());
+    write_json(&report_file, &report)
+}
+
+/// Fails unless the filter normalized the frame.
+#[tracing::instrument(skip_all)]
+fn check(values: &[f32]) -> eyre::Result<()> {
+    if values.iter().any(|value| !(-1.0..=1.0).contains(value)) {
+        eyre::bail!("frame is not normalized");
+    }
+    Ok(())
+}
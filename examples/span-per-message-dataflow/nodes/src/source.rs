@@ -0,0 +1,48 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::Float32Array, dora_core::config::DataId};
+use span_per_message_dataflow_nodes::{env_or, message_parameters, message_span, trace_to_chrome};
+
+/// Sends `COUNT` frames of `FRAME_LEN` values on `frame`, one per `tick`, each with a new
+/// message id.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 40)?;
+    let frame_len: usize = env_or("FRAME_LEN", 16384)?;
+    let output = DataId::from("frame".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    trace_to_chrome(&node_id, || {
+        let mut sent = 0;
+        while let Some(event) = events.recv() {
+            match event {
+                Event::Input { id, .. } => match id.as_str() {
+                    "tick" => {
+                        // the message starts here, so its span starts with the tick
+                        message_span("tick", sent).in_scope(|| -> eyre::Result<()> {
+                            let frame = generate(sent, frame_len);
+                            node.send_output(output.clone(), message_parameters(sent), frame)?;
+                            Ok(())
+                        })?;
+                        sent += 1;
+                        if sent >= count {
+                            break;
+                        }
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            }
+        }
+        println!("sent {sent} frames");
+        Ok(())
+    })
+}
+
+/// A deterministic frame, a sine wave whose phase depends on the message id.
+#[tracing::instrument]
+fn generate(id: u64, len: usize) -> Float32Array {
+    (0..len)
+        .map(|i| ((i as f32 + id as f32) * 0.01).sin())
+        .collect()
+}
@@ -0,0 +1,142 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, node_env};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Where the runner downloads the model of the transcriber from, if it's missing.
+const MODEL_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Subset of `SinkReport` in `nodes/src/sink.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkReport {
+    source: Option<String>,
+    transcripts: Vec<Transcript>,
+}
+
+/// Subset of `Transcript` in `nodes/src/sink.rs`.
+#[derive(Debug, Deserialize)]
+struct Transcript {
+    window: i64,
+    start_ms: i64,
+    end_ms: i64,
+    inference_ms: i64,
+}
+
+/// Downloads the ggml model of the transcriber into `path`, named like the file on Hugging
+/// Face.
+async fn download_model(path: &Path) -> eyre::Result<()> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| eyre::eyre!("invalid model path {}", path.display()))?;
+    println!("downloading {name} to {}", path.display());
+    let status = tokio::process::Command::new("curl")
+        .args(["--location", "--fail", "--create-dirs", "--output"])
+        .arg(path)
+        .arg(format!("{MODEL_URL}/{name}"))
+        .status()
+        .await
+        .wrap_err("failed to run curl")?;
+    if !status.success() {
+        bail!("failed to download {name}: curl exited with {status}");
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("speech-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    // also removes the synthetic WAV file, so it's created again
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dataflow = Path::new("dataflow.yml");
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let model_path = node_env(&descriptor, "transcriber", "MODEL_PATH")?;
+    // the durations are given in seconds
+    let millis = |id, key| -> eyre::Result<i64> {
+        let secs: f64 = node_env(&descriptor, id, key)?
+            .parse()
+            .wrap_err_with(|| format!("`{key}` of `{id}` is not a number"))?;
+        Ok((secs * 1000.0) as i64)
+    };
+    let window_ms = millis("transcriber", "WINDOW_SECS")?;
+    let synthetic_ms = millis("capture", "SYNTHETIC_SECS")?;
+    let max_ms = millis("capture", "MAX_SECS")?;
+
+    if !Path::new(&model_path).exists() {
+        download_model(Path::new(&model_path)).await?;
+    }
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: SinkReport = serde_json::from_str(
+        &std::fs::read_to_string("out/transcript.json")
+            .context("the sink did not write a report")?,
+    )?;
+    let Some(source) = &report.source else {
+        bail!("the sink received no transcripts");
+    };
+    println!(
+        "transcribed {} windows of audio from the {source}",
+        report.transcripts.len()
+    );
+
+    // the windows follow each other without gaps, and all but the last one are full
+    let mut expected_start = 0;
+    for (i, transcript) in report.transcripts.iter().enumerate() {
+        if transcript.window != i as i64 || transcript.start_ms != expected_start {
+            bail!(
+                "expected window {i} to start at {expected_start} ms, got window {} at {} ms",
+                transcript.window,
+                transcript.start_ms
+            );
+        }
+        let len = transcript.end_ms - transcript.start_ms;
+        let last = i + 1 == report.transcripts.len();
+        if len > window_ms || (!last && len != window_ms) {
+            bail!("window {i} is {len} ms long, expected {window_ms} ms");
+        }
+        expected_start = transcript.end_ms;
+    }
+
+    // every sample of the source was transcribed
+    match source.as_str() {
+        "wav" if expected_start != synthetic_ms => bail!(
+            "expected {synthetic_ms} ms of synthetic speech to be transcribed, got \
+             {expected_start} ms"
+        ),
+        "microphone" if expected_start < max_ms - 1000 => bail!(
+            "expected about {max_ms} ms of recording to be transcribed, got {expected_start} ms"
+        ),
+        "wav" | "microphone" => {}
+        other => bail!("unexpected source `{other}`"),
+    }
+
+    // the transcriber has to keep up with the audio
+    let inference_ms: i64 = report
+        .transcripts
+        .iter()
+        .map(|transcript| transcript.inference_ms)
+        .sum();
+    println!(
+        "transcribing {expected_start} ms of audio took {inference_ms} ms, a real-time factor \
+         of {:.2}",
+        inference_ms as f64 / expected_start as f64
+    );
+    if inference_ms >= expected_start {
+        bail!("the transcriber is slower than real time");
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
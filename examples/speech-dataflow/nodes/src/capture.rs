@@ -0,0 +1,229 @@
+use cpal::{
+    FromSample, SampleFormat, SizedSample,
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+};
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::Float32Array,
+    dora_core::config::DataId,
+};
+use eyre::{Context, OptionExt, bail};
+use speech_dataflow_nodes::{
+    CHUNK_KEY, Resampler, SAMPLE_RATE, SAMPLE_RATE_KEY, SOURCE_KEY, START_MS_KEY, env_or, read_wav,
+    sample_ms, synthetic_speech, write_wav,
+};
+use std::{
+    path::PathBuf,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+/// Where the audio comes from.
+enum Source {
+    /// The default input device. The stream runs until it's dropped, and sends the samples of
+    /// every buffer, resampled, through the channel.
+    Microphone {
+        _stream: cpal::Stream,
+        samples: mpsc::Receiver<Vec<f32>>,
+    },
+    /// A WAV file, replayed in real time.
+    Wav { samples: Vec<f32>, sent: usize },
+}
+
+impl Source {
+    fn name(&self) -> &'static str {
+        match self {
+            Source::Microphone { .. } => "microphone",
+            Source::Wav { .. } => "wav",
+        }
+    }
+
+    /// Appends the samples that arrived since the last call. Returns `false` once the source
+    /// has no more samples.
+    fn read(&mut self, elapsed: Duration, pending: &mut Vec<f32>) -> bool {
+        match self {
+            Source::Microphone { samples, .. } => {
+                pending.extend(samples.try_iter().flatten());
+                true
+            }
+            Source::Wav { samples, sent } => {
+                let due =
+                    ((elapsed.as_secs_f64() * SAMPLE_RATE as f64) as usize).min(samples.len());
+                pending.extend_from_slice(&samples[*sent..due]);
+                *sent = due;
+                due < samples.len()
+            }
+        }
+    }
+}
+
+/// Captures audio and sends it on `audio` in chunks of `CHUNK_MS`, as mono `Float32` samples
+/// at 16 kHz, the format that whisper expects.
+///
+/// `AUDIO_SOURCE` selects the source:
+///
+/// - `microphone` records the default input device of the system, until the dataflow is
+///   stopped, or for `MAX_SECS` if it's not 0.
+/// - `wav` replays `WAV_FILE` in real time, and stops at its end. If the file doesn't exist,
+///   it's created with `SYNTHETIC_SECS` seconds of synthetic speech first.
+/// - `auto` uses the microphone if there is one, and falls back to `wav` otherwise, e.g. on a
+///   headless CI machine.
+///
+/// The samples that arrived are sent on every `tick`, once they fill a chunk.
+fn main() -> eyre::Result<()> {
+    let audio_source: String = env_or("AUDIO_SOURCE", "auto".to_owned())?;
+    let chunk_ms: usize = env_or("CHUNK_MS", 500)?;
+    let max_secs: f64 = env_or("MAX_SECS", 0.0)?;
+    let chunk_len = chunk_ms * SAMPLE_RATE as usize / 1000;
+    let output = DataId::from("audio".to_owned());
+
+    let mut source = match audio_source.as_str() {
+        "microphone" => open_microphone()?,
+        "wav" => open_wav()?,
+        "auto" => match open_microphone() {
+            Ok(source) => source,
+            Err(err) => {
+                println!("no microphone ({err:#}), falling back to a WAV file");
+                open_wav()?
+            }
+        },
+        other => bail!("unknown AUDIO_SOURCE `{other}`, expected `microphone`, `wav` or `auto`"),
+    };
+    let source_name = source.name();
+    println!("capturing from {source_name}");
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let started = Instant::now();
+    let mut pending = Vec::new();
+    let mut sent = 0;
+    let mut chunk = 0;
+    let mut send_chunk = |node: &mut DoraNode, samples: Vec<f32>| -> eyre::Result<()> {
+        let mut parameters = MetadataParameters::default();
+        parameters.insert(
+            SAMPLE_RATE_KEY.into(),
+            Parameter::Integer(SAMPLE_RATE.into()),
+        );
+        parameters.insert(CHUNK_KEY.into(), Parameter::Integer(chunk));
+        parameters.insert(START_MS_KEY.into(), Parameter::Integer(sample_ms(sent)));
+        parameters.insert(SOURCE_KEY.into(), Parameter::String(source_name.into()));
+        sent += samples.len();
+        chunk += 1;
+        node.send_output(output.clone(), parameters, Float32Array::from(samples))?;
+        Ok(())
+    };
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let elapsed = started.elapsed();
+                    let mut more = source.read(elapsed, &mut pending);
+                    if max_secs > 0.0 && elapsed.as_secs_f64() >= max_secs {
+                        more = false;
+                    }
+                    while pending.len() >= chunk_len {
+                        let rest = pending.split_off(chunk_len);
+                        send_chunk(&mut node, std::mem::replace(&mut pending, rest))?;
+                    }
+                    if !more {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    // the last chunk is usually shorter
+    if !pending.is_empty() {
+        send_chunk(&mut node, pending)?;
+    }
+    println!(
+        "sent {:.1} s of audio in {chunk} chunks",
+        sent as f64 / SAMPLE_RATE as f64
+    );
+    Ok(())
+}
+
+fn open_microphone() -> eyre::Result<Source> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or_eyre("no default input device")?;
+    let supported = device.default_input_config()?;
+    println!(
+        "recording `{}` at {} Hz, {} channels, {}",
+        device.name().unwrap_or_default(),
+        supported.sample_rate().0,
+        supported.channels(),
+        supported.sample_format()
+    );
+    let config: cpal::StreamConfig = supported.config();
+    let (sender, samples) = mpsc::channel();
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => build_stream::<f32>(&device, &config, sender),
+        SampleFormat::I16 => build_stream::<i16>(&device, &config, sender),
+        SampleFormat::U16 => build_stream::<u16>(&device, &config, sender),
+        SampleFormat::I32 => build_stream::<i32>(&device, &config, sender),
+        other => bail!("unsupported sample format {other}"),
+    }?;
+    stream.play()?;
+    Ok(Source::Microphone {
+        _stream: stream,
+        samples,
+    })
+}
+
+/// Mixes every buffer down to mono, resamples it, and sends it to the node.
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sender: mpsc::Sender<Vec<f32>>,
+) -> eyre::Result<cpal::Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = config.channels as usize;
+    let mut resampler = Resampler::new(config.sample_rate.0);
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let mono: Vec<f32> = data
+                .chunks(channels)
+                .map(|frame| {
+                    frame
+                        .iter()
+                        .map(|sample| sample.to_sample::<f32>())
+                        .sum::<f32>()
+                        / channels as f32
+                })
+                .collect();
+            let mut samples = Vec::with_capacity(mono.len());
+            resampler.process(&mono, &mut samples);
+            // fails only once the node stopped reading
+            let _ = sender.send(samples);
+        },
+        |err| eprintln!("audio input error: {err}"),
+        None,
+    )?;
+    Ok(stream)
+}
+
+fn open_wav() -> eyre::Result<Source> {
+    let path: PathBuf = env_or("WAV_FILE", "out/synthetic.wav".into())?;
+    if !path.exists() {
+        let secs: f32 = env_or("SYNTHETIC_SECS", 12.0)?;
+        println!("writing {secs} s of synthetic speech to {}", path.display());
+        write_wav(&path, &synthetic_speech(secs))?;
+    }
+    let samples = read_wav(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    println!(
+        "replaying {:.1} s of {}",
+        samples.len() as f64 / SAMPLE_RATE as f64,
+        path.display()
+    );
+    Ok(Source::Wav { samples, sent: 0 })
+}
@@ -0,0 +1,158 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{Context, bail};
+use std::{f32::consts::TAU, path::Path};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// The sample rate that whisper expects, and that the capture node resamples to.
+pub const SAMPLE_RATE: u32 = 16_000;
+
+/// Metadata keys of the `audio` output of the capture node.
+pub const SAMPLE_RATE_KEY: &str = "sample_rate";
+pub const CHUNK_KEY: &str = "chunk";
+/// Metadata key of the source of the audio, `microphone` or `wav`, kept until the sink.
+pub const SOURCE_KEY: &str = "source";
+/// Metadata keys of the position of an audio chunk or a transcript in the stream, in
+/// milliseconds since the capture started.
+pub const START_MS_KEY: &str = "start_ms";
+pub const END_MS_KEY: &str = "end_ms";
+/// Metadata keys of the `text` output of the transcriber.
+pub const WINDOW_KEY: &str = "window";
+pub const INFERENCE_MS_KEY: &str = "inference_ms";
+
+/// The position of sample `sample` of the stream, in milliseconds.
+pub fn sample_ms(sample: usize) -> i64 {
+    (sample as u64 * 1000 / SAMPLE_RATE as u64) as i64
+}
+
+/// Converts a stream from `from` Hz to [`SAMPLE_RATE`] by linear interpolation.
+///
+/// Keeps the position and the last sample between calls, so that a stream can be resampled
+/// in buffers of any size without clicks at their borders.
+pub struct Resampler {
+    /// Input samples per output sample.
+    step: f64,
+    /// The position of the next output sample, relative to `previous`.
+    position: f64,
+    previous: f32,
+}
+
+impl Resampler {
+    pub fn new(from: u32) -> Self {
+        Self {
+            step: from as f64 / SAMPLE_RATE as f64,
+            position: 0.0,
+            previous: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        for sample in input {
+            // output samples between `previous` and `sample`
+            while self.position < 1.0 {
+                let t = self.position as f32;
+                output.push(self.previous + t * (sample - self.previous));
+                self.position += self.step;
+            }
+            self.position -= 1.0;
+            self.previous = *sample;
+        }
+    }
+}
+
+/// Reads a WAV file, mixed down to mono and resampled to [`SAMPLE_RATE`].
+pub fn read_wav(path: &Path) -> eyre::Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+    let mono: Vec<f32> = interleaved
+        .chunks(spec.channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    let mut samples = Vec::with_capacity(mono.len());
+    Resampler::new(spec.sample_rate).process(&mono, &mut samples);
+    Ok(samples)
+}
+
+/// Writes mono samples at [`SAMPLE_RATE`] as a 16 bit WAV file.
+pub fn write_wav(path: &Path, samples: &[f32]) -> eyre::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    for sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+/// `secs` seconds of audio that resembles speech in its rhythm, for machines without a
+/// microphone: bursts of a few hundred milliseconds with a gliding pitch and its overtones,
+/// separated by short pauses, and longer pauses every few bursts, like between sentences.
+///
+/// It contains no words, so whisper transcribes it as silence or noise, if at all, but it
+/// exercises the whole pipeline with a deterministic stream.
+pub fn synthetic_speech(secs: f32) -> Vec<f32> {
+    let len = (secs * SAMPLE_RATE as f32) as usize;
+    let mut samples = vec![0.0; len];
+    let mut start = SAMPLE_RATE as usize / 2;
+    let mut burst = 0;
+    while start < len {
+        let duration = SAMPLE_RATE as usize * (200 + 70 * (burst % 4)) / 1000;
+        let pitch = 110.0 + 25.0 * (burst % 5) as f32;
+        let mut phase = 0.0;
+        for i in 0..duration.min(len - start) {
+            let t = i as f32 / duration as f32;
+            // rises and falls, like the pitch of a syllable
+            let frequency = pitch * (1.0 + 0.15 * (t * TAU / 2.0).sin());
+            phase += TAU * frequency / SAMPLE_RATE as f32;
+            let voice: f32 = (1..=4)
+                .map(|harmonic| (phase * harmonic as f32).sin() / harmonic as f32)
+                .sum();
+            let envelope = (t * TAU / 2.0).sin();
+            samples[start + i] = 0.3 * envelope * voice;
+        }
+        burst += 1;
+        let pause = if burst % 5 == 0 { 700 } else { 120 };
+        start += duration + SAMPLE_RATE as usize * pause / 1000;
+    }
+    samples
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
+
+pub fn string_parameter<'a>(
+    parameters: &'a MetadataParameters,
+    key: &str,
+) -> eyre::Result<&'a str> {
+    match parameters.get(key) {
+        Some(Parameter::String(value)) => Ok(value),
+        Some(other) => bail!("expected string `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
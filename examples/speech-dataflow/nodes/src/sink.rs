@@ -0,0 +1,82 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::AsArray};
+use eyre::OptionExt;
+use serde::Serialize;
+use speech_dataflow_nodes::{
+    END_MS_KEY, INFERENCE_MS_KEY, SOURCE_KEY, START_MS_KEY, WINDOW_KEY, env_or, integer_parameter,
+    string_parameter, write_json,
+};
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize)]
+struct Transcript {
+    window: i64,
+    start_ms: i64,
+    end_ms: i64,
+    inference_ms: i64,
+    text: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct SinkReport {
+    /// `microphone` or `wav`, see `capture.rs`.
+    source: Option<String>,
+    transcripts: Vec<Transcript>,
+}
+
+/// Prints the `text` of the transcriber as it arrives, and writes all of it to `REPORT_FILE`
+/// once `text` is closed.
+fn main() -> eyre::Result<()> {
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/transcript.json".into())?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = SinkReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "text" => {
+                    let parameters = &metadata.parameters;
+                    let text = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?
+                        .iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let transcript = Transcript {
+                        window: integer_parameter(parameters, WINDOW_KEY)?,
+                        start_ms: integer_parameter(parameters, START_MS_KEY)?,
+                        end_ms: integer_parameter(parameters, END_MS_KEY)?,
+                        inference_ms: integer_parameter(parameters, INFERENCE_MS_KEY)?,
+                        text,
+                    };
+                    let seconds = |ms: i64| ms as f64 / 1000.0;
+                    println!(
+                        "{:>6.1} s - {:>6.1} s: {}",
+                        seconds(transcript.start_ms),
+                        seconds(transcript.end_ms),
+                        if transcript.text.is_empty() {
+                            "(nothing)"
+                        } else {
+                            &transcript.text
+                        }
+                    );
+                    report.source = Some(string_parameter(parameters, SOURCE_KEY)?.to_owned());
+                    report.transcripts.push(transcript);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "text" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    write_json(&report_file, &report)?;
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter,
+    arrow::{
+        array::{AsArray, StringArray},
+        datatypes::Float32Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::{Context, OptionExt, bail};
+use speech_dataflow_nodes::{
+    END_MS_KEY, INFERENCE_MS_KEY, SAMPLE_RATE, SAMPLE_RATE_KEY, SOURCE_KEY, START_MS_KEY,
+    WINDOW_KEY, env_or, integer_parameter, sample_ms, string_parameter,
+};
+use std::{path::PathBuf, time::Instant};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+/// Transcribes the `audio` chunks of the capture node with whisper, in windows of
+/// `WINDOW_SECS`, and sends the text of each window on `text`.
+///
+/// whisper needs a few seconds of context to recognize words, so it can't transcribe every
+/// chunk on its own. The window is a trade-off between latency and accuracy. Each `text` has
+/// the start and end of its window in the stream, and the time the transcription took, which
+/// has to stay below the length of the window to keep up with real time.
+///
+/// Loads the ggml model in `MODEL_PATH`, e.g. `ggml-tiny.en.bin` from
+/// <https://huggingface.co/ggerganov/whisper.cpp>. Transcribes the last, partial window once
+/// `audio` is closed.
+fn main() -> eyre::Result<()> {
+    let model_path: PathBuf = env_or("MODEL_PATH", "models/ggml-tiny.en.bin".into())?;
+    let language: String = env_or("LANGUAGE", "en".to_owned())?;
+    let window_secs: f64 = env_or("WINDOW_SECS", 5.0)?;
+    let threads: i32 = env_or("THREADS", 4)?;
+    let window_len = (window_secs * SAMPLE_RATE as f64) as usize;
+    let output = DataId::from("text".to_owned());
+
+    if !model_path.exists() {
+        bail!(
+            "no whisper model at {}, download one from \
+             https://huggingface.co/ggerganov/whisper.cpp",
+            model_path.display()
+        );
+    }
+    // whisper.cpp logs every step of loading the model otherwise
+    whisper_rs::install_logging_hooks();
+    let context = WhisperContext::new_with_params(
+        &model_path.to_string_lossy(),
+        WhisperContextParameters::default(),
+    )
+    .with_context(|| format!("failed to load {}", model_path.display()))?;
+    let mut state = context.create_state()?;
+    println!("loaded {}", model_path.display());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut window: Vec<f32> = Vec::with_capacity(window_len);
+    // the position of the first sample of `window` in the stream
+    let mut window_start = 0;
+    let mut windows = 0;
+    let mut source = String::new();
+    let mut transcribe =
+        |node: &mut DoraNode, samples: &[f32], start: usize, source: &str| -> eyre::Result<()> {
+            let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+            params.set_language(Some(&language));
+            params.set_n_threads(threads);
+            // every window is transcribed on its own
+            params.set_no_context(true);
+            params.set_print_progress(false);
+            params.set_print_realtime(false);
+            params.set_print_special(false);
+            params.set_print_timestamps(false);
+
+            let started = Instant::now();
+            state.full(params, samples)?;
+            let mut text = String::new();
+            for segment in 0..state.full_n_segments()? {
+                text.push_str(&state.full_get_segment_text_lossy(segment)?);
+            }
+            let inference_ms = started.elapsed().as_millis() as i64;
+
+            let text = text.trim();
+            let (start_ms, end_ms) = (sample_ms(start), sample_ms(start + samples.len()));
+            println!("[{start_ms} ms - {end_ms} ms] `{text}` in {inference_ms} ms");
+            let mut parameters = MetadataParameters::default();
+            parameters.insert(WINDOW_KEY.into(), Parameter::Integer(windows));
+            parameters.insert(START_MS_KEY.into(), Parameter::Integer(start_ms));
+            parameters.insert(END_MS_KEY.into(), Parameter::Integer(end_ms));
+            parameters.insert(INFERENCE_MS_KEY.into(), Parameter::Integer(inference_ms));
+            parameters.insert(SOURCE_KEY.into(), Parameter::String(source.into()));
+            node.send_output(output.clone(), parameters, StringArray::from(vec![text]))?;
+            windows += 1;
+            Ok(())
+        };
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "audio" => {
+                    let sample_rate = integer_parameter(&metadata.parameters, SAMPLE_RATE_KEY)?;
+                    if sample_rate != SAMPLE_RATE as i64 {
+                        bail!("expected audio at {SAMPLE_RATE} Hz, got {sample_rate} Hz");
+                    }
+                    let start_ms = integer_parameter(&metadata.parameters, START_MS_KEY)?;
+                    let expected_ms = sample_ms(window_start + window.len());
+                    if start_ms != expected_ms {
+                        // transcribed anyway, but the timestamps of the text are off
+                        eprintln!("expected a chunk at {expected_ms} ms, got one at {start_ms} ms");
+                    }
+                    source = string_parameter(&metadata.parameters, SOURCE_KEY)?.to_owned();
+                    let samples = data
+                        .as_primitive_opt::<Float32Type>()
+                        .ok_or_eyre("expected a Float32 array")?
+                        .values();
+
+                    // a chunk can complete a window and start the next one
+                    let mut samples: &[f32] = samples;
+                    while !samples.is_empty() {
+                        let take = (window_len - window.len()).min(samples.len());
+                        window.extend_from_slice(&samples[..take]);
+                        samples = &samples[take..];
+                        if window.len() == window_len {
+                            transcribe(&mut node, &window, window_start, &source)?;
+                            window_start += window.len();
+                            window.clear();
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "audio" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if !window.is_empty() {
+        transcribe(&mut node, &window, window_start, &source)?;
+    }
+    Ok(())
+}
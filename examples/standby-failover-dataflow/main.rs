@@ -0,0 +1,93 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Upper bound for the time between the last result of the primary and the first result
+/// of the standby, heartbeat timeout (250 ms) plus check interval (20 ms) plus some slack.
+const MAX_GAP_MS: f64 = 500.0;
+/// Time after the primary started at which it is killed.
+const KILL_AFTER: Duration = Duration::from_secs(4);
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("standby-failover-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    std::fs::create_dir_all("out")?;
+    let pid_file = Path::new("out/primary.pid");
+    let result_file = Path::new("out/failover.json");
+    for file in [pid_file, result_file] {
+        if file.exists() {
+            std::fs::remove_file(file)?;
+        }
+    }
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    let dataflow_task = tokio::spawn(dora.run_dataflow(dataflow));
+
+    // wait for the primary to come up, let it run for a while, then kill it
+    let start = Instant::now();
+    let pid = loop {
+        if let Ok(pid) = std::fs::read_to_string(pid_file) {
+            break pid;
+        }
+        if start.elapsed() > Duration::from_secs(60) {
+            bail!("primary did not start");
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    };
+    tokio::time::sleep(KILL_AFTER).await;
+    tracing::info!("killing primary (pid {pid})");
+    let status = tokio::process::Command::new("kill")
+        .args(["-9", pid.trim()])
+        .status()
+        .await?;
+    if !status.success() {
+        bail!("failed to kill primary");
+    }
+
+    // the dataflow reports an error because the primary was killed, the consumer's summary
+    // tells whether the failover worked
+    if let Err(err) = dataflow_task.await? {
+        tracing::info!("dataflow finished with error (expected after killing the primary): {err}");
+    }
+
+    let summary: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(result_file).context("consumer did not write a summary")?,
+    )?;
+    println!("failover summary: {summary}");
+    let Some(gap_ms) = summary["takeover_gap_ms"].as_f64() else {
+        bail!("the standby never took over");
+    };
+    if summary["missing"]
+        .as_array()
+        .is_none_or(|missing| !missing.is_empty())
+    {
+        bail!("results are missing: {}", summary["missing"]);
+    }
+    if summary["mismatches"] != 0 {
+        bail!(
+            "{} results differ from the expected state",
+            summary["mismatches"]
+        );
+    }
+    if gap_ms > MAX_GAP_MS {
+        bail!("takeover gap of {gap_ms:.1} ms exceeds {MAX_GAP_MS} ms");
+    }
+    println!(
+        "standby took over at seq {} after a gap of {gap_ms:.1} ms ({} duplicate results)",
+        summary["takeover_seq"], summary["duplicates"]
+    );
+
+    Ok(())
+}
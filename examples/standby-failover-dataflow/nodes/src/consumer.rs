@@ -0,0 +1,86 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::Context;
+use standby_failover_dataflow_nodes::{State, seq, value};
+use std::{collections::BTreeMap, time::Instant};
+
+/// Receives the results of both primary and standby and checks the handover.
+///
+/// The `reading` input is used to compute the expected results independently of the
+/// stateful nodes. A summary is written to `RESULT_FILE` for the runner.
+fn main() -> eyre::Result<()> {
+    let result_file = std::env::var("RESULT_FILE").context("RESULT_FILE is required")?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut readings = BTreeMap::new();
+    let mut delivered = BTreeMap::new();
+    let mut duplicates = 0;
+    let mut last_primary: Option<Instant> = None;
+    let mut first_standby: Option<(Instant, i64)> = None;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let seq = seq(&metadata.parameters)?;
+                let value = value(&data)?;
+                match id.as_str() {
+                    "reading" => {
+                        readings.insert(seq, value);
+                        continue;
+                    }
+                    "primary" => last_primary = Some(Instant::now()),
+                    "standby" => {
+                        if first_standby.is_none() {
+                            println!("first result from standby: seq {seq}");
+                            first_standby = Some((Instant::now(), seq));
+                        }
+                    }
+                    other => {
+                        eprintln!("Ignoring unexpected input `{other}`");
+                        continue;
+                    }
+                }
+                if delivered.insert(seq, value).is_some() {
+                    duplicates += 1;
+                }
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let mut expected = State::default();
+    let mut missing = Vec::new();
+    let mut mismatches = 0;
+    for (seq, reading) in &readings {
+        let mean = expected.apply(*reading);
+        match delivered.get(seq) {
+            Some(value) if (value - mean).abs() > 1e-9 => mismatches += 1,
+            Some(_) => {}
+            None => missing.push(*seq),
+        }
+    }
+
+    // the gap is the time between the last result of the primary and the first result of
+    // the standby, as seen by the consumer
+    let takeover_gap_ms = match (last_primary, first_standby) {
+        (Some(primary), Some((standby, _))) => {
+            Some(standby.saturating_duration_since(primary).as_secs_f64() * 1000.0)
+        }
+        _ => None,
+    };
+    let summary = serde_json::json!({
+        "readings": readings.len(),
+        "delivered": delivered.len(),
+        "duplicates": duplicates,
+        "missing": missing,
+        "mismatches": mismatches,
+        "takeover_seq": first_standby.map(|(_, seq)| seq),
+        "takeover_gap_ms": takeover_gap_ms,
+    });
+    println!("{summary}");
+    std::fs::write(&result_file, summary.to_string())
+        .with_context(|| format!("failed to write {result_file}"))?;
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use dora_node_api::{ArrowData, MetadataParameters, Parameter};
+use eyre::{Context, eyre};
+
+/// Every reading and result carries the sequence number of the source reading.
+pub fn seq(parameters: &MetadataParameters) -> eyre::Result<i64> {
+    match parameters.get("seq") {
+        Some(Parameter::Integer(seq)) => Ok(*seq),
+        other => Err(eyre!("expected integer `seq` parameter, got {other:?}")),
+    }
+}
+
+pub fn parameters(seq: i64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert("seq".into(), Parameter::Integer(seq));
+    parameters
+}
+
+pub fn value(data: &ArrowData) -> eyre::Result<f64> {
+    f64::try_from(data).context("expected a single float64 value")
+}
+
+/// The state that primary and standby keep in sync: a running mean over all readings.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct State {
+    pub count: u64,
+    pub sum: f64,
+}
+
+impl State {
+    pub fn apply(&mut self, reading: f64) -> f64 {
+        self.count += 1;
+        self.sum += reading;
+        self.mean()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    /// Encodes the state for the heartbeat, as `[count, sum]`.
+    pub fn to_vec(self) -> Vec<f64> {
+        vec![self.count as f64, self.sum]
+    }
+
+    pub fn from_slice(values: &[f64]) -> eyre::Result<Self> {
+        match values {
+            [count, sum] => Ok(Self {
+                count: *count as u64,
+                sum: *sum,
+            }),
+            other => Err(eyre!("expected `[count, sum]`, got {other:?}")),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::{Context, bail, eyre};
+use standby_failover_dataflow_nodes::{State, parameters, seq, value};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// A reading and the state right after applying it.
+struct Entry {
+    seq: i64,
+    reading: f64,
+    state: State,
+}
+
+/// Stateful node that runs either as `primary` or as `standby`, selected by `ROLE`.
+///
+/// Both consume the same readings. The primary publishes results and sends a heartbeat with
+/// its state on every `tick`. The standby only publishes after the heartbeat has been missing
+/// for `HEARTBEAT_TIMEOUT_MS`. It then first replays the results that the primary did not
+/// confirm in its last heartbeat, so that no reading is skipped at the handover.
+fn main() -> eyre::Result<()> {
+    let role = std::env::var("ROLE").context("ROLE is required")?;
+    let primary = match role.as_str() {
+        "primary" => true,
+        "standby" => false,
+        other => bail!("unknown ROLE `{other}`, expected `primary` or `standby`"),
+    };
+    let timeout = Duration::from_millis(
+        std::env::var("HEARTBEAT_TIMEOUT_MS")
+            .unwrap_or_else(|_| "250".to_owned())
+            .parse()
+            .context("invalid HEARTBEAT_TIMEOUT_MS")?,
+    );
+    if let Ok(pid_file) = std::env::var("PID_FILE") {
+        // lets the runner kill this node to simulate a crash
+        std::fs::write(&pid_file, std::process::id().to_string())
+            .with_context(|| format!("failed to write {pid_file}"))?;
+    }
+    let result_output = DataId::from("result".to_owned());
+    let heartbeat_output = DataId::from("heartbeat".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut state = State::default();
+    let mut history: VecDeque<Entry> = VecDeque::new();
+    let mut last_seq = -1;
+    let mut active = primary;
+    // the standby gives the primary one timeout from startup to send its first heartbeat
+    let mut last_heartbeat = Instant::now();
+    let mut confirmed_seq = -1;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "reading" => {
+                    let seq = seq(&metadata.parameters)?;
+                    let reading = value(&data)?;
+                    let mean = state.apply(reading);
+                    last_seq = seq;
+                    history.push_back(Entry {
+                        seq,
+                        reading,
+                        state,
+                    });
+                    if history.len() > 1000 {
+                        history.pop_front();
+                    }
+                    if active {
+                        node.send_output(
+                            result_output.clone(),
+                            parameters(seq),
+                            mean.into_arrow(),
+                        )?;
+                    }
+                }
+                "tick" if primary => {
+                    node.send_output(
+                        heartbeat_output.clone(),
+                        parameters(last_seq),
+                        Float64Array::from(state.to_vec()),
+                    )?;
+                }
+                "tick" => {
+                    if !active && last_heartbeat.elapsed() > timeout {
+                        println!(
+                            "no heartbeat for {:?}, taking over after seq {confirmed_seq}",
+                            last_heartbeat.elapsed()
+                        );
+                        active = true;
+                        for entry in history.iter().filter(|entry| entry.seq > confirmed_seq) {
+                            node.send_output(
+                                result_output.clone(),
+                                parameters(entry.seq),
+                                entry.state.mean().into_arrow(),
+                            )?;
+                        }
+                    }
+                }
+                "heartbeat" => {
+                    last_heartbeat = Instant::now();
+                    confirmed_seq = seq(&metadata.parameters)?;
+                    let values = data
+                        .as_primitive_opt::<Float64Type>()
+                        .ok_or_else(|| eyre!("expected float64 heartbeat"))?;
+                    let primary_state = State::from_slice(values.values())?;
+
+                    // compare with our own state at the same reading and adopt the primary's
+                    // state if they diverged, e.g. because the standby dropped a reading
+                    if let Some(position) = history.iter().position(|e| e.seq == confirmed_seq) {
+                        if history[position].state != primary_state {
+                            eprintln!(
+                                "state diverged at seq {confirmed_seq}: {:?} != {primary_state:?}, resyncing",
+                                history[position].state
+                            );
+                            state = primary_state;
+                            history[position].state = state;
+                            for entry in history.iter_mut().skip(position + 1) {
+                                state.apply(entry.reading);
+                                entry.state = state;
+                            }
+                        }
+                        // confirmed entries are not needed for a replay anymore
+                        history.drain(..position);
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                // the timer never closes, so stop once the readings end
+                if id.as_str() == "reading" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "{role} processed {} readings, mean {:.4}",
+        state.count,
+        state.mean()
+    );
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use dora_tracing::set_up_tracing;
+use example_node_utils::now_us;
+use example_runner_utils::{Control, Dora};
+use eyre::{Context, OptionExt, bail};
+use std::{
+    fmt::Write as _,
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// Often enough to time the startup steps to a few milliseconds.
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+struct Args {
+    runs: usize,
+    /// Number of nodes of the benchmarked chains, including source and sink.
+    sizes: Vec<usize>,
+}
+
+impl Args {
+    fn parse() -> eyre::Result<Self> {
+        let mut args = Self {
+            runs: 5,
+            sizes: vec![2, 5, 10],
+        };
+        let mut raw = std::env::args().skip(1);
+        while let Some(arg) = raw.next() {
+            match arg.as_str() {
+                "--runs" => {
+                    let runs = raw.next().ok_or_eyre("--runs requires a value")?;
+                    args.runs = runs.parse().wrap_err("invalid --runs")?;
+                }
+                "--sizes" => {
+                    let sizes = raw.next().ok_or_eyre("--sizes requires a value")?;
+                    args.sizes = sizes
+                        .split(',')
+                        .map(|size| size.trim().parse())
+                        .collect::<Result<_, _>>()
+                        .wrap_err("invalid --sizes")?;
+                }
+                other => bail!("unknown argument `{other}`"),
+            }
+        }
+        if args.sizes.iter().any(|&size| size < 2) {
+            bail!("a topology needs at least 2 nodes (source and sink)");
+        }
+        Ok(args)
+    }
+}
+
+/// Timings of a single cold start, in milliseconds.
+struct Sample {
+    /// Daemon process spawned → daemon registered at the coordinator.
+    daemon_connect: f64,
+    /// `dora start` → last node process running.
+    node_spawn: f64,
+    /// Last node process running → first message arrived at the sink.
+    first_output: f64,
+    /// `dora start` → first message arrived at the sink.
+    total: f64,
+}
+
+/// A column of the report, and how to read it from a sample.
+type Phase = (&'static str, fn(&Sample) -> f64);
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("startup-benchmark-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let args = Args::parse()?;
+    std::fs::create_dir_all("out")?;
+
+    // measure the `dora` binary directly, `cargo run` would add its own startup time
+    let checkout = Dora::from_env()?;
+    let dora = checkout.build_cli().await?;
+    let mut dataflows = Vec::new();
+    for &size in &args.sizes {
+        let dataflow = write_chain_dataflow(size)?;
+        checkout.build_dataflow(&dataflow).await?;
+        dataflows.push((size, dataflow));
+    }
+
+    let mut report = String::new();
+    writeln!(
+        report,
+        "{:<6} {:<16} {:>10} {:>10} {:>10}",
+        "nodes", "phase", "min ms", "median ms", "max ms"
+    )?;
+    for (size, dataflow) in dataflows {
+        let mut samples = Vec::new();
+        for run in 0..args.runs {
+            let dora = dora.clone();
+            let dataflow = dataflow.clone();
+            let sample =
+                tokio::task::spawn_blocking(move || cold_start(&dora, &dataflow, size, run))
+                    .await??;
+            tracing::info!(
+                "{size} nodes, run {run}: total {:.1} ms (daemon connect {:.1} ms)",
+                sample.total,
+                sample.daemon_connect
+            );
+            samples.push(sample);
+        }
+
+        let phases: [Phase; 4] = [
+            ("daemon connect", |s| s.daemon_connect),
+            ("node spawn", |s| s.node_spawn),
+            ("first output", |s| s.first_output),
+            ("start → sink", |s| s.total),
+        ];
+        for (name, phase) in phases {
+            let mut values: Vec<f64> = samples.iter().map(phase).collect();
+            values.sort_by(f64::total_cmp);
+            writeln!(
+                report,
+                "{size:<6} {name:<16} {:>10.1} {:>10.1} {:>10.1}",
+                values[0],
+                values[values.len() / 2],
+                values[values.len() - 1]
+            )?;
+        }
+    }
+
+    println!("\n{report}");
+    std::fs::write("out/report.txt", report)?;
+    Ok(())
+}
+
+/// Starts a fresh coordinator and daemon, runs the dataflow once, and shuts everything down.
+fn cold_start(dora: &Path, dataflow: &Path, size: usize, run: usize) -> eyre::Result<Sample> {
+    let result_file = result_file(size);
+    if result_file.exists() {
+        std::fs::remove_file(&result_file)?;
+    }
+
+    let interface_port =
+        port_check::free_local_ipv4_port_in_range(10000..=15000).ok_or_eyre("No available port")?;
+    let control_port = port_check::free_local_ipv4_port_in_range((interface_port + 1)..=15000)
+        .ok_or_eyre("No available port")?;
+    let localhost = Ipv4Addr::LOCALHOST.to_string();
+
+    let mut processes = Processes(Vec::new());
+    processes.0.push(
+        Command::new(dora)
+            .arg("coordinator")
+            .args(["--interface", &localhost, "--control-interface", &localhost])
+            .args(["--port", &interface_port.to_string()])
+            .args(["--control-port", &control_port.to_string()])
+            .stdout(Stdio::null())
+            .spawn()
+            .context("failed to spawn coordinator")?,
+    );
+    let mut control = Control::connect(
+        SocketAddr::from((Ipv4Addr::LOCALHOST, control_port)),
+        Duration::from_secs(30),
+    )?;
+
+    let daemon_spawned = Instant::now();
+    processes.0.push(
+        Command::new(dora)
+            .arg("daemon")
+            .args(["--coordinator-addr", &localhost])
+            .args(["--coordinator-port", &interface_port.to_string()])
+            .stdout(Stdio::null())
+            .spawn()
+            .context("failed to spawn daemon")?,
+    );
+    control
+        .wait_until(Duration::from_secs(30), POLL_INTERVAL, |c| {
+            Ok(c.connected_daemons()?.len() == 1)
+        })
+        .wrap_err("daemon did not connect to the coordinator")?;
+    let daemon_connect = daemon_spawned.elapsed();
+
+    let start_us = now_us();
+    let status = Command::new(dora)
+        .arg("start")
+        .arg(dataflow)
+        .arg("--detach")
+        .args(["--coordinator-addr", &localhost])
+        .args(["--coordinator-port", &interface_port.to_string()])
+        .stdout(Stdio::null())
+        .status()?;
+    if !status.success() {
+        bail!("failed to start dataflow (run {run})");
+    }
+
+    // the sink writes its measurements as soon as the first message arrived
+    let waiting = Instant::now();
+    while !result_file.exists() {
+        if waiting.elapsed() > Duration::from_secs(60) {
+            bail!("sink did not report a result (run {run})");
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    control.wait_until(Duration::from_secs(30), POLL_INTERVAL, |c| {
+        Ok(c.running_dataflows()? == 0)
+    })?;
+    control.destroy()?;
+    processes.wait()?;
+
+    let result: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&result_file)?)?;
+    let started_at = result["started_at_us"]
+        .as_object()
+        .ok_or_eyre("result is missing `started_at_us`")?;
+    if started_at.len() != size {
+        bail!(
+            "expected start times of {size} nodes, got {}",
+            started_at.len()
+        );
+    }
+    let last_started_us = started_at
+        .values()
+        .filter_map(|t| t.as_u64())
+        .max()
+        .ok_or_eyre("no start times in result")?;
+    let received_at_us = result["received_at_us"]
+        .as_u64()
+        .ok_or_eyre("result is missing `received_at_us`")?;
+
+    let ms = |from: u64, to: u64| (to as f64 - from as f64) / 1000.0;
+    Ok(Sample {
+        daemon_connect: daemon_connect.as_secs_f64() * 1000.0,
+        node_spawn: ms(start_us, last_started_us),
+        first_output: ms(last_started_us, received_at_us),
+        total: ms(start_us, received_at_us),
+    })
+}
+
+/// Kills the coordinator and daemon if a run fails half-way.
+struct Processes(Vec<Child>);
+
+impl Processes {
+    fn wait(&mut self) -> eyre::Result<()> {
+        for mut child in self.0.drain(..) {
+            child.wait()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Processes {
+    fn drop(&mut self) {
+        for child in &mut self.0 {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+fn result_file(size: usize) -> PathBuf {
+    std::env::current_dir()
+        .unwrap()
+        .join(format!("out/result-{size}.json"))
+}
+
+/// Writes `out/chain-<size>.yml`: a source, `size - 2` relays, and a sink.
+fn write_chain_dataflow(size: usize) -> eyre::Result<PathBuf> {
+    let mut yaml = String::from("nodes:\n");
+    writeln!(
+        yaml,
+        "    - id: source\n      build: cargo build --release --manifest-path ../nodes/Cargo.toml\n      path: ../nodes/target/release/source\n      outputs:\n          - data\n"
+    )?;
+    let mut upstream = "source".to_owned();
+    for i in 1..size - 1 {
+        let id = format!("relay-{i}");
+        writeln!(
+            yaml,
+            "    - id: {id}\n      path: ../nodes/target/release/relay\n      inputs:\n          data: {upstream}/data\n      outputs:\n          - data\n"
+        )?;
+        upstream = id;
+    }
+    writeln!(
+        yaml,
+        "    - id: sink\n      path: ../nodes/target/release/sink\n      inputs:\n          data: {upstream}/data\n      env:\n          RESULT_FILE: {}",
+        result_file(size).display()
+    )?;
+
+    let path = PathBuf::from(format!("out/chain-{size}.yml"));
+    std::fs::write(&path, yaml)?;
+    Ok(path)
+}
@@ -0,0 +1,41 @@
+use dora_node_api::{MetadataParameters, Parameter};
+
+pub use example_node_utils::now_us;
+
+/// Metadata key of the process start times of all nodes the message passed through.
+pub const STARTED_AT_KEY: &str = "started_at_us";
+/// Metadata key of the ids of these nodes, in the same order.
+pub const NODES_KEY: &str = "nodes";
+/// Metadata key of the time at which the source sent its first output.
+pub const FIRST_OUTPUT_KEY: &str = "first_output_us";
+
+/// Returns a copy of `parameters` with the start time of the current node appended, as
+/// [`now_us`] timestamp.
+pub fn append_start(
+    parameters: &MetadataParameters,
+    node_id: &str,
+    started_at_us: u64,
+) -> MetadataParameters {
+    // integer parameters are signed
+    let started_at_us = started_at_us as i64;
+    let mut parameters = parameters.clone();
+    match parameters.get_mut(STARTED_AT_KEY) {
+        Some(Parameter::ListInt(times)) => times.push(started_at_us),
+        _ => {
+            parameters.insert(
+                STARTED_AT_KEY.into(),
+                Parameter::ListInt(vec![started_at_us]),
+            );
+        }
+    }
+    match parameters.get_mut(NODES_KEY) {
+        Some(Parameter::ListString(nodes)) => nodes.push(node_id.to_owned()),
+        _ => {
+            parameters.insert(
+                NODES_KEY.into(),
+                Parameter::ListString(vec![node_id.to_owned()]),
+            );
+        }
+    }
+    parameters
+}
@@ -0,0 +1,26 @@
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+use startup_benchmark_nodes::{append_start, now_us};
+
+fn main() -> eyre::Result<()> {
+    let started_at_us = now_us();
+    let output = DataId::from("data".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "data" => {
+                    let parameters = append_start(&metadata.parameters, &node_id, started_at_us);
+                    node.send_output(output.clone(), parameters, data.0)?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
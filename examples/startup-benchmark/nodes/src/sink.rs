@@ -0,0 +1,54 @@
+use dora_node_api::{self, DoraNode, Event, Parameter};
+use eyre::{Context, bail};
+use startup_benchmark_nodes::{FIRST_OUTPUT_KEY, NODES_KEY, STARTED_AT_KEY, append_start, now_us};
+
+fn main() -> eyre::Result<()> {
+    let started_at_us = now_us();
+    let result_file = std::env::var("RESULT_FILE").context("RESULT_FILE is required")?;
+
+    let (node, mut events) = DoraNode::init_from_env()?;
+    let node_id = node.id().to_string();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata,
+                data: _,
+            } => {
+                let received_at_us = now_us();
+                let parameters = append_start(&metadata.parameters, &node_id, started_at_us);
+                let (
+                    Some(Parameter::ListString(nodes)),
+                    Some(Parameter::ListInt(started_at)),
+                    Some(Parameter::Integer(first_output_us)),
+                ) = (
+                    parameters.get(NODES_KEY),
+                    parameters.get(STARTED_AT_KEY),
+                    parameters.get(FIRST_OUTPUT_KEY),
+                )
+                else {
+                    bail!("input `{id}` is missing the timing parameters");
+                };
+                let started_at: serde_json::Map<_, _> = nodes
+                    .iter()
+                    .cloned()
+                    .zip(started_at.iter().map(|&t| t.into()))
+                    .collect();
+                let result = serde_json::json!({
+                    "started_at_us": started_at,
+                    "first_output_us": first_output_us,
+                    "received_at_us": received_at_us,
+                });
+                std::fs::write(&result_file, result.to_string())
+                    .with_context(|| format!("failed to write {result_file}"))?;
+                println!("first message arrived after passing {} nodes", nodes.len());
+                break;
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use dora_node_api::{self, DoraNode, IntoArrow, Parameter, dora_core::config::DataId};
+use startup_benchmark_nodes::{FIRST_OUTPUT_KEY, append_start, now_us};
+
+fn main() -> eyre::Result<()> {
+    let started_at_us = now_us();
+    let output = DataId::from("data".to_owned());
+
+    let (mut node, _events) = DoraNode::init_from_env()?;
+
+    // send right away: `init_from_env` only returns once all nodes of the dataflow are ready
+    let mut parameters = append_start(&Default::default(), &node.id().to_string(), started_at_us);
+    parameters.insert(FIRST_OUTPUT_KEY.into(), Parameter::Integer(now_us() as i64));
+    node.send_output(output, parameters, 0u64.into_arrow())?;
+
+    Ok(())
+}
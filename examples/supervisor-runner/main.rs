@@ -0,0 +1,182 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, OptionExt, bail};
+use serde::Serialize;
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("supervisor-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let mut args = std::env::args().skip(1);
+    let mut policy = RestartPolicy::default();
+    let mut dataflow = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-restarts" => {
+                let value = args.next().ok_or_eyre("--max-restarts requires a value")?;
+                policy.max_restarts = value.parse()?;
+            }
+            path => dataflow = Some(path.to_owned()),
+        }
+    }
+
+    let dora = Dora::from_env()?;
+    if let Some(dataflow) = dataflow {
+        let dataflow = Path::new(&dataflow);
+        dora.build_dataflow(dataflow).await?;
+        return supervise(&dora, dataflow, &policy).await;
+    }
+
+    // the worker crashes on its first two attempts, so three restarts are enough
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    supervise(&dora, dataflow, &policy).await?;
+
+    // with a single restart, the retry budget is exhausted before the worker recovers
+    let strict = RestartPolicy {
+        max_restarts: 1,
+        ..RestartPolicy::default()
+    };
+    let expected = format!("failed {} times, giving up", strict.max_restarts + 1);
+    match supervise(&dora, dataflow, &strict).await {
+        Ok(()) => bail!("supervisor did not give up after exhausting its retry budget"),
+        Err(err) if err.to_string().contains(&expected) => {
+            tracing::info!("supervisor gave up as expected");
+        }
+        Err(err) => return Err(err.wrap_err("supervisor failed for another reason than giving up")),
+    }
+
+    Ok(())
+}
+
+struct RestartPolicy {
+    /// Number of restarts after the initial start before giving up.
+    max_restarts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    /// A run that lasted at least this long resets the backoff delay and the retry budget.
+    stable_after: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            stable_after: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Machine-readable supervisor events, printed as one JSON object per line.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum SupervisorEvent<'a> {
+    Started {
+        dataflow: &'a Path,
+        attempt: u32,
+    },
+    Exited {
+        attempt: u32,
+        success: bool,
+        exit_code: Option<i32>,
+        runtime_ms: u128,
+    },
+    Restarting {
+        attempt: u32,
+        backoff_ms: u128,
+        restarts_left: u32,
+    },
+    GaveUp {
+        attempts: u32,
+    },
+    Completed {
+        attempts: u32,
+    },
+    Interrupted {
+        attempt: u32,
+    },
+}
+
+impl SupervisorEvent<'_> {
+    fn emit(&self) {
+        println!("{}", serde_json::to_string(self).unwrap());
+    }
+}
+
+/// Runs the dataflow until it finishes successfully, restarting it with exponential backoff
+/// whenever it fails.
+async fn supervise(dora: &Dora, dataflow: &Path, policy: &RestartPolicy) -> eyre::Result<()> {
+    let mut attempt = 0;
+    let mut restarts_left = policy.max_restarts;
+    let mut backoff = policy.initial_backoff;
+
+    loop {
+        attempt += 1;
+        SupervisorEvent::Started { dataflow, attempt }.emit();
+
+        let started = Instant::now();
+        let mut child = dataflow_command(dora, dataflow, attempt)
+            .spawn()
+            .wrap_err("failed to spawn dora daemon")?;
+        let status = tokio::select! {
+            status = child.wait() => status?,
+            _ = tokio::signal::ctrl_c() => {
+                // don't restart a dataflow that the user wants to stop
+                child.kill().await?;
+                SupervisorEvent::Interrupted { attempt }.emit();
+                return Ok(());
+            }
+        };
+        let runtime = started.elapsed();
+        SupervisorEvent::Exited {
+            attempt,
+            success: status.success(),
+            exit_code: status.code(),
+            runtime_ms: runtime.as_millis(),
+        }
+        .emit();
+
+        if status.success() {
+            SupervisorEvent::Completed { attempts: attempt }.emit();
+            return Ok(());
+        }
+
+        if runtime >= policy.stable_after {
+            restarts_left = policy.max_restarts;
+            backoff = policy.initial_backoff;
+        }
+        if restarts_left == 0 {
+            SupervisorEvent::GaveUp { attempts: attempt }.emit();
+            bail!("dataflow failed {attempt} times, giving up");
+        }
+        restarts_left -= 1;
+
+        SupervisorEvent::Restarting {
+            attempt: attempt + 1,
+            backoff_ms: backoff.as_millis(),
+            restarts_left,
+        }
+        .emit();
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(policy.max_backoff);
+    }
+}
+
+fn dataflow_command(dora: &Dora, dataflow: &Path, attempt: u32) -> tokio::process::Command {
+    let mut cmd = dora.cli(["daemon", "--run-dataflow"]);
+    cmd.arg(dataflow);
+    // inherited by all nodes spawned by the daemon
+    cmd.env("SUPERVISOR_ATTEMPT", attempt.to_string());
+    cmd.kill_on_drop(true);
+    cmd
+}
@@ -0,0 +1,41 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use example_node_utils::env_or;
+use eyre::bail;
+
+/// Number of ticks after which the worker crashes on a failing attempt.
+const CRASH_AFTER: u64 = 20;
+/// Number of ticks after which the worker finishes successfully.
+const TOTAL_TICKS: u64 = 50;
+
+fn main() -> eyre::Result<()> {
+    // set by the supervisor for every (re)start of the dataflow
+    let attempt: u32 = env_or("SUPERVISOR_ATTEMPT", 1)?;
+    // number of attempts that crash before the worker starts to succeed
+    let fail_attempts: u32 = env_or("FAIL_ATTEMPTS", 0)?;
+    let output = DataId::from("count".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    println!("worker started (attempt {attempt})");
+
+    for count in 1..=TOTAL_TICKS {
+        let event = match events.recv() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            Event::Input { id, .. } if id.as_str() == "tick" => {
+                node.send_output(output.clone(), Default::default(), count.into_arrow())?;
+                if count == CRASH_AFTER && attempt <= fail_attempts {
+                    bail!("simulated crash after {count} ticks (attempt {attempt})");
+                }
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("worker finished (attempt {attempt})");
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter,
+    arrow::{array::AsArray, datatypes::Float64Type},
+};
+use eyre::{bail, eyre};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Number of samples per summary line.
+const WINDOW: usize = 100;
+
+#[derive(Default)]
+struct StreamStats {
+    samples: u64,
+    flagged: BTreeSet<i64>,
+    window: Vec<f64>,
+    window_means: Vec<f64>,
+}
+
+impl StreamStats {
+    fn flush_window(&mut self, name: &str) {
+        let n = self.window.len() as f64;
+        let mean = self.window.iter().sum::<f64>() / n;
+        let std_dev = (self.window.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+        println!(
+            "{name}: samples {:>5}..{:<5} mean {mean:>8.3} std {std_dev:>7.3}",
+            self.samples as usize - self.window.len(),
+            self.samples
+        );
+        self.window_means.push(mean);
+        self.window.clear();
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let expected: u64 = std::env::var("SAMPLES")
+        .ok()
+        .and_then(|samples| samples.parse().ok())
+        .unwrap_or(0);
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut streams: BTreeMap<String, StreamStats> = BTreeMap::new();
+    let mut reported_anomalies = BTreeSet::new();
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                if id.as_str() == "sensor_anomalies" {
+                    reported_anomalies.insert(u64::try_from(&data)? as i64);
+                    continue;
+                }
+
+                let values = data
+                    .as_primitive_opt::<Float64Type>()
+                    .ok_or_else(|| eyre!("expected float64 data"))?;
+                let stream = streams.entry(id.to_string()).or_default();
+                let Some(Parameter::Integer(seq)) = metadata.parameters.get("seq") else {
+                    bail!("`{id}` sample without `seq` parameter");
+                };
+                if *seq as u64 != stream.samples {
+                    bail!(
+                        "`{id}` skipped samples: expected {}, got {seq}",
+                        stream.samples
+                    );
+                }
+                if let Some(Parameter::ListInt(shape)) = metadata.parameters.get("shape")
+                    && shape.iter().product::<i64>() as usize != values.len()
+                {
+                    bail!(
+                        "`{id}` sample of length {} has shape {shape:?}",
+                        values.len()
+                    );
+                }
+                if let Some(Parameter::Bool(true)) = metadata.parameters.get("anomaly") {
+                    stream.flagged.insert(*seq);
+                }
+
+                stream.samples += 1;
+                let mean = values.values().iter().sum::<f64>() / values.len() as f64;
+                stream.window.push(mean);
+                if stream.window.len() == WINDOW {
+                    stream.flush_window(id.as_str());
+                }
+            }
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    for (name, stream) in &streams {
+        if expected != 0 && stream.samples != expected {
+            bail!(
+                "`{name}` sent {} samples, expected {expected}",
+                stream.samples
+            );
+        }
+        if let (Some(first), Some(last)) = (stream.window_means.first(), stream.window_means.last())
+        {
+            println!(
+                "{name}: {} samples, {} anomalies, mean drifted from {first:.3} to {last:.3}",
+                stream.samples,
+                stream.flagged.len()
+            );
+        }
+    }
+
+    let Some(sensor) = streams.get("sensor") else {
+        bail!("no samples received from `sensor`");
+    };
+    if sensor.flagged != reported_anomalies {
+        bail!("anomaly flags in the metadata don't match the `anomalies` output");
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{CargoBuild, Dora};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path, process::Stdio, time::Duration};
+use tokio::process::Command;
+
+const DORA_TAP: &str = "nodes/target/release/dora-tap";
+/// The example to tap.
+const TAPPED: &str = "../rust-dataflow/dataflow.yml";
+/// Number of values that `rust-node` of the tapped example sends.
+const RANDOM_VALUES: u64 = 100;
+
+/// Subset of `TapSummary` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct TapSummary {
+    inputs: BTreeMap<String, InputSummary>,
+}
+
+/// Subset of `InputSummary` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct InputSummary {
+    messages: u64,
+    data_type: String,
+    rows: usize,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("tap-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    CargoBuild::new("tap-dataflow-nodes")
+        .manifest_path("nodes/Cargo.toml")
+        .build()
+        .await?;
+
+    // a copy of the rust-dataflow example with a `tap` node on all of its outputs
+    let status = Command::new(DORA_TAP)
+        .args(["attach", TAPPED])
+        .status()
+        .await?;
+    if !status.success() {
+        bail!("dora-tap attach failed with {status}");
+    }
+    let tapped = Path::new("../rust-dataflow/dataflow.tap.yml");
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(tapped).await?;
+    let dataflow = tokio::spawn(dora.run_dataflow(tapped));
+    let watched = watch().await;
+    let ran = dataflow.await?;
+    std::fs::remove_file(tapped)?;
+    watched?;
+    ran?;
+
+    let summary: TapSummary = serde_json::from_str(
+        &std::fs::read_to_string("out/tap.json").context("dora-tap did not write a summary")?,
+    )?;
+    // the tap only sees the messages sent after it connected, so it may miss the first ones
+    for (input, data_type, max_messages) in [
+        ("rust-node.random", "UInt64", RANDOM_VALUES),
+        ("rust-status-node.status", "Utf8", RANDOM_VALUES),
+    ] {
+        let Some(summary) = summary.inputs.get(input) else {
+            bail!("dora-tap received nothing on `{input}`");
+        };
+        if summary.data_type != data_type || summary.rows != 1 {
+            bail!(
+                "expected single {data_type} values on `{input}`, dora-tap saw {} rows of {}",
+                summary.rows,
+                summary.data_type
+            );
+        }
+        if summary.messages == 0 || summary.messages > max_messages {
+            bail!(
+                "expected up to {max_messages} messages on `{input}`, dora-tap saw {}",
+                summary.messages
+            );
+        }
+        println!(
+            "dora-tap saw {} {data_type} messages on `{input}`",
+            summary.messages
+        );
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Runs `dora-tap watch` until the tapped dataflow closes its inputs.
+///
+/// The tap node can only connect while the dataflow is running, so this retries until it
+/// connects.
+async fn watch() -> eyre::Result<()> {
+    for _ in 0..300 {
+        let output = Command::new(DORA_TAP)
+            .args(["watch", "--rows", "1", "--summary", "out/tap.json"])
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(Duration::from_secs(60), output)
+            .await
+            .context("dora-tap did not exit")??;
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.contains("failed to connect") {
+            bail!("dora-tap watch failed with {}: {stderr}", output.status);
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    bail!("dora-tap could not connect to the dataflow");
+}
@@ -0,0 +1,273 @@
+use clap::{Parser, Subcommand};
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::Array,
+        util::display::{ArrayFormatter, FormatOptions},
+    },
+    dora_core::config::NodeId,
+};
+use eyre::{Context, OptionExt};
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tap_dataflow_nodes::{InputSummary, TapSummary, attach, input_id};
+
+/// Prints the messages on the edges of a running dataflow, like tcpdump.
+///
+/// dora only delivers messages to the inputs that a dataflow declares, so tapping takes two
+/// steps: `attach` writes a copy of a dataflow with a dynamic `tap` node that subscribes to the
+/// edges, and `watch` connects as that node while the copy runs.
+#[derive(Debug, Parser)]
+#[command(name = "dora-tap")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Writes a copy of DATAFLOW with a dynamic tap node that subscribes to EDGES.
+    Attach {
+        dataflow: PathBuf,
+        /// Outputs to tap, e.g. `camera/image`. Taps every output of the dataflow if none is
+        /// given.
+        edges: Vec<String>,
+        /// Id of the tap node.
+        #[arg(long, default_value = "tap")]
+        node: String,
+        /// Where to write the copy. Defaults to `<name>.tap.yml` next to DATAFLOW, where the
+        /// relative paths of the dataflow stay valid.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Connects as the tap node of a running dataflow and prints what it receives.
+    Watch {
+        /// Id of the tap node.
+        #[arg(long, default_value = "tap")]
+        node: String,
+        /// Only prints the messages of these edges, e.g. `camera/image`. Can be repeated.
+        #[arg(long = "edge")]
+        edges: Vec<String>,
+        /// Number of values to print of each message, 0 to only print the rates.
+        #[arg(long, default_value_t = 3)]
+        rows: usize,
+        /// Seconds between two tables of the message rates, 0 for none.
+        #[arg(long, default_value_t = 1.0)]
+        interval: f64,
+        /// Exits after receiving this many messages.
+        #[arg(long)]
+        count: Option<u64>,
+        /// Writes what it received per input as JSON to this file when it exits.
+        #[arg(long)]
+        summary: Option<PathBuf>,
+    },
+}
+
+fn main() -> eyre::Result<()> {
+    match Cli::parse().command {
+        Command::Attach {
+            dataflow,
+            edges,
+            node,
+            output,
+        } => {
+            let mut descriptor: serde_yaml::Value = serde_yaml::from_str(
+                &std::fs::read_to_string(&dataflow)
+                    .with_context(|| format!("failed to read {}", dataflow.display()))?,
+            )?;
+            let edges = attach(&mut descriptor, &node, &edges)?;
+            let output = match output {
+                Some(output) => output,
+                None => {
+                    let stem = dataflow
+                        .file_stem()
+                        .ok_or_eyre("DATAFLOW has no file name")?;
+                    dataflow.with_file_name(format!("{}.tap.yml", stem.to_string_lossy()))
+                }
+            };
+            std::fs::write(&output, serde_yaml::to_string(&descriptor)?)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            for edge in &edges {
+                println!("{edge} -> {node}/{}", input_id(edge));
+            }
+            println!(
+                "wrote {}, start it and run `dora-tap watch`",
+                output.display()
+            );
+            Ok(())
+        }
+        Command::Watch {
+            node,
+            edges,
+            rows,
+            interval,
+            count,
+            summary,
+        } => {
+            let inputs: Vec<_> = edges.iter().map(|edge| input_id(edge)).collect();
+            let options = WatchOptions {
+                inputs,
+                rows,
+                interval: (interval > 0.0).then(|| Duration::from_secs_f64(interval)),
+                count,
+            };
+            let tap_summary = watch(&node, &options)?;
+            if let Some(summary) = summary {
+                if let Some(parent) = summary.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&summary, serde_json::to_string_pretty(&tap_summary)?)
+                    .with_context(|| format!("failed to write {}", summary.display()))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+struct WatchOptions {
+    /// Inputs to print the messages of, all if empty.
+    inputs: Vec<String>,
+    rows: usize,
+    interval: Option<Duration>,
+    count: Option<u64>,
+}
+
+#[derive(Default)]
+struct InputStats {
+    summary: InputSummary,
+    first: Option<Instant>,
+    last: Option<Instant>,
+    /// Messages and bytes since the last rate table.
+    window: (u64, u64),
+}
+
+fn watch(node: &str, options: &WatchOptions) -> eyre::Result<TapSummary> {
+    // connecting fails while the tapped dataflow isn't running
+    let (_node, mut events) = DoraNode::init_from_node_id(NodeId::from(node.to_owned()))
+        .with_context(|| format!("failed to connect as dynamic node `{node}`"))?;
+    println!("connected as `{node}`");
+
+    let started = Instant::now();
+    let mut last_table = started;
+    let mut inputs: BTreeMap<String, InputStats> = BTreeMap::new();
+    let mut received = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let now = Instant::now();
+                let stats = inputs.entry(id.to_string()).or_default();
+                let array: &dyn Array = &**data;
+                let data_type = array.data_type().to_string();
+                if stats.summary.data_type != data_type {
+                    // the first message, or the sender changed its schema
+                    println!("{id}: {data_type}");
+                    stats.summary.data_type = data_type;
+                }
+                let bytes = array.get_array_memory_size() as u64;
+                stats.summary.messages += 1;
+                stats.summary.bytes += bytes;
+                stats.summary.rows = array.len();
+                stats.first.get_or_insert(now);
+                stats.last = Some(now);
+                stats.window.0 += 1;
+                stats.window.1 += bytes;
+
+                let shown = options.inputs.is_empty() || options.inputs.contains(&id.to_string());
+                if shown && options.rows > 0 {
+                    let parameters: Vec<_> = metadata
+                        .parameters
+                        .iter()
+                        .map(|(key, value)| format!("{key}: {value:?}"))
+                        .collect();
+                    println!(
+                        "{:>9.3}s {id} #{} {} rows {}{}",
+                        (now - started).as_secs_f64(),
+                        stats.summary.messages,
+                        array.len(),
+                        preview(array, options.rows),
+                        if parameters.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" {{{}}}", parameters.join(", "))
+                        }
+                    );
+                }
+
+                received += 1;
+                if options.count.is_some_and(|count| received >= count) {
+                    break;
+                }
+            }
+            Event::InputClosed { id } => println!("{id}: closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+        if let Some(interval) = options.interval
+            && last_table.elapsed() >= interval
+        {
+            print_rates(&mut inputs, last_table.elapsed());
+            last_table = Instant::now();
+        }
+    }
+
+    let mut summary = TapSummary::default();
+    for (id, stats) in inputs {
+        let mut input = stats.summary;
+        if let (Some(first), Some(last)) = (stats.first, stats.last)
+            && input.messages > 1
+        {
+            input.rate_hz = Some((input.messages - 1) as f64 / (last - first).as_secs_f64());
+        }
+        println!(
+            "{id}: {} messages, {} bytes, {}{}",
+            input.messages,
+            input.bytes,
+            input.data_type,
+            input
+                .rate_hz
+                .map(|rate| format!(", {rate:.1} msg/s"))
+                .unwrap_or_default()
+        );
+        summary.inputs.insert(id, input);
+    }
+    Ok(summary)
+}
+
+/// The first `rows` values of `array`.
+fn preview(array: &dyn Array, rows: usize) -> String {
+    let options = FormatOptions::default();
+    let Ok(formatter) = ArrayFormatter::try_new(array, &options) else {
+        return "[?]".to_owned();
+    };
+    let mut values: Vec<_> = (0..array.len().min(rows))
+        .map(|index| {
+            let value = formatter.value(index).to_string();
+            match value.char_indices().nth(60) {
+                Some((end, _)) => format!("{}...", &value[..end]),
+                None => value,
+            }
+        })
+        .collect();
+    if array.len() > rows {
+        values.push("...".to_owned());
+    }
+    format!("[{}]", values.join(", "))
+}
+
+/// Prints the message rate of each input since the last table.
+fn print_rates(inputs: &mut BTreeMap<String, InputStats>, elapsed: Duration) {
+    let seconds = elapsed.as_secs_f64();
+    println!("--- last {seconds:.1} s ---");
+    for (id, stats) in inputs {
+        let (messages, bytes) = std::mem::take(&mut stats.window);
+        println!(
+            "{id:<40} {:>8.1} msg/s {:>10.1} KiB/s  {}",
+            messages as f64 / seconds,
+            bytes as f64 / 1024.0 / seconds,
+            stats.summary.data_type
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use eyre::{OptionExt, bail};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+
+/// The outputs of all nodes of `dataflow`, as `<node>/<output>`, or as
+/// `<node>/<operator>/<output>` for the operators of runtime nodes with an `operators` list.
+pub fn declared_edges(dataflow: &Value) -> eyre::Result<Vec<String>> {
+    let nodes = dataflow["nodes"]
+        .as_sequence()
+        .ok_or_eyre("dataflow has no `nodes`")?;
+    let outputs = |value: &Value| -> Vec<String> {
+        value["outputs"]
+            .as_sequence()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_owned)
+            .collect()
+    };
+
+    let mut edges = Vec::new();
+    for node in nodes {
+        let id = node["id"].as_str().ok_or_eyre("node without `id`")?;
+        // the outputs of a single `operator` belong to the node
+        for output in outputs(node).into_iter().chain(outputs(&node["operator"])) {
+            edges.push(format!("{id}/{output}"));
+        }
+        for operator in node["operators"].as_sequence().into_iter().flatten() {
+            let operator_id = operator["id"]
+                .as_str()
+                .ok_or_eyre("operator without `id`")?;
+            for output in outputs(operator) {
+                edges.push(format!("{id}/{operator_id}/{output}"));
+            }
+        }
+    }
+    Ok(edges)
+}
+
+/// The id of the input of the tap node that subscribes to `edge`, e.g. `camera.image` for
+/// `camera/image`.
+pub fn input_id(edge: &str) -> String {
+    edge.replace('/', ".")
+}
+
+/// Adds the dynamic node `tap_id` to `dataflow`, with an input for each of `edges`, or for
+/// every declared output if `edges` is empty. Returns the tapped edges.
+pub fn attach(dataflow: &mut Value, tap_id: &str, edges: &[String]) -> eyre::Result<Vec<String>> {
+    let declared = declared_edges(dataflow)?;
+    let edges = if edges.is_empty() {
+        declared
+    } else {
+        for edge in edges {
+            if !declared.contains(edge) {
+                bail!("the dataflow has no output `{edge}`, it has {declared:?}");
+            }
+        }
+        edges.to_vec()
+    };
+    if edges.is_empty() {
+        bail!("the dataflow has no outputs to tap");
+    }
+
+    let nodes = dataflow["nodes"]
+        .as_sequence_mut()
+        .ok_or_eyre("dataflow has no `nodes`")?;
+    if nodes.iter().any(|node| node["id"] == tap_id) {
+        bail!("the dataflow already has a node `{tap_id}`");
+    }
+    let mut inputs = Mapping::new();
+    for edge in &edges {
+        inputs.insert(input_id(edge).into(), edge.as_str().into());
+    }
+    let mut tap = Mapping::new();
+    tap.insert("id".into(), tap_id.into());
+    tap.insert("path".into(), "dynamic".into());
+    tap.insert("inputs".into(), inputs.into());
+    nodes.push(tap.into());
+    Ok(edges)
+}
+
+/// What `dora-tap` saw on one input, written with `--summary`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InputSummary {
+    pub messages: u64,
+    /// Memory size of the received arrays.
+    pub bytes: u64,
+    /// The Arrow data type of the last message.
+    pub data_type: String,
+    /// Number of rows of the last message.
+    pub rows: usize,
+    /// Messages per second between the first and the last message.
+    pub rate_hz: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TapSummary {
+    /// By input id, see [`input_id`].
+    pub inputs: BTreeMap<String, InputSummary>,
+}
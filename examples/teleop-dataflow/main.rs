@@ -0,0 +1,132 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, RosDistro, read_json};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Replayed instead of a gamepad, which CI doesn't have. It waits a bit for the discovery of
+/// turtlesim, drives forward, turns left in place, drives forward in turbo, and releases the
+/// deadman button.
+const JOY_SCRIPT: &str = "joy-script.jsonl";
+
+/// Where turtlesim starts `turtle1`, facing right.
+const START: (f64, f64) = (5.544, 5.544);
+
+/// Subset of `GamepadReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct GamepadReport {
+    source: String,
+    commands: u64,
+    stops: u64,
+    max_linear: f64,
+    max_angular: f64,
+}
+
+/// Subset of `BridgeReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct BridgeReport {
+    published: u64,
+    timeouts: u64,
+    poses: u64,
+    last_pose: Option<TurtlePose>,
+    distance: f64,
+}
+
+/// Subset of `TurtlePose` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct TurtlePose {
+    x: f64,
+    y: f64,
+    theta: f64,
+    linear_velocity: f64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("teleop-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let ros = RosDistro::detect()?;
+    // pass `--skip-install` where turtlesim can't be installed with apt, e.g. in CI without sudo
+    let skip_install = std::env::args().any(|arg| arg == "--skip-install");
+    ros.ensure_packages(&["turtlesim"], skip_install).await?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    // the dataflow sources `ROS` in its build command
+    let dora = Dora::from_env()?
+        .env("ROS", ros.setup_script())
+        .env("REPLAY_FILE", JOY_SCRIPT);
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    // a fresh turtlesim, with `turtle1` at the start
+    let mut turtlesim = ros
+        .command("ros2 run turtlesim turtlesim_node")
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start turtlesim")?;
+
+    let result = dora.run_dataflow(dataflow).await;
+    turtlesim.kill().await?;
+    result?;
+
+    let gamepad: GamepadReport = read_json("out/gamepad.json")?;
+    println!(
+        "gamepad: {} commands and {} stops from {}, up to {:.2} m/s and {:.2} rad/s",
+        gamepad.commands, gamepad.stops, gamepad.source, gamepad.max_linear, gamepad.max_angular
+    );
+    if gamepad.source != JOY_SCRIPT {
+        bail!("expected the gamepad to replay {JOY_SCRIPT}");
+    }
+    if gamepad.stops != 1 {
+        bail!("expected a single stop when the deadman button is released");
+    }
+    // the script holds the stick at 1 in turbo, and at -1 on the right stick
+    let close = |value: f64, expected: f64| (value - expected).abs() <= 1e-9;
+    if !close(gamepad.max_linear, 4.0) || !close(gamepad.max_angular, 2.0) {
+        bail!("expected up to 4 m/s in turbo and 2 rad/s, `cmd_vel` doesn't follow the sticks");
+    }
+
+    let bridge: BridgeReport = read_json("out/bridge.json")?;
+    println!(
+        "bridge: {} commands, {} timeouts, {} poses, moved {:.2}",
+        bridge.published, bridge.timeouts, bridge.poses, bridge.distance
+    );
+    if bridge.published != gamepad.commands {
+        bail!(
+            "the gamepad sent {} commands, the bridge published {}",
+            gamepad.commands,
+            bridge.published
+        );
+    }
+    if bridge.timeouts != 0 {
+        bail!("expected no timeouts, the gamepad stops the turtle itself");
+    }
+    let Some(pose) = bridge.last_pose else {
+        bail!("no poses of the turtle, did turtlesim start?");
+    };
+    println!(
+        "turtle1 ended at ({:.2}, {:.2}), facing {:.2} rad",
+        pose.x, pose.y, pose.theta
+    );
+    // about 1.3 to the right, a turn of 1.6 rad to the left, and 2 up
+    if pose.x - START.0 < 0.8 || pose.y - START.1 < 1.2 {
+        bail!("expected the turtle to drive right and then up");
+    }
+    if !(1.0..=2.2).contains(&pose.theta) {
+        bail!("expected the turtle to face up after turning left");
+    }
+    if pose.linear_velocity.abs() > 0.01 {
+        bail!("expected the turtle to stop after the deadman button was released");
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
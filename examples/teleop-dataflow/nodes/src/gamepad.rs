@@ -0,0 +1,210 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, arrow::array::Float64Array,
+    dora_core::config::DataId,
+};
+use eyre::{Context, eyre};
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::PathBuf,
+    time::Instant,
+};
+use teleop_dataflow_nodes::{GamepadReport, JoyState, JoyStep, TeleopConfig, env_or, write_json};
+
+enum Source {
+    Gamepads {
+        gilrs: Box<Gilrs>,
+        /// The gamepad that was used last.
+        active: Option<GamepadId>,
+    },
+    Replay(Vec<JoyStep>),
+}
+
+/// Reads a gamepad with `gilrs`, and sends a velocity command `[linear, angular]` on `cmd_vel`
+/// on every `tick` while `ENABLE_BUTTON` is held.
+///
+/// The left stick drives and the right stick turns, with up to `MAX_LINEAR` and `MAX_ANGULAR`
+/// at full stick, times `TURBO_SCALE` while `TURBO_BUTTON` is held. Sends a zero command once
+/// when `ENABLE_BUTTON` is released or the gamepad disconnects. Follows the gamepad that was
+/// used last, if several are connected.
+///
+/// Replays the `JoyStep` lines of `REPLAY_FILE` instead of reading a gamepad if it's set, and
+/// stops at its end. Records the gamepad to `RECORD_FILE` in the same format if it's set.
+/// Writes a `GamepadReport` to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let config = TeleopConfig {
+        max_linear: env_or("MAX_LINEAR", 2.0)?,
+        max_angular: env_or("MAX_ANGULAR", 2.0)?,
+        turbo_scale: env_or("TURBO_SCALE", 2.0)?,
+        deadzone: env_or("DEADZONE", 0.1)?,
+    };
+    let enable_button = button(&env_or("ENABLE_BUTTON", "LeftTrigger".to_owned())?)?;
+    let turbo_button = button(&env_or("TURBO_BUTTON", "RightTrigger".to_owned())?)?;
+    let replay_file: String = env_or("REPLAY_FILE", String::new())?;
+    let record_file: String = env_or("RECORD_FILE", String::new())?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/gamepad.json".to_owned())?.into();
+    let output = DataId::from("cmd_vel".to_owned());
+
+    let mut report = GamepadReport::default();
+    let mut source = if replay_file.is_empty() {
+        let gilrs = Gilrs::new().map_err(|err| eyre!("failed to open the gamepads: {err}"))?;
+        for (_, gamepad) in gilrs.gamepads() {
+            println!("found {}", gamepad.name());
+        }
+        report.source = "gilrs".to_owned();
+        Source::Gamepads {
+            gilrs: Box::new(gilrs),
+            active: None,
+        }
+    } else {
+        report.source = replay_file.clone();
+        Source::Replay(read_replay(&replay_file)?)
+    };
+    let mut recorder = match record_file.as_str() {
+        "" => None,
+        path => Some(BufWriter::new(
+            File::create(path).with_context(|| format!("failed to create {path}"))?,
+        )),
+    };
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    let mut last_state = None;
+    let mut enabled = false;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let elapsed_ms = start.elapsed().as_millis() as u64;
+                    let state = match &mut source {
+                        Source::Gamepads { gilrs, active } => {
+                            // reading the events also updates the state of the gamepads
+                            while let Some(gilrs::Event { id, event, .. }) = gilrs.next_event() {
+                                match event {
+                                    EventType::Connected => {
+                                        println!("{} connected", gilrs.gamepad(id).name());
+                                    }
+                                    EventType::Disconnected => {
+                                        println!("{} disconnected", gilrs.gamepad(id).name());
+                                        if *active == Some(id) {
+                                            *active = None;
+                                        }
+                                    }
+                                    _ => *active = Some(id),
+                                }
+                            }
+                            match active.map(|id| gilrs.gamepad(id)) {
+                                Some(gamepad) => {
+                                    if report.gamepad.as_deref() != Some(gamepad.name()) {
+                                        report.gamepad = Some(gamepad.name().to_owned());
+                                    }
+                                    JoyState {
+                                        left_y: gamepad.value(Axis::LeftStickY).into(),
+                                        right_x: gamepad.value(Axis::RightStickX).into(),
+                                        enable: gamepad.is_pressed(enable_button),
+                                        turbo: gamepad.is_pressed(turbo_button),
+                                    }
+                                }
+                                None => JoyState::default(),
+                            }
+                        }
+                        Source::Replay(steps) => {
+                            if steps.last().is_none_or(|last| elapsed_ms > last.at_ms) {
+                                println!("replayed {replay_file}");
+                                break;
+                            }
+                            steps
+                                .iter()
+                                .rev()
+                                .find(|step| step.at_ms <= elapsed_ms)
+                                .map(|step| step.state)
+                                .unwrap_or_default()
+                        }
+                    };
+
+                    if last_state != Some(state) {
+                        if let Some(recorder) = &mut recorder {
+                            let step = JoyStep {
+                                at_ms: elapsed_ms,
+                                state,
+                            };
+                            writeln!(recorder, "{}", serde_json::to_string(&step)?)?;
+                        }
+                        last_state = Some(state);
+                    }
+
+                    match config.cmd_vel(&state) {
+                        Some(command) => {
+                            enabled = true;
+                            send(&mut node, &output, &mut report, command)?;
+                        }
+                        None if enabled => {
+                            enabled = false;
+                            send(&mut node, &output, &mut report, [0.0, 0.0])?;
+                            report.stops += 1;
+                        }
+                        None => {}
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    if enabled {
+        send(&mut node, &output, &mut report, [0.0, 0.0])?;
+        report.stops += 1;
+    }
+    if let Some(mut recorder) = recorder {
+        recorder.flush()?;
+    }
+
+    println!(
+        "sent {} commands and {} stops from {}",
+        report.commands, report.stops, report.source
+    );
+    write_json(&report_file, &report)
+}
+
+fn send(
+    node: &mut DoraNode,
+    output: &DataId,
+    report: &mut GamepadReport,
+    command: [f64; 2],
+) -> eyre::Result<()> {
+    report.max_linear = report.max_linear.max(command[0].abs());
+    report.max_angular = report.max_angular.max(command[1].abs());
+    report.commands += 1;
+    node.send_output(
+        output.clone(),
+        MetadataParameters::default(),
+        Float64Array::from(command.to_vec()),
+    )
+}
+
+/// The button named `name` in `gilrs`, e.g. `LeftTrigger` for the left shoulder button.
+fn button(name: &str) -> eyre::Result<Button> {
+    serde_json::from_value(serde_json::Value::String(name.to_owned()))
+        .with_context(|| format!("unknown gamepad button `{name}`"))
+}
+
+/// The steps of the replay file `path`, ordered by `at_ms`.
+fn read_replay(path: &str) -> eyre::Result<Vec<JoyStep>> {
+    let file = File::open(path).with_context(|| format!("failed to open {path}"))?;
+    let mut steps = Vec::new();
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let step: JoyStep = serde_json::from_str(&line)
+            .with_context(|| format!("{path}:{}: invalid step", number + 1))?;
+        steps.push(step);
+    }
+    steps.sort_by_key(|step| step.at_ms);
+    Ok(steps)
+}
@@ -0,0 +1,147 @@
+use dora_ros2_bridge::{
+    ros2_client::{self, NodeOptions},
+    rustdds::{self, policy},
+};
+use eyre::{Context, eyre};
+use futures::task::SpawnExt;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// What the teleoperation reads from a gamepad, with the sticks in [-1, 1], up and right
+/// positive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JoyState {
+    pub left_y: f64,
+    pub right_x: f64,
+    /// The deadman button, without which the robot doesn't move.
+    pub enable: bool,
+    pub turbo: bool,
+}
+
+/// A line of a `REPLAY_FILE` or `RECORD_FILE` of `gamepad`: the state from `at_ms` after the
+/// start on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct JoyStep {
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub state: JoyState,
+}
+
+/// Maps the sticks to velocity commands, like `teleop_twist_joy` of ROS: the left stick drives
+/// forward and back, the right stick turns.
+#[derive(Debug, Clone, Copy)]
+pub struct TeleopConfig {
+    /// In m/s, at full stick.
+    pub max_linear: f64,
+    /// In rad/s, at full stick.
+    pub max_angular: f64,
+    /// Multiplies both while `turbo` is held.
+    pub turbo_scale: f64,
+    /// Stick deflections below it count as centered, so that a worn stick doesn't creep.
+    pub deadzone: f64,
+}
+
+impl TeleopConfig {
+    /// The command `[linear, angular]` for `state`, or `None` while `enable` isn't held.
+    pub fn cmd_vel(&self, state: &JoyState) -> Option<[f64; 2]> {
+        if !state.enable {
+            return None;
+        }
+        let scale = if state.turbo { self.turbo_scale } else { 1.0 };
+        Some([
+            scale * self.max_linear * self.apply_deadzone(state.left_y),
+            // pushing right turns clockwise, which is negative around z
+            -scale * self.max_angular * self.apply_deadzone(state.right_x),
+        ])
+    }
+
+    /// Rescales the range outside of the deadzone to [-1, 1], so that the command doesn't jump
+    /// at its edge.
+    fn apply_deadzone(&self, value: f64) -> f64 {
+        let magnitude = value.abs().min(1.0);
+        if magnitude < self.deadzone {
+            return 0.0;
+        }
+        value.signum() * (magnitude - self.deadzone) / (1.0 - self.deadzone)
+    }
+}
+
+/// Written by `gamepad` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GamepadReport {
+    /// `gilrs`, or the `REPLAY_FILE`.
+    pub source: String,
+    /// The name of the last gamepad that was used.
+    pub gamepad: Option<String>,
+    /// Commands sent on `cmd_vel`, including the stops.
+    pub commands: u64,
+    /// Zero commands, sent when `enable` was released.
+    pub stops: u64,
+    pub max_linear: f64,
+    pub max_angular: f64,
+}
+
+/// Written by `turtle-bridge` to its `REPORT_FILE`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BridgeReport {
+    pub turtle: String,
+    /// Commands published on `/<turtle>/cmd_vel`.
+    pub published: u64,
+    /// Zero commands published because no command arrived for `CMD_TIMEOUT_MS`.
+    pub timeouts: u64,
+    pub poses: u64,
+    pub first_pose: Option<TurtlePose>,
+    pub last_pose: Option<TurtlePose>,
+    /// The length of the path of the turtle.
+    pub distance: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TurtlePose {
+    pub x: f64,
+    pub y: f64,
+    pub theta: f64,
+    pub linear_velocity: f64,
+    pub angular_velocity: f64,
+}
+
+/// Creates a ROS2 node in the `/dora_teleop` namespace, and spawns its spinner on `pool`.
+///
+/// The spinner handles discovery and other background work of the node.
+pub fn init_ros_node(
+    name: &str,
+    pool: &futures::executor::ThreadPool,
+) -> eyre::Result<ros2_client::Node> {
+    let ros_context = ros2_client::Context::new().unwrap();
+    let mut ros_node = ros_context
+        .new_node(
+            ros2_client::NodeName::new("/dora_teleop", name)
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre!("failed to create ros2 node: {e:?}"))?;
+
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+    Ok(ros_node)
+}
+
+/// Reliable, keeping the last `depth` messages.
+pub fn reliable_qos(depth: i32) -> rustdds::QosPolicies {
+    rustdds::QosPolicyBuilder::new()
+        .durability(policy::Durability::Volatile)
+        .reliability(policy::Reliability::Reliable {
+            max_blocking_time: rustdds::Duration::from_millis(100),
+        })
+        .history(policy::History::KeepLast { depth })
+        .build()
+}
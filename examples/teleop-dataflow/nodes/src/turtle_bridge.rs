@@ -0,0 +1,165 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{array::AsArray, datatypes::Float64Type},
+    merged::{MergeExternal, MergedEvent},
+};
+use dora_ros2_bridge::{
+    messages::{
+        geometry_msgs::msg::{Twist, Vector3},
+        turtlesim::msg::Pose,
+    },
+    ros2_client,
+};
+use eyre::{Context, OptionExt, bail, eyre};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use teleop_dataflow_nodes::{
+    BridgeReport, TurtlePose, env_or, init_ros_node, reliable_qos, write_json,
+};
+
+/// Publishes the velocity commands `[linear, angular]` of `cmd_vel` as `Twist` messages on
+/// `/<TURTLE>/cmd_vel`, and follows the turtle on `/<TURTLE>/pose`.
+///
+/// The gamepad only sends commands while its deadman button is held, and a single stop when it
+/// is released. If that stop gets lost, or the gamepad node dies, the last command would keep the
+/// turtle going, so the bridge checks on every `tick` and publishes a stop itself once no command
+/// arrived for `CMD_TIMEOUT_MS`.
+///
+/// Stops when `cmd_vel` is closed, and writes a `BridgeReport` to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let turtle: String = env_or("TURTLE", "turtle1".to_owned())?;
+    let cmd_timeout = Duration::from_millis(env_or("CMD_TIMEOUT_MS", 500)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/bridge.json".to_owned())?.into();
+
+    let (_node, events) = DoraNode::init_from_env()?;
+    let pool = futures::executor::ThreadPool::new()?;
+    let mut ros_node = init_ros_node("turtle_bridge", &pool)?;
+    let publisher = create_publisher(&mut ros_node, &turtle)?;
+    let pose_reader = create_pose_reader(&mut ros_node, &turtle)?;
+
+    let merged = events.merge_external(Box::pin(pose_reader.async_stream()));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut report = BridgeReport {
+        turtle: turtle.clone(),
+        ..Default::default()
+    };
+    // the time of the last command that moves the turtle, until it's stopped
+    let mut last_command: Option<Instant> = None;
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, data, .. } => match id.as_str() {
+                    "cmd_vel" => {
+                        let command = data
+                            .as_primitive_opt::<Float64Type>()
+                            .ok_or_eyre("expected a Float64 array")?;
+                        let &[linear, angular] = command.values().as_ref() else {
+                            bail!("expected `[linear, angular]`, got {} values", command.len());
+                        };
+                        publish(&publisher, twist(linear, angular))?;
+                        report.published += 1;
+                        let moving = linear != 0.0 || angular != 0.0;
+                        last_command = moving.then(Instant::now);
+                    }
+                    "tick" => {
+                        if last_command.is_some_and(|last| last.elapsed() >= cmd_timeout) {
+                            eprintln!("no command for {cmd_timeout:?}, stopping `{turtle}`");
+                            publish(&publisher, twist(0.0, 0.0))?;
+                            report.timeouts += 1;
+                            last_command = None;
+                        }
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    if id.as_str() == "cmd_vel" {
+                        break;
+                    }
+                }
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(Ok((pose, _))) => {
+                let pose = TurtlePose {
+                    x: pose.x.into(),
+                    y: pose.y.into(),
+                    theta: pose.theta.into(),
+                    linear_velocity: pose.linear_velocity.into(),
+                    angular_velocity: pose.angular_velocity.into(),
+                };
+                if let Some(last) = &report.last_pose {
+                    report.distance += (pose.x - last.x).hypot(pose.y - last.y);
+                }
+                report.first_pose.get_or_insert(pose);
+                report.last_pose = Some(pose);
+                report.poses += 1;
+            }
+            MergedEvent::External(Err(err)) => {
+                eprintln!("failed to read pose of `{turtle}`: {err:?}");
+            }
+        }
+    }
+
+    println!(
+        "published {} commands and {} timeout stops, `{turtle}` moved {:.2}",
+        report.published, report.timeouts, report.distance
+    );
+    write_json(&report_file, &report)
+}
+
+fn twist(linear: f64, angular: f64) -> Twist {
+    Twist {
+        linear: Vector3 {
+            x: linear,
+            ..Default::default()
+        },
+        angular: Vector3 {
+            z: angular,
+            ..Default::default()
+        },
+    }
+}
+
+fn publish(publisher: &ros2_client::Publisher<Twist>, twist: Twist) -> eyre::Result<()> {
+    publisher
+        .publish(twist)
+        .map_err(|e| eyre!("failed to publish velocity command: {e:?}"))
+}
+
+fn create_publisher(
+    ros_node: &mut ros2_client::Node,
+    turtle: &str,
+) -> eyre::Result<ros2_client::Publisher<Twist>> {
+    let topic = ros_node
+        .create_topic(
+            &ros2_client::Name::new(&format!("/{turtle}"), "cmd_vel")
+                .map_err(|e| eyre!("failed to create ROS2 name: {e}"))?,
+            ros2_client::MessageTypeName::new("geometry_msgs", "Twist"),
+            &reliable_qos(1),
+        )
+        .context("failed to create topic")?;
+    ros_node
+        .create_publisher::<Twist>(&topic, None)
+        .context("failed to create publisher")
+}
+
+fn create_pose_reader(
+    ros_node: &mut ros2_client::Node,
+    turtle: &str,
+) -> eyre::Result<ros2_client::Subscription<Pose>> {
+    let topic = ros_node
+        .create_topic(
+            &ros2_client::Name::new(&format!("/{turtle}"), "pose")
+                .map_err(|e| eyre!("failed to create ROS2 name: {e}"))?,
+            ros2_client::MessageTypeName::new("turtlesim", "Pose"),
+            &Default::default(),
+        )
+        .context("failed to create topic")?;
+    ros_node
+        .create_subscription::<Pose>(&topic, None)
+        .context("failed to create subscription")
+}
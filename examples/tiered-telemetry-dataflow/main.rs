@@ -0,0 +1,178 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{path::Path, time::Duration};
+use tokio::{
+    net::TcpStream,
+    process::{Child, Command},
+    time::{Instant, sleep},
+};
+
+/// Must match `COUNT` and `PERIOD_MS` of the vehicle in `dataflow.yml`.
+const SAMPLES: u64 = 1000;
+const VEHICLE_DURATION: Duration = Duration::from_secs(10);
+/// The listen endpoint in `config/cloud.json5`.
+const CLOUD_ADDRESS: &str = "127.0.0.1:7461";
+/// How much the arrival of the batches at the cloud may jitter against their publication.
+const ARRIVAL_JITTER: Duration = Duration::from_millis(100);
+
+/// Subset of `RecorderReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct RecorderReport {
+    rows: u64,
+    raw_bytes: u64,
+    file_bytes: u64,
+}
+
+/// Subset of `UplinkReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct UplinkReport {
+    budget_bytes_per_sec: u64,
+    burst_bytes: u64,
+    summaries: u64,
+    batches: u64,
+    bytes: u64,
+    json_bytes: u64,
+    deferred: u64,
+    dropped: u64,
+}
+
+/// Subset of `CloudReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct CloudReport {
+    batches: Vec<BatchArrival>,
+    complete: bool,
+}
+
+/// Subset of `BatchArrival` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct BatchArrival {
+    at_ms: u64,
+    bytes: u64,
+    summaries: u64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("tiered-telemetry-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    // the cloud is up before the vehicle connects to it
+    let mut cloud = start_cloud().await?;
+    dora.run_dataflow(dataflow).await?;
+    if !cloud.wait().await?.success() {
+        bail!("cloud consumer failed");
+    }
+
+    let recorder: RecorderReport = read_json("out/recorder.json")?;
+    let uplink: UplinkReport = read_json("out/uplink.json")?;
+    let cloud: CloudReport = read_json("out/cloud.json")?;
+    let per_sec = |bytes: u64| bytes as f64 / VEHICLE_DURATION.as_secs_f64();
+    println!(
+        "local: {} samples, {:.0} B/s raw, {} bytes of parquet",
+        recorder.rows,
+        per_sec(recorder.raw_bytes),
+        recorder.file_bytes
+    );
+    println!(
+        "uplink: {} summaries in {} batches, {:.0} B/s ({} bytes of JSON compressed to {}), \
+         budget {} B/s, {} deferred, {} dropped",
+        uplink.summaries,
+        uplink.batches,
+        per_sec(uplink.bytes),
+        uplink.json_bytes,
+        uplink.bytes,
+        uplink.budget_bytes_per_sec,
+        uplink.deferred,
+        uplink.dropped
+    );
+
+    // the local tier is complete, and the cloud tier is the same data, decimated
+    let mut verify = Command::new("nodes/target/release/verify-recording");
+    verify
+        .arg("out/telemetry.parquet")
+        .args(["--rows", &SAMPLES.to_string()])
+        .args(["--cloud-report", "out/cloud.json"]);
+    if !verify.status().await?.success() {
+        bail!("recording verification failed");
+    }
+    if recorder.rows != SAMPLES {
+        bail!("the recorder should record all {SAMPLES} samples");
+    }
+
+    if !cloud.complete {
+        bail!("the last batch of the uplink did not reach the cloud");
+    }
+    let received = |f: fn(&BatchArrival) -> u64| cloud.batches.iter().map(f).sum::<u64>();
+    if uplink.dropped != 0 || received(|b| b.summaries) != uplink.summaries {
+        bail!("the cloud should receive all summaries");
+    }
+    if received(|b| b.bytes) != uplink.bytes {
+        bail!(
+            "the cloud received {} bytes, the uplink published {}",
+            received(|b| b.bytes),
+            uplink.bytes
+        );
+    }
+
+    // over any interval of `t` seconds, at most `burst + budget * t` bytes arrived
+    let budget = uplink.budget_bytes_per_sec as f64;
+    for (i, first) in cloud.batches.iter().enumerate() {
+        let mut bytes = 0;
+        for last in &cloud.batches[i..] {
+            bytes += last.bytes;
+            let interval = Duration::from_millis(last.at_ms - first.at_ms) + ARRIVAL_JITTER;
+            let allowed = uplink.burst_bytes as f64 + budget * interval.as_secs_f64();
+            if bytes as f64 > allowed {
+                bail!(
+                    "{bytes} bytes arrived within {} ms, more than the budget allows",
+                    last.at_ms - first.at_ms
+                );
+            }
+        }
+    }
+    // without the decimation, the budget would be far too small
+    if per_sec(recorder.raw_bytes) < 10.0 * budget {
+        bail!("the raw stream should exceed the budget by far");
+    }
+    println!(
+        "the uplink stayed within its budget of {} B/s, {:.0}x less than the raw stream",
+        uplink.budget_bytes_per_sec,
+        recorder.raw_bytes as f64 / uplink.bytes as f64
+    );
+
+    println!("Everything Done");
+    Ok(())
+}
+
+async fn start_cloud() -> eyre::Result<Child> {
+    let cloud = Command::new("nodes/target/release/cloud-consumer")
+        .args(["--config", "config/cloud.json5"])
+        .args(["--report", "out/cloud.json"])
+        .args(["--timeout-secs", "90"])
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start cloud consumer")?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while TcpStream::connect(CLOUD_ADDRESS).await.is_err() {
+        if Instant::now() > deadline {
+            bail!("the cloud consumer is not listening on {CLOUD_ADDRESS}");
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    Ok(cloud)
+}
@@ -0,0 +1,83 @@
+//! The consumer in the cloud, a zenoh application outside of the dataflow.
+//!
+//! Usage: `cloud-consumer --config <path> [--report <path>] [--timeout-secs <n>]`
+//!
+//! Subscribes to `telemetry/**`, decodes the `UplinkBatch`es of the vehicle, and stops once the
+//! last batch arrived, or after `--timeout-secs`. Then it writes a `CloudReport` with the size
+//! and arrival time of every batch and all summaries to `--report`.
+
+use eyre::{Context, bail, eyre};
+use std::{
+    path::PathBuf,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+use tiered_telemetry_dataflow_nodes::{
+    BatchArrival, CloudReport, UplinkBatch, open_session, write_json,
+};
+use zenoh::Wait;
+
+fn main() -> eyre::Result<()> {
+    let mut config = None;
+    let mut report_file = PathBuf::from("out/cloud.json");
+    let mut timeout = Duration::from_secs(60);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--config" => config = Some(PathBuf::from(value()?)),
+            "--report" => report_file = value()?.into(),
+            "--timeout-secs" => timeout = Duration::from_secs(value()?.parse()?),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    let config = config.ok_or_else(|| {
+        eyre!("usage: cloud-consumer --config <path> [--report <path>] [--timeout-secs <n>]")
+    })?;
+
+    let session = open_session(&config)?;
+    let (tx, rx) = mpsc::channel();
+    let _subscriber = session
+        .declare_subscriber("telemetry/**")
+        .callback(move |sample| {
+            let _ = tx.send((Instant::now(), sample.payload().to_bytes().into_owned()));
+        })
+        .wait()
+        .map_err(|err| eyre!("failed to subscribe: {err}"))?;
+    println!("cloud consumer started as {}", session.zid());
+
+    let mut report = CloudReport::default();
+    let deadline = Instant::now() + timeout;
+    let mut first_arrival = None;
+    while let Ok((arrived, payload)) =
+        rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+    {
+        let batch = UplinkBatch::decode(&payload).context("failed to decode batch")?;
+        let first_arrival = *first_arrival.get_or_insert(arrived);
+        println!(
+            "batch {}: {} summaries in {} bytes",
+            batch.seq,
+            batch.summaries.len(),
+            payload.len()
+        );
+        report.batches.push(BatchArrival {
+            seq: batch.seq,
+            at_ms: arrived.duration_since(first_arrival).as_millis() as u64,
+            bytes: payload.len() as u64,
+            summaries: batch.summaries.len() as u64,
+        });
+        report.summaries.extend(batch.summaries);
+        if batch.last {
+            report.complete = true;
+            break;
+        }
+    }
+
+    if !report.complete {
+        eprintln!("the last batch did not arrive within {timeout:?}");
+    }
+    write_json(&report_file, &report)
+}
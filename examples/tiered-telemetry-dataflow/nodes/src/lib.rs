@@ -0,0 +1,297 @@
+use dora_node_api::arrow::{
+    array::{Array, ArrayRef, AsArray, Float64Array, StructArray, UInt64Array},
+    datatypes::{DataType, Field, Float64Type, UInt64Type},
+};
+use eyre::{Context, OptionExt, eyre};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use zenoh::{Config, Session, Wait};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// The measured channels of a `Sample`, in the order of its columns.
+pub const CHANNELS: [&str; 4] = ["speed_mps", "engine_rpm", "battery_v", "coolant_c"];
+/// The size of a sample in Arrow: `seq`, `timestamp_ms`, and the channels, 8 bytes each.
+pub const SAMPLE_BYTES: u64 = 8 * (2 + CHANNELS.len() as u64);
+
+/// Sent by `vehicle` on `sample` at full rate, and recorded by `recorder`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub seq: u64,
+    /// Vehicle time, since the vehicle started.
+    pub timestamp_ms: u64,
+    /// The values of the `CHANNELS`.
+    pub values: [f64; CHANNELS.len()],
+}
+
+/// Encodes samples as a struct array with the columns `seq`, `timestamp_ms`, and one `Float64`
+/// column per channel, one row per sample.
+pub fn samples_to_arrow(samples: &[Sample]) -> StructArray {
+    let seq: UInt64Array = samples.iter().map(|s| s.seq).collect();
+    let timestamps: UInt64Array = samples.iter().map(|s| s.timestamp_ms).collect();
+    let mut columns = vec![
+        (
+            Arc::new(Field::new("seq", DataType::UInt64, false)),
+            Arc::new(seq) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("timestamp_ms", DataType::UInt64, false)),
+            Arc::new(timestamps) as ArrayRef,
+        ),
+    ];
+    for (i, channel) in CHANNELS.into_iter().enumerate() {
+        let values: Float64Array = samples.iter().map(|s| s.values[i]).collect();
+        columns.push((
+            Arc::new(Field::new(channel, DataType::Float64, false)),
+            Arc::new(values) as ArrayRef,
+        ));
+    }
+    StructArray::from(columns)
+}
+
+pub fn samples_from_arrow(array: &dyn Array) -> eyre::Result<Vec<Sample>> {
+    let array = array
+        .as_struct_opt()
+        .ok_or_eyre("expected a struct array")?;
+    let column = |name: &str| {
+        array
+            .column_by_name(name)
+            .ok_or_else(|| eyre!("missing column `{name}`"))
+    };
+    let seq = column("seq")?
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or_eyre("`seq` is not a UInt64 array")?;
+    let timestamps = column("timestamp_ms")?
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or_eyre("`timestamp_ms` is not a UInt64 array")?;
+    let channels = CHANNELS
+        .into_iter()
+        .map(|channel| {
+            column(channel)?
+                .as_primitive_opt::<Float64Type>()
+                .ok_or_else(|| eyre!("`{channel}` is not a Float64 array"))
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    Ok((0..array.len())
+        .map(|i| Sample {
+            seq: seq.value(i),
+            timestamp_ms: timestamps.value(i),
+            values: std::array::from_fn(|c| channels[c].value(i)),
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub last: f64,
+}
+
+/// The decimated form of the samples of one window, sent by `summarizer` on `summary`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub window_start_ms: u64,
+    pub window_ms: u64,
+    pub samples: u64,
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub channels: BTreeMap<String, ChannelStats>,
+}
+
+/// Summarizes samples into windows of `window_ms` vehicle time, aligned to multiples of it.
+///
+/// Used by `summarizer` on the live samples, and by `verify-recording` on the recorded ones,
+/// which must give the same summaries.
+#[derive(Debug)]
+pub struct Summarizer {
+    window_ms: u64,
+    current: Vec<Sample>,
+}
+
+impl Summarizer {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms: window_ms.max(1),
+            current: Vec::new(),
+        }
+    }
+
+    /// Adds a sample, and returns the summary of the previous window if the sample starts a
+    /// new one.
+    pub fn push(&mut self, sample: Sample) -> Option<Summary> {
+        let window = |s: &Sample| s.timestamp_ms / self.window_ms;
+        let finished = match self.current.first() {
+            Some(first) if window(first) != window(&sample) => self.finish(),
+            _ => None,
+        };
+        self.current.push(sample);
+        finished
+    }
+
+    /// Summarizes the current window, which may be incomplete.
+    pub fn finish(&mut self) -> Option<Summary> {
+        let samples = std::mem::take(&mut self.current);
+        let (first, last) = (samples.first()?, samples.last()?);
+        let channels = CHANNELS
+            .into_iter()
+            .enumerate()
+            .map(|(c, channel)| {
+                let values = samples.iter().map(|s| s.values[c]);
+                let stats = ChannelStats {
+                    min: values.clone().fold(f64::INFINITY, f64::min),
+                    max: values.clone().fold(f64::NEG_INFINITY, f64::max),
+                    mean: values.sum::<f64>() / samples.len() as f64,
+                    last: last.values[c],
+                };
+                (channel.to_owned(), stats)
+            })
+            .collect();
+        Some(Summary {
+            window_start_ms: first.timestamp_ms / self.window_ms * self.window_ms,
+            window_ms: self.window_ms,
+            samples: samples.len() as u64,
+            first_seq: first.seq,
+            last_seq: last.seq,
+            channels,
+        })
+    }
+}
+
+/// Published by `uplink`, as gzip-compressed JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UplinkBatch {
+    pub seq: u64,
+    pub summaries: Vec<Summary>,
+    /// Set on the batch that `uplink` sends when it stops.
+    pub last: bool,
+}
+
+impl UplinkBatch {
+    pub fn encode(&self) -> eyre::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.flush()?;
+        Ok(encoder.finish()?)
+    }
+
+    pub fn decode(payload: &[u8]) -> eyre::Result<Self> {
+        let mut json = Vec::new();
+        GzDecoder::new(payload)
+            .read_to_end(&mut json)
+            .context("invalid gzip payload")?;
+        serde_json::from_slice(&json).context("invalid uplink batch")
+    }
+}
+
+/// Limits the uplink to `rate` bytes per second on average, with bursts of up to `burst`
+/// bytes.
+///
+/// Over any interval of `t` seconds, at most `burst + rate * t` bytes pass.
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u64, burst: u64) -> Self {
+        Self {
+            rate: rate.max(1) as f64,
+            burst: burst as f64,
+            tokens: burst as f64,
+            refilled: Instant::now(),
+        }
+    }
+
+    /// Takes the tokens for `bytes` if there are enough of them.
+    pub fn try_take(&mut self, bytes: u64) -> bool {
+        self.refill();
+        if self.tokens < bytes as f64 {
+            return false;
+        }
+        self.tokens -= bytes as f64;
+        true
+    }
+
+    /// How long until there are enough tokens for `bytes`, or `None` if `bytes` exceeds the
+    /// burst size, so that there never will be.
+    pub fn time_until(&mut self, bytes: u64) -> Option<Duration> {
+        if bytes as f64 > self.burst {
+            return None;
+        }
+        self.refill();
+        let missing = (bytes as f64 - self.tokens).max(0.0);
+        Some(Duration::from_secs_f64(missing / self.rate))
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.refilled = now;
+    }
+}
+
+/// Written by `recorder` when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RecorderReport {
+    pub rows: u64,
+    /// `rows * SAMPLE_BYTES`, what sending the samples uncompressed would take.
+    pub raw_bytes: u64,
+    pub file_bytes: u64,
+}
+
+/// Written by `uplink` when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UplinkReport {
+    pub budget_bytes_per_sec: u64,
+    pub burst_bytes: u64,
+    pub summaries: u64,
+    pub batches: u64,
+    /// Compressed payload bytes that were published.
+    pub bytes: u64,
+    /// Uncompressed JSON bytes of the published batches.
+    pub json_bytes: u64,
+    /// Ticks on which the pending summaries had to wait for the budget.
+    pub deferred: u64,
+    /// Summaries that were dropped because the backlog was full.
+    pub dropped: u64,
+}
+
+/// A batch as received by `cloud-consumer`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchArrival {
+    pub seq: u64,
+    /// Since the first batch arrived.
+    pub at_ms: u64,
+    pub bytes: u64,
+    pub summaries: u64,
+}
+
+/// Written by `cloud-consumer` when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CloudReport {
+    pub batches: Vec<BatchArrival>,
+    pub summaries: Vec<Summary>,
+    /// Whether the last batch of the uplink arrived.
+    pub complete: bool,
+}
+
+pub fn open_session(config: &Path) -> eyre::Result<Session> {
+    let config = Config::from_file(config)
+        .map_err(|err| eyre!("failed to load zenoh config {}: {err}", config.display()))?;
+    zenoh::open(config)
+        .wait()
+        .map_err(|err| eyre!("failed to open zenoh session: {err}"))
+}
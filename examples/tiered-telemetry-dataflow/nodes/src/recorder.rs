@@ -0,0 +1,76 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{Array, AsArray},
+        datatypes::Schema,
+        record_batch::RecordBatch,
+    },
+};
+use eyre::{Context, OptionExt};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use std::{fs::File, path::PathBuf, sync::Arc};
+use tiered_telemetry_dataflow_nodes::{
+    RecorderReport, SAMPLE_BYTES, env_or, samples_to_arrow, write_json,
+};
+
+/// Records every `sample` at full rate to the parquet file `OUTPUT_FILE`, compressed with zstd.
+///
+/// The samples are written in row groups of `ROW_GROUP_ROWS`, which the writer buffers in
+/// memory until they are full, or until the recorder stops. Writes a `RecorderReport` to
+/// `REPORT_FILE` once the file is closed.
+fn main() -> eyre::Result<()> {
+    let output_file: PathBuf = env_or("OUTPUT_FILE", "out/telemetry.parquet".to_owned())?.into();
+    let row_group_rows: usize = env_or("ROW_GROUP_ROWS", 250)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/recorder.json".to_owned())?.into();
+
+    if let Some(parent) = output_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = File::create(&output_file)
+        .with_context(|| format!("failed to create {}", output_file.display()))?;
+    let schema = Arc::new(Schema::new(samples_to_arrow(&[]).fields().clone()));
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .set_max_row_group_size(row_group_rows)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, schema, Some(properties))?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut report = RecorderReport::default();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "sample" => {
+                    let samples = data
+                        .as_struct_opt()
+                        .ok_or_eyre("expected a struct array of samples")?;
+                    writer
+                        .write(&RecordBatch::from(samples.clone()))
+                        .context("failed to write samples")?;
+                    report.rows += samples.len() as u64;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    // writes the last row group and the footer, without which the file can't be read
+    let metadata = writer.close().context("failed to close recording")?;
+    report.raw_bytes = report.rows * SAMPLE_BYTES;
+    report.file_bytes = std::fs::metadata(&output_file)?.len();
+    println!(
+        "recorded {} samples in {} row groups, {} bytes",
+        report.rows,
+        metadata.row_groups.len(),
+        report.file_bytes
+    );
+    write_json(&report_file, &report)
+}
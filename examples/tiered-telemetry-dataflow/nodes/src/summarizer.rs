@@ -0,0 +1,54 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::StringArray, dora_core::config::DataId};
+use tiered_telemetry_dataflow_nodes::{Summarizer, Summary, env_or, samples_from_arrow};
+
+/// Decimates the `sample` stream to one `Summary` per `WINDOW_MS` of vehicle time, with the
+/// minimum, maximum, mean, and last value of each channel, and sends it on `summary`.
+///
+/// Sends the incomplete last window as well once `sample` is closed.
+fn main() -> eyre::Result<()> {
+    let window_ms: u64 = env_or("WINDOW_MS", 1000)?;
+    let output = DataId::from("summary".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut summarizer = Summarizer::new(window_ms);
+    let mut send = |summary: Summary| -> eyre::Result<()> {
+        println!(
+            "window at {} ms: {} samples",
+            summary.window_start_ms, summary.samples
+        );
+        node.send_output(
+            output.clone(),
+            Default::default(),
+            StringArray::from(vec![serde_json::to_string(&summary)?]),
+        )?;
+        Ok(())
+    };
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "sample" => {
+                    for sample in samples_from_arrow(&*data)? {
+                        if let Some(summary) = summarizer.push(sample) {
+                            send(summary)?;
+                        }
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "sample" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    if let Some(summary) = summarizer.finish() {
+        send(summary)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::AsArray};
+use eyre::{Context, OptionExt, eyre};
+use std::{collections::VecDeque, path::PathBuf};
+use tiered_telemetry_dataflow_nodes::{
+    Summary, TokenBucket, UplinkBatch, UplinkReport, env_or, open_session, write_json,
+};
+use zenoh::{Session, Wait, qos::CongestionControl};
+
+/// Publishes the `summary` inputs to the cloud on the zenoh key `KEY`, within a bandwidth
+/// budget of `BUDGET_BYTES_PER_SEC`.
+///
+/// Collects the summaries and publishes them as one gzip-compressed `UplinkBatch` per `tick`.
+/// A token bucket with bursts of `BURST_BYTES` enforces the budget. When a batch doesn't fit,
+/// the summaries wait for the next tick, and the oldest ones are dropped once more than
+/// `MAX_BACKLOG` wait. Once `summary` is closed, waits for the budget to publish the rest as
+/// the last batch. Writes an `UplinkReport` to `REPORT_FILE`.
+fn main() -> eyre::Result<()> {
+    let config: PathBuf = env_or("ZENOH_CONFIG", "config/vehicle.json5".to_owned())?.into();
+    let key = env_or("KEY", "telemetry/vehicle-1/summaries".to_owned())?;
+    let budget: u64 = env_or("BUDGET_BYTES_PER_SEC", 512)?;
+    let burst: u64 = env_or("BURST_BYTES", budget)?;
+    let max_backlog: usize = env_or("MAX_BACKLOG", 30)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/uplink.json".to_owned())?.into();
+
+    let session = open_session(&config)?;
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut uplink = Uplink {
+        session,
+        key,
+        bucket: TokenBucket::new(budget, burst),
+        pending: VecDeque::new(),
+        report: UplinkReport {
+            budget_bytes_per_sec: budget,
+            burst_bytes: burst,
+            ..Default::default()
+        },
+    };
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "summary" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected JSON strings")?;
+                    for row in rows.iter().flatten() {
+                        let summary: Summary =
+                            serde_json::from_str(row).context("invalid summary")?;
+                        uplink.pending.push_back(summary);
+                        if uplink.pending.len() > max_backlog {
+                            uplink.pending.pop_front();
+                            uplink.report.dropped += 1;
+                        }
+                    }
+                }
+                "tick" => {
+                    if !uplink.pending.is_empty() && !uplink.try_publish(false)? {
+                        uplink.report.deferred += 1;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "summary" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    // the rest, and the end of the stream for the cloud, still within the budget
+    while !uplink.try_publish(true)? {
+        let payload = uplink.batch(true).encode()?;
+        let wait = uplink
+            .bucket
+            .time_until(payload.len() as u64)
+            .ok_or_else(|| eyre!("the last batch exceeds the burst size"))?;
+        std::thread::sleep(wait);
+    }
+
+    let report = &uplink.report;
+    println!(
+        "published {} summaries in {} batches, {} bytes ({} bytes of JSON), {} deferred, {} \
+         dropped",
+        report.summaries,
+        report.batches,
+        report.bytes,
+        report.json_bytes,
+        report.deferred,
+        report.dropped
+    );
+    write_json(&report_file, report)
+}
+
+struct Uplink {
+    session: Session,
+    key: String,
+    bucket: TokenBucket,
+    pending: VecDeque<Summary>,
+    report: UplinkReport,
+}
+
+impl Uplink {
+    fn batch(&self, last: bool) -> UplinkBatch {
+        UplinkBatch {
+            seq: self.report.batches,
+            summaries: self.pending.iter().cloned().collect(),
+            last,
+        }
+    }
+
+    /// Publishes the pending summaries if the budget allows it.
+    fn try_publish(&mut self, last: bool) -> eyre::Result<bool> {
+        let mut batch = self.batch(last);
+        let mut payload = batch.encode()?;
+        // a batch that is larger than a burst would never fit
+        while self.bucket.time_until(payload.len() as u64).is_none() {
+            if self.pending.pop_front().is_none() {
+                return Err(eyre!("an empty batch exceeds the burst size"));
+            }
+            self.report.dropped += 1;
+            batch = self.batch(last);
+            payload = batch.encode()?;
+        }
+        let bytes = payload.len() as u64;
+        if !self.bucket.try_take(bytes) {
+            return Ok(false);
+        }
+        self.session
+            .put(&self.key, payload)
+            .congestion_control(CongestionControl::Block)
+            .wait()
+            .map_err(|err| eyre!("failed to publish batch: {err}"))?;
+
+        self.pending.clear();
+        self.report.batches += 1;
+        self.report.summaries += batch.summaries.len() as u64;
+        self.report.bytes += bytes;
+        self.report.json_bytes += serde_json::to_vec(&batch)?.len() as u64;
+        Ok(true)
+    }
+}
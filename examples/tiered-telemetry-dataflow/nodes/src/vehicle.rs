@@ -0,0 +1,62 @@
+use dora_node_api::{self, DoraNode, Event, dora_core::config::DataId};
+use tiered_telemetry_dataflow_nodes::{Sample, env_or, samples_to_arrow};
+
+/// Sends a `Sample` of simulated vehicle telemetry on `sample` per `tick`, and stops after
+/// `COUNT` samples.
+///
+/// The vehicle time advances by `PERIOD_MS` per sample, so that the windows of the summaries
+/// don't depend on the timer's jitter.
+fn main() -> eyre::Result<()> {
+    let count: u64 = env_or("COUNT", 1000)?;
+    let period_ms: u64 = env_or("PERIOD_MS", 10)?;
+    let output = DataId::from("sample".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    // deterministic sensor noise
+    let mut noise_state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut noise = move || {
+        noise_state ^= noise_state << 13;
+        noise_state ^= noise_state >> 7;
+        noise_state ^= noise_state << 17;
+        (noise_state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    };
+
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    let timestamp_ms = seq * period_ms;
+                    let t = timestamp_ms as f64 / 1000.0;
+                    let speed = 12.0 + 8.0 * (t / 3.0).sin() + 0.2 * noise();
+                    let sample = Sample {
+                        seq,
+                        timestamp_ms,
+                        values: [
+                            speed,
+                            800.0 + 95.0 * speed + 40.0 * noise(),
+                            12.6 - 0.01 * t + 0.05 * noise(),
+                            70.0 + 20.0 * (1.0 - (-t / 4.0).exp()) + 0.5 * noise(),
+                        ],
+                    };
+                    node.send_output(
+                        output.clone(),
+                        Default::default(),
+                        samples_to_arrow(&[sample]),
+                    )?;
+                    seq += 1;
+                    if seq >= count {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("sent {seq} samples");
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! Checks the local recording of `recorder`, and the summaries that reached the cloud against
+//! it.
+//!
+//! Usage: `verify-recording <file> [--rows <n>] [--cloud-report <path>]`
+
+use dora_node_api::arrow::array::StructArray;
+use eyre::{Context, bail, eyre};
+use parquet::{arrow::arrow_reader::ParquetRecordBatchReaderBuilder, basic::Compression};
+use std::{fs::File, path::PathBuf};
+use tiered_telemetry_dataflow_nodes::{CloudReport, Summarizer, Summary, samples_from_arrow};
+
+fn main() -> eyre::Result<()> {
+    let mut file = None;
+    let mut rows = None;
+    let mut cloud_report = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rows" => rows = args.next().map(|v| v.parse::<u64>()).transpose()?,
+            "--cloud-report" => cloud_report = args.next().map(PathBuf::from),
+            other if file.is_none() => file = Some(PathBuf::from(other)),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    let file = file.ok_or_else(|| {
+        eyre!("usage: verify-recording <file> [--rows <n>] [--cloud-report <path>]")
+    })?;
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(
+        File::open(&file).with_context(|| format!("failed to open {}", file.display()))?,
+    )?;
+    let metadata = reader.metadata().clone();
+    let mut samples = Vec::new();
+    for batch in reader.build()? {
+        samples.extend(samples_from_arrow(&StructArray::from(batch?))?);
+    }
+
+    // complete: every sample once, in order
+    println!(
+        "recording: {} samples in {} row groups",
+        samples.len(),
+        metadata.num_row_groups()
+    );
+    if let Some(rows) = rows
+        && samples.len() as u64 != rows
+    {
+        bail!("expected {rows} samples, found {}", samples.len());
+    }
+    for (i, sample) in samples.iter().enumerate() {
+        if sample.seq != i as u64 {
+            bail!("sample {i} has seq {}, samples are missing", sample.seq);
+        }
+    }
+    if samples
+        .windows(2)
+        .any(|pair| pair[1].timestamp_ms <= pair[0].timestamp_ms)
+    {
+        bail!("the timestamps are not increasing");
+    }
+    for row_group in metadata.row_groups() {
+        for column in row_group.columns() {
+            if !matches!(column.compression(), Compression::ZSTD(_)) {
+                bail!(
+                    "column `{}` is compressed with {}, not zstd",
+                    column.column_path(),
+                    column.compression()
+                );
+            }
+        }
+    }
+
+    // the cloud got the same summaries as summarizing the recording gives
+    let Some(cloud_report) = cloud_report else {
+        return Ok(());
+    };
+    let cloud: CloudReport = serde_json::from_str(
+        &std::fs::read_to_string(&cloud_report)
+            .with_context(|| format!("{} was not written", cloud_report.display()))?,
+    )?;
+    let window_ms = cloud
+        .summaries
+        .first()
+        .ok_or_else(|| eyre!("the cloud received no summaries"))?
+        .window_ms;
+    let mut summarizer = Summarizer::new(window_ms);
+    let mut expected: Vec<Summary> = samples
+        .into_iter()
+        .filter_map(|sample| summarizer.push(sample))
+        .collect();
+    expected.extend(summarizer.finish());
+    if cloud.summaries.len() != expected.len() {
+        bail!(
+            "the cloud received {} summaries, the recording gives {}",
+            cloud.summaries.len(),
+            expected.len()
+        );
+    }
+    for (received, expected) in cloud.summaries.iter().zip(&expected) {
+        if !matches(received, expected) {
+            bail!(
+                "the summary of the window at {} ms doesn't match the recording:\n\
+                 received {received:?}\nexpected {expected:?}",
+                expected.window_start_ms
+            );
+        }
+    }
+    println!(
+        "cloud: all {} summaries match the recording",
+        expected.len()
+    );
+    Ok(())
+}
+
+/// Equal, apart from rounding of the JSON encoding.
+fn matches(received: &Summary, expected: &Summary) -> bool {
+    let close = |a: f64, b: f64| (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0);
+    received.window_start_ms == expected.window_start_ms
+        && received.samples == expected.samples
+        && received.first_seq == expected.first_seq
+        && received.last_seq == expected.last_seq
+        && received.channels.len() == expected.channels.len()
+        && expected.channels.iter().all(|(channel, e)| {
+            received.channels.get(channel).is_some_and(|r| {
+                close(r.min, e.min)
+                    && close(r.max, e.max)
+                    && close(r.mean, e.mean)
+                    && close(r.last, e.last)
+            })
+        })
+}
@@ -0,0 +1,122 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, node_env};
+use eyre::{Context, OptionExt, bail};
+use serde::Deserialize;
+use std::{
+    io::IsTerminal,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Subset of `WorkerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct WorkerReport {
+    jobs: u64,
+    completed: u64,
+    pending: Vec<u64>,
+}
+
+struct Args {
+    /// The node to attach tokio-console to.
+    node: String,
+    /// Only checks that the console is reachable, without launching tokio-console.
+    no_console: bool,
+}
+
+impl Args {
+    fn parse() -> eyre::Result<Self> {
+        let mut args = Self {
+            node: "worker".to_owned(),
+            no_console: false,
+        };
+        let mut raw = std::env::args().skip(1);
+        while let Some(arg) = raw.next() {
+            match arg.as_str() {
+                "--node" => args.node = raw.next().ok_or_eyre("--node requires a value")?,
+                "--no-console" => args.no_console = true,
+                other => bail!("unknown argument `{other}`"),
+            }
+        }
+        Ok(args)
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("tokio-console-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let args = Args::parse()?;
+    let dataflow = Path::new("dataflow.yml");
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let console_addr = node_env(&descriptor, &args.node, "TOKIO_CONSOLE_BIND")
+        .context("the node must set TOKIO_CONSOLE_BIND to be attached to")?;
+    let stuck_every: u64 = node_env(&descriptor, "worker", "STUCK_EVERY")?.parse()?;
+
+    let dora = Dora::from_env()?;
+    // builds the nodes with `--cfg tokio_unstable`, from `.cargo/config.toml`
+    dora.build_dataflow(dataflow).await?;
+    let mut running = tokio::spawn(dora.run_dataflow(dataflow));
+
+    let start = Instant::now();
+    while !port_check::is_port_reachable(&console_addr) {
+        if running.is_finished() || start.elapsed() > Duration::from_secs(30) {
+            bail!(
+                "console of `{}` never became reachable on {console_addr}",
+                args.node
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    println!("console of `{}` is listening on {console_addr}", args.node);
+
+    let console = which::which("tokio-console").ok();
+    match console {
+        Some(console) if !args.no_console && std::io::stdout().is_terminal() => {
+            let mut console = tokio::process::Command::new(console)
+                .arg(format!("http://{console_addr}"))
+                .kill_on_drop(true)
+                .spawn()
+                .context("failed to start tokio-console")?;
+            tokio::select! {
+                result = &mut running => result??,
+                status = console.wait() => {
+                    status?;
+                    println!("tokio-console exited, waiting for the dataflow to finish");
+                    running.await??;
+                }
+            }
+        }
+        Some(_) => running.await??,
+        None => {
+            println!(
+                "tokio-console is not installed, install it with `cargo install --locked \
+                 tokio-console` and attach it with `tokio-console http://{console_addr}`"
+            );
+            running.await??;
+        }
+    }
+
+    let report: WorkerReport = serde_json::from_str(
+        &std::fs::read_to_string("out/worker.json").context("worker did not write a report")?,
+    )?;
+    let expected: Vec<u64> = (1..=report.jobs)
+        .filter(|job| stuck_every > 0 && job % stuck_every == 0)
+        .collect();
+    if report.pending != expected {
+        bail!(
+            "expected exactly the jobs {expected:?} to hang, got {:?}",
+            report.pending
+        );
+    }
+    println!(
+        "{} of {} jobs completed, the tasks of jobs {:?} hang as expected",
+        report.completed, report.jobs, report.pending
+    );
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use dora_node_api::ArrowData;
+use eyre::Context;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Written by the worker when the `job` input closes.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WorkerReport {
+    pub jobs: u64,
+    pub completed: u64,
+    /// Ids of the jobs whose task never finished.
+    pub pending: Vec<u64>,
+}
+
+/// Jobs and their results are sent as a single job id.
+pub fn job_id(data: &ArrowData) -> eyre::Result<u64> {
+    u64::try_from(data).context("expected a single uint64 job id")
+}
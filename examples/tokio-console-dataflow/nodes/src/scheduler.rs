@@ -0,0 +1,72 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tokio::time::Instant;
+use tokio_console_dataflow_nodes::{env_or, job_id};
+
+/// Sends a new job id on `job` on every `tick`, for `DURATION_MS`, and counts the ids that come
+/// back on `result`.
+///
+/// Serves tokio-console on `TOKIO_CONSOLE_BIND`.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    console_subscriber::init();
+
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let output = DataId::from("job".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    // a long-running task, to have something besides the short-lived ones in the console
+    let results = Arc::new(AtomicU64::new(0));
+    tokio::task::Builder::new().name("progress").spawn({
+        let results = results.clone();
+        async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5));
+            loop {
+                interval.tick().await;
+                println!("{} results so far", results.load(Ordering::Relaxed));
+            }
+        }
+    })?;
+
+    let start = Instant::now();
+    let mut next_job: u64 = 1;
+    while let Some(event) = events.recv_async().await {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    node.send_output(output.clone(), Default::default(), next_job.into_arrow())?;
+                    next_job += 1;
+                }
+                "result" => {
+                    job_id(&data)?;
+                    results.fetch_add(1, Ordering::Relaxed);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "sent {} jobs, got {} results",
+        next_job - 1,
+        results.load(Ordering::Relaxed)
+    );
+    Ok(())
+}
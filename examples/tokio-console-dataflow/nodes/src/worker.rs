@@ -0,0 +1,112 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use std::{
+    collections::BTreeSet,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use tokio_console_dataflow_nodes::{WorkerReport, env_or, job_id, write_json};
+
+/// Runs every `job` in its own task, which waits `JOB_MS` for a simulated device, and sends the
+/// job id on `result` when the task is done.
+///
+/// Every `STUCK_EVERY`th job waits for [`DeviceReply`], which has a bug that makes the task hang
+/// forever. `0` disables the bug. When the `job` input closes, the worker gives the tasks
+/// `GRACE_MS` to finish, and writes the ids of those that didn't to `REPORT_FILE`.
+///
+/// Serves tokio-console on `TOKIO_CONSOLE_BIND`.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    console_subscriber::init();
+
+    let job_time = Duration::from_millis(env_or("JOB_MS", 200)?);
+    let stuck_every: u64 = env_or("STUCK_EVERY", 0)?;
+    let grace = Duration::from_millis(env_or("GRACE_MS", 1000)?);
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/worker.json".to_owned())?.into();
+    let output = DataId::from("result".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+    let mut report = WorkerReport::default();
+    let mut pending = BTreeSet::new();
+    loop {
+        tokio::select! {
+            event = events.recv_async() => match event {
+                Some(Event::Input { id, metadata: _, data }) => match id.as_str() {
+                    "job" => {
+                        let job = job_id(&data)?;
+                        report.jobs += 1;
+                        pending.insert(job);
+                        let done = done_tx.clone();
+                        let stuck = stuck_every > 0 && job % stuck_every == 0;
+                        tokio::task::Builder::new()
+                            .name(&format!("job {job}"))
+                            .spawn(async move {
+                                if stuck {
+                                    DeviceReply::default().await;
+                                } else {
+                                    tokio::time::sleep(job_time).await;
+                                }
+                                let _ = done.send(job);
+                            })?;
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Some(Event::InputClosed { id }) => {
+                    if id.as_str() == "job" {
+                        break;
+                    }
+                }
+                Some(Event::Stop(_)) | None => break,
+                Some(other) => eprintln!("Received unexpected input: {other:?}"),
+            },
+            Some(job) = done_rx.recv() => {
+                pending.remove(&job);
+                report.completed += 1;
+                node.send_output(output.clone(), Default::default(), job.into_arrow())?;
+            }
+        }
+    }
+
+    let _ = tokio::time::timeout(grace, async {
+        while let Some(job) = done_rx.recv().await {
+            pending.remove(&job);
+            report.completed += 1;
+        }
+    })
+    .await;
+
+    report.pending = pending.into_iter().collect();
+    println!(
+        "{} jobs, {} completed, pending: {:?}",
+        report.jobs, report.completed, report.pending
+    );
+    write_json(&report_file, &report)
+}
+
+/// Waits for a reply of the simulated device, the second time it's polled.
+///
+/// The bug: it returns `Pending` without waking the task or storing the waker, so nothing ever
+/// polls it again. tokio-console flags such tasks with a "lost waker" warning.
+#[derive(Default)]
+struct DeviceReply {
+    polled: bool,
+}
+
+impl Future for DeviceReply {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if self.polled {
+            return Poll::Ready(());
+        }
+        self.polled = true;
+        // missing: `cx.waker().wake_by_ref()`, or handing the waker to whatever completes
+        // the reply
+        Poll::Pending
+    }
+}
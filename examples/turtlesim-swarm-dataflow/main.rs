@@ -0,0 +1,85 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, node_env};
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Largest allowed distance of a turtle to its place in the formation at the end, in
+/// turtlesim units. The window is 11 units wide.
+const MAX_FORMATION_ERROR: f64 = 0.5;
+
+/// Subset of `SwarmReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SwarmReport {
+    turtles: Vec<TurtleStats>,
+    final_error: Option<f64>,
+}
+
+/// Subset of `TurtleStats` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct TurtleStats {
+    turtle: String,
+    poses: u64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("turtlesim-swarm-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dataflow = Path::new("dataflow.yml");
+    let descriptor: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(dataflow)?)?;
+    let turtles: usize = node_env(&descriptor, "spawner", "TURTLES")?.parse()?;
+    if node_env(&descriptor, "pose-monitor", "TURTLES")?.parse::<usize>()? != turtles {
+        bail!("`spawner` and `pose-monitor` must have the same `TURTLES`");
+    }
+
+    let dora = Dora::from_env()?;
+    dora.build_dataflow(dataflow).await?;
+
+    // a fresh turtlesim, which starts with `turtle1` only
+    let ros = std::env::var("ROS").unwrap_or_else(|_| "/opt/ros/jazzy/setup.bash".into());
+    let mut turtlesim = tokio::process::Command::new("bash")
+        .arg("-c")
+        .arg(format!(
+            "source {ros}; exec ros2 run turtlesim turtlesim_node"
+        ))
+        .kill_on_drop(true)
+        .spawn()
+        .context("failed to start turtlesim")?;
+
+    let result = dora.run_dataflow(dataflow).await;
+    turtlesim.kill().await?;
+    result?;
+
+    let report: SwarmReport = serde_json::from_str(
+        &std::fs::read_to_string("out/swarm.json")
+            .context("pose-monitor did not write a report")?,
+    )?;
+    for stats in &report.turtles {
+        println!("{:<10} {:>6} poses", stats.turtle, stats.poses);
+    }
+    if report.turtles.len() != turtles {
+        bail!(
+            "expected {turtles} turtles in the report, got {}",
+            report.turtles.len()
+        );
+    }
+    if let Some(stats) = report.turtles.iter().find(|stats| stats.poses == 0) {
+        bail!("no poses of `{}`, was it spawned?", stats.turtle);
+    }
+    match report.final_error {
+        Some(error) if error <= MAX_FORMATION_ERROR => {
+            println!("all {turtles} turtles are in formation, largest error {error:.3}");
+        }
+        Some(error) => {
+            bail!("largest formation error {error:.3} exceeds {MAX_FORMATION_ERROR}")
+        }
+        None => bail!("the formation controller never sent a formation error"),
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, arrow::array::AsArray, dora_core::config::DataId,
+};
+use dora_ros2_bridge::{
+    messages::geometry_msgs::msg::{Twist, Vector3},
+    ros2_client,
+};
+use eyre::{Context, OptionExt, eyre};
+use std::{
+    collections::HashMap,
+    f64::consts::{PI, TAU},
+    time::{Duration, Instant},
+};
+use turtlesim_swarm_dataflow_nodes::{TurtlePose, env_or, init_ros_node, reliable_qos};
+
+/// The center of the turtlesim window.
+const CENTER: (f64, f64) = (5.544, 5.544);
+/// Gains of the go-to-goal controller of each turtle.
+const LINEAR_GAIN: f64 = 2.0;
+const ANGULAR_GAIN: f64 = 6.0;
+const MAX_LINEAR: f64 = 2.0;
+
+/// Drives the turtles into a circle of `RADIUS` around the center of the window, which
+/// rotates with `ANGULAR_SPEED`.
+///
+/// Starts once the turtles were `spawned`, and then publishes a velocity command on
+/// `/<turtle>/cmd_vel` for every turtle on every `tick`, based on the latest `poses`. Sends the
+/// largest distance of a turtle to its place on `formation_error`. After `DURATION_MS`, it
+/// stops the turtles and exits.
+fn main() -> eyre::Result<()> {
+    let radius: f64 = env_or("RADIUS", 3.0)?;
+    let angular_speed: f64 = env_or("ANGULAR_SPEED", 0.1)?;
+    let duration = Duration::from_millis(env_or("DURATION_MS", 20000)?);
+    let output = DataId::from("formation_error".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let pool = futures::executor::ThreadPool::new()?;
+    let mut ros_node = init_ros_node("formation_controller", &pool)?;
+
+    let mut publishers: Vec<(String, ros2_client::Publisher<Twist>)> = Vec::new();
+    let mut poses: HashMap<String, TurtlePose> = HashMap::new();
+    let mut started: Option<Instant> = None;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, .. } => match id.as_str() {
+                "spawned" => {
+                    let turtles = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    for turtle in turtles.iter().flatten() {
+                        publishers
+                            .push((turtle.to_owned(), create_publisher(&mut ros_node, turtle)?));
+                    }
+                    println!("controlling {} turtles", publishers.len());
+                    started = Some(Instant::now());
+                }
+                "poses" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    for row in rows.iter().flatten() {
+                        let pose: TurtlePose = serde_json::from_str(row)?;
+                        poses.insert(pose.turtle.clone(), pose);
+                    }
+                }
+                "tick" => {
+                    let Some(started) = started else {
+                        continue;
+                    };
+                    if started.elapsed() >= duration {
+                        for (_, publisher) in &publishers {
+                            let stop = Twist {
+                                linear: Vector3::default(),
+                                angular: Vector3::default(),
+                            };
+                            publish(publisher, stop)?;
+                        }
+                        break;
+                    }
+                    if publishers
+                        .iter()
+                        .any(|(turtle, _)| !poses.contains_key(turtle))
+                    {
+                        // wait for the first pose of every turtle
+                        continue;
+                    }
+
+                    let phase = angular_speed * started.elapsed().as_secs_f64();
+                    let mut error: f64 = 0.0;
+                    for (i, (turtle, publisher)) in publishers.iter().enumerate() {
+                        let angle = phase + TAU * i as f64 / publishers.len() as f64;
+                        let target = (
+                            CENTER.0 + radius * angle.cos(),
+                            CENTER.1 + radius * angle.sin(),
+                        );
+                        let pose = &poses[turtle];
+                        error = error.max((target.0 - pose.x).hypot(target.1 - pose.y));
+                        publish(publisher, go_to(pose, target))?;
+                    }
+                    node.send_output(output.clone(), Default::default(), error.into_arrow())?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+    Ok(())
+}
+
+/// Turns towards `target`, and drives towards it when facing it roughly.
+fn go_to(pose: &TurtlePose, target: (f64, f64)) -> Twist {
+    let (dx, dy) = (target.0 - pose.x, target.1 - pose.y);
+    let distance = dx.hypot(dy);
+    // wrapped to [-pi, pi)
+    let heading_error = (dy.atan2(dx) - pose.theta + PI).rem_euclid(TAU) - PI;
+    Twist {
+        linear: Vector3 {
+            x: (LINEAR_GAIN * distance).min(MAX_LINEAR) * heading_error.cos().max(0.0),
+            ..Default::default()
+        },
+        angular: Vector3 {
+            z: ANGULAR_GAIN * heading_error,
+            ..Default::default()
+        },
+    }
+}
+
+fn publish(publisher: &ros2_client::Publisher<Twist>, twist: Twist) -> eyre::Result<()> {
+    publisher
+        .publish(twist)
+        .map_err(|e| eyre!("failed to publish velocity command: {e:?}"))
+}
+
+fn create_publisher(
+    ros_node: &mut ros2_client::Node,
+    turtle: &str,
+) -> eyre::Result<ros2_client::Publisher<Twist>> {
+    let topic = ros_node
+        .create_topic(
+            &ros2_client::Name::new(&format!("/{turtle}"), "cmd_vel")
+                .map_err(|e| eyre!("failed to create ROS2 name: {e}"))?,
+            ros2_client::MessageTypeName::new("geometry_msgs", "Twist"),
+            &reliable_qos(1),
+        )
+        .context("failed to create topic")?;
+    ros_node
+        .create_publisher::<Twist>(&topic, None)
+        .context("failed to create publisher")
+}
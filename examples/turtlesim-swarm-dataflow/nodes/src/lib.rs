@@ -0,0 +1,78 @@
+use dora_ros2_bridge::{
+    ros2_client::{self, NodeOptions},
+    rustdds::{self, policy},
+};
+use eyre::{Context, eyre};
+use futures::task::SpawnExt;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::env_or;
+
+/// Latest pose of a turtle, sent as one JSON row per turtle on `poses`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurtlePose {
+    pub turtle: String,
+    pub x: f64,
+    pub y: f64,
+    pub theta: f64,
+}
+
+/// Written by the pose monitor when the formation controller stops.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SwarmReport {
+    pub turtles: Vec<TurtleStats>,
+    /// The largest distance of a turtle to its place in the formation, at the end.
+    pub final_error: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TurtleStats {
+    pub turtle: String,
+    /// Number of `pose` messages received from the turtle.
+    pub poses: u64,
+    pub last: Option<TurtlePose>,
+}
+
+/// The names of `count` turtles. `turtle1` is the one that turtlesim starts with.
+pub fn turtle_names(count: usize) -> Vec<String> {
+    (1..=count).map(|i| format!("turtle{i}")).collect()
+}
+
+/// Creates a ROS2 node in the `/dora_swarm` namespace, and spawns its spinner on `pool`.
+///
+/// The spinner handles service discovery and other background work of the node.
+pub fn init_ros_node(
+    name: &str,
+    pool: &futures::executor::ThreadPool,
+) -> eyre::Result<ros2_client::Node> {
+    let ros_context = ros2_client::Context::new().unwrap();
+    let mut ros_node = ros_context
+        .new_node(
+            ros2_client::NodeName::new("/dora_swarm", name)
+                .map_err(|e| eyre!("failed to create ROS2 node name: {e}"))?,
+            NodeOptions::new().enable_rosout(true),
+        )
+        .map_err(|e| eyre!("failed to create ros2 node: {e:?}"))?;
+
+    let spinner = ros_node
+        .spinner()
+        .map_err(|e| eyre!("failed to create spinner: {e:?}"))?;
+    pool.spawn(async {
+        if let Err(err) = spinner.spin().await {
+            eprintln!("ros2 spinner failed: {err:?}");
+        }
+    })
+    .context("failed to spawn ros2 spinner")?;
+    Ok(ros_node)
+}
+
+/// Reliable, keeping the last `depth` messages.
+pub fn reliable_qos(depth: i32) -> rustdds::QosPolicies {
+    rustdds::QosPolicyBuilder::new()
+        .durability(policy::Durability::Volatile)
+        .reliability(policy::Reliability::Reliable {
+            max_blocking_time: rustdds::Duration::from_millis(100),
+        })
+        .history(policy::History::KeepLast { depth })
+        .build()
+}
@@ -0,0 +1,136 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, StringArray},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use dora_ros2_bridge::{messages::turtlesim::msg::Pose, ros2_client};
+use eyre::{Context, OptionExt, eyre};
+use futures::StreamExt;
+use std::path::PathBuf;
+use turtlesim_swarm_dataflow_nodes::{
+    SwarmReport, TurtlePose, TurtleStats, env_or, init_ros_node, turtle_names,
+};
+
+/// Subscribes to `/<turtle>/pose` of all `TURTLES` turtles, and sends the latest pose of each
+/// turtle on `poses` on every `tick`.
+///
+/// Writes a [`SwarmReport`] to `REPORT_FILE` when the `formation_error` input is closed, i.e.
+/// when the formation controller stopped.
+fn main() -> eyre::Result<()> {
+    let count: usize = env_or("TURTLES", 5)?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/swarm.json".to_owned())?.into();
+    let output = DataId::from("poses".to_owned());
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let pool = futures::executor::ThreadPool::new()?;
+    let mut ros_node = init_ros_node("pose_monitor", &pool)?;
+
+    let mut stats: Vec<TurtleStats> = turtle_names(count)
+        .into_iter()
+        .map(|turtle| TurtleStats {
+            turtle,
+            poses: 0,
+            last: None,
+        })
+        .collect();
+    // a subscription doesn't need the turtle to exist yet, it receives poses once it's spawned
+    let readers = stats
+        .iter()
+        .map(|stats| create_pose_reader(&mut ros_node, &stats.turtle))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let pose_streams = futures::stream::select_all(
+        readers
+            .iter()
+            .enumerate()
+            .map(|(i, reader)| Box::pin(reader.async_stream().map(move |pose| (i, pose)))),
+    );
+
+    let merged = events.merge_external(Box::pin(pose_streams));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut final_error = None;
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, data, .. } => match id.as_str() {
+                    "tick" => {
+                        let rows = stats
+                            .iter()
+                            .filter_map(|stats| stats.last.as_ref())
+                            .map(serde_json::to_string)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        if !rows.is_empty() {
+                            node.send_output(
+                                output.clone(),
+                                Default::default(),
+                                StringArray::from(rows),
+                            )?;
+                        }
+                    }
+                    "formation_error" => {
+                        let errors = data
+                            .as_primitive_opt::<Float64Type>()
+                            .ok_or_eyre("expected a Float64 array")?;
+                        final_error = errors.iter().flatten().last().or(final_error);
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    println!("Input `{id}` was closed");
+                    if id.as_str() == "formation_error" {
+                        break;
+                    }
+                }
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External((i, Ok((pose, _)))) => {
+                let stats = &mut stats[i];
+                stats.poses += 1;
+                stats.last = Some(TurtlePose {
+                    turtle: stats.turtle.clone(),
+                    x: pose.x.into(),
+                    y: pose.y.into(),
+                    theta: pose.theta.into(),
+                });
+            }
+            MergedEvent::External((i, Err(err))) => {
+                eprintln!("failed to read pose of `{}`: {err:?}", stats[i].turtle);
+            }
+        }
+    }
+
+    for stats in &stats {
+        println!("received {} poses of `{}`", stats.poses, stats.turtle);
+    }
+    let report = SwarmReport {
+        turtles: stats,
+        final_error,
+    };
+    if let Some(parent) = report_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&report_file, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("failed to write {}", report_file.display()))
+}
+
+fn create_pose_reader(
+    ros_node: &mut ros2_client::Node,
+    turtle: &str,
+) -> eyre::Result<ros2_client::Subscription<Pose>> {
+    let topic = ros_node
+        .create_topic(
+            &ros2_client::Name::new(&format!("/{turtle}"), "pose")
+                .map_err(|e| eyre!("failed to create ROS2 name: {e}"))?,
+            ros2_client::MessageTypeName::new("turtlesim", "Pose"),
+            &Default::default(),
+        )
+        .context("failed to create topic")?;
+    ros_node
+        .create_subscription::<Pose>(&topic, None)
+        .context("failed to create subscription")
+}
@@ -0,0 +1,118 @@
+use dora_node_api::{self, DoraNode, arrow::array::StringArray, dora_core::config::DataId};
+use dora_ros2_bridge::{
+    messages::turtlesim::service::{Spawn, SpawnRequest},
+    ros2_client,
+};
+use eyre::{bail, eyre};
+use std::{
+    f32::consts::FRAC_PI_2,
+    time::{Duration, Instant},
+};
+use turtlesim_swarm_dataflow_nodes::{env_or, init_ros_node, reliable_qos, turtle_names};
+
+/// Spawns `TURTLES` turtles in total through the `/spawn` service of turtlesim, all at once,
+/// and sends their names on `spawned` when they are all there.
+///
+/// Each spawn uses its own service client: a client only keeps the response that it waits
+/// for, so concurrent requests on one client would lose each other's responses.
+fn main() -> eyre::Result<()> {
+    let count: usize = env_or("TURTLES", 5)?;
+    let names = turtle_names(count);
+    let output = DataId::from("spawned".to_owned());
+
+    let (mut node, _events) = DoraNode::init_from_env()?;
+    let pool = futures::executor::ThreadPool::new()?;
+    let mut ros_node = init_ros_node("spawner", &pool)?;
+
+    // `turtle1` exists already, spawn the others in a row at the bottom of the window
+    let requests: Vec<SpawnRequest> = names[1..]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| SpawnRequest {
+            x: 1.0 + 9.0 * i as f32 / count.saturating_sub(2).max(1) as f32,
+            y: 1.0,
+            theta: FRAC_PI_2,
+            name: name.clone(),
+        })
+        .collect();
+    let clients = requests
+        .iter()
+        .map(|_| {
+            ros_node.create_client::<Spawn>(
+                ros2_client::ServiceMapping::Enhanced,
+                &ros2_client::Name::new("/", "spawn").unwrap(),
+                &ros2_client::ServiceTypeName::new("turtlesim", "Spawn"),
+                reliable_qos(1),
+                reliable_qos(1),
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| eyre!("failed to create spawn client: {e:?}"))?;
+
+    println!("wait for spawn service");
+    futures::executor::block_on(futures::future::try_join_all(
+        clients
+            .iter()
+            .map(|client| wait_for_service(client, &ros_node)),
+    ))?;
+
+    let start = Instant::now();
+    let spawned = futures::executor::block_on(futures::future::try_join_all(
+        clients
+            .iter()
+            .zip(requests)
+            .map(|(client, request)| spawn(client, request)),
+    ))?;
+    println!(
+        "spawned {} turtles concurrently in {:?}",
+        spawned.len(),
+        start.elapsed()
+    );
+
+    node.send_output(output, Default::default(), StringArray::from(names))?;
+    Ok(())
+}
+
+async fn wait_for_service(
+    client: &ros2_client::Client<Spawn>,
+    ros_node: &ros2_client::Node,
+) -> eyre::Result<()> {
+    for _ in 0..10 {
+        let ready = client.wait_for_service(ros_node);
+        futures::pin_mut!(ready);
+        let timeout = futures_timer::Delay::new(Duration::from_secs(2));
+        match futures::future::select(ready, timeout).await {
+            futures::future::Either::Left(((), _)) => return Ok(()),
+            futures::future::Either::Right(_) => {
+                println!("timeout while waiting for spawn service, retrying");
+            }
+        }
+    }
+    bail!("spawn service not available, is turtlesim running?");
+}
+
+async fn spawn(client: &ros2_client::Client<Spawn>, request: SpawnRequest) -> eyre::Result<String> {
+    let name = request.name.clone();
+    let request_id = client.async_send_request(request).await?;
+
+    let response = client.async_receive_response(request_id);
+    futures::pin_mut!(response);
+    let timeout = futures_timer::Delay::new(Duration::from_secs(15));
+    match futures::future::select(response, timeout).await {
+        futures::future::Either::Left((Ok(response), _)) => {
+            // turtlesim answers with the name that it used, or fails if the name is taken
+            if response.name != name {
+                bail!(
+                    "requested `{name}`, but turtlesim spawned `{}`",
+                    response.name
+                );
+            }
+            println!("spawned `{name}`");
+            Ok(name)
+        }
+        futures::future::Either::Left((Err(err), _)) => bail!(err),
+        futures::future::Either::Right(_) => {
+            bail!("timeout while waiting for `{name}` to spawn");
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use dora_node_api::{self, DoraNode, Event};
+use eyre::{Context, bail};
+use units_dataflow_nodes::{Quantity, si_unit, unit};
+use uom::si::{
+    f64::{Length, Time, Velocity},
+    length::meter,
+    thermodynamic_temperature::kelvin,
+    time::second,
+    velocity::meter_per_second,
+};
+
+fn main() -> eyre::Result<()> {
+    let expected_rejections = match std::env::var("EXPECTED_REJECTIONS") {
+        Ok(value) => Some(
+            value
+                .parse::<usize>()
+                .context("invalid EXPECTED_REJECTIONS")?,
+        ),
+        Err(_) => None,
+    };
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut range: Option<Length> = None;
+    let mut speed: Option<Velocity> = None;
+    let mut accepted = 0;
+    let mut rejected = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let value = f64::try_from(&data).context("expected a single float64 value")?;
+
+                // the convention: every number carries a unit, and it is the SI unit
+                let Some(unit) = unit(&metadata.parameters) else {
+                    if rejected == 0 {
+                        eprintln!("rejecting `{id}` value {value}: no unit in metadata");
+                    }
+                    rejected += 1;
+                    continue;
+                };
+                let quantity = Quantity::from_parameters(&metadata.parameters, value)?;
+                if unit != si_unit(quantity.name())? {
+                    eprintln!("rejecting `{id}` value {value} {unit}: not normalized to SI");
+                    rejected += 1;
+                    continue;
+                }
+
+                match (id.as_str(), quantity) {
+                    ("range", Quantity::Length(length)) => {
+                        check_bounds(&id, length.get::<meter>(), 0.0, 10.0)?;
+                        range = Some(length);
+                    }
+                    ("speed", Quantity::Velocity(velocity)) => {
+                        check_bounds(&id, velocity.get::<meter_per_second>(), 0.0, 5.0)?;
+                        speed = Some(velocity);
+                    }
+                    ("temperature", Quantity::Temperature(temperature)) => {
+                        check_bounds(&id, temperature.get::<kelvin>(), 250.0, 350.0)?;
+                    }
+                    (other, quantity) => {
+                        bail!("input `{other}` received an unexpected {}", quantity.name())
+                    }
+                }
+                accepted += 1;
+
+                if let (Some(range), Some(speed)) = (range, speed) {
+                    // dividing a length by a velocity yields a time, checked by the compiler
+                    let time_to_contact: Time = range / speed;
+                    if accepted % 50 == 0 {
+                        println!(
+                            "range {:.3} m at {:.3} m/s: {:.2} s to contact",
+                            range.get::<meter>(),
+                            speed.get::<meter_per_second>(),
+                            time_to_contact.get::<second>()
+                        );
+                    }
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("accepted {accepted} values, rejected {rejected} without unit");
+    if let Some(expected) = expected_rejections
+        && rejected != expected
+    {
+        bail!("expected {expected} rejected values, got {rejected}");
+    }
+    Ok(())
+}
+
+/// A value outside of the physically plausible range usually means a unit mix-up, e.g. a
+/// range in cm that was read as m.
+fn check_bounds(id: &str, value: f64, min: f64, max: f64) -> eyre::Result<()> {
+    if !(min..=max).contains(&value) {
+        bail!("`{id}` value {value} is outside of the plausible range {min}..={max}");
+    }
+    Ok(())
+}
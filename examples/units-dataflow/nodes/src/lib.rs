@@ -0,0 +1,131 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{bail, eyre};
+use uom::si::{
+    f64::{Length, ThermodynamicTemperature, Velocity},
+    length::{centimeter, foot, inch, kilometer, meter, millimeter},
+    thermodynamic_temperature::{degree_celsius, degree_fahrenheit, kelvin},
+    velocity::{kilometer_per_hour, meter_per_second, mile_per_hour},
+};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the kind of quantity, e.g. `length`.
+pub const QUANTITY_KEY: &str = "quantity";
+/// Metadata key of the unit of the value, e.g. `cm`.
+pub const UNIT_KEY: &str = "unit";
+
+/// A physical quantity, stored in a type that knows its dimension.
+///
+/// Mixing up dimensions, e.g. dividing a temperature by a velocity, fails to compile. Mixing
+/// up units can't happen at all, because values only enter and leave through `parse` and
+/// `value_in`, which always take the unit into account.
+#[derive(Debug, Clone, Copy)]
+pub enum Quantity {
+    Length(Length),
+    Velocity(Velocity),
+    Temperature(ThermodynamicTemperature),
+}
+
+impl Quantity {
+    pub fn parse(quantity: &str, unit: &str, value: f64) -> eyre::Result<Self> {
+        Ok(match (quantity, unit) {
+            ("length", "m") => Self::Length(Length::new::<meter>(value)),
+            ("length", "mm") => Self::Length(Length::new::<millimeter>(value)),
+            ("length", "cm") => Self::Length(Length::new::<centimeter>(value)),
+            ("length", "km") => Self::Length(Length::new::<kilometer>(value)),
+            ("length", "in") => Self::Length(Length::new::<inch>(value)),
+            ("length", "ft") => Self::Length(Length::new::<foot>(value)),
+            ("velocity", "m/s") => Self::Velocity(Velocity::new::<meter_per_second>(value)),
+            ("velocity", "km/h") => Self::Velocity(Velocity::new::<kilometer_per_hour>(value)),
+            ("velocity", "mph") => Self::Velocity(Velocity::new::<mile_per_hour>(value)),
+            ("temperature", "K") => {
+                Self::Temperature(ThermodynamicTemperature::new::<kelvin>(value))
+            }
+            ("temperature", "degC") => {
+                Self::Temperature(ThermodynamicTemperature::new::<degree_celsius>(value))
+            }
+            ("temperature", "degF") => {
+                Self::Temperature(ThermodynamicTemperature::new::<degree_fahrenheit>(value))
+            }
+            _ => bail!("unsupported unit `{unit}` for quantity `{quantity}`"),
+        })
+    }
+
+    /// Creates a quantity from a value in the SI unit of `quantity`.
+    pub fn from_si(quantity: &str, value: f64) -> eyre::Result<Self> {
+        Self::parse(quantity, si_unit(quantity)?, value)
+    }
+
+    /// Reads a quantity from the `quantity` and `unit` metadata parameters.
+    ///
+    /// Fails for messages without a unit, there is no sensible default.
+    pub fn from_parameters(parameters: &MetadataParameters, value: f64) -> eyre::Result<Self> {
+        let quantity = string_parameter(parameters, QUANTITY_KEY)?;
+        let unit = string_parameter(parameters, UNIT_KEY)?;
+        Self::parse(quantity, unit, value)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Length(_) => "length",
+            Self::Velocity(_) => "velocity",
+            Self::Temperature(_) => "temperature",
+        }
+    }
+
+    pub fn value_in(&self, unit: &str) -> eyre::Result<f64> {
+        Ok(match (self, unit) {
+            (Self::Length(length), "m") => length.get::<meter>(),
+            (Self::Length(length), "mm") => length.get::<millimeter>(),
+            (Self::Length(length), "cm") => length.get::<centimeter>(),
+            (Self::Length(length), "km") => length.get::<kilometer>(),
+            (Self::Length(length), "in") => length.get::<inch>(),
+            (Self::Length(length), "ft") => length.get::<foot>(),
+            (Self::Velocity(velocity), "m/s") => velocity.get::<meter_per_second>(),
+            (Self::Velocity(velocity), "km/h") => velocity.get::<kilometer_per_hour>(),
+            (Self::Velocity(velocity), "mph") => velocity.get::<mile_per_hour>(),
+            (Self::Temperature(temperature), "K") => temperature.get::<kelvin>(),
+            (Self::Temperature(temperature), "degC") => temperature.get::<degree_celsius>(),
+            (Self::Temperature(temperature), "degF") => temperature.get::<degree_fahrenheit>(),
+            _ => bail!("unsupported unit `{unit}` for quantity `{}`", self.name()),
+        })
+    }
+
+    /// Returns the value in SI units, together with the metadata parameters describing it.
+    pub fn to_si(&self) -> eyre::Result<(f64, MetadataParameters)> {
+        let unit = si_unit(self.name())?;
+        Ok((self.value_in(unit)?, parameters(self.name(), unit)))
+    }
+}
+
+/// The unit that every quantity is normalized to.
+pub fn si_unit(quantity: &str) -> eyre::Result<&'static str> {
+    match quantity {
+        "length" => Ok("m"),
+        "velocity" => Ok("m/s"),
+        "temperature" => Ok("K"),
+        other => Err(eyre!("unknown quantity `{other}`")),
+    }
+}
+
+pub fn parameters(quantity: &str, unit: &str) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(QUANTITY_KEY.into(), Parameter::String(quantity.to_owned()));
+    parameters.insert(UNIT_KEY.into(), Parameter::String(unit.to_owned()));
+    parameters
+}
+
+pub fn unit(parameters: &MetadataParameters) -> Option<&str> {
+    match parameters.get(UNIT_KEY) {
+        Some(Parameter::String(unit)) => Some(unit),
+        _ => None,
+    }
+}
+
+fn string_parameter<'a>(parameters: &'a MetadataParameters, key: &str) -> eyre::Result<&'a str> {
+    match parameters.get(key) {
+        Some(Parameter::String(value)) => Ok(value),
+        Some(other) => bail!("expected string `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
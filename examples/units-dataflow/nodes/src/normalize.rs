@@ -0,0 +1,47 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use eyre::Context;
+use units_dataflow_nodes::{Quantity, unit};
+
+/// Converts every input to SI units and sends it on the output named after its quantity,
+/// e.g. `length` in `m`. Inputs without a unit or with an unknown unit are dropped with an
+/// error message instead of being guessed.
+fn main() -> eyre::Result<()> {
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut converted = 0;
+    let mut dropped = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => {
+                let value = f64::try_from(&data).context("expected a single float64 value")?;
+                let quantity = match Quantity::from_parameters(&metadata.parameters, value) {
+                    Ok(quantity) => quantity,
+                    Err(err) => {
+                        eprintln!("dropping `{id}` value {value}: {err}");
+                        dropped += 1;
+                        continue;
+                    }
+                };
+                let (si, parameters) = quantity.to_si()?;
+                if converted < 3 {
+                    println!(
+                        "`{id}`: {value} {} -> {si} {}",
+                        unit(&metadata.parameters).unwrap_or("?"),
+                        unit(&parameters).unwrap_or("?")
+                    );
+                }
+                node.send_output(
+                    DataId::from(quantity.name().to_owned()),
+                    parameters,
+                    si.into_arrow(),
+                )?;
+                converted += 1;
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("converted {converted} values, dropped {dropped}");
+    Ok(())
+}
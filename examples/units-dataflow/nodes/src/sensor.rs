@@ -0,0 +1,61 @@
+use dora_node_api::{self, DoraNode, Event, IntoArrow, dora_core::config::DataId};
+use eyre::Context;
+use units_dataflow_nodes::{Quantity, env_or, parameters};
+
+/// Sends a slowly varying value of `QUANTITY` in `UNIT`, with the unit in the metadata.
+///
+/// Without `UNIT`, the node behaves like a legacy driver: it sends plain numbers in its
+/// `RAW_UNIT` but doesn't tell anyone which unit that is.
+fn main() -> eyre::Result<()> {
+    let quantity = std::env::var("QUANTITY").context("QUANTITY is required")?;
+    let unit = std::env::var("UNIT").ok();
+    let raw_unit = std::env::var("RAW_UNIT").ok();
+    let base: f64 = env_or("BASE", 0.0)?;
+    let amplitude: f64 = env_or("AMPLITUDE", 1.0)?;
+    let messages: u64 = env_or("MESSAGES", 100)?;
+    let output = DataId::from("value".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut seq = 0;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data: _,
+            } => match id.as_str() {
+                "tick" => {
+                    // the true value, in SI units
+                    let si = base + amplitude * (seq as f64 * 0.1).sin();
+                    let quantity_value = Quantity::from_si(&quantity, si)?;
+                    let (value, parameters) = match (&unit, &raw_unit) {
+                        (Some(unit), _) => {
+                            (quantity_value.value_in(unit)?, parameters(&quantity, unit))
+                        }
+                        (None, Some(raw_unit)) => {
+                            (quantity_value.value_in(raw_unit)?, Default::default())
+                        }
+                        (None, None) => (si, Default::default()),
+                    };
+                    node.send_output(output.clone(), parameters, value.into_arrow())?;
+                    seq += 1;
+                    if seq >= messages {
+                        break;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "sent {seq} {quantity} values in {}",
+        unit.as_deref()
+            .or(raw_unit.as_deref())
+            .unwrap_or("SI units")
+    );
+    Ok(())
+}
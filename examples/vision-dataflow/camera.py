@@ -0,0 +1,120 @@
+#!/usr/bin/env python3
+"""
+Camera with a video fallback.
+- Captures `CAPTURE_PATH`, a camera index or a video URL, with OpenCV
+- Falls back to replaying `VIDEO_FILE` when the camera can't be opened, e.g. on a machine
+  without a camera. If the file doesn't exist, it's created first: a slow zoom into
+  `FALLBACK_IMAGE`, by default the picture of a bus that ultralytics ships with its package
+- Sends one `IMAGE_WIDTH`x`IMAGE_HEIGHT` frame on `image` every tick, and exits after `FRAMES`
+  frames, or at the end of the video
+
+The frames use the format of the `opencv-video-capture` node of the node hub: a flat `uint8`
+array in `bgr8`, with the `width`, `height` and `encoding` of the frame in its metadata. The
+metadata also has the number of the frame, and whether it came from the `camera` or the
+`video`.
+"""
+
+import os
+
+import cv2
+import numpy as np
+import pyarrow as pa
+from dora import Node
+
+CAPTURE_PATH = os.getenv("CAPTURE_PATH", "0")
+VIDEO_FILE = os.getenv("VIDEO_FILE", "out/fallback.mp4")
+FALLBACK_IMAGE = os.getenv("FALLBACK_IMAGE", "")
+FRAMES = int(os.getenv("FRAMES", "60"))
+IMAGE_WIDTH = int(os.getenv("IMAGE_WIDTH", "640"))
+IMAGE_HEIGHT = int(os.getenv("IMAGE_HEIGHT", "480"))
+
+
+def open_camera():
+    """The camera at `CAPTURE_PATH`, or `None` if it can't deliver a frame."""
+    path = int(CAPTURE_PATH) if CAPTURE_PATH.isnumeric() else CAPTURE_PATH
+    capture = cv2.VideoCapture(path)
+    if capture.isOpened() and capture.read()[0]:
+        return capture
+    capture.release()
+    return None
+
+
+def fallback_image():
+    if FALLBACK_IMAGE:
+        path = FALLBACK_IMAGE
+    else:
+        # installed with the detector
+        from ultralytics.utils import ASSETS
+
+        path = str(ASSETS / "bus.jpg")
+    image = cv2.imread(path)
+    if image is None:
+        raise FileNotFoundError(f"failed to read the fallback image {path}")
+    return image
+
+
+def write_fallback_video(path):
+    """Writes `FRAMES` frames that zoom from the whole fallback image to its center."""
+    image = fallback_image()
+    os.makedirs(os.path.dirname(path) or ".", exist_ok=True)
+    writer = cv2.VideoWriter(
+        path, cv2.VideoWriter_fourcc(*"mp4v"), 20, (IMAGE_WIDTH, IMAGE_HEIGHT)
+    )
+    height, width = image.shape[:2]
+    fit = min(IMAGE_WIDTH / width, IMAGE_HEIGHT / height)
+    for frame in range(FRAMES):
+        zoom = 1.0 + 0.4 * frame / max(FRAMES - 1, 1)
+        scaled = cv2.resize(image, None, fx=fit * zoom, fy=fit * zoom)
+        canvas = np.full((IMAGE_HEIGHT, IMAGE_WIDTH, 3), 64, dtype=np.uint8)
+        # the center of `scaled` on the center of the canvas, cropped on both
+        scaled_height, scaled_width = scaled.shape[:2]
+        x, y = (IMAGE_WIDTH - scaled_width) // 2, (IMAGE_HEIGHT - scaled_height) // 2
+        source = scaled[max(-y, 0) :, max(-x, 0) :][:IMAGE_HEIGHT, :IMAGE_WIDTH]
+        canvas[
+            max(y, 0) : max(y, 0) + source.shape[0], max(x, 0) : max(x, 0) + source.shape[1]
+        ] = source
+        writer.write(canvas)
+    writer.release()
+
+
+def main():
+    capture = open_camera()
+    source = "camera"
+    if capture is None:
+        if not os.path.exists(VIDEO_FILE):
+            print(f"writing a fallback video to {VIDEO_FILE}", flush=True)
+            write_fallback_video(VIDEO_FILE)
+        print(f"no camera at `{CAPTURE_PATH}`, replaying {VIDEO_FILE}", flush=True)
+        capture = cv2.VideoCapture(VIDEO_FILE)
+        source = "video"
+    if not capture.isOpened():
+        raise RuntimeError(f"failed to open {VIDEO_FILE}")
+
+    node = Node()
+    frame = 0
+    for event in node:
+        if event["type"] == "INPUT" and event["id"] == "tick":
+            if frame == FRAMES:
+                break
+            ok, image = capture.read()
+            if not ok:
+                print("reached the end of the video", flush=True)
+                break
+            image = cv2.resize(image, (IMAGE_WIDTH, IMAGE_HEIGHT))
+            metadata = {
+                "width": IMAGE_WIDTH,
+                "height": IMAGE_HEIGHT,
+                "encoding": "bgr8",
+                "frame": frame,
+                "source": source,
+            }
+            node.send_output("image", pa.array(image.ravel()), metadata)
+            frame += 1
+        elif event["type"] == "STOP":
+            break
+    capture.release()
+    print(f"sent {frame} frames from the {source}", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,77 @@
+#!/usr/bin/env python3
+"""
+YOLOv8 object detector.
+- Loads the ultralytics model `MODEL`, which ultralytics downloads on the first run
+- Detects the objects in every frame of `image`, an `rgb8` or `bgr8` image in the format of the
+  `opencv-video-capture` node, with at least `CONFIDENCE`
+- Sends the boxes of each frame on `bbox`, as one row with the `xyxy` pixel coordinates of all
+  boxes in a flat list, and their confidences and class names, with the metadata of the frame
+"""
+
+import os
+import time
+
+import numpy as np
+import pyarrow as pa
+from dora import Node
+from ultralytics import YOLO
+
+MODEL = os.getenv("MODEL", "yolov8n.pt")
+CONFIDENCE = float(os.getenv("CONFIDENCE", "0.25"))
+DEVICE = os.getenv("DEVICE") or None
+
+BBOX = pa.struct(
+    [
+        pa.field("bbox", pa.list_(pa.float32()), nullable=False),
+        pa.field("conf", pa.list_(pa.float32()), nullable=False),
+        pa.field("labels", pa.list_(pa.utf8()), nullable=False),
+    ]
+)
+
+
+def to_image(value, metadata):
+    """The frame as a `height x width x 3` BGR array, the order that ultralytics expects."""
+    width, height = metadata["width"], metadata["height"]
+    encoding = metadata.get("encoding", "bgr8")
+    if encoding not in ("rgb8", "bgr8"):
+        raise ValueError(f"unsupported image encoding `{encoding}`")
+    image = value.to_numpy().reshape(height, width, 3)
+    if encoding == "rgb8":
+        image = np.ascontiguousarray(image[..., ::-1])
+    return image
+
+
+def main():
+    model = YOLO(MODEL)
+    print(f"loaded {MODEL}", flush=True)
+
+    node = Node()
+    frames = 0
+    for event in node:
+        if event["type"] == "INPUT" and event["id"] == "image":
+            metadata = event["metadata"]
+            image = to_image(event["value"], metadata)
+
+            started = time.perf_counter()
+            result = model(image, conf=CONFIDENCE, device=DEVICE, verbose=False)[0]
+            inference_ms = (time.perf_counter() - started) * 1000
+
+            boxes = result.boxes
+            bbox = {
+                "bbox": boxes.xyxy.cpu().numpy().ravel().tolist(),
+                "conf": boxes.conf.cpu().numpy().tolist(),
+                "labels": [result.names[int(cls)] for cls in boxes.cls.cpu().numpy()],
+            }
+            metadata["format"] = "xyxy"
+            metadata["inference_ms"] = inference_ms
+            node.send_output("bbox", pa.array([bbox], type=BBOX), metadata)
+            frames += 1
+        elif event["type"] == "INPUT_CLOSED":
+            print(f"Input `{event['id']}` was closed", flush=True)
+        elif event["type"] == "STOP":
+            break
+    print(f"detected objects in {frames} frames", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,127 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{collections::BTreeSet, path::Path};
+
+/// Must match the `env` of the camera and the detector in `dataflow.yml`.
+const FRAMES: i64 = 60;
+const CONFIDENCE: f32 = 0.25;
+/// What YOLOv8 finds in the fallback video, which shows a bus with people in front of it.
+const FALLBACK_LABELS: [&str; 2] = ["bus", "person"];
+
+/// The report that `plot.py` writes.
+#[derive(Debug, Deserialize)]
+struct DetectionReport {
+    frames: Vec<Frame>,
+}
+
+/// Subset of a frame of `plot.py`.
+#[derive(Debug, Deserialize)]
+struct Frame {
+    frame: i64,
+    source: String,
+    width: f32,
+    height: f32,
+    detections: Vec<Detection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Detection {
+    label: String,
+    conf: f32,
+    bbox: [f32; 4],
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("vision-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let dora = Dora::from_env()?.uv();
+    dora.uv_venv("3.11").create().await?;
+
+    // installs OpenCV, ultralytics and PyTorch into the venv
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await?;
+
+    let report: DetectionReport = serde_json::from_str(
+        &std::fs::read_to_string("out/detections.json")
+            .context("the plot did not write a report")?,
+    )?;
+    let Some(last) = report.frames.last() else {
+        bail!("the detector sent no boxes");
+    };
+    // the detector skips frames when it is slower than the camera, but always works on the
+    // latest one
+    if report
+        .frames
+        .windows(2)
+        .any(|pair| pair[0].frame >= pair[1].frame)
+    {
+        bail!("the boxes are not in the order of the frames");
+    }
+    if last.frame != FRAMES - 1 {
+        bail!(
+            "expected the last boxes to be of frame {}, got frame {}",
+            FRAMES - 1,
+            last.frame
+        );
+    }
+    for frame in &report.frames {
+        for detection in &frame.detections {
+            let [x1, y1, x2, y2] = detection.bbox;
+            // YOLO clips the boxes to the image
+            let inside = 0.0 <= x1
+                && x1 < x2
+                && x2 <= frame.width
+                && 0.0 <= y1
+                && y1 < y2
+                && y2 <= frame.height;
+            if !inside
+                || detection.label.is_empty()
+                || !(CONFIDENCE..=1.0).contains(&detection.conf)
+            {
+                bail!("invalid detection in frame {}: {detection:?}", frame.frame);
+            }
+        }
+    }
+
+    let labels: BTreeSet<&str> = report
+        .frames
+        .iter()
+        .flat_map(|frame| &frame.detections)
+        .map(|detection| detection.label.as_str())
+        .collect();
+    println!(
+        "detected {labels:?} in {} of {FRAMES} frames from the {}",
+        report.frames.len(),
+        last.source
+    );
+    // only the objects of the fallback video are known
+    if last.source == "video" {
+        for label in FALLBACK_LABELS {
+            if !labels.contains(label) {
+                bail!("expected a `{label}` in the fallback video");
+            }
+        }
+    }
+
+    let video = std::fs::metadata("out/annotated.mp4")
+        .context("the plot did not write the annotated video")?;
+    if video.len() == 0 {
+        bail!("the annotated video is empty");
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
@@ -0,0 +1,138 @@
+#!/usr/bin/env python3
+"""
+Visualization sink.
+- Draws the boxes of `bbox` on the frame of `image` that they were detected in, matched by the
+  `frame` in their metadata
+- Shows the annotated frames in a window if `SHOW` is `true`, or on `auto` if there is a
+  display, and writes them to `OUTPUT_VIDEO`
+- Writes the detections of every frame to `REPORT_FILE` when `bbox` closes
+
+The detector skips frames when it's slower than the camera, so only the frames with boxes are
+drawn, and the frames without are dropped after a while.
+"""
+
+import json
+import os
+import sys
+
+import cv2
+import numpy as np
+from dora import Node
+
+SHOW = os.getenv("SHOW", "auto")
+OUTPUT_VIDEO = os.getenv("OUTPUT_VIDEO", "out/annotated.mp4")
+REPORT_FILE = os.getenv("REPORT_FILE", "out/detections.json")
+FPS = float(os.getenv("FPS", "20"))
+# frames kept until their boxes arrive
+PENDING_FRAMES = 30
+
+
+def should_show():
+    if SHOW == "auto":
+        return sys.platform != "linux" or bool(
+            os.getenv("DISPLAY") or os.getenv("WAYLAND_DISPLAY")
+        )
+    return SHOW == "true"
+
+
+def to_bgr(value, metadata):
+    width, height = metadata["width"], metadata["height"]
+    image = value.to_numpy().reshape(height, width, 3)
+    if metadata.get("encoding", "bgr8") == "rgb8":
+        image = image[..., ::-1]
+    # a writable copy to draw on
+    return np.ascontiguousarray(image)
+
+
+def draw(image, detections):
+    for detection in detections:
+        x1, y1, x2, y2 = (int(round(v)) for v in detection["bbox"])
+        text = f"{detection['label']} {detection['conf']:.0%}"
+        cv2.rectangle(image, (x1, y1), (x2, y2), (0, 255, 0), 2)
+        (text_width, text_height), _ = cv2.getTextSize(text, cv2.FONT_HERSHEY_SIMPLEX, 0.5, 1)
+        top = max(y1 - text_height - 6, 0)
+        # the label on a filled background, above the box if there is room
+        bottom = top + text_height + 6
+        cv2.rectangle(image, (x1, top), (x1 + text_width + 4, bottom), (0, 255, 0), -1)
+        cv2.putText(
+            image,
+            text,
+            (x1 + 2, bottom - 4),
+            cv2.FONT_HERSHEY_SIMPLEX,
+            0.5,
+            (0, 0, 0),
+            1,
+        )
+
+
+def main():
+    show = should_show()
+    node = Node()
+    pending = {}
+    writer = None
+    frames = []
+    for event in node:
+        if event["type"] == "INPUT" and event["id"] == "image":
+            metadata = event["metadata"]
+            pending[metadata["frame"]] = to_bgr(event["value"], metadata)
+            for frame in sorted(pending)[:-PENDING_FRAMES]:
+                del pending[frame]
+        elif event["type"] == "INPUT" and event["id"] == "bbox":
+            metadata = event["metadata"]
+            image = pending.pop(metadata["frame"], None)
+            if image is None:
+                print(f"no image for the boxes of frame {metadata['frame']}", flush=True)
+                continue
+            row = event["value"].to_pylist()[0]
+            bbox = row["bbox"]
+            detections = [
+                {"label": label, "conf": conf, "bbox": bbox[4 * i : 4 * i + 4]}
+                for i, (label, conf) in enumerate(zip(row["labels"], row["conf"]))
+            ]
+            draw(image, detections)
+            print(
+                f"frame {metadata['frame']}: "
+                + (", ".join(d["label"] for d in detections) or "nothing")
+                + f" ({metadata['inference_ms']:.1f} ms)",
+                flush=True,
+            )
+            frames.append(
+                {
+                    "frame": metadata["frame"],
+                    "source": metadata["source"],
+                    "width": metadata["width"],
+                    "height": metadata["height"],
+                    "inference_ms": metadata["inference_ms"],
+                    "detections": detections,
+                }
+            )
+
+            if writer is None:
+                os.makedirs(os.path.dirname(OUTPUT_VIDEO) or ".", exist_ok=True)
+                height, width = image.shape[:2]
+                writer = cv2.VideoWriter(
+                    OUTPUT_VIDEO, cv2.VideoWriter_fourcc(*"mp4v"), FPS, (width, height)
+                )
+            writer.write(image)
+            if show:
+                cv2.imshow("detections", image)
+                cv2.waitKey(1)
+        elif event["type"] == "INPUT_CLOSED":
+            print(f"Input `{event['id']}` was closed", flush=True)
+            if event["id"] == "bbox":
+                break
+        elif event["type"] == "STOP":
+            break
+
+    if writer is not None:
+        writer.release()
+    if show:
+        cv2.destroyAllWindows()
+    os.makedirs(os.path.dirname(REPORT_FILE) or ".", exist_ok=True)
+    with open(REPORT_FILE, "w") as file:
+        json.dump({"frames": frames}, file, indent=2)
+    print(f"wrote the detections of {len(frames)} frames to {REPORT_FILE}", flush=True)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,237 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail, eyre};
+use futures::{SinkExt, StreamExt, stream::SplitSink};
+use serde::Deserialize;
+use std::{
+    f64::consts::{FRAC_PI_2, PI},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time::{Instant, sleep},
+};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+/// Must match `WS_ADDR` and `HTTP_ADDR` in `dataflow.yml`.
+const WS_URL: &str = "ws://127.0.0.1:9001";
+const HTTP_ADDR: &str = "127.0.0.1:8080";
+/// Speeds of the scripted session, the same as the buttons of the teleop page.
+const LINEAR: f64 = 0.5;
+const ANGULAR: f64 = 1.0;
+/// Must match `CMD_TIMEOUT_MS` in `dataflow.yml`.
+const CMD_TIMEOUT: Duration = Duration::from_millis(500);
+
+type Sender = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+
+/// Subset of `ServerMessage` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Pose(Pose),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct Pose {
+    x: f64,
+    y: f64,
+    theta: f64,
+}
+
+impl Pose {
+    fn distance(&self, other: &Pose) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("web-teleop-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let dataflow_task = tokio::spawn(dora.run_dataflow(dataflow));
+    let session = teleop_session().await;
+    // the simulator stops after `DURATION_MS`, which ends the dataflow
+    dataflow_task.await??;
+    session
+}
+
+/// Drives the robot like a browser would, and checks the poses that come back.
+async fn teleop_session() -> eyre::Result<()> {
+    let (mut sender, mut receiver) = connect().await?.split();
+    let latest: Arc<Mutex<Option<Pose>>> = Arc::default();
+    let poses = tokio::spawn({
+        let latest = latest.clone();
+        async move {
+            while let Some(message) = receiver.next().await {
+                match message? {
+                    Message::Text(text) => {
+                        let ServerMessage::Pose(pose) = serde_json::from_str(&text)?;
+                        *latest.lock().unwrap() = Some(pose);
+                    }
+                    Message::Close(_) => break,
+                    _ => {}
+                }
+            }
+            eyre::Ok(())
+        }
+    });
+    let pose = || {
+        latest
+            .lock()
+            .unwrap()
+            .ok_or_else(|| eyre!("no pose received"))
+    };
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while latest.lock().unwrap().is_none() {
+        if Instant::now() > deadline {
+            bail!("the gateway did not send a pose");
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    let start = pose()?;
+
+    drive(&mut sender, LINEAR, 0.0, Duration::from_secs(2)).await?;
+    let forward = pose()?;
+    check("forward x", forward.x - start.x, 2.0 * LINEAR, 0.2)?;
+    check("forward y", forward.y - start.y, 0.0, 0.1)?;
+
+    drive(
+        &mut sender,
+        0.0,
+        ANGULAR,
+        Duration::from_secs_f64(FRAC_PI_2 / ANGULAR),
+    )
+    .await?;
+    let turned = pose()?;
+    let rotation = (turned.theta - forward.theta + PI).rem_euclid(2.0 * PI) - PI;
+    check("turn angle", rotation, FRAC_PI_2, 0.25)?;
+    check("turn offset", turned.distance(&forward), 0.0, 0.05)?;
+
+    drive(&mut sender, LINEAR, 0.0, Duration::from_secs(2)).await?;
+    let left = pose()?;
+    check("left x", left.x - turned.x, 0.0, 0.2)?;
+    check("left y", left.y - turned.y, 2.0 * LINEAR, 0.2)?;
+
+    // a single command without a stop, like a browser losing its connection
+    send(&mut sender, LINEAR, 0.0).await?;
+    sleep(CMD_TIMEOUT * 3).await;
+    let coasted = pose()?;
+    sleep(Duration::from_secs(1)).await;
+    check(
+        "distance after last command",
+        coasted.distance(&left),
+        LINEAR * CMD_TIMEOUT.as_secs_f64(),
+        0.15,
+    )?;
+    check(
+        "distance after timeout",
+        pose()?.distance(&coasted),
+        0.0,
+        0.01,
+    )?;
+
+    check_video().await?;
+
+    sender.close().await?;
+    poses.await??;
+    Ok(())
+}
+
+async fn connect() -> eyre::Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    // the gateway starts listening when the dataflow is up
+    let deadline = Instant::now() + Duration::from_secs(60);
+    loop {
+        match tokio_tungstenite::connect_async(WS_URL).await {
+            Ok((socket, _)) => return Ok(socket),
+            Err(_) if Instant::now() < deadline => sleep(Duration::from_millis(500)).await,
+            Err(err) => return Err(err).wrap_err("failed to connect to ws-gateway"),
+        }
+    }
+}
+
+async fn send(sender: &mut Sender, linear: f64, angular: f64) -> eyre::Result<()> {
+    let command = serde_json::json!({ "type": "cmd_vel", "linear": linear, "angular": angular });
+    sender.send(Message::text(command.to_string())).await?;
+    Ok(())
+}
+
+/// Repeats a command every 50 ms for `duration`, then stops and waits for the robot to settle.
+async fn drive(
+    sender: &mut Sender,
+    linear: f64,
+    angular: f64,
+    duration: Duration,
+) -> eyre::Result<()> {
+    println!("driving with {linear} m/s, {angular} rad/s for {duration:?}");
+    let end = Instant::now() + duration;
+    while Instant::now() < end {
+        send(sender, linear, angular).await?;
+        sleep(Duration::from_millis(50).min(end.saturating_duration_since(Instant::now()))).await;
+    }
+    let stop = serde_json::json!({ "type": "stop" });
+    sender.send(Message::text(stop.to_string())).await?;
+    sleep(Duration::from_millis(300)).await;
+    Ok(())
+}
+
+fn check(name: &str, actual: f64, expected: f64, tolerance: f64) -> eyre::Result<()> {
+    println!("{name}: {actual:.3} (expected {expected:.3} ± {tolerance})");
+    if (actual - expected).abs() > tolerance {
+        bail!("{name} is {actual:.3}, expected {expected:.3} ± {tolerance}");
+    }
+    Ok(())
+}
+
+/// Checks that the video sink serves JPEG snapshots and a live MJPEG stream.
+async fn check_video() -> eyre::Result<()> {
+    let mut stream = TcpStream::connect(HTTP_ADDR).await?;
+    stream
+        .write_all(b"GET /snapshot.jpg HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    let body_start = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| eyre!("invalid snapshot response"))?
+        + 4;
+    if !response.starts_with(b"HTTP/1.1 200") || !response[body_start..].starts_with(&[0xff, 0xd8])
+    {
+        bail!("/snapshot.jpg did not return a JPEG image");
+    }
+
+    // the stream has to keep delivering frames, not just the latest one
+    let mut stream = TcpStream::connect(HTTP_ADDR).await?;
+    stream
+        .write_all(b"GET /stream.mjpg HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await?;
+    let mut received = Vec::new();
+    let mut buf = vec![0; 64 * 1024];
+    let frames = |received: &[u8]| received.windows(7).filter(|w| *w == b"--frame").count();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while frames(&received) < 3 {
+            let len = stream.read(&mut buf).await?;
+            if len == 0 {
+                bail!("/stream.mjpg closed");
+            }
+            received.extend_from_slice(&buf[..len]);
+        }
+        eyre::Ok(())
+    })
+    .await
+    .wrap_err("timeout waiting for MJPEG frames")??;
+    println!("received a snapshot and {} MJPEG frames", frames(&received));
+    Ok(())
+}
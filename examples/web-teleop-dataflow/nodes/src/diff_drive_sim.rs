@@ -0,0 +1,190 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter,
+    arrow::{
+        array::{AsArray, Float64Array, UInt8Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use std::{
+    f64::consts::PI,
+    time::{Duration, Instant},
+};
+use web_teleop_dataflow_nodes::{ENCODING_KEY, HEIGHT_KEY, WIDTH_KEY, env_or};
+
+const WIDTH: usize = 160;
+const HEIGHT: usize = 120;
+const PIXELS_PER_METER: f64 = 20.0;
+const MAX_TRAIL: usize = 2000;
+
+/// Simulates a differential-drive robot in real time, stepping on every `tick`.
+///
+/// The `cmd_vel` input `[linear, angular]` is converted to wheel speeds, which are limited to
+/// `MAX_WHEEL_SPEED`. If no command arrives for `CMD_TIMEOUT_MS`, the robot stops.
+///
+/// Sends the pose `[x, y, theta]` on `pose` after every step, and a top-down view of the robot
+/// and its trail as `rgb8` image on `image` every `IMAGE_EVERY` steps. Stops after
+/// `DURATION_MS`, if set.
+fn main() -> eyre::Result<()> {
+    let wheel_base: f64 = env_or("WHEEL_BASE", 0.3)?;
+    let max_wheel_speed: f64 = env_or("MAX_WHEEL_SPEED", 1.0)?;
+    let cmd_timeout = Duration::from_millis(env_or("CMD_TIMEOUT_MS", 500)?);
+    let image_every: u64 = env_or("IMAGE_EVERY", 5)?;
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let pose_output = DataId::from("pose".to_owned());
+    let image_output = DataId::from("image".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut robot = Robot::default();
+    let mut trail: Vec<(f64, f64)> = Vec::new();
+    let mut command: Option<([f64; 2], Instant)> = None;
+    let mut distance = 0.0;
+    let mut steps = 0;
+    let start = Instant::now();
+    let mut last_step = start;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    let now = Instant::now();
+                    let dt = (now - last_step).as_secs_f64();
+                    last_step = now;
+
+                    // deadman: a lost connection must not leave the robot driving
+                    let [linear, angular] = match command {
+                        Some((command, received)) if now - received < cmd_timeout => command,
+                        Some(_) => {
+                            println!("no command for {cmd_timeout:?}, stopping");
+                            command = None;
+                            [0.0, 0.0]
+                        }
+                        None => [0.0, 0.0],
+                    };
+                    let (x, y) = (robot.x, robot.y);
+                    robot.step(linear, angular, dt, wheel_base, max_wheel_speed);
+                    distance += (robot.x - x).hypot(robot.y - y);
+                    if trail.last() != Some(&(robot.x, robot.y)) {
+                        trail.push((robot.x, robot.y));
+                        if trail.len() > MAX_TRAIL {
+                            trail.remove(0);
+                        }
+                    }
+
+                    node.send_output(
+                        pose_output.clone(),
+                        Default::default(),
+                        Float64Array::from(vec![robot.x, robot.y, robot.theta]),
+                    )?;
+                    if steps % image_every == 0 {
+                        let mut parameters = MetadataParameters::default();
+                        parameters.insert(WIDTH_KEY.into(), Parameter::Integer(WIDTH as i64));
+                        parameters.insert(HEIGHT_KEY.into(), Parameter::Integer(HEIGHT as i64));
+                        parameters.insert(ENCODING_KEY.into(), Parameter::String("rgb8".into()));
+                        node.send_output(
+                            image_output.clone(),
+                            parameters,
+                            UInt8Array::from(render(&robot, &trail)),
+                        )?;
+                    }
+                    steps += 1;
+                }
+                "cmd_vel" => {
+                    let values = data
+                        .as_primitive_opt::<Float64Type>()
+                        .ok_or_eyre("expected Float64 cmd_vel")?;
+                    match &values.values()[..] {
+                        &[linear, angular] => command = Some(([linear, angular], Instant::now())),
+                        other => eprintln!("ignoring cmd_vel with {} values", other.len()),
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!(
+        "stopped at x={:.2} y={:.2} theta={:.2} after driving {distance:.2} m",
+        robot.x, robot.y, robot.theta
+    );
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct Robot {
+    x: f64,
+    y: f64,
+    theta: f64,
+}
+
+impl Robot {
+    fn step(&mut self, linear: f64, angular: f64, dt: f64, wheel_base: f64, max_wheel_speed: f64) {
+        // the wheels saturate like real motors, which also limits turning while driving fast
+        let left = (linear - angular * wheel_base / 2.0).clamp(-max_wheel_speed, max_wheel_speed);
+        let right = (linear + angular * wheel_base / 2.0).clamp(-max_wheel_speed, max_wheel_speed);
+        let linear = (left + right) / 2.0;
+        let angular = (right - left) / wheel_base;
+
+        // integrate along the mean heading of the step
+        let heading = self.theta + angular * dt / 2.0;
+        self.x += linear * dt * heading.cos();
+        self.y += linear * dt * heading.sin();
+        self.theta = (self.theta + angular * dt + PI).rem_euclid(2.0 * PI) - PI;
+    }
+}
+
+/// Top-down view centered on the origin, with a grid of 1 m.
+fn render(robot: &Robot, trail: &[(f64, f64)]) -> Vec<u8> {
+    let mut image = Vec::with_capacity(WIDTH * HEIGHT * 3);
+    for py in 0..HEIGHT {
+        for px in 0..WIDTH {
+            let on_grid = (px as i64 - WIDTH as i64 / 2) % PIXELS_PER_METER as i64 == 0
+                || (py as i64 - HEIGHT as i64 / 2) % PIXELS_PER_METER as i64 == 0;
+            let shade: u8 = if on_grid { 60 } else { 30 };
+            image.extend_from_slice(&[shade; 3]);
+        }
+    }
+    let mut set = |x: f64, y: f64, color: [u8; 3]| {
+        let px = (WIDTH as f64 / 2.0 + x * PIXELS_PER_METER).round();
+        // image rows grow downwards, y grows upwards
+        let py = (HEIGHT as f64 / 2.0 - y * PIXELS_PER_METER).round();
+        if (0.0..WIDTH as f64).contains(&px) && (0.0..HEIGHT as f64).contains(&py) {
+            let index = (py as usize * WIDTH + px as usize) * 3;
+            image[index..index + 3].copy_from_slice(&color);
+        }
+    };
+
+    for &(x, y) in trail {
+        set(x, y, [40, 160, 80]);
+    }
+    let radius = 0.15;
+    let steps = (radius * PIXELS_PER_METER) as i64;
+    for i in -steps..=steps {
+        for j in -steps..=steps {
+            let (dx, dy) = (i as f64 / PIXELS_PER_METER, j as f64 / PIXELS_PER_METER);
+            if dx.hypot(dy) <= radius {
+                set(robot.x + dx, robot.y + dy, [240, 140, 30]);
+            }
+        }
+    }
+    for i in 0..=(0.4 * PIXELS_PER_METER) as i64 {
+        let length = i as f64 / PIXELS_PER_METER;
+        set(
+            robot.x + length * robot.theta.cos(),
+            robot.y + length * robot.theta.sin(),
+            [255, 255, 255],
+        );
+    }
+    image
+}
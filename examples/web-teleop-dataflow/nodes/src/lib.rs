@@ -0,0 +1,38 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::bail;
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::env_or;
+
+/// Metadata keys of the `image` output of the simulator.
+pub const WIDTH_KEY: &str = "width";
+pub const HEIGHT_KEY: &str = "height";
+pub const ENCODING_KEY: &str = "encoding";
+
+/// Messages from the browser to `ws-gateway`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Velocity command in m/s and rad/s. The browser repeats it while a key is held, the robot
+    /// stops when the commands stop arriving.
+    CmdVel {
+        linear: f64,
+        angular: f64,
+    },
+    Stop,
+}
+
+/// Messages from `ws-gateway` to the browser.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Pose { x: f64, y: f64, theta: f64 },
+}
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
@@ -0,0 +1,202 @@
+use dora_node_api::{
+    self, DoraNode, Event, Parameter,
+    arrow::{array::AsArray, datatypes::UInt8Type},
+};
+use eyre::{Context, OptionExt, bail};
+use jpeg_encoder::{ColorType, Encoder};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+use web_teleop_dataflow_nodes::{ENCODING_KEY, HEIGHT_KEY, WIDTH_KEY, env_or, integer_parameter};
+
+const INDEX: &str = include_str!("../../web/index.html");
+
+/// The latest encoded frame, shared with the threads serving the HTTP clients.
+#[derive(Default)]
+struct Latest {
+    frame: Mutex<Frame>,
+    updated: Condvar,
+}
+
+#[derive(Default)]
+struct Frame {
+    seq: u64,
+    jpeg: Option<Arc<Vec<u8>>>,
+    closed: bool,
+}
+
+/// Serves the teleop page and the `image` input as MJPEG stream on `HTTP_ADDR`.
+///
+/// - `GET /` returns the teleop page, which connects to the WebSocket at `WS_URL`.
+/// - `GET /stream.mjpg` streams every frame as JPEG, browsers show it in an `<img>` tag.
+/// - `GET /snapshot.jpg` returns the latest frame.
+///
+/// Stops when the `image` input closes.
+fn main() -> eyre::Result<()> {
+    let addr: String = env_or("HTTP_ADDR", "127.0.0.1:8080".to_owned())?;
+    let ws_url: String = env_or("WS_URL", "ws://127.0.0.1:9001".to_owned())?;
+    let quality: u8 = env_or("JPEG_QUALITY", 80)?;
+
+    let index = Arc::new(INDEX.replace("{{WS_URL}}", &ws_url));
+    let latest = Arc::new(Latest::default());
+    let listener =
+        TcpListener::bind(&addr).with_context(|| format!("failed to listen on {addr}"))?;
+    println!("teleop page at http://{addr}/");
+    std::thread::spawn({
+        let latest = latest.clone();
+        move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        eprintln!("failed to accept connection: {err}");
+                        continue;
+                    }
+                };
+                let latest = latest.clone();
+                let index = index.clone();
+                std::thread::spawn(move || {
+                    // errors are mostly browsers closing the stream
+                    if let Err(err) = serve(stream, &latest, &index) {
+                        eprintln!("HTTP client failed: {err}");
+                    }
+                });
+            }
+        }
+    });
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let width = integer_parameter(&metadata.parameters, WIDTH_KEY)?;
+                    let height = integer_parameter(&metadata.parameters, HEIGHT_KEY)?;
+                    match metadata.parameters.get(ENCODING_KEY) {
+                        Some(Parameter::String(encoding)) if encoding == "rgb8" => {}
+                        other => bail!("expected rgb8 image, got encoding {other:?}"),
+                    }
+                    let pixels = data
+                        .as_primitive_opt::<UInt8Type>()
+                        .ok_or_eyre("expected UInt8 image")?;
+                    let mut jpeg = Vec::new();
+                    Encoder::new(&mut jpeg, quality).encode(
+                        pixels.values(),
+                        width.try_into()?,
+                        height.try_into()?,
+                        ColorType::Rgb,
+                    )?;
+
+                    let mut frame = latest.frame.lock().unwrap();
+                    frame.seq += 1;
+                    frame.jpeg = Some(Arc::new(jpeg));
+                    latest.updated.notify_all();
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "image" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let mut frame = latest.frame.lock().unwrap();
+    frame.closed = true;
+    latest.updated.notify_all();
+    println!("encoded {} frames", frame.seq);
+    Ok(())
+}
+
+fn serve(mut stream: TcpStream, latest: &Latest, index: &str) -> eyre::Result<()> {
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(&stream);
+    reader.read_line(&mut request_line)?;
+    // the headers are not needed
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+
+    match path {
+        "/" => respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            index.as_bytes(),
+        ),
+        "/snapshot.jpg" => {
+            let jpeg = latest.frame.lock().unwrap().jpeg.clone();
+            match jpeg {
+                Some(jpeg) => respond(&mut stream, "200 OK", "image/jpeg", &jpeg),
+                None => respond(
+                    &mut stream,
+                    "503 Service Unavailable",
+                    "text/plain",
+                    b"no frame yet",
+                ),
+            }
+        }
+        "/stream.mjpg" => {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary=frame\r\n\
+                 Cache-Control: no-cache\r\nConnection: close\r\n\r\n"
+            )?;
+            let mut sent = 0;
+            loop {
+                let (seq, jpeg) = {
+                    let (frame, _) = latest
+                        .updated
+                        .wait_timeout_while(
+                            latest.frame.lock().unwrap(),
+                            Duration::from_secs(1),
+                            |frame| frame.seq == sent && !frame.closed,
+                        )
+                        .unwrap();
+                    if frame.closed {
+                        return Ok(());
+                    }
+                    (frame.seq, frame.jpeg.clone())
+                };
+                // a slow client skips frames instead of falling behind
+                let Some(jpeg) = jpeg.filter(|_| seq != sent) else {
+                    continue;
+                };
+                sent = seq;
+                write!(
+                    stream,
+                    "--frame\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+                    jpeg.len()
+                )?;
+                stream.write_all(&jpeg)?;
+                stream.write_all(b"\r\n")?;
+            }
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", b"not found"),
+    }
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> eyre::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::{
+        array::{AsArray, Float64Array},
+        datatypes::Float64Type,
+    },
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, OptionExt, bail, eyre};
+use futures::channel::mpsc::UnboundedSender;
+use std::{
+    io::ErrorKind,
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, TryRecvError},
+    },
+    time::Duration,
+};
+use tungstenite::Message;
+use web_teleop_dataflow_nodes::{ClientMessage, ServerMessage, env_or};
+
+/// Senders of the threads serving the connected browsers.
+type Clients = Arc<Mutex<Vec<mpsc::Sender<String>>>>;
+
+/// Bridges browsers to the dataflow over WebSocket on `WS_ADDR`.
+///
+/// Every `cmd_vel` message of a client is clamped to `MAX_LINEAR` and `MAX_ANGULAR` and sent on
+/// `cmd_vel` as `[linear, angular]`. The last command wins if several clients are connected.
+/// Every `pose` input is sent to all clients as JSON.
+///
+/// Stops when the `pose` input closes.
+fn main() -> eyre::Result<()> {
+    let addr: String = env_or("WS_ADDR", "127.0.0.1:9001".to_owned())?;
+    let max_linear: f64 = env_or("MAX_LINEAR", 1.0)?;
+    let max_angular: f64 = env_or("MAX_ANGULAR", 2.0)?;
+    let output = DataId::from("cmd_vel".to_owned());
+
+    let listener =
+        TcpListener::bind(&addr).with_context(|| format!("failed to listen on {addr}"))?;
+    println!("teleop WebSocket listening on ws://{addr}");
+
+    // every client gets its own thread, the commands are merged into the dora event loop
+    let clients = Clients::default();
+    let (commands_tx, commands_rx) = futures::channel::mpsc::unbounded();
+    std::thread::spawn({
+        let clients = clients.clone();
+        move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        eprintln!("failed to accept connection: {err}");
+                        continue;
+                    }
+                };
+                let (poses_tx, poses_rx) = mpsc::channel();
+                clients.lock().unwrap().push(poses_tx);
+                let commands = commands_tx.clone();
+                std::thread::spawn(move || {
+                    if let Err(err) = serve_client(stream, &commands, poses_rx) {
+                        eprintln!("teleop client failed: {err:?}");
+                    }
+                    // a browser that goes away must not leave the robot driving
+                    let _ = commands.unbounded_send(ClientMessage::Stop);
+                });
+            }
+        }
+    });
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let merged = events.merge_external(Box::pin(commands_rx));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut commands = 0;
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input {
+                    id,
+                    metadata: _,
+                    data,
+                } => match id.as_str() {
+                    "pose" => {
+                        let pose = data
+                            .as_primitive_opt::<Float64Type>()
+                            .ok_or_eyre("expected Float64 pose")?;
+                        let &[x, y, theta] = &pose.values()[..] else {
+                            bail!("expected [x, y, theta], got {} values", pose.len());
+                        };
+                        let text = serde_json::to_string(&ServerMessage::Pose { x, y, theta })?;
+                        // the send fails once the thread of a client has ended
+                        clients
+                            .lock()
+                            .unwrap()
+                            .retain(|client| client.send(text.clone()).is_ok());
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::InputClosed { id } => {
+                    if id.as_str() == "pose" {
+                        break;
+                    }
+                }
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External(command) => {
+                let (linear, angular) = match command {
+                    ClientMessage::CmdVel { linear, angular } => (linear, angular),
+                    ClientMessage::Stop => (0.0, 0.0),
+                };
+                if !linear.is_finite() || !angular.is_finite() {
+                    eprintln!("ignoring invalid command {linear}, {angular}");
+                    continue;
+                }
+                let command = [
+                    linear.clamp(-max_linear, max_linear),
+                    angular.clamp(-max_angular, max_angular),
+                ];
+                node.send_output(
+                    output.clone(),
+                    Default::default(),
+                    Float64Array::from(command.to_vec()),
+                )?;
+                commands += 1;
+            }
+        }
+    }
+
+    println!("forwarded {commands} commands");
+    Ok(())
+}
+
+/// Reads the commands of one browser, and sends it the poses in between.
+fn serve_client(
+    stream: TcpStream,
+    commands: &UnboundedSender<ClientMessage>,
+    poses: mpsc::Receiver<String>,
+) -> eyre::Result<()> {
+    let peer = stream.peer_addr()?;
+    let mut socket =
+        tungstenite::accept(stream).map_err(|err| eyre!("handshake with {peer} failed: {err}"))?;
+    println!("teleop client {peer} connected");
+    // a short read timeout, so that the poses don't wait for the next command
+    socket
+        .get_ref()
+        .set_read_timeout(Some(Duration::from_millis(10)))?;
+
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                Ok(command) => {
+                    if commands.unbounded_send(command).is_err() {
+                        return Ok(());
+                    }
+                }
+                Err(err) => eprintln!("ignoring invalid message from {peer}: {err}"),
+            },
+            Ok(Message::Close(_)) => {
+                println!("teleop client {peer} disconnected");
+                return Ok(());
+            }
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(err))
+                if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(err) => return Err(err).wrap_err_with(|| format!("connection to {peer} failed")),
+        }
+        loop {
+            match poses.try_recv() {
+                Ok(text) => socket.send(Message::text(text))?,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+    }
+}
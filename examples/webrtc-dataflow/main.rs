@@ -0,0 +1,161 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::{Dora, read_json};
+use eyre::{Context, bail, eyre};
+use serde::Deserialize;
+use std::{path::Path, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    process::Command,
+};
+
+/// Must match `HTTP_ADDR`, `WIDTH`, `HEIGHT`, and `FRAMES` in `dataflow.yml`.
+const HTTP_ADDR: &str = "127.0.0.1:8096";
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+const FRAMES: u64 = 450;
+/// Frames of the video that the viewer receives, 3 seconds.
+const VIEWER_FRAMES: u64 = 90;
+
+/// Subset of `SinkStatus` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct SinkStatus {
+    frames: u64,
+    encoded: u64,
+    keyframes: u64,
+    connections: u64,
+}
+
+/// Subset of `ViewerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ViewerReport {
+    packets: u64,
+    frames: u64,
+    keyframes: u64,
+    first_keyframe_ms: Option<u64>,
+    decoded: u64,
+    width: u32,
+    height: u32,
+    square_frames: u64,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("webrtc-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    match std::fs::remove_dir_all("out") {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let dataflow_task = tokio::spawn(dora.run_dataflow(dataflow));
+    let viewer = watch().await;
+    // the scene stops after `FRAMES`, which ends the dataflow
+    dataflow_task.await??;
+    viewer?;
+
+    let sink: SinkStatus = read_json("out/sink.json")?;
+    println!(
+        "sink: received {} images, encoded {} with {} keyframes, for {} viewers",
+        sink.frames, sink.encoded, sink.keyframes, sink.connections
+    );
+    if sink.frames != FRAMES {
+        bail!("expected the sink to receive all {FRAMES} images");
+    }
+    if sink.connections == 0 || sink.encoded == 0 {
+        bail!("the sink never streamed to a viewer");
+    }
+    // nobody watches before the viewer connects, and after it left
+    if sink.encoded >= FRAMES {
+        bail!("the sink should only encode while someone watches");
+    }
+
+    println!("Everything Done");
+    Ok(())
+}
+
+/// Waits for the page, and watches the video with `webrtc-viewer`, which connects the way the
+/// page does.
+async fn watch() -> eyre::Result<()> {
+    let start = tokio::time::Instant::now();
+    while !port_check::is_port_reachable(HTTP_ADDR) {
+        if start.elapsed() > Duration::from_secs(60) {
+            bail!("the sink did not listen on {HTTP_ADDR}");
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    let index = get("/").await?;
+    if !String::from_utf8_lossy(&index).contains("RTCPeerConnection") {
+        bail!("the page should connect with an RTCPeerConnection");
+    }
+
+    let status = Command::new("nodes/target/release/webrtc-viewer")
+        .args(["--url", &format!("http://{HTTP_ADDR}")])
+        .args(["--frames", &VIEWER_FRAMES.to_string()])
+        .args(["--timeout-secs", "10"])
+        .args(["--report", "out/viewer.json"])
+        .kill_on_drop(true)
+        .status()
+        .await
+        .context("failed to run webrtc-viewer")?;
+    if !status.success() {
+        bail!("webrtc-viewer failed");
+    }
+
+    let viewer: ViewerReport = read_json("out/viewer.json")?;
+    println!(
+        "viewer: {} frames in {} packets, {} keyframes, the first after {:?} ms, decoded {} of \
+         {}x{}, {} with the square",
+        viewer.frames,
+        viewer.packets,
+        viewer.keyframes,
+        viewer.first_keyframe_ms,
+        viewer.decoded,
+        viewer.width,
+        viewer.height,
+        viewer.square_frames
+    );
+    if viewer.keyframes == 0 {
+        bail!("the viewer received no keyframe, so a browser could not show the video");
+    }
+    if (viewer.width, viewer.height) != (WIDTH, HEIGHT) {
+        bail!("expected a {WIDTH}x{HEIGHT} video");
+    }
+    // frames before the first keyframe can't be decoded
+    if viewer.decoded * 3 < VIEWER_FRAMES * 2 {
+        bail!("expected the viewer to decode most of the {VIEWER_FRAMES} frames");
+    }
+    if viewer.square_frames * 10 < viewer.decoded * 9 {
+        bail!("expected the red square of the scene in the decoded video");
+    }
+    Ok(())
+}
+
+async fn get(path: &str) -> eyre::Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(HTTP_ADDR).await?;
+    stream
+        .write_all(
+            format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .as_bytes(),
+        )
+        .await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    let body_start = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| eyre!("invalid response to {path}"))?
+        + 4;
+    if !response.starts_with(b"HTTP/1.1 200") {
+        bail!("{path} failed");
+    }
+    Ok(response.split_off(body_start))
+}
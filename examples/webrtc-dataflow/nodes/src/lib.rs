@@ -0,0 +1,136 @@
+use dora_node_api::{
+    MetadataParameters, Parameter,
+    arrow::{
+        array::{Array, AsArray},
+        datatypes::UInt8Type,
+    },
+};
+use eyre::{OptionExt, bail, eyre};
+use serde::{Deserialize, Serialize};
+use webrtc::{
+    api::{
+        API, APIBuilder, interceptor_registry::register_default_interceptors,
+        media_engine::MediaEngine,
+    },
+    interceptor::registry::Registry,
+};
+
+pub use example_node_utils::{env_or, write_json};
+
+/// Metadata keys of the `image` outputs.
+pub const WIDTH_KEY: &str = "width";
+pub const HEIGHT_KEY: &str = "height";
+pub const ENCODING_KEY: &str = "encoding";
+pub const FRAME_KEY: &str = "frame";
+
+/// An `rgb8` image, as `scene-sim` sends it on `image`.
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub frame: u64,
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Decodes an `image` input: the pixels as a `UInt8` array, the rest in the metadata.
+    pub fn from_input(parameters: &MetadataParameters, data: &dyn Array) -> eyre::Result<Self> {
+        match parameters.get(ENCODING_KEY) {
+            Some(Parameter::String(encoding)) if encoding == "rgb8" => {}
+            other => bail!("expected an rgb8 image, got encoding {other:?}"),
+        }
+        let image = Self {
+            frame: integer_parameter(parameters, FRAME_KEY)? as u64,
+            width: integer_parameter(parameters, WIDTH_KEY)?.try_into()?,
+            height: integer_parameter(parameters, HEIGHT_KEY)?.try_into()?,
+            pixels: data
+                .as_primitive_opt::<UInt8Type>()
+                .ok_or_eyre("expected a UInt8 array")?
+                .values()
+                .to_vec(),
+        };
+        if image.pixels.len() != image.width as usize * image.height as usize * 3 {
+            bail!(
+                "image {} has {} bytes, expected {}x{} rgb8",
+                image.frame,
+                image.pixels.len(),
+                image.width,
+                image.height
+            );
+        }
+        Ok(image)
+    }
+}
+
+/// Returned by `GET /api/status` of `webrtc-sink`, and written to its `REPORT_FILE`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SinkStatus {
+    /// Images received.
+    pub frames: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Images encoded and sent, only while someone watches.
+    pub encoded: u64,
+    pub keyframes: u64,
+    /// Peers that are connected now.
+    pub viewers: usize,
+    /// Peers that ever connected.
+    pub connections: u64,
+}
+
+/// Returned by `GET /api/config` of `webrtc-sink`, so that the page uses the same ICE servers.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PageConfig {
+    pub ice_servers: Vec<String>,
+}
+
+/// Written by `webrtc-viewer` to its `--report`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ViewerReport {
+    pub packets: u64,
+    pub bytes: u64,
+    /// Frames of the video, reassembled from the RTP packets.
+    pub frames: u64,
+    /// IDR frames, where a decoder can start.
+    pub keyframes: u64,
+    /// Milliseconds from sending the offer to the first keyframe.
+    pub first_keyframe_ms: Option<u64>,
+    /// Frames that decoded to an image, from the first keyframe on.
+    pub decoded: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Decoded images that show the red square of `scene-sim`.
+    pub square_frames: u64,
+}
+
+/// A WebRTC API with the default codecs, which include H.264, and the default interceptors,
+/// e.g. for NACK and RTCP reports.
+pub fn webrtc_api() -> eyre::Result<API> {
+    let mut media_engine = MediaEngine::default();
+    media_engine.register_default_codecs()?;
+    let registry = register_default_interceptors(Registry::new(), &mut media_engine)?;
+    Ok(APIBuilder::new()
+        .with_media_engine(media_engine)
+        .with_interceptor_registry(registry)
+        .build())
+}
+
+/// Whether the H.264 access unit `annex_b`, in the byte stream format of Annex B, has an IDR
+/// slice, i.e. is a keyframe.
+pub fn is_keyframe(annex_b: &[u8]) -> bool {
+    annex_b
+        .windows(4)
+        // the start codes `00 00 01`, `00 00 00 01` end in the same 3 bytes
+        .any(|window| window[..3] == [0, 0, 1] && window[3] & 0x1f == NAL_IDR_SLICE)
+}
+
+/// The `nal_unit_type` of a slice of an IDR picture.
+const NAL_IDR_SLICE: u8 = 5;
+
+pub fn integer_parameter(parameters: &MetadataParameters, key: &str) -> eyre::Result<i64> {
+    match parameters.get(key) {
+        Some(Parameter::Integer(value)) => Ok(*value),
+        Some(other) => bail!("expected integer `{key}` parameter, got {other:?}"),
+        None => bail!("missing `{key}` parameter"),
+    }
+}
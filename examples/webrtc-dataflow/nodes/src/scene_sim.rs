@@ -0,0 +1,82 @@
+use dora_node_api::{
+    self, DoraNode, Event, MetadataParameters, Parameter, arrow::array::UInt8Array,
+    dora_core::config::DataId,
+};
+use webrtc_dataflow_nodes::{ENCODING_KEY, FRAME_KEY, HEIGHT_KEY, WIDTH_KEY, env_or};
+
+/// The color of the square, which `webrtc-viewer` looks for in the decoded video.
+const SQUARE_COLOR: [u8; 3] = [220, 30, 30];
+const SQUARE_SIZE: u32 = 40;
+
+/// Renders a red square that bounces across a gray gradient on every `tick`, like a camera
+/// would send images, and sends each as `WIDTH` x `HEIGHT` `rgb8` image on `image`.
+///
+/// Stops after `FRAMES` frames, or never if it's 0.
+fn main() -> eyre::Result<()> {
+    let width: u32 = env_or("WIDTH", 320)?;
+    let height: u32 = env_or("HEIGHT", 240)?;
+    let frames: u64 = env_or("FRAMES", 0)?;
+    let output = DataId::from("image".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let (mut x, mut y) = (0i64, height as i64 / 3);
+    let (mut vx, mut vy) = (4i64, 3i64);
+    let mut frame = 0u64;
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, .. } => match id.as_str() {
+                "tick" => {
+                    if frames > 0 && frame == frames {
+                        break;
+                    }
+                    let mut parameters = MetadataParameters::default();
+                    parameters.insert(FRAME_KEY.into(), Parameter::Integer(frame as i64));
+                    parameters.insert(WIDTH_KEY.into(), Parameter::Integer(width.into()));
+                    parameters.insert(HEIGHT_KEY.into(), Parameter::Integer(height.into()));
+                    parameters.insert(ENCODING_KEY.into(), Parameter::String("rgb8".into()));
+                    node.send_output(
+                        output.clone(),
+                        parameters,
+                        UInt8Array::from(render(x as u32, y as u32, width, height)),
+                    )?;
+
+                    let (max_x, max_y) =
+                        ((width - SQUARE_SIZE) as i64, (height - SQUARE_SIZE) as i64);
+                    if !(0..=max_x).contains(&(x + vx)) {
+                        vx = -vx;
+                    }
+                    if !(0..=max_y).contains(&(y + vy)) {
+                        vy = -vy;
+                    }
+                    (x, y) = (x + vx, y + vy);
+                    frame += 1;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => println!("Input `{id}` was closed"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    println!("rendered {frame} frames");
+    Ok(())
+}
+
+/// A horizontal gradient with the square at `x`, `y` on top.
+fn render(x: u32, y: u32, width: u32, height: u32) -> Vec<u8> {
+    let mut image = Vec::with_capacity(width as usize * height as usize * 3);
+    for row in 0..height {
+        for column in 0..width {
+            let inside =
+                (x..x + SQUARE_SIZE).contains(&column) && (y..y + SQUARE_SIZE).contains(&row);
+            if inside {
+                image.extend_from_slice(&SQUARE_COLOR);
+            } else {
+                image.extend_from_slice(&[(40 + 120 * column / width.max(1)) as u8; 3]);
+            }
+        }
+    }
+    image
+}
@@ -0,0 +1,336 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::Html,
+    routing::{get, post},
+};
+use bytes::Bytes;
+use dora_node_api::{DoraNode, Event};
+use eyre::{Context, OptionExt, bail};
+use openh264::{
+    encoder::Encoder,
+    formats::{RgbSliceU8, YUVBuffer},
+};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use webrtc::{
+    api::{API, media_engine::MIME_TYPE_H264},
+    ice_transport::ice_server::RTCIceServer,
+    media::Sample,
+    peer_connection::{
+        RTCPeerConnection, configuration::RTCConfiguration,
+        peer_connection_state::RTCPeerConnectionState,
+        sdp::session_description::RTCSessionDescription,
+    },
+    rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication,
+    rtp_transceiver::rtp_codec::RTCRtpCodecCapability,
+    track::track_local::{TrackLocal, track_local_static_sample::TrackLocalStaticSample},
+};
+use webrtc_dataflow_nodes::{
+    Image, PageConfig, SinkStatus, env_or, is_keyframe, webrtc_api, write_json,
+};
+
+const INDEX: &str = include_str!("../../web/index.html");
+
+struct Peer {
+    connection: Arc<RTCPeerConnection>,
+    connected: bool,
+}
+
+/// What the HTTP handlers and the dora event loop share.
+struct Shared {
+    api: API,
+    ice_servers: Vec<String>,
+    /// Every peer gets this track, so each frame is encoded once for all of them.
+    track: Arc<TrackLocalStaticSample>,
+    peers: Mutex<HashMap<u64, Peer>>,
+    next_peer: AtomicU64,
+    /// Set when a viewer connects or lost the picture, the next frame is then encoded as a
+    /// keyframe, where its decoder can start.
+    keyframe_requested: AtomicBool,
+    status: Mutex<SinkStatus>,
+}
+
+/// Streams every `image` to the browsers that watch, as H.264 over WebRTC.
+///
+/// Serves a page on `HTTP_ADDR` that shows the video. Its signaling is a single request: the
+/// page posts its offer to `POST /offer`, and gets the answer with all ICE candidates of the
+/// node. `GET /api/status` returns the `SinkStatus`. For viewers outside of the local network,
+/// set `ICE_SERVERS` to a comma-separated list of STUN or TURN URLs, which the page uses too.
+///
+/// Encodes only while someone watches. Writes the last `SinkStatus` to `REPORT_FILE` when the
+/// input closes.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let addr: String = env_or("HTTP_ADDR", "127.0.0.1:8096".to_owned())?;
+    let ice_servers: String = env_or("ICE_SERVERS", String::new())?;
+    let report_file: PathBuf = env_or("REPORT_FILE", "out/sink.json".to_owned())?.into();
+
+    let shared = Arc::new(Shared {
+        api: webrtc_api()?,
+        ice_servers: ice_servers
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_owned)
+            .collect(),
+        track: Arc::new(TrackLocalStaticSample::new(
+            RTCRtpCodecCapability {
+                mime_type: MIME_TYPE_H264.to_owned(),
+                ..Default::default()
+            },
+            "video".to_owned(),
+            "dora".to_owned(),
+        )),
+        peers: Mutex::default(),
+        next_peer: AtomicU64::new(0),
+        keyframe_requested: AtomicBool::new(false),
+        status: Mutex::default(),
+    });
+    let app = Router::new()
+        .route("/", get(page))
+        .route("/api/config", get(api_config))
+        .route("/api/status", get(api_status))
+        .route("/offer", post(offer))
+        .with_state(shared.clone());
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to listen on {addr}"))?;
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            eprintln!("HTTP server failed: {err}");
+        }
+    });
+    println!("serving the video on http://{addr}/");
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+    let mut encoder = Encoder::new().context("failed to create H.264 encoder")?;
+    let mut last_frame: Option<Instant> = None;
+    while let Some(event) = events.recv_async().await {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "image" => {
+                    let image = Image::from_input(&metadata.parameters, &data.0)?;
+                    let now = Instant::now();
+                    // how far the timestamps of the video advance
+                    let duration = last_frame.map_or(Duration::from_millis(33), |last| now - last);
+                    last_frame = Some(now);
+
+                    let viewers = {
+                        let mut status = shared.status.lock().unwrap();
+                        if status.frames == 0 {
+                            if image.width % 2 != 0 || image.height % 2 != 0 {
+                                bail!(
+                                    "H.264 needs an even width and height, got {}x{}",
+                                    image.width,
+                                    image.height
+                                );
+                            }
+                            (status.width, status.height) = (image.width, image.height);
+                        } else if (image.width, image.height) != (status.width, status.height) {
+                            bail!(
+                                "image {} is {}x{}, the stream was started with {}x{}",
+                                image.frame,
+                                image.width,
+                                image.height,
+                                status.width,
+                                status.height
+                            );
+                        }
+                        status.frames += 1;
+                        status.viewers
+                    };
+                    if viewers == 0 {
+                        continue;
+                    }
+
+                    if shared.keyframe_requested.swap(false, Ordering::Relaxed) {
+                        encoder.force_intra_frame();
+                    }
+                    let yuv = YUVBuffer::from_rgb_source(RgbSliceU8::new(
+                        &image.pixels,
+                        (image.width as usize, image.height as usize),
+                    ));
+                    let h264 = encoder
+                        .encode(&yuv)
+                        .context("failed to encode image")?
+                        .to_vec();
+                    let keyframe = is_keyframe(&h264);
+                    let sample = Sample {
+                        data: Bytes::from(h264),
+                        duration,
+                        ..Default::default()
+                    };
+                    // a peer that went away must not stop the others
+                    if let Err(err) = shared.track.write_sample(&sample).await {
+                        eprintln!("failed to send image {}: {err}", image.frame);
+                    }
+                    let mut status = shared.status.lock().unwrap();
+                    status.encoded += 1;
+                    status.keyframes += u64::from(keyframe);
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                println!("Input `{id}` was closed");
+                if id.as_str() == "image" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    let peers: Vec<_> = shared.peers.lock().unwrap().drain().collect();
+    for (id, peer) in peers {
+        if let Err(err) = peer.connection.close().await {
+            eprintln!("failed to close the connection to viewer {id}: {err}");
+        }
+    }
+    let status = shared.status.lock().unwrap().clone();
+    println!(
+        "received {} images, sent {} to {} viewers",
+        status.frames, status.encoded, status.connections
+    );
+    write_json(&report_file, &status)
+}
+
+async fn page() -> Html<&'static str> {
+    Html(INDEX)
+}
+
+async fn api_config(State(shared): State<Arc<Shared>>) -> Json<PageConfig> {
+    Json(PageConfig {
+        ice_servers: shared.ice_servers.clone(),
+    })
+}
+
+async fn api_status(State(shared): State<Arc<Shared>>) -> Json<SinkStatus> {
+    Json(shared.status.lock().unwrap().clone())
+}
+
+async fn offer(
+    State(shared): State<Arc<Shared>>,
+    Json(offer): Json<RTCSessionDescription>,
+) -> Result<Json<RTCSessionDescription>, (StatusCode, String)> {
+    answer(&shared, offer).await.map(Json).map_err(|err| {
+        eprintln!("failed to answer an offer: {err:#}");
+        (StatusCode::BAD_REQUEST, format!("{err:#}"))
+    })
+}
+
+/// Connects a new peer that receives the video track, and answers its `offer`.
+///
+/// Answers only once the ICE candidates of the node are gathered, so that the answer carries
+/// them all and the peer needs no further signaling.
+async fn answer(
+    shared: &Arc<Shared>,
+    offer: RTCSessionDescription,
+) -> eyre::Result<RTCSessionDescription> {
+    let mut config = RTCConfiguration::default();
+    if !shared.ice_servers.is_empty() {
+        config.ice_servers = vec![RTCIceServer {
+            urls: shared.ice_servers.clone(),
+            ..Default::default()
+        }];
+    }
+    let connection = Arc::new(shared.api.new_peer_connection(config).await?);
+    let id = shared.next_peer.fetch_add(1, Ordering::Relaxed);
+    shared.peers.lock().unwrap().insert(
+        id,
+        Peer {
+            connection: connection.clone(),
+            connected: false,
+        },
+    );
+    let answer = negotiate(shared, &connection, id, offer).await;
+    if answer.is_err() {
+        shared.peers.lock().unwrap().remove(&id);
+        let _ = connection.close().await;
+    }
+    answer
+}
+
+async fn negotiate(
+    shared: &Arc<Shared>,
+    connection: &RTCPeerConnection,
+    id: u64,
+    offer: RTCSessionDescription,
+) -> eyre::Result<RTCSessionDescription> {
+    let sender = connection
+        .add_track(shared.track.clone() as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+    // the peer asks for a keyframe over RTCP when it lost the picture; the task ends with the
+    // connection
+    let keyframe_shared = shared.clone();
+    tokio::spawn(async move {
+        let mut buf = vec![0; 1500];
+        while let Ok((packets, _)) = sender.read(&mut buf).await {
+            let lost = packets.iter().any(|packet| {
+                packet
+                    .as_any()
+                    .downcast_ref::<PictureLossIndication>()
+                    .is_some()
+            });
+            if lost {
+                keyframe_shared
+                    .keyframe_requested
+                    .store(true, Ordering::Relaxed);
+            }
+        }
+    });
+    let state_shared = shared.clone();
+    connection.on_peer_connection_state_change(Box::new(move |state| {
+        state_changed(&state_shared, id, state);
+        Box::pin(async {})
+    }));
+
+    connection.set_remote_description(offer).await?;
+    let answer = connection.create_answer(None).await?;
+    let mut gathered = connection.gathering_complete_promise().await;
+    connection.set_local_description(answer).await?;
+    let _ = gathered.recv().await;
+    connection
+        .local_description()
+        .await
+        .ok_or_eyre("the connection has no local description")
+}
+
+fn state_changed(shared: &Shared, id: u64, state: RTCPeerConnectionState) {
+    let mut peers = shared.peers.lock().unwrap();
+    match state {
+        RTCPeerConnectionState::Connected => {
+            if let Some(peer) = peers.get_mut(&id) {
+                peer.connected = true;
+            }
+            shared.status.lock().unwrap().connections += 1;
+            shared.keyframe_requested.store(true, Ordering::Relaxed);
+            println!("viewer {id} connected");
+        }
+        RTCPeerConnectionState::Disconnected => {
+            if let Some(peer) = peers.get_mut(&id) {
+                peer.connected = false;
+            }
+            println!("viewer {id} disconnected");
+        }
+        RTCPeerConnectionState::Failed | RTCPeerConnectionState::Closed => {
+            if let Some(peer) = peers.remove(&id) {
+                println!("viewer {id} left");
+                tokio::spawn(async move {
+                    let _ = peer.connection.close().await;
+                });
+            }
+        }
+        _ => {}
+    }
+    shared.status.lock().unwrap().viewers = peers.values().filter(|peer| peer.connected).count();
+}
@@ -0,0 +1,223 @@
+//! A viewer of `webrtc-sink` without a browser, e.g. for checks on a headless machine.
+//!
+//! Usage: `webrtc-viewer [--url <url>] [--frames <n>] [--timeout-secs <n>] [--report <path>]`
+//!
+//! Connects to the `webrtc-sink` on `--url` the way the page does, receives `--frames` frames
+//! of the video, and decodes them with OpenH264. Writes a `ViewerReport` to `--report`, or
+//! fails if the frames don't arrive within `--timeout-secs`.
+
+use eyre::{Context, OptionExt, bail, eyre};
+use openh264::decoder::Decoder;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use webrtc::{
+    media::io::sample_builder::SampleBuilder,
+    peer_connection::{
+        configuration::RTCConfiguration, sdp::session_description::RTCSessionDescription,
+    },
+    rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication,
+    rtp::codecs::h264::H264Packet,
+    rtp_transceiver::{
+        RTCRtpTransceiverInit, rtp_codec::RTPCodecType,
+        rtp_transceiver_direction::RTCRtpTransceiverDirection,
+    },
+};
+use webrtc_dataflow_nodes::{ViewerReport, is_keyframe, webrtc_api, write_json};
+
+/// Packets that the sample builder waits for a late packet, before it drops the frame.
+const MAX_LATE_PACKETS: u16 = 64;
+/// The RTP clock rate of video.
+const VIDEO_CLOCK_RATE: u32 = 90_000;
+/// The red square of `scene-sim` has 40x40 pixels, some of which the encoder blurs.
+const MIN_SQUARE_PIXELS: usize = 800;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let mut url = "http://127.0.0.1:8096".to_owned();
+    let mut frames: u64 = 60;
+    let mut timeout = Duration::from_secs(30);
+    let mut report_file = PathBuf::from("out/viewer.json");
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--url" => url = value()?,
+            "--frames" => frames = value()?.parse()?,
+            "--timeout-secs" => timeout = Duration::from_secs(value()?.parse()?),
+            "--report" => report_file = PathBuf::from(value()?),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+
+    let report = tokio::time::timeout(timeout, watch(&url, frames))
+        .await
+        .map_err(|_| eyre!("received fewer than {frames} frames within {timeout:?}"))??;
+    println!(
+        "received {} frames in {} packets, decoded {} of {}x{}",
+        report.frames, report.packets, report.decoded, report.width, report.height
+    );
+    write_json(&report_file, &report)
+}
+
+async fn watch(url: &str, frames: u64) -> eyre::Result<ViewerReport> {
+    let connection = Arc::new(
+        webrtc_api()?
+            .new_peer_connection(RTCConfiguration::default())
+            .await?,
+    );
+    connection
+        .add_transceiver_from_kind(
+            RTPCodecType::Video,
+            Some(RTCRtpTransceiverInit {
+                direction: RTCRtpTransceiverDirection::Recvonly,
+                send_encodings: Vec::new(),
+            }),
+        )
+        .await?;
+    let (track_tx, mut track_rx) = tokio::sync::mpsc::channel(1);
+    connection.on_track(Box::new(move |track, _, _| {
+        let track_tx = track_tx.clone();
+        Box::pin(async move {
+            let _ = track_tx.send(track).await;
+        })
+    }));
+
+    // like the page, send the offer once all ICE candidates are gathered
+    let offer = connection.create_offer(None).await?;
+    let mut gathered = connection.gathering_complete_promise().await;
+    connection.set_local_description(offer).await?;
+    let _ = gathered.recv().await;
+    let offer = connection
+        .local_description()
+        .await
+        .ok_or_eyre("the connection has no local description")?;
+    let start = Instant::now();
+    let answer: RTCSessionDescription = post_json(url, "/offer", &offer).await?;
+    connection.set_remote_description(answer).await?;
+    let track = track_rx
+        .recv()
+        .await
+        .ok_or_eyre("the connection closed before the video arrived")?;
+
+    // the sink sends a keyframe when a viewer connects, in case it's lost, ask again until
+    // one arrives
+    let keyframe_received = Arc::new(AtomicBool::new(false));
+    tokio::spawn({
+        let connection = connection.clone();
+        let keyframe_received = keyframe_received.clone();
+        let media_ssrc = track.ssrc();
+        async move {
+            while !keyframe_received.load(Ordering::Relaxed) {
+                let pli = PictureLossIndication {
+                    sender_ssrc: 0,
+                    media_ssrc,
+                };
+                if connection.write_rtcp(&[Box::new(pli)]).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+    });
+
+    let mut samples = SampleBuilder::new(MAX_LATE_PACKETS, H264Packet::default(), VIDEO_CLOCK_RATE);
+    let mut decoder = Decoder::new().context("failed to create H.264 decoder")?;
+    let mut rgb = Vec::new();
+    let mut report = ViewerReport::default();
+    while report.frames < frames {
+        let (packet, _) = track.read_rtp().await.context("failed to read the video")?;
+        report.packets += 1;
+        report.bytes += packet.payload.len() as u64;
+        samples.push(packet);
+        while let Some(sample) = samples.pop() {
+            report.frames += 1;
+            if is_keyframe(&sample.data) {
+                report.keyframes += 1;
+                report
+                    .first_keyframe_ms
+                    .get_or_insert(start.elapsed().as_millis() as u64);
+                keyframe_received.store(true, Ordering::Relaxed);
+            }
+            // the frames before the first keyframe refer to frames that this viewer never got
+            if report.keyframes == 0 {
+                continue;
+            }
+            let Some(yuv) = decoder
+                .decode(&sample.data)
+                .context("failed to decode frame")?
+            else {
+                continue;
+            };
+            let (width, height) = yuv.dimensions();
+            rgb.resize(width * height * 3, 0);
+            yuv.write_rgb8(&mut rgb);
+            report.decoded += 1;
+            (report.width, report.height) = (width as u32, height as u32);
+            if square_pixels(&rgb) >= MIN_SQUARE_PIXELS {
+                report.square_frames += 1;
+            }
+        }
+    }
+
+    connection.close().await?;
+    Ok(report)
+}
+
+/// Counts the pixels in the red of the square of `scene-sim`, whose background is gray.
+fn square_pixels(rgb: &[u8]) -> usize {
+    rgb.chunks_exact(3)
+        .filter(|pixel| pixel[0] > 150 && pixel[1] < 90 && pixel[2] < 90)
+        .count()
+}
+
+/// Posts `body` as JSON to `path` on `url`, an `http://<host>:<port>` URL, and parses the JSON
+/// response.
+async fn post_json<T: Serialize, R: DeserializeOwned>(
+    url: &str,
+    path: &str,
+    body: &T,
+) -> eyre::Result<R> {
+    let addr = url
+        .strip_prefix("http://")
+        .ok_or_eyre("only http:// URLs are supported")?
+        .trim_end_matches('/');
+    let body = serde_json::to_vec(body)?;
+    let mut stream = TcpStream::connect(addr)
+        .await
+        .with_context(|| format!("failed to connect to {addr}"))?;
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(request.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+
+    let response = String::from_utf8(response)?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_eyre("invalid HTTP response")?;
+    if !head.starts_with("HTTP/1.1 200") {
+        bail!(
+            "{path} failed: {}: {body}",
+            head.lines().next().unwrap_or_default()
+        );
+    }
+    Ok(serde_json::from_str(body)?)
+}
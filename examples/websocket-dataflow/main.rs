@@ -0,0 +1,120 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::{path::Path, time::Duration};
+use tokio::{
+    net::TcpStream,
+    time::{Instant, sleep, timeout},
+};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+/// Must match `WS_ADDR` in `dataflow.yml`.
+const WS_URL: &str = "ws://127.0.0.1:9002";
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Subset of `ServerMessage` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct ServerMessage {
+    input: String,
+    text: String,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("websocket-dataflow-runner").wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    let dataflow_task = tokio::spawn(dora.run_dataflow(dataflow));
+    let session = browser_session().await;
+    // `shout` stops after `DURATION_MS`, which ends the dataflow
+    dataflow_task.await??;
+    session
+}
+
+/// Two clients, like two browser tabs: each gets the replies to its own messages, and both get
+/// the status broadcasts.
+async fn browser_session() -> eyre::Result<()> {
+    let mut alice = connect().await?;
+    let mut bob = connect().await?;
+
+    send(&mut alice, "hello dora").await?;
+    let reply = next_message(&mut alice, "reply").await?;
+    if reply.text != "HELLO DORA" {
+        bail!("expected `HELLO DORA`, got `{}`", reply.text);
+    }
+
+    send(&mut bob, "from bob").await?;
+    let reply = next_message(&mut bob, "reply").await?;
+    if reply.text != "FROM BOB" {
+        bail!("bob got the reply `{}`, expected `FROM BOB`", reply.text);
+    }
+
+    // earlier statuses may still be queued, the ones after the replies count both messages
+    for (name, socket) in [("alice", &mut alice), ("bob", &mut bob)] {
+        loop {
+            let status = next_message(socket, "status").await?;
+            if status.text == "2 messages from 2 clients" {
+                println!("{name} got status `{}`", status.text);
+                break;
+            }
+        }
+    }
+
+    alice.close(None).await?;
+    bob.close(None).await?;
+    Ok(())
+}
+
+async fn connect() -> eyre::Result<Socket> {
+    // the gateway starts listening when the dataflow is up
+    let deadline = Instant::now() + Duration::from_secs(60);
+    loop {
+        match tokio_tungstenite::connect_async(WS_URL).await {
+            Ok((socket, _)) => return Ok(socket),
+            Err(_) if Instant::now() < deadline => sleep(Duration::from_millis(500)).await,
+            Err(err) => return Err(err).wrap_err("failed to connect to ws-gateway"),
+        }
+    }
+}
+
+async fn send(socket: &mut Socket, text: &str) -> eyre::Result<()> {
+    let message = serde_json::json!({ "text": text });
+    socket.send(Message::text(message.to_string())).await?;
+    Ok(())
+}
+
+/// Waits for the next message from `input`. Fails on a reply to another client, or if no
+/// message arrives within 3 s.
+async fn next_message(socket: &mut Socket, input: &str) -> eyre::Result<ServerMessage> {
+    let receive = async {
+        while let Some(message) = socket.next().await {
+            let Message::Text(text) = message? else {
+                continue;
+            };
+            let message: ServerMessage = serde_json::from_str(&text)?;
+            if message.input == input {
+                return Ok(message);
+            }
+            if message.input == "reply" {
+                bail!(
+                    "got a reply that was meant for another client: `{}`",
+                    message.text
+                );
+            }
+        }
+        bail!("the gateway closed the connection")
+    };
+    timeout(Duration::from_secs(3), receive)
+        .await
+        .with_context(|| format!("no `{input}` message within 3 s"))?
+}
@@ -0,0 +1,35 @@
+use dora_node_api::{MetadataParameters, Parameter};
+use serde::{Deserialize, Serialize};
+
+pub use example_node_utils::env_or;
+
+/// Metadata key of the id of the client that a message came from, or is meant for.
+pub const CLIENT_KEY: &str = "client";
+
+/// Sent by browsers.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientMessage {
+    pub text: String,
+}
+
+/// Sent to browsers for every input of the gateway.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerMessage {
+    pub input: String,
+    /// The client that the message is meant for, `None` for all clients.
+    pub client: Option<u64>,
+    pub text: String,
+}
+
+pub fn client_parameters(client: u64) -> MetadataParameters {
+    let mut parameters = MetadataParameters::default();
+    parameters.insert(CLIENT_KEY.into(), Parameter::Integer(client as i64));
+    parameters
+}
+
+pub fn client(parameters: &MetadataParameters) -> Option<u64> {
+    match parameters.get(CLIENT_KEY) {
+        Some(Parameter::Integer(client)) => u64::try_from(*client).ok(),
+        _ => None,
+    }
+}
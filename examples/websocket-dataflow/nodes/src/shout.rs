@@ -0,0 +1,66 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+};
+use eyre::OptionExt;
+use std::{
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
+use websocket_dataflow_nodes::{client, client_parameters, env_or};
+
+/// Replies to every `message` with its text in upper case on `reply`, for the client that sent
+/// it. Sends how many messages it has seen from how many clients on `status` on every `tick`.
+///
+/// Stops after `DURATION_MS`.
+fn main() -> eyre::Result<()> {
+    // runs until the dataflow stops if unset
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let reply_output = DataId::from("reply".to_owned());
+    let status_output = DataId::from("status".to_owned());
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let start = Instant::now();
+    let mut messages = 0;
+    let mut clients = BTreeSet::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, metadata, data } => match id.as_str() {
+                "message" => {
+                    let texts = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    let client =
+                        client(&metadata.parameters).ok_or_eyre("message without client")?;
+                    clients.insert(client);
+                    for text in texts.iter().flatten() {
+                        messages += 1;
+                        node.send_output(
+                            reply_output.clone(),
+                            client_parameters(client),
+                            StringArray::from(vec![text.to_uppercase()]),
+                        )?;
+                    }
+                }
+                "tick" => {
+                    if start.elapsed() >= duration {
+                        break;
+                    }
+                    let status = format!("{messages} messages from {} clients", clients.len());
+                    node.send_output(
+                        status_output.clone(),
+                        Default::default(),
+                        StringArray::from(vec![status]),
+                    )?;
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
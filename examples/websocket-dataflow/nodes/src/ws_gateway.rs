@@ -0,0 +1,154 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::{AsArray, StringArray},
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, OptionExt};
+use futures::{SinkExt, StreamExt, channel::mpsc::UnboundedSender};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast,
+};
+use tokio_tungstenite::tungstenite::Message;
+use websocket_dataflow_nodes::{ClientMessage, ServerMessage, client, client_parameters, env_or};
+
+/// A text message of a browser, with the id of its connection.
+#[derive(Debug)]
+struct Incoming {
+    client: u64,
+    text: String,
+}
+
+/// Exposes the dataflow to browsers over WebSocket on `WS_ADDR`.
+///
+/// Text of the clients is merged into the event loop as external events, and sent on `message`
+/// with the id of the client in the `client` metadata parameter. Every input is forwarded to
+/// the clients as a [`ServerMessage`]: to the client in its `client` parameter if it has one,
+/// and to all clients otherwise.
+///
+/// Stops when all inputs are closed.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let addr: String = env_or("WS_ADDR", "127.0.0.1:9002".to_owned())?;
+    let output = DataId::from("message".to_owned());
+
+    let listener = TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to listen on {addr}"))?;
+    println!("WebSocket gateway listening on ws://{addr}");
+
+    let (incoming_tx, incoming_rx) = futures::channel::mpsc::unbounded();
+    let (outgoing_tx, _) = broadcast::channel::<ServerMessage>(256);
+    tokio::spawn({
+        let outgoing = outgoing_tx.clone();
+        async move {
+            let next_client = Arc::new(AtomicU64::new(1));
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(connection) => connection,
+                    Err(err) => {
+                        eprintln!("failed to accept connection: {err}");
+                        continue;
+                    }
+                };
+                let client = next_client.fetch_add(1, Ordering::Relaxed);
+                println!("client {client} connected from {peer}");
+                let incoming = incoming_tx.clone();
+                let outgoing = outgoing.subscribe();
+                tokio::spawn(async move {
+                    if let Err(err) = serve_client(client, stream, incoming, outgoing).await {
+                        eprintln!("client {client} failed: {err:?}");
+                    }
+                    println!("client {client} disconnected");
+                });
+            }
+        }
+    });
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let mut events = events.merge_external(Box::pin(incoming_rx));
+
+    while let Some(event) = events.next().await {
+        match event {
+            MergedEvent::External(Incoming { client, text }) => {
+                node.send_output(
+                    output.clone(),
+                    client_parameters(client),
+                    StringArray::from(vec![text]),
+                )?;
+            }
+            MergedEvent::Dora(event) => match event {
+                Event::Input { id, metadata, data } => {
+                    let texts = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array")?;
+                    let client = client(&metadata.parameters);
+                    for text in texts.iter().flatten() {
+                        // fails only if no client is connected
+                        let _ = outgoing_tx.send(ServerMessage {
+                            input: id.to_string(),
+                            client,
+                            text: text.to_owned(),
+                        });
+                    }
+                }
+                Event::InputClosed { id } => println!("Input `{id}` was closed"),
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+        }
+    }
+
+    Ok(())
+}
+
+async fn serve_client(
+    client: u64,
+    stream: TcpStream,
+    incoming: UnboundedSender<Incoming>,
+    mut outgoing: broadcast::Receiver<ServerMessage>,
+) -> eyre::Result<()> {
+    let socket = tokio_tungstenite::accept_async(stream).await?;
+    let (mut sender, mut receiver) = socket.split();
+    loop {
+        tokio::select! {
+            message = receiver.next() => match message.transpose()? {
+                Some(Message::Text(text)) => {
+                    let message: ClientMessage = match serde_json::from_str(&text) {
+                        Ok(message) => message,
+                        Err(err) => {
+                            eprintln!("ignoring invalid message of client {client}: {err}");
+                            continue;
+                        }
+                    };
+                    incoming.unbounded_send(Incoming {
+                        client,
+                        text: message.text,
+                    })?;
+                }
+                Some(Message::Close(_)) | None => return Ok(()),
+                Some(_) => {}
+            },
+            message = outgoing.recv() => match message {
+                Ok(message) => {
+                    if message.client.is_none_or(|target| target == client) {
+                        sender.send(Message::text(serde_json::to_string(&message)?)).await?;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!("client {client} is too slow, skipped {skipped} messages");
+                }
+                // the dataflow is done
+                Err(broadcast::error::RecvError::Closed) => {
+                    sender.close().await?;
+                    return Ok(());
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use dora_tracing::set_up_tracing;
+use example_runner_utils::Dora;
+use eyre::{Context, bail};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path, time::Duration};
+use tokio::{
+    net::TcpStream,
+    process::{Child, Command},
+    time::{Instant, sleep},
+};
+
+/// Must match `DURATION_MS` in `dataflow.yml`.
+const GATEWAY_DURATION: Duration = Duration::from_secs(12);
+/// Must match `min_interval_ms` of the telemetry rule in `forwarding.json5`.
+const TELEMETRY_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Subset of `PeerReport` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct PeerReport {
+    received: BTreeMap<String, u64>,
+    echoes: u64,
+}
+
+/// Subset of `AuditEntry` in `nodes/src/lib.rs`.
+#[derive(Debug, Deserialize)]
+struct AuditEntry {
+    action: String,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    set_up_tracing("zenoh-dual-network-dataflow-runner")
+        .wrap_err("failed to set up tracing subscriber")?;
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::env::set_current_dir(root.join(file!()).parent().unwrap())
+        .wrap_err("failed to set working dir")?;
+
+    let dora = Dora::from_env()?;
+    let dataflow = Path::new("dataflow.yml");
+    dora.build_dataflow(dataflow).await?;
+
+    // both networks exist before the gateway connects to them
+    let mut robot = start_peer("robot", "config/robot-peer.json5", "127.0.0.1:7447").await?;
+    let mut cloud = start_peer("cloud", "config/cloud-router.json5", "127.0.0.1:7448").await?;
+
+    dora.run_dataflow(dataflow).await?;
+
+    for (name, peer) in [("robot", &mut robot), ("cloud", &mut cloud)] {
+        if !peer.wait().await?.success() {
+            bail!("{name} peer failed");
+        }
+    }
+    let robot = read_report("out/robot.json")?;
+    let cloud = read_report("out/cloud.json")?;
+    let received = |report: &PeerReport, key: &str| report.received.get(key).copied().unwrap_or(0);
+
+    // outbound
+    for key in [
+        "fleet/robot-1/telemetry/battery",
+        "fleet/robot-1/status",
+        "shared/announce",
+    ] {
+        if received(&cloud, key) < 5 {
+            bail!(
+                "the cloud received {} samples on `{key}`",
+                received(&cloud, key)
+            );
+        }
+    }
+    let imu = received(&cloud, "fleet/robot-1/telemetry/imu");
+    let max_imu = (GATEWAY_DURATION.as_millis() / TELEMETRY_INTERVAL.as_millis()) as u64 + 1;
+    if imu == 0 || imu > max_imu {
+        bail!("the cloud received {imu} IMU samples, expected at most {max_imu}");
+    }
+    if let Some(key) = cloud
+        .received
+        .keys()
+        .find(|key| key.contains("camera") || key.contains("debug") || key.starts_with("robot/"))
+    {
+        bail!("`{key}` leaked to the external network");
+    }
+
+    // inbound
+    if received(&robot, "robot/cmd/velocity") < 20 || received(&robot, "shared/announce") < 5 {
+        bail!(
+            "the robot missed commands or announcements: {:?}",
+            robot.received
+        );
+    }
+    if let Some(key) = robot
+        .received
+        .keys()
+        .find(|key| key.ends_with("shutdown") || key.starts_with("fleet/"))
+    {
+        bail!("`{key}` leaked to the internal network");
+    }
+
+    if robot.echoes > 0 || cloud.echoes > 0 {
+        bail!(
+            "the gateway sent {} samples back to the robot and {} to the cloud",
+            robot.echoes,
+            cloud.echoes
+        );
+    }
+
+    let mut actions: BTreeMap<String, u64> = BTreeMap::new();
+    for line in std::fs::read_to_string("out/audit.jsonl")?.lines() {
+        let entry: AuditEntry = serde_json::from_str(line)?;
+        *actions.entry(entry.action).or_default() += 1;
+    }
+    for action in ["forwarded", "excluded", "too_large", "rate_limited"] {
+        if !actions.contains_key(action) {
+            bail!("no `{action}` entries in the audit log: {actions:?}");
+        }
+    }
+    println!("gateway audit: {actions:?}");
+
+    Ok(())
+}
+
+async fn start_peer(role: &str, config: &str, address: &str) -> eyre::Result<Child> {
+    let peer = Command::new("nodes/target/release/network-peer")
+        .args(["--role", role, "--config", config])
+        .args(["--duration-secs", "25"])
+        .args(["--report", &format!("out/{role}.json")])
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("failed to start {role} peer"))?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while TcpStream::connect(address).await.is_err() {
+        if Instant::now() > deadline {
+            bail!("{role} peer is not listening on {address}");
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    Ok(peer)
+}
+
+fn read_report(path: &str) -> eyre::Result<PeerReport> {
+    let report = std::fs::read_to_string(path).with_context(|| format!("failed to read {path}"))?;
+    Ok(serde_json::from_str(&report)?)
+}
@@ -0,0 +1,51 @@
+use dora_node_api::{self, DoraNode, Event, arrow::array::AsArray};
+use eyre::OptionExt;
+use std::{collections::BTreeMap, path::PathBuf};
+use zenoh_dual_network_dataflow_nodes::{
+    Action, AuditEntry, Direction, create_jsonl, env_or, write_jsonl,
+};
+
+/// Writes the `audit` rows of the gateway to `AUDIT_FILE`, and prints how many samples were
+/// forwarded or dropped per direction when the input closes.
+fn main() -> eyre::Result<()> {
+    let audit_file: PathBuf = env_or("AUDIT_FILE", "out/audit.jsonl".to_owned())?.into();
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+
+    let mut log = create_jsonl(&audit_file)?;
+    let mut counts: BTreeMap<(Direction, Action), u64> = BTreeMap::new();
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input {
+                id,
+                metadata: _,
+                data,
+            } => match id.as_str() {
+                "audit" => {
+                    let rows = data
+                        .as_string_opt::<i32>()
+                        .ok_or_eyre("expected a Utf8 array of audit entries")?;
+                    for row in rows.iter() {
+                        let entry: AuditEntry =
+                            serde_json::from_str(row.ok_or_eyre("null audit entry")?)?;
+                        *counts.entry((entry.direction, entry.action)).or_default() += 1;
+                        write_jsonl(&mut log, &entry)?;
+                    }
+                }
+                other => eprintln!("Ignoring unexpected input `{other}`"),
+            },
+            Event::InputClosed { id } => {
+                if id.as_str() == "audit" {
+                    break;
+                }
+            }
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    for ((direction, action), count) in counts {
+        println!("{direction:?} {action:?}: {count}");
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use eyre::{Context, eyre};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, io::Write, path::Path};
+use zenoh::{Config, Session, Wait, key_expr::KeyExpr};
+
+pub use example_node_utils::env_or;
+
+/// Attachment of every sample that `zenoh-gateway` forwarded. The gateway never forwards such
+/// a sample again, so that rules in both directions can't bounce samples back and forth.
+pub const GATEWAY_MARKER: &str = "dora-zenoh-gateway";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// From the robot's internal network to the external network.
+    Outbound,
+    Inbound,
+}
+
+/// Content of `forwarding.json5`.
+#[derive(Debug, Deserialize)]
+pub struct Rules {
+    pub outbound: Vec<Rule>,
+    pub inbound: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Rule {
+    pub key: String,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub strip_prefix: Option<String>,
+    pub add_prefix: Option<String>,
+    pub min_interval_ms: Option<u64>,
+    pub max_payload_bytes: Option<usize>,
+}
+
+impl Rule {
+    /// The key on the target network, `None` if it's not a valid key expression.
+    pub fn target_key(&self, key: &str) -> Option<KeyExpr<'static>> {
+        let rest = match &self.strip_prefix {
+            Some(prefix) if key == prefix => "",
+            Some(prefix) => key
+                .strip_prefix(prefix.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+                .unwrap_or(key),
+            None => key,
+        };
+        let target = match (&self.add_prefix, rest) {
+            (Some(prefix), "") => prefix.clone(),
+            (Some(prefix), rest) => format!("{prefix}/{rest}"),
+            (None, rest) => rest.to_owned(),
+        };
+        KeyExpr::try_from(target).ok()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Forwarded,
+    Excluded,
+    TooLarge,
+    RateLimited,
+    /// The sample was forwarded by the gateway itself.
+    Loop,
+    InvalidKey,
+}
+
+/// One row of the `audit` output: what the gateway did with a sample.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub direction: Direction,
+    pub key: String,
+    pub target: Option<String>,
+    pub bytes: usize,
+    pub action: Action,
+}
+
+/// Payload of every sample that `network-peer` publishes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeerMessage {
+    /// `robot` or `cloud`.
+    pub from: String,
+    pub seq: u64,
+    pub data: String,
+}
+
+/// Written by `network-peer` when it stops.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PeerReport {
+    pub role: String,
+    pub published: BTreeMap<String, u64>,
+    /// Samples published by the other role, by key.
+    pub received: BTreeMap<String, u64>,
+    /// Own samples that the gateway sent back.
+    pub echoes: u64,
+}
+
+pub fn open_session(config: &Path) -> eyre::Result<Session> {
+    let config = Config::from_file(config)
+        .map_err(|err| eyre!("failed to load zenoh config {}: {err}", config.display()))?;
+    zenoh::open(config)
+        .wait()
+        .map_err(|err| eyre!("failed to open zenoh session: {err}"))
+}
+
+pub fn create_jsonl(path: &Path) -> eyre::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::File::create(path).with_context(|| format!("failed to create {}", path.display()))
+}
+
+pub fn write_jsonl<T: Serialize>(file: &mut std::fs::File, record: &T) -> eyre::Result<()> {
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
@@ -0,0 +1,164 @@
+//! A zenoh application outside of the dataflow, on one of the two networks of the gateway.
+//!
+//! Usage: `network-peer --role <robot|cloud> --config <path> [--duration-secs <n>]
+//! [--report <path>]`
+//!
+//! - `robot` plays the computers of the robot's internal network. It publishes telemetry,
+//!   camera images, debug output and its status, and subscribes to `robot/cmd/**`.
+//! - `cloud` plays the router of the fleet backend. It publishes commands for this robot,
+//!   including a `shutdown` that must not reach it, and commands for another robot. It
+//!   subscribes to `fleet/**`.
+//!
+//! Both publish on `shared/announce` and subscribe to `shared/**`. When the peer stops, it
+//! writes what it published and received to `--report`.
+
+use eyre::{Context, bail, eyre};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use zenoh::Wait;
+use zenoh_dual_network_dataflow_nodes::{GATEWAY_MARKER, PeerMessage, PeerReport, open_session};
+
+const STEP: Duration = Duration::from_millis(20);
+
+/// `(key, every n steps, payload size)` of the samples that a role publishes.
+const ROBOT_TOPICS: &[(&str, u64, usize)] = &[
+    ("robot/telemetry/imu", 1, 64),
+    ("robot/telemetry/battery", 50, 16),
+    ("robot/telemetry/camera", 10, 200_000),
+    ("robot/debug/planner", 1, 2048),
+    ("robot/status", 50, 16),
+    ("shared/announce", 50, 16),
+];
+const CLOUD_TOPICS: &[(&str, u64, usize)] = &[
+    ("fleet/robot-1/cmd/velocity", 10, 32),
+    ("fleet/robot-1/cmd/shutdown", 50, 16),
+    ("fleet/robot-2/cmd/velocity", 10, 32),
+    ("shared/announce", 50, 16),
+];
+
+struct Args {
+    role: String,
+    config: PathBuf,
+    duration: Duration,
+    report: PathBuf,
+}
+
+fn main() -> eyre::Result<()> {
+    let args = parse_args()?;
+    let (topics, subscriptions) = match args.role.as_str() {
+        "robot" => (ROBOT_TOPICS, ["robot/cmd/**", "shared/**"]),
+        "cloud" => (CLOUD_TOPICS, ["fleet/**", "shared/**"]),
+        other => bail!("unknown role `{other}`"),
+    };
+
+    let session = open_session(&args.config)?;
+    println!("{} peer started as {}", args.role, session.zid());
+
+    let report = Arc::new(Mutex::new(PeerReport {
+        role: args.role.clone(),
+        ..Default::default()
+    }));
+    let mut subscribers = Vec::new();
+    for key in subscriptions {
+        let report = report.clone();
+        let role = args.role.clone();
+        let subscriber = session
+            .declare_subscriber(key)
+            .callback(move |sample| {
+                let Ok(message) =
+                    serde_json::from_slice::<PeerMessage>(&sample.payload().to_bytes())
+                else {
+                    eprintln!("ignoring invalid sample on `{}`", sample.key_expr());
+                    return;
+                };
+                let from_gateway = sample.attachment().is_some_and(|attachment| {
+                    attachment.to_bytes().as_ref() == GATEWAY_MARKER.as_bytes()
+                });
+                let mut report = report.lock().unwrap();
+                if message.from != role {
+                    *report
+                        .received
+                        .entry(sample.key_expr().to_string())
+                        .or_default() += 1;
+                } else if from_gateway {
+                    report.echoes += 1;
+                }
+            })
+            .wait()
+            .map_err(|err| eyre!("failed to subscribe to `{key}`: {err}"))?;
+        subscribers.push(subscriber);
+    }
+
+    let start = Instant::now();
+    let mut step = 0;
+    while start.elapsed() < args.duration {
+        for &(key, every, size) in topics {
+            if step % every != 0 {
+                continue;
+            }
+            let message = PeerMessage {
+                from: args.role.clone(),
+                seq: step / every,
+                data: "x".repeat(size),
+            };
+            session
+                .put(key, serde_json::to_vec(&message)?)
+                .wait()
+                .map_err(|err| eyre!("failed to publish `{key}`: {err}"))?;
+            *report
+                .lock()
+                .unwrap()
+                .published
+                .entry(key.to_owned())
+                .or_default() += 1;
+        }
+        step += 1;
+        std::thread::sleep((start + STEP * step as u32).saturating_duration_since(Instant::now()));
+    }
+
+    drop(subscribers);
+    let report = report.lock().unwrap();
+    println!(
+        "{} peer received {:?}, {} echoes",
+        report.role, report.received, report.echoes
+    );
+    if let Some(parent) = args.report.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&args.report, serde_json::to_string_pretty(&*report)?)
+        .with_context(|| format!("failed to write {}", args.report.display()))?;
+    Ok(())
+}
+
+fn parse_args() -> eyre::Result<Args> {
+    let mut role = None;
+    let mut config = None;
+    let mut duration = Duration::from_secs(20);
+    let mut report = None;
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = || {
+            argv.next()
+                .ok_or_else(|| eyre!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--role" => role = Some(value()?),
+            "--config" => config = Some(PathBuf::from(value()?)),
+            "--duration-secs" => duration = Duration::from_secs(value()?.parse()?),
+            "--report" => report = Some(PathBuf::from(value()?)),
+            other => bail!("unexpected argument `{other}`"),
+        }
+    }
+    let Some(role) = role else {
+        bail!("missing `--role`");
+    };
+    Ok(Args {
+        report: report.unwrap_or_else(|| format!("out/{role}.json").into()),
+        config: config.ok_or_else(|| eyre!("missing `--config`"))?,
+        role,
+        duration,
+    })
+}
@@ -0,0 +1,210 @@
+use dora_node_api::{
+    self, DoraNode, Event,
+    arrow::array::StringArray,
+    dora_core::config::DataId,
+    merged::{MergeExternal, MergedEvent},
+};
+use eyre::{Context, eyre};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use zenoh::{Session, Wait, key_expr::KeyExpr, sample::Sample};
+use zenoh_dual_network_dataflow_nodes::{
+    Action, AuditEntry, Direction, GATEWAY_MARKER, Rule, Rules, env_or, open_session,
+};
+
+/// Holds one zenoh session on the robot's internal network (`INTERNAL_CONFIG`) and one on the
+/// external network (`EXTERNAL_CONFIG`), and forwards samples between them according to the
+/// rules in `RULES`.
+///
+/// Sends what it did with every sample on `audit`, one JSON `AuditEntry` per row, batched per
+/// `tick`. Stops after `DURATION_MS`, if set.
+fn main() -> eyre::Result<()> {
+    let internal_config: PathBuf =
+        env_or("INTERNAL_CONFIG", "config/internal.json5".to_owned())?.into();
+    let external_config: PathBuf =
+        env_or("EXTERNAL_CONFIG", "config/external.json5".to_owned())?.into();
+    let rules_file: PathBuf = env_or("RULES", "forwarding.json5".to_owned())?.into();
+    let duration = Duration::from_millis(env_or("DURATION_MS", u64::MAX)?);
+    let output = DataId::from("audit".to_owned());
+
+    let rules: Rules = json5::from_str(
+        &std::fs::read_to_string(&rules_file)
+            .with_context(|| format!("failed to read {}", rules_file.display()))?,
+    )
+    .with_context(|| format!("invalid rules in {}", rules_file.display()))?;
+    let outbound = compile(rules.outbound)?;
+    let inbound = compile(rules.inbound)?;
+    let rules = |direction| match direction {
+        Direction::Outbound => &outbound,
+        Direction::Inbound => &inbound,
+    };
+
+    let internal = open_session(&internal_config).wrap_err("internal network")?;
+    let external = open_session(&external_config).wrap_err("external network")?;
+    let session = |direction| match direction {
+        Direction::Outbound => (&internal, &external),
+        Direction::Inbound => (&external, &internal),
+    };
+    println!(
+        "connected to internal network as {} and external network as {}",
+        internal.zid(),
+        external.zid()
+    );
+
+    // the subscribers of both sessions feed one channel, which is merged into the event loop;
+    // they are undeclared when dropped, at the end of `main`
+    let (samples_tx, samples_rx) = futures::channel::mpsc::unbounded();
+    let mut subscribers = Vec::new();
+    for direction in [Direction::Outbound, Direction::Inbound] {
+        let (source, _) = session(direction);
+        for (index, CompiledRule { rule, .. }) in rules(direction).iter().enumerate() {
+            let tx = samples_tx.clone();
+            let subscriber = source
+                .declare_subscriber(rule.key.clone())
+                .callback(move |sample| {
+                    let _ = tx.unbounded_send((direction, index, sample));
+                })
+                .wait()
+                .map_err(|err| eyre!("failed to subscribe to `{}`: {err}", rule.key))?;
+            subscribers.push(subscriber);
+        }
+    }
+
+    let (mut node, events) = DoraNode::init_from_env()?;
+    let merged = events.merge_external(Box::pin(samples_rx));
+    let events = futures::executor::block_on_stream(merged);
+
+    let mut last_forwarded: HashMap<(Direction, String), Instant> = HashMap::new();
+    let mut audit = Vec::new();
+    let mut counts: BTreeMap<Action, u64> = BTreeMap::new();
+    let start = Instant::now();
+    for event in events {
+        match event {
+            MergedEvent::Dora(event) => match event {
+                Event::Input {
+                    id,
+                    metadata: _,
+                    data: _,
+                } => match id.as_str() {
+                    "tick" => {
+                        if start.elapsed() >= duration {
+                            break;
+                        }
+                        if audit.is_empty() {
+                            continue;
+                        }
+                        let rows = audit
+                            .drain(..)
+                            .map(|entry| serde_json::to_string(&entry))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        node.send_output(
+                            output.clone(),
+                            Default::default(),
+                            StringArray::from(rows),
+                        )?;
+                    }
+                    other => eprintln!("Ignoring unexpected input `{other}`"),
+                },
+                Event::Stop(_) => break,
+                other => eprintln!("Received unexpected input: {other:?}"),
+            },
+            MergedEvent::External((direction, index, sample)) => {
+                let (_, target) = session(direction);
+                let rule = &rules(direction)[index];
+                let entry = forward(direction, rule, &sample, target, &mut last_forwarded)?;
+                *counts.entry(entry.action).or_default() += 1;
+                audit.push(entry);
+            }
+        }
+    }
+
+    println!("samples by action: {counts:?}");
+    Ok(())
+}
+
+/// A rule with its key expressions parsed.
+struct CompiledRule {
+    rule: Rule,
+    exclude: Vec<KeyExpr<'static>>,
+}
+
+fn compile(rules: Vec<Rule>) -> eyre::Result<Vec<CompiledRule>> {
+    rules
+        .into_iter()
+        .map(|rule| {
+            KeyExpr::try_from(rule.key.clone())
+                .map_err(|err| eyre!("invalid key `{}`: {err}", rule.key))?;
+            let exclude = rule
+                .exclude
+                .iter()
+                .map(|key| {
+                    KeyExpr::try_from(key.clone())
+                        .map_err(|err| eyre!("invalid exclude `{key}`: {err}"))
+                })
+                .collect::<eyre::Result<_>>()?;
+            Ok(CompiledRule { rule, exclude })
+        })
+        .collect()
+}
+
+fn forward(
+    direction: Direction,
+    compiled: &CompiledRule,
+    sample: &Sample,
+    target: &Session,
+    last_forwarded: &mut HashMap<(Direction, String), Instant>,
+) -> eyre::Result<AuditEntry> {
+    let rule = &compiled.rule;
+    let key = sample.key_expr();
+    let bytes = sample.payload().len();
+    let mut entry = AuditEntry {
+        direction,
+        key: key.to_string(),
+        target: None,
+        bytes,
+        action: Action::Forwarded,
+    };
+
+    let from_gateway = sample
+        .attachment()
+        .is_some_and(|attachment| attachment.to_bytes().as_ref() == GATEWAY_MARKER.as_bytes());
+    let rate_limited = || {
+        rule.min_interval_ms.is_some_and(|interval| {
+            last_forwarded
+                .get(&(direction, key.to_string()))
+                .is_some_and(|last| last.elapsed() < Duration::from_millis(interval))
+        })
+    };
+    entry.action = if from_gateway {
+        Action::Loop
+    } else if compiled
+        .exclude
+        .iter()
+        .any(|exclude| exclude.intersects(key))
+    {
+        Action::Excluded
+    } else if rule.max_payload_bytes.is_some_and(|max| bytes > max) {
+        Action::TooLarge
+    } else if rate_limited() {
+        Action::RateLimited
+    } else {
+        match rule.target_key(key.as_str()) {
+            Some(target_key) => {
+                entry.target = Some(target_key.to_string());
+                target
+                    .put(target_key, sample.payload().clone())
+                    .encoding(sample.encoding().clone())
+                    .attachment(GATEWAY_MARKER)
+                    .wait()
+                    .map_err(|err| eyre!("failed to forward `{key}`: {err}"))?;
+                last_forwarded.insert((direction, key.to_string()), Instant::now());
+                Action::Forwarded
+            }
+            None => Action::InvalidKey,
+        }
+    };
+    Ok(entry)
+}
@@ -0,0 +1,146 @@
+//! Clock-driven generator for synthetic numeric and tensor streams.
+//!
+//! On every `tick` input, the node draws one sample of the configured shape and sends it on
+//! its `data` output. Everything is configured through env variables in the dataflow YAML:
+//!
+//! | Variable              | Default  | Description                                                    |
+//! | --------------------- | -------- | -------------------------------------------------------------- |
+//! | `DISTRIBUTION`        | `normal` | `normal`, `uniform`, or `exponential`                          |
+//! | `MEAN`, `STD_DEV`     | `0`, `1` | parameters of the `normal` distribution                        |
+//! | `LOW`, `HIGH`         | `0`, `1` | parameters of the `uniform` distribution                       |
+//! | `RATE`                | `1`      | parameter of the `exponential` distribution                    |
+//! | `SHAPE`               | `1`      | comma-separated tensor shape, e.g. `3,4`                       |
+//! | `DRIFT`               | `0`      | offset added to every value per sample, accumulating over time |
+//! | `ANOMALY_PROBABILITY` | `0`      | probability that a sample contains an injected anomaly         |
+//! | `ANOMALY_MAGNITUDE`   | `6`      | size of an anomaly, in multiples of the distribution's spread  |
+//! | `SAMPLES`             | `0`      | number of samples to send before exiting, `0` for unlimited    |
+//! | `SEED`                | random   | seed for reproducible streams                                  |
+//!
+//! Every sample carries the metadata parameters `seq`, `shape`, and `anomaly`. For samples
+//! with an injected anomaly, the `seq` number is additionally sent on the `anomalies`
+//! output, which can be used as ground truth when evaluating detectors.
+
+use dora_node_api::{
+    self, DoraNode, Event, IntoArrow, MetadataParameters, Parameter, arrow::array::Float64Array,
+    dora_core::config::DataId,
+};
+use example_node_utils::env_or;
+use eyre::{Context, bail};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rand_distr::{Distribution as _, Exp, Normal, Uniform};
+
+enum Distribution {
+    Normal(Normal<f64>),
+    Uniform(Uniform<f64>),
+    Exponential(Exp<f64>),
+}
+
+struct Source {
+    distribution: Distribution,
+    /// Standard deviation of the distribution, used to scale injected anomalies.
+    spread: f64,
+}
+
+impl Source {
+    fn from_env() -> eyre::Result<Self> {
+        let distribution = std::env::var("DISTRIBUTION").unwrap_or_else(|_| "normal".to_owned());
+        Ok(match distribution.as_str() {
+            "normal" => {
+                let std_dev = env_or("STD_DEV", 1.0)?;
+                Self {
+                    distribution: Distribution::Normal(Normal::new(env_or("MEAN", 0.0)?, std_dev)?),
+                    spread: std_dev,
+                }
+            }
+            "uniform" => {
+                let (low, high) = (env_or("LOW", 0.0)?, env_or("HIGH", 1.0)?);
+                if low >= high {
+                    bail!("LOW must be smaller than HIGH");
+                }
+                Self {
+                    distribution: Distribution::Uniform(Uniform::new(low, high)),
+                    spread: (high - low) / 12f64.sqrt(),
+                }
+            }
+            "exponential" => {
+                let rate = env_or("RATE", 1.0)?;
+                Self {
+                    distribution: Distribution::Exponential(Exp::new(rate)?),
+                    spread: 1.0 / rate,
+                }
+            }
+            other => bail!("unknown DISTRIBUTION `{other}`"),
+        })
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> f64 {
+        match &self.distribution {
+            Distribution::Normal(distribution) => distribution.sample(rng),
+            Distribution::Uniform(distribution) => distribution.sample(rng),
+            Distribution::Exponential(distribution) => distribution.sample(rng),
+        }
+    }
+}
+
+fn main() -> eyre::Result<()> {
+    let source = Source::from_env()?;
+    let shape: Vec<i64> = std::env::var("SHAPE")
+        .unwrap_or_else(|_| "1".to_owned())
+        .split(',')
+        .map(|dim| dim.trim().parse().wrap_err("invalid SHAPE"))
+        .collect::<eyre::Result<_>>()?;
+    let len = shape.iter().product::<i64>() as usize;
+    let drift = env_or("DRIFT", 0.0)?;
+    let anomaly_probability = env_or("ANOMALY_PROBABILITY", 0.0)?;
+    let anomaly_offset = env_or("ANOMALY_MAGNITUDE", 6.0)? * source.spread;
+    let samples = env_or("SAMPLES", 0.0)? as u64;
+    let mut rng = match std::env::var("SEED") {
+        Ok(seed) => StdRng::seed_from_u64(seed.parse().wrap_err("invalid SEED")?),
+        Err(_) => StdRng::from_entropy(),
+    };
+
+    let data_output = DataId::from("data".to_owned());
+    let anomalies_output = DataId::from("anomalies".to_owned());
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+
+    let mut seq = 0u64;
+    while samples == 0 || seq < samples {
+        let event = match events.recv() {
+            Some(input) => input,
+            None => break,
+        };
+
+        match event {
+            Event::Input { id, .. } if id.as_str() == "tick" => {
+                let offset = drift * seq as f64;
+                let mut values: Vec<f64> =
+                    (0..len).map(|_| source.sample(&mut rng) + offset).collect();
+
+                let anomaly = rng.gen_bool(anomaly_probability);
+                if anomaly {
+                    // push a single element far away from the distribution, in a random direction
+                    let index = rng.gen_range(0..len);
+                    let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+                    values[index] += sign * anomaly_offset;
+                    node.send_output(
+                        anomalies_output.clone(),
+                        Default::default(),
+                        seq.into_arrow(),
+                    )?;
+                }
+
+                let mut parameters = MetadataParameters::default();
+                parameters.insert("seq".into(), Parameter::Integer(seq as i64));
+                parameters.insert("shape".into(), Parameter::ListInt(shape.clone()));
+                parameters.insert("anomaly".into(), Parameter::Bool(anomaly));
+                node.send_output(data_output.clone(), parameters, Float64Array::from(values))?;
+                seq += 1;
+            }
+            Event::Input { id, .. } => eprintln!("Ignoring unexpected input `{id}`"),
+            Event::Stop(_) => break,
+            other => eprintln!("Received unexpected input: {other:?}"),
+        }
+    }
+
+    Ok(())
+}
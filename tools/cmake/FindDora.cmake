@@ -0,0 +1,77 @@
+# Finds the dora node API libraries in a dora checkout, after `cargo build --release` built
+# them, and provides them as imported targets that link the system libraries they need:
+#
+#   Dora::node_api_c     the static library of `dora-node-api-c`, with `node_api.h`
+#   Dora::node_api_cxx   the static library of `dora-node-api-cxx`
+#
+# Request them as components:
+#
+#   list(APPEND CMAKE_MODULE_PATH path/to/this/dir)
+#   find_package(Dora REQUIRED COMPONENTS node_api_c)
+#   target_link_libraries(my_node PRIVATE Dora::node_api_c)
+#
+# The C++ API is generated by cxx, so nodes that use it compile its bridge source along with
+# their own, e.g. `target/cxxbridge/dora-node-api-cxx/src/lib.rs.cc`.
+#
+# Set `DORA_ROOT_DIR` to the dora checkout, it defaults to the `DORA` environment variable, and
+# `DORA_LIB_DIR` if cargo didn't put the libraries into its `target/release`. Build in the
+# `Release` configuration on Windows, the libraries use the dynamic release C runtime of Rust.
+#
+# `DORA_SYSTEM_LIBRARIES` lists the system libraries that static Rust libraries depend on, for
+# linking other Rust static libraries that wrap the dora APIs.
+
+set(DORA_ROOT_DIR "$ENV{DORA}" CACHE PATH "The dora checkout")
+set(DORA_LIB_DIR "${DORA_ROOT_DIR}/target/release" CACHE PATH "Where cargo built the dora libraries")
+
+find_package(Threads REQUIRED)
+
+if(WIN32)
+  set(DORA_SYSTEM_LIBRARIES
+    advapi32 userenv kernel32 ws2_32 bcrypt ncrypt schannel ntdll iphlpapi cfgmgr32 credui
+    crypt32 cryptnet fwpuclnt gdi32 msimg32 mswsock ole32 oleaut32 opengl32 secur32 shell32
+    synchronization user32 winspool winhttp rpcrt4)
+elseif(APPLE)
+  set(DORA_SYSTEM_LIBRARIES "-framework CoreServices" "-framework Security" resolv m z Threads::Threads)
+else()
+  set(DORA_SYSTEM_LIBRARIES m rt ${CMAKE_DL_LIBS} z Threads::Threads)
+endif()
+
+if(NOT Dora_FIND_COMPONENTS)
+  set(Dora_FIND_COMPONENTS node_api_c node_api_cxx)
+endif()
+
+foreach(_dora_component IN LISTS Dora_FIND_COMPONENTS)
+  string(TOUPPER "${_dora_component}" _dora_var)
+  # the full file name, so that a shared library of the same name isn't picked instead
+  find_library(DORA_${_dora_var}_LIBRARY
+    NAMES "${CMAKE_STATIC_LIBRARY_PREFIX}dora_${_dora_component}${CMAKE_STATIC_LIBRARY_SUFFIX}"
+    PATHS "${DORA_LIB_DIR}"
+    NO_DEFAULT_PATH)
+  if(NOT DORA_${_dora_var}_LIBRARY)
+    set(Dora_${_dora_component}_FOUND FALSE)
+    continue()
+  endif()
+  set(Dora_${_dora_component}_FOUND TRUE)
+
+  if(NOT TARGET Dora::${_dora_component})
+    add_library(Dora::${_dora_component} STATIC IMPORTED)
+    set_target_properties(Dora::${_dora_component} PROPERTIES
+      IMPORTED_LOCATION "${DORA_${_dora_var}_LIBRARY}"
+      INTERFACE_LINK_LIBRARIES "${DORA_SYSTEM_LIBRARIES}")
+    if(MSVC)
+      # the static C runtime conflicts with the dynamic one of the Rust libraries
+      set_property(TARGET Dora::${_dora_component} PROPERTY
+        INTERFACE_LINK_OPTIONS /NODEFAULTLIB:libcmt)
+    endif()
+  endif()
+endforeach()
+
+if(TARGET Dora::node_api_c)
+  set_property(TARGET Dora::node_api_c PROPERTY
+    INTERFACE_INCLUDE_DIRECTORIES "${DORA_ROOT_DIR}/apis/c/node")
+endif()
+
+include(FindPackageHandleStandardArgs)
+find_package_handle_standard_args(Dora
+  REQUIRED_VARS DORA_ROOT_DIR
+  HANDLE_COMPONENTS)
@@ -0,0 +1,62 @@
+use eyre::Context;
+use std::path::{Path, PathBuf};
+
+/// A directory under `examples/`.
+#[derive(Debug, Clone)]
+pub struct Example {
+    pub name: String,
+    pub dir: PathBuf,
+    /// Whether the example has a `main.rs` runner, i.e. `cargo run --example <name>` works.
+    pub runner: bool,
+    /// Relative to `dir`.
+    pub dataflows: Vec<PathBuf>,
+}
+
+impl Example {
+    /// The dataflow to run for examples without a runner: `dataflow.yml` or `dataflow.yaml`, or
+    /// the only dataflow of the example.
+    pub fn default_dataflow(&self) -> Option<&Path> {
+        let named = self
+            .dataflows
+            .iter()
+            .find(|path| path == &Path::new("dataflow.yml") || path == &Path::new("dataflow.yaml"));
+        match (named, self.dataflows.as_slice()) {
+            (Some(path), _) => Some(path),
+            (None, [only]) => Some(only),
+            (None, _) => None,
+        }
+    }
+}
+
+/// All examples in `examples_dir` that have a runner or at least one dataflow, sorted by name.
+pub fn find_examples(examples_dir: &Path) -> eyre::Result<Vec<Example>> {
+    let entries = std::fs::read_dir(examples_dir)
+        .with_context(|| format!("failed to read {}", examples_dir.display()))?;
+    let mut examples = Vec::new();
+    for entry in entries {
+        let dir = entry?.path();
+        let name = dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        if !dir.is_dir() || name.starts_with('.') {
+            continue;
+        }
+        let dataflows = validate_dataflows::find_dataflows(&dir)?
+            .into_iter()
+            .map(|path| path.strip_prefix(&dir).map(Path::to_owned))
+            .collect::<Result<Vec<_>, _>>()?;
+        let runner = dir.join("main.rs").is_file();
+        if runner || !dataflows.is_empty() {
+            examples.push(Example {
+                name,
+                dir,
+                runner,
+                dataflows,
+            });
+        }
+    }
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(examples)
+}
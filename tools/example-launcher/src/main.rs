@@ -0,0 +1,217 @@
+//! Lists the examples of this repository and runs them by name.
+//!
+//! Examples with a runner are run with `cargo run --example <name>`. Examples that only consist
+//! of dataflows are built and run with `dora build --uv` and `dora run --uv`, like their READMEs
+//! describe.
+
+use clap::Parser;
+use example_runner_utils::{Dora, run};
+use examples::{Example, find_examples};
+use eyre::{Context, bail};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tokio::process::Command;
+
+mod examples;
+
+#[derive(Debug, Parser)]
+#[command(about = "Lists and runs the examples of this repository")]
+struct Args {
+    /// Example to run, by its directory name under `examples/`.
+    #[arg(conflicts_with_all = ["list", "all"])]
+    example: Option<String>,
+    /// Lists the examples and their dataflows. The default without EXAMPLE or --all.
+    #[arg(long)]
+    list: bool,
+    /// Runs every example that has a runner, one after the other.
+    #[arg(long, conflicts_with = "list")]
+    all: bool,
+    /// Only lists or runs examples whose name contains PATTERN. Can be repeated.
+    #[arg(long, value_name = "PATTERN")]
+    filter: Vec<String>,
+    /// Continues with the next example after a failure when running --all.
+    #[arg(long, requires = "all")]
+    keep_going: bool,
+    /// Dataflow to run for an example without a runner, relative to its directory.
+    #[arg(long, requires = "example")]
+    dataflow: Option<PathBuf>,
+    /// Arguments for the runner of EXAMPLE, after `--`.
+    #[arg(last = true, requires = "example")]
+    runner_args: Vec<String>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let root = std::fs::canonicalize(&root)
+        .with_context(|| format!("failed to resolve {}", root.display()))?;
+    let examples: Vec<Example> = find_examples(&root.join("examples"))?
+        .into_iter()
+        .filter(|example| {
+            args.filter.is_empty()
+                || args
+                    .filter
+                    .iter()
+                    .any(|pattern| example.name.contains(pattern.as_str()))
+        })
+        .collect();
+    if examples.is_empty() {
+        bail!("no examples match {:?}", args.filter);
+    }
+
+    if let Some(name) = &args.example {
+        let Some(example) = examples.iter().find(|example| &example.name == name) else {
+            let similar: Vec<_> = examples
+                .iter()
+                .filter(|example| example.name.contains(name.as_str()))
+                .map(|example| example.name.as_str())
+                .collect();
+            if similar.is_empty() {
+                bail!("no example `{name}`, see `--list`");
+            }
+            bail!(
+                "no example `{name}`, did you mean one of: {}",
+                similar.join(", ")
+            );
+        };
+        run_example(&root, example, args.dataflow.as_deref(), &args.runner_args).await
+    } else if args.all {
+        run_all(&root, &examples, args.keep_going).await
+    } else {
+        print_list(&examples);
+        Ok(())
+    }
+}
+
+fn print_list(examples: &[Example]) {
+    let width = examples
+        .iter()
+        .map(|example| example.name.len())
+        .max()
+        .unwrap_or_default()
+        .max("EXAMPLE".len());
+    println!("{:<width$}  {:<6}  DATAFLOWS", "EXAMPLE", "RUNNER");
+    for example in examples {
+        let dataflows: Vec<_> = example
+            .dataflows
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        println!(
+            "{:<width$}  {:<6}  {}",
+            example.name,
+            if example.runner { "yes" } else { "no" },
+            dataflows.join(", ")
+        );
+    }
+    let runners = examples.iter().filter(|example| example.runner).count();
+    println!(
+        "\n{} examples, {runners} with a runner. Examples without a runner are run with \
+         `dora run --uv` on their dataflow.",
+        examples.len()
+    );
+}
+
+async fn run_example(
+    root: &Path,
+    example: &Example,
+    dataflow: Option<&Path>,
+    runner_args: &[String],
+) -> eyre::Result<()> {
+    if example.runner {
+        if dataflow.is_some() {
+            bail!(
+                "`{}` has a runner, pass its dataflow after `--` if the runner accepts one",
+                example.name
+            );
+        }
+        let mut cmd = Command::new(cargo());
+        cmd.current_dir(root)
+            .args(["run", "--example", &example.name])
+            .arg("--")
+            .args(runner_args);
+        return run(&mut cmd, &format!("example `{}` failed", example.name)).await;
+    }
+
+    if !runner_args.is_empty() {
+        bail!("`{}` has no runner to pass arguments to", example.name);
+    }
+    let dataflow = match dataflow {
+        Some(dataflow) => dataflow,
+        None => example.default_dataflow().ok_or_else(|| {
+            let dataflows: Vec<_> = example
+                .dataflows
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            eyre::eyre!(
+                "`{}` has several dataflows, select one with --dataflow: {}",
+                example.name,
+                dataflows.join(", ")
+            )
+        })?,
+    };
+    // the uv environment of the example is created in its directory
+    let dora = Dora::from_env()?;
+    let dora = Dora::new(std::path::absolute(dora.root())?).uv();
+    std::env::set_current_dir(&example.dir)
+        .with_context(|| format!("failed to enter {}", example.dir.display()))?;
+    dora.build_dataflow(dataflow).await?;
+    dora.run_dataflow(dataflow).await
+}
+
+async fn run_all(root: &Path, examples: &[Example], keep_going: bool) -> eyre::Result<()> {
+    let (runnable, skipped): (Vec<_>, Vec<_>) = examples.iter().partition(|example| example.runner);
+
+    let mut results: Vec<(&str, Duration, eyre::Result<()>)> = Vec::new();
+    for example in runnable {
+        println!("==> {}", example.name);
+        let start = Instant::now();
+        let result = run_example(root, example, None, &[]).await;
+        let failed = result.is_err();
+        if let Err(err) = &result {
+            eprintln!("{err:#}");
+        }
+        results.push((&example.name, start.elapsed(), result));
+        if failed && !keep_going {
+            break;
+        }
+    }
+
+    println!();
+    for (name, elapsed, result) in &results {
+        let status = if result.is_ok() { "ok" } else { "FAILED" };
+        println!("{status:<6}  {:>7.1}s  {name}", elapsed.as_secs_f64());
+    }
+    if !skipped.is_empty() {
+        let names: Vec<_> = skipped
+            .iter()
+            .map(|example| example.name.as_str())
+            .collect();
+        println!(
+            "skipped {} examples without a runner: {}",
+            skipped.len(),
+            names.join(", ")
+        );
+    }
+
+    let failed = results
+        .iter()
+        .filter(|(_, _, result)| result.is_err())
+        .count();
+    if failed > 0 {
+        bail!("{failed} of {} examples failed", results.len());
+    }
+    Ok(())
+}
+
+/// The cargo that runs the launcher, so that runners use the same toolchain.
+fn cargo() -> PathBuf {
+    std::env::var_os("CARGO")
+        .unwrap_or_else(|| "cargo".into())
+        .into()
+}
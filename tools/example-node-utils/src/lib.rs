@@ -0,0 +1,42 @@
+//! Helpers for the nodes of the examples, which the node crates in `examples/*/nodes` depend on
+//! by path.
+
+use eyre::{Context, eyre};
+use serde::Serialize;
+use std::{path::Path, str::FromStr, time::SystemTime};
+
+/// Parses the environment variable `name`, which the dataflow sets in the `env` of the node, or
+/// returns `default` if it's not set.
+pub fn env_or<T>(name: &str, default: T) -> eyre::Result<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|err| eyre!("invalid value for {name}: {err}")),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Writes `value` as pretty-printed JSON to `path`, creating its parent directories, e.g. the
+/// report of a node that the runner checks.
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> eyre::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(value)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Wall clock time in microseconds since the Unix epoch.
+///
+/// The runners use it too, so their timestamps are comparable with the ones of the nodes on the
+/// same machine.
+pub fn now_us() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as u64
+}
@@ -0,0 +1,103 @@
+use crate::run;
+use std::path::PathBuf;
+use tokio::process::Command;
+
+/// A `cargo build` of one package, in release mode unless [`CargoBuild::debug`] is set.
+#[derive(Debug, Clone)]
+pub struct CargoBuild {
+    package: String,
+    manifest_path: Option<PathBuf>,
+    release: bool,
+    features: Vec<String>,
+    target_dir: Option<PathBuf>,
+    setup_script: Option<PathBuf>,
+}
+
+impl CargoBuild {
+    pub fn new(package: &str) -> Self {
+        Self {
+            package: package.to_owned(),
+            manifest_path: None,
+            release: true,
+            features: Vec::new(),
+            target_dir: None,
+            setup_script: None,
+        }
+    }
+
+    pub fn manifest_path(mut self, manifest_path: impl Into<PathBuf>) -> Self {
+        self.manifest_path = Some(manifest_path.into());
+        self
+    }
+
+    pub fn debug(mut self) -> Self {
+        self.release = false;
+        self
+    }
+
+    pub fn feature(mut self, feature: &str) -> Self {
+        self.features.push(feature.to_owned());
+        self
+    }
+
+    pub fn target_dir(mut self, target_dir: impl Into<PathBuf>) -> Self {
+        self.target_dir = Some(target_dir.into());
+        self
+    }
+
+    /// A script to `source` in bash before building, e.g. the `setup.bash` of a ROS 2
+    /// installation for packages that generate bindings. A `.bat` file is called in `cmd`
+    /// instead, e.g. the `local_setup.bat` of ROS 2 on Windows.
+    pub fn setup_script(mut self, script: impl Into<PathBuf>) -> Self {
+        self.setup_script = Some(script.into());
+        self
+    }
+
+    pub async fn build(self) -> eyre::Result<()> {
+        let mut args = vec!["build".into(), "--package".into(), self.package.clone()];
+        if let Some(manifest_path) = &self.manifest_path {
+            args.push("--manifest-path".into());
+            args.push(manifest_path.display().to_string());
+        }
+        if self.release {
+            args.push("--release".into());
+        }
+        if !self.features.is_empty() {
+            args.push("--features".into());
+            args.push(self.features.join(","));
+        }
+        if let Some(target_dir) = &self.target_dir {
+            args.push("--target-dir".into());
+            args.push(target_dir.display().to_string());
+        }
+
+        let cargo = crate::cargo();
+        let mut cmd = match &self.setup_script {
+            Some(script) if crate::is_batch_file(script) => {
+                // cmd has no `"$@"`, so the arguments become part of the command line
+                let args: Vec<_> = args.iter().map(|arg| format!(r#""{arg}""#)).collect();
+                crate::batch_command(
+                    script,
+                    &format!(r#""{}" {}"#, cargo.display(), args.join(" ")),
+                )
+            }
+            Some(script) => {
+                let mut cmd = Command::new("bash");
+                cmd.arg("-c").arg(format!(
+                    r#"source "{}" && "{}" "$@""#,
+                    script.display(),
+                    cargo.display()
+                ));
+                // `$0` of the script
+                cmd.arg("cargo").args(&args);
+                cmd
+            }
+            None => {
+                let mut cmd = Command::new(cargo);
+                cmd.args(&args);
+                cmd
+            }
+        };
+        run(&mut cmd, &format!("failed to compile {}", self.package)).await
+    }
+}
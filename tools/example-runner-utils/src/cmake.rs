@@ -0,0 +1,68 @@
+use crate::run;
+use std::{ffi::OsString, path::PathBuf};
+use tokio::process::Command;
+
+/// Configures a CMake project and builds it with `cmake --build`, in the `Release`
+/// configuration, so that it uses the same C runtime as the Rust libraries on Windows.
+#[derive(Debug, Clone)]
+pub struct CmakeBuild {
+    source_dir: PathBuf,
+    build_dir: PathBuf,
+    definitions: Vec<(String, OsString)>,
+}
+
+impl CmakeBuild {
+    /// The project in `source_dir`, built in its `build/cmake` by default.
+    pub fn new(source_dir: impl Into<PathBuf>) -> Self {
+        let source_dir = source_dir.into();
+        Self {
+            build_dir: source_dir.join("build").join("cmake"),
+            source_dir,
+            definitions: Vec::new(),
+        }
+    }
+
+    pub fn build_dir(mut self, build_dir: impl Into<PathBuf>) -> Self {
+        self.build_dir = build_dir.into();
+        self
+    }
+
+    /// Sets the cache variable `key`, replacing an earlier value, e.g. `DORA_LIB_DIR`.
+    pub fn define(mut self, key: &str, value: impl Into<OsString>) -> Self {
+        self.definitions.retain(|(k, _)| k != key);
+        self.definitions.push((key.to_owned(), value.into()));
+        self
+    }
+
+    pub async fn build(self) -> eyre::Result<()> {
+        let mut configure = Command::new("cmake");
+        configure
+            .arg("-S")
+            .arg(&self.source_dir)
+            .arg("-B")
+            .arg(&self.build_dir)
+            .arg("-DCMAKE_BUILD_TYPE=Release");
+        for (key, value) in &self.definitions {
+            let mut definition = OsString::from(format!("-D{key}="));
+            definition.push(value);
+            configure.arg(definition);
+        }
+        let project = self.source_dir.display();
+        run(
+            &mut configure,
+            &format!("failed to configure CMake project `{project}`, is cmake installed?"),
+        )
+        .await?;
+
+        let mut build = Command::new("cmake");
+        build
+            .arg("--build")
+            .arg(&self.build_dir)
+            .args(["--config", "Release"]);
+        run(
+            &mut build,
+            &format!("failed to build CMake project `{project}`"),
+        )
+        .await
+    }
+}
@@ -0,0 +1,108 @@
+use communication_layer_request_reply::{
+    RequestReplyConnection, RequestReplyLayer, TcpLayer, TcpRequestReplyConnection,
+};
+use dora_message::{
+    cli_to_coordinator::ControlRequest,
+    coordinator_to_cli::{ControlRequestReply, DataflowListEntry, DataflowStatus},
+};
+use eyre::{Context, bail};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// Client for the coordinator's control port, the same interface that the `dora` CLI uses.
+pub struct Control {
+    session: Box<TcpRequestReplyConnection>,
+}
+
+impl Control {
+    /// Retries until the coordinator accepts control connections.
+    pub fn connect(control_addr: SocketAddr, timeout: Duration) -> eyre::Result<Self> {
+        let start = Instant::now();
+        loop {
+            match TcpLayer::new().connect(control_addr) {
+                Ok(session) => return Ok(Self { session }),
+                Err(err) if start.elapsed() > timeout => {
+                    return Err(err).wrap_err_with(|| {
+                        format!("failed to connect to coordinator at {control_addr}")
+                    });
+                }
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+    }
+
+    /// The IDs of the daemons that are connected to the coordinator.
+    pub fn connected_daemons(&mut self) -> eyre::Result<Vec<String>> {
+        match self.request(&ControlRequest::ConnectedMachines)? {
+            ControlRequestReply::ConnectedDaemons(daemons) => {
+                Ok(daemons.iter().map(|daemon| daemon.to_string()).collect())
+            }
+            other => bail!("unexpected reply to ConnectedMachines: {other:?}"),
+        }
+    }
+
+    /// All dataflows that the coordinator knows, including the finished and failed ones.
+    pub fn dataflows(&mut self) -> eyre::Result<Vec<DataflowListEntry>> {
+        match self.request(&ControlRequest::List)? {
+            ControlRequestReply::DataflowList(list) => Ok(list.0),
+            other => bail!("unexpected reply to List: {other:?}"),
+        }
+    }
+
+    /// The status of the dataflow called `name`, or `None` if the coordinator doesn't know it.
+    pub fn status(&mut self, name: &str) -> eyre::Result<Option<DataflowStatus>> {
+        Ok(self
+            .dataflows()?
+            .into_iter()
+            .find(|entry| entry.id.name.as_deref() == Some(name))
+            .map(|entry| entry.status))
+    }
+
+    pub fn running_dataflows(&mut self) -> eyre::Result<usize> {
+        Ok(self
+            .dataflows()?
+            .iter()
+            .filter(|entry| matches!(entry.status, DataflowStatus::Running))
+            .count())
+    }
+
+    /// Polls `condition` every `interval` until it holds.
+    pub fn wait_until(
+        &mut self,
+        timeout: Duration,
+        interval: Duration,
+        mut condition: impl FnMut(&mut Self) -> eyre::Result<bool>,
+    ) -> eyre::Result<()> {
+        let start = Instant::now();
+        while !condition(self)? {
+            if start.elapsed() > timeout {
+                bail!("timed out after {timeout:?}");
+            }
+            std::thread::sleep(interval);
+        }
+        Ok(())
+    }
+
+    /// Stops all dataflows and shuts down the coordinator and all connected daemons.
+    pub fn destroy(&mut self) -> eyre::Result<()> {
+        match self.request(&ControlRequest::Destroy)? {
+            ControlRequestReply::DestroyOk => Ok(()),
+            other => bail!("unexpected reply to Destroy: {other:?}"),
+        }
+    }
+
+    fn request(&mut self, request: &ControlRequest) -> eyre::Result<ControlRequestReply> {
+        let reply_raw = self
+            .session
+            .request(&serde_json::to_vec(request)?)
+            .wrap_err("failed to send request to coordinator")?;
+        let reply: ControlRequestReply =
+            serde_json::from_slice(&reply_raw).wrap_err("failed to parse coordinator reply")?;
+        if let ControlRequestReply::Error(err) = reply {
+            bail!("coordinator returned an error: {err}");
+        }
+        Ok(reply)
+    }
+}
@@ -0,0 +1,27 @@
+use eyre::{Context, OptionExt, bail};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Reads a JSON file that a node wrote, e.g. a report that the runner checks after the dataflow
+/// finished.
+pub fn read_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> eyre::Result<T> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("{} was not written", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// The value of `key` in the `env` of the node `id` of a dataflow descriptor, so that runners
+/// check their results against what the dataflow configures instead of repeating it.
+pub fn node_env(dataflow: &serde_yaml::Value, id: &str, key: &str) -> eyre::Result<String> {
+    let node = dataflow["nodes"]
+        .as_sequence()
+        .and_then(|nodes| nodes.iter().find(|node| node["id"] == id))
+        .ok_or_eyre(format!("dataflow has no node `{id}`"))?;
+    match &node["env"][key] {
+        serde_yaml::Value::String(value) => Ok(value.clone()),
+        serde_yaml::Value::Number(value) => Ok(value.to_string()),
+        serde_yaml::Value::Bool(value) => Ok(value.to_string()),
+        _ => bail!("node `{id}` has no `{key}`"),
+    }
+}
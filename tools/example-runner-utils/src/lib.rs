@@ -0,0 +1,208 @@
+//! Helpers for the example runners in `examples/*/main.rs`: building dora packages and native
+//! nodes, directly or with CMake, building and running dataflows with the dora checkout in
+//! `DORA`, setting up the uv environments of Python nodes, querying the coordinator of a running
+//! cluster, reading the configuration and the results of dataflows, and finding the ROS 2
+//! installation of the ROS 2 examples.
+
+use eyre::{Context, bail};
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+
+pub use cargo::CargoBuild;
+pub use cmake::CmakeBuild;
+pub use control::Control;
+pub use dataflow::{node_env, read_json};
+pub use native::{Language, NativeNode, pkg_config};
+pub use ros::RosDistro;
+pub use uv::UvVenv;
+
+mod cargo;
+mod cmake;
+mod control;
+mod dataflow;
+mod native;
+mod ros;
+mod uv;
+
+/// The dora checkout that the examples run against.
+#[derive(Debug, Clone)]
+pub struct Dora {
+    root: PathBuf,
+    uv: bool,
+    envs: Vec<(OsString, OsString)>,
+}
+
+impl Dora {
+    /// Reads the path of the dora checkout from `DORA`.
+    pub fn from_env() -> eyre::Result<Self> {
+        let root = std::env::var_os("DORA")
+            .ok_or_else(|| eyre::eyre!("`DORA` must be set to the path of a dora checkout"))?;
+        Ok(Self::new(root))
+    }
+
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            uv: false,
+            envs: Vec::new(),
+        }
+    }
+
+    /// Builds and runs dataflows with `--uv`, so that Python nodes use the uv environment of
+    /// the example. Dataflows are then run with `dora run` instead of `dora daemon`.
+    pub fn uv(mut self) -> Self {
+        self.uv = true;
+        self
+    }
+
+    /// Sets an environment variable for every `dora` command, and so for the `build`
+    /// commands and nodes of the dataflows, e.g. `ROS` for dataflows that source it.
+    pub fn env(mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> Self {
+        self.envs
+            .push((key.as_ref().to_owned(), value.as_ref().to_owned()));
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn manifest_path(&self) -> PathBuf {
+        self.root.join("Cargo.toml")
+    }
+
+    pub fn target_dir(&self) -> PathBuf {
+        self.root.join("target")
+    }
+
+    /// Where [`Dora::package`] builds put their libraries, for [`NativeNode::lib_dir`].
+    pub fn release_dir(&self) -> PathBuf {
+        self.target_dir().join("release")
+    }
+
+    /// A release build of a package of the dora workspace, e.g. `dora-node-api-c`.
+    pub fn package(&self, package: &str) -> CargoBuild {
+        CargoBuild::new(package).manifest_path(self.manifest_path())
+    }
+
+    /// A CMake build of the project in `source_dir` that finds this checkout with the
+    /// `FindDora.cmake` of `tools/cmake`, and the libraries in [`Dora::release_dir`].
+    pub fn cmake(&self, source_dir: impl Into<PathBuf>) -> CmakeBuild {
+        CmakeBuild::new(source_dir)
+            .define("DORA_ROOT_DIR", &self.root)
+            .define("DORA_LIB_DIR", self.release_dir())
+    }
+
+    /// The uv environment of the Python nodes of an example, with `python` as interpreter,
+    /// e.g. `3.11`.
+    pub fn uv_venv(&self, python: &str) -> UvVenv {
+        UvVenv::new(python, self.root.join("apis").join("python").join("node"))
+    }
+
+    /// `dora` with the given arguments, built from the checkout if needed.
+    pub fn cli<I, S>(&self, args: I) -> Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut cmd = Command::new(cargo());
+        cmd.arg("run");
+        cmd.arg("--manifest-path").arg(self.manifest_path());
+        cmd.arg("--package").arg("dora-cli");
+        cmd.arg("--release");
+        cmd.arg("--").args(args);
+        cmd.envs(self.envs.iter().map(|(key, value)| (key, value)));
+        cmd
+    }
+
+    /// Builds `dora` from the checkout, and returns the path of the binary.
+    ///
+    /// For runners that start `dora` many times or time it, where [`Dora::cli`] would add the
+    /// startup of `cargo run`. The environment of [`Dora::env`] is not set on the binary.
+    pub async fn build_cli(&self) -> eyre::Result<PathBuf> {
+        self.package("dora-cli").build().await?;
+        let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.target_dir());
+        Ok(target_dir
+            .join("release")
+            .join(format!("dora{}", std::env::consts::EXE_SUFFIX)))
+    }
+
+    /// Validates `dataflow` and runs `dora build` on it.
+    ///
+    /// The returned future doesn't borrow `self` or `dataflow`, so it can be spawned.
+    pub fn build_dataflow(
+        &self,
+        dataflow: &Path,
+    ) -> impl Future<Output = eyre::Result<()>> + Send + 'static {
+        let valid = validate_dataflows::check(dataflow);
+        let mut cmd = self.cli(["build"]);
+        cmd.arg(dataflow);
+        if self.uv {
+            cmd.arg("--uv");
+        }
+        async move {
+            valid?;
+            run(&mut cmd, "failed to build dataflow").await
+        }
+    }
+
+    /// Runs `dataflow` with a local daemon, until it stops.
+    ///
+    /// The returned future doesn't borrow `self` or `dataflow`, so it can be spawned.
+    pub fn run_dataflow(
+        &self,
+        dataflow: &Path,
+    ) -> impl Future<Output = eyre::Result<()>> + Send + 'static {
+        let mut cmd = if self.uv {
+            let mut cmd = self.cli(["run"]);
+            cmd.arg(dataflow).arg("--uv");
+            cmd
+        } else {
+            let mut cmd = self.cli(["daemon", "--run-dataflow"]);
+            cmd.arg(dataflow);
+            cmd
+        };
+        async move { run(&mut cmd, "failed to run dataflow").await }
+    }
+}
+
+/// Runs `cmd` and fails with `error` if it doesn't succeed.
+pub async fn run(cmd: &mut Command, error: &str) -> eyre::Result<()> {
+    let status = cmd.status().await.wrap_err_with(|| error.to_owned())?;
+    if !status.success() {
+        bail!("{error}: {status}");
+    }
+    Ok(())
+}
+
+/// The cargo that runs the example, so that runners use the same toolchain.
+fn cargo() -> PathBuf {
+    std::env::var_os("CARGO")
+        .unwrap_or_else(|| "cargo".into())
+        .into()
+}
+
+/// Whether `script` is a batch file, like the `local_setup.bat` of ROS 2 on Windows, which
+/// `cmd` calls instead of bash sourcing it.
+fn is_batch_file(script: &Path) -> bool {
+    script
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("bat"))
+}
+
+/// `command` in a `cmd` that called the batch file `script` first.
+fn batch_command(script: &Path, command: &str) -> Command {
+    let line = format!(r#"/C call "{}" && {command}"#, script.display());
+    let mut cmd = Command::new("cmd");
+    // cmd has its own quoting rules, which the escaping of `Command::arg` breaks
+    #[cfg(windows)]
+    cmd.raw_arg(line);
+    #[cfg(not(windows))]
+    cmd.arg(line);
+    cmd
+}
@@ -0,0 +1,266 @@
+use crate::run;
+use eyre::{Context, bail};
+use std::{
+    env::consts::EXE_SUFFIX,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// Compiled with `clang` by default, or `cl.exe` on Windows.
+    C,
+    /// Compiled as C++17 with `clang++` by default, or `cl.exe` on Windows.
+    Cxx,
+}
+
+/// An executable node built from C or C++ sources and linked against a dora node API library.
+///
+/// Links the system libraries that the Rust standard library and the dora libraries need on
+/// the current platform. On Windows, the node is built with the MSVC toolchain against the
+/// dynamic C runtime, like the Rust libraries, since mixing it with the static one of
+/// `libcmt` fails to link.
+#[derive(Debug, Clone)]
+pub struct NativeNode {
+    name: String,
+    language: Language,
+    compiler: Option<OsString>,
+    sources: Vec<PathBuf>,
+    libs: Vec<String>,
+    lib_dirs: Vec<PathBuf>,
+    args: Vec<OsString>,
+    output_dir: PathBuf,
+}
+
+impl NativeNode {
+    pub fn new(language: Language, name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            language,
+            compiler: None,
+            sources: Vec::new(),
+            libs: Vec::new(),
+            lib_dirs: Vec::new(),
+            args: Vec::new(),
+            output_dir: PathBuf::from("build"),
+        }
+    }
+
+    pub fn c(name: &str) -> Self {
+        Self::new(Language::C, name)
+    }
+
+    pub fn cxx(name: &str) -> Self {
+        Self::new(Language::Cxx, name)
+    }
+
+    /// Overrides the default compiler of the language, e.g. with `gcc`. The compiler must
+    /// accept clang's arguments, or on Windows those of `cl.exe`, like `clang-cl`.
+    pub fn compiler(mut self, compiler: impl Into<OsString>) -> Self {
+        self.compiler = Some(compiler.into());
+        self
+    }
+
+    pub fn source(mut self, source: impl Into<PathBuf>) -> Self {
+        self.sources.push(source.into());
+        self
+    }
+
+    /// Links `lib`, e.g. `dora_node_api_c`.
+    pub fn link(mut self, lib: &str) -> Self {
+        self.libs.push(lib.to_owned());
+        self
+    }
+
+    /// Looks for libraries in `dir`, e.g. [`Dora::release_dir`](crate::Dora::release_dir).
+    pub fn lib_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.lib_dirs.push(dir.as_ref().to_owned());
+        self
+    }
+
+    /// Passes `arg` to the compiler, in the syntax of the compiler of the platform.
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Adds whitespace-separated `flags`, like the output of [`pkg_config`].
+    pub fn flags(mut self, flags: &str) -> Self {
+        self.args
+            .extend(flags.split_whitespace().map(OsString::from));
+        self
+    }
+
+    /// Where the executable is written, `build` by default.
+    pub fn output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = dir.into();
+        self
+    }
+
+    /// Compiles the node and returns the path of the executable.
+    pub async fn build(self) -> eyre::Result<PathBuf> {
+        if self.sources.is_empty() {
+            bail!("no sources for node `{}`", self.name);
+        }
+        tokio::fs::create_dir_all(&self.output_dir).await?;
+        let output = self.output_dir.join(format!("{}{EXE_SUFFIX}", self.name));
+
+        let mut cmd = if cfg!(target_env = "msvc") {
+            self.msvc_command(&output)?
+        } else {
+            self.clang_command(&output)
+        };
+        run(&mut cmd, &format!("failed to compile node `{}`", self.name)).await?;
+        Ok(output)
+    }
+
+    fn clang_command(&self, output: &Path) -> Command {
+        let compiler = self
+            .compiler
+            .clone()
+            .unwrap_or_else(|| match self.language {
+                Language::C => "clang".into(),
+                Language::Cxx => "clang++".into(),
+            });
+        let mut cmd = Command::new(compiler);
+        cmd.args(&self.sources);
+        if self.language == Language::Cxx {
+            cmd.arg("-std=c++17");
+        }
+        if cfg!(target_os = "macos") {
+            let arch = match std::env::consts::ARCH {
+                "aarch64" => "arm64",
+                other => other,
+            };
+            cmd.arg("-arch").arg(arch);
+        }
+        cmd.args(&self.args);
+        for dir in &self.lib_dirs {
+            cmd.arg("-L").arg(dir);
+        }
+        cmd.args(self.libs.iter().map(|lib| format!("-l{lib}")));
+        cmd.args(system_libs().iter().map(|lib| format!("-l{lib}")));
+        cmd.args(system_flags());
+        cmd.arg("--output").arg(output);
+        cmd
+    }
+
+    /// `cl.exe` with the environment of the newest Visual Studio installation, so that it
+    /// also works outside of a developer command prompt.
+    fn msvc_command(&self, output: &Path) -> eyre::Result<Command> {
+        let target = format!("{}-pc-windows-msvc", std::env::consts::ARCH);
+        let cl = cc::windows_registry::find(&target, "cl.exe");
+        let mut cmd = match (&self.compiler, cl) {
+            (Some(compiler), cl) => {
+                let mut cmd = Command::new(compiler);
+                // e.g. `INCLUDE` and `LIB`, which `clang-cl` needs too
+                for (key, value) in cl.iter().flat_map(|cl| cl.get_envs()) {
+                    if let Some(value) = value {
+                        cmd.env(key, value);
+                    }
+                }
+                cmd
+            }
+            (None, Some(cl)) => Command::from(cl),
+            (None, None) => bail!(
+                "failed to find `cl.exe`, install the C++ build tools of Visual Studio or run \
+                 from a developer command prompt"
+            ),
+        };
+
+        // `/MD` is the dynamic C runtime that Rust uses, `/EHsc` the exception model of C++
+        cmd.args(["/nologo", "/MD"]);
+        if self.language == Language::Cxx {
+            cmd.args(["/std:c++17", "/EHsc"]);
+        }
+        cmd.args(&self.sources);
+        cmd.args(&self.args);
+        let mut objects = OsString::from("/Fo");
+        objects.push(self.output_dir.as_os_str());
+        objects.push("\\");
+        let mut executable = OsString::from("/Fe");
+        executable.push(output.as_os_str());
+        cmd.arg(objects).arg(executable);
+
+        cmd.arg("/link");
+        for dir in &self.lib_dirs {
+            let mut lib_path = OsString::from("/LIBPATH:");
+            lib_path.push(dir.as_os_str());
+            cmd.arg(lib_path);
+        }
+        cmd.args(self.libs.iter().map(|lib| format!("{lib}.lib")));
+        cmd.args(system_libs().iter().map(|lib| format!("{lib}.lib")));
+        cmd.arg("/NODEFAULTLIB:libcmt");
+        Ok(cmd)
+    }
+}
+
+/// The compiler and linker flags of a library, from `pkg-config --cflags --libs`.
+pub fn pkg_config(library: &str) -> eyre::Result<String> {
+    let output = std::process::Command::new("pkg-config")
+        .args(["--cflags", "--libs", library])
+        .output()
+        .wrap_err("failed to run pkg-config")?;
+    if !output.status.success() {
+        bail!(
+            "`{library}` not found via pkg-config, make sure it's installed and in your \
+             PKG_CONFIG_PATH: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+}
+
+/// Libraries that static Rust libraries depend on.
+fn system_libs() -> &'static [&'static str] {
+    if cfg!(target_os = "linux") {
+        &["m", "rt", "dl", "z"]
+    } else if cfg!(target_os = "macos") {
+        &["System", "resolv", "pthread", "c", "m", "z"]
+    } else if cfg!(target_os = "windows") {
+        &[
+            "advapi32",
+            "userenv",
+            "kernel32",
+            "ws2_32",
+            "bcrypt",
+            "ncrypt",
+            "schannel",
+            "ntdll",
+            "iphlpapi",
+            "cfgmgr32",
+            "credui",
+            "crypt32",
+            "cryptnet",
+            "fwpuclnt",
+            "gdi32",
+            "msimg32",
+            "mswsock",
+            "ole32",
+            "oleaut32",
+            "opengl32",
+            "secur32",
+            "shell32",
+            "synchronization",
+            "user32",
+            "winspool",
+            "winhttp",
+            "rpcrt4",
+            "msvcrt",
+        ]
+    } else {
+        &[]
+    }
+}
+
+/// Flags besides the `system_libs` for clang.
+fn system_flags() -> &'static [&'static str] {
+    if cfg!(target_os = "linux") {
+        &["-pthread"]
+    } else if cfg!(target_os = "macos") {
+        &["-framework", "CoreServices", "-framework", "Security"]
+    } else {
+        &[]
+    }
+}
@@ -0,0 +1,193 @@
+use eyre::{Context, bail};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+use tokio::process::Command;
+
+/// Where the apt packages of ROS 2 install the distros, one directory per distro.
+const ROS_ROOT: &str = "/opt/ros";
+
+/// A ROS 2 installation that the examples source before they build or start ROS 2 nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RosDistro {
+    name: String,
+    setup_script: PathBuf,
+}
+
+impl RosDistro {
+    /// Finds the ROS 2 installation to use, in this order:
+    ///
+    /// 1. `ROS`, the path of a `setup.bash`, or of a `local_setup.bat` on Windows. The distro
+    ///    is `ROS_DISTRO` if set, or the name of the directory of the script.
+    /// 2. `ROS_DISTRO`, which a sourced installation sets, in `/opt/ros`.
+    /// 3. The newest distro in `/opt/ros`. Distros are named in alphabetical order, `rolling`
+    ///    is only used if there's no other.
+    pub fn detect() -> eyre::Result<Self> {
+        let distro = std::env::var("ROS_DISTRO").ok().filter(|d| !d.is_empty());
+        if let Some(script) = std::env::var_os("ROS") {
+            let setup_script = PathBuf::from(script);
+            let name = distro
+                .or_else(|| {
+                    let dir = setup_script.parent()?.file_name()?;
+                    Some(dir.to_string_lossy().into_owned())
+                })
+                .ok_or_else(|| {
+                    eyre::eyre!(
+                        "can't tell the distro of `ROS={}`, set `ROS_DISTRO` too",
+                        setup_script.display()
+                    )
+                })?;
+            return Ok(Self { name, setup_script });
+        }
+        if let Some(name) = distro {
+            let setup_script = Path::new(ROS_ROOT).join(&name).join("setup.bash");
+            if !setup_script.exists() {
+                bail!("`ROS_DISTRO={name}` is not installed in {ROS_ROOT}");
+            }
+            return Ok(Self { name, setup_script });
+        }
+
+        let mut installed = installed_distros()?;
+        installed.sort_by_key(|name| (name != "rolling", name.clone()));
+        let Some(name) = installed.pop() else {
+            bail!(
+                "no ROS 2 installation found in {ROS_ROOT}, set `ROS` to its `setup.bash` or \
+                 `ROS_DISTRO` to its name"
+            );
+        };
+        if !installed.is_empty() {
+            println!("using ROS 2 {name}, set `ROS_DISTRO` to use another one of {installed:?}");
+        }
+        Ok(Self {
+            setup_script: Path::new(ROS_ROOT).join(&name).join("setup.bash"),
+            name,
+        })
+    }
+
+    /// The name of the distro, e.g. `jazzy`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The `setup.bash` to source, or `local_setup.bat` to call, e.g. for
+    /// [`crate::CargoBuild::setup_script`].
+    pub fn setup_script(&self) -> &Path {
+        &self.setup_script
+    }
+
+    /// The apt package of the ROS 2 package `package` for this distro, e.g.
+    /// `ros-humble-examples-rclcpp-minimal-service` for `examples_rclcpp_minimal_service`.
+    pub fn apt_package(&self, package: &str) -> String {
+        format!("ros-{}-{}", self.name, package.replace('_', "-"))
+    }
+
+    /// Installs the ROS 2 packages `packages` that are missing, with apt.
+    ///
+    /// A package counts as installed if `ros2 pkg prefix` finds it, no matter how it was
+    /// installed, e.g. from source on Fedora or Arch. With `skip_install`, e.g. in CI
+    /// environments without sudo, missing packages are an error instead.
+    pub async fn ensure_packages(&self, packages: &[&str], skip_install: bool) -> eyre::Result<()> {
+        let mut missing = Vec::new();
+        for &package in packages {
+            if !self.has_package(package).await? {
+                missing.push(package);
+            }
+        }
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let apt_packages: Vec<_> = missing.iter().map(|p| self.apt_package(p)).collect();
+        if skip_install {
+            bail!(
+                "the ROS 2 packages {missing:?} are not installed, install them first, e.g. \
+                 with `sudo apt install {}`",
+                apt_packages.join(" ")
+            );
+        }
+        if cfg!(windows) || !has_command("apt-get").await? {
+            bail!(
+                "the ROS 2 packages {missing:?} are not installed, and there's no apt to install \
+                 them, install them for {} first",
+                self.setup_script.display()
+            );
+        }
+        self.apt_install(&missing).await
+    }
+
+    /// Whether `ros2 pkg prefix` finds the ROS 2 package `package`.
+    pub async fn has_package(&self, package: &str) -> eyre::Result<bool> {
+        let status = self
+            .command(&format!("ros2 pkg prefix {package}"))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .wrap_err("failed to run `ros2 pkg prefix`")?;
+        Ok(status.success())
+    }
+
+    /// Installs the ROS 2 packages `packages` of this distro with apt, through `sudo` unless
+    /// running as root, e.g. in a container.
+    pub async fn apt_install(&self, packages: &[&str]) -> eyre::Result<()> {
+        let packages: Vec<_> = packages.iter().map(|p| self.apt_package(p)).collect();
+        let sudo = if is_root().await? { "" } else { "sudo " };
+        let mut cmd = Command::new("bash");
+        cmd.arg("-c").arg(format!(
+            "{sudo}apt-get update && {sudo}apt-get install -y {}",
+            packages.join(" ")
+        ));
+        crate::run(&mut cmd, &format!("failed to install {packages:?}")).await
+    }
+
+    /// `command` in a bash that sourced the installation first, e.g. `ros2 run ...`, or on
+    /// Windows in a `cmd` that called its `local_setup.bat`.
+    pub fn command(&self, command: &str) -> Command {
+        if crate::is_batch_file(&self.setup_script) {
+            return crate::batch_command(&self.setup_script, command);
+        }
+        let mut cmd = Command::new("bash");
+        cmd.arg("-c").arg(format!(
+            "source {} && {command}",
+            self.setup_script.display()
+        ));
+        cmd
+    }
+}
+
+/// The distros in `/opt/ros` that have a `setup.bash`.
+fn installed_distros() -> eyre::Result<Vec<String>> {
+    let entries = match std::fs::read_dir(ROS_ROOT) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).wrap_err_with(|| format!("failed to read {ROS_ROOT}")),
+    };
+    let mut distros = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.path().join("setup.bash").exists() {
+            distros.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    Ok(distros)
+}
+
+async fn has_command(name: &str) -> eyre::Result<bool> {
+    let status = Command::new("bash")
+        .arg("-c")
+        .arg(format!("command -v {name}"))
+        .stdout(Stdio::null())
+        .status()
+        .await
+        .wrap_err("failed to run bash")?;
+    Ok(status.success())
+}
+
+async fn is_root() -> eyre::Result<bool> {
+    let output = Command::new("id")
+        .arg("-u")
+        .output()
+        .await
+        .wrap_err("failed to run `id`")?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim() == "0")
+}
@@ -0,0 +1,63 @@
+use crate::run;
+use std::{ffi::OsStr, path::PathBuf};
+use tokio::process::Command;
+
+/// A uv virtual environment in the `.venv` of the current directory, with the Python API of
+/// the dora checkout installed, for the Python nodes of dataflows run with
+/// [`Dora::uv`](crate::Dora::uv).
+#[derive(Debug, Clone)]
+pub struct UvVenv {
+    python: String,
+    system_site_packages: bool,
+    node_api: PathBuf,
+}
+
+impl UvVenv {
+    pub(crate) fn new(python: &str, node_api: PathBuf) -> Self {
+        Self {
+            python: python.to_owned(),
+            system_site_packages: false,
+            node_api,
+        }
+    }
+
+    /// Lets the environment use the packages of the system Python too, e.g. bindings that are
+    /// installed with apt.
+    pub fn system_site_packages(mut self) -> Self {
+        self.system_site_packages = true;
+        self
+    }
+
+    /// Creates the environment, or replaces an existing one, and installs the dora API into it.
+    pub async fn create(&self) -> eyre::Result<()> {
+        let mut venv = Command::new("uv");
+        venv.args(["venv", "--seed", "-p", &self.python]);
+        if self.system_site_packages {
+            venv.arg("--system-site-packages");
+        }
+        run(
+            &mut venv,
+            "failed to create venv, is uv installed? See \
+             https://docs.astral.sh/uv/getting-started/installation/",
+        )
+        .await?;
+
+        let mut install = Command::new("uv");
+        install
+            .args(["pip", "install", "--reinstall", "-e"])
+            .arg(&self.node_api);
+        run(&mut install, "failed to install the dora Python API").await
+    }
+
+    /// Runs `uv pip install` with `args` in the environment, e.g. for packages that only some
+    /// runs of an example need.
+    pub async fn pip_install<I, S>(&self, args: I) -> eyre::Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut install = Command::new("uv");
+        install.args(["pip", "install"]).args(args);
+        run(&mut install, "failed to install Python packages").await
+    }
+}
@@ -0,0 +1,793 @@
+//! Checks dora dataflow descriptors before they are built or run.
+//!
+//! - node ids are unique,
+//! - `path`s, Python operators and the files that `build` commands refer to exist, and the
+//!   programs that `build` commands run are on the PATH,
+//! - every input refers to a timer or to an output that its node declares,
+//! - `$VAR` and `${VAR}` placeholders are set in the environment.
+//!
+//! Runners call [`check`] before they build a dataflow, so that a typo in the YAML fails right
+//! away with a clear message instead of after a long build or in the middle of a run.
+
+use eyre::{Context, bail};
+use serde_yaml::{Mapping, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    /// The node that the issue was found in, `None` for the dataflow as a whole.
+    pub node: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: ")?,
+            Severity::Error => write!(f, "error: ")?,
+        }
+        if let Some(node) = &self.node {
+            write!(f, "node `{node}`: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Whether placeholders that are not set in the environment are errors or warnings.
+    pub require_env: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { require_env: true }
+    }
+}
+
+/// Validates `dataflow` and fails with all errors that were found. Warnings are printed to
+/// stderr.
+pub fn check(dataflow: &Path) -> eyre::Result<()> {
+    let mut errors = Vec::new();
+    for issue in validate(dataflow, &Options::default())? {
+        match issue.severity {
+            Severity::Warning => eprintln!("{}: {issue}", dataflow.display()),
+            Severity::Error => errors.push(issue.to_string()),
+        }
+    }
+    if !errors.is_empty() {
+        bail!(
+            "invalid dataflow {}:\n  {}",
+            dataflow.display(),
+            errors.join("\n  ")
+        );
+    }
+    Ok(())
+}
+
+/// Returns the issues of `dataflow`. Relative paths in it are resolved against its directory.
+pub fn validate(dataflow: &Path, options: &Options) -> eyre::Result<Vec<Issue>> {
+    let yaml = std::fs::read_to_string(dataflow)
+        .with_context(|| format!("failed to read {}", dataflow.display()))?;
+    let descriptor: Value = serde_yaml::from_str(&yaml)
+        .with_context(|| format!("failed to parse {}", dataflow.display()))?;
+    let base = match dataflow.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut validator = Validator {
+        base,
+        options,
+        issues: Vec::new(),
+    };
+    validator.validate(&descriptor);
+    validator
+        .issues
+        .sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    Ok(validator.issues)
+}
+
+/// Returns all dataflow descriptors below `root`: YAML files with a top-level `nodes` key.
+///
+/// Skips hidden directories, build output like `target` and `out`, and `fragments`
+/// directories, whose files only form a dataflow once they are composed.
+pub fn find_dataflows(root: &Path) -> eyre::Result<Vec<PathBuf>> {
+    const SKIP: &[&str] = &[
+        "target",
+        "out",
+        "build",
+        "install",
+        "node_modules",
+        "fragments",
+    ];
+
+    let mut dataflows = Vec::new();
+    let mut dirs = vec![root.to_owned()];
+    while let Some(dir) = dirs.pop() {
+        let entries =
+            std::fs::read_dir(&dir).with_context(|| format!("failed to read {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if path.is_dir() {
+                if !name.starts_with('.') && !SKIP.contains(&name.as_ref()) {
+                    dirs.push(path);
+                }
+            } else if name.ends_with(".yml") || name.ends_with(".yaml") {
+                let is_dataflow = std::fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|yaml| serde_yaml::from_str::<Value>(&yaml).ok())
+                    .is_some_and(|value| value.get("nodes").is_some());
+                if is_dataflow {
+                    dataflows.push(path);
+                }
+            }
+        }
+    }
+    dataflows.sort();
+    Ok(dataflows)
+}
+
+struct Validator<'a> {
+    base: &'a Path,
+    options: &'a Options,
+    issues: Vec<Issue>,
+}
+
+/// A node, or an operator of a runtime node, with the inputs it reads.
+struct InputSet<'a> {
+    node: String,
+    inputs: Option<&'a Value>,
+}
+
+impl Validator<'_> {
+    fn error(&mut self, node: Option<&str>, message: String) {
+        self.issues.push(Issue {
+            severity: Severity::Error,
+            node: node.map(str::to_owned),
+            message,
+        });
+    }
+
+    fn warning(&mut self, node: Option<&str>, message: String) {
+        self.issues.push(Issue {
+            severity: Severity::Warning,
+            node: node.map(str::to_owned),
+            message,
+        });
+    }
+
+    fn validate(&mut self, descriptor: &Value) {
+        let Some(nodes) = descriptor.get("nodes").and_then(Value::as_sequence) else {
+            self.error(None, "missing `nodes` list".to_owned());
+            return;
+        };
+
+        // outputs by node, or by `node/operator` for nodes with an `operators` list
+        let mut outputs: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut runtime_nodes = BTreeSet::new();
+        let mut input_sets = Vec::new();
+        let any_build = nodes.iter().any(|node| node.get("build").is_some());
+        for (index, node) in nodes.iter().enumerate() {
+            let Some(node) = node.as_mapping() else {
+                self.error(None, format!("node #{index} is not a mapping"));
+                continue;
+            };
+            let Some(id) = node.get("id").and_then(Value::as_str) else {
+                self.error(None, format!("node #{index} has no `id`"));
+                continue;
+            };
+            if id.contains('/') {
+                self.error(Some(id), "ids must not contain `/`".to_owned());
+            }
+            if outputs.contains_key(id) {
+                self.error(Some(id), "duplicate node id".to_owned());
+                continue;
+            }
+
+            if let Some(operators) = node.get("operators").and_then(Value::as_sequence) {
+                for operator in operators {
+                    let Some(operator_id) = operator.get("id").and_then(Value::as_str) else {
+                        self.error(Some(id), "operator without `id`".to_owned());
+                        continue;
+                    };
+                    outputs.insert(
+                        format!("{id}/{operator_id}"),
+                        self.outputs(id, operator.get("outputs")),
+                    );
+                    input_sets.push(InputSet {
+                        node: format!("{id}/{operator_id}"),
+                        inputs: operator.get("inputs"),
+                    });
+                    self.check_operator(id, operator);
+                }
+                outputs.insert(id.to_owned(), BTreeSet::new());
+                runtime_nodes.insert(id.to_owned());
+            } else if let Some(operator) = node.get("operator") {
+                // the outputs of a single operator belong to the node
+                outputs.insert(id.to_owned(), self.outputs(id, operator.get("outputs")));
+                input_sets.push(InputSet {
+                    node: id.to_owned(),
+                    inputs: operator.get("inputs"),
+                });
+                self.check_operator(id, operator);
+            } else {
+                outputs.insert(id.to_owned(), self.outputs(id, node.get("outputs")));
+                input_sets.push(InputSet {
+                    node: id.to_owned(),
+                    inputs: node.get("inputs"),
+                });
+                self.check_path(id, node, any_build);
+            }
+
+            if let Some(build) = node.get("build").and_then(Value::as_str) {
+                self.check_build(id, build);
+            }
+            if let Some(env) = node.get("env").and_then(Value::as_mapping) {
+                for (key, value) in env {
+                    if let Some(value) = value.as_str() {
+                        let key = key.as_str().unwrap_or_default();
+                        self.expand(id, &format!("env `{key}`"), value);
+                    }
+                }
+            }
+            if let Some(args) = node.get("args").and_then(Value::as_str) {
+                self.expand(id, "args", args);
+            }
+        }
+
+        let mut used = BTreeSet::new();
+        for InputSet { node, inputs } in &input_sets {
+            let Some(inputs) = inputs else {
+                continue;
+            };
+            let Some(inputs) = inputs.as_mapping() else {
+                self.error(Some(node), "`inputs` is not a mapping".to_owned());
+                continue;
+            };
+            for (input, source) in inputs {
+                let input = input.as_str().unwrap_or_default();
+                let source = match source {
+                    Value::String(source) => Some(source.as_str()),
+                    Value::Mapping(source) => source.get("source").and_then(Value::as_str),
+                    _ => None,
+                };
+                let Some(source) = source else {
+                    self.error(Some(node), format!("input `{input}` has no source"));
+                    continue;
+                };
+                if let Some(message) = self.check_source(source, &outputs, &runtime_nodes) {
+                    self.error(Some(node), format!("input `{input}`: {message}"));
+                } else {
+                    used.insert(source.to_owned());
+                }
+            }
+        }
+        for (node, node_outputs) in &outputs {
+            for output in node_outputs {
+                if !used.contains(&format!("{node}/{output}")) {
+                    // fine for outputs that only external tools like `dora-record` read
+                    self.warning(Some(node), format!("output `{output}` is not used"));
+                }
+            }
+        }
+    }
+
+    fn outputs(&mut self, node: &str, declared: Option<&Value>) -> BTreeSet<String> {
+        let mut outputs = BTreeSet::new();
+        for output in declared.and_then(Value::as_sequence).into_iter().flatten() {
+            match output.as_str() {
+                Some(output) if !outputs.insert(output.to_owned()) => {
+                    self.error(Some(node), format!("duplicate output `{output}`"));
+                }
+                Some(_) => {}
+                None => self.error(Some(node), format!("invalid output {output:?}")),
+            }
+        }
+        outputs
+    }
+
+    /// Returns why `source` is invalid, if it is.
+    fn check_source(
+        &self,
+        source: &str,
+        outputs: &BTreeMap<String, BTreeSet<String>>,
+        runtime_nodes: &BTreeSet<String>,
+    ) -> Option<String> {
+        if let Some(timer) = source.strip_prefix("dora/") {
+            let valid = match timer.split('/').collect::<Vec<_>>()[..] {
+                ["timer", "millis" | "secs", interval] => {
+                    interval.parse::<u64>().is_ok_and(|interval| interval > 0)
+                }
+                _ => false,
+            };
+            return (!valid).then(|| {
+                format!("invalid timer `{source}`, expected `dora/timer/millis/<n>` or `dora/timer/secs/<n>`")
+            });
+        }
+        // output ids may contain `/`, node and operator ids may not
+        let Some((node, output)) = source.split_once('/') else {
+            return Some(format!(
+                "invalid source `{source}`, expected `<node>/<output>`"
+            ));
+        };
+        let (node, output) = match output.split_once('/') {
+            Some((operator, output)) if runtime_nodes.contains(node) => {
+                (format!("{node}/{operator}"), output)
+            }
+            _ => (node.to_owned(), output),
+        };
+        let Some(node_outputs) = outputs.get(&node) else {
+            let known = outputs.keys().map(String::as_str);
+            return Some(format!(
+                "unknown node `{node}`{}",
+                did_you_mean(&node, known)
+            ));
+        };
+        if !node_outputs.contains(output) {
+            let known = node_outputs.iter().map(String::as_str);
+            return Some(format!(
+                "`{node}` has no output `{output}`{}",
+                did_you_mean(output, known)
+            ));
+        }
+        None
+    }
+
+    fn check_path(&mut self, id: &str, node: &Mapping, any_build: bool) {
+        let Some(path) = node.get("path").and_then(Value::as_str) else {
+            self.error(Some(id), "no `path`, `operator`, or `operators`".to_owned());
+            return;
+        };
+        // paths of git nodes are relative to the repository
+        if path == "dynamic" || path.contains("://") || node.get("git").is_some() {
+            return;
+        }
+        let Some(path) = self.expand(id, "path", path) else {
+            return;
+        };
+        let is_file = path.contains('/') || path.contains('\\') || path.contains('.');
+        if is_file {
+            if !self.base.join(&path).exists() && !any_build {
+                self.error(
+                    Some(id),
+                    format!("`{path}` does not exist and nothing builds it"),
+                );
+            }
+        } else if !any_build && !on_path(&path) {
+            // an executable that `dora build` would install, e.g. with pip
+            self.warning(Some(id), format!("`{path}` is not on the PATH"));
+        }
+    }
+
+    /// Checks Python operators. Shared libraries are only built by the runners, so they can't be
+    /// checked up front.
+    fn check_operator(&mut self, id: &str, operator: &Value) {
+        let Some(python) = operator.get("python") else {
+            return;
+        };
+        // either the path, or a mapping with `source`
+        let source = python
+            .as_str()
+            .or_else(|| python.get("source").and_then(Value::as_str));
+        if let Some(source) = source
+            && !self.base.join(source).exists()
+        {
+            self.error(
+                Some(id),
+                format!("Python operator `{source}` does not exist"),
+            );
+        }
+    }
+
+    /// Checks the programs that a `build` command runs, and the files that it refers to:
+    /// manifests, packages installed with `pip install -e`, requirement files, and directories
+    /// that it changes into.
+    fn check_build(&mut self, id: &str, build: &str) {
+        let Some(words) = shlex::split(build) else {
+            self.error(
+                Some(id),
+                format!("unbalanced quotes in build command `{build}`"),
+            );
+            return;
+        };
+        // `bash -c "a && b"` runs a script
+        let script = match &words[..] {
+            [shell, flag, script, ..]
+                if ["bash", "sh"].contains(&shell.as_str()) && flag == "-c" =>
+            {
+                script.clone()
+            }
+            _ => build.to_owned(),
+        };
+
+        let mut dir = self.base.to_owned();
+        for command in script.split(['&', ';', '|', '\n']) {
+            let Some(words) = shlex::split(command) else {
+                continue;
+            };
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+            let mut paths = Vec::new();
+            match &words[..] {
+                ["cd", target] => {
+                    let Some(target) = self.expand(id, "build", target) else {
+                        return;
+                    };
+                    dir = dir.join(&target);
+                    if !dir.is_dir() {
+                        self.error(Some(id), format!("build changes into missing `{target}`"));
+                        return;
+                    }
+                    continue;
+                }
+                ["source", ..] => continue,
+                _ => {}
+            }
+            // the program, after variable assignments like `CC=clang cargo build`
+            if let Some(program) = words.iter().find(|word| !word.contains('='))
+                && !program.contains(['/', '\\', '$'])
+                && !SHELL_BUILTINS.contains(program)
+                && !on_path(program)
+            {
+                self.warning(
+                    Some(id),
+                    format!("build runs `{program}`, which is not on the PATH"),
+                );
+            }
+            for pair in words.windows(2) {
+                match pair {
+                    ["--manifest-path", path] => paths.push(*path),
+                    ["-e" | "--editable" | "-r" | "--requirement", path] => paths.push(*path),
+                    _ => {}
+                }
+            }
+            for path in paths {
+                if path.contains("://") || path.starts_with("git+") {
+                    continue;
+                }
+                let Some(path) = self.expand(id, "build", path) else {
+                    continue;
+                };
+                if !dir.join(&path).exists() {
+                    self.error(Some(id), format!("build refers to missing `{path}`"));
+                }
+            }
+        }
+    }
+
+    /// Expands `$VAR` and `${VAR}` in `value`, `None` if a variable is not set. A `$` that
+    /// doesn't start a name is kept, an empty `${}` is an error like in a shell.
+    fn expand(&mut self, node: &str, field: &str, value: &str) -> Option<String> {
+        let mut expanded = String::new();
+        let mut missing = Vec::new();
+        let mut empty = false;
+        let mut rest = value;
+        while let Some(start) = rest.find('$') {
+            expanded.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let (name, len) = match after.strip_prefix('{') {
+                Some(braced) => match braced.find('}') {
+                    Some(end) => (&braced[..end], end + 2),
+                    None => (braced, braced.len() + 1),
+                },
+                None => {
+                    let end = after
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                        .unwrap_or(after.len());
+                    (&after[..end], end)
+                }
+            };
+            if name.is_empty() && after.starts_with('{') {
+                empty = true;
+            } else if name.is_empty() {
+                expanded.push('$');
+            } else {
+                match std::env::var(name) {
+                    Ok(value) => expanded.push_str(&value),
+                    Err(_) => missing.push(name.to_owned()),
+                }
+            }
+            rest = &after[len..];
+        }
+        expanded.push_str(rest);
+
+        if empty {
+            self.error(Some(node), format!("{field} has an empty `${{}}`"));
+            return None;
+        }
+        if missing.is_empty() {
+            return Some(expanded);
+        }
+        let message = format!("{field} uses unset `${}`", missing.join("`, `$"));
+        if self.options.require_env {
+            self.error(Some(node), message);
+        } else {
+            self.warning(Some(node), message);
+        }
+        None
+    }
+}
+
+/// Commands of the shell that build scripts use, which are not programs on the PATH.
+const SHELL_BUILTINS: &[&str] = &[
+    "echo", "export", "set", "test", "[", "true", "false", "exit",
+];
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|paths| {
+        std::env::split_paths(&paths)
+            .any(|dir| dir.join(program).is_file() || dir.join(format!("{program}.exe")).is_file())
+    })
+}
+
+/// ` (did you mean `x`?)` if one of `candidates` is a likely typo of `name`.
+fn did_you_mean<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2.max(name.len() / 4))
+        .min()
+        .map(|(_, candidate)| format!(" (did you mean `{candidate}`?)"))
+        .unwrap_or_default()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The issues of the dataflow `yaml`, with paths relative to this crate.
+    fn issues(yaml: &str) -> Vec<Issue> {
+        let descriptor: Value = serde_yaml::from_str(yaml).unwrap();
+        let options = Options::default();
+        let mut validator = Validator {
+            base: Path::new(env!("CARGO_MANIFEST_DIR")),
+            options: &options,
+            issues: Vec::new(),
+        };
+        validator.validate(&descriptor);
+        validator.issues
+    }
+
+    fn errors(yaml: &str) -> Vec<String> {
+        issues(yaml)
+            .into_iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(|issue| issue.to_string())
+            .collect()
+    }
+
+    fn warnings(yaml: &str) -> Vec<String> {
+        issues(yaml)
+            .into_iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+            .map(|issue| issue.to_string())
+            .collect()
+    }
+
+    fn expand(value: &str) -> (Option<String>, Vec<Issue>) {
+        let options = Options::default();
+        let mut validator = Validator {
+            base: Path::new("."),
+            options: &options,
+            issues: Vec::new(),
+        };
+        let expanded = validator.expand("node", "path", value);
+        (expanded, validator.issues)
+    }
+
+    /// A sink that reads `source`.
+    fn sink_of(source: &str) -> String {
+        format!(
+            "nodes:
+  - id: camera
+    path: Cargo.toml
+    outputs: [image]
+  - id: sink
+    path: Cargo.toml
+    inputs:
+      camera: camera/image
+      input: {source}
+"
+        )
+    }
+
+    #[test]
+    fn timer_inputs() {
+        for timer in ["dora/timer/millis/100", "dora/timer/secs/1"] {
+            assert_eq!(errors(&sink_of(timer)), Vec::<String>::new(), "{timer}");
+        }
+        for timer in [
+            "dora/timer/millis/0",
+            "dora/timer/millis/fast",
+            "dora/timer/hours/1",
+            "dora/timer/millis",
+            "dora/clock",
+        ] {
+            let errors = errors(&sink_of(timer));
+            assert_eq!(errors.len(), 1, "{timer}: {errors:?}");
+            assert!(errors[0].contains(&format!("invalid timer `{timer}`")));
+        }
+    }
+
+    #[test]
+    fn operator_sources() {
+        let yaml = "nodes:
+  - id: runtime
+    operators:
+      - id: detector
+        python: src/lib.rs
+        outputs: [boxes]
+      - id: plotter
+        python:
+          source: src/main.rs
+        inputs:
+          boxes: runtime/detector/boxes
+      - id: missing
+        python: missing.py
+        inputs:
+          boxes: runtime/detector/other
+";
+        let errors = errors(yaml);
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors.contains(
+            &"error: node `runtime`: Python operator `missing.py` does not exist".to_owned()
+        ));
+        assert!(errors.contains(
+            &"error: node `runtime/missing`: input `boxes`: `runtime/detector` has no output `other`"
+                .to_owned()
+        ));
+    }
+
+    #[test]
+    fn single_operator_outputs_belong_to_the_node() {
+        let yaml = "nodes:
+  - id: detector
+    operator:
+      python: src/lib.rs
+      outputs: [boxes]
+  - id: sink
+    path: Cargo.toml
+    inputs:
+      boxes: detector/boxes
+";
+        assert_eq!(issues(yaml).len(), 0);
+    }
+
+    #[test]
+    fn set_variables_are_expanded() {
+        // set by cargo for the tests
+        let (expanded, issues) = expand("${CARGO_PKG_NAME}/$CARGO_PKG_NAME.bin");
+        assert_eq!(
+            expanded.as_deref(),
+            Some("validate-dataflows/validate-dataflows.bin")
+        );
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn unset_variables_are_errors() {
+        let (expanded, issues) = expand("${VALIDATE_DATAFLOWS_UNSET}/$VALIDATE_DATAFLOWS_UNSET2");
+        assert_eq!(expanded, None);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(
+            issues[0].message,
+            "path uses unset `$VALIDATE_DATAFLOWS_UNSET`, `$VALIDATE_DATAFLOWS_UNSET2`"
+        );
+    }
+
+    #[test]
+    fn empty_placeholders_are_errors() {
+        let (expanded, issues) = expand("bin/${}/node");
+        assert_eq!(expanded, None);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "path has an empty `${}`");
+    }
+
+    #[test]
+    fn dollars_without_a_name_are_kept() {
+        let (expanded, issues) = expand("costs 5$ or $-1");
+        assert_eq!(expanded.as_deref(), Some("costs 5$ or $-1"));
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn unknown_outputs_suggest_close_names() {
+        assert_eq!(
+            errors(&sink_of("camera/imgae")),
+            [
+                "error: node `sink`: input `input`: `camera` has no output `imgae` (did you mean `image`?)"
+            ]
+        );
+        assert_eq!(
+            errors(&sink_of("camrea/image")),
+            ["error: node `sink`: input `input`: unknown node `camrea` (did you mean `camera`?)"]
+        );
+        assert_eq!(
+            errors(&sink_of("camera/depth")),
+            ["error: node `sink`: input `input`: `camera` has no output `depth`"]
+        );
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("image", "image"), 0);
+        assert_eq!(edit_distance("imgae", "image"), 2);
+        assert_eq!(edit_distance("image", "images"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(
+            did_you_mean("imag", ["depth", "image", "images"].into_iter()),
+            " (did you mean `image`?)"
+        );
+        assert_eq!(did_you_mean("depth", ["image"].into_iter()), "");
+    }
+
+    /// A node with the build command `build`.
+    fn built_by(build: &str) -> String {
+        format!(
+            "nodes:
+  - id: node
+    build: {build}
+    path: target/release/node
+"
+        )
+    }
+
+    #[test]
+    fn build_programs_missing_from_path() {
+        assert_eq!(
+            warnings(&built_by("validate-dataflows-missing-tool build")),
+            [
+                "warning: node `node`: build runs `validate-dataflows-missing-tool`, which is not on the PATH"
+            ]
+        );
+        assert_eq!(
+            warnings(&built_by(
+                r#"bash -c "cd src && validate-dataflows-missing-tool build""#
+            )),
+            [
+                "warning: node `node`: build runs `validate-dataflows-missing-tool`, which is not on the PATH"
+            ]
+        );
+        // cargo runs the tests, so it's on the PATH
+        assert!(issues(&built_by("cargo build --manifest-path Cargo.toml")).is_empty());
+        assert!(issues(&built_by("CARGO_INCREMENTAL=0 cargo build")).is_empty());
+        assert!(issues(&built_by(r#"bash -c "export A=1 && ./build.sh""#)).is_empty());
+    }
+
+    #[test]
+    fn build_files_must_exist() {
+        assert_eq!(
+            errors(&built_by("cargo build --manifest-path missing/Cargo.toml")),
+            ["error: node `node`: build refers to missing `missing/Cargo.toml`"]
+        );
+        assert_eq!(
+            errors(&built_by(r#"bash -c "cd missing && cargo build""#)),
+            ["error: node `node`: build changes into missing `missing`"]
+        );
+    }
+}
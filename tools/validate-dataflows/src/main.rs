@@ -0,0 +1,63 @@
+//! Usage: `validate-dataflows [--ignore-env] [PATHS...]`
+//!
+//! Validates the given dataflow files, and every dataflow in the given directories. Defaults to
+//! the current directory. With `--ignore-env`, unset environment variables are only warnings.
+
+use eyre::bail;
+use std::path::PathBuf;
+use validate_dataflows::{Options, Severity, find_dataflows, validate};
+
+fn main() -> eyre::Result<()> {
+    let mut options = Options::default();
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--ignore-env" => options.require_env = false,
+            other if other.starts_with('-') => bail!("unexpected argument `{other}`"),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+
+    let mut dataflows = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            dataflows.extend(find_dataflows(&path)?);
+        } else {
+            dataflows.push(path);
+        }
+    }
+
+    let mut invalid = 0;
+    let mut warnings = 0;
+    for dataflow in &dataflows {
+        let issues = match validate(dataflow, &options) {
+            Ok(issues) => issues,
+            Err(err) => {
+                eprintln!("{}: error: {err:#}", dataflow.display());
+                invalid += 1;
+                continue;
+            }
+        };
+        if issues.iter().any(|issue| issue.severity == Severity::Error) {
+            invalid += 1;
+        }
+        for issue in issues {
+            if issue.severity == Severity::Warning {
+                warnings += 1;
+            }
+            eprintln!("{}: {issue}", dataflow.display());
+        }
+    }
+
+    println!(
+        "checked {} dataflows: {invalid} invalid, {warnings} warnings",
+        dataflows.len()
+    );
+    if invalid > 0 {
+        bail!("{invalid} invalid dataflows");
+    }
+    Ok(())
+}